@@ -77,6 +77,84 @@ impl From<GetBlockResult> for ListDeploysResult {
     }
 }
 
+/// The signers whose approvals are required for a `Deploy`, recorded in a file alongside the
+/// `Deploy` file.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct RequiredSigners {
+    /// The hash of the `Deploy` requiring the approvals.
+    pub(crate) deploy_hash: DeployHash,
+    /// The public keys of the required signers.
+    pub(crate) signers: Vec<PublicKey>,
+}
+
+impl RequiredSigners {
+    /// Returns the path of the file recording the required signers of the `Deploy` file at
+    /// `deploy_path`.
+    pub(crate) fn path(deploy_path: &str) -> String {
+        format!("{}.signers", deploy_path)
+    }
+
+    /// Reads the required signers recorded alongside the `Deploy` file at `deploy_path`, if any.
+    pub(crate) fn read(deploy_path: &str) -> Result<Option<RequiredSigners>> {
+        let path = Self::path(deploy_path);
+        if !Path::new(&path).exists() {
+            return Ok(None);
+        }
+        let file = File::open(&path).map_err(|error| Error::IoError {
+            context: format!("unable to read required signers file at '{}'", path),
+            error,
+        })?;
+        let required_signers = serde_json::from_reader(BufReader::new(file))?;
+        Ok(Some(required_signers))
+    }
+
+    /// Writes the required signers to `output`.
+    pub(crate) fn write<W: Write>(&self, mut output: W) -> Result<()> {
+        let content = serde_json::to_string_pretty(self)?;
+        output
+            .write_all(content.as_bytes())
+            .map_err(|error| Error::IoError {
+                context: "unable to write required signers".to_owned(),
+                error,
+            })
+    }
+}
+
+/// The result of checking a `Deploy`'s approvals against the associated keys of its account, as
+/// returned by [`check_approvals()`](fn.check_approvals.html).
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ApprovalsReport {
+    /// The hash of the `Deploy`.
+    pub deploy_hash: DeployHash,
+    /// The public key of the account under which the `Deploy` is executed.
+    pub account: PublicKey,
+    /// The total weight of associated keys which must approve the `Deploy`.
+    pub deployment_threshold: u8,
+    /// The total weight of the associated keys which have validly approved the `Deploy`.
+    pub approved_weight: u8,
+    /// The `Deploy`'s approvals.
+    pub approvals: Vec<ApprovalStatus>,
+    /// The required signers, as recorded by `make-deploy`, who have not yet approved the `Deploy`.
+    pub missing_signers: Vec<PublicKey>,
+    /// Whether the `Deploy` is approved by enough associated keys of its account to be executed.
+    /// This requires every approval to be valid and made by an associated key, and
+    /// `approved_weight` to be at least `deployment_threshold`.
+    pub is_authorized: bool,
+}
+
+/// A single approval of a `Deploy`, as listed in an [`ApprovalsReport`].
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ApprovalStatus {
+    /// The public key of the signer.
+    pub signer: PublicKey,
+    /// Whether the signature is a valid signature of the `Deploy` hash by the signer.
+    pub is_valid: bool,
+    /// The weight of the signer as an associated key of the account, or `None` if the signer is
+    /// not an associated key.
+    pub weight: Option<u8>,
+}
+
 /// An output abstraction for associating a Write with some metadata.
 pub(super) enum OutputKind<'a> {
     File {
@@ -214,6 +292,10 @@ pub(super) trait DeployExt {
     where
         R: Read,
         W: Write;
+
+    /// Adds the approvals of `other`, a copy of this `Deploy` signed by other parties, skipping
+    /// those by signers who have already approved this `Deploy`.
+    fn merge_approvals(&mut self, other: &Deploy) -> Result<()>;
 }
 
 impl DeployExt for Deploy {
@@ -333,6 +415,32 @@ impl DeployExt for Deploy {
         deploy.write_deploy(output)?;
         Ok(())
     }
+
+    fn merge_approvals(&mut self, other: &Deploy) -> Result<()> {
+        if other.id() != self.id() {
+            return Err(Error::MismatchedDeploy {
+                expected: *self.id(),
+                actual: *other.id(),
+            });
+        }
+        for approval in other.approvals() {
+            if self
+                .approvals()
+                .iter()
+                .any(|existing| existing.signer() == approval.signer())
+            {
+                continue;
+            }
+            approval
+                .verify(self.id())
+                .map_err(|error| Error::CryptoError {
+                    context: "merge_approvals",
+                    error,
+                })?;
+            self.add_approval(approval.clone());
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -549,6 +657,48 @@ mod tests {
         );
     }
 
+    #[test]
+    fn should_merge_approvals() {
+        let bytes = SAMPLE_DEPLOY.as_bytes();
+        let mut deploy = Deploy::read_deploy(bytes).unwrap();
+        let mut first_copy = Deploy::read_deploy(bytes).unwrap();
+        first_copy.sign(&SecretKey::generate_ed25519().unwrap());
+        let mut second_copy = Deploy::read_deploy(bytes).unwrap();
+        second_copy.sign(&SecretKey::generate_ed25519().unwrap());
+
+        deploy.merge_approvals(&first_copy).unwrap();
+        deploy.merge_approvals(&second_copy).unwrap();
+        assert_eq!(deploy.approvals().len(), 4);
+        deploy
+            .is_valid()
+            .unwrap_or_else(|error| panic!("{} - {:#?}", error, deploy));
+
+        // Approvals by signers who have already approved the deploy are skipped.
+        deploy.merge_approvals(&first_copy).unwrap();
+        assert_eq!(deploy.approvals().len(), 4);
+    }
+
+    #[test]
+    fn should_not_merge_approvals_of_different_deploy() {
+        let mut deploy = Deploy::read_deploy(SAMPLE_DEPLOY.as_bytes()).unwrap();
+        let other_deploy = Deploy::with_payment_and_session(
+            deploy_params().try_into().unwrap(),
+            ExecutableDeployItem::Transfer {
+                args: RuntimeArgs::default(),
+            },
+            ExecutableDeployItem::Transfer {
+                args: RuntimeArgs::default(),
+            },
+        )
+        .unwrap();
+
+        assert!(matches!(
+            deploy.merge_approvals(&other_deploy),
+            Err(Error::MismatchedDeploy { .. })
+        ));
+        assert_eq!(deploy.approvals().len(), 2);
+    }
+
     #[test]
     fn should_create_transfer() {
         use casper_types::{AsymmetricType, PublicKey};
@@ -0,0 +1,338 @@
+//! Waiting for a `Deploy` to be executed or to expire.
+//!
+//! The outcome is taken from the node's event stream if its address is given, falling back to
+//! polling the node's JSON-RPC server if the event stream is unavailable.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use casper_node::{
+    rpcs::info::GetDeployResult,
+    types::{BlockHash, DeployHash, Timestamp},
+};
+use casper_types::ExecutionResult;
+
+use crate::{
+    error::{Error, Result},
+    rpc::RpcCall,
+};
+
+/// The path of the event stream on which `DeployProcessed` and `DeployExpired` events are sent.
+const EVENT_STREAM_PATH: &str = "events/main";
+/// The query restricting the event stream to `DeployProcessed` and `DeployExpired` events.
+const EVENT_STREAM_QUERY: &str = "event_type=DeployProcessed,DeployExpired";
+/// The prefix of an event stream line carrying an event's data.
+const DATA_PREFIX: &str = "data:";
+/// The interval between requests for the `Deploy` when polling.
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// The JSON-RPC error code returned by the node for a `Deploy` it doesn't know.
+const NO_SUCH_DEPLOY_ERROR_CODE: i64 = -32000;
+
+/// The outcome of a `Deploy` sent to the network.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+pub enum DeployOutcome {
+    /// The `Deploy` has been executed in the given `Block`.
+    Processed {
+        /// The hash of the `Block` in which the `Deploy` was executed.
+        block_hash: BlockHash,
+        /// The result of executing the `Deploy`.
+        execution_result: ExecutionResult,
+    },
+    /// The `Deploy` expired without being executed.
+    Expired,
+}
+
+/// The status of a `Deploy` which has been waited for.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+pub struct DeployStatus {
+    /// The outcome of the `Deploy`.
+    pub outcome: DeployOutcome,
+    /// The reason the event stream could not be used, if it was given but the outcome had to be
+    /// polled for instead.
+    pub event_stream_error: Option<String>,
+}
+
+impl DeployOutcome {
+    /// The exit code for a `Deploy` which executed successfully.
+    pub const SUCCESS_EXIT_CODE: i32 = 0;
+    /// The exit code for a `Deploy` whose execution failed.
+    pub const EXECUTION_FAILED_EXIT_CODE: i32 = 2;
+    /// The exit code for a `Deploy` which expired without being executed.
+    pub const EXPIRED_EXIT_CODE: i32 = 3;
+    /// The exit code for a `Deploy` which was neither executed nor expired before the timeout.
+    pub const TIMED_OUT_EXIT_CODE: i32 = 4;
+
+    /// Returns the process exit code representing this outcome.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            DeployOutcome::Processed {
+                execution_result: ExecutionResult::Success { .. },
+                ..
+            } => Self::SUCCESS_EXIT_CODE,
+            DeployOutcome::Processed {
+                execution_result: ExecutionResult::Failure { .. },
+                ..
+            } => Self::EXECUTION_FAILED_EXIT_CODE,
+            DeployOutcome::Expired => Self::EXPIRED_EXIT_CODE,
+        }
+    }
+}
+
+/// The subset of the node's event stream events relevant to the outcome of a `Deploy`.
+///
+/// Fields of the node's events not listed here are ignored.
+#[derive(Deserialize)]
+enum DeployEvent {
+    DeployProcessed {
+        deploy_hash: DeployHash,
+        block_hash: BlockHash,
+        execution_result: ExecutionResult,
+    },
+    DeployExpired {
+        deploy_hash: DeployHash,
+    },
+}
+
+impl DeployEvent {
+    fn into_outcome(self, expected_deploy_hash: &DeployHash) -> Option<DeployOutcome> {
+        match self {
+            DeployEvent::DeployProcessed {
+                deploy_hash,
+                block_hash,
+                execution_result,
+            } if deploy_hash == *expected_deploy_hash => Some(DeployOutcome::Processed {
+                block_hash,
+                execution_result,
+            }),
+            DeployEvent::DeployExpired { deploy_hash } if deploy_hash == *expected_deploy_hash => {
+                Some(DeployOutcome::Expired)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Waits for the given `Deploy` to be executed or to expire, returning `Error::TimedOut` if
+/// neither happens within `timeout`.
+pub(crate) async fn wait_for_deploy(
+    maybe_rpc_id: &str,
+    node_address: &str,
+    verbosity_level: u64,
+    maybe_event_stream_address: &str,
+    deploy_hash: DeployHash,
+    timeout: Duration,
+) -> Result<DeployStatus> {
+    let wait = async {
+        let mut event_stream_error = None;
+        if !maybe_event_stream_address.is_empty() {
+            match subscribe(maybe_event_stream_address).await {
+                Ok(response) => {
+                    match listen(
+                        maybe_rpc_id,
+                        node_address,
+                        verbosity_level,
+                        response,
+                        &deploy_hash,
+                    )
+                    .await?
+                    {
+                        Some(outcome) => {
+                            return Ok(DeployStatus {
+                                outcome,
+                                event_stream_error,
+                            })
+                        }
+                        None => event_stream_error = Some("event stream closed".to_string()),
+                    }
+                }
+                Err(error) => {
+                    event_stream_error = Some(format!("failed to subscribe: {}", error));
+                }
+            }
+        }
+        let outcome = poll(maybe_rpc_id, node_address, verbosity_level, &deploy_hash).await?;
+        Ok(DeployStatus {
+            outcome,
+            event_stream_error,
+        })
+    };
+    tokio::time::timeout(timeout, wait)
+        .await
+        .map_err(|_| Error::TimedOut {
+            deploy_hash,
+            timeout,
+        })?
+}
+
+/// Connects to the node's event stream, filtered to `DeployProcessed` and `DeployExpired` events.
+async fn subscribe(event_stream_address: &str) -> Result<reqwest::Response> {
+    let url = format!(
+        "{}/{}?{}",
+        event_stream_address.trim_end_matches('/'),
+        EVENT_STREAM_PATH,
+        EVENT_STREAM_QUERY
+    );
+    reqwest::get(&url)
+        .await
+        .and_then(reqwest::Response::error_for_status)
+        .map_err(Error::FailedToGetResponse)
+}
+
+/// Reads events from the event stream until one for the given `Deploy` arrives.
+///
+/// Returns `None` if the stream is closed by the node before then.
+async fn listen(
+    maybe_rpc_id: &str,
+    node_address: &str,
+    verbosity_level: u64,
+    mut response: reqwest::Response,
+    deploy_hash: &DeployHash,
+) -> Result<Option<DeployOutcome>> {
+    // The deploy may have been executed before the subscription was made, in which case its event
+    // will never arrive on the stream.
+    if let Some(outcome) =
+        get_outcome(maybe_rpc_id, node_address, verbosity_level, deploy_hash).await?
+    {
+        return Ok(Some(outcome));
+    }
+
+    let mut buffer = Vec::new();
+    while let Some(chunk) = response.chunk().await.map_err(Error::FailedToGetResponse)? {
+        buffer.extend_from_slice(&chunk);
+        while let Some(index) = buffer.iter().position(|byte| *byte == b'\n') {
+            let line: Vec<u8> = buffer.drain(..=index).collect();
+            if let Some(outcome) = parse_line(&String::from_utf8_lossy(&line), deploy_hash) {
+                return Ok(Some(outcome));
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// Parses a line of the event stream, returning the outcome if it carries the data of an event for
+/// the given `Deploy`.
+///
+/// Lines other than data lines, and other events such as the initial `ApiVersion`, are skipped.
+fn parse_line(line: &str, deploy_hash: &DeployHash) -> Option<DeployOutcome> {
+    let data = line.trim_end().strip_prefix(DATA_PREFIX)?;
+    serde_json::from_str::<DeployEvent>(data.trim_start())
+        .ok()?
+        .into_outcome(deploy_hash)
+}
+
+/// Requests the `Deploy` repeatedly until it has been executed or has expired.
+async fn poll(
+    maybe_rpc_id: &str,
+    node_address: &str,
+    verbosity_level: u64,
+    deploy_hash: &DeployHash,
+) -> Result<DeployOutcome> {
+    loop {
+        if let Some(outcome) =
+            get_outcome(maybe_rpc_id, node_address, verbosity_level, deploy_hash).await?
+        {
+            return Ok(outcome);
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+/// Requests the `Deploy` once, returning its outcome if it has been executed or has expired.
+///
+/// A `Deploy` not yet known to the node is treated as pending, since it may still be on its way
+/// to the node.  Any other error response from the node is returned.
+async fn get_outcome(
+    maybe_rpc_id: &str,
+    node_address: &str,
+    verbosity_level: u64,
+    deploy_hash: &DeployHash,
+) -> Result<Option<DeployOutcome>> {
+    let response = match RpcCall::new(maybe_rpc_id, node_address, verbosity_level)
+        .get_deploy(&deploy_hash.inner().to_string())
+        .await
+    {
+        Ok(response) => response,
+        Err(Error::ResponseIsError(error)) if error.code == NO_SUCH_DEPLOY_ERROR_CODE => {
+            return Ok(None)
+        }
+        Err(error) => return Err(error),
+    };
+    let result_value = response
+        .get_result()
+        .cloned()
+        .ok_or_else(|| Error::InvalidRpcResponse(response.clone()))?;
+    let result: GetDeployResult = serde_json::from_value(result_value)?;
+
+    if let Some(json_execution_result) = result.execution_results.into_iter().next() {
+        return Ok(Some(DeployOutcome::Processed {
+            block_hash: json_execution_result.block_hash,
+            execution_result: json_execution_result.result,
+        }));
+    }
+    if result.deploy.header().expired(Timestamp::now()) {
+        return Ok(Some(DeployOutcome::Expired));
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use casper_hashing::Digest;
+    use casper_types::{ExecutionEffect, U512};
+
+    use super::*;
+
+    #[test]
+    fn should_parse_outcome_from_event_stream_lines() {
+        let deploy_hash = DeployHash::new(Digest::hash(&[1u8]));
+        let other_deploy_hash = DeployHash::new(Digest::hash(&[2u8]));
+        let block_hash = BlockHash::new(Digest::hash(&[3u8]));
+        let execution_result = ExecutionResult::Failure {
+            effect: ExecutionEffect::default(),
+            transfers: vec![],
+            cost: U512::from(10),
+            error_message: "error".to_string(),
+        };
+
+        // Fields of the node's events which aren't needed are ignored.
+        let processed = json!({
+            "DeployProcessed": {
+                "deploy_hash": deploy_hash,
+                "account": "01d9bf2148748a85c89da5aad8ee0b0fc2d105fd39d41a4c796536354f0ae2900c",
+                "block_hash": block_hash,
+                "execution_result": execution_result,
+            }
+        });
+        let outcome = parse_line(&format!("data:{}\n", processed), &deploy_hash)
+            .expect("should parse outcome");
+        assert_eq!(
+            outcome,
+            DeployOutcome::Processed {
+                block_hash,
+                execution_result
+            }
+        );
+        assert_eq!(
+            outcome.exit_code(),
+            DeployOutcome::EXECUTION_FAILED_EXIT_CODE
+        );
+
+        let expired = json!({ "DeployExpired": { "deploy_hash": deploy_hash } });
+        assert_eq!(
+            parse_line(&format!("data: {}", expired), &deploy_hash),
+            Some(DeployOutcome::Expired)
+        );
+        assert_eq!(
+            parse_line(&format!("data:{}", expired), &other_deploy_hash),
+            None
+        );
+        assert_eq!(
+            parse_line("data:{\"ApiVersion\":\"1.4.3\"}", &deploy_hash),
+            None
+        );
+        assert_eq!(parse_line("id:5", &deploy_hash), None);
+    }
+}
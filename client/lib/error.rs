@@ -1,10 +1,13 @@
-use std::{num::ParseIntError, path::PathBuf};
+use std::{num::ParseIntError, path::PathBuf, time::Duration};
 
 use humantime::{DurationError, TimestampError};
 use jsonrpc_lite::JsonRpc;
 use thiserror::Error;
 
-use casper_node::{crypto::Error as CryptoError, types::ExcessiveSizeDeployError};
+use casper_node::{
+    crypto::Error as CryptoError,
+    types::{DeployHash, ExcessiveSizeDeployError, HeaderChainError},
+};
 use casper_types::{
     bytesrepr::Error as ToBytesError, CLValueError, UIntParseError, URefFromStrError,
 };
@@ -161,6 +164,32 @@ pub enum Error {
     #[error("Invalid response: {0}")]
     InvalidResponse(#[from] ValidateResponseError),
 
+    /// Speculative execution of a `Deploy` used to estimate its payment amount failed.
+    #[error("Failed to estimate payment amount: {0}")]
+    FailedToEstimatePayment(String),
+
+    /// A downloaded block header could not be verified against the trusted header chain.
+    #[error("Invalid block header: {0}")]
+    InvalidHeaderChain(#[from] HeaderChainError),
+
+    /// A `Deploy` whose approvals were to be merged is not a copy of the `Deploy` being signed.
+    #[error("Can't merge approvals of deploy {actual} into deploy {expected}")]
+    MismatchedDeploy {
+        /// The hash of the `Deploy` being signed.
+        expected: DeployHash,
+        /// The hash of the `Deploy` whose approvals were to be merged.
+        actual: DeployHash,
+    },
+
+    /// A `Deploy` was neither executed nor expired within the time allowed to wait for it.
+    #[error("Timed out after {timeout:?} waiting for deploy {deploy_hash}")]
+    TimedOut {
+        /// The hash of the `Deploy` being waited for.
+        deploy_hash: DeployHash,
+        /// The time allowed to wait for the `Deploy`.
+        timeout: Duration,
+    },
+
     /// Failed to create a DictionaryIdentifier
     #[error("Failed to parse the dictionary identifier")]
     FailedToParseDictionaryIdentifier,
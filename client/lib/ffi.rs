@@ -55,6 +55,10 @@ pub enum casper_error_t {
     CASPER_DEPLOY_SIZE_TOO_LARGE = -24,
     CASPER_FAILED_TO_CREATE_DICTIONARY_IDENTIFIER = -25,
     CASPER_FAILED_TO_PARSE_STATE_IDENTIFIER = -26,
+    CASPER_FAILED_TO_ESTIMATE_PAYMENT = -27,
+    CASPER_INVALID_HEADER_CHAIN = -28,
+    CASPER_MISMATCHED_DEPLOY = -29,
+    CASPER_TIMED_OUT = -30,
 }
 
 trait AsFFIError {
@@ -96,6 +100,10 @@ impl AsFFIError for Error {
             Error::FailedToParseStateIdentifier => {
                 casper_error_t::CASPER_FAILED_TO_PARSE_STATE_IDENTIFIER
             }
+            Error::FailedToEstimatePayment(_) => casper_error_t::CASPER_FAILED_TO_ESTIMATE_PAYMENT,
+            Error::InvalidHeaderChain(_) => casper_error_t::CASPER_INVALID_HEADER_CHAIN,
+            Error::MismatchedDeploy { .. } => casper_error_t::CASPER_MISMATCHED_DEPLOY,
+            Error::TimedOut { .. } => casper_error_t::CASPER_TIMED_OUT,
         }
     }
 }
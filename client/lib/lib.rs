@@ -14,30 +14,44 @@
 
 mod cl_type;
 mod deploy;
+mod deploy_status;
 mod error;
 #[cfg(feature = "ffi")]
 pub mod ffi;
 pub mod keygen;
+pub mod light_client;
 mod parsing;
 mod rpc;
 mod validation;
 
-use std::{convert::TryInto, fs, io::Cursor};
+use std::{
+    collections::BTreeSet,
+    convert::TryInto,
+    fs::{self, File},
+    io::Cursor,
+};
 
 use jsonrpc_lite::JsonRpc;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 use casper_execution_engine::core::engine_state::ExecutableDeployItem;
 use casper_hashing::Digest;
 use casper_node::{
-    rpcs::state::{DictionaryIdentifier, GlobalStateIdentifier},
-    types::{BlockHash, Deploy},
+    rpcs::{
+        account::SpeculativeExecResult,
+        chain::GetBlockResult,
+        state::{
+            DictionaryIdentifier, GetAccountInfoResult, GetBalanceResult, GlobalStateIdentifier,
+        },
+    },
+    types::{BlockHash, Deploy, DeployHash, JsonBlock},
 };
-use casper_types::Key;
+use casper_types::{AsymmetricType, ExecutionResult, Key, PublicKey, U512};
 
 pub use cl_type::help;
-pub use deploy::ListDeploysResult;
-use deploy::{DeployExt, DeployParams, OutputKind};
+pub use deploy::{ApprovalStatus, ApprovalsReport, ListDeploysResult};
+use deploy::{DeployExt, DeployParams, OutputKind, RequiredSigners};
+pub use deploy_status::{DeployOutcome, DeployStatus};
 pub use error::Error;
 use error::Result;
 pub use rpc::map_hashing_error;
@@ -80,6 +94,103 @@ pub async fn put_deploy(
         .await
 }
 
+/// Creates a `Deploy` and executes it speculatively against the global state of a block, without
+/// sending it to the network for execution.
+///
+/// * `maybe_rpc_id` is the JSON-RPC identifier, applied to the request and returned in the
+///   response. If it can be parsed as an `i64` it will be used as a JSON integer. If empty, a
+///   random `i64` will be assigned. Otherwise the provided string will be used verbatim.
+/// * `node_address` is the hostname or IP and port of the node on which the HTTP service is
+///   running, e.g. `"http://127.0.0.1:7777"`.
+/// * When `verbosity_level` is `1`, the JSON-RPC request will be printed to `stdout` with long
+///   string fields (e.g. hex-formatted raw Wasm bytes) shortened to a string indicating the char
+///   count of the field.  When `verbosity_level` is greater than `1`, the request will be printed
+///   to `stdout` with no abbreviation of long fields.  When `verbosity_level` is `0`, the request
+///   will not be printed to `stdout`.
+/// * `maybe_block_id` must be a hex-encoded, 32-byte hash digest or a `u64` representing the
+///   `Block` height or empty. If empty, the deploy will be executed against the post-state of the
+///   latest `Block`.
+/// * `deploy_params` contains deploy-related options for this `Deploy`. See
+///   [`DeployStrParams`](struct.DeployStrParams.html) for more details.
+/// * `session_params` contains session-related options for this `Deploy`. See
+///   [`SessionStrParams`](struct.SessionStrParams.html) for more details.
+/// * `payment_params` contains payment-related options for this `Deploy`. See
+///   [`PaymentStrParams`](struct.PaymentStrParams.html) for more details.
+pub async fn speculative_exec(
+    maybe_rpc_id: &str,
+    node_address: &str,
+    verbosity_level: u64,
+    maybe_block_id: &str,
+    deploy_params: DeployStrParams<'_>,
+    session_params: SessionStrParams<'_>,
+    payment_params: PaymentStrParams<'_>,
+) -> Result<JsonRpc> {
+    let deploy = Deploy::with_payment_and_session(
+        deploy_params.try_into()?,
+        payment_params.try_into()?,
+        session_params.try_into()?,
+    )?;
+    RpcCall::new(maybe_rpc_id, node_address, verbosity_level)
+        .speculative_exec(maybe_block_id, deploy)
+        .await
+}
+
+/// Estimates the standard payment amount required to execute a `Deploy`, by executing it
+/// speculatively against the global state of the latest `Block`.
+///
+/// * `maybe_rpc_id` is the JSON-RPC identifier, applied to the request and returned in the
+///   response. If it can be parsed as an `i64` it will be used as a JSON integer. If empty, a
+///   random `i64` will be assigned. Otherwise the provided string will be used verbatim.
+/// * `node_address` is the hostname or IP and port of the node on which the HTTP service is
+///   running, e.g. `"http://127.0.0.1:7777"`.
+/// * When `verbosity_level` is `1`, the JSON-RPC request will be printed to `stdout` with long
+///   string fields (e.g. hex-formatted raw Wasm bytes) shortened to a string indicating the char
+///   count of the field.  When `verbosity_level` is greater than `1`, the request will be printed
+///   to `stdout` with no abbreviation of long fields.  When `verbosity_level` is `0`, the request
+///   will not be printed to `stdout`.
+/// * `deploy_params` contains deploy-related options for this `Deploy`. See
+///   [`DeployStrParams`](struct.DeployStrParams.html) for more details.
+/// * `session_params` contains session-related options for this `Deploy`. See
+///   [`SessionStrParams`](struct.SessionStrParams.html) for more details.
+/// * `max_payment_amount` is the standard payment amount, in motes, with which the `Deploy` is
+///   executed in order to measure its cost. It must cover the cost of execution, and the session
+///   account's main purse must hold at least this amount.
+/// * `safety_margin` is the percentage added to the measured cost, e.g. `"10"` to return 110% of
+///   the cost.
+///
+/// The returned amount can be passed to
+/// [`PaymentStrParams::with_amount()`](struct.PaymentStrParams.html#method.with_amount).
+pub async fn estimate_payment(
+    maybe_rpc_id: &str,
+    node_address: &str,
+    verbosity_level: u64,
+    deploy_params: DeployStrParams<'_>,
+    session_params: SessionStrParams<'_>,
+    max_payment_amount: &str,
+    safety_margin: &str,
+) -> Result<U512> {
+    let safety_margin = parsing::safety_margin(safety_margin)?;
+    let deploy = Deploy::with_payment_and_session(
+        deploy_params.try_into()?,
+        PaymentStrParams::with_amount(max_payment_amount).try_into()?,
+        session_params.try_into()?,
+    )?;
+    let response = RpcCall::new(maybe_rpc_id, node_address, verbosity_level)
+        .speculative_exec("", deploy)
+        .await?;
+    let result_value = response
+        .get_result()
+        .cloned()
+        .ok_or_else(|| Error::InvalidRpcResponse(response.clone()))?;
+    let result: SpeculativeExecResult = serde_json::from_value(result_value)?;
+    match result.execution_result {
+        ExecutionResult::Success { cost, .. } => Ok(cost + cost * safety_margin / 100),
+        ExecutionResult::Failure { error_message, .. } => {
+            Err(Error::FailedToEstimatePayment(error_message))
+        }
+    }
+}
+
 /// Creates a `Deploy` and outputs it to a file or stdout.
 ///
 /// As a file, the `Deploy` can subsequently be signed by other parties using
@@ -151,6 +262,196 @@ pub fn sign_deploy_file(
 
     Deploy::sign_and_write_deploy(Cursor::new(input), secret_key, output.get()?)?;
 
+    output.commit()?;
+    copy_required_signers(input_path, maybe_output_path, force)
+}
+
+/// Records the signers whose approvals are required for a previously-saved `Deploy`, in a file
+/// alongside the `Deploy` file.
+///
+/// The record is copied along with the `Deploy` by
+/// [`sign_deploy_file()`](fn.sign_deploy_file.html) and
+/// [`merge_deploy_approvals()`](fn.merge_deploy_approvals.html), and the signers who have not yet
+/// approved the `Deploy` are listed by [`check_approvals()`](fn.check_approvals.html).
+///
+/// * `deploy_path` specifies the path to the previously-saved `Deploy` file.
+/// * `required_signers` are the hex-encoded public keys of the required signers.
+/// * If `force` is true, an existing record for the `Deploy` file will be overwritten. If `force`
+///   is false and a record exists,
+///   [`Error::FileAlreadyExists`](enum.Error.html#variant.FileAlreadyExists) is returned and the
+///   record will not be written.
+pub fn record_required_signers(
+    deploy_path: &str,
+    required_signers: &[&str],
+    force: bool,
+) -> Result<()> {
+    let deploy = read_deploy_file(deploy_path)?;
+    let signers = required_signers
+        .iter()
+        .map(|public_key| PublicKey::from_hex(public_key).map_err(|_| Error::FailedToParseKey))
+        .collect::<Result<Vec<_>>>()?;
+    let required_signers = RequiredSigners {
+        deploy_hash: *deploy.id(),
+        signers,
+    };
+
+    let path = RequiredSigners::path(deploy_path);
+    let output = OutputKind::file(&path, force);
+    required_signers.write(output.get()?)?;
+    output.commit()
+}
+
+/// Reads a previously-saved `Deploy` from a file, adds the approvals from copies of it which have
+/// been signed by other parties, optionally signs it, and outputs it to a file or stdout.
+///
+/// * `input_path` specifies the path to the previously-saved `Deploy` file.
+/// * `partial_paths` specify the paths to copies of the `Deploy` holding other parties' approvals.
+///   Each approval is verified before being added, and approvals by signers who have already
+///   approved the `Deploy` are skipped.
+/// * `maybe_secret_key` specifies the path to the secret key with which to sign the `Deploy`, or
+///   if empty, the `Deploy` is not signed.
+/// * `maybe_output_path` specifies the output file, or if empty, will print it to `stdout`.
+/// * If `force` is true, and a file exists at `maybe_output_path`, it will be overwritten. If
+///   `force` is false and a file exists at `maybe_output_path`,
+///   [`Error::FileAlreadyExists`](enum.Error.html#variant.FileAlreadyExists) is returned and a file
+///   will not be written.
+pub fn merge_deploy_approvals(
+    input_path: &str,
+    partial_paths: &[&str],
+    maybe_secret_key: &str,
+    maybe_output_path: &str,
+    force: bool,
+) -> Result<()> {
+    let mut deploy = read_deploy_file(input_path)?;
+    for partial_path in partial_paths {
+        let partial_deploy = read_deploy_file(partial_path)?;
+        deploy.merge_approvals(&partial_deploy)?;
+    }
+    if !maybe_secret_key.is_empty() {
+        let secret_key = parsing::secret_key(maybe_secret_key)?;
+        deploy.sign(&secret_key);
+    }
+
+    let output = if maybe_output_path.is_empty() {
+        OutputKind::Stdout
+    } else {
+        OutputKind::file(maybe_output_path, force)
+    };
+    deploy.write_deploy(output.get()?)?;
+    output.commit()?;
+    copy_required_signers(input_path, maybe_output_path, force)
+}
+
+/// Checks the approvals of a previously-saved `Deploy` against the associated keys and deployment
+/// threshold of its account, as of a given `Block`.
+///
+/// * `maybe_rpc_id` is the JSON-RPC identifier, applied to the request and returned in the
+///   response. If it can be parsed as an `i64` it will be used as a JSON integer. If empty, a
+///   random `i64` will be assigned. Otherwise the provided string will be used verbatim.
+/// * `node_address` is the hostname or IP and port of the node on which the HTTP service is
+///   running, e.g. `"http://127.0.0.1:7777"`.
+/// * When `verbosity_level` is `1`, the JSON-RPC request will be printed to `stdout` with long
+///   string fields (e.g. hex-formatted raw Wasm bytes) shortened to a string indicating the char
+///   count of the field.  When `verbosity_level` is greater than `1`, the request will be printed
+///   to `stdout` with no abbreviation of long fields.  When `verbosity_level` is `0`, the request
+///   will not be printed to `stdout`.
+/// * `input_path` specifies the path to the previously-saved `Deploy` file. If the required
+///   signers of the `Deploy` were recorded by
+///   [`record_required_signers()`](fn.record_required_signers.html), those who have not approved
+///   it are listed in the report.
+/// * `maybe_block_id` must be a hex-encoded, 32-byte hash digest or a `u64` representing the
+///   `Block` height or empty. If empty, the account as of the latest `Block` will be used.
+pub async fn check_approvals(
+    maybe_rpc_id: &str,
+    node_address: &str,
+    verbosity_level: u64,
+    input_path: &str,
+    maybe_block_id: &str,
+) -> Result<ApprovalsReport> {
+    let deploy = read_deploy_file(input_path)?;
+    let account_public_key = deploy.header().account().clone();
+
+    let response = RpcCall::new(maybe_rpc_id, node_address, verbosity_level)
+        .get_account_info(&account_public_key.to_hex(), maybe_block_id)
+        .await?;
+    let result_value = response
+        .get_result()
+        .cloned()
+        .ok_or_else(|| Error::InvalidRpcResponse(response.clone()))?;
+    let account = serde_json::from_value::<GetAccountInfoResult>(result_value)?.account;
+
+    let approvals: Vec<ApprovalStatus> = deploy
+        .approvals()
+        .iter()
+        .map(|approval| ApprovalStatus {
+            signer: approval.signer().clone(),
+            is_valid: approval.verify(deploy.id()).is_ok(),
+            weight: account.associated_key_weight(&approval.signer().to_account_hash()),
+        })
+        .collect();
+
+    // As when the deploy is executed, each associated key's weight is counted once, however many
+    // times it has approved the deploy.
+    let mut signed_account_hashes = BTreeSet::new();
+    let approved_weight = approvals
+        .iter()
+        .filter(|approval| approval.is_valid)
+        .filter(|approval| signed_account_hashes.insert(approval.signer.to_account_hash()))
+        .filter_map(|approval| approval.weight)
+        .fold(0u8, |total, weight| total.saturating_add(weight));
+    let deployment_threshold = account.deployment_threshold();
+    let is_authorized = !approvals.is_empty()
+        && approvals
+            .iter()
+            .all(|approval| approval.is_valid && approval.weight.is_some())
+        && approved_weight >= deployment_threshold;
+
+    let missing_signers = match RequiredSigners::read(input_path)? {
+        Some(required_signers) if required_signers.deploy_hash == *deploy.id() => required_signers
+            .signers
+            .into_iter()
+            .filter(|signer| {
+                !approvals
+                    .iter()
+                    .any(|approval| approval.is_valid && approval.signer == *signer)
+            })
+            .collect(),
+        _ => vec![],
+    };
+
+    Ok(ApprovalsReport {
+        deploy_hash: *deploy.id(),
+        account: account_public_key,
+        deployment_threshold,
+        approved_weight,
+        approvals,
+        missing_signers,
+        is_authorized,
+    })
+}
+
+/// Reads a previously-saved `Deploy` from the file at `input_path`.
+fn read_deploy_file(input_path: &str) -> Result<Deploy> {
+    let input = File::open(input_path).map_err(|error| Error::IoError {
+        context: format!("unable to read deploy file at '{}'", input_path),
+        error,
+    })?;
+    Deploy::read_deploy(input)
+}
+
+/// Copies the record of the required signers of the `Deploy` file at `input_path`, if any, to sit
+/// alongside the `Deploy` file at `maybe_output_path`.
+fn copy_required_signers(input_path: &str, maybe_output_path: &str, force: bool) -> Result<()> {
+    if maybe_output_path.is_empty() {
+        return Ok(());
+    }
+    let required_signers = match RequiredSigners::read(input_path)? {
+        Some(required_signers) => required_signers,
+        None => return Ok(()),
+    };
+    let path = RequiredSigners::path(maybe_output_path);
+    let output = OutputKind::file(&path, force);
+    required_signers.write(output.get()?)?;
     output.commit()
 }
 
@@ -296,6 +597,45 @@ pub async fn get_deploy(
         .await
 }
 
+/// Waits for a `Deploy` to be executed or to expire, returning its outcome.
+///
+/// If `maybe_event_stream_address` is non-empty, the outcome is taken from the node's event
+/// stream, otherwise, or if the event stream can't be used, the `Deploy` is requested from the
+/// node repeatedly until it has been executed or has expired.  A `Deploy` not yet known to the
+/// node is waited for as though it were pending, while any other error response from the node is
+/// returned.  The reason the event stream couldn't be used, if any, is returned in
+/// `DeployStatus::event_stream_error`.
+///
+/// * `maybe_rpc_id`, `node_address` and `verbosity_level` are as described in
+///   [`get_deploy()`](fn.get_deploy.html).
+/// * `maybe_event_stream_address` is the hostname or IP and port of the node on which the event
+///   stream server is running, e.g. `"http://127.0.0.1:9999"`, or empty.
+/// * `deploy_hash` must be a hex-encoded, 32-byte hash digest.
+/// * `timeout` is a human-readable duration, e.g. `"5min"`, after which `Error::TimedOut` is
+///   returned if the `Deploy` has neither been executed nor expired.
+pub async fn wait_for_deploy(
+    maybe_rpc_id: &str,
+    node_address: &str,
+    verbosity_level: u64,
+    maybe_event_stream_address: &str,
+    deploy_hash: &str,
+    timeout: &str,
+) -> Result<DeployStatus> {
+    let deploy_hash = Digest::from_hex(deploy_hash)
+        .map(DeployHash::new)
+        .map_err(|error| map_hashing_error(error)("deploy_hash"))?;
+    let timeout = parsing::timeout(timeout)?;
+    deploy_status::wait_for_deploy(
+        maybe_rpc_id,
+        node_address,
+        verbosity_level,
+        maybe_event_stream_address,
+        deploy_hash,
+        timeout.into(),
+    )
+    .await
+}
+
 /// Retrieves a `Block` from the network.
 ///
 /// * `maybe_rpc_id` is the JSON-RPC identifier, applied to the request and returned in the
@@ -445,6 +785,122 @@ pub async fn get_balance(
         .await
 }
 
+/// Retrieves a purse's balance from the network as of a given `Block`.
+///
+/// * `maybe_rpc_id`, `node_address` and `verbosity_level` are as described in
+///   [`get_balance()`](fn.get_balance.html).
+/// * `maybe_block_id` must be a hex-encoded, 32-byte hash digest or a `u64` representing the
+///   `Block` height or empty. If empty, the balance as of the latest `Block` will be retrieved.
+/// * `purse` is a URef, formatted as e.g.
+/// ```text
+/// uref-0102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f20-007
+/// ```
+pub async fn get_balance_at_block(
+    maybe_rpc_id: &str,
+    node_address: &str,
+    verbosity_level: u64,
+    maybe_block_id: &str,
+    purse: &str,
+) -> Result<JsonRpc> {
+    let json_block =
+        get_json_block(maybe_rpc_id, node_address, verbosity_level, maybe_block_id).await?;
+    RpcCall::new(maybe_rpc_id, node_address, verbosity_level)
+        .get_balance(&json_block.header.state_root_hash.to_string(), purse)
+        .await
+}
+
+/// Retrieves a purse's balance from the network as of each `Block` in a range of heights.
+///
+/// * `maybe_rpc_id`, `node_address` and `verbosity_level` are as described in
+///   [`get_balance()`](fn.get_balance.html).
+/// * `purse` is a URef, formatted as e.g.
+/// ```text
+/// uref-0102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f20-007
+/// ```
+/// * `start_height` and `end_height` are the heights of the first and last `Block`s of the range,
+///   inclusive, formatted as `u64`s.  The purse must exist as of every `Block` in the range.
+pub async fn get_balance_history(
+    maybe_rpc_id: &str,
+    node_address: &str,
+    verbosity_level: u64,
+    purse: &str,
+    start_height: &str,
+    end_height: &str,
+) -> Result<Vec<BalanceAtBlock>> {
+    let start_height = parsing::block_height(start_height, "start_height")?;
+    let end_height = parsing::block_height(end_height, "end_height")?;
+    if start_height > end_height {
+        return Err(Error::InvalidArgument {
+            context: "end_height",
+            error: format!(
+                "end height {} is lower than start height {}",
+                end_height, start_height
+            ),
+        });
+    }
+
+    let mut history = Vec::new();
+    for height in start_height..=end_height {
+        let json_block = get_json_block(
+            maybe_rpc_id,
+            node_address,
+            verbosity_level,
+            &height.to_string(),
+        )
+        .await?;
+        let state_root_hash = json_block.header.state_root_hash;
+        let response = RpcCall::new(maybe_rpc_id, node_address, verbosity_level)
+            .get_balance(&state_root_hash.to_string(), purse)
+            .await?;
+        let result_value = response
+            .get_result()
+            .cloned()
+            .ok_or_else(|| Error::InvalidRpcResponse(response.clone()))?;
+        let result: GetBalanceResult = serde_json::from_value(result_value)?;
+        history.push(BalanceAtBlock {
+            height,
+            block_hash: json_block.hash,
+            state_root_hash,
+            balance_value: result.balance_value,
+        });
+    }
+    Ok(history)
+}
+
+/// A purse's balance as of a given `Block`, as returned by
+/// [`get_balance_history()`](fn.get_balance_history.html).
+#[derive(Serialize, Deserialize, Debug)]
+pub struct BalanceAtBlock {
+    /// The height of the `Block`.
+    pub height: u64,
+    /// The hash of the `Block`.
+    pub block_hash: BlockHash,
+    /// The state root hash of the `Block`.
+    pub state_root_hash: Digest,
+    /// The balance of the purse.
+    pub balance_value: U512,
+}
+
+/// Retrieves the given `Block`, returning an error if the node doesn't have it.
+async fn get_json_block(
+    maybe_rpc_id: &str,
+    node_address: &str,
+    verbosity_level: u64,
+    maybe_block_id: &str,
+) -> Result<JsonBlock> {
+    let response = RpcCall::new(maybe_rpc_id, node_address, verbosity_level)
+        .get_block(maybe_block_id)
+        .await?;
+    let result_value = response
+        .get_result()
+        .cloned()
+        .ok_or_else(|| Error::InvalidRpcResponse(response.clone()))?;
+    let result: GetBlockResult = serde_json::from_value(result_value)?;
+    result
+        .block
+        .ok_or_else(|| Error::InvalidRpcResponse(response.clone()))
+}
+
 /// Retrieves era information from the network.
 ///
 /// * `maybe_rpc_id` is the JSON-RPC identifier, applied to the request and returned in the
@@ -524,6 +980,39 @@ pub async fn get_account_info(
         .await
 }
 
+/// Retrieves a page of the deploys sent by an Account, ordered by the height of the `Block` in
+/// which they were executed.
+///
+/// * `maybe_rpc_id` is the JSON-RPC identifier, applied to the request and returned in the
+///   response. If it can be parsed as an `i64` it will be used as a JSON integer. If empty, a
+///   random `i64` will be assigned. Otherwise the provided string will be used verbatim.
+/// * `node_address` is the hostname or IP and port of the node on which the HTTP service is
+///   running, e.g. `"http://127.0.0.1:7777"`.
+/// * When `verbosity_level` is `1`, the JSON-RPC request will be printed to `stdout` with long
+///   string fields (e.g. hex-formatted raw Wasm bytes) shortened to a string indicating the char
+///   count of the field.  When `verbosity_level` is greater than `1`, the request will be printed
+///   to `stdout` with no abbreviation of long fields.  When `verbosity_level` is `0`, the request
+///   will not be printed to `stdout`.
+/// * `public_key` the public key associated with the `Account`
+/// * `maybe_start_height` is the `Block` height from which to start listing deploys, or empty to
+///   start from genesis.
+/// * `maybe_cursor` is the hex-encoded deploy hash returned as `next_cursor` in the previous page,
+///   or empty. If provided, `maybe_start_height` is ignored.
+/// * `maybe_limit` is the maximum number of deploys to return, or empty to use the node's default.
+pub async fn get_account_deploys(
+    maybe_rpc_id: &str,
+    node_address: &str,
+    verbosity_level: u64,
+    public_key: &str,
+    maybe_start_height: &str,
+    maybe_cursor: &str,
+    maybe_limit: &str,
+) -> Result<JsonRpc> {
+    RpcCall::new(maybe_rpc_id, node_address, verbosity_level)
+        .get_account_deploys(public_key, maybe_start_height, maybe_cursor, maybe_limit)
+        .await
+}
+
 /// Retrieves information from global state using either a Block hash or a state root hash.
 ///
 /// * `maybe_rpc_id` is the JSON-RPC identifier, applied to the request and returned in the
@@ -0,0 +1,273 @@
+//! Functions for querying global state in light-client mode.
+//!
+//! In light-client mode, every query is made against the state root hash of a `Block` which has
+//! first been verified to carry valid finality signatures from a known set of validators, and
+//! every response is verified against that state root hash using the Merkle proof it contains.
+//! This allows an untrusted node to be queried safely.
+//!
+//! The trusted validators are given as a map of public key to weight, and must be the validators
+//! of the era in which the queried `Block` was created. A `Block` is accepted if validators
+//! holding more than a third of the total weight have signed it.
+//!
+//! [`sync_headers()`](fn.sync_headers.html) extends a
+//! [`TrustedHeaderChain`](../../casper_node/types/struct.TrustedHeaderChain.html) from a trusted
+//! checkpoint, following changes to the validator set across eras, so that the trusted validators
+//! of later eras need not be known in advance.
+
+use std::collections::BTreeMap;
+
+use jsonrpc_lite::JsonRpc;
+
+use casper_node::{
+    rpcs::chain::{GetBlockHeadersResult, GetBlockResult},
+    types::{BlockHeader, BlockSignatures, JsonBlock, TrustedHeaderChain},
+};
+use casper_types::{AsymmetricType, Key, PublicKey, U512};
+
+use crate::{
+    error::{Error, Result},
+    rpc::RpcCall,
+    validation::{self, ValidateResponseError},
+    DictionaryItemStrParams, GlobalStateStrParams,
+};
+
+/// Retrieves a `Block` from the network and verifies it against the finality signatures of the
+/// trusted validators.
+///
+/// * `maybe_rpc_id` is the JSON-RPC identifier, applied to the request and returned in the
+///   response. If it can be parsed as an `i64` it will be used as a JSON integer. If empty, a
+///   random `i64` will be assigned. Otherwise the provided string will be used verbatim.
+/// * `node_address` is the hostname or IP and port of the node on which the HTTP service is
+///   running, e.g. `"http://127.0.0.1:7777"`.
+/// * When `verbosity_level` is `1`, the JSON-RPC request will be printed to `stdout` with long
+///   string fields (e.g. hex-formatted raw Wasm bytes) shortened to a string indicating the char
+///   count of the field.  When `verbosity_level` is greater than `1`, the request will be printed
+///   to `stdout` with no abbreviation of long fields.  When `verbosity_level` is `0`, the request
+///   will not be printed to `stdout`.
+/// * `maybe_block_id` must be a hex-encoded, 32-byte hash digest or a `u64` representing the
+///   `Block` height or empty. If empty, the latest `Block` will be retrieved.
+/// * `trusted_validators` maps the public key of each validator of the `Block`'s era to its
+///   weight.
+pub async fn get_block(
+    maybe_rpc_id: &str,
+    node_address: &str,
+    verbosity_level: u64,
+    maybe_block_id: &str,
+    trusted_validators: &BTreeMap<PublicKey, U512>,
+) -> Result<JsonRpc> {
+    let (response, _) = get_finalized_block(
+        maybe_rpc_id,
+        node_address,
+        verbosity_level,
+        maybe_block_id,
+        trusted_validators,
+    )
+    .await?;
+    Ok(response)
+}
+
+/// Retrieves an account's information from the network, verified against the global state of a
+/// `Block` which has been verified against the finality signatures of the trusted validators.
+///
+/// * `maybe_rpc_id`, `node_address`, `verbosity_level`, `maybe_block_id` and `trusted_validators`
+///   are as described in [`get_block()`](fn.get_block.html).
+/// * `public_key` is the hex-encoded public key of the account.
+pub async fn get_account_info(
+    maybe_rpc_id: &str,
+    node_address: &str,
+    verbosity_level: u64,
+    public_key: &str,
+    maybe_block_id: &str,
+    trusted_validators: &BTreeMap<PublicKey, U512>,
+) -> Result<JsonRpc> {
+    let (_, json_block) = get_finalized_block(
+        maybe_rpc_id,
+        node_address,
+        verbosity_level,
+        maybe_block_id,
+        trusted_validators,
+    )
+    .await?;
+    let key = PublicKey::from_hex(public_key)
+        .map(|public_key| Key::Account(public_key.to_account_hash()))
+        .map_err(|_| Error::FailedToParseKey)?;
+
+    let response = RpcCall::new(maybe_rpc_id, node_address, verbosity_level)
+        .get_account_info(public_key, &json_block.hash.inner().to_string())
+        .await?;
+    validation::validate_get_account_info_response(
+        &response,
+        &json_block.header.state_root_hash,
+        &key,
+    )?;
+    Ok(response)
+}
+
+/// Retrieves a purse's balance from the network, verified against the global state of a `Block`
+/// which has been verified against the finality signatures of the trusted validators.
+///
+/// * `maybe_rpc_id`, `node_address`, `verbosity_level`, `maybe_block_id` and `trusted_validators`
+///   are as described in [`get_block()`](fn.get_block.html).
+/// * `purse` is a URef, formatted as e.g.
+/// ```text
+/// uref-0102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f20-007
+/// ```
+pub async fn get_balance(
+    maybe_rpc_id: &str,
+    node_address: &str,
+    verbosity_level: u64,
+    maybe_block_id: &str,
+    purse: &str,
+    trusted_validators: &BTreeMap<PublicKey, U512>,
+) -> Result<JsonRpc> {
+    let (_, json_block) = get_finalized_block(
+        maybe_rpc_id,
+        node_address,
+        verbosity_level,
+        maybe_block_id,
+        trusted_validators,
+    )
+    .await?;
+    RpcCall::new(maybe_rpc_id, node_address, verbosity_level)
+        .get_balance(&json_block.header.state_root_hash.to_string(), purse)
+        .await
+}
+
+/// Retrieves a stored value from the network, verified against the global state of a `Block`
+/// which has been verified against the finality signatures of the trusted validators.
+///
+/// * `maybe_rpc_id`, `node_address`, `verbosity_level`, `maybe_block_id` and `trusted_validators`
+///   are as described in [`get_block()`](fn.get_block.html).
+/// * `key` and `path` are as described in
+///   [`query_global_state()`](../fn.query_global_state.html).
+pub async fn query_global_state(
+    maybe_rpc_id: &str,
+    node_address: &str,
+    verbosity_level: u64,
+    maybe_block_id: &str,
+    key: &str,
+    path: &str,
+    trusted_validators: &BTreeMap<PublicKey, U512>,
+) -> Result<JsonRpc> {
+    let (_, json_block) = get_finalized_block(
+        maybe_rpc_id,
+        node_address,
+        verbosity_level,
+        maybe_block_id,
+        trusted_validators,
+    )
+    .await?;
+    let state_root_hash = json_block.header.state_root_hash.to_string();
+    let global_state_str_params = GlobalStateStrParams {
+        is_block_hash: false,
+        hash_value: &state_root_hash,
+    };
+    RpcCall::new(maybe_rpc_id, node_address, verbosity_level)
+        .query_global_state(global_state_str_params, key, path)
+        .await
+}
+
+/// Retrieves a stored value from a dictionary, verified against the global state of a `Block`
+/// which has been verified against the finality signatures of the trusted validators.
+///
+/// * `maybe_rpc_id`, `node_address`, `verbosity_level`, `maybe_block_id` and `trusted_validators`
+///   are as described in [`get_block()`](fn.get_block.html).
+/// * `dictionary_str_params` contains dictionary item identifier options for this query. See
+///   [`DictionaryItemStrParams`](../struct.DictionaryItemStrParams.html) for more details.
+pub async fn get_dictionary_item(
+    maybe_rpc_id: &str,
+    node_address: &str,
+    verbosity_level: u64,
+    maybe_block_id: &str,
+    dictionary_str_params: DictionaryItemStrParams<'_>,
+    trusted_validators: &BTreeMap<PublicKey, U512>,
+) -> Result<JsonRpc> {
+    let (_, json_block) = get_finalized_block(
+        maybe_rpc_id,
+        node_address,
+        verbosity_level,
+        maybe_block_id,
+        trusted_validators,
+    )
+    .await?;
+    let response = RpcCall::new(maybe_rpc_id, node_address, verbosity_level)
+        .get_dictionary_item(
+            &json_block.header.state_root_hash.to_string(),
+            dictionary_str_params,
+        )
+        .await?;
+    validation::validate_get_dictionary_item_response(
+        &response,
+        &json_block.header.state_root_hash,
+    )?;
+    Ok(response)
+}
+
+/// Downloads up to `count` block headers following the latest header of `chain`, along with their
+/// finality signatures, and appends each to `chain` once it has been verified.
+///
+/// Returns the number of headers appended, which is less than `count` if the node doesn't have
+/// enough blocks.  If a header fails verification, an error is returned and `chain` contains all
+/// headers verified before it.
+///
+/// * `maybe_rpc_id`, `node_address` and `verbosity_level` are as described in
+///   [`get_block()`](fn.get_block.html).
+/// * `chain` is the chain of trusted headers to extend.
+/// * `count` is the maximum number of headers to download.
+pub async fn sync_headers(
+    maybe_rpc_id: &str,
+    node_address: &str,
+    verbosity_level: u64,
+    chain: &mut TrustedHeaderChain,
+    count: u32,
+) -> Result<u32> {
+    let mut synced = 0;
+    while synced < count {
+        let start_height = chain.latest_header().height() + 1;
+        let response = RpcCall::new(maybe_rpc_id, node_address, verbosity_level)
+            .get_block_headers(start_height, count - synced)
+            .await?;
+        let value = response
+            .get_result()
+            .ok_or(ValidateResponseError::ValidateResponseFailedToParse)?;
+        let result: GetBlockHeadersResult = serde_json::from_value(value.to_owned())?;
+        if result.headers.is_empty() {
+            break;
+        }
+
+        for header_with_signatures in result.headers.into_iter().take((count - synced) as usize) {
+            let header = BlockHeader::from(header_with_signatures.header);
+            let mut signatures = BlockSignatures::new(header.hash(), header.era_id());
+            for proof in header_with_signatures.proofs {
+                let (public_key, signature) = proof.into();
+                signatures.insert_proof(public_key, signature);
+            }
+            chain.push(header, &signatures)?;
+            synced += 1;
+        }
+    }
+    Ok(synced)
+}
+
+/// Retrieves the given `Block` and verifies it against the finality signatures of the trusted
+/// validators, returning both the response and the parsed `Block`.
+async fn get_finalized_block(
+    maybe_rpc_id: &str,
+    node_address: &str,
+    verbosity_level: u64,
+    maybe_block_id: &str,
+    trusted_validators: &BTreeMap<PublicKey, U512>,
+) -> Result<(JsonRpc, JsonBlock)> {
+    let response = RpcCall::new(maybe_rpc_id, node_address, verbosity_level)
+        .get_block(maybe_block_id)
+        .await?;
+    let value = response
+        .get_result()
+        .ok_or(ValidateResponseError::ValidateResponseFailedToParse)?;
+    let result: GetBlockResult = serde_json::from_value(value.to_owned())?;
+    let json_block = result
+        .block
+        .ok_or(ValidateResponseError::NoBlockInResponse)?;
+    validation::validate_block_finality(&json_block, trusted_validators)?;
+    Ok((response, json_block))
+}
@@ -653,6 +653,26 @@ pub(crate) fn transfer_id(value: &str) -> Result<u64> {
     })
 }
 
+pub(crate) fn block_height(value: &str, context: &'static str) -> Result<u64> {
+    value
+        .parse()
+        .map_err(|error| Error::FailedToParseInt { context, error })
+}
+
+pub(crate) fn safety_margin(value: &str) -> Result<u32> {
+    value.parse().map_err(|error| Error::FailedToParseInt {
+        context: "safety_margin",
+        error,
+    })
+}
+
+pub(crate) fn timeout(value: &str) -> Result<TimeDiff> {
+    TimeDiff::from_str(value).map_err(|error| Error::FailedToParseTimeDiff {
+        context: "timeout",
+        error,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use casper_types::{
@@ -1157,6 +1177,26 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn should_parse_safety_margin() {
+        assert_eq!(safety_margin("0").unwrap(), 0);
+        assert_eq!(safety_margin("10").unwrap(), 10);
+        assert_eq!(safety_margin("250").unwrap(), 250);
+    }
+
+    #[test]
+    fn should_fail_to_parse_invalid_safety_margin() {
+        for value in &["", "-1", "10%", "1.5"] {
+            assert!(matches!(
+                safety_margin(value),
+                Err(Error::FailedToParseInt {
+                    context: "safety_margin",
+                    ..
+                })
+            ));
+        }
+    }
+
     mod missing_args {
 
         use super::*;
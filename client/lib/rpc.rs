@@ -12,10 +12,14 @@ use casper_hashing::Digest;
 use casper_node::{
     crypto,
     rpcs::{
-        account::{PutDeploy, PutDeployParams},
+        account::{
+            GetAccountDeploys, GetAccountDeploysParams, PutDeploy, PutDeployParams,
+            SpeculativeExec, SpeculativeExecParams,
+        },
         chain::{
-            BlockIdentifier, GetBlock, GetBlockParams, GetBlockTransfers, GetBlockTransfersParams,
-            GetEraInfoBySwitchBlock, GetEraInfoParams, GetStateRootHash, GetStateRootHashParams,
+            BlockIdentifier, GetBlock, GetBlockHeaders, GetBlockHeadersParams, GetBlockParams,
+            GetBlockTransfers, GetBlockTransfersParams, GetEraInfoBySwitchBlock, GetEraInfoParams,
+            GetStateRootHash, GetStateRootHashParams,
         },
         docs::ListRpcs,
         info::{GetDeploy, GetDeployParams, GetValidatorChanges},
@@ -134,8 +138,7 @@ impl RpcCall {
             dictionary_identifier,
         };
 
-        let response = GetDictionaryItem::request_with_map_params(self, params).await?;
-        Ok(response)
+        GetDictionaryItem::request_with_map_params(self, params).await
     }
 
     pub(crate) async fn get_state_root_hash(self, maybe_block_identifier: &str) -> Result<JsonRpc> {
@@ -242,6 +245,19 @@ impl RpcCall {
         PutDeploy::request_with_map_params(self, params).await
     }
 
+    pub(crate) async fn speculative_exec(
+        self,
+        maybe_block_identifier: &str,
+        deploy: Deploy,
+    ) -> Result<JsonRpc> {
+        let block_identifier = Self::block_identifier(maybe_block_identifier)?;
+        let params = SpeculativeExecParams {
+            block_identifier,
+            deploy,
+        };
+        SpeculativeExec::request_with_map_params(self, params).await
+    }
+
     pub(crate) async fn get_block(self, maybe_block_identifier: &str) -> Result<JsonRpc> {
         let maybe_block_identifier = Self::block_identifier(maybe_block_identifier)?;
         let response = match maybe_block_identifier {
@@ -255,6 +271,14 @@ impl RpcCall {
         Ok(response)
     }
 
+    pub(crate) async fn get_block_headers(self, start_height: u64, count: u32) -> Result<JsonRpc> {
+        let params = GetBlockHeadersParams {
+            start_height,
+            count,
+        };
+        GetBlockHeaders::request_with_map_params(self, params).await
+    }
+
     pub(crate) async fn get_block_transfers(self, maybe_block_identifier: &str) -> Result<JsonRpc> {
         let maybe_block_identifier = Self::block_identifier(maybe_block_identifier)?;
         let response = match maybe_block_identifier {
@@ -285,6 +309,52 @@ impl RpcCall {
         GetAccountInfo::request_with_map_params(self, params).await
     }
 
+    pub(crate) async fn get_account_deploys(
+        self,
+        public_key: &str,
+        maybe_start_height: &str,
+        maybe_cursor: &str,
+        maybe_limit: &str,
+    ) -> Result<JsonRpc> {
+        let public_key = PublicKey::from_hex(public_key).map_err(|_| Error::FailedToParseKey)?;
+        let start_height = if maybe_start_height.is_empty() {
+            None
+        } else {
+            let height = maybe_start_height
+                .parse()
+                .map_err(|error| Error::FailedToParseInt {
+                    context: "start_height",
+                    error,
+                })?;
+            Some(height)
+        };
+        let cursor = if maybe_cursor.is_empty() {
+            None
+        } else {
+            let hash = Digest::from_hex(maybe_cursor)
+                .map_err(|error| map_hashing_error(error)("cursor"))?;
+            Some(DeployHash::new(hash))
+        };
+        let limit = if maybe_limit.is_empty() {
+            None
+        } else {
+            let limit = maybe_limit
+                .parse()
+                .map_err(|error| Error::FailedToParseInt {
+                    context: "limit",
+                    error,
+                })?;
+            Some(limit)
+        };
+        let params = GetAccountDeploysParams {
+            public_key,
+            start_height,
+            cursor,
+            limit,
+        };
+        GetAccountDeploys::request_with_map_params(self, params).await
+    }
+
     pub(crate) async fn query_global_state(
         self,
         global_state_str_params: GlobalStateStrParams<'_>,
@@ -434,10 +504,18 @@ impl RpcClient for GetBalance {
     const RPC_METHOD: &'static str = Self::METHOD;
 }
 
+impl RpcClient for SpeculativeExec {
+    const RPC_METHOD: &'static str = Self::METHOD;
+}
+
 impl RpcClient for GetBlock {
     const RPC_METHOD: &'static str = Self::METHOD;
 }
 
+impl RpcClient for GetBlockHeaders {
+    const RPC_METHOD: &'static str = Self::METHOD;
+}
+
 impl RpcClient for GetBlockTransfers {
     const RPC_METHOD: &'static str = Self::METHOD;
 }
@@ -466,6 +544,10 @@ impl RpcClient for GetAccountInfo {
     const RPC_METHOD: &'static str = Self::METHOD;
 }
 
+impl RpcClient for GetAccountDeploys {
+    const RPC_METHOD: &'static str = Self::METHOD;
+}
+
 impl RpcClient for GetDictionaryItem {
     const RPC_METHOD: &'static str = Self::METHOD;
 }
@@ -491,7 +573,9 @@ pub(crate) trait IntoJsonMap: Serialize {
 }
 
 impl IntoJsonMap for PutDeployParams {}
+impl IntoJsonMap for SpeculativeExecParams {}
 impl IntoJsonMap for GetBlockParams {}
+impl IntoJsonMap for GetBlockHeadersParams {}
 impl IntoJsonMap for GetBlockTransfersParams {}
 impl IntoJsonMap for GetStateRootHashParams {}
 impl IntoJsonMap for GetDeployParams {}
@@ -501,5 +585,6 @@ impl IntoJsonMap for GetEraInfoParams {}
 impl IntoJsonMap for ListRpcs {}
 impl IntoJsonMap for GetAuctionInfoParams {}
 impl IntoJsonMap for GetAccountInfoParams {}
+impl IntoJsonMap for GetAccountDeploysParams {}
 impl IntoJsonMap for GetDictionaryItemParams {}
 impl IntoJsonMap for QueryGlobalStateParams {}
@@ -1,4 +1,4 @@
-use std::convert::TryFrom;
+use std::{collections::BTreeMap, convert::TryFrom};
 
 use jsonrpc_lite::JsonRpc;
 use thiserror::Error;
@@ -10,13 +10,14 @@ use casper_hashing::Digest;
 use casper_node::{
     rpcs::{
         chain::{BlockIdentifier, EraSummary, GetEraInfoResult},
-        state::GlobalStateIdentifier,
+        state::{GetAccountInfoResult, GetDictionaryItemResult, GlobalStateIdentifier},
     },
     types::{
-        json_compatibility, Block, BlockHeader, BlockValidationError, JsonBlock, JsonBlockHeader,
+        json_compatibility, validate_finality_signatures, Block, BlockHeader, BlockSignatures,
+        BlockValidationError, HeaderChainError, JsonBlock, JsonBlockHeader,
     },
 };
-use casper_types::{bytesrepr, Key, StoredValue, U512};
+use casper_types::{bytesrepr, Key, PublicKey, StoredValue, U512};
 
 const GET_ITEM_RESULT_BALANCE_VALUE: &str = "balance_value";
 const GET_ITEM_RESULT_STORED_VALUE: &str = "stored_value";
@@ -65,6 +66,10 @@ pub enum ValidateResponseError {
     /// An invalid combination of state identifier and block header response
     #[error("Invalid combination of state identifier and block header in response")]
     InvalidGlobalStateResponse,
+
+    /// A block was not finalized by the trusted validators.
+    #[error("block not finalized by the trusted validators: {0}")]
+    NotFinalized(#[from] HeaderChainError),
 }
 
 impl From<bytesrepr::Error> for ValidateResponseError {
@@ -221,8 +226,11 @@ pub(crate) fn validate_query_global_state(
         | (GlobalStateIdentifier::StateRootHash(_), Some(_)) => {
             return Err(ValidateResponseError::InvalidGlobalStateResponse);
         }
-        (GlobalStateIdentifier::BlockHash(_), Some(json_header)) => {
+        (GlobalStateIdentifier::BlockHash(block_hash), Some(json_header)) => {
             let block_header = BlockHeader::from(json_header);
+            if block_header.hash() != block_hash {
+                return Err(ValidateResponseError::UnexpectedBlockHash);
+            }
             *block_header.state_root_hash()
         }
         (GlobalStateIdentifier::StateRootHash(hash), None) => hash,
@@ -232,6 +240,70 @@ pub(crate) fn validate_query_global_state(
         .map_err(Into::into)
 }
 
+pub(crate) fn validate_get_account_info_response(
+    response: &JsonRpc,
+    state_root_hash: &Digest,
+    key: &Key,
+) -> Result<(), ValidateResponseError> {
+    let value = response
+        .get_result()
+        .ok_or(ValidateResponseError::ValidateResponseFailedToParse)?;
+
+    let result: GetAccountInfoResult = serde_json::from_value(value.to_owned())?;
+
+    let proof_bytes = base16::decode(&result.merkle_proof)
+        .map_err(|_| ValidateResponseError::ValidateResponseFailedToParse)?;
+    let proofs: Vec<TrieMerkleProof<Key, StoredValue>> = bytesrepr::deserialize(proof_bytes)?;
+
+    let proof_value: &StoredValue = {
+        let last_proof = proofs
+            .last()
+            .ok_or(ValidateResponseError::ValidateResponseFailedToParse)?;
+        last_proof.value()
+    };
+
+    match json_compatibility::StoredValue::try_from(proof_value.clone()) {
+        Ok(json_compatibility::StoredValue::Account(account)) if account == result.account => (),
+        _ => return Err(ValidateResponseError::SerializedValueNotContainedInProof),
+    }
+
+    core::validate_query_proof(&state_root_hash.to_owned(), &proofs, key, &[], proof_value)
+        .map_err(Into::into)
+}
+
+pub(crate) fn validate_get_dictionary_item_response(
+    response: &JsonRpc,
+    state_root_hash: &Digest,
+) -> Result<(), ValidateResponseError> {
+    let value = response
+        .get_result()
+        .ok_or(ValidateResponseError::ValidateResponseFailedToParse)?;
+
+    let result: GetDictionaryItemResult = serde_json::from_value(value.to_owned())?;
+
+    let key = Key::from_formatted_str(&result.dictionary_key)
+        .map_err(|_| ValidateResponseError::ValidateResponseFailedToParse)?;
+
+    let proof_bytes = base16::decode(&result.merkle_proof)
+        .map_err(|_| ValidateResponseError::ValidateResponseFailedToParse)?;
+    let proofs: Vec<TrieMerkleProof<Key, StoredValue>> = bytesrepr::deserialize(proof_bytes)?;
+
+    let proof_value: &StoredValue = {
+        let last_proof = proofs
+            .last()
+            .ok_or(ValidateResponseError::ValidateResponseFailedToParse)?;
+        last_proof.value()
+    };
+
+    match json_compatibility::StoredValue::try_from(proof_value.clone()) {
+        Ok(json_proof_value) if json_proof_value == result.stored_value => (),
+        _ => return Err(ValidateResponseError::SerializedValueNotContainedInProof),
+    }
+
+    core::validate_query_proof(&state_root_hash.to_owned(), &proofs, &key, &[], proof_value)
+        .map_err(Into::into)
+}
+
 pub(crate) fn validate_get_balance_response(
     response: &JsonRpc,
     state_root_hash: &Digest,
@@ -307,3 +379,91 @@ pub(crate) fn validate_get_block_response(
     }
     Ok(())
 }
+
+/// Validates that the given block was finalized by the trusted validators, i.e. that it carries
+/// valid finality signatures from trusted validators holding more than a third of their total
+/// weight.
+pub(crate) fn validate_block_finality(
+    json_block: &JsonBlock,
+    trusted_validators: &BTreeMap<PublicKey, U512>,
+) -> Result<(), ValidateResponseError> {
+    let mut signatures = BlockSignatures::new(json_block.hash, json_block.header.era_id);
+    for proof in &json_block.proofs {
+        signatures.insert_proof(proof.public_key().clone(), *proof.signature());
+    }
+    validate_finality_signatures(&signatures, trusted_validators)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use casper_node::{
+        rpcs::docs::DocExample,
+        types::{BlockHash, FinalitySignature, JsonProof},
+    };
+    use casper_types::SecretKey;
+
+    use super::*;
+
+    fn validator(seed: u8) -> (SecretKey, PublicKey) {
+        let secret_key = SecretKey::ed25519_from_bytes([seed; 32]).unwrap();
+        let public_key = PublicKey::from(&secret_key);
+        (secret_key, public_key)
+    }
+
+    /// Returns the example block, signed by the validators with the given seeds.
+    fn block_signed_by(seeds: &[u8]) -> JsonBlock {
+        let mut json_block = JsonBlock::new(Block::doc_example().clone(), None);
+        let (block_hash, era_id) = (json_block.hash, json_block.header.era_id);
+        json_block.proofs = seeds
+            .iter()
+            .map(|seed| {
+                let (secret_key, public_key) = validator(*seed);
+                let finality_signature =
+                    FinalitySignature::new(block_hash, era_id, &secret_key, public_key.clone());
+                JsonProof::from((public_key, finality_signature.signature))
+            })
+            .collect();
+        json_block
+    }
+
+    fn trusted_validators(weights: &[(u8, u64)]) -> BTreeMap<PublicKey, U512> {
+        weights
+            .iter()
+            .map(|(seed, weight)| (validator(*seed).1, U512::from(*weight)))
+            .collect()
+    }
+
+    #[test]
+    fn should_accept_block_signed_by_more_than_a_third_of_the_weight() {
+        let trusted_validators = trusted_validators(&[(1, 2), (2, 1), (3, 2)]);
+        // Signatures by validators which are not trusted don't count towards the weight.
+        let json_block = block_signed_by(&[1, 4]);
+        assert!(validate_block_finality(&json_block, &trusted_validators).is_ok());
+    }
+
+    #[test]
+    fn should_reject_block_signed_by_at_most_a_third_of_the_weight() {
+        let trusted_validators = trusted_validators(&[(1, 1), (2, 1), (3, 1)]);
+        let json_block = block_signed_by(&[1, 4]);
+        assert!(matches!(
+            validate_block_finality(&json_block, &trusted_validators),
+            Err(ValidateResponseError::NotFinalized(
+                HeaderChainError::InsufficientFinalitySignatures { signed_weight, total_weight }
+            )) if signed_weight == U512::from(1) && total_weight == U512::from(3)
+        ));
+    }
+
+    #[test]
+    fn should_reject_block_with_invalid_signature() {
+        let trusted_validators = trusted_validators(&[(1, 1)]);
+        let mut json_block = block_signed_by(&[1]);
+        json_block.hash = BlockHash::new(Digest::hash(b"another block"));
+        assert!(matches!(
+            validate_block_finality(&json_block, &trusted_validators),
+            Err(ValidateResponseError::NotFinalized(
+                HeaderChainError::InvalidFinalitySignature(_)
+            ))
+        ));
+    }
+}
@@ -2,7 +2,7 @@ use async_trait::async_trait;
 use clap::{App, ArgMatches};
 use jsonrpc_lite::JsonRpc;
 
-use casper_client::Error;
+use casper_client::{DeployStatus, Error};
 
 /// The result of a successful execution of a given client command.
 pub enum Success {
@@ -10,6 +10,9 @@ pub enum Success {
     Response(JsonRpc),
     /// The output which should be presented to the user for non-RPC client commands.
     Output(String),
+    /// The status of a deploy which has been waited for, its outcome determining the process exit
+    /// code.
+    DeployStatus(DeployStatus),
 }
 
 impl From<JsonRpc> for Success {
@@ -110,6 +110,10 @@ pub mod secret_key {
             .value_of(ARG_NAME)
             .unwrap_or_else(|| panic!("should have {} arg", ARG_NAME))
     }
+
+    pub fn get_optional<'a>(matches: &'a ArgMatches) -> Option<&'a str> {
+        matches.value_of(ARG_NAME)
+    }
 }
 
 /// Handles the arg for whether to overwrite existing output file(s).
@@ -147,7 +151,7 @@ pub mod force {
 pub mod state_root_hash {
     use super::*;
 
-    const ARG_NAME: &str = "state-root-hash";
+    pub(crate) const ARG_NAME: &str = "state-root-hash";
     const ARG_SHORT: &str = "s";
     const ARG_VALUE_NAME: &str = super::ARG_HEX_STRING;
     const ARG_HELP: &str = "Hex-encoded hash of the state root";
@@ -167,13 +171,17 @@ pub mod state_root_hash {
             .value_of(ARG_NAME)
             .unwrap_or_else(|| panic!("should have {} arg", ARG_NAME))
     }
+
+    pub(crate) fn get_optional<'a>(matches: &'a ArgMatches) -> Option<&'a str> {
+        matches.value_of(ARG_NAME)
+    }
 }
 
 /// Handles providing the arg for and retrieval of the block hash or block height.
 pub mod block_identifier {
     use super::*;
 
-    const ARG_NAME: &str = "block-identifier";
+    pub(crate) const ARG_NAME: &str = "block-identifier";
     const ARG_SHORT: &str = "b";
     const ARG_VALUE_NAME: &str = "HEX STRING OR INTEGER";
     const ARG_HELP: &str =
@@ -195,6 +203,60 @@ pub mod block_identifier {
     }
 }
 
+/// Handles providing the arg for and retrieval of the node's event stream hostname/IP and port.
+pub mod event_stream_address {
+    use super::*;
+
+    const ARG_NAME: &str = "event-stream-address";
+    const ARG_VALUE_NAME: &str = "HOST:PORT";
+    const ARG_HELP: &str =
+        "Hostname or IP and port of node on which the event stream service is running, e.g. \
+        'http://localhost:9999'. If given, the outcome of the deploy is taken from its events, \
+        otherwise, or if the event stream can't be reached, the deploy is polled for using \
+        --node-address";
+
+    pub(crate) fn arg(order: usize) -> Arg<'static, 'static> {
+        Arg::with_name(ARG_NAME)
+            .long(ARG_NAME)
+            .required(false)
+            .value_name(ARG_VALUE_NAME)
+            .help(ARG_HELP)
+            .display_order(order)
+    }
+
+    pub(crate) fn get<'a>(matches: &'a ArgMatches) -> &'a str {
+        matches.value_of(ARG_NAME).unwrap_or_default()
+    }
+}
+
+/// Handles providing the arg for and retrieval of the time to wait for a deploy's outcome.
+pub mod wait_timeout {
+    use super::*;
+
+    const ARG_NAME: &str = "timeout";
+    const ARG_VALUE_NAME: &str = "DURATION";
+    const ARG_DEFAULT: &str = "5min";
+    const ARG_HELP: &str =
+        "Time to wait for the deploy to be executed or to expire, e.g. '90s' or '10min', after \
+        which the command fails";
+
+    pub(crate) fn arg(order: usize) -> Arg<'static, 'static> {
+        Arg::with_name(ARG_NAME)
+            .long(ARG_NAME)
+            .required(false)
+            .default_value(ARG_DEFAULT)
+            .value_name(ARG_VALUE_NAME)
+            .help(ARG_HELP)
+            .display_order(order)
+    }
+
+    pub(crate) fn get<'a>(matches: &'a ArgMatches) -> &'a str {
+        matches
+            .value_of(ARG_NAME)
+            .unwrap_or_else(|| panic!("should have {} arg", ARG_NAME))
+    }
+}
+
 /// Internal module to handle providing the arg for and retrieval of the public key or session
 /// account.
 mod sealed_public_key {
@@ -231,7 +293,12 @@ mod sealed_public_key {
                 ""
             }
         });
+        parse(value)
+    }
 
+    /// Parses `value` as a hex-encoded public key or as the path to a public key file, returning
+    /// the hex-encoded public key.
+    pub(super) fn parse(value: &str) -> Result<String, Error> {
         // Try to read as a PublicKey PEM file first.
         if let Ok(public_key) = PublicKey::from_file(value) {
             return Ok(public_key.to_hex());
@@ -253,6 +320,38 @@ mod sealed_public_key {
     }
 }
 
+/// Handles providing the arg for and retrieval of the public keys of the signers whose approvals
+/// are required for a deploy.
+pub(super) mod required_signer {
+    use super::*;
+
+    const ARG_NAME: &str = "required-signer";
+    const ARG_VALUE_NAME: &str = "FORMATTED STRING or PATH";
+    const ARG_HELP: &str =
+        "Public key of a signer whose approval is required for the deploy. May be given multiple \
+        times. The required signers are recorded in a file alongside the output deploy file, and \
+        those who have not yet signed it are listed by the 'check-approvals' subcommand. Each \
+        public key may instead be read in from a file, as for --public-key";
+
+    pub fn arg(order: usize) -> Arg<'static, 'static> {
+        Arg::with_name(ARG_NAME)
+            .long(ARG_NAME)
+            .required(false)
+            .multiple(true)
+            .number_of_values(1)
+            .value_name(ARG_VALUE_NAME)
+            .help(ARG_HELP)
+            .display_order(order)
+    }
+
+    pub fn get(matches: &ArgMatches) -> Result<Vec<String>, Error> {
+        matches
+            .values_of(ARG_NAME)
+            .map(|values| values.map(sealed_public_key::parse).collect())
+            .unwrap_or_else(|| Ok(vec![]))
+    }
+}
+
 /// Handles providing the arg for and retrieval of the public key.
 pub(super) mod public_key {
     use super::*;
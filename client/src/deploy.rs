@@ -1,3 +1,4 @@
+mod check_approvals;
 mod creation_common;
 mod get;
 mod list;
@@ -6,11 +7,15 @@ mod make_transfer;
 mod put;
 mod send;
 mod sign;
+mod speculative_exec;
 mod transfer;
+mod wait;
 
+pub use check_approvals::CheckApprovals;
 pub use list::ListDeploys;
 pub use make::MakeDeploy;
 pub use make_transfer::MakeTransfer;
 pub use send::SendDeploy;
 pub use sign::SignDeploy;
 pub use transfer::Transfer;
+pub use wait::WaitDeploy;
@@ -5,7 +5,7 @@ use std::process;
 
 use clap::{App, AppSettings, Arg, ArgGroup, ArgMatches};
 
-use casper_client::{help, PaymentStrParams, SessionStrParams};
+use casper_client::{help, DeployStrParams, Error, PaymentStrParams, SessionStrParams};
 
 use crate::common;
 
@@ -15,9 +15,12 @@ pub(super) enum DisplayOrder {
     Verbose,
     NodeAddress,
     RpcId,
+    BlockIdentifier,
     SecretKey,
     Input,
+    PartialDeploy,
     Output,
+    RequiredSigner,
     TransferAmount,
     TransferTargetAccount,
     TransferId,
@@ -38,6 +41,8 @@ pub(super) enum DisplayOrder {
     SessionTransfer,
     SessionAccount,
     StandardPayment,
+    EstimatePayment,
+    EstimatePaymentMargin,
     PaymentCode,
     PaymentArgSimple,
     PaymentArgsComplex,
@@ -47,6 +52,9 @@ pub(super) enum DisplayOrder {
     PaymentPackageName,
     PaymentEntryPoint,
     PaymentVersion,
+    Wait,
+    EventStreamAddress,
+    WaitTimeout,
     Force,
 }
 
@@ -185,6 +193,40 @@ pub(super) fn payment_str_params<'a>(matches: &'a ArgMatches) -> PaymentStrParam
     unreachable!("clap arg groups and parsing should prevent this")
 }
 
+/// Returns the standard payment amount estimated by the node if `--estimate-payment` was passed.
+pub(super) async fn estimated_payment_amount(
+    matches: &ArgMatches<'_>,
+    maybe_rpc_id: &str,
+    node_address: &str,
+    verbosity_level: u64,
+) -> Result<Option<String>, Error> {
+    let max_payment_amount = match estimate_payment::get(matches) {
+        Some(max_payment_amount) => max_payment_amount,
+        None => return Ok(None),
+    };
+    let session_account = common::session_account::get(matches)?;
+
+    casper_client::estimate_payment(
+        maybe_rpc_id,
+        node_address,
+        verbosity_level,
+        DeployStrParams {
+            secret_key: common::secret_key::get(matches),
+            timestamp: timestamp::get(matches),
+            ttl: ttl::get(matches),
+            gas_price: gas_price::get(matches),
+            dependencies: dependencies::get(matches),
+            chain_name: chain_name::get(matches),
+            session_account: &session_account,
+        },
+        session_str_params(matches),
+        max_payment_amount,
+        estimate_payment_margin::get(matches),
+    )
+    .await
+    .map(|payment_amount| Some(payment_amount.to_string()))
+}
+
 /// Handles providing the arg for and retrieval of the timestamp.
 pub(super) mod timestamp {
     use super::*;
@@ -206,8 +248,10 @@ pub(super) mod timestamp {
             .display_order(DisplayOrder::Timestamp as usize)
     }
 
-    pub(in crate::deploy) fn get<'a>(matches: &'a ArgMatches) -> &'a str {
-        matches.value_of(ARG_NAME).unwrap_or_default()
+    pub fn get<'a>(matches: &'a ArgMatches) -> &'a str {
+        matches
+            .value_of(ARG_NAME)
+            .unwrap_or_else(|| panic!("should have {} arg", ARG_NAME))
     }
 }
 
@@ -234,8 +278,10 @@ pub(super) mod ttl {
             .display_order(DisplayOrder::Ttl as usize)
     }
 
-    pub(in crate::deploy) fn get<'a>(matches: &'a ArgMatches) -> &'a str {
-        matches.value_of(ARG_NAME).unwrap_or_default()
+    pub fn get<'a>(matches: &'a ArgMatches) -> &'a str {
+        matches
+            .value_of(ARG_NAME)
+            .unwrap_or_else(|| panic!("should have {} arg", ARG_NAME))
     }
 }
 
@@ -259,8 +305,10 @@ pub(super) mod gas_price {
             .display_order(DisplayOrder::GasPrice as usize)
     }
 
-    pub(in crate::deploy) fn get<'a>(matches: &'a ArgMatches) -> &'a str {
-        matches.value_of(ARG_NAME).unwrap_or_default()
+    pub fn get<'a>(matches: &'a ArgMatches) -> &'a str {
+        matches
+            .value_of(ARG_NAME)
+            .unwrap_or_else(|| panic!("should have {} arg", ARG_NAME))
     }
 }
 
@@ -513,6 +561,97 @@ pub(super) mod standard_payment_amount {
     }
 }
 
+/// Handles providing the arg for and retrieval of the estimate-payment arg.
+pub(super) mod estimate_payment {
+    use super::*;
+
+    pub(in crate::deploy) const ARG_NAME: &str = "estimate-payment";
+    const ARG_VALUE_NAME: &str = "MAX_AMOUNT";
+    const ARG_HELP: &str =
+        "If provided, uses the standard-payment system contract with an amount estimated by \
+        executing the deploy speculatively on the node at --node-address. The value is the \
+        'amount' arg used for that execution, so must cover its cost and be held by the session \
+        account. The estimated amount is the resulting cost plus --estimate-payment-margin. This \
+        arg is incompatible with all other --payment-xxx args";
+
+    pub(in crate::deploy) fn arg() -> Arg<'static, 'static> {
+        Arg::with_name(ARG_NAME)
+            .long(ARG_NAME)
+            .required(false)
+            .value_name(ARG_VALUE_NAME)
+            .help(ARG_HELP)
+            .display_order(DisplayOrder::EstimatePayment as usize)
+    }
+
+    pub fn get<'a>(matches: &'a ArgMatches) -> Option<&'a str> {
+        matches.value_of(ARG_NAME)
+    }
+}
+
+/// Handles providing the arg for and retrieval of the estimate-payment-margin arg.
+pub(super) mod estimate_payment_margin {
+    use super::*;
+
+    const ARG_NAME: &str = "estimate-payment-margin";
+    const ARG_VALUE_NAME: &str = "PERCENT";
+    const ARG_DEFAULT: &str = "10";
+    const ARG_HELP: &str =
+        "The safety margin added to the cost measured by --estimate-payment, as a percentage of \
+        that cost";
+
+    pub(in crate::deploy) fn arg() -> Arg<'static, 'static> {
+        Arg::with_name(ARG_NAME)
+            .long(ARG_NAME)
+            .required(false)
+            .default_value(ARG_DEFAULT)
+            .value_name(ARG_VALUE_NAME)
+            .help(ARG_HELP)
+            .display_order(DisplayOrder::EstimatePaymentMargin as usize)
+    }
+
+    pub fn get<'a>(matches: &'a ArgMatches) -> &'a str {
+        matches
+            .value_of(ARG_NAME)
+            .unwrap_or_else(|| panic!("should have {} arg", ARG_NAME))
+    }
+}
+
+/// Handles providing the arg for and retrieval of the wait flag.
+pub(super) mod wait {
+    use super::*;
+
+    pub(in crate::deploy) const ARG_NAME: &str = "wait";
+    const ARG_HELP: &str =
+        "If passed, waits for the deploy to be executed or to expire, then prints its outcome. \
+        The command exits with 0 if the deploy executed successfully, 2 if its execution failed, \
+        3 if it expired and 4 if --timeout elapsed first";
+
+    pub(in crate::deploy) fn arg() -> Arg<'static, 'static> {
+        Arg::with_name(ARG_NAME)
+            .long(ARG_NAME)
+            .required(false)
+            .help(ARG_HELP)
+            .display_order(DisplayOrder::Wait as usize)
+    }
+
+    pub(in crate::deploy) fn get(matches: &ArgMatches) -> bool {
+        matches.is_present(ARG_NAME)
+    }
+}
+
+/// Applies the args for waiting for the outcome of a deploy sent to the network.
+pub(super) fn apply_wait_options<'a, 'b>(subcommand: App<'a, 'b>) -> App<'a, 'b> {
+    subcommand
+        .arg(wait::arg())
+        .arg(
+            common::event_stream_address::arg(DisplayOrder::EventStreamAddress as usize)
+                .requires(wait::ARG_NAME),
+        )
+        .arg(common::wait_timeout::arg(
+            DisplayOrder::WaitTimeout as usize,
+        ))
+}
+
 pub(super) fn apply_common_creation_options<'a, 'b>(
     subcommand: App<'a, 'b>,
     include_node_address: bool,
@@ -610,6 +749,16 @@ pub(crate) fn apply_common_payment_options(
         )
 }
 
+/// Adds the args for estimating the standard payment amount, adding `--estimate-payment` to the
+/// "payment" group set up by `apply_common_payment_options()`.
+pub(super) fn apply_payment_estimation_options(
+    subcommand: App<'static, 'static>,
+) -> App<'static, 'static> {
+    subcommand
+        .arg(estimate_payment::arg().group("payment"))
+        .arg(estimate_payment_margin::arg())
+}
+
 pub(super) fn show_arg_examples_and_exit_if_required(matches: &ArgMatches<'_>) {
     // If we printed the arg examples, exit the process.
     if show_arg_examples::get(matches) {
@@ -620,7 +769,7 @@ pub(super) fn show_arg_examples_and_exit_if_required(matches: &ArgMatches<'_>) {
 pub(super) mod output {
     use super::*;
 
-    const ARG_NAME: &str = "output";
+    pub(in crate::deploy) const ARG_NAME: &str = "output";
     const ARG_SHORT_NAME: &str = "o";
     const ARG_VALUE_NAME: &str = common::ARG_PATH;
     const ARG_HELP: &str = "Path to output deploy file. If omitted, defaults to stdout. If file exists, it will be overwritten";
@@ -1,7 +1,7 @@
 use async_trait::async_trait;
 use clap::{App, ArgMatches, SubCommand};
 
-use casper_client::{DeployStrParams, Error};
+use casper_client::{DeployStrParams, Error, PaymentStrParams};
 
 use super::creation_common;
 use crate::{command::ClientCommand, common, Success};
@@ -19,7 +19,20 @@ impl<'a, 'b> ClientCommand<'a, 'b> for MakeDeploy {
     fn build(display_order: usize) -> App<'a, 'b> {
         let subcommand = SubCommand::with_name(Self::NAME)
             .about(Self::ABOUT)
+            .arg(
+                common::node_address::arg(creation_common::DisplayOrder::NodeAddress as usize)
+                    .required(false),
+            )
+            .arg(common::rpc_id::arg(
+                creation_common::DisplayOrder::RpcId as usize,
+            ))
             .arg(creation_common::output::arg())
+            .arg(
+                common::required_signer::arg(
+                    creation_common::DisplayOrder::RequiredSigner as usize,
+                )
+                .requires(creation_common::output::ARG_NAME),
+            )
             .arg(common::force::arg(
                 creation_common::DisplayOrder::Force as usize,
                 true,
@@ -27,6 +40,7 @@ impl<'a, 'b> ClientCommand<'a, 'b> for MakeDeploy {
             .display_order(display_order);
         let subcommand = creation_common::apply_common_session_options(subcommand);
         let subcommand = creation_common::apply_common_payment_options(subcommand);
+        let subcommand = creation_common::apply_payment_estimation_options(subcommand);
         creation_common::apply_common_creation_options(subcommand, false)
     }
 
@@ -41,10 +55,19 @@ impl<'a, 'b> ClientCommand<'a, 'b> for MakeDeploy {
         let chain_name = creation_common::chain_name::get(matches);
 
         let session_str_params = creation_common::session_str_params(matches);
-        let payment_str_params = creation_common::payment_str_params(matches);
+        let maybe_rpc_id = common::rpc_id::get(matches);
+        let node_address = common::node_address::get(matches);
+        let estimated_payment_amount =
+            creation_common::estimated_payment_amount(matches, maybe_rpc_id, node_address, 0)
+                .await?;
+        let payment_str_params = match estimated_payment_amount.as_deref() {
+            Some(payment_amount) => PaymentStrParams::with_amount(payment_amount),
+            None => creation_common::payment_str_params(matches),
+        };
 
         let maybe_output_path = creation_common::output::get(matches).unwrap_or_default();
         let session_account = common::session_account::get(matches).unwrap_or_default();
+        let required_signers = common::required_signer::get(matches)?;
 
         let force = common::force::get(matches);
 
@@ -62,13 +85,17 @@ impl<'a, 'b> ClientCommand<'a, 'b> for MakeDeploy {
             session_str_params,
             payment_str_params,
             force,
-        )
-        .map(|_| {
-            Success::Output(if maybe_output_path.is_empty() {
-                String::new()
-            } else {
-                format!("Wrote the deploy to {}", maybe_output_path)
-            })
-        })
+        )?;
+
+        if !required_signers.is_empty() {
+            let required_signers: Vec<&str> = required_signers.iter().map(String::as_str).collect();
+            casper_client::record_required_signers(maybe_output_path, &required_signers, force)?;
+        }
+
+        Ok(Success::Output(if maybe_output_path.is_empty() {
+            String::new()
+        } else {
+            format!("Wrote the deploy to {}", maybe_output_path)
+        }))
     }
 }
@@ -1,8 +1,8 @@
 use async_trait::async_trait;
 use clap::{App, ArgMatches, SubCommand};
 
-use casper_client::{DeployStrParams, Error};
-use casper_node::rpcs::account::PutDeploy;
+use casper_client::{DeployStrParams, Error, PaymentStrParams};
+use casper_node::rpcs::account::{PutDeploy, PutDeployResult};
 
 use super::creation_common::{self, DisplayOrder};
 use crate::{command::ClientCommand, common, Success};
@@ -20,6 +20,8 @@ impl<'a, 'b> ClientCommand<'a, 'b> for PutDeploy {
             .arg(common::rpc_id::arg(DisplayOrder::RpcId as usize));
         let subcommand = creation_common::apply_common_session_options(subcommand);
         let subcommand = creation_common::apply_common_payment_options(subcommand);
+        let subcommand = creation_common::apply_payment_estimation_options(subcommand);
+        let subcommand = creation_common::apply_wait_options(subcommand);
         creation_common::apply_common_creation_options(subcommand, true)
     }
 
@@ -39,9 +41,19 @@ impl<'a, 'b> ClientCommand<'a, 'b> for PutDeploy {
         let session_account = common::session_account::get(matches)?;
 
         let session_str_params = creation_common::session_str_params(matches);
-        let payment_str_params = creation_common::payment_str_params(matches);
+        let estimated_payment_amount = creation_common::estimated_payment_amount(
+            matches,
+            maybe_rpc_id,
+            node_address,
+            verbosity_level,
+        )
+        .await?;
+        let payment_str_params = match estimated_payment_amount.as_deref() {
+            Some(payment_amount) => PaymentStrParams::with_amount(payment_amount),
+            None => creation_common::payment_str_params(matches),
+        };
 
-        casper_client::put_deploy(
+        let response = casper_client::put_deploy(
             maybe_rpc_id,
             node_address,
             verbosity_level,
@@ -57,7 +69,27 @@ impl<'a, 'b> ClientCommand<'a, 'b> for PutDeploy {
             session_str_params,
             payment_str_params,
         )
+        .await?;
+
+        if !creation_common::wait::get(matches) {
+            return Ok(Success::from(response));
+        }
+
+        casper_client::pretty_print_at_level(&response, verbosity_level.max(1));
+        let result_value = response
+            .get_result()
+            .cloned()
+            .ok_or_else(|| Error::InvalidRpcResponse(response.clone()))?;
+        let deploy_hash = serde_json::from_value::<PutDeployResult>(result_value)?.deploy_hash;
+        casper_client::wait_for_deploy(
+            maybe_rpc_id,
+            node_address,
+            verbosity_level,
+            common::event_stream_address::get(matches),
+            &deploy_hash.inner().to_string(),
+            common::wait_timeout::get(matches),
+        )
         .await
-        .map(Success::from)
+        .map(Success::DeployStatus)
     }
 }
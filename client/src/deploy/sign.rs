@@ -1,5 +1,5 @@
 use async_trait::async_trait;
-use clap::{App, ArgMatches, SubCommand};
+use clap::{App, Arg, ArgMatches, SubCommand};
 
 use casper_client::Error;
 
@@ -8,21 +8,55 @@ use crate::{command::ClientCommand, common, Success};
 
 pub struct SignDeploy;
 
+/// Handles providing the arg for and retrieval of the paths to copies of the deploy signed by
+/// other parties.
+mod partial_deploy {
+    use super::*;
+
+    pub(super) const ARG_NAME: &str = "merge";
+    const ARG_SHORT: &str = "m";
+    const ARG_VALUE_NAME: &str = common::ARG_PATH;
+    const ARG_HELP: &str =
+        "Path to a copy of the input deploy signed by other parties, whose approvals are added to \
+        the output deploy. May be given multiple times. If given, --secret-key is optional";
+
+    pub(super) fn arg() -> Arg<'static, 'static> {
+        Arg::with_name(ARG_NAME)
+            .long(ARG_NAME)
+            .short(ARG_SHORT)
+            .required(false)
+            .multiple(true)
+            .number_of_values(1)
+            .value_name(ARG_VALUE_NAME)
+            .help(ARG_HELP)
+            .display_order(creation_common::DisplayOrder::PartialDeploy as usize)
+    }
+
+    pub(super) fn get<'a>(matches: &'a ArgMatches) -> Vec<&'a str> {
+        matches
+            .values_of(ARG_NAME)
+            .map(|values| values.collect())
+            .unwrap_or_default()
+    }
+}
+
 #[async_trait]
 impl<'a, 'b> ClientCommand<'a, 'b> for SignDeploy {
     const NAME: &'static str = "sign-deploy";
     const ABOUT: &'static str =
-        "Reads a previously-saved deploy from a file, cryptographically signs it, and outputs it \
-        to a file or stdout";
+        "Reads a previously-saved deploy from a file, cryptographically signs it and/or adds the \
+        approvals from copies of it signed by other parties, and outputs it to a file or stdout";
 
     fn build(display_order: usize) -> App<'a, 'b> {
         SubCommand::with_name(Self::NAME)
             .about(Self::ABOUT)
             .display_order(display_order)
-            .arg(common::secret_key::arg(
-                creation_common::DisplayOrder::SecretKey as usize,
-            ))
+            .arg(
+                common::secret_key::arg(creation_common::DisplayOrder::SecretKey as usize)
+                    .required_unless(partial_deploy::ARG_NAME),
+            )
             .arg(creation_common::input::arg())
+            .arg(partial_deploy::arg())
             .arg(creation_common::output::arg())
             .arg(common::force::arg(
                 creation_common::DisplayOrder::Force as usize,
@@ -32,20 +66,33 @@ impl<'a, 'b> ClientCommand<'a, 'b> for SignDeploy {
 
     async fn run(matches: &ArgMatches<'a>) -> Result<Success, Error> {
         let input_path = creation_common::input::get(matches);
-        let secret_key = common::secret_key::get(matches);
+        let partial_paths = partial_deploy::get(matches);
         let maybe_output_path = creation_common::output::get(matches).unwrap_or_default();
         let force = common::force::get(matches);
-        casper_client::sign_deploy_file(input_path, secret_key, maybe_output_path, force).map(
-            |_| {
-                Success::Output(if maybe_output_path.is_empty() {
-                    String::new()
-                } else {
-                    format!(
-                        "Signed the deploy at {} and wrote to {}",
-                        input_path, maybe_output_path
-                    )
-                })
-            },
-        )
+
+        let action = if partial_paths.is_empty() {
+            let secret_key = common::secret_key::get(matches);
+            casper_client::sign_deploy_file(input_path, secret_key, maybe_output_path, force)?;
+            "Signed"
+        } else {
+            let maybe_secret_key = common::secret_key::get_optional(matches).unwrap_or_default();
+            casper_client::merge_deploy_approvals(
+                input_path,
+                &partial_paths,
+                maybe_secret_key,
+                maybe_output_path,
+                force,
+            )?;
+            "Merged approvals into"
+        };
+
+        Ok(Success::Output(if maybe_output_path.is_empty() {
+            String::new()
+        } else {
+            format!(
+                "{} the deploy at {} and wrote to {}",
+                action, input_path, maybe_output_path
+            )
+        }))
     }
 }
@@ -0,0 +1,140 @@
+use std::str;
+
+use async_trait::async_trait;
+use clap::{App, Arg, ArgMatches, SubCommand};
+
+use casper_client::Error;
+use casper_node::rpcs::account::GetAccountDeploys;
+
+use crate::{command::ClientCommand, common, Success};
+
+/// This struct defines the order in which the args are shown for this subcommand's help message.
+enum DisplayOrder {
+    Verbose,
+    NodeAddress,
+    RpcId,
+    PublicKey,
+    StartHeight,
+    Cursor,
+    Limit,
+}
+
+/// Handles providing the arg for and retrieval of the start height.
+mod start_height {
+    use super::*;
+
+    const ARG_NAME: &str = "start-height";
+    const ARG_SHORT: &str = "s";
+    const ARG_VALUE_NAME: &str = "INTEGER";
+    const ARG_HELP: &str =
+        "Height of the block from which to start listing deploys. Defaults to genesis";
+
+    pub(super) fn arg() -> Arg<'static, 'static> {
+        Arg::with_name(ARG_NAME)
+            .long(ARG_NAME)
+            .short(ARG_SHORT)
+            .required(false)
+            .value_name(ARG_VALUE_NAME)
+            .help(ARG_HELP)
+            .display_order(DisplayOrder::StartHeight as usize)
+    }
+
+    pub(super) fn get<'a>(matches: &'a ArgMatches) -> &'a str {
+        matches.value_of(ARG_NAME).unwrap_or_default()
+    }
+}
+
+/// Handles providing the arg for and retrieval of the cursor.
+mod cursor {
+    use super::*;
+
+    const ARG_NAME: &str = "cursor";
+    const ARG_SHORT: &str = "c";
+    const ARG_VALUE_NAME: &str = "HEX STRING";
+    const ARG_HELP: &str =
+        "Hex-encoded deploy hash returned as 'next_cursor' by a previous call, used to retrieve \
+        the next page of deploys. Takes precedence over --start-height";
+
+    pub(super) fn arg() -> Arg<'static, 'static> {
+        Arg::with_name(ARG_NAME)
+            .long(ARG_NAME)
+            .short(ARG_SHORT)
+            .required(false)
+            .value_name(ARG_VALUE_NAME)
+            .help(ARG_HELP)
+            .display_order(DisplayOrder::Cursor as usize)
+    }
+
+    pub(super) fn get<'a>(matches: &'a ArgMatches) -> &'a str {
+        matches.value_of(ARG_NAME).unwrap_or_default()
+    }
+}
+
+/// Handles providing the arg for and retrieval of the page size limit.
+mod limit {
+    use super::*;
+
+    const ARG_NAME: &str = "limit";
+    const ARG_SHORT: &str = "l";
+    const ARG_VALUE_NAME: &str = "INTEGER";
+    const ARG_HELP: &str =
+        "Maximum number of deploys to return. If not provided, the node's default is used";
+
+    pub(super) fn arg() -> Arg<'static, 'static> {
+        Arg::with_name(ARG_NAME)
+            .long(ARG_NAME)
+            .short(ARG_SHORT)
+            .required(false)
+            .value_name(ARG_VALUE_NAME)
+            .help(ARG_HELP)
+            .display_order(DisplayOrder::Limit as usize)
+    }
+
+    pub(super) fn get<'a>(matches: &'a ArgMatches) -> &'a str {
+        matches.value_of(ARG_NAME).unwrap_or_default()
+    }
+}
+
+#[async_trait]
+impl<'a, 'b> ClientCommand<'a, 'b> for GetAccountDeploys {
+    const NAME: &'static str = "get-account-deploys";
+    const ABOUT: &'static str =
+        "Retrieves a page of the deploys sent by an account, ordered by block height";
+
+    fn build(display_order: usize) -> App<'a, 'b> {
+        SubCommand::with_name(Self::NAME)
+            .about(Self::ABOUT)
+            .display_order(display_order)
+            .arg(common::verbose::arg(DisplayOrder::Verbose as usize))
+            .arg(common::node_address::arg(
+                DisplayOrder::NodeAddress as usize,
+            ))
+            .arg(common::rpc_id::arg(DisplayOrder::RpcId as usize))
+            .arg(common::public_key::arg(DisplayOrder::PublicKey as usize))
+            .arg(start_height::arg())
+            .arg(cursor::arg())
+            .arg(limit::arg())
+    }
+
+    async fn run(matches: &ArgMatches<'a>) -> Result<Success, Error> {
+        let maybe_rpc_id = common::rpc_id::get(matches);
+        let node_address = common::node_address::get(matches);
+        let verbosity_level = common::verbose::get(matches);
+        let public_key = common::public_key::get(matches)?;
+        let maybe_start_height = start_height::get(matches);
+        let maybe_cursor = cursor::get(matches);
+        let maybe_limit = limit::get(matches);
+
+        casper_client::get_account_deploys(
+            maybe_rpc_id,
+            node_address,
+            verbosity_level,
+            &public_key,
+            maybe_start_height,
+            maybe_cursor,
+            maybe_limit,
+        )
+        .await
+        .map(Success::from)
+    }
+}
@@ -14,9 +14,66 @@ enum DisplayOrder {
     NodeAddress,
     RpcId,
     StateRootHash,
+    BlockIdentifier,
+    StartHeight,
+    EndHeight,
     PurseURef,
 }
 
+/// Handles providing the arg for and retrieval of the first block height of a range.
+mod start_height {
+    use super::*;
+
+    pub(super) const ARG_NAME: &str = "start-height";
+    const ARG_VALUE_NAME: &str = "INTEGER";
+    const ARG_HELP: &str =
+        "Height of the first block of a range. If given, the purse's balance as of each block from \
+        this height to --end-height inclusive is output";
+
+    pub(super) fn arg() -> Arg<'static, 'static> {
+        Arg::with_name(ARG_NAME)
+            .long(ARG_NAME)
+            .required(false)
+            .value_name(ARG_VALUE_NAME)
+            .help(ARG_HELP)
+            .requires(end_height::ARG_NAME)
+            .conflicts_with_all(&[
+                common::state_root_hash::ARG_NAME,
+                common::block_identifier::ARG_NAME,
+            ])
+            .display_order(DisplayOrder::StartHeight as usize)
+    }
+
+    pub(super) fn get<'a>(matches: &'a ArgMatches) -> Option<&'a str> {
+        matches.value_of(ARG_NAME)
+    }
+}
+
+/// Handles providing the arg for and retrieval of the last block height of a range.
+mod end_height {
+    use super::*;
+
+    pub(super) const ARG_NAME: &str = "end-height";
+    const ARG_VALUE_NAME: &str = "INTEGER";
+    const ARG_HELP: &str = "Height of the last block of a range, given along with --start-height";
+
+    pub(super) fn arg() -> Arg<'static, 'static> {
+        Arg::with_name(ARG_NAME)
+            .long(ARG_NAME)
+            .required(false)
+            .value_name(ARG_VALUE_NAME)
+            .help(ARG_HELP)
+            .requires(start_height::ARG_NAME)
+            .display_order(DisplayOrder::EndHeight as usize)
+    }
+
+    pub(super) fn get<'a>(matches: &'a ArgMatches) -> &'a str {
+        matches
+            .value_of(ARG_NAME)
+            .unwrap_or_else(|| panic!("should have {} arg", ARG_NAME))
+    }
+}
+
 /// Handles providing the arg for and retrieval of the purse URef.
 mod purse_uref {
     use super::*;
@@ -48,7 +105,9 @@ mod purse_uref {
 #[async_trait]
 impl<'a, 'b> ClientCommand<'a, 'b> for GetBalance {
     const NAME: &'static str = "get-balance";
-    const ABOUT: &'static str = "Retrieves a purse's balance from the network";
+    const ABOUT: &'static str =
+        "Retrieves a purse's balance from the network, either as of a single block or as of each \
+        block in a range of heights";
 
     fn build(display_order: usize) -> App<'a, 'b> {
         SubCommand::with_name(Self::NAME)
@@ -59,9 +118,16 @@ impl<'a, 'b> ClientCommand<'a, 'b> for GetBalance {
                 DisplayOrder::NodeAddress as usize,
             ))
             .arg(common::rpc_id::arg(DisplayOrder::RpcId as usize))
-            .arg(common::state_root_hash::arg(
-                DisplayOrder::StateRootHash as usize,
+            .arg(
+                common::state_root_hash::arg(DisplayOrder::StateRootHash as usize)
+                    .required(false)
+                    .conflicts_with(common::block_identifier::ARG_NAME),
+            )
+            .arg(common::block_identifier::arg(
+                DisplayOrder::BlockIdentifier as usize,
             ))
+            .arg(start_height::arg())
+            .arg(end_height::arg())
             .arg(purse_uref::arg())
     }
 
@@ -69,17 +135,43 @@ impl<'a, 'b> ClientCommand<'a, 'b> for GetBalance {
         let maybe_rpc_id = common::rpc_id::get(matches);
         let node_address = common::node_address::get(matches);
         let verbosity_level = common::verbose::get(matches);
-        let state_root_hash = common::state_root_hash::get(matches);
         let purse_uref = purse_uref::get(matches);
 
-        casper_client::get_balance(
-            maybe_rpc_id,
-            node_address,
-            verbosity_level,
-            state_root_hash,
-            purse_uref,
-        )
-        .await
-        .map(Success::from)
+        if let Some(start_height) = start_height::get(matches) {
+            let end_height = end_height::get(matches);
+            let history = casper_client::get_balance_history(
+                maybe_rpc_id,
+                node_address,
+                verbosity_level,
+                purse_uref,
+                start_height,
+                end_height,
+            )
+            .await?;
+            return Ok(Success::Output(
+                serde_json::to_string_pretty(&history).expect("should encode"),
+            ));
+        }
+
+        match common::state_root_hash::get_optional(matches) {
+            Some(state_root_hash) => casper_client::get_balance(
+                maybe_rpc_id,
+                node_address,
+                verbosity_level,
+                state_root_hash,
+                purse_uref,
+            )
+            .await
+            .map(Success::from),
+            None => casper_client::get_balance_at_block(
+                maybe_rpc_id,
+                node_address,
+                verbosity_level,
+                common::block_identifier::get(matches),
+                purse_uref,
+            )
+            .await
+            .map(Success::from),
+        }
     }
 }
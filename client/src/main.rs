@@ -5,6 +5,7 @@ mod common;
 mod deploy;
 mod docs;
 mod generate_completion;
+mod get_account_deploys;
 mod get_account_info;
 mod get_auction_info;
 mod get_balance;
@@ -19,9 +20,9 @@ use std::process;
 
 use clap::{crate_description, crate_version, App};
 
-use casper_client::Error;
+use casper_client::{DeployOutcome, Error};
 use casper_node::rpcs::{
-    account::PutDeploy,
+    account::{GetAccountDeploys, PutDeploy, SpeculativeExec},
     chain::{GetBlock, GetBlockTransfers, GetEraInfoBySwitchBlock, GetStateRootHash},
     docs::ListRpcs,
     info::{GetDeploy, GetValidatorChanges},
@@ -30,7 +31,10 @@ use casper_node::rpcs::{
 
 use account_address::GenerateAccountHash as AccountAddress;
 use command::{ClientCommand, Success};
-use deploy::{ListDeploys, MakeDeploy, MakeTransfer, SendDeploy, SignDeploy, Transfer};
+use deploy::{
+    CheckApprovals, ListDeploys, MakeDeploy, MakeTransfer, SendDeploy, SignDeploy, Transfer,
+    WaitDeploy,
+};
 use generate_completion::GenerateCompletion;
 use keygen::Keygen;
 
@@ -41,10 +45,13 @@ enum DisplayOrder {
     PutDeploy,
     MakeDeploy,
     SignDeploy,
+    CheckApprovals,
     SendDeploy,
+    SpeculativeExec,
     Transfer,
     MakeTransfer,
     GetDeploy,
+    WaitDeploy,
     GetBlock,
     GetBlockTransfers,
     ListDeploys,
@@ -53,6 +60,7 @@ enum DisplayOrder {
     GetDictionaryItem,
     GetBalance,
     GetAccountInfo,
+    GetAccountDeploys,
     GetEraInfo,
     GetAuctionInfo,
     GetValidatorChanges,
@@ -69,10 +77,15 @@ fn cli<'a, 'b>() -> App<'a, 'b> {
         .subcommand(PutDeploy::build(DisplayOrder::PutDeploy as usize))
         .subcommand(MakeDeploy::build(DisplayOrder::MakeDeploy as usize))
         .subcommand(SignDeploy::build(DisplayOrder::SignDeploy as usize))
+        .subcommand(CheckApprovals::build(DisplayOrder::CheckApprovals as usize))
         .subcommand(SendDeploy::build(DisplayOrder::SendDeploy as usize))
+        .subcommand(SpeculativeExec::build(
+            DisplayOrder::SpeculativeExec as usize,
+        ))
         .subcommand(Transfer::build(DisplayOrder::Transfer as usize))
         .subcommand(MakeTransfer::build(DisplayOrder::MakeTransfer as usize))
         .subcommand(GetDeploy::build(DisplayOrder::GetDeploy as usize))
+        .subcommand(WaitDeploy::build(DisplayOrder::WaitDeploy as usize))
         .subcommand(GetBlock::build(DisplayOrder::GetBlock as usize))
         .subcommand(GetBlockTransfers::build(
             DisplayOrder::GetBlockTransfers as usize,
@@ -80,6 +93,9 @@ fn cli<'a, 'b>() -> App<'a, 'b> {
         .subcommand(ListDeploys::build(DisplayOrder::ListDeploys as usize))
         .subcommand(GetBalance::build(DisplayOrder::GetBalance as usize))
         .subcommand(GetAccountInfo::build(DisplayOrder::GetAccountInfo as usize))
+        .subcommand(GetAccountDeploys::build(
+            DisplayOrder::GetAccountDeploys as usize,
+        ))
         .subcommand(GetStateRootHash::build(
             DisplayOrder::GetStateRootHash as usize,
         ))
@@ -111,10 +127,13 @@ async fn main() {
         (PutDeploy::NAME, Some(matches)) => (PutDeploy::run(matches).await, matches),
         (MakeDeploy::NAME, Some(matches)) => (MakeDeploy::run(matches).await, matches),
         (SignDeploy::NAME, Some(matches)) => (SignDeploy::run(matches).await, matches),
+        (CheckApprovals::NAME, Some(matches)) => (CheckApprovals::run(matches).await, matches),
         (SendDeploy::NAME, Some(matches)) => (SendDeploy::run(matches).await, matches),
+        (SpeculativeExec::NAME, Some(matches)) => (SpeculativeExec::run(matches).await, matches),
         (Transfer::NAME, Some(matches)) => (Transfer::run(matches).await, matches),
         (MakeTransfer::NAME, Some(matches)) => (MakeTransfer::run(matches).await, matches),
         (GetDeploy::NAME, Some(matches)) => (GetDeploy::run(matches).await, matches),
+        (WaitDeploy::NAME, Some(matches)) => (WaitDeploy::run(matches).await, matches),
         (GetBlock::NAME, Some(matches)) => (GetBlock::run(matches).await, matches),
         (GetBlockTransfers::NAME, Some(matches)) => {
             (GetBlockTransfers::run(matches).await, matches)
@@ -122,6 +141,9 @@ async fn main() {
         (ListDeploys::NAME, Some(matches)) => (ListDeploys::run(matches).await, matches),
         (GetBalance::NAME, Some(matches)) => (GetBalance::run(matches).await, matches),
         (GetAccountInfo::NAME, Some(matches)) => (GetAccountInfo::run(matches).await, matches),
+        (GetAccountDeploys::NAME, Some(matches)) => {
+            (GetAccountDeploys::run(matches).await, matches)
+        }
         (GetStateRootHash::NAME, Some(matches)) => (GetStateRootHash::run(matches).await, matches),
         (GetEraInfoBySwitchBlock::NAME, Some(matches)) => {
             (GetEraInfoBySwitchBlock::run(matches).await, matches)
@@ -158,10 +180,26 @@ async fn main() {
             casper_client::pretty_print_at_level(&response, verbosity_level)
         }
         Ok(Success::Output(output)) => println!("{}", output),
+        Ok(Success::DeployStatus(status)) => {
+            if let Some(error) = &status.event_stream_error {
+                if common::verbose::get(matches) > 0 {
+                    println!("Fell back to polling for the deploy: {}", error);
+                }
+            }
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&status.outcome).expect("should encode")
+            );
+            process::exit(status.outcome.exit_code());
+        }
         Err(Error::ResponseIsError(error)) => {
             casper_client::pretty_print_at_level(&error, verbosity_level);
             process::exit(1);
         }
+        Err(error @ Error::TimedOut { .. }) => {
+            println!("{}", error);
+            process::exit(DeployOutcome::TIMED_OUT_EXIT_CODE);
+        }
         Err(error) => {
             println!("{}", error);
             process::exit(1);
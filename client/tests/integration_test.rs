@@ -16,13 +16,21 @@ use casper_client::{
 use casper_node::{
     crypto::Error as CryptoError,
     rpcs::{
-        account::{PutDeploy, PutDeployParams},
-        chain::{GetStateRootHash, GetStateRootHashParams},
+        account::{
+            PutDeploy, PutDeployParams, SpeculativeExec, SpeculativeExecParams,
+            SpeculativeExecResult,
+        },
+        chain::{
+            GetBlock, GetBlockParams, GetBlockResult, GetStateRootHash, GetStateRootHashParams,
+        },
+        docs::DocExample,
         info::{GetDeploy, GetDeployParams},
         state::{GetBalance, GetBalanceParams, GetDictionaryItem, GetDictionaryItemParams},
         RpcWithOptionalParams, RpcWithParams,
     },
+    types::JsonBlock,
 };
+use casper_types::{ExecutionEffect, ExecutionResult, ProtocolVersion, U512};
 
 const VALID_PURSE_UREF: &str =
     "uref-0102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f20-007";
@@ -44,6 +52,51 @@ where
         .map(|builder: Builder, _params: P| builder.success(()).unwrap())
 }
 
+fn speculative_exec_filter(
+    execution_result: fn() -> ExecutionResult,
+) -> impl Filter<Extract = (Response<Body>,), Error = Rejection> + Copy {
+    warp_json_rpc::filters::json_rpc()
+        .and(warp_json_rpc::filters::method(SpeculativeExec::METHOD))
+        .and(warp_json_rpc::filters::params::<SpeculativeExecParams>())
+        .map(move |builder: Builder, _params: SpeculativeExecParams| {
+            let result = SpeculativeExecResult {
+                api_version: ProtocolVersion::V1_0_0,
+                block_hash: SpeculativeExecResult::doc_example().block_hash,
+                execution_result: execution_result(),
+                gas_profile: None,
+            };
+            builder.success(result).unwrap()
+        })
+}
+
+fn get_block_filter(
+    json_block: fn() -> JsonBlock,
+) -> impl Filter<Extract = (Response<Body>,), Error = Rejection> + Copy {
+    warp_json_rpc::filters::json_rpc()
+        .and(warp_json_rpc::filters::method(GetBlock::METHOD))
+        .and(warp_json_rpc::filters::params::<GetBlockParams>())
+        .map(move |builder: Builder, _params: GetBlockParams| {
+            let result = GetBlockResult {
+                api_version: ProtocolVersion::V1_0_0,
+                block: Some(json_block()),
+            };
+            builder.success(result).unwrap()
+        })
+}
+
+fn get_deploy_error_filter(
+    error_code: i64,
+) -> impl Filter<Extract = (Response<Body>,), Error = Rejection> + Copy {
+    warp_json_rpc::filters::json_rpc()
+        .and(warp_json_rpc::filters::method(GetDeploy::METHOD))
+        .and(warp_json_rpc::filters::params::<GetDeployParams>())
+        .map(move |builder: Builder, _params: GetDeployParams| {
+            builder
+                .error(warp_json_rpc::Error::custom(error_code, "error"))
+                .unwrap()
+        })
+}
+
 fn test_filter_without_params(
     method: &'static str,
 ) -> impl Filter<Extract = (Response<Body>,), Error = Rejection> + Copy {
@@ -128,6 +181,24 @@ impl MockServerHandle {
             .map(|_| ())
     }
 
+    async fn get_balance_history(
+        &self,
+        purse_uref: &str,
+        start_height: &str,
+        end_height: &str,
+    ) -> Result<(), Error> {
+        casper_client::get_balance_history(
+            "1",
+            &self.url(),
+            0,
+            purse_uref,
+            start_height,
+            end_height,
+        )
+        .await
+        .map(|_| ())
+    }
+
     async fn get_deploy(&self, deploy_hash: &str) -> Result<(), Error> {
         casper_client::get_deploy("1", &self.url(), 0, deploy_hash)
             .await
@@ -219,12 +290,31 @@ impl MockServerHandle {
         .map(|_| ())
     }
 
+    async fn estimate_payment(&self, safety_margin: &str) -> Result<U512, Error> {
+        casper_client::estimate_payment(
+            "1",
+            &self.url(),
+            0,
+            deploy_params::test_data_valid(),
+            session_params::test_data_with_package_hash(),
+            "10000000000",
+            safety_margin,
+        )
+        .await
+    }
+
     async fn send_deploy_file(&self, input_path: &str) -> Result<(), Error> {
         casper_client::send_deploy_file("1", &self.url(), 0, input_path)
             .await
             .map(|_| ())
     }
 
+    async fn wait_for_deploy(&self, deploy_hash: &str, timeout: &str) -> Result<(), Error> {
+        casper_client::wait_for_deploy("1", &self.url(), 0, "", deploy_hash, timeout)
+            .await
+            .map(|_| ())
+    }
+
     async fn get_auction_info(&self, maybe_block_id: &str) -> Result<(), Error> {
         casper_client::get_auction_info("1", &self.url(), 0, maybe_block_id)
             .await
@@ -453,6 +543,38 @@ mod get_balance {
     }
 }
 
+mod get_balance_history {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn should_fail_with_bad_start_height() {
+        let server_handle = MockServerHandle::spawn::<GetBalanceParams>(GetBalance::METHOD);
+        assert!(matches!(
+            server_handle
+                .get_balance_history(VALID_PURSE_UREF, "ten", "20")
+                .await,
+            Err(Error::FailedToParseInt {
+                context: "start_height",
+                ..
+            })
+        ));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn should_fail_with_end_height_below_start_height() {
+        let server_handle = MockServerHandle::spawn::<GetBalanceParams>(GetBalance::METHOD);
+        assert!(matches!(
+            server_handle
+                .get_balance_history(VALID_PURSE_UREF, "20", "10")
+                .await,
+            Err(Error::InvalidArgument {
+                context: "end_height",
+                ..
+            })
+        ));
+    }
+}
+
 mod get_state_root_hash {
     use super::*;
 
@@ -830,6 +952,42 @@ mod get_deploy {
     }
 }
 
+mod wait_for_deploy {
+    use super::*;
+
+    const DEPLOY_HASH: &str = "09dcee4b212cfd53642ab323fbef07dafafc6f945a80a00147f62910a915c4e6";
+    /// The error code returned by the node for an unknown deploy.
+    const NO_SUCH_DEPLOY_ERROR_CODE: i64 = -32000;
+    /// The error code returned by the node for a pruned deploy.
+    const DEPLOY_PRUNED_ERROR_CODE: i64 = -32015;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn should_wait_while_deploy_unknown() {
+        let server_handle = MockServerHandle::spawn_with_filter(
+            get_deploy_error_filter(NO_SUCH_DEPLOY_ERROR_CODE),
+            DEFAULT_RATE_LIMIT,
+            DEFAULT_RATE_PER,
+        );
+        assert!(matches!(
+            server_handle.wait_for_deploy(DEPLOY_HASH, "3s").await,
+            Err(Error::TimedOut { .. })
+        ));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn should_fail_with_other_error_response() {
+        let server_handle = MockServerHandle::spawn_with_filter(
+            get_deploy_error_filter(DEPLOY_PRUNED_ERROR_CODE),
+            DEFAULT_RATE_LIMIT,
+            DEFAULT_RATE_PER,
+        );
+        assert!(matches!(
+            server_handle.wait_for_deploy(DEPLOY_HASH, "3s").await,
+            Err(Error::ResponseIsError(error)) if error.code == DEPLOY_PRUNED_ERROR_CODE
+        ));
+    }
+}
+
 mod get_auction_info {
     use super::*;
 
@@ -1334,6 +1492,84 @@ mod put_deploy {
     }
 }
 
+mod estimate_payment {
+    use super::*;
+
+    const COST: u64 = 123_456;
+
+    fn success() -> ExecutionResult {
+        ExecutionResult::Success {
+            effect: ExecutionEffect::default(),
+            transfers: vec![],
+            cost: U512::from(COST),
+        }
+    }
+
+    fn failure() -> ExecutionResult {
+        ExecutionResult::Failure {
+            effect: ExecutionEffect::default(),
+            transfers: vec![],
+            cost: U512::from(COST),
+            error_message: "Out of gas error".to_string(),
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn should_add_safety_margin_to_cost() {
+        let server_handle = MockServerHandle::spawn_with_filter(
+            speculative_exec_filter(success),
+            DEFAULT_RATE_LIMIT,
+            DEFAULT_RATE_PER,
+        );
+        assert_eq!(
+            server_handle.estimate_payment("10").await.unwrap(),
+            U512::from(COST + COST / 10)
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn should_estimate_cost_with_zero_safety_margin() {
+        let server_handle = MockServerHandle::spawn_with_filter(
+            speculative_exec_filter(success),
+            DEFAULT_RATE_LIMIT,
+            DEFAULT_RATE_PER,
+        );
+        assert_eq!(
+            server_handle.estimate_payment("0").await.unwrap(),
+            U512::from(COST)
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn should_fail_if_execution_fails() {
+        let server_handle = MockServerHandle::spawn_with_filter(
+            speculative_exec_filter(failure),
+            DEFAULT_RATE_LIMIT,
+            DEFAULT_RATE_PER,
+        );
+        assert!(matches!(
+            server_handle.estimate_payment("10").await,
+            Err(Error::FailedToEstimatePayment(error_message)) if error_message == "Out of gas error"
+        ));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn should_fail_with_invalid_safety_margin() {
+        let server_handle = MockServerHandle::spawn_with_filter(
+            speculative_exec_filter(success),
+            DEFAULT_RATE_LIMIT,
+            DEFAULT_RATE_PER,
+        );
+        assert!(matches!(
+            server_handle.estimate_payment("ten").await,
+            Err(Error::FailedToParseInt {
+                context: "safety_margin",
+                ..
+            })
+        ));
+    }
+}
+
 mod rate_limit {
     use super::*;
     use casper_node::types::Timestamp;
@@ -1399,3 +1635,115 @@ mod transfer {
             .is_ok());
     }
 }
+
+mod light_client {
+    use std::collections::BTreeMap;
+
+    use casper_client::ValidateResponseError;
+    use casper_node::types::{Block, FinalitySignature, HeaderChainError, JsonProof};
+    use casper_types::{PublicKey, SecretKey};
+
+    use super::*;
+
+    fn validator(seed: u8) -> (SecretKey, PublicKey) {
+        let secret_key = SecretKey::ed25519_from_bytes([seed; 32]).unwrap();
+        let public_key = PublicKey::from(&secret_key);
+        (secret_key, public_key)
+    }
+
+    /// Returns the example block, signed by the validators with seeds 1 and 4.
+    fn signed_block() -> JsonBlock {
+        let mut json_block = JsonBlock::new(Block::doc_example().clone(), None);
+        let (block_hash, era_id) = (json_block.hash, json_block.header.era_id);
+        json_block.proofs = [1, 4]
+            .iter()
+            .map(|seed| {
+                let (secret_key, public_key) = validator(*seed);
+                let finality_signature =
+                    FinalitySignature::new(block_hash, era_id, &secret_key, public_key.clone());
+                JsonProof::from((public_key, finality_signature.signature))
+            })
+            .collect();
+        json_block
+    }
+
+    fn trusted_validators(weights: &[(u8, u64)]) -> BTreeMap<PublicKey, U512> {
+        weights
+            .iter()
+            .map(|(seed, weight)| (validator(*seed).1, U512::from(*weight)))
+            .collect()
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn should_get_block_signed_by_more_than_a_third_of_the_weight() {
+        let server_handle = MockServerHandle::spawn_with_filter(
+            get_block_filter(signed_block),
+            DEFAULT_RATE_LIMIT,
+            DEFAULT_RATE_PER,
+        );
+        let block_id = signed_block().hash.inner().to_string();
+        let trusted_validators = trusted_validators(&[(1, 2), (2, 1), (3, 2)]);
+        let response = casper_client::light_client::get_block(
+            "1",
+            &server_handle.url(),
+            0,
+            &block_id,
+            &trusted_validators,
+        )
+        .await
+        .unwrap();
+        let result: GetBlockResult =
+            serde_json::from_value(response.get_result().unwrap().clone()).unwrap();
+        assert_eq!(result.block.unwrap().hash, signed_block().hash);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn should_reject_block_signed_by_at_most_a_third_of_the_weight() {
+        let server_handle = MockServerHandle::spawn_with_filter(
+            get_block_filter(signed_block),
+            DEFAULT_RATE_LIMIT,
+            DEFAULT_RATE_PER,
+        );
+        let block_id = signed_block().hash.inner().to_string();
+        let trusted_validators = trusted_validators(&[(1, 1), (2, 1), (3, 1)]);
+        assert!(matches!(
+            casper_client::light_client::get_block(
+                "1",
+                &server_handle.url(),
+                0,
+                &block_id,
+                &trusted_validators,
+            )
+            .await,
+            Err(Error::InvalidResponse(ValidateResponseError::NotFinalized(
+                HeaderChainError::InsufficientFinalitySignatures { .. }
+            )))
+        ));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn should_not_query_balance_against_unfinalized_block() {
+        // The mock server only serves `chain_get_block`, so the balance query is never reached.
+        let server_handle = MockServerHandle::spawn_with_filter(
+            get_block_filter(signed_block),
+            DEFAULT_RATE_LIMIT,
+            DEFAULT_RATE_PER,
+        );
+        let block_id = signed_block().hash.inner().to_string();
+        let trusted_validators = trusted_validators(&[(2, 1), (3, 1)]);
+        assert!(matches!(
+            casper_client::light_client::get_balance(
+                "1",
+                &server_handle.url(),
+                0,
+                &block_id,
+                VALID_PURSE_UREF,
+                &trusted_validators,
+            )
+            .await,
+            Err(Error::InvalidResponse(ValidateResponseError::NotFinalized(
+                HeaderChainError::InsufficientFinalitySignatures { .. }
+            )))
+        ));
+    }
+}
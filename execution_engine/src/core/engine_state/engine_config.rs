@@ -1,6 +1,9 @@
 //! Support for runtime configuration of the execution engine - as an integral property of the
 //! `EngineState` instance.
-use crate::shared::{system_config::SystemConfig, wasm_config::WasmConfig};
+use crate::shared::{
+    system_config::SystemConfig, wasm_config::WasmConfig,
+    wasm_module_cache::DEFAULT_MAX_WASM_MODULE_CACHE_ENTRIES,
+};
 
 /// Default value for a maximum query depth configuration option.
 pub const DEFAULT_MAX_QUERY_DEPTH: u64 = 5;
@@ -21,6 +24,8 @@ pub struct EngineConfig {
     max_runtime_call_stack_height: u32,
     wasm_config: WasmConfig,
     system_config: SystemConfig,
+    /// Maximum number of deserialized stored contract modules retained across deploys.
+    max_wasm_module_cache_entries: usize,
 }
 
 impl Default for EngineConfig {
@@ -31,6 +36,7 @@ impl Default for EngineConfig {
             max_runtime_call_stack_height: DEFAULT_MAX_RUNTIME_CALL_STACK_HEIGHT,
             wasm_config: WasmConfig::default(),
             system_config: SystemConfig::default(),
+            max_wasm_module_cache_entries: DEFAULT_MAX_WASM_MODULE_CACHE_ENTRIES,
         }
     }
 }
@@ -50,9 +56,19 @@ impl EngineConfig {
             max_runtime_call_stack_height,
             wasm_config,
             system_config,
+            max_wasm_module_cache_entries: DEFAULT_MAX_WASM_MODULE_CACHE_ENTRIES,
         }
     }
 
+    /// Sets the maximum number of entries of the Wasm module cache, where `0` disables the cache.
+    pub fn with_max_wasm_module_cache_entries(
+        mut self,
+        max_wasm_module_cache_entries: usize,
+    ) -> EngineConfig {
+        self.max_wasm_module_cache_entries = max_wasm_module_cache_entries;
+        self
+    }
+
     /// Returns the current max associated keys config.
     pub fn max_associated_keys(&self) -> u32 {
         self.max_associated_keys
@@ -72,4 +88,9 @@ impl EngineConfig {
     pub fn system_config(&self) -> &SystemConfig {
         &self.system_config
     }
+
+    /// Returns the maximum number of entries of the Wasm module cache.
+    pub fn max_wasm_module_cache_entries(&self) -> usize {
+        self.max_wasm_module_cache_entries
+    }
 }
@@ -36,7 +36,10 @@ use crate::{
         execution,
         tracking_copy::{TrackingCopy, TrackingCopyExt},
     },
-    shared::{newtypes::CorrelationId, wasm, wasm_prep, wasm_prep::Preprocessor},
+    shared::{
+        newtypes::CorrelationId, wasm, wasm_module_cache::WasmModuleCache, wasm_prep,
+        wasm_prep::Preprocessor,
+    },
     storage::global_state::StateReader,
 };
 
@@ -361,6 +364,7 @@ impl ExecutableDeployItem {
         account: &Account,
         correlation_id: CorrelationId,
         preprocessor: &Preprocessor,
+        wasm_module_cache: &WasmModuleCache,
         protocol_version: &ProtocolVersion,
         system_contract_registry: SystemContractRegistry,
         phase: Phase,
@@ -582,7 +586,11 @@ impl ExecutableDeployItem {
             .borrow_mut()
             .get_contract_wasm(correlation_id, contract.contract_wasm_hash())?;
 
-        let module = wasm_prep::deserialize(contract_wasm.bytes())?;
+        let module = wasm_module_cache.get_or_insert_with(
+            contract.contract_wasm_hash(),
+            preprocessor.wasm_config(),
+            || wasm_prep::deserialize(contract_wasm.bytes()).map_err(Error::from),
+        )?;
 
         match entry_point.entry_point_type() {
             EntryPointType::Session => {
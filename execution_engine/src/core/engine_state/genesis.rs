@@ -1,5 +1,5 @@
 //! Support for a genesis process.
-use std::{cell::RefCell, collections::BTreeMap, fmt, iter, rc::Rc};
+use std::{cell::RefCell, collections::BTreeMap, fmt, iter, rc::Rc, sync::Arc};
 
 use datasize::DataSize;
 use num::Zero;
@@ -46,7 +46,10 @@ use crate::{
         runtime::RuntimeStack,
         tracking_copy::{TrackingCopy, TrackingCopyExt},
     },
-    shared::{newtypes::CorrelationId, system_config::SystemConfig, wasm_config::WasmConfig},
+    shared::{
+        newtypes::CorrelationId, system_config::SystemConfig, wasm_config::WasmConfig,
+        wasm_module_cache::WasmModuleCache,
+    },
     storage::global_state::StateProvider,
 };
 
@@ -756,11 +759,12 @@ where
         protocol_version: ProtocolVersion,
         correlation_id: CorrelationId,
         engine_config: EngineConfig,
+        wasm_module_cache: Arc<WasmModuleCache>,
         exec_config: ExecConfig,
         tracking_copy: Rc<RefCell<TrackingCopy<<S as StateProvider>::Reader>>>,
         system_module: Module,
     ) -> Self {
-        let executor = Executor::new(engine_config);
+        let executor = Executor::new(engine_config, wasm_module_cache);
 
         let phase = Phase::System;
         let genesis_config_hash_bytes = genesis_config_hash.as_ref();
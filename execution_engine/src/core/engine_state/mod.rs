@@ -12,6 +12,7 @@ pub mod genesis;
 pub mod get_bids;
 pub mod op;
 pub mod query;
+pub mod read_set;
 pub mod run_genesis_request;
 pub mod step;
 mod transfer;
@@ -23,6 +24,7 @@ use std::{
     convert::TryFrom,
     iter::FromIterator,
     rc::Rc,
+    sync::Arc,
 };
 
 use num::Zero;
@@ -63,6 +65,7 @@ pub use self::{
     genesis::{ExecConfig, GenesisAccount, GenesisSuccess, SystemContractRegistry},
     get_bids::{GetBidsRequest, GetBidsResult},
     query::{QueryRequest, QueryResult},
+    read_set::ReadSet,
     step::{RewardItem, SlashItem, StepError, StepRequest, StepSuccess},
     transfer::{TransferArgs, TransferRuntimeArgsBuilder, TransferTargetMode},
     upgrade::{UpgradeConfig, UpgradeSuccess},
@@ -76,15 +79,22 @@ use crate::{
             upgrade::{ProtocolUpgradeError, SystemUpgrader},
         },
         execution::{self, DirectSystemContractCall, Executor},
-        runtime::RuntimeStack,
+        runtime::{
+            execution_trace::ExecutionTrace,
+            gas_profiler::{GasProfile, GasProfiler},
+            RuntimeStack,
+        },
         tracking_copy::{TrackingCopy, TrackingCopyExt},
     },
     shared::{
         additive_map::AdditiveMap, newtypes::CorrelationId, transform::Transform,
-        wasm_prep::Preprocessor,
+        wasm_module_cache::WasmModuleCache, wasm_prep::Preprocessor,
     },
     storage::{
-        global_state::{lmdb::LmdbGlobalState, StateProvider},
+        global_state::{
+            lmdb::{LmdbGlobalState, TriePruningBatch, TriePruningProgress},
+            StateProvider,
+        },
         trie::Trie,
     },
 };
@@ -111,6 +121,7 @@ pub const WASMLESS_TRANSFER_FIXED_GAS_PRICE: u64 = 1;
 pub struct EngineState<S> {
     config: EngineConfig,
     state: S,
+    wasm_module_cache: Arc<WasmModuleCache>,
 }
 
 impl EngineState<LmdbGlobalState> {
@@ -121,6 +132,21 @@ impl EngineState<LmdbGlobalState> {
         }
         Ok(())
     }
+
+    /// Runs a single batch of pruning tries which are not reachable from the retained state roots.
+    ///
+    /// See [`LmdbGlobalState::prune_tries`].
+    pub fn prune_tries(
+        &self,
+        correlation_id: CorrelationId,
+        progress: &mut TriePruningProgress,
+        state_roots: Vec<Digest>,
+        batch_size: usize,
+    ) -> Result<TriePruningBatch, Error> {
+        self.state
+            .prune_tries(correlation_id, progress, state_roots, batch_size)
+            .map_err(Error::from)
+    }
 }
 
 impl<S> EngineState<S>
@@ -130,7 +156,13 @@ where
 {
     /// Creates new engine state.
     pub fn new(state: S, config: EngineConfig) -> EngineState<S> {
-        EngineState { config, state }
+        let wasm_module_cache =
+            Arc::new(WasmModuleCache::new(config.max_wasm_module_cache_entries()));
+        EngineState {
+            config,
+            state,
+            wasm_module_cache,
+        }
     }
 
     /// Returns engine config.
@@ -138,8 +170,22 @@ where
         &self.config
     }
 
+    /// Returns the cache of deserialized stored contract modules shared by all executions.
+    pub fn wasm_module_cache(&self) -> &WasmModuleCache {
+        &self.wasm_module_cache
+    }
+
     /// Updates current engine config with a new instance.
+    ///
+    /// The Wasm module cache is emptied if the Wasm config or the cache size changes.
     pub fn update_config(&mut self, new_config: EngineConfig) {
+        if new_config.wasm_config() != self.config.wasm_config()
+            || new_config.max_wasm_module_cache_entries()
+                != self.config.max_wasm_module_cache_entries()
+        {
+            self.wasm_module_cache
+                .reset(new_config.max_wasm_module_cache_entries());
+        }
         self.config = new_config
     }
 
@@ -184,6 +230,7 @@ where
             protocol_version,
             correlation_id,
             *self.config(),
+            Arc::clone(&self.wasm_module_cache),
             ee_config.clone(),
             tracking_copy,
             system_module,
@@ -427,6 +474,31 @@ where
         }
     }
 
+    /// Creates a new tracking copy instance, recording its accesses in `read_set` if given.
+    fn recording_tracking_copy(
+        &self,
+        hash: Digest,
+        read_set: Option<Rc<RefCell<ReadSet>>>,
+    ) -> Result<Option<TrackingCopy<S::Reader>>, Error> {
+        let mut maybe_tracking_copy = self.tracking_copy(hash)?;
+        if let (Some(tracking_copy), Some(read_set)) = (maybe_tracking_copy.as_mut(), read_set) {
+            tracking_copy.record_reads_into(read_set);
+        }
+        Ok(maybe_tracking_copy)
+    }
+
+    /// Records in `read_set`, if given, that the system contract registry was read.
+    ///
+    /// Every deploy depends on the registry, including where it is read from the pre-state rather
+    /// than through the recording tracking copy.
+    fn record_registry_read(read_set: Option<&Rc<RefCell<ReadSet>>>) {
+        if let Some(read_set) = read_set {
+            read_set
+                .borrow_mut()
+                .record_value(Key::SystemContractRegistry);
+        }
+    }
+
     /// Executes a query.
     ///
     /// For a given root [`Key`] it does a path lookup through the named keys.
@@ -467,43 +539,176 @@ where
         correlation_id: CorrelationId,
         mut exec_request: ExecuteRequest,
     ) -> Result<ExecutionResults, Error> {
-        let executor = Executor::new(*self.config());
+        let executor = Executor::new(*self.config(), Arc::clone(&self.wasm_module_cache));
 
         let deploys = exec_request.take_deploys();
         let mut results = ExecutionResults::with_capacity(deploys.len());
 
         for deploy_item in deploys {
-            let result = match deploy_item.session {
-                ExecutableDeployItem::Transfer { .. } => self.transfer(
-                    correlation_id,
-                    &executor,
-                    exec_request.protocol_version,
-                    exec_request.parent_state_hash,
-                    BlockTime::new(exec_request.block_time),
-                    deploy_item,
-                    exec_request.proposer.clone(),
-                ),
-                _ => self.deploy(
-                    correlation_id,
-                    &executor,
-                    exec_request.protocol_version,
-                    exec_request.parent_state_hash,
-                    BlockTime::new(exec_request.block_time),
-                    deploy_item,
-                    exec_request.proposer.clone(),
-                ),
-            };
-            match result {
-                Ok(result) => results.push_back(result),
-                Err(error) => {
-                    return Err(error);
-                }
-            };
+            let result = self.execute_deploy_item(
+                correlation_id,
+                &executor,
+                &exec_request,
+                deploy_item,
+                None,
+            )?;
+            results.push_back(result);
+        }
+
+        Ok(results)
+    }
+
+    /// Runs a deploy execution request like [`EngineState::run_execute`], additionally recording
+    /// the global state accesses made by each deploy.
+    ///
+    /// Returns the execution result of each deploy along with its [`ReadSet`].
+    pub fn run_execute_recording_reads(
+        &self,
+        correlation_id: CorrelationId,
+        mut exec_request: ExecuteRequest,
+    ) -> Result<Vec<(ExecutionResult, ReadSet)>, Error> {
+        let executor = Executor::new(*self.config(), Arc::clone(&self.wasm_module_cache));
+
+        let deploys = exec_request.take_deploys();
+        let mut results = Vec::with_capacity(deploys.len());
+
+        for deploy_item in deploys {
+            let read_set = Rc::new(RefCell::new(ReadSet::default()));
+            let result = self.execute_deploy_item(
+                correlation_id,
+                &executor,
+                &exec_request,
+                deploy_item,
+                Some(Rc::clone(&read_set)),
+            )?;
+            results.push((result, read_set.take()));
+        }
+
+        Ok(results)
+    }
+
+    /// Runs a deploy execution request like [`EngineState::run_execute`], additionally profiling
+    /// the gas charged while executing the Wasm of each deploy.
+    ///
+    /// Returns the execution result of each deploy along with its [`GasProfile`].
+    pub fn run_execute_profiling_gas(
+        &self,
+        correlation_id: CorrelationId,
+        mut exec_request: ExecuteRequest,
+    ) -> Result<Vec<(ExecutionResult, GasProfile)>, Error> {
+        let deploys = exec_request.take_deploys();
+        let mut results = Vec::with_capacity(deploys.len());
+
+        for deploy_item in deploys {
+            let gas_profiler = Rc::new(RefCell::new(GasProfiler::default()));
+            let executor = Executor::new(*self.config(), Arc::clone(&self.wasm_module_cache))
+                .with_gas_profiler(Rc::clone(&gas_profiler));
+            let result = self.execute_deploy_item(
+                correlation_id,
+                &executor,
+                &exec_request,
+                deploy_item,
+                None,
+            )?;
+            let gas_profile = gas_profiler.borrow_mut().take_profile();
+            results.push((result, gas_profile));
+        }
+
+        Ok(results)
+    }
+
+    /// Runs a deploy execution request like [`EngineState::run_execute`], additionally recording
+    /// the host functions called while executing the Wasm of each deploy.
+    ///
+    /// Returns the execution result of each deploy along with its [`ExecutionTrace`].
+    pub fn run_execute_tracing(
+        &self,
+        correlation_id: CorrelationId,
+        mut exec_request: ExecuteRequest,
+    ) -> Result<Vec<(ExecutionResult, ExecutionTrace)>, Error> {
+        let deploys = exec_request.take_deploys();
+        let mut results = Vec::with_capacity(deploys.len());
+
+        for deploy_item in deploys {
+            let execution_trace = Rc::new(RefCell::new(ExecutionTrace::default()));
+            let executor = Executor::new(*self.config(), Arc::clone(&self.wasm_module_cache))
+                .with_execution_trace(Rc::clone(&execution_trace));
+            let result = self.execute_deploy_item(
+                correlation_id,
+                &executor,
+                &exec_request,
+                deploy_item,
+                None,
+            )?;
+            let execution_trace = execution_trace.borrow_mut().take();
+            results.push((result, execution_trace));
         }
 
         Ok(results)
     }
 
+    /// Returns `true` if executing a deploy which made the accesses recorded in `read_set` against
+    /// `original_state_hash` would yield the same result when executed against
+    /// `current_state_hash`.
+    pub fn has_same_read_outcome(
+        &self,
+        correlation_id: CorrelationId,
+        read_set: &ReadSet,
+        original_state_hash: Digest,
+        current_state_hash: Digest,
+    ) -> Result<bool, Error> {
+        if original_state_hash == current_state_hash {
+            return Ok(true);
+        }
+        let original = self
+            .state
+            .checkout(original_state_hash)
+            .map_err(Into::into)?
+            .ok_or(Error::RootNotFound(original_state_hash))?;
+        let current = self
+            .state
+            .checkout(current_state_hash)
+            .map_err(Into::into)?
+            .ok_or(Error::RootNotFound(current_state_hash))?;
+        read_set
+            .has_same_outcome(correlation_id, &original, &current)
+            .map_err(|error| Error::Exec(error.into()))
+    }
+
+    /// Executes a single deploy of an execution request, recording its global state accesses in
+    /// `read_set` if given.
+    fn execute_deploy_item(
+        &self,
+        correlation_id: CorrelationId,
+        executor: &Executor,
+        exec_request: &ExecuteRequest,
+        deploy_item: DeployItem,
+        read_set: Option<Rc<RefCell<ReadSet>>>,
+    ) -> Result<ExecutionResult, Error> {
+        match deploy_item.session {
+            ExecutableDeployItem::Transfer { .. } => self.transfer(
+                correlation_id,
+                executor,
+                exec_request.protocol_version,
+                exec_request.parent_state_hash,
+                BlockTime::new(exec_request.block_time),
+                deploy_item,
+                exec_request.proposer.clone(),
+                read_set,
+            ),
+            _ => self.deploy(
+                correlation_id,
+                executor,
+                exec_request.protocol_version,
+                exec_request.parent_state_hash,
+                BlockTime::new(exec_request.block_time),
+                deploy_item,
+                exec_request.proposer.clone(),
+                read_set,
+            ),
+        }
+    }
+
     fn get_authorized_account(
         &self,
         correlation_id: CorrelationId,
@@ -570,12 +775,14 @@ where
         blocktime: BlockTime,
         deploy_item: DeployItem,
         proposer: PublicKey,
+        read_set: Option<Rc<RefCell<ReadSet>>>,
     ) -> Result<ExecutionResult, Error> {
-        let tracking_copy = match self.tracking_copy(prestate_hash) {
+        let tracking_copy = match self.recording_tracking_copy(prestate_hash, read_set.clone()) {
             Err(error) => return Ok(ExecutionResult::precondition_failure(error)),
             Ok(None) => return Err(Error::RootNotFound(prestate_hash)),
             Ok(Some(tracking_copy)) => Rc::new(RefCell::new(tracking_copy)),
         };
+        Self::record_registry_read(read_set.as_ref());
 
         let preprocessor = {
             let wasm_config = *self.config().wasm_config();
@@ -1172,6 +1379,7 @@ where
         blocktime: BlockTime,
         deploy_item: DeployItem,
         proposer: PublicKey,
+        read_set: Option<Rc<RefCell<ReadSet>>>,
     ) -> Result<ExecutionResult, Error> {
         // spec: https://casperlabs.atlassian.net/wiki/spaces/EN/pages/123404576/Payment+code+execution+specification
 
@@ -1184,7 +1392,7 @@ where
         // Create tracking copy (which functions as a deploy context)
         // validation_spec_2: prestate_hash check
         // do this second; as there is no reason to proceed if the prestate hash is invalid
-        let tracking_copy = match self.tracking_copy(prestate_hash) {
+        let tracking_copy = match self.recording_tracking_copy(prestate_hash, read_set.clone()) {
             Err(error) => return Ok(ExecutionResult::precondition_failure(error)),
             Ok(None) => return Err(Error::RootNotFound(prestate_hash)),
             Ok(Some(tracking_copy)) => Rc::new(RefCell::new(tracking_copy)),
@@ -1228,11 +1436,13 @@ where
         // we do this upfront as there is no reason to continue if session logic is invalid
         let system_contract_registry =
             self.get_system_contract_registry(correlation_id, prestate_hash)?;
+        Self::record_registry_read(read_set.as_ref());
         let session_metadata = match session.get_deploy_metadata(
             Rc::clone(&tracking_copy),
             &account,
             correlation_id,
             &preprocessor,
+            &self.wasm_module_cache,
             &protocol_version,
             system_contract_registry,
             Phase::Session,
@@ -1316,6 +1526,7 @@ where
                 &account,
                 correlation_id,
                 &preprocessor,
+                &self.wasm_module_cache,
                 &protocol_version,
                 system_contract_registry,
                 phase,
@@ -1838,7 +2049,7 @@ where
                 .map_err(Error::from)?
         };
 
-        let executor = Executor::new(*self.config());
+        let executor = Executor::new(*self.config(), Arc::clone(&self.wasm_module_cache));
 
         let mut named_keys = auction_contract.named_keys().to_owned();
         let base_key = Key::from(*auction_contract_hash);
@@ -1944,7 +2155,7 @@ where
             Ok(Some(tracking_copy)) => Rc::new(RefCell::new(tracking_copy)),
         };
 
-        let executor = Executor::new(*self.config());
+        let executor = Executor::new(*self.config(), Arc::clone(&self.wasm_module_cache));
 
         let preprocessor = {
             let config = self.config();
@@ -0,0 +1,98 @@
+//! Support for recording which parts of global state a deploy depends on.
+use std::collections::BTreeSet;
+
+use casper_types::{Key, StoredValue};
+
+use crate::{shared::newtypes::CorrelationId, storage::global_state::StateReader};
+
+/// The global state accesses made while executing a single deploy.
+///
+/// Executing the deploy against a different state yields the same execution result as long as
+/// every recorded access has the same outcome there, see [`ReadSet::has_same_outcome`].
+#[derive(Clone, Default, PartialEq, Eq, Debug)]
+pub struct ReadSet {
+    /// Keys whose value was read.
+    values: BTreeSet<Key>,
+    /// Keys which were only added to, which depends on their presence and type but not on their
+    /// value.
+    additions: BTreeSet<Key>,
+    /// Prefixes of keys which were listed.
+    prefixes: BTreeSet<Vec<u8>>,
+}
+
+impl ReadSet {
+    /// Records that the value under `key` was read.
+    pub(crate) fn record_value(&mut self, key: Key) {
+        self.additions.remove(&key);
+        self.values.insert(key);
+    }
+
+    /// Records that `key` was added to.
+    pub(crate) fn record_addition(&mut self, key: Key) {
+        if !self.values.contains(&key) {
+            self.additions.insert(key);
+        }
+    }
+
+    /// Records that the keys starting with `prefix` were listed.
+    pub(crate) fn record_prefix(&mut self, prefix: &[u8]) {
+        if !self.prefixes.contains(prefix) {
+            self.prefixes.insert(prefix.to_vec());
+        }
+    }
+
+    /// Returns the keys whose value was read.
+    pub fn values(&self) -> &BTreeSet<Key> {
+        &self.values
+    }
+
+    /// Returns the keys which were only added to.
+    pub fn additions(&self) -> &BTreeSet<Key> {
+        &self.additions
+    }
+
+    /// Returns the prefixes of keys which were listed.
+    pub fn prefixes(&self) -> &BTreeSet<Vec<u8>> {
+        &self.prefixes
+    }
+
+    /// Returns `true` if every recorded access has the same outcome against `current` as against
+    /// `original`.
+    ///
+    /// Read keys must hold the same value, keys which were added to must hold a value of the same
+    /// type and listed prefixes must match the same keys.
+    pub fn has_same_outcome<R>(
+        &self,
+        correlation_id: CorrelationId,
+        original: &R,
+        current: &R,
+    ) -> Result<bool, R::Error>
+    where
+        R: StateReader<Key, StoredValue>,
+    {
+        for key in &self.values {
+            if original.read(correlation_id, key)? != current.read(correlation_id, key)? {
+                return Ok(false);
+            }
+        }
+        for key in &self.additions {
+            let original_type = original
+                .read(correlation_id, key)?
+                .map(|value| value.type_name());
+            let current_type = current
+                .read(correlation_id, key)?
+                .map(|value| value.type_name());
+            if original_type != current_type {
+                return Ok(false);
+            }
+        }
+        for prefix in &self.prefixes {
+            if original.keys_with_prefix(correlation_id, prefix)?
+                != current.keys_with_prefix(correlation_id, prefix)?
+            {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+}
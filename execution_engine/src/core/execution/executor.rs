@@ -1,4 +1,4 @@
-use std::{cell::RefCell, collections::BTreeSet, rc::Rc};
+use std::{cell::RefCell, collections::BTreeSet, rc::Rc, sync::Arc};
 
 use parity_wasm::elements::Module;
 use tracing::warn;
@@ -17,11 +17,18 @@ use crate::{
     core::{
         engine_state::{execution_result::ExecutionResult, EngineConfig},
         execution::{address_generator::AddressGenerator, Error},
-        runtime::{extract_access_rights_from_keys, instance_and_memory, Runtime, RuntimeStack},
+        runtime::{
+            execution_trace::ExecutionTrace, extract_access_rights_from_keys,
+            gas_profiler::GasProfiler, instance_and_memory, profiled_instance_and_memory, Runtime,
+            RuntimeStack,
+        },
         runtime_context::{self, RuntimeContext},
         tracking_copy::{TrackingCopy, TrackingCopyExt},
     },
-    shared::{execution_journal::ExecutionJournal, newtypes::CorrelationId},
+    shared::{
+        execution_journal::ExecutionJournal, newtypes::CorrelationId,
+        wasm_module_cache::WasmModuleCache,
+    },
     storage::global_state::StateReader,
 };
 
@@ -71,13 +78,37 @@ macro_rules! on_fail_charge {
 /// Executor object deals with execution of WASM modules.
 pub struct Executor {
     config: EngineConfig,
+    wasm_module_cache: Arc<WasmModuleCache>,
+    gas_profiler: Option<Rc<RefCell<GasProfiler>>>,
+    execution_trace: Option<Rc<RefCell<ExecutionTrace>>>,
 }
 
 #[allow(clippy::too_many_arguments)]
 impl Executor {
     /// Creates new executor object.
-    pub fn new(config: EngineConfig) -> Self {
-        Executor { config }
+    pub fn new(config: EngineConfig, wasm_module_cache: Arc<WasmModuleCache>) -> Self {
+        Executor {
+            config,
+            wasm_module_cache,
+            gas_profiler: None,
+            execution_trace: None,
+        }
+    }
+
+    /// Attributes the gas charged while executing Wasm modules to the call stacks of
+    /// `gas_profiler`.
+    pub(crate) fn with_gas_profiler(mut self, gas_profiler: Rc<RefCell<GasProfiler>>) -> Self {
+        self.gas_profiler = Some(gas_profiler);
+        self
+    }
+
+    /// Records the host functions called while executing Wasm modules in `execution_trace`.
+    pub(crate) fn with_execution_trace(
+        mut self,
+        execution_trace: Rc<RefCell<ExecutionTrace>>,
+    ) -> Self {
+        self.execution_trace = Some(execution_trace);
+        self
     }
 
     /// Returns config.
@@ -117,10 +148,20 @@ impl Executor {
         let entry_point_type = entry_point.entry_point_type();
         let entry_point_access = entry_point.access();
 
-        let (instance, memory) = on_fail_charge!(instance_and_memory(
+        if let Some(gas_profiler) = &self.gas_profiler {
+            let frame = match phase {
+                Phase::System => "system",
+                Phase::Payment => "payment",
+                Phase::Session => "session",
+                Phase::FinalizePayment => "finalize_payment",
+            };
+            gas_profiler.borrow_mut().start(frame.to_string());
+        }
+        let (instance, memory, wasm_frames) = on_fail_charge!(profiled_instance_and_memory(
             module.clone(),
             protocol_version,
-            self.config.wasm_config()
+            self.config.wasm_config(),
+            self.gas_profiler.as_ref()
         ));
 
         let access_rights = {
@@ -160,7 +201,16 @@ impl Executor {
             transfers,
         );
 
-        let mut runtime = Runtime::new(self.config, memory, module, context, stack);
+        let mut runtime = Runtime::new(
+            self.config,
+            Arc::clone(&self.wasm_module_cache),
+            memory,
+            module,
+            context,
+            stack,
+        )
+        .with_gas_profiler(self.gas_profiler.clone(), wasm_frames)
+        .with_execution_trace(self.execution_trace.clone());
 
         let accounts_access_rights = {
             let keys: Vec<Key> = account.named_keys().values().cloned().collect();
@@ -632,7 +682,14 @@ impl Executor {
         let (instance, memory) =
             instance_and_memory(module.clone(), protocol_version, self.config.wasm_config())?;
 
-        let runtime = Runtime::new(self.config, memory, module, runtime_context, stack);
+        let runtime = Runtime::new(
+            self.config,
+            Arc::clone(&self.wasm_module_cache),
+            memory,
+            module,
+            runtime_context,
+            stack,
+        );
 
         Ok((instance, runtime))
     }
@@ -0,0 +1,682 @@
+//! Recording of the host functions called while executing deploys, for debugging contracts.
+//!
+//! When tracing, every host function called by the executed Wasm is recorded along with its
+//! arguments, its result, the gas counter before and after the call and the transforms applied
+//! while it ran. Calls into other contracts additionally record the call stack they were made from.
+//! The injected `gas` host function is not recorded, as the gas it charges shows in the gas
+//! counters of the surrounding calls.
+//!
+//! Arguments passed in Wasm memory as a pointer and a size are read and decoded as the type the host
+//! function expects, e.g. a `Key` or a `CLValue`, while all other arguments are recorded as they
+//! were passed.
+//!
+//! Traces serialize to JSON, so that the traces of two versions of a contract can be compared with
+//! a plain diff.
+use std::{collections::BTreeSet, io::Write, mem};
+
+use serde::{Deserialize, Serialize};
+use wasmi::{RuntimeValue, Trap, TrapKind};
+
+use casper_types::{
+    account::AccountHash,
+    bytesrepr::{self, Bytes, FromBytes},
+    contracts::{EntryPoints, NamedKeys},
+    system::{auction::EraInfo, CallStackElement},
+    CLValue, ContractHash, ContractPackageHash, ContractVersion, EraId, Group, Key, Phase,
+    RuntimeArgs, TransformEntry, URef, U512,
+};
+
+use crate::core::{execution::Error, resolvers::v1_function_index::FunctionIndex};
+
+/// A value passed to or returned from a host function.
+///
+/// Floating point values are kept as their bit patterns.
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum TraceValue {
+    /// A 32-bit integer.
+    I32(i32),
+    /// A 64-bit integer.
+    I64(i64),
+    /// A 32-bit float.
+    F32(u32),
+    /// A 64-bit float.
+    F64(u64),
+}
+
+impl From<RuntimeValue> for TraceValue {
+    fn from(value: RuntimeValue) -> Self {
+        match value {
+            RuntimeValue::I32(value) => TraceValue::I32(value),
+            RuntimeValue::I64(value) => TraceValue::I64(value),
+            RuntimeValue::F32(value) => TraceValue::F32(value.to_bits()),
+            RuntimeValue::F64(value) => TraceValue::F64(value.to_bits()),
+        }
+    }
+}
+
+/// An argument passed to a host function.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+pub struct TraceArg {
+    /// The name of the argument.
+    pub name: String,
+    /// The value of the argument.
+    pub value: TraceArgValue,
+}
+
+impl TraceArg {
+    fn new(name: &str, value: TraceArgValue) -> Self {
+        TraceArg {
+            name: name.to_string(),
+            value,
+        }
+    }
+}
+
+/// The value of an argument passed to a host function.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum TraceArgValue {
+    /// A value passed as is, e.g. a flag or a pointer to an output parameter.
+    Wasm(TraceValue),
+    /// A value read from Wasm memory, as the JSON representation of the type the host function
+    /// expects.
+    Decoded(serde_json::Value),
+    /// The bytes read from Wasm memory if they are not a valid value of the expected type.
+    Undecodable(Bytes),
+}
+
+/// Decodes a value read from Wasm memory, returning `None` if the bytes are invalid.
+type Decoder = fn(&[u8]) -> Option<serde_json::Value>;
+
+/// The way an argument is passed to a host function.
+#[derive(Clone, Copy)]
+enum ArgLayout {
+    /// A single Wasm value.
+    Wasm(&'static str),
+    /// A pointer to a value in Wasm memory followed by its size.
+    Memory(&'static str, Decoder),
+}
+
+fn decode<T: FromBytes + Serialize>(bytes: &[u8]) -> Option<serde_json::Value> {
+    let value: T = bytesrepr::deserialize(bytes.to_vec()).ok()?;
+    serde_json::to_value(value).ok()
+}
+
+/// Decodes a string passed as its raw UTF-8 bytes rather than serialized.
+fn decode_utf8(bytes: &[u8]) -> Option<serde_json::Value> {
+    String::from_utf8(bytes.to_vec())
+        .ok()
+        .map(serde_json::Value::String)
+}
+
+fn decode_raw(bytes: &[u8]) -> Option<serde_json::Value> {
+    serde_json::to_value(Bytes::from(bytes.to_vec())).ok()
+}
+
+/// Returns the arguments taken by the given host function, mirroring how `invoke_host_function`
+/// parses them.
+fn arg_layout(func: FunctionIndex) -> Vec<ArgLayout> {
+    use ArgLayout::{Memory, Wasm};
+
+    match func {
+        FunctionIndex::WriteFuncIndex | FunctionIndex::AddFuncIndex => vec![
+            Memory("key", decode::<Key>),
+            Memory("value", decode::<CLValue>),
+        ],
+        FunctionIndex::ReadFuncIndex => vec![Memory("key", decode::<Key>), Wasm("output_size_ptr")],
+        FunctionIndex::NewFuncIndex => {
+            vec![Wasm("uref_ptr"), Memory("value", decode::<CLValue>)]
+        }
+        FunctionIndex::RetFuncIndex => vec![Memory("value", decode::<CLValue>)],
+        FunctionIndex::CallContractFuncIndex => vec![
+            Memory("contract_hash", decode::<ContractHash>),
+            Memory("entry_point_name", decode::<String>),
+            Memory("runtime_args", decode::<RuntimeArgs>),
+            Wasm("result_size_ptr"),
+        ],
+        FunctionIndex::GetKeyFuncIndex => vec![
+            Memory("name", decode::<String>),
+            Wasm("output_ptr"),
+            Wasm("output_size"),
+            Wasm("bytes_written_ptr"),
+        ],
+        FunctionIndex::GasFuncIndex => vec![Wasm("gas")],
+        FunctionIndex::HasKeyFuncIndex | FunctionIndex::RemoveKeyFuncIndex => {
+            vec![Memory("name", decode::<String>)]
+        }
+        FunctionIndex::PutKeyFuncIndex => vec![
+            Memory("name", decode::<String>),
+            Memory("key", decode::<Key>),
+        ],
+        FunctionIndex::IsValidURefFnIndex => vec![Memory("uref", decode::<URef>)],
+        FunctionIndex::RevertFuncIndex => vec![Wasm("status")],
+        FunctionIndex::AddAssociatedKeyFuncIndex | FunctionIndex::UpdateAssociatedKeyFuncIndex => {
+            vec![
+                Memory("account_hash", decode::<AccountHash>),
+                Wasm("weight"),
+            ]
+        }
+        FunctionIndex::RemoveAssociatedKeyFuncIndex => {
+            vec![Memory("account_hash", decode::<AccountHash>)]
+        }
+        FunctionIndex::SetActionThresholdFuncIndex => {
+            vec![Wasm("action_type"), Wasm("threshold")]
+        }
+        FunctionIndex::LoadNamedKeysFuncIndex => {
+            vec![Wasm("total_keys_ptr"), Wasm("result_size_ptr")]
+        }
+        FunctionIndex::GetCallerIndex | FunctionIndex::NewDictionaryFuncIndex => {
+            vec![Wasm("output_size_ptr")]
+        }
+        FunctionIndex::GetBlocktimeIndex
+        | FunctionIndex::GetPhaseIndex
+        | FunctionIndex::GetMainPurseIndex => vec![Wasm("dest_ptr")],
+        FunctionIndex::CreatePurseIndex => vec![Wasm("dest_ptr"), Wasm("dest_size")],
+        FunctionIndex::TransferToAccountIndex => vec![
+            Memory("account_hash", decode::<AccountHash>),
+            Memory("amount", decode::<U512>),
+            Memory("id", decode::<Option<u64>>),
+            Wasm("result_ptr"),
+        ],
+        FunctionIndex::TransferFromPurseToAccountIndex => vec![
+            Memory("source", decode::<URef>),
+            Memory("account_hash", decode::<AccountHash>),
+            Memory("amount", decode::<U512>),
+            Memory("id", decode::<Option<u64>>),
+            Wasm("result_ptr"),
+        ],
+        FunctionIndex::TransferFromPurseToPurseIndex => vec![
+            Memory("source", decode::<URef>),
+            Memory("target", decode::<URef>),
+            Memory("amount", decode::<U512>),
+            Memory("id", decode::<Option<u64>>),
+        ],
+        FunctionIndex::GetBalanceIndex => {
+            vec![Memory("purse", decode::<URef>), Wasm("output_size_ptr")]
+        }
+        FunctionIndex::GetSystemContractIndex => vec![
+            Wasm("system_contract_index"),
+            Wasm("dest_ptr"),
+            Wasm("dest_size"),
+        ],
+        FunctionIndex::ReadHostBufferIndex => vec![
+            Wasm("dest_ptr"),
+            Wasm("dest_size"),
+            Wasm("bytes_written_ptr"),
+        ],
+        FunctionIndex::CreateContractPackageAtHash => vec![
+            Wasm("hash_dest_ptr"),
+            Wasm("access_dest_ptr"),
+            Wasm("is_locked"),
+        ],
+        FunctionIndex::AddContractVersion => vec![
+            Memory("contract_package_hash", decode::<ContractPackageHash>),
+            Wasm("version_ptr"),
+            Memory("entry_points", decode::<EntryPoints>),
+            Memory("named_keys", decode::<NamedKeys>),
+            Wasm("output_ptr"),
+            Wasm("output_size"),
+            Wasm("bytes_written_ptr"),
+        ],
+        FunctionIndex::DisableContractVersion => vec![
+            Memory("contract_package_hash", decode::<ContractPackageHash>),
+            Memory("contract_hash", decode::<ContractHash>),
+        ],
+        FunctionIndex::CallVersionedContract => vec![
+            Memory("contract_package_hash", decode::<ContractPackageHash>),
+            Memory("contract_version", decode::<Option<ContractVersion>>),
+            Memory("entry_point_name", decode::<String>),
+            Memory("runtime_args", decode::<RuntimeArgs>),
+            Wasm("result_size_ptr"),
+        ],
+        FunctionIndex::CreateContractUserGroup => vec![
+            Memory("contract_package_hash", decode::<ContractPackageHash>),
+            Memory("label", decode::<String>),
+            Wasm("num_new_urefs"),
+            Memory("existing_urefs", decode::<BTreeSet<URef>>),
+            Wasm("output_size_ptr"),
+        ],
+        #[cfg(feature = "test-support")]
+        FunctionIndex::PrintIndex => vec![Memory("text", decode::<String>)],
+        FunctionIndex::GetRuntimeArgsizeIndex => {
+            vec![Memory("name", decode_utf8), Wasm("size_ptr")]
+        }
+        FunctionIndex::GetRuntimeArgIndex => vec![
+            Memory("name", decode_utf8),
+            Wasm("dest_ptr"),
+            Wasm("dest_size"),
+        ],
+        FunctionIndex::RemoveContractUserGroupIndex => vec![
+            Memory("contract_package_hash", decode::<ContractPackageHash>),
+            Memory("label", decode::<Group>),
+        ],
+        FunctionIndex::ExtendContractUserGroupURefsIndex => vec![
+            Memory("contract_package_hash", decode::<ContractPackageHash>),
+            Memory("label", decode::<String>),
+            Wasm("value_size_ptr"),
+        ],
+        FunctionIndex::RemoveContractUserGroupURefsIndex => vec![
+            Memory("contract_package_hash", decode::<ContractPackageHash>),
+            Memory("label", decode::<String>),
+            Memory("urefs", decode::<BTreeSet<URef>>),
+        ],
+        FunctionIndex::Blake2b => vec![
+            Memory("input", decode_raw),
+            Wasm("out_ptr"),
+            Wasm("out_size"),
+        ],
+        FunctionIndex::RecordTransfer => vec![
+            Memory("to", decode::<Option<AccountHash>>),
+            Memory("source", decode::<URef>),
+            Memory("target", decode::<URef>),
+            Memory("amount", decode::<U512>),
+            Memory("id", decode::<Option<u64>>),
+        ],
+        FunctionIndex::RecordEraInfo => vec![
+            Memory("era_id", decode::<EraId>),
+            Memory("era_info", decode::<EraInfo>),
+        ],
+        FunctionIndex::DictionaryGetFuncIndex => vec![
+            Memory("uref", decode::<URef>),
+            Memory("dictionary_item_key", decode_utf8),
+            Wasm("output_size_ptr"),
+        ],
+        FunctionIndex::DictionaryPutFuncIndex => vec![
+            Memory("uref", decode::<URef>),
+            Memory("dictionary_item_key", decode_utf8),
+            Memory("value", decode::<CLValue>),
+        ],
+        FunctionIndex::LoadCallStack => {
+            vec![Wasm("call_stack_len_ptr"), Wasm("result_size_ptr")]
+        }
+    }
+}
+
+/// Decodes the arguments passed to the given host function, reading the values passed in Wasm
+/// memory with `read_memory`.
+///
+/// Values which can't be read from memory are recorded as their pointer and size.  If the
+/// arguments don't match the host function's signature, they are all recorded as passed.
+pub(crate) fn decode_args<F>(
+    func: FunctionIndex,
+    args: &[RuntimeValue],
+    read_memory: F,
+) -> Vec<TraceArg>
+where
+    F: Fn(u32, u32) -> Option<Vec<u8>>,
+{
+    let layout = arg_layout(func);
+    let expected_len: usize = layout
+        .iter()
+        .map(|arg| match arg {
+            ArgLayout::Wasm(_) => 1,
+            ArgLayout::Memory(..) => 2,
+        })
+        .sum();
+    if expected_len != args.len() {
+        return args
+            .iter()
+            .enumerate()
+            .map(|(index, value)| {
+                TraceArg::new(
+                    &format!("arg{}", index),
+                    TraceArgValue::Wasm(TraceValue::from(*value)),
+                )
+            })
+            .collect();
+    }
+
+    let mut args = args.iter().copied();
+    let mut decoded = Vec::with_capacity(layout.len());
+    for arg in layout {
+        match arg {
+            ArgLayout::Wasm(name) => {
+                let value = args.next().expect("checked arg count");
+                decoded.push(TraceArg::new(
+                    name,
+                    TraceArgValue::Wasm(TraceValue::from(value)),
+                ));
+            }
+            ArgLayout::Memory(name, decoder) => {
+                let ptr = args.next().expect("checked arg count");
+                let size = args.next().expect("checked arg count");
+                let bytes = match (ptr, size) {
+                    (RuntimeValue::I32(ptr), RuntimeValue::I32(size)) => {
+                        read_memory(ptr as u32, size as u32)
+                    }
+                    _ => None,
+                };
+                match bytes {
+                    Some(bytes) => {
+                        let value = match decoder(&bytes) {
+                            Some(value) => TraceArgValue::Decoded(value),
+                            None => TraceArgValue::Undecodable(Bytes::from(bytes)),
+                        };
+                        decoded.push(TraceArg::new(name, value));
+                    }
+                    None => {
+                        decoded.push(TraceArg::new(
+                            &format!("{}_ptr", name),
+                            TraceArgValue::Wasm(TraceValue::from(ptr)),
+                        ));
+                        decoded.push(TraceArg::new(
+                            &format!("{}_size", name),
+                            TraceArgValue::Wasm(TraceValue::from(size)),
+                        ));
+                    }
+                }
+            }
+        }
+    }
+    decoded
+}
+
+/// An element of the call stack a contract was called from.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum TraceCallStackElement {
+    /// Session code.
+    Session {
+        /// The account hash of the caller.
+        account_hash: AccountHash,
+    },
+    /// A stored contract called as session code.
+    StoredSession {
+        /// The account hash of the caller.
+        account_hash: AccountHash,
+        /// The contract package hash.
+        contract_package_hash: ContractPackageHash,
+        /// The contract hash.
+        contract_hash: ContractHash,
+    },
+    /// A stored contract.
+    StoredContract {
+        /// The contract package hash.
+        contract_package_hash: ContractPackageHash,
+        /// The contract hash.
+        contract_hash: ContractHash,
+    },
+}
+
+impl From<&CallStackElement> for TraceCallStackElement {
+    fn from(call_stack_element: &CallStackElement) -> Self {
+        match *call_stack_element {
+            CallStackElement::Session { account_hash } => {
+                TraceCallStackElement::Session { account_hash }
+            }
+            CallStackElement::StoredSession {
+                account_hash,
+                contract_package_hash,
+                contract_hash,
+            } => TraceCallStackElement::StoredSession {
+                account_hash,
+                contract_package_hash,
+                contract_hash,
+            },
+            CallStackElement::StoredContract {
+                contract_package_hash,
+                contract_hash,
+            } => TraceCallStackElement::StoredContract {
+                contract_package_hash,
+                contract_hash,
+            },
+        }
+    }
+}
+
+/// A single call of a host function.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+pub struct HostFunctionCall {
+    /// The name of the host function.
+    pub host_function: String,
+    /// The phase of the deploy the host function was called in.
+    pub phase: String,
+    /// The height of the call stack the host function was called from, `1` in session or payment
+    /// code.
+    pub call_depth: usize,
+    /// The arguments passed to the host function.
+    pub args: Vec<TraceArg>,
+    /// The value returned to the Wasm caller, if any.
+    pub return_value: Option<TraceValue>,
+    /// The value placed in the host buffer by the host function, e.g. a value read from global
+    /// state or the value returned by a called contract.
+    pub host_buffer: Option<CLValue>,
+    /// The error execution was stopped with, if the host function trapped.
+    pub error: Option<String>,
+    /// The gas counter before the call.
+    pub gas_before: U512,
+    /// The gas counter after the call.
+    pub gas_after: U512,
+    /// The call stack a call into another contract was made from.
+    pub call_stack: Option<Vec<TraceCallStackElement>>,
+    /// The transforms applied while the host function ran, including those applied by the
+    /// contracts it called.
+    pub transforms: Vec<TransformEntry>,
+}
+
+impl HostFunctionCall {
+    /// Creates a call of `host_function` which has not returned yet.
+    pub(crate) fn new(
+        host_function: &str,
+        phase: Phase,
+        call_depth: usize,
+        args: Vec<TraceArg>,
+        gas_before: U512,
+        call_stack: Option<Vec<TraceCallStackElement>>,
+    ) -> Self {
+        HostFunctionCall {
+            host_function: host_function.to_string(),
+            phase: phase_name(phase).to_string(),
+            call_depth,
+            args,
+            return_value: None,
+            host_buffer: None,
+            error: None,
+            gas_before,
+            gas_after: gas_before,
+            call_stack,
+            transforms: Vec::new(),
+        }
+    }
+
+    /// Records the outcome of the call.
+    pub(crate) fn set_result(&mut self, result: &Result<Option<RuntimeValue>, Trap>) {
+        match result {
+            Ok(return_value) => self.return_value = return_value.map(TraceValue::from),
+            Err(trap) => self.error = Some(describe_trap(trap)),
+        }
+    }
+}
+
+/// The host functions called while executing a deploy, in the order they were called.
+#[derive(Clone, Default, PartialEq, Eq, Serialize, Deserialize, Debug)]
+pub struct ExecutionTrace {
+    host_function_calls: Vec<HostFunctionCall>,
+}
+
+impl ExecutionTrace {
+    /// Returns the recorded host function calls.
+    pub fn host_function_calls(&self) -> &[HostFunctionCall] {
+        &self.host_function_calls
+    }
+
+    /// Returns `true` if no host function call was recorded.
+    pub fn is_empty(&self) -> bool {
+        self.host_function_calls.is_empty()
+    }
+
+    /// Returns the trace as pretty-printed JSON.
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Writes the trace to `writer` as pretty-printed JSON.
+    pub fn write_json<W: Write>(&self, writer: W) -> Result<(), serde_json::Error> {
+        serde_json::to_writer_pretty(writer, self)
+    }
+
+    /// Records the start of a host function call, returning its index in the trace.
+    ///
+    /// Calls are recorded when they start, so that they precede the calls made by the contracts
+    /// they call.
+    pub(crate) fn push(&mut self, host_function_call: HostFunctionCall) -> usize {
+        self.host_function_calls.push(host_function_call);
+        self.host_function_calls.len() - 1
+    }
+
+    /// Returns the host function call at `index`.
+    pub(crate) fn get_mut(&mut self, index: usize) -> Option<&mut HostFunctionCall> {
+        self.host_function_calls.get_mut(index)
+    }
+
+    /// Takes the recorded host function calls, leaving the trace empty.
+    pub(crate) fn take(&mut self) -> ExecutionTrace {
+        mem::take(self)
+    }
+}
+
+fn phase_name(phase: Phase) -> &'static str {
+    match phase {
+        Phase::System => "system",
+        Phase::Payment => "payment",
+        Phase::Session => "session",
+        Phase::FinalizePayment => "finalize_payment",
+    }
+}
+
+fn describe_trap(trap: &Trap) -> String {
+    match trap.kind() {
+        TrapKind::Host(host_error) => match host_error.downcast_ref::<Error>() {
+            Some(error) => format!("{:?}", error),
+            None => format!("{:?}", host_error),
+        },
+        trap_kind => format!("{:?}", trap_kind),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use casper_types::ApiError;
+
+    use super::*;
+
+    #[test]
+    fn should_record_result_of_host_function_call() {
+        let mut execution_trace = ExecutionTrace::default();
+        let index = execution_trace.push(HostFunctionCall::new(
+            "host_function_revert",
+            Phase::Session,
+            1,
+            vec![TraceArg::new(
+                "status",
+                TraceArgValue::Wasm(TraceValue::I32(1)),
+            )],
+            U512::from(10),
+            None,
+        ));
+        let call = execution_trace.get_mut(index).unwrap();
+        call.set_result(&Err(Trap::from(Error::Revert(ApiError::User(1)))));
+        call.gas_after = U512::from(20);
+
+        let call = &execution_trace.host_function_calls()[0];
+        assert_eq!(call.phase, "session");
+        assert_eq!(call.return_value, None);
+        assert_eq!(
+            call.error,
+            Some(format!("{:?}", Error::Revert(ApiError::User(1))))
+        );
+        assert_eq!(call.gas_before, U512::from(10));
+        assert_eq!(call.gas_after, U512::from(20));
+    }
+
+    #[test]
+    fn should_round_trip_through_json() {
+        let mut execution_trace = ExecutionTrace::default();
+        let index = execution_trace.push(HostFunctionCall::new(
+            "host_function_get_named_arg_size",
+            Phase::Payment,
+            1,
+            vec![
+                TraceArg::new(
+                    "name",
+                    TraceArgValue::Decoded(serde_json::Value::String("amount".to_string())),
+                ),
+                TraceArg::new("size_ptr", TraceArgValue::Wasm(TraceValue::I64(-1))),
+            ],
+            U512::zero(),
+            Some(vec![TraceCallStackElement::Session {
+                account_hash: AccountHash::new([1; 32]),
+            }]),
+        ));
+        execution_trace
+            .get_mut(index)
+            .unwrap()
+            .set_result(&Ok(Some(RuntimeValue::I32(0))));
+        execution_trace.get_mut(index).unwrap().host_buffer = Some(CLValue::from_t(7u64).unwrap());
+
+        let json = execution_trace.to_json().unwrap();
+        let parsed: ExecutionTrace = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, execution_trace);
+        assert_eq!(execution_trace.take().host_function_calls().len(), 1);
+        assert!(execution_trace.is_empty());
+    }
+
+    #[test]
+    fn should_decode_args_passed_in_memory() {
+        let key = Key::Hash([1; 32]);
+        let key_bytes = bytesrepr::serialize(key).unwrap();
+        let key_size = key_bytes.len() as i32;
+        let read_memory = |ptr: u32, size: u32| match (ptr, size) {
+            (8, size) if size == key_size as u32 => Some(key_bytes.clone()),
+            (16, 3) => Some(vec![0xff; 3]),
+            _ => None,
+        };
+
+        let args = decode_args(
+            FunctionIndex::ReadFuncIndex,
+            &[
+                RuntimeValue::I32(8),
+                RuntimeValue::I32(key_size),
+                RuntimeValue::I32(64),
+            ],
+            read_memory,
+        );
+        assert_eq!(
+            args,
+            vec![
+                TraceArg::new(
+                    "key",
+                    TraceArgValue::Decoded(serde_json::to_value(key).unwrap())
+                ),
+                TraceArg::new("output_size_ptr", TraceArgValue::Wasm(TraceValue::I32(64))),
+            ]
+        );
+
+        let args = decode_args(
+            FunctionIndex::HasKeyFuncIndex,
+            &[RuntimeValue::I32(16), RuntimeValue::I32(3)],
+            read_memory,
+        );
+        assert_eq!(
+            args,
+            vec![TraceArg::new(
+                "name",
+                TraceArgValue::Undecodable(Bytes::from(vec![0xff; 3]))
+            )]
+        );
+
+        let args = decode_args(
+            FunctionIndex::RemoveKeyFuncIndex,
+            &[RuntimeValue::I32(1 << 20), RuntimeValue::I32(3)],
+            read_memory,
+        );
+        assert_eq!(
+            args,
+            vec![
+                TraceArg::new("name_ptr", TraceArgValue::Wasm(TraceValue::I32(1 << 20))),
+                TraceArg::new("name_size", TraceArgValue::Wasm(TraceValue::I32(3))),
+            ]
+        );
+    }
+}
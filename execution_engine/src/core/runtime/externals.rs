@@ -1,4 +1,4 @@
-use std::{collections::BTreeSet, convert::TryFrom};
+use std::{cell::RefCell, collections::BTreeSet, convert::TryFrom};
 
 use wasmi::{Externals, RuntimeArgs, RuntimeValue, Trap};
 
@@ -9,11 +9,17 @@ use casper_types::{
     bytesrepr::{self, ToBytes},
     contracts::{ContractPackageStatus, EntryPoints, NamedKeys},
     system::auction::EraInfo,
-    ContractHash, ContractPackageHash, ContractVersion, EraId, Gas, Group, Key, StoredValue, URef,
-    U512,
+    ContractHash, ContractPackageHash, ContractVersion, EraId, ExecutionEffect, Gas, Group, Key,
+    StoredValue, URef, U512,
 };
 
-use super::{args::Args, scoped_instrumenter::ScopedInstrumenter, Error, Runtime};
+use super::{
+    args::Args,
+    execution_trace::{self, ExecutionTrace, HostFunctionCall, TraceCallStackElement},
+    gas_profiler::GasProfiler,
+    scoped_instrumenter::{self, ScopedInstrumenter},
+    Error, Runtime,
+};
 use crate::{
     core::resolvers::v1_function_index::FunctionIndex,
     shared::host_function_costs::{Cost, HostFunction, DEFAULT_HOST_FUNCTION_NEW_DICTIONARY},
@@ -31,7 +37,139 @@ where
         args: RuntimeArgs,
     ) -> Result<Option<RuntimeValue>, Trap> {
         let func = FunctionIndex::try_from(index).expect("unknown function index");
+        let execution_trace = self.execution_trace.clone();
+        let traced_call = execution_trace
+            .as_ref()
+            .and_then(|execution_trace| self.begin_traced_call(func, &args, execution_trace));
+        let result = match self.gas_profiler.clone() {
+            Some(gas_profiler) => self.invoke_profiled_host_function(func, args, &gas_profiler),
+            None => self.invoke_host_function(func, args),
+        };
+        if let (Some(execution_trace), Some(traced_call)) = (execution_trace, traced_call) {
+            self.end_traced_call(traced_call, &result, &execution_trace);
+        }
+        result
+    }
+}
 
+/// A host function call in progress, recorded in an [`ExecutionTrace`].
+struct TracedCall {
+    /// The index of the call in the trace.
+    index: usize,
+    /// The length of the execution journal when the call started.
+    journal_len: usize,
+    /// Whether the host buffer was already set when the call started.
+    host_buffer_was_set: bool,
+}
+
+impl<'a, R> Runtime<'a, R>
+where
+    R: StateReader<Key, StoredValue>,
+    R::Error: Into<Error>,
+{
+    /// Invokes a host function, attributing the gas it charges to the current call stack of
+    /// `gas_profiler`.
+    ///
+    /// Gas charged by the contracts called by the host function is attributed to their own call
+    /// stacks, and is therefore not attributed to the host function again.
+    fn invoke_profiled_host_function(
+        &mut self,
+        func: FunctionIndex,
+        args: RuntimeArgs,
+        gas_profiler: &RefCell<GasProfiler>,
+    ) -> Result<Option<RuntimeValue>, Trap> {
+        let depth = {
+            let mut gas_profiler = gas_profiler.borrow_mut();
+            if let Some(wasm_frames) = &self.wasm_frames {
+                wasm_frames.sync(&mut gas_profiler);
+            }
+            let depth = gas_profiler.depth();
+            if let Some(host_function) = scoped_instrumenter::host_function_name(func) {
+                gas_profiler.push_frame(host_function.to_string());
+            }
+            depth
+        };
+        let gas_counter_before = self.gas_counter();
+        let recorded_before = gas_profiler.borrow().recorded();
+
+        let result = self.invoke_host_function(func, args);
+
+        let mut gas_profiler = gas_profiler.borrow_mut();
+        let charged = self
+            .gas_counter()
+            .value()
+            .saturating_sub(gas_counter_before.value());
+        let charged_by_callees = gas_profiler.recorded().value() - recorded_before.value();
+        gas_profiler.record(Gas::new(charged.saturating_sub(charged_by_callees)));
+        gas_profiler.truncate(depth);
+        result
+    }
+
+    /// Records the start of a call of `func` in `execution_trace`.
+    ///
+    /// Returns `None` for the injected gas function, which is not traced.
+    fn begin_traced_call(
+        &self,
+        func: FunctionIndex,
+        args: &RuntimeArgs,
+        execution_trace: &RefCell<ExecutionTrace>,
+    ) -> Option<TracedCall> {
+        let host_function = scoped_instrumenter::host_function_name(func)?;
+        let call_stack = match func {
+            FunctionIndex::CallContractFuncIndex | FunctionIndex::CallVersionedContract => Some(
+                self.stack()
+                    .call_stack_elements()
+                    .iter()
+                    .map(TraceCallStackElement::from)
+                    .collect(),
+            ),
+            _ => None,
+        };
+        let host_function_call = HostFunctionCall::new(
+            host_function,
+            self.context.phase(),
+            self.stack().len(),
+            execution_trace::decode_args(func, args.as_ref(), |ptr, size| {
+                self.bytes_from_mem(ptr, size as usize).ok()
+            }),
+            self.gas_counter().value(),
+            call_stack,
+        );
+        Some(TracedCall {
+            index: execution_trace.borrow_mut().push(host_function_call),
+            journal_len: self.context.execution_journal_len(),
+            host_buffer_was_set: self.host_buffer.is_some(),
+        })
+    }
+
+    /// Records the outcome of `traced_call` in `execution_trace`.
+    fn end_traced_call(
+        &self,
+        traced_call: TracedCall,
+        result: &Result<Option<RuntimeValue>, Trap>,
+        execution_trace: &RefCell<ExecutionTrace>,
+    ) {
+        let journal = self
+            .context
+            .execution_journal_since(traced_call.journal_len);
+        let mut execution_trace = execution_trace.borrow_mut();
+        let host_function_call = match execution_trace.get_mut(traced_call.index) {
+            Some(host_function_call) => host_function_call,
+            None => return,
+        };
+        host_function_call.set_result(result);
+        if !traced_call.host_buffer_was_set {
+            host_function_call.host_buffer = self.host_buffer.clone();
+        }
+        host_function_call.gas_after = self.gas_counter().value();
+        host_function_call.transforms = ExecutionEffect::from(&journal).transforms;
+    }
+
+    fn invoke_host_function(
+        &mut self,
+        func: FunctionIndex,
+        args: RuntimeArgs,
+    ) -> Result<Option<RuntimeValue>, Trap> {
         let mut scoped_instrumenter = ScopedInstrumenter::new(func);
 
         let host_function_costs = self.config.wasm_config().take_host_function_costs();
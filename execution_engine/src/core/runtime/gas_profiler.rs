@@ -0,0 +1,561 @@
+//! Attribution of the gas charged while executing Wasm to the call stacks it was charged in.
+//!
+//! When profiling, every Wasm module is instrumented before being instantiated: each function
+//! which charges gas records its index and its call depth in two exported globals on entry, and
+//! restores them whenever a call made by it returns. Host functions read these globals to rebuild
+//! the Wasm call stack, so that all gas charged by a host function, including the gas charged by
+//! the injected `gas` host function for the executed opcodes, is attributed to the full call stack
+//! of the deploy: the phase, the stored contracts called, the Wasm functions and the host function.
+//!
+//! The instrumented module is only ever instantiated; it is neither stored nor charged for, hence
+//! a profiled execution charges exactly the same gas as an unprofiled one.
+use std::{
+    collections::BTreeMap,
+    io::{self, Write},
+    mem,
+};
+
+use itertools::Itertools;
+use parity_wasm::elements::{
+    ExportEntry, ExportSection, External, GlobalEntry, GlobalSection, GlobalType, InitExpr,
+    Instruction, Internal, Local, Module, Section, Type, ValueType,
+};
+use wasmi::{GlobalRef, ModuleRef};
+
+use casper_types::Gas;
+
+/// Name of the exported global holding the index of the executing Wasm function.
+const FUNCTION_GLOBAL_NAME: &str = "__casper_gas_profiler_function";
+/// Name of the exported global holding the call depth of the executing Wasm function.
+const DEPTH_GLOBAL_NAME: &str = "__casper_gas_profiler_depth";
+/// Module and field name of the host function injected to charge gas for the executed opcodes.
+const GAS_FUNCTION_IMPORT: (&str, &str) = ("env", "gas");
+
+/// The gas charged while executing deploys, keyed by the call stacks it was charged in.
+///
+/// Call stacks are made of frames separated by `;`, from the outermost to the innermost, as in the
+/// folded stacks format consumed by flamegraph tools.
+#[derive(Clone, Default, PartialEq, Eq, Debug)]
+pub struct GasProfile {
+    stacks: BTreeMap<String, Gas>,
+}
+
+impl GasProfile {
+    /// Returns the gas charged in each call stack.
+    pub fn stacks(&self) -> &BTreeMap<String, Gas> {
+        &self.stacks
+    }
+
+    /// Returns the total gas charged in all call stacks.
+    pub fn total(&self) -> Gas {
+        self.stacks.values().copied().sum()
+    }
+
+    /// Returns `true` if no gas was attributed to any call stack.
+    pub fn is_empty(&self) -> bool {
+        self.stacks.is_empty()
+    }
+
+    /// Adds the gas charged in the call stacks of `other` to this profile.
+    pub fn merge(&mut self, other: GasProfile) {
+        for (stack, gas) in other.stacks {
+            *self.stacks.entry(stack).or_default() += gas;
+        }
+    }
+
+    /// Writes the profile in the folded stacks format, one call stack and the gas charged in it
+    /// per line.
+    pub fn write_folded<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        for (stack, gas) in &self.stacks {
+            writeln!(writer, "{} {}", stack, gas)?;
+        }
+        Ok(())
+    }
+
+    fn record(&mut self, stack: String, gas: Gas) {
+        *self.stacks.entry(stack).or_default() += gas;
+    }
+}
+
+/// The call stack of an execution being profiled, shared by the runtimes of all contracts it
+/// calls.
+///
+/// Frames are `None` for Wasm functions which have not charged any gas yet.
+#[derive(Default, Debug)]
+pub(crate) struct GasProfiler {
+    frames: Vec<Option<String>>,
+    recorded: Gas,
+    profile: GasProfile,
+}
+
+impl GasProfiler {
+    /// Starts profiling a new execution, with `frame` as its outermost frame.
+    pub(crate) fn start(&mut self, frame: String) {
+        self.frames.clear();
+        self.frames.push(Some(frame));
+    }
+
+    /// Returns the number of frames in the current call stack.
+    pub(crate) fn depth(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Pushes a new innermost frame onto the current call stack.
+    pub(crate) fn push_frame(&mut self, frame: String) {
+        self.frames.push(Some(frame));
+    }
+
+    /// Replaces the frame at `depth` of the current call stack, dropping all frames above it.
+    pub(crate) fn set_frame(&mut self, depth: usize, frame: String) {
+        self.frames.resize(depth + 1, None);
+        self.frames[depth] = Some(frame);
+    }
+
+    /// Drops all frames above `depth` from the current call stack.
+    pub(crate) fn truncate(&mut self, depth: usize) {
+        self.frames.truncate(depth);
+    }
+
+    /// Returns the total gas recorded so far.
+    pub(crate) fn recorded(&self) -> Gas {
+        self.recorded
+    }
+
+    /// Attributes `gas` to the current call stack.
+    pub(crate) fn record(&mut self, gas: Gas) {
+        if gas == Gas::default() {
+            return;
+        }
+        self.recorded += gas;
+        let stack = self.frames.iter().flatten().join(";");
+        self.profile.record(stack, gas);
+    }
+
+    /// Returns the profile recorded so far, leaving an empty one in its place.
+    pub(crate) fn take_profile(&mut self) -> GasProfile {
+        mem::take(&mut self.profile)
+    }
+}
+
+/// Names of the functions of an instrumented module, keyed by their index in the module.
+#[derive(Debug)]
+pub(crate) struct FunctionNames {
+    names: BTreeMap<u32, String>,
+    gas_function_index: Option<u32>,
+}
+
+impl FunctionNames {
+    /// Collects the names of the functions of a preprocessed module from its exports and, where
+    /// present, from its name section.
+    ///
+    /// The stack height limiter replaces exported functions by thunks calling them, so the names
+    /// of exported thunks are given to the functions they call.
+    fn new(module: &Module, imported_function_count: u32, gas_function_index: Option<u32>) -> Self {
+        let mut function_names = FunctionNames {
+            names: BTreeMap::new(),
+            gas_function_index,
+        };
+        if let Some(export_section) = module.export_section() {
+            for export_entry in export_section.entries() {
+                if let Internal::Function(index) = export_entry.internal() {
+                    let index =
+                        thunk_target(module, *index, imported_function_count, gas_function_index)
+                            .unwrap_or(*index);
+                    function_names.insert(index, export_entry.field());
+                }
+            }
+        }
+        if let Some(names) = module
+            .names_section()
+            .and_then(|names_section| names_section.functions())
+        {
+            for (original_index, name) in names.names().iter() {
+                let index = function_names.preprocessed_index(original_index);
+                function_names.insert(index, name);
+            }
+        }
+        function_names
+    }
+
+    /// Returns the name of the function at `index`, falling back to its index in the module
+    /// before preprocessing.
+    fn get(&self, index: u32) -> String {
+        match self.names.get(&index) {
+            Some(name) => name.clone(),
+            None => format!("func[{}]", self.original_index(index)),
+        }
+    }
+
+    fn insert(&mut self, index: u32, name: &str) {
+        // Frames are separated by `;` in the folded stacks format.
+        self.names.insert(index, name.replace(';', ":"));
+    }
+
+    /// The name section is copied from the original module, before the `gas` host function was
+    /// imported and shifted the indices of all functions defined in the module.
+    fn preprocessed_index(&self, original_index: u32) -> u32 {
+        match self.gas_function_index {
+            Some(gas_function_index) if original_index >= gas_function_index => original_index + 1,
+            _ => original_index,
+        }
+    }
+
+    fn original_index(&self, index: u32) -> u32 {
+        match self.gas_function_index {
+            Some(gas_function_index) if index > gas_function_index => index - 1,
+            _ => index,
+        }
+    }
+}
+
+/// Returns the function called by the function at `index` if the latter is a thunk, i.e. it calls
+/// a single function and charges no gas.
+fn thunk_target(
+    module: &Module,
+    index: u32,
+    imported_function_count: u32,
+    gas_function_index: Option<u32>,
+) -> Option<u32> {
+    let defined_index = index.checked_sub(imported_function_count)?;
+    let func_body = module
+        .code_section()?
+        .bodies()
+        .get(defined_index as usize)?;
+    let mut callees =
+        func_body
+            .code()
+            .elements()
+            .iter()
+            .filter_map(|instruction| match instruction {
+                Instruction::Call(callee) => Some(*callee),
+                _ => None,
+            });
+    let callee = callees.next()?;
+    if callees.next().is_some() || Some(callee) == gas_function_index {
+        return None;
+    }
+    Some(callee)
+}
+
+/// The globals through which an instance of an instrumented module exposes its call stack.
+pub(crate) struct WasmFrames {
+    function: GlobalRef,
+    depth: GlobalRef,
+    function_names: FunctionNames,
+    base_depth: usize,
+}
+
+impl WasmFrames {
+    /// Returns the call stack globals of `instance`, whose outermost function is called at
+    /// `base_depth` of the profiler's call stack.
+    pub(crate) fn new(
+        instance: &ModuleRef,
+        function_names: FunctionNames,
+        base_depth: usize,
+    ) -> Option<Self> {
+        let global = |name: &str| {
+            instance
+                .export_by_name(name)
+                .and_then(|extern_val| extern_val.as_global().cloned())
+        };
+        Some(WasmFrames {
+            function: global(FUNCTION_GLOBAL_NAME)?,
+            depth: global(DEPTH_GLOBAL_NAME)?,
+            function_names,
+            base_depth,
+        })
+    }
+
+    /// Updates the profiler's call stack to the Wasm function currently executing.
+    pub(crate) fn sync(&self, gas_profiler: &mut GasProfiler) {
+        let depth = self.depth.get().try_into::<i32>().unwrap_or_default();
+        let function = self.function.get().try_into::<i32>().unwrap_or_default();
+        if depth <= 0 {
+            gas_profiler.truncate(self.base_depth);
+            return;
+        }
+        gas_profiler.set_frame(
+            self.base_depth + depth as usize - 1,
+            self.function_names.get(function as u32),
+        );
+    }
+}
+
+/// Instruments a preprocessed module to expose its call stack through two exported globals.
+///
+/// Only functions which charge gas are instrumented, which leaves out the thunks generated by the
+/// stack height limiter, so that they don't appear in the call stacks.
+pub(crate) fn instrument(module: Module) -> (Module, FunctionNames) {
+    let mut module = match module.parse_names() {
+        Ok(module) => module,
+        // Profiling doesn't depend on the names, so an invalid name section is ignored.
+        Err((_, module)) => module,
+    };
+
+    let mut imported_function_count = 0;
+    let mut imported_global_count = 0;
+    let mut gas_function_index = None;
+    if let Some(import_section) = module.import_section() {
+        for import_entry in import_section.entries() {
+            match import_entry.external() {
+                External::Function(_) => {
+                    if (import_entry.module(), import_entry.field()) == GAS_FUNCTION_IMPORT {
+                        gas_function_index = Some(imported_function_count);
+                    }
+                    imported_function_count += 1;
+                }
+                External::Global(_) => imported_global_count += 1,
+                External::Table(_) | External::Memory(_) => (),
+            }
+        }
+    }
+    let function_names = FunctionNames::new(&module, imported_function_count, gas_function_index);
+
+    let param_counts: Vec<u32> = {
+        let types: Vec<&Type> = module
+            .type_section()
+            .map(|type_section| type_section.types().iter().collect())
+            .unwrap_or_default();
+        module
+            .function_section()
+            .map(|function_section| {
+                function_section
+                    .entries()
+                    .iter()
+                    .map(|func| match types.get(func.type_ref() as usize) {
+                        Some(Type::Function(function_type)) => function_type.params().len() as u32,
+                        None => 0,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+
+    let (function_global, depth_global) = add_globals(&mut module, imported_global_count);
+
+    if let Some(code_section) = module.code_section_mut() {
+        for (defined_index, func_body) in code_section.bodies_mut().iter_mut().enumerate() {
+            let instructions = func_body.code().elements();
+            let charges_gas = match gas_function_index {
+                Some(gas_function_index) => instructions
+                    .iter()
+                    .any(|instruction| *instruction == Instruction::Call(gas_function_index)),
+                None => true,
+            };
+            if !charges_gas {
+                continue;
+            }
+
+            let function_index = (imported_function_count + defined_index as u32) as i32;
+            let depth_local = param_counts.get(defined_index).copied().unwrap_or_default()
+                + func_body
+                    .locals()
+                    .iter()
+                    .map(|local| local.count())
+                    .sum::<u32>();
+            func_body.locals_mut().push(Local::new(1, ValueType::I32));
+
+            let restore_frame = [
+                Instruction::GetLocal(depth_local),
+                Instruction::SetGlobal(depth_global),
+                Instruction::I32Const(function_index),
+                Instruction::SetGlobal(function_global),
+            ];
+            let mut instrumented = vec![
+                Instruction::GetGlobal(depth_global),
+                Instruction::I32Const(1),
+                Instruction::I32Add,
+                Instruction::TeeLocal(depth_local),
+                Instruction::SetGlobal(depth_global),
+                Instruction::I32Const(function_index),
+                Instruction::SetGlobal(function_global),
+            ];
+            for instruction in func_body.code_mut().elements_mut().drain(..) {
+                let returns_from_wasm_call = match instruction {
+                    Instruction::Call(index) => index >= imported_function_count,
+                    Instruction::CallIndirect(..) => true,
+                    _ => false,
+                };
+                instrumented.push(instruction);
+                if returns_from_wasm_call {
+                    instrumented.extend_from_slice(&restore_frame);
+                }
+            }
+            *func_body.code_mut().elements_mut() = instrumented;
+        }
+    }
+
+    (module, function_names)
+}
+
+/// Adds and exports the mutable `i32` globals holding the index and the call depth of the
+/// executing function, returning their indices.
+fn add_globals(module: &mut Module, imported_global_count: u32) -> (u32, u32) {
+    let new_global = || {
+        GlobalEntry::new(
+            GlobalType::new(ValueType::I32, true),
+            InitExpr::new(vec![Instruction::I32Const(0), Instruction::End]),
+        )
+    };
+    let defined_global_count = match module.global_section_mut() {
+        Some(global_section) => {
+            let count = global_section.entries().len() as u32;
+            global_section.entries_mut().push(new_global());
+            global_section.entries_mut().push(new_global());
+            count
+        }
+        None => {
+            module
+                .sections_mut()
+                .push(Section::Global(GlobalSection::with_entries(vec![
+                    new_global(),
+                    new_global(),
+                ])));
+            0
+        }
+    };
+    let function_global = imported_global_count + defined_global_count;
+    let depth_global = function_global + 1;
+
+    let export_entries = vec![
+        ExportEntry::new(
+            FUNCTION_GLOBAL_NAME.to_string(),
+            Internal::Global(function_global),
+        ),
+        ExportEntry::new(
+            DEPTH_GLOBAL_NAME.to_string(),
+            Internal::Global(depth_global),
+        ),
+    ];
+    match module.export_section_mut() {
+        Some(export_section) => export_section.entries_mut().extend(export_entries),
+        None => module
+            .sections_mut()
+            .push(Section::Export(ExportSection::with_entries(export_entries))),
+    }
+
+    (function_global, depth_global)
+}
+
+#[cfg(test)]
+mod tests {
+    use parity_wasm::{
+        builder,
+        elements::{Instructions, Internal},
+    };
+
+    use super::*;
+
+    /// Builds a module importing `gas` like a preprocessed one, whose exported function calls
+    /// another one.
+    fn make_module() -> Module {
+        builder::module()
+            .import()
+            .module("env")
+            .field("gas")
+            .external()
+            .func(0)
+            .build()
+            .function()
+            .signature()
+            .build()
+            .body()
+            .with_instructions(Instructions::new(vec![
+                Instruction::I32Const(10),
+                Instruction::Call(0),
+                Instruction::Call(2),
+                Instruction::End,
+            ]))
+            .build()
+            .build()
+            .function()
+            .signature()
+            .build()
+            .body()
+            .with_instructions(Instructions::new(vec![
+                Instruction::I32Const(5),
+                Instruction::Call(0),
+                Instruction::End,
+            ]))
+            .build()
+            .build()
+            .export()
+            .field("call")
+            .internal()
+            .func(1)
+            .build()
+            .build()
+    }
+
+    #[test]
+    fn should_instrument_functions_charging_gas() {
+        let (module, function_names) = instrument(make_module());
+
+        let exported_globals: Vec<(&str, u32)> = module
+            .export_section()
+            .unwrap()
+            .entries()
+            .iter()
+            .filter_map(|export_entry| match export_entry.internal() {
+                Internal::Global(index) => Some((export_entry.field(), *index)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            exported_globals,
+            vec![(FUNCTION_GLOBAL_NAME, 0), (DEPTH_GLOBAL_NAME, 1)]
+        );
+
+        let caller = &module.code_section().unwrap().bodies()[0];
+        assert_eq!(caller.locals(), &[Local::new(1, ValueType::I32)]);
+        assert_eq!(
+            caller.code().elements(),
+            &[
+                Instruction::GetGlobal(1),
+                Instruction::I32Const(1),
+                Instruction::I32Add,
+                Instruction::TeeLocal(0),
+                Instruction::SetGlobal(1),
+                Instruction::I32Const(1),
+                Instruction::SetGlobal(0),
+                Instruction::I32Const(10),
+                Instruction::Call(0),
+                Instruction::Call(2),
+                Instruction::GetLocal(0),
+                Instruction::SetGlobal(1),
+                Instruction::I32Const(1),
+                Instruction::SetGlobal(0),
+                Instruction::End,
+            ]
+        );
+
+        assert_eq!(function_names.get(1), "call");
+        assert_eq!(function_names.get(2), "func[1]");
+    }
+
+    #[test]
+    fn should_attribute_gas_to_call_stacks() {
+        let mut gas_profiler = GasProfiler::default();
+        gas_profiler.start("session".to_string());
+        gas_profiler.set_frame(1, "call".to_string());
+        gas_profiler.record(Gas::new(10.into()));
+        // A frame of a function which charged no gas yet is left out of the stack.
+        gas_profiler.set_frame(3, "inner".to_string());
+        gas_profiler.push_frame("host_function_write".to_string());
+        gas_profiler.record(Gas::new(5.into()));
+        gas_profiler.truncate(2);
+        gas_profiler.record(Gas::new(1.into()));
+        gas_profiler.record(Gas::default());
+
+        let gas_profile = gas_profiler.take_profile();
+        assert_eq!(gas_profiler.recorded(), Gas::new(16.into()));
+        assert_eq!(gas_profile.total(), Gas::new(16.into()));
+
+        let mut folded = Vec::new();
+        gas_profile.write_folded(&mut folded).unwrap();
+        assert_eq!(
+            String::from_utf8(folded).unwrap(),
+            "session;call 11\nsession;call;inner;host_function_write 5\n"
+        );
+    }
+}
@@ -1,7 +1,9 @@
 //! This module contains executor state of the WASM code.
 mod args;
 mod auction_internal;
+pub mod execution_trace;
 mod externals;
+pub mod gas_profiler;
 mod handle_payment_internal;
 mod host_function_flag;
 mod mint_internal;
@@ -10,10 +12,13 @@ pub mod stack;
 mod standard_payment_internal;
 
 use std::{
+    cell::RefCell,
     cmp,
     collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     convert::TryFrom,
     iter::IntoIterator,
+    rc::Rc,
+    sync::Arc,
 };
 
 use itertools::Itertools;
@@ -47,13 +52,19 @@ use crate::{
         engine_state::EngineConfig,
         execution::{self, Error},
         resolvers::{create_module_resolver, memory_resolver::MemoryResolver},
-        runtime::{host_function_flag::HostFunctionFlag, scoped_instrumenter::ScopedInstrumenter},
+        runtime::{
+            execution_trace::ExecutionTrace,
+            gas_profiler::{GasProfiler, WasmFrames},
+            host_function_flag::HostFunctionFlag,
+            scoped_instrumenter::ScopedInstrumenter,
+        },
         runtime_context::{self, RuntimeContext},
         Address,
     },
     shared::{
         host_function_costs::{Cost, HostFunction},
         wasm_config::WasmConfig,
+        wasm_module_cache::WasmModuleCache,
     },
     storage::global_state::StateReader,
 };
@@ -62,12 +73,16 @@ pub use stack::{RuntimeStack, RuntimeStackFrame, RuntimeStackOverflow};
 /// Represents the runtime properties of a WASM execution.
 pub struct Runtime<'a, R> {
     config: EngineConfig,
+    wasm_module_cache: Arc<WasmModuleCache>,
     memory: MemoryRef,
     module: Module,
     host_buffer: Option<CLValue>,
     context: RuntimeContext<'a, R>,
     stack: RuntimeStack,
     host_function_flag: HostFunctionFlag,
+    gas_profiler: Option<Rc<RefCell<GasProfiler>>>,
+    wasm_frames: Option<WasmFrames>,
+    execution_trace: Option<Rc<RefCell<ExecutionTrace>>>,
 }
 
 /// Creates an WASM module instance and a memory instance.
@@ -97,6 +112,30 @@ pub fn instance_and_memory(
     Ok((instance, memory))
 }
 
+/// Creates a WASM module instance and a memory instance like [`instance_and_memory`].
+///
+/// If a gas profiler is given, the module is instrumented to expose its call stack, and the
+/// returned [`WasmFrames`] places it on top of the current call stack of the profiler.
+pub(crate) fn profiled_instance_and_memory(
+    parity_module: Module,
+    protocol_version: ProtocolVersion,
+    wasm_config: &WasmConfig,
+    gas_profiler: Option<&Rc<RefCell<GasProfiler>>>,
+) -> Result<(ModuleRef, MemoryRef, Option<WasmFrames>), Error> {
+    let gas_profiler = match gas_profiler {
+        Some(gas_profiler) => gas_profiler,
+        None => {
+            let (instance, memory) =
+                instance_and_memory(parity_module, protocol_version, wasm_config)?;
+            return Ok((instance, memory, None));
+        }
+    };
+    let (parity_module, function_names) = gas_profiler::instrument(parity_module);
+    let (instance, memory) = instance_and_memory(parity_module, protocol_version, wasm_config)?;
+    let wasm_frames = WasmFrames::new(&instance, function_names, gas_profiler.borrow().depth());
+    Ok((instance, memory, wasm_frames))
+}
+
 /// Turns `key` into a `([u8; 32], AccessRights)` tuple.
 /// Returns None if `key` is not `Key::URef` as it wouldn't have `AccessRights`
 /// associated with it. Helper function for creating `named_keys` associating
@@ -984,6 +1023,7 @@ where
     /// Creates a new runtime instance.
     pub(crate) fn new(
         config: EngineConfig,
+        wasm_module_cache: Arc<WasmModuleCache>,
         memory: MemoryRef,
         module: Module,
         context: RuntimeContext<'a, R>,
@@ -992,27 +1032,56 @@ where
         Self::check_preconditions(&stack);
         Runtime {
             config,
+            wasm_module_cache,
             memory,
             module,
             host_buffer: None,
             context,
             stack,
             host_function_flag: HostFunctionFlag::default(),
+            gas_profiler: None,
+            wasm_frames: None,
+            execution_trace: None,
         }
     }
 
-    /// Creates a new runtime instance by cloning the config, memory, module and host function flag
-    /// from `self`.
+    /// Attributes the gas charged by the host functions called from the module instance to the
+    /// call stacks of `gas_profiler`, using `wasm_frames` to locate them in the instance.
+    pub(crate) fn with_gas_profiler(
+        mut self,
+        gas_profiler: Option<Rc<RefCell<GasProfiler>>>,
+        wasm_frames: Option<WasmFrames>,
+    ) -> Self {
+        self.gas_profiler = gas_profiler;
+        self.wasm_frames = wasm_frames;
+        self
+    }
+
+    /// Records the host functions called from the module instance in `execution_trace`.
+    pub(crate) fn with_execution_trace(
+        mut self,
+        execution_trace: Option<Rc<RefCell<ExecutionTrace>>>,
+    ) -> Self {
+        self.execution_trace = execution_trace;
+        self
+    }
+
+    /// Creates a new runtime instance by cloning the config, Wasm module cache, memory, module, host
+    /// function flag, gas profiler and execution trace from `self`.
     fn new_from_self(&self, context: RuntimeContext<'a, R>, stack: RuntimeStack) -> Self {
         Self::check_preconditions(&stack);
         Runtime {
             config: self.config,
+            wasm_module_cache: Arc::clone(&self.wasm_module_cache),
             memory: self.memory.clone(),
             module: self.module.clone(),
             host_buffer: None,
             context,
             stack,
             host_function_flag: self.host_function_flag.clone(),
+            gas_profiler: self.gas_profiler.clone(),
+            wasm_frames: None,
+            execution_trace: self.execution_trace.clone(),
         }
     }
 
@@ -2198,13 +2267,30 @@ where
                 None => return Err(Error::KeyNotFound(key)),
             };
 
-            parity_wasm::deserialize_buffer(contract_wasm.bytes())?
+            self.wasm_module_cache.get_or_insert_with(
+                contract.contract_wasm_hash(),
+                self.config.wasm_config(),
+                || parity_wasm::deserialize_buffer(contract_wasm.bytes()).map_err(Error::from),
+            )?
         };
 
         let entry_point_name = entry_point.name();
 
-        let (instance, memory) =
-            instance_and_memory(module.clone(), protocol_version, self.config.wasm_config())?;
+        if let Some(gas_profiler) = &self.gas_profiler {
+            // The frame is dropped by the host function which called this contract once it
+            // returns.
+            gas_profiler.borrow_mut().push_frame(format!(
+                "{}::{}",
+                contract_hash.to_formatted_string(),
+                entry_point_name
+            ));
+        }
+        let (instance, memory, wasm_frames) = profiled_instance_and_memory(
+            module.clone(),
+            protocol_version,
+            self.config.wasm_config(),
+            self.gas_profiler.as_ref(),
+        )?;
 
         let access_rights = {
             let mut keys: Vec<Key> = named_keys.values().cloned().collect();
@@ -2252,7 +2338,16 @@ where
 
         stack.push(call_stack_element)?;
 
-        let mut runtime = Runtime::new(config, memory, module, context, stack);
+        let mut runtime = Runtime::new(
+            config,
+            Arc::clone(&self.wasm_module_cache),
+            memory,
+            module,
+            context,
+            stack,
+        )
+        .with_gas_profiler(self.gas_profiler.clone(), wasm_frames)
+        .with_execution_trace(self.execution_trace.clone());
 
         let result = instance.invoke_export(entry_point_name, &[], &mut runtime);
 
@@ -565,6 +565,16 @@ where
         self.tracking_copy.borrow().execution_journal()
     }
 
+    /// Returns the number of transforms recorded in the `ExecutionJournal`.
+    pub(crate) fn execution_journal_len(&self) -> usize {
+        self.tracking_copy.borrow().execution_journal_len()
+    }
+
+    /// Returns the transforms recorded in the `ExecutionJournal` from position `start` on.
+    pub(crate) fn execution_journal_since(&self, start: usize) -> ExecutionJournal {
+        self.tracking_copy.borrow().execution_journal_since(start)
+    }
+
     /// Returns list of transfers.
     pub fn transfers(&self) -> &Vec<TransferAddr> {
         &self.transfers
@@ -5,9 +5,11 @@ pub(self) mod meter;
 mod tests;
 
 use std::{
+    cell::RefCell,
     collections::{BTreeSet, HashMap, HashSet, VecDeque},
     convert::{From, TryInto},
     iter,
+    rc::Rc,
 };
 
 use linked_hash_map::LinkedHashMap;
@@ -23,7 +25,10 @@ pub use self::ext::TrackingCopyExt;
 use self::meter::{heap_meter::HeapSize, Meter};
 use super::engine_state::EngineConfig;
 use crate::{
-    core::{engine_state::execution_effect::ExecutionEffect, runtime_context::dictionary},
+    core::{
+        engine_state::{execution_effect::ExecutionEffect, read_set::ReadSet},
+        runtime_context::dictionary,
+    },
     shared::{
         execution_journal::ExecutionJournal,
         newtypes::CorrelationId,
@@ -212,6 +217,8 @@ pub struct TrackingCopy<R> {
     reader: R,
     cache: TrackingCopyCache<HeapSize>,
     journal: ExecutionJournal,
+    /// Records the accesses made through this tracking copy and its forks, if set.
+    read_set: Option<Rc<RefCell<ReadSet>>>,
 }
 
 #[derive(Debug)]
@@ -244,9 +251,16 @@ impl<R: StateReader<Key, StoredValue>> TrackingCopy<R> {
              * be fraction of wasm memory
              * limit? */
             journal: Default::default(),
+            read_set: None,
         }
     }
 
+    /// Records all subsequent accesses made through this tracking copy and its forks in
+    /// `read_set`.
+    pub fn record_reads_into(&mut self, read_set: Rc<RefCell<ReadSet>>) {
+        self.read_set = Some(read_set);
+    }
+
     pub fn reader(&self) -> &R {
         &self.reader
     }
@@ -264,13 +278,26 @@ impl<R: StateReader<Key, StoredValue>> TrackingCopy<R> {
     /// forking, however we recognize this is sub-optimal and will revisit
     /// in the future.
     pub fn fork(&self) -> TrackingCopy<&TrackingCopy<R>> {
-        TrackingCopy::new(self)
+        let mut fork = TrackingCopy::new(self);
+        fork.read_set = self.read_set.clone();
+        fork
     }
 
     pub fn get(
         &mut self,
         correlation_id: CorrelationId,
         key: &Key,
+    ) -> Result<Option<StoredValue>, R::Error> {
+        if let Some(read_set) = &self.read_set {
+            read_set.borrow_mut().record_value(key.normalize());
+        }
+        self.get_unrecorded(correlation_id, key)
+    }
+
+    fn get_unrecorded(
+        &mut self,
+        correlation_id: CorrelationId,
+        key: &Key,
     ) -> Result<Option<StoredValue>, R::Error> {
         if let Some(value) = self.cache.get(key) {
             return Ok(Some(value.to_owned()));
@@ -288,6 +315,9 @@ impl<R: StateReader<Key, StoredValue>> TrackingCopy<R> {
         correlation_id: CorrelationId,
         key_tag: &KeyTag,
     ) -> Result<BTreeSet<Key>, R::Error> {
+        if let Some(read_set) = &self.read_set {
+            read_set.borrow_mut().record_prefix(&[*key_tag as u8]);
+        }
         let mut ret: BTreeSet<Key> = BTreeSet::new();
         match self.cache.get_key_tag_reads_cached(key_tag) {
             Some(keys) => ret.extend(keys),
@@ -337,7 +367,10 @@ impl<R: StateReader<Key, StoredValue>> TrackingCopy<R> {
         value: StoredValue,
     ) -> Result<AddResult, R::Error> {
         let normalized_key = key.normalize();
-        let current_value = match self.get(correlation_id, &normalized_key)? {
+        if let Some(read_set) = &self.read_set {
+            read_set.borrow_mut().record_addition(normalized_key);
+        }
+        let current_value = match self.get_unrecorded(correlation_id, &normalized_key)? {
             None => return Ok(AddResult::KeyNotFound(normalized_key)),
             Some(current_value) => current_value,
         };
@@ -410,6 +443,16 @@ impl<R: StateReader<Key, StoredValue>> TrackingCopy<R> {
         self.journal.clone()
     }
 
+    /// Returns the number of transforms recorded in the execution journal.
+    pub fn execution_journal_len(&self) -> usize {
+        self.journal.len()
+    }
+
+    /// Returns the transforms recorded in the execution journal from position `start` on.
+    pub fn execution_journal_since(&self, start: usize) -> ExecutionJournal {
+        self.journal.since(start)
+    }
+
     /// Calling `query()` avoids calling into `self.cache`, so this will not return any values
     /// written or mutated in this `TrackingCopy` via previous calls to `write()` or `add()`, since
     /// these updates are only held in `self.cache`.
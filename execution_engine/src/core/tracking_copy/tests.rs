@@ -1,4 +1,8 @@
-use std::{cell::Cell, iter, rc::Rc};
+use std::{
+    cell::{Cell, RefCell},
+    iter,
+    rc::Rc,
+};
 
 use assert_matches::assert_matches;
 use proptest::prelude::*;
@@ -16,7 +20,11 @@ use super::{
     meter::count_meter::Count, AddResult, TrackingCopy, TrackingCopyCache, TrackingCopyQueryResult,
 };
 use crate::{
-    core::{engine_state::EngineConfig, runtime_context::dictionary, ValidationError},
+    core::{
+        engine_state::{read_set::ReadSet, EngineConfig},
+        runtime_context::dictionary,
+        ValidationError,
+    },
     shared::{execution_journal::ExecutionJournal, newtypes::CorrelationId, transform::Transform},
     storage::{
         global_state::{in_memory::InMemoryGlobalState, StateProvider, StateReader},
@@ -1143,3 +1151,95 @@ fn query_with_large_depth_with_urefs_should_fail() {
         result
     );
 }
+
+#[test]
+fn tracking_copy_should_record_reads_into_read_set() {
+    let correlation_id = CorrelationId::new();
+    let counter = Rc::new(Cell::new(0));
+    let db = CountingDb::new(counter);
+    let read_set = Rc::new(RefCell::new(ReadSet::default()));
+    let mut tc = TrackingCopy::new(db);
+    tc.record_reads_into(Rc::clone(&read_set));
+    let read_key = Key::Hash([0u8; 32]);
+    let added_key = Key::Hash([1u8; 32]);
+    let added_then_read_key = Key::Hash([2u8; 32]);
+    let forked_read_key = Key::Hash([3u8; 32]);
+    let value = StoredValue::CLValue(CLValue::from_t(3_i32).unwrap());
+
+    let _ = tc.read(correlation_id, &read_key);
+    let _ = tc.add(correlation_id, added_key, value.clone());
+    let _ = tc.add(correlation_id, added_then_read_key, value);
+    let _ = tc.read(correlation_id, &added_then_read_key);
+    let _ = tc.get_keys(correlation_id, &KeyTag::URef);
+    let _ = tc.fork().read(correlation_id, &forked_read_key);
+
+    let read_set = read_set.borrow();
+    assert_eq!(
+        read_set.values().iter().copied().collect::<Vec<_>>(),
+        vec![read_key, added_then_read_key, forked_read_key]
+    );
+    assert_eq!(
+        read_set.additions().iter().copied().collect::<Vec<_>>(),
+        vec![added_key]
+    );
+    assert_eq!(
+        read_set.prefixes().iter().cloned().collect::<Vec<_>>(),
+        vec![vec![KeyTag::URef as u8]]
+    );
+}
+
+#[test]
+fn read_set_should_compare_outcomes_of_recorded_accesses() {
+    let correlation_id = CorrelationId::new();
+    let read_key = Key::Hash([0u8; 32]);
+    let added_key = Key::Hash([1u8; 32]);
+    let account_hash = AccountHash::new([2; 32]);
+    let account_value = StoredValue::Account(Account::create(
+        account_hash,
+        NamedKeys::default(),
+        URef::new([42; 32], AccessRights::READ_ADD_WRITE),
+    ));
+    let u512 = |value: u64| StoredValue::CLValue(CLValue::from_t(U512::from(value)).unwrap());
+
+    let (original_state, original_root_hash) = InMemoryGlobalState::from_pairs(
+        correlation_id,
+        &[(read_key, u512(1)), (added_key, u512(5))],
+    )
+    .unwrap();
+    let (current_state, current_root_hash) = InMemoryGlobalState::from_pairs(
+        correlation_id,
+        &[
+            (read_key, u512(1)),
+            (added_key, u512(9)),
+            (Key::Account(account_hash), account_value),
+        ],
+    )
+    .unwrap();
+    let original = original_state
+        .checkout(original_root_hash)
+        .unwrap()
+        .unwrap();
+    let current = current_state.checkout(current_root_hash).unwrap().unwrap();
+
+    // Only the value of `added_key` changed, which doesn't affect adding to it.
+    let mut read_set = ReadSet::default();
+    read_set.record_value(read_key);
+    read_set.record_addition(added_key);
+    assert!(read_set
+        .has_same_outcome(correlation_id, &original, &current)
+        .unwrap());
+
+    // Reading the changed value is a conflict.
+    let mut read_set = ReadSet::default();
+    read_set.record_value(added_key);
+    assert!(!read_set
+        .has_same_outcome(correlation_id, &original, &current)
+        .unwrap());
+
+    // Listing accounts is a conflict, as an account was created.
+    let mut read_set = ReadSet::default();
+    read_set.record_prefix(&[KeyTag::Account as u8]);
+    assert!(!read_set
+        .has_same_outcome(correlation_id, &original, &current)
+        .unwrap());
+}
@@ -12,4 +12,5 @@ pub mod transform;
 pub mod utils;
 pub mod wasm;
 pub mod wasm_config;
+pub mod wasm_module_cache;
 pub mod wasm_prep;
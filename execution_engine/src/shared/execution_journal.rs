@@ -28,6 +28,11 @@ impl ExecutionJournal {
         self.0.len()
     }
 
+    /// Returns a journal of the transforms recorded from position `start` on.
+    pub fn since(&self, start: usize) -> ExecutionJournal {
+        ExecutionJournal(self.0.get(start..).unwrap_or_default().to_vec())
+    }
+
     /// Adds a transform to the journal.
     pub fn push(&mut self, entry: (Key, Transform)) {
         self.0.push(entry)
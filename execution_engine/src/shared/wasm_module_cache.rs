@@ -0,0 +1,275 @@
+//! A bounded cache of deserialized Wasm modules of stored contracts.
+//!
+//! Calling a stored contract requires turning its `ContractWasm` bytes into a
+//! [`Module`](parity_wasm::elements::Module), which dominates the cost of calling popular
+//! contracts. The cache keeps the most recently used modules, keyed by the contract wasm hash and
+//! the hash of the [`WasmConfig`] in force, so that a change of the Wasm costs never yields a
+//! module prepared under a different config.
+//!
+//! Only `parity_wasm` modules are cached. Instantiated `wasmi` modules are reference counted
+//! without synchronization and carry per-execution state such as memory and globals, so they can
+//! neither be shared between deploys nor across threads.
+use std::{
+    fmt::{self, Debug, Formatter},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex, MutexGuard,
+    },
+};
+
+use linked_hash_map::LinkedHashMap;
+use parity_wasm::elements::Module;
+
+use casper_hashing::Digest;
+use casper_types::{
+    bytesrepr::{self, ToBytes},
+    ContractWasmHash,
+};
+
+use crate::shared::wasm_config::WasmConfig;
+
+/// Default maximum number of modules held by the cache.
+pub const DEFAULT_MAX_WASM_MODULE_CACHE_ENTRIES: usize = 256;
+
+type CacheKey = (ContractWasmHash, Digest);
+
+struct Inner {
+    capacity: usize,
+    modules: LinkedHashMap<CacheKey, Module>,
+}
+
+/// A thread-safe, least-recently-used cache of deserialized Wasm modules.
+///
+/// A capacity of `0` disables the cache.
+pub struct WasmModuleCache {
+    inner: Mutex<Inner>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl WasmModuleCache {
+    /// Creates a new, empty cache holding at most `capacity` modules.
+    pub fn new(capacity: usize) -> Self {
+        WasmModuleCache {
+            inner: Mutex::new(Inner {
+                capacity,
+                modules: LinkedHashMap::new(),
+            }),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// Returns the module of the contract wasm with the given hash, prepared under `wasm_config`.
+    ///
+    /// On a miss, the module is created by `prepare_module` and retained, evicting the least
+    /// recently used module if the cache is full.
+    pub fn get_or_insert_with<F, E>(
+        &self,
+        contract_wasm_hash: ContractWasmHash,
+        wasm_config: &WasmConfig,
+        prepare_module: F,
+    ) -> Result<Module, E>
+    where
+        F: FnOnce() -> Result<Module, E>,
+        E: From<bytesrepr::Error>,
+    {
+        if self.capacity() == 0 {
+            return prepare_module();
+        }
+
+        let key = (contract_wasm_hash, Digest::hash(wasm_config.to_bytes()?));
+        if let Some(module) = self.lock().modules.get_refresh(&key) {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return Ok(module.clone());
+        }
+        self.misses.fetch_add(1, Ordering::Relaxed);
+
+        // The lock is not held while preparing the module, so a concurrent miss on the same key
+        // may prepare it twice; both results are identical.
+        let module = prepare_module()?;
+        let mut inner = self.lock();
+        inner.modules.insert(key, module.clone());
+        while inner.modules.len() > inner.capacity {
+            inner.modules.pop_front();
+        }
+        Ok(module)
+    }
+
+    /// Removes all modules from the cache and sets its new capacity.
+    pub fn reset(&self, capacity: usize) {
+        let mut inner = self.lock();
+        inner.capacity = capacity;
+        inner.modules.clear();
+    }
+
+    /// Returns the maximum number of modules held by the cache.
+    pub fn capacity(&self) -> usize {
+        self.lock().capacity
+    }
+
+    /// Returns the number of modules currently held by the cache.
+    pub fn len(&self) -> usize {
+        self.lock().modules.len()
+    }
+
+    /// Returns `true` if the cache holds no modules.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the total number of lookups served from the cache.
+    pub fn hits(&self) -> u64 {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    /// Returns the total number of lookups which required preparing the module.
+    pub fn misses(&self) -> u64 {
+        self.misses.load(Ordering::Relaxed)
+    }
+
+    fn lock(&self) -> MutexGuard<Inner> {
+        // The cache holds no invariants which a panicking thread could break.
+        self.inner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl Default for WasmModuleCache {
+    fn default() -> Self {
+        WasmModuleCache::new(DEFAULT_MAX_WASM_MODULE_CACHE_ENTRIES)
+    }
+}
+
+impl Debug for WasmModuleCache {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("WasmModuleCache")
+            .field("capacity", &self.capacity())
+            .field("len", &self.len())
+            .field("hits", &self.hits())
+            .field("misses", &self.misses())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use parity_wasm::builder;
+
+    use super::*;
+    use crate::shared::opcode_costs::OpcodeCosts;
+
+    fn prepare(calls: &mut u32) -> Result<Module, bytesrepr::Error> {
+        *calls += 1;
+        Ok(builder::module().build())
+    }
+
+    #[test]
+    fn should_serve_repeated_lookups_from_cache() {
+        let cache = WasmModuleCache::new(2);
+        let wasm_config = WasmConfig::default();
+        let hash = ContractWasmHash::new([1; 32]);
+        let mut calls = 0;
+
+        cache
+            .get_or_insert_with(hash, &wasm_config, || prepare(&mut calls))
+            .unwrap();
+        cache
+            .get_or_insert_with(hash, &wasm_config, || prepare(&mut calls))
+            .unwrap();
+
+        assert_eq!(calls, 1);
+        assert_eq!(cache.hits(), 1);
+        assert_eq!(cache.misses(), 1);
+    }
+
+    #[test]
+    fn should_key_modules_by_wasm_config() {
+        let cache = WasmModuleCache::new(2);
+        let wasm_config = WasmConfig::default();
+        let other_wasm_config = WasmConfig::new(
+            wasm_config.max_memory + 1,
+            wasm_config.max_stack_height,
+            OpcodeCosts::default(),
+            Default::default(),
+            Default::default(),
+        );
+        let hash = ContractWasmHash::new([1; 32]);
+        let mut calls = 0;
+
+        cache
+            .get_or_insert_with(hash, &wasm_config, || prepare(&mut calls))
+            .unwrap();
+        cache
+            .get_or_insert_with(hash, &other_wasm_config, || prepare(&mut calls))
+            .unwrap();
+
+        assert_eq!(calls, 2);
+        assert_eq!(cache.misses(), 2);
+    }
+
+    #[test]
+    fn should_evict_least_recently_used_module() {
+        let cache = WasmModuleCache::new(2);
+        let wasm_config = WasmConfig::default();
+        let first = ContractWasmHash::new([1; 32]);
+        let second = ContractWasmHash::new([2; 32]);
+        let third = ContractWasmHash::new([3; 32]);
+        let mut calls = 0;
+
+        for hash in [first, second, first, third].iter() {
+            cache
+                .get_or_insert_with(*hash, &wasm_config, || prepare(&mut calls))
+                .unwrap();
+        }
+        assert_eq!(cache.len(), 2);
+        assert_eq!(calls, 3);
+
+        // `second` was the least recently used module when `third` was inserted.
+        cache
+            .get_or_insert_with(first, &wasm_config, || prepare(&mut calls))
+            .unwrap();
+        assert_eq!(calls, 3);
+        cache
+            .get_or_insert_with(second, &wasm_config, || prepare(&mut calls))
+            .unwrap();
+        assert_eq!(calls, 4);
+    }
+
+    #[test]
+    fn should_not_retain_modules_when_disabled() {
+        let cache = WasmModuleCache::new(0);
+        let wasm_config = WasmConfig::default();
+        let hash = ContractWasmHash::new([1; 32]);
+        let mut calls = 0;
+
+        for _ in 0..2 {
+            cache
+                .get_or_insert_with(hash, &wasm_config, || prepare(&mut calls))
+                .unwrap();
+        }
+
+        assert_eq!(calls, 2);
+        assert!(cache.is_empty());
+        assert_eq!(cache.hits(), 0);
+    }
+
+    #[test]
+    fn should_clear_modules_on_reset() {
+        let cache = WasmModuleCache::new(2);
+        let wasm_config = WasmConfig::default();
+        let mut calls = 0;
+
+        cache
+            .get_or_insert_with(ContractWasmHash::new([1; 32]), &wasm_config, || {
+                prepare(&mut calls)
+            })
+            .unwrap();
+        cache.reset(1);
+
+        assert!(cache.is_empty());
+        assert_eq!(cache.capacity(), 1);
+    }
+}
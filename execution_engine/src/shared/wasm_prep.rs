@@ -60,6 +60,11 @@ impl Preprocessor {
         Self { wasm_config }
     }
 
+    /// Returns the Wasm config the preprocessor applies.
+    pub fn wasm_config(&self) -> &WasmConfig {
+        &self.wasm_config
+    }
+
     /// Preprocesses Wasm bytes and returns a module.
     ///
     /// This process consists of a few steps:
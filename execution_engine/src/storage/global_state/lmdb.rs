@@ -1,7 +1,15 @@
-use std::{ops::Deref, sync::Arc};
+use std::{
+    fs, io,
+    ops::Deref,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use lmdb::{Cursor, Database, Environment, EnvironmentFlags};
+use tracing::warn;
 
 use casper_hashing::Digest;
-use casper_types::{Key, StoredValue};
+use casper_types::{bytesrepr::FromBytes, Key, StoredValue};
 
 use crate::{
     shared::{additive_map::AdditiveMap, newtypes::CorrelationId, transform::Transform},
@@ -9,12 +17,13 @@ use crate::{
         error,
         global_state::{commit, StateProvider, StateReader},
         store::Store,
-        transaction_source::{lmdb::LmdbEnvironment, Transaction, TransactionSource},
+        transaction_source::{lmdb::LmdbEnvironment, Readable, Transaction, TransactionSource},
         trie::{merkle_proof::TrieMerkleProof, operations::create_hashed_empty_trie, Trie},
         trie_store::{
             lmdb::LmdbTrieStore,
             operations::{
-                keys_with_prefix, missing_trie_keys, put_trie, read, read_with_proof, ReadResult,
+                keys_with_prefix, mark_reachable_tries, missing_trie_keys, put_trie, read,
+                read_with_proof, ReadResult,
             },
         },
     },
@@ -41,6 +50,99 @@ pub struct LmdbGlobalStateView {
     pub(crate) root_hash: Digest,
 }
 
+/// Filename of the scratch LMDB environment in which trie pruning marks the reachable tries.
+const TRIE_PRUNING_MARKS_FILENAME: &str = "trie_pruning_marks.lmdb";
+
+/// The progress of pruning unreachable tries from the trie store, carried over between the batches
+/// run by [`LmdbGlobalState::prune_tries`].
+///
+/// The keys of the tries marked as reachable are written to a scratch LMDB environment rather than
+/// held in memory, since global state can hold more tries than fit in memory.  The scratch
+/// environment is deleted when the progress is dropped.
+#[derive(Debug)]
+pub struct TriePruningProgress {
+    /// The scratch environment holding the marks.
+    marks_environment: Environment,
+    /// The database of the keys of all tries marked as reachable so far.
+    marks: Database,
+    /// The path of the scratch environment.
+    marks_path: PathBuf,
+    /// The keys of the tries still to be marked along with their descendants.
+    trie_keys_to_mark: Vec<Digest>,
+    /// The number of tries marked as reachable so far.
+    marked_count: usize,
+    /// The key of the next trie to be checked by the sweep, or `None` if the sweep hasn't started.
+    next_key_to_sweep: Option<Digest>,
+}
+
+impl TriePruningProgress {
+    /// Creates the progress of a new pruning run, marking reachable tries in a scratch LMDB
+    /// environment of at most `map_size` bytes in the directory `path`.
+    ///
+    /// Marks left behind by an interrupted run are discarded.
+    pub fn new<P: AsRef<Path>>(path: P, map_size: usize) -> Result<Self, error::Error> {
+        let marks_path = path.as_ref().join(TRIE_PRUNING_MARKS_FILENAME);
+        remove_trie_pruning_marks(&marks_path);
+        // The marks are discarded if the process stops, so there is no need to sync them to disk.
+        let marks_environment = Environment::new()
+            .set_flags(
+                EnvironmentFlags::NO_SUB_DIR
+                    | EnvironmentFlags::NO_READAHEAD
+                    | EnvironmentFlags::NO_SYNC,
+            )
+            .set_map_size(map_size)
+            .open(&marks_path)?;
+        let marks = marks_environment.open_db(None)?;
+        Ok(TriePruningProgress {
+            marks_environment,
+            marks,
+            marks_path,
+            trie_keys_to_mark: Vec::new(),
+            marked_count: 0,
+            next_key_to_sweep: None,
+        })
+    }
+
+    /// Returns the number of tries marked as reachable so far.
+    pub fn reachable_count(&self) -> usize {
+        self.marked_count
+    }
+}
+
+impl Drop for TriePruningProgress {
+    fn drop(&mut self) {
+        remove_trie_pruning_marks(&self.marks_path);
+    }
+}
+
+/// Removes the scratch LMDB environment of trie pruning at `marks_path`, if any.
+fn remove_trie_pruning_marks(marks_path: &Path) {
+    let mut lock_path = marks_path.as_os_str().to_owned();
+    lock_path.push("-lock");
+    for path in [marks_path, Path::new(&lock_path)] {
+        match fs::remove_file(path) {
+            Ok(()) => (),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => (),
+            Err(error) => {
+                warn!(path = %path.display(), %error, "failed to remove trie pruning marks")
+            }
+        }
+    }
+}
+
+/// The outcome of a single batch of trie pruning.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TriePruningBatch {
+    /// The number of tries marked as reachable.
+    pub marked: usize,
+    /// The number of tries checked by the sweep.
+    pub swept: usize,
+    /// The number of unreachable tries deleted.
+    pub deleted: usize,
+    /// Whether the sweep has reached the end of the trie store.
+    pub complete: bool,
+}
+
 impl LmdbGlobalState {
     /// Creates an empty state from an existing environment and trie_store.
     pub fn empty(
@@ -71,6 +173,96 @@ impl LmdbGlobalState {
             empty_root_hash,
         }
     }
+
+    /// Runs a single batch of pruning tries which are not reachable from the retained state roots.
+    ///
+    /// A pruning run starts with a new `TriePruningProgress` and continues until a batch is
+    /// `complete`.  The first batch must be given all retained state roots, and each later batch
+    /// every state root committed since the previous one.  Each batch first marks the tries
+    /// reachable from the state roots given so far, and once they are all marked, checks the tries
+    /// following the last one swept and deletes the unmarked ones.  At most `batch_size` tries are
+    /// marked and checked by a batch.
+    ///
+    /// Marking and sweeping happen in a single read-write transaction, so no commit can interleave
+    /// with them: a caller which records committed state roots under the same lock it holds while
+    /// calling this never has freshly committed tries deleted.
+    pub fn prune_tries(
+        &self,
+        correlation_id: CorrelationId,
+        progress: &mut TriePruningProgress,
+        state_roots: Vec<Digest>,
+        batch_size: usize,
+    ) -> Result<TriePruningBatch, error::Error> {
+        let mut txn = self.environment.create_read_write_txn()?;
+        let mut marks_txn = progress.marks_environment.begin_rw_txn()?;
+        progress.trie_keys_to_mark.extend(state_roots);
+        progress.trie_keys_to_mark.push(self.empty_root_hash);
+        let marked = mark_reachable_tries::<
+            Key,
+            StoredValue,
+            lmdb::RwTransaction,
+            LmdbTrieStore,
+            lmdb::RwTransaction,
+            error::Error,
+        >(
+            correlation_id,
+            &txn,
+            self.trie_store.deref(),
+            &mut marks_txn,
+            progress.marks,
+            &mut progress.trie_keys_to_mark,
+            batch_size,
+        )?;
+        progress.marked_count += marked;
+
+        // Only sweep once all tries reachable from the given state roots are marked.
+        let sweep_size = if progress.trie_keys_to_mark.is_empty() {
+            batch_size.saturating_sub(marked)
+        } else {
+            0
+        };
+        let handle: Database = Store::<Digest, Trie<Key, StoredValue>>::handle(&*self.trie_store);
+        let mut swept_keys = Vec::new();
+        let mut next_key_to_sweep = progress.next_key_to_sweep;
+        if sweep_size > 0 {
+            let mut cursor = lmdb::Transaction::open_ro_cursor(&txn, handle)?;
+            // Note: `iter_from` panics if there is no key from the given one onwards, so the sweep
+            //       resumes from the first key not yet checked rather than after the last one
+            //       checked, which may have been deleted.
+            let iter = match progress.next_key_to_sweep {
+                Some(next_key_to_sweep) => cursor.iter_from(next_key_to_sweep),
+                None => cursor.iter(),
+            };
+            swept_keys = iter
+                .map(|(raw_key, _)| Digest::from_bytes(raw_key).map(|(trie_key, _)| trie_key))
+                .take(sweep_size + 1)
+                .collect::<Result<Vec<Digest>, _>>()?;
+            next_key_to_sweep = if swept_keys.len() > sweep_size {
+                swept_keys.pop()
+            } else {
+                None
+            };
+        }
+
+        let mut deleted = 0;
+        for trie_key in &swept_keys {
+            if marks_txn.read(progress.marks, trie_key.as_ref())?.is_none() {
+                txn.del(handle, trie_key, None)?;
+                deleted += 1;
+            }
+        }
+        marks_txn.commit()?;
+        txn.commit()?;
+
+        let complete = sweep_size > 0 && next_key_to_sweep.is_none();
+        progress.next_key_to_sweep = next_key_to_sweep;
+        Ok(TriePruningBatch {
+            marked,
+            swept: swept_keys.len(),
+            deleted,
+            complete,
+        })
+    }
 }
 
 impl StateReader<Key, StoredValue> for LmdbGlobalStateView {
@@ -235,6 +427,8 @@ impl StateProvider for LmdbGlobalState {
 
 #[cfg(test)]
 mod tests {
+    use std::mem;
+
     use lmdb::DatabaseFlags;
     use tempfile::tempdir;
 
@@ -411,4 +605,111 @@ mod tests {
                 .unwrap()
         );
     }
+
+    #[test]
+    fn prune_tries_deletes_only_unreachable_tries() {
+        let correlation_id = CorrelationId::new();
+        let test_pairs_updated = create_test_pairs_updated();
+
+        let (state, root_hash) = create_test_state();
+
+        let effects: AdditiveMap<Key, Transform> = test_pairs_updated
+            .iter()
+            .map(|TestPair { key, value }| (*key, Transform::Write(value.to_owned())))
+            .collect();
+        let updated_hash = state.commit(correlation_id, root_hash, effects).unwrap();
+
+        // Mark and sweep in small batches, retaining only the updated state root.
+        let marks_dir = tempdir().unwrap();
+        let mut progress =
+            TriePruningProgress::new(marks_dir.path(), DEFAULT_TEST_MAX_DB_SIZE).unwrap();
+        let mut state_roots = vec![updated_hash];
+        let mut deleted = 0;
+        loop {
+            let batch = state
+                .prune_tries(
+                    correlation_id,
+                    &mut progress,
+                    mem::take(&mut state_roots),
+                    2,
+                )
+                .unwrap();
+            assert!(batch.marked + batch.swept <= 2);
+            deleted += batch.deleted;
+            if batch.complete {
+                break;
+            }
+        }
+        assert!(deleted > 0);
+
+        assert!(state.checkout(root_hash).unwrap().is_none());
+        let updated_checkout = state.checkout(updated_hash).unwrap().unwrap();
+        for TestPair { key, value } in test_pairs_updated.iter().cloned() {
+            assert_eq!(
+                Some(value),
+                updated_checkout.read(correlation_id, &key).unwrap()
+            );
+        }
+        assert!(state
+            .missing_trie_keys(correlation_id, vec![updated_hash, state.empty_root_hash])
+            .unwrap()
+            .is_empty());
+
+        drop(progress);
+        assert!(fs::read_dir(marks_dir.path()).unwrap().next().is_none());
+    }
+
+    #[test]
+    fn prune_tries_retains_state_roots_committed_during_run() {
+        let correlation_id = CorrelationId::new();
+        let test_pairs_updated = create_test_pairs_updated();
+
+        let (state, root_hash) = create_test_state();
+
+        let marks_dir = tempdir().unwrap();
+        let mut progress =
+            TriePruningProgress::new(marks_dir.path(), DEFAULT_TEST_MAX_DB_SIZE).unwrap();
+        let batch = state
+            .prune_tries(correlation_id, &mut progress, vec![root_hash], 1)
+            .unwrap();
+        assert!(!batch.complete);
+
+        // Commit on top of the retained state root between batches, and pass the new state root to
+        // the next batch like a caller recording commits would.
+        let effects: AdditiveMap<Key, Transform> = test_pairs_updated
+            .iter()
+            .map(|TestPair { key, value }| (*key, Transform::Write(value.to_owned())))
+            .collect();
+        let updated_hash = state.commit(correlation_id, root_hash, effects).unwrap();
+        let mut state_roots = vec![updated_hash];
+        loop {
+            let batch = state
+                .prune_tries(
+                    correlation_id,
+                    &mut progress,
+                    mem::take(&mut state_roots),
+                    1,
+                )
+                .unwrap();
+            if batch.complete {
+                break;
+            }
+        }
+
+        assert!(state
+            .missing_trie_keys(
+                correlation_id,
+                vec![root_hash, updated_hash, state.empty_root_hash]
+            )
+            .unwrap()
+            .is_empty());
+        // Only the reachable tries are left, and all of them were marked.
+        let txn = state.environment.create_read_txn().unwrap();
+        let handle: Database = Store::<Digest, Trie<Key, StoredValue>>::handle(&*state.trie_store);
+        let trie_count = lmdb::Transaction::open_ro_cursor(&txn, handle)
+            .unwrap()
+            .iter_start()
+            .count();
+        assert_eq!(progress.reachable_count(), trie_count);
+    }
 }
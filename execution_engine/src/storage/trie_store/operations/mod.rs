@@ -296,6 +296,73 @@ where
     Ok(missing_descendants)
 }
 
+/// Marks up to `max_count` tries taken from `trie_keys_to_visit` and their descendants as
+/// reachable, by writing their keys to the `marks` database.  Returns the number of tries marked.
+///
+/// Tries which are already marked are not visited again, so marking can be spread over several
+/// calls and extended incrementally with new root hashes.  The keys of tries still to be visited
+/// are left in `trie_keys_to_visit`.  Keys referenced but not present in the trie store are marked
+/// too.
+pub fn mark_reachable_tries<K, V, T, S, M, E>(
+    _correlation_id: CorrelationId,
+    txn: &T,
+    store: &S,
+    marks_txn: &mut M,
+    marks: M::Handle,
+    trie_keys_to_visit: &mut Vec<Digest>,
+    max_count: usize,
+) -> Result<usize, E>
+where
+    K: ToBytes + FromBytes + Eq + std::fmt::Debug,
+    V: ToBytes + FromBytes + std::fmt::Debug,
+    T: Readable<Handle = S::Handle>,
+    S: TrieStore<K, V>,
+    S::Error: From<T::Error>,
+    M: Readable + Writable,
+    M::Handle: Copy,
+    E: From<S::Error> + From<M::Error> + From<bytesrepr::Error>,
+{
+    let mut marked = 0;
+    while marked < max_count {
+        let trie_key = match trie_keys_to_visit.pop() {
+            Some(trie_key) => trie_key,
+            None => break,
+        };
+        if marks_txn.read(marks, trie_key.as_ref())?.is_some() {
+            continue;
+        }
+        marks_txn.write(marks, trie_key.as_ref(), &[])?;
+        marked += 1;
+        let maybe_retrieved_trie: Option<Trie<K, V>> = store.get(txn, &trie_key)?;
+        match maybe_retrieved_trie {
+            None | Some(Trie::Leaf { .. }) => (),
+            Some(Trie::Node { pointer_block }) => {
+                for (_, pointer) in pointer_block.as_indexed_pointers() {
+                    match pointer {
+                        // Leaves have no descendants, so there is no need to read them, unless
+                        // they are left to be marked by a later call.
+                        Pointer::LeafPointer(descendant_leaf_trie_key) if marked < max_count => {
+                            if marks_txn
+                                .read(marks, descendant_leaf_trie_key.as_ref())?
+                                .is_none()
+                            {
+                                marks_txn.write(marks, descendant_leaf_trie_key.as_ref(), &[])?;
+                                marked += 1;
+                            }
+                        }
+                        Pointer::LeafPointer(descendant_trie_key)
+                        | Pointer::NodePointer(descendant_trie_key) => {
+                            trie_keys_to_visit.push(descendant_trie_key)
+                        }
+                    }
+                }
+            }
+            Some(Trie::Extension { pointer, .. }) => trie_keys_to_visit.push(pointer.into_hash()),
+        }
+    }
+    Ok(marked)
+}
+
 #[cfg(test)]
 pub fn check_integrity<K, V, T, S, E>(
     _correlation_id: CorrelationId,
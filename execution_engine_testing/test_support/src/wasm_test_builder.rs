@@ -2,7 +2,8 @@ use std::{
     collections::BTreeMap,
     convert::{TryFrom, TryInto},
     ffi::OsStr,
-    fs,
+    fs::{self, File},
+    io::{BufWriter, Write},
     ops::Deref,
     path::{Path, PathBuf},
     rc::Rc,
@@ -26,6 +27,7 @@ use casper_execution_engine::{
             QueryResult, StepError, SystemContractRegistry, UpgradeConfig, UpgradeSuccess,
         },
         execution,
+        runtime::{execution_trace::ExecutionTrace, gas_profiler::GasProfile},
     },
     shared::{
         additive_map::AdditiveMap,
@@ -86,6 +88,8 @@ pub struct WasmTestBuilder<S> {
     engine_state: Rc<EngineState<S>>,
     /// [`ExecutionResult`] is wrapped in [`Rc`] to work around a missing [`Clone`] implementation
     exec_results: Vec<Vec<Rc<ExecutionResult>>>,
+    /// Execution traces of the deploys of traced exec calls, keyed by the index of the exec call.
+    execution_traces: BTreeMap<usize, Vec<ExecutionTrace>>,
     upgrade_results: Vec<Result<UpgradeSuccess, engine_state::Error>>,
     genesis_hash: Option<Digest>,
     post_state_hash: Option<Digest>,
@@ -124,6 +128,7 @@ impl Default for InMemoryWasmTestBuilder {
         WasmTestBuilder {
             engine_state: Rc::new(engine_state),
             exec_results: Vec::new(),
+            execution_traces: BTreeMap::new(),
             upgrade_results: Vec::new(),
             genesis_hash: None,
             post_state_hash: None,
@@ -145,6 +150,7 @@ impl<S> Clone for WasmTestBuilder<S> {
         WasmTestBuilder {
             engine_state: Rc::clone(&self.engine_state),
             exec_results: self.exec_results.clone(),
+            execution_traces: self.execution_traces.clone(),
             upgrade_results: self.upgrade_results.clone(),
             genesis_hash: self.genesis_hash,
             post_state_hash: self.post_state_hash,
@@ -207,6 +213,7 @@ impl LmdbWasmTestBuilder {
         WasmTestBuilder {
             engine_state: Rc::new(engine_state),
             exec_results: Vec::new(),
+            execution_traces: BTreeMap::new(),
             upgrade_results: Vec::new(),
             genesis_hash: None,
             post_state_hash: None,
@@ -271,6 +278,7 @@ impl LmdbWasmTestBuilder {
         WasmTestBuilder {
             engine_state: Rc::new(engine_state),
             exec_results: Vec::new(),
+            execution_traces: BTreeMap::new(),
             upgrade_results: Vec::new(),
             genesis_hash: None,
             post_state_hash: Some(post_state_hash),
@@ -475,6 +483,67 @@ where
         self
     }
 
+    /// Runs an [`ExecuteRequest`] like [`WasmTestBuilder::exec`], profiling the gas charged while
+    /// executing the Wasm of its deploys.
+    ///
+    /// The profiles of all deploys are merged and written to `folded_stacks_path` in the folded
+    /// stacks format, from which a flamegraph can be generated with e.g. `inferno-flamegraph`.
+    pub fn exec_with_gas_profile<P: AsRef<Path>>(
+        &mut self,
+        mut exec_request: ExecuteRequest,
+        folded_stacks_path: P,
+    ) -> &mut Self {
+        exec_request.parent_state_hash = self.post_state_hash.expect("expected post_state_hash");
+        let results = self
+            .engine_state
+            .run_execute_profiling_gas(CorrelationId::new(), exec_request)
+            .expect("should execute");
+
+        let mut gas_profile = GasProfile::default();
+        let mut execution_results = Vec::with_capacity(results.len());
+        for (execution_result, deploy_gas_profile) in results {
+            gas_profile.merge(deploy_gas_profile);
+            self.transforms
+                .push(execution_result.execution_journal().clone().into());
+            execution_results.push(Rc::new(execution_result));
+        }
+        self.exec_results.push(execution_results);
+
+        let file = File::create(folded_stacks_path).expect("should create gas profile file");
+        let mut writer = BufWriter::new(file);
+        gas_profile
+            .write_folded(&mut writer)
+            .and_then(|()| writer.flush())
+            .expect("should write gas profile");
+        self
+    }
+
+    /// Runs an [`ExecuteRequest`] like [`WasmTestBuilder::exec`], recording the host functions
+    /// called while executing the Wasm of its deploys.
+    ///
+    /// The traces are available from [`WasmTestBuilder::get_execution_traces`] and can be exported
+    /// with [`ExecutionTrace::to_json`].
+    pub fn exec_with_execution_trace(&mut self, mut exec_request: ExecuteRequest) -> &mut Self {
+        exec_request.parent_state_hash = self.post_state_hash.expect("expected post_state_hash");
+        let results = self
+            .engine_state
+            .run_execute_tracing(CorrelationId::new(), exec_request)
+            .expect("should execute");
+
+        let mut execution_results = Vec::with_capacity(results.len());
+        let mut execution_traces = Vec::with_capacity(results.len());
+        for (execution_result, execution_trace) in results {
+            self.transforms
+                .push(execution_result.execution_journal().clone().into());
+            execution_results.push(Rc::new(execution_result));
+            execution_traces.push(execution_trace);
+        }
+        self.execution_traces
+            .insert(self.exec_results.len(), execution_traces);
+        self.exec_results.push(execution_results);
+        self
+    }
+
     /// Commit effects of previous exec call on the latest post-state hash.
     pub fn commit(&mut self) -> &mut Self {
         let prestate_hash = self.post_state_hash.expect("Should have genesis hash");
@@ -699,6 +768,24 @@ where
         self.exec_results.get(index)
     }
 
+    /// Returns the execution traces of the deploys of a specific exec, if it was traced.
+    pub fn get_execution_traces(&self, index: usize) -> Option<&Vec<ExecutionTrace>> {
+        self.execution_traces.get(&index)
+    }
+
+    /// Returns the execution trace of the first deploy of the last exec, which must have been
+    /// traced.
+    pub fn last_execution_trace(&self) -> &ExecutionTrace {
+        let index = self
+            .exec_results
+            .len()
+            .checked_sub(1)
+            .expect("Expected to be called after run()");
+        self.get_execution_traces(index)
+            .and_then(|execution_traces| execution_traces.get(0))
+            .expect("Expected the last exec to be traced")
+    }
+
     /// Returns a count of exec results.
     pub fn get_exec_results_count(&self) -> usize {
         self.exec_results.len()
@@ -0,0 +1,96 @@
+use casper_engine_test_support::{
+    ExecuteRequestBuilder, InMemoryWasmTestBuilder, DEFAULT_ACCOUNT_ADDR,
+    DEFAULT_RUN_GENESIS_REQUEST,
+};
+use casper_execution_engine::core::runtime::execution_trace::{
+    ExecutionTrace, TraceArg, TraceArgValue, TraceValue,
+};
+use casper_types::{ApiError, CLValue, RuntimeArgs};
+
+const USER_ERROR: u16 = 1;
+
+/// Creates session code which stores a `u64` under a new URef and then reverts.
+fn make_session_code_writing_and_reverting() -> Vec<u8> {
+    let wat = format!(
+        r#"(module
+        (import "env" "casper_new_uref" (func $new_uref (param i32 i32 i32)))
+        (import "env" "casper_revert" (func $revert (param i32)))
+        (func $call
+          (call $new_uref (i32.const 64) (i32.const 0) (i32.const 13))
+          (call $revert (i32.const {status})))
+        (data (i32.const 0) "\08\00\00\00\07\00\00\00\00\00\00\00\05")
+        (export "call" (func $call))
+        (memory $memory 1)
+      )"#,
+        status = u32::from(ApiError::User(USER_ERROR)),
+    );
+    wabt::wat2wasm(wat).expect("should parse wat")
+}
+
+#[ignore]
+#[test]
+fn should_trace_host_function_calls() {
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder.run_genesis(&DEFAULT_RUN_GENESIS_REQUEST);
+
+    let exec_request = ExecuteRequestBuilder::module_bytes(
+        *DEFAULT_ACCOUNT_ADDR,
+        make_session_code_writing_and_reverting(),
+        RuntimeArgs::default(),
+    )
+    .build();
+    builder
+        .exec_with_execution_trace(exec_request)
+        .expect_failure();
+
+    let execution_trace = builder.last_execution_trace();
+    let session_calls: Vec<_> = execution_trace
+        .host_function_calls()
+        .iter()
+        .filter(|call| call.phase == "session")
+        .collect();
+    assert_eq!(session_calls.len(), 2);
+
+    let new_uref = session_calls[0];
+    assert_eq!(new_uref.host_function, "host_function_new_uref");
+    assert_eq!(new_uref.call_depth, 1);
+    assert_eq!(
+        new_uref.args,
+        vec![
+            TraceArg {
+                name: "uref_ptr".to_string(),
+                value: TraceArgValue::Wasm(TraceValue::I32(64)),
+            },
+            TraceArg {
+                name: "value".to_string(),
+                value: TraceArgValue::Decoded(
+                    serde_json::to_value(CLValue::from_t(7u64).unwrap()).unwrap()
+                ),
+            },
+        ]
+    );
+    assert_eq!(new_uref.return_value, None);
+    assert_eq!(new_uref.error, None);
+    assert!(new_uref.gas_after > new_uref.gas_before);
+    assert_eq!(new_uref.transforms.len(), 1);
+    assert!(new_uref.transforms[0].key.starts_with("uref-"));
+
+    let revert = session_calls[1];
+    assert_eq!(revert.host_function, "host_function_revert");
+    assert_eq!(
+        revert.args,
+        vec![TraceArg {
+            name: "status".to_string(),
+            value: TraceArgValue::Wasm(TraceValue::I32(
+                u32::from(ApiError::User(USER_ERROR)) as i32
+            )),
+        }]
+    );
+    assert!(revert.error.is_some());
+    assert!(revert.transforms.is_empty());
+    assert!(revert.gas_before >= new_uref.gas_after);
+
+    let json = execution_trace.to_json().expect("should serialize trace");
+    let parsed: ExecutionTrace = serde_json::from_str(&json).expect("should deserialize trace");
+    assert_eq!(&parsed, execution_trace);
+}
@@ -0,0 +1,89 @@
+use std::fs;
+
+use parity_wasm::{
+    builder,
+    elements::{Instruction, Instructions},
+};
+
+use casper_engine_test_support::{
+    DeployItemBuilder, ExecuteRequestBuilder, InMemoryWasmTestBuilder, ARG_AMOUNT,
+    DEFAULT_ACCOUNT_ADDR, DEFAULT_PAYMENT, DEFAULT_RUN_GENESIS_REQUEST,
+};
+use casper_execution_engine::core::engine_state::ExecuteRequest;
+use casper_types::{contracts::DEFAULT_ENTRY_POINT_NAME, runtime_args, RuntimeArgs};
+
+/// Creates session code whose entry point calls another function.
+fn make_session_code_with_nested_call() -> Vec<u8> {
+    let module = builder::module()
+        .function()
+        .signature()
+        .build()
+        .body()
+        .with_instructions(Instructions::new(vec![
+            Instruction::Call(1),
+            Instruction::End,
+        ]))
+        .build()
+        .build()
+        .function()
+        .signature()
+        .build()
+        .body()
+        .with_instructions(Instructions::new(vec![
+            Instruction::Nop,
+            Instruction::Nop,
+            Instruction::End,
+        ]))
+        .build()
+        .build()
+        .export()
+        .field(DEFAULT_ENTRY_POINT_NAME)
+        .build()
+        .memory()
+        .build()
+        .build();
+    parity_wasm::serialize(module).expect("should serialize")
+}
+
+fn make_exec_request() -> ExecuteRequest {
+    let deploy_item = DeployItemBuilder::new()
+        .with_address(*DEFAULT_ACCOUNT_ADDR)
+        .with_session_bytes(make_session_code_with_nested_call(), RuntimeArgs::default())
+        .with_empty_payment_bytes(runtime_args! {
+            ARG_AMOUNT => *DEFAULT_PAYMENT
+        })
+        .with_authorization_keys(&[*DEFAULT_ACCOUNT_ADDR])
+        .with_deploy_hash([42; 32])
+        .build();
+    ExecuteRequestBuilder::from_deploy_item(deploy_item).build()
+}
+
+#[ignore]
+#[test]
+fn should_write_gas_profile_of_nested_calls() {
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder.run_genesis(&DEFAULT_RUN_GENESIS_REQUEST);
+
+    builder.exec(make_exec_request()).expect_success();
+    let unprofiled_cost = builder.last_exec_gas_cost();
+
+    let temp_dir = tempfile::tempdir().expect("should create temp dir");
+    let folded_stacks_path = temp_dir.path().join("session.folded");
+    builder
+        .exec_with_gas_profile(make_exec_request(), &folded_stacks_path)
+        .expect_success();
+
+    // Profiling must not change the gas charged.
+    assert_eq!(builder.last_exec_gas_cost(), unprofiled_cost);
+
+    let folded_stacks = fs::read_to_string(&folded_stacks_path).expect("should read gas profile");
+    let stacks: Vec<&str> = folded_stacks
+        .lines()
+        .map(|line| {
+            let (stack, gas) = line.rsplit_once(' ').expect("should have gas");
+            assert!(gas.parse::<u64>().expect("should parse gas") > 0);
+            stack
+        })
+        .collect();
+    assert_eq!(stacks, vec!["session;call", "session;call;func[1]"]);
+}
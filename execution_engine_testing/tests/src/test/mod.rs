@@ -3,8 +3,10 @@ mod contract_api;
 mod contract_context;
 mod counter;
 mod deploy;
+mod execution_trace;
 mod explorer;
 mod gas_counter;
+mod gas_profiler;
 mod get_balance;
 mod groups;
 mod host_function_costs;
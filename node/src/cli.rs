@@ -5,7 +5,7 @@
 pub mod arglang;
 
 use std::{
-    env, fs,
+    env, fs, mem,
     path::{Path, PathBuf},
     str::FromStr,
 };
@@ -18,13 +18,14 @@ use toml::{value::Table, Value};
 use tracing::{error, info, warn};
 
 use crate::{
+    components::{chainspec_loader, contract_runtime::ContractRuntime, storage::Storage},
     logging,
     reactor::{initializer, joiner, participating, ReactorExit, Runner},
     setup_signal_hooks,
-    types::ExitCode,
+    types::{Chainspec, ExitCode},
     utils::{
         pid_file::{PidFile, PidFileOutcome},
-        WithDir,
+        Loadable, WithDir,
     },
 };
 
@@ -75,6 +76,26 @@ pub enum Cli {
         #[structopt(long)]
         new_config: PathBuf,
     },
+    /// Prune tries unreachable from the state roots of the most recent blocks from global state.
+    ///
+    /// The number of blocks whose state roots are retained is taken from the
+    /// `contract_runtime.trie_pruning_retained_state_roots` setting.  The node must not be running.
+    PruneGlobalState {
+        /// Path to configuration file.
+        config: PathBuf,
+
+        #[structopt(
+            short = "C",
+            long,
+            env = "NODE_CONFIG",
+            use_delimiter(true),
+            value_delimiter(";")
+        )]
+        /// Overrides and extensions for configuration file entries in the form
+        /// <SECTION>.<KEY>=<VALUE>.  For example,
+        /// '-C=contract_runtime.trie_pruning_retained_state_roots=100'
+        config_ext: Vec<ConfigExt>,
+    },
 }
 
 #[derive(Debug)]
@@ -279,9 +300,90 @@ impl Cli {
                 )?;
                 Ok(ExitCode::Success as i32)
             }
+            Cli::PruneGlobalState { config, config_ext } => {
+                let config = Self::init(&config, config_ext)?;
+
+                info!(version = %crate::VERSION_STRING.as_str(), "pruning global state");
+                Self::prune_global_state(config)?;
+                Ok(ExitCode::Success as i32)
+            }
         }
     }
 
+    /// Prunes the tries unreachable from the state roots of the most recent blocks, holding the
+    /// pidfile so that no node can be started meanwhile.
+    fn prune_global_state(config: WithDir<participating::Config>) -> anyhow::Result<()> {
+        let storage_config = config.map_ref(|cfg| cfg.storage.clone());
+        let root = storage_config.with_dir(storage_config.value().path.clone());
+
+        // Note: Do not change `_pidfile` to `_`, or it will be dropped prematurely.
+        let _pidfile = match PidFile::acquire(root.join("initializer.pid")) {
+            PidFileOutcome::AnotherNodeRunning(_) => {
+                anyhow::bail!("another node instance is running (pidfile is locked)");
+            }
+            PidFileOutcome::Crashed(pidfile) => {
+                // Keep the pidfile, so that the next node run still detects the crash.
+                mem::forget(pidfile);
+                anyhow::bail!(
+                    "previous node instance seems to have crashed, run the node to check the \
+                     integrity of its data before pruning"
+                );
+            }
+            PidFileOutcome::Clean(pidfile) => pidfile,
+            PidFileOutcome::PidFileError(err) => {
+                return Err(anyhow::anyhow!(err));
+            }
+        };
+
+        let chainspec = Chainspec::from_path(config.dir())?;
+        let storage = Storage::new(
+            &storage_config,
+            None,
+            chainspec.protocol_config.version,
+            false,
+            &chainspec.network_config.name,
+        )?;
+        let contract_runtime_config = config.value().contract_runtime;
+        let contract_runtime = ContractRuntime::new(
+            chainspec.protocol_config.version,
+            storage.root_path(),
+            &contract_runtime_config,
+            chainspec.wasm_config,
+            chainspec.system_costs_config,
+            chainspec.core_config.max_associated_keys,
+            chainspec.core_config.max_runtime_call_stack_height,
+            &Registry::new(),
+        )?;
+
+        let mut state_roots = storage.read_state_root_hashes_for_trie_pruning(
+            contract_runtime_config.trie_pruning_retained_state_roots(),
+        )?;
+        // If the node has been upgraded but not yet run, the next run will start from the
+        // post-upgrade state root.  Committing the upgrade is deterministic, so commit it here too
+        // and retain its post-state, exactly as the node will recreate it on start.
+        if let Some(highest_block) = storage.read_highest_block()? {
+            let activation_point = chainspec.protocol_config.activation_point.era_id();
+            if highest_block.header().is_switch_block()
+                && highest_block.header().era_id().successor() == activation_point
+            {
+                let upgrade_config = chainspec_loader::new_upgrade_config(
+                    &chainspec,
+                    &highest_block,
+                    highest_block.header().protocol_version(),
+                );
+                let upgrade_success = contract_runtime.commit_upgrade(*upgrade_config)?;
+                state_roots.insert(0, upgrade_success.post_state_hash);
+            }
+        }
+        info!(
+            state_root_count = state_roots.len(),
+            "pruning unreachable tries, this may take a while"
+        );
+        let deleted = contract_runtime.prune_tries(state_roots)?;
+        info!(deleted, "finished pruning unreachable tries");
+        Ok(())
+    }
+
     /// Parses the config file for the current version of casper-node, and initializes logging.
     fn init(
         config: &Path,
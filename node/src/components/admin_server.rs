@@ -0,0 +1,108 @@
+//! Admin server
+//!
+//! The admin server provides the node operator with a simple RESTful HTTP API to inspect and manage
+//! the peers of the node. As it allows changing which peers the node connects to, the server only
+//! ever listens on a loopback address and is disabled by default. It is entirely separate from the
+//! public REST and JSON-RPC servers.
+//!
+//! The actual server is run in a backgrounded task. HTTP requests are translated into reactor
+//! requests to the small network component.
+//!
+//! Peers are identified by their full hex node ID, as listed by the `/peers` endpoint. Currently
+//! this component supports the following endpoints:
+//! GET /peers : the state of every known outgoing address along with its last connection error,
+//!     and the blocked and reserved peers.
+//!     example: curl -X GET 'http://127.0.0.1:<port>/peers'
+//! POST /peers/connect/<address> : connects to an address, never forgetting it.
+//! POST /peers/disconnect/<address> : disconnects from and forgets an address.
+//!     example: curl -X POST 'http://127.0.0.1:<port>/peers/connect/1.2.3.4:35000'
+//! POST /peers/block/<node_id>[?duration=<duration>] : blocks a peer, for the configured blocklist
+//!     retain duration unless given.
+//! POST /peers/unblock/<node_id> : unblocks a peer.
+//!     example: curl -X POST 'http://127.0.0.1:<port>/peers/block/<node_id>?duration=1day'
+//! POST /peers/reserve/<node_id> : reserves a peer, so that it is never blocked after committing
+//!     offenses and its address is never forgotten.
+//! POST /peers/unreserve/<node_id> : revokes the reservation of a peer.
+
+mod config;
+mod filters;
+mod http_server;
+
+use futures::{future::BoxFuture, FutureExt};
+use tokio::{sync::oneshot, task::JoinHandle};
+use tracing::{debug, error, warn};
+
+use crate::{
+    effect::{requests::NetworkAdminRequest, EffectBuilder},
+    reactor::Finalize,
+    utils::{self, ListeningError},
+};
+
+pub use config::Config;
+
+/// A helper trait capturing all of this components Request type dependencies.
+pub(crate) trait ReactorEventT: From<NetworkAdminRequest> + Send {}
+
+impl<REv> ReactorEventT for REv where REv: From<NetworkAdminRequest> + Send + 'static {}
+
+#[derive(Debug)]
+pub(crate) struct AdminServer {
+    /// When the message is sent, it signals the server loop to exit cleanly.
+    shutdown_sender: oneshot::Sender<()>,
+    /// The task handle which will only join once the server loop has exited.
+    server_join_handle: Option<JoinHandle<()>>,
+}
+
+impl AdminServer {
+    /// Starts the admin server, refusing to listen on any address other than a loopback one.
+    pub(crate) fn new<REv>(
+        config: Config,
+        effect_builder: EffectBuilder<REv>,
+    ) -> Result<Self, ListeningError>
+    where
+        REv: ReactorEventT,
+    {
+        let address = utils::resolve_address(&config.address).map_err(|error| {
+            warn!(%error, address=%config.address, "failed to start admin server, invalid address");
+            ListeningError::ResolveAddress(error)
+        })?;
+        if !address.ip().is_loopback() {
+            warn!(%address, "failed to start admin server, not a loopback address");
+            return Err(ListeningError::NotLoopback(address));
+        }
+
+        let (shutdown_sender, shutdown_receiver) = oneshot::channel::<()>();
+
+        let builder = utils::start_listening(&config.address)?;
+        let server_join_handle = tokio::spawn(http_server::run(
+            builder,
+            effect_builder,
+            shutdown_receiver,
+            config.qps_limit,
+        ));
+
+        Ok(AdminServer {
+            shutdown_sender,
+            server_join_handle: Some(server_join_handle),
+        })
+    }
+}
+
+impl Finalize for AdminServer {
+    fn finalize(mut self) -> BoxFuture<'static, ()> {
+        async {
+            let _ = self.shutdown_sender.send(());
+
+            // Wait for the server to exit cleanly.
+            if let Some(join_handle) = self.server_join_handle.take() {
+                match join_handle.await {
+                    Ok(_) => debug!("admin server exited cleanly"),
+                    Err(error) => error!(%error, "could not join admin server task cleanly"),
+                }
+            } else {
+                warn!("admin server shutdown while already shut down")
+            }
+        }
+        .boxed()
+    }
+}
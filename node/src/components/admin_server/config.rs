@@ -0,0 +1,41 @@
+use datasize::DataSize;
+use serde::{Deserialize, Serialize};
+
+/// Default binding address for the admin HTTP server.
+///
+/// Uses a random port, only reachable from the local machine.
+const DEFAULT_ADDRESS: &str = "127.0.0.1:0";
+/// Default rate limit in qps.
+const DEFAULT_QPS_LIMIT: u64 = 10;
+
+/// Admin HTTP server configuration.
+#[derive(Clone, DataSize, Debug, Deserialize, Serialize)]
+// Disallow unknown fields to ensure config files and command-line overrides contain valid keys.
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// Setting to enable the admin HTTP server.
+    pub enable_server: bool,
+
+    /// Address to bind the admin HTTP server to, which must be a loopback address.
+    pub address: String,
+
+    /// Max rate limit in qps.
+    pub qps_limit: u64,
+}
+
+impl Config {
+    /// Creates a default instance for `AdminServer`.
+    pub fn new() -> Self {
+        Config {
+            enable_server: false,
+            address: DEFAULT_ADDRESS.to_string(),
+            qps_limit: DEFAULT_QPS_LIMIT,
+        }
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config::new()
+    }
+}
@@ -0,0 +1,435 @@
+use std::{fmt::Display, net::SocketAddr, str::FromStr};
+
+use http::Response;
+use hyper::Body;
+use serde::Deserialize;
+use warp::{
+    filters::BoxedFilter,
+    http::StatusCode,
+    reject::Rejection,
+    reply::{self, Reply},
+    Filter,
+};
+
+use super::ReactorEventT;
+use crate::{
+    effect::{requests::NetworkAdminRequest, EffectBuilder},
+    reactor::QueueKind,
+    types::{NodeId, TimeDiff},
+};
+
+/// The peers URL path.
+pub const PEERS_API_PATH: &str = "peers";
+
+/// The URL path below `PEERS_API_PATH` to connect to an address.
+pub const CONNECT_API_PATH: &str = "connect";
+
+/// The URL path below `PEERS_API_PATH` to disconnect from an address.
+pub const DISCONNECT_API_PATH: &str = "disconnect";
+
+/// The URL path below `PEERS_API_PATH` to block a peer.
+pub const BLOCK_API_PATH: &str = "block";
+
+/// The URL path below `PEERS_API_PATH` to unblock a peer.
+pub const UNBLOCK_API_PATH: &str = "unblock";
+
+/// The URL path below `PEERS_API_PATH` to reserve a peer.
+pub const RESERVE_API_PATH: &str = "reserve";
+
+/// The URL path below `PEERS_API_PATH` to revoke the reservation of a peer.
+pub const UNRESERVE_API_PATH: &str = "unreserve";
+
+/// Query parameters of the block endpoint.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BlockQuery {
+    /// The duration of the block, e.g. "1day".
+    duration: Option<TimeDiff>,
+}
+
+/// Matches `POST /peers/<action>/<param>`, extracting the parameter.
+fn peers_action(
+    action: &'static str,
+) -> impl Filter<Extract = (String,), Error = Rejection> + Clone {
+    warp::post()
+        .and(warp::path(PEERS_API_PATH))
+        .and(warp::path(action))
+        .and(warp::path::param::<String>())
+        .and(warp::path::end())
+}
+
+/// Parses a path parameter, replying with "400 Bad Request" if it is invalid.
+fn parse_param<T>(param: &str) -> Result<T, Response<Body>>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    param.parse().map_err(|error| {
+        reply::with_status(
+            format!("invalid path parameter '{}': {}", param, error),
+            StatusCode::BAD_REQUEST,
+        )
+        .into_response()
+    })
+}
+
+pub(super) fn create_peers_status_filter<REv: ReactorEventT>(
+    effect_builder: EffectBuilder<REv>,
+) -> BoxedFilter<(Response<Body>,)> {
+    warp::get()
+        .and(warp::path(PEERS_API_PATH))
+        .and(warp::path::end())
+        .and_then(move || async move {
+            let peers_status = effect_builder
+                .make_request(
+                    |responder| NetworkAdminRequest::GetPeersStatus { responder },
+                    QueueKind::Api,
+                )
+                .await;
+            Ok::<_, Rejection>(reply::json(&peers_status).into_response())
+        })
+        .boxed()
+}
+
+pub(super) fn create_connect_filter<REv: ReactorEventT>(
+    effect_builder: EffectBuilder<REv>,
+) -> BoxedFilter<(Response<Body>,)> {
+    peers_action(CONNECT_API_PATH)
+        .and_then(move |param: String| async move {
+            let addr: SocketAddr = match parse_param(&param) {
+                Ok(addr) => addr,
+                Err(response) => return Ok(response),
+            };
+            effect_builder
+                .make_request(
+                    |responder| NetworkAdminRequest::Connect { addr, responder },
+                    QueueKind::Api,
+                )
+                .await;
+            Ok::<_, Rejection>(StatusCode::NO_CONTENT.into_response())
+        })
+        .boxed()
+}
+
+pub(super) fn create_disconnect_filter<REv: ReactorEventT>(
+    effect_builder: EffectBuilder<REv>,
+) -> BoxedFilter<(Response<Body>,)> {
+    peers_action(DISCONNECT_API_PATH)
+        .and_then(move |param: String| async move {
+            let addr: SocketAddr = match parse_param(&param) {
+                Ok(addr) => addr,
+                Err(response) => return Ok(response),
+            };
+            effect_builder
+                .make_request(
+                    |responder| NetworkAdminRequest::Disconnect { addr, responder },
+                    QueueKind::Api,
+                )
+                .await;
+            Ok::<_, Rejection>(StatusCode::NO_CONTENT.into_response())
+        })
+        .boxed()
+}
+
+pub(super) fn create_block_filter<REv: ReactorEventT>(
+    effect_builder: EffectBuilder<REv>,
+) -> BoxedFilter<(Response<Body>,)> {
+    peers_action(BLOCK_API_PATH)
+        .and(warp::query::<BlockQuery>())
+        .and_then(move |param: String, query: BlockQuery| async move {
+            let peer_id: NodeId = match parse_param(&param) {
+                Ok(peer_id) => peer_id,
+                Err(response) => return Ok(response),
+            };
+            effect_builder
+                .make_request(
+                    |responder| NetworkAdminRequest::Block {
+                        peer_id: Box::new(peer_id),
+                        duration: query.duration,
+                        responder,
+                    },
+                    QueueKind::Api,
+                )
+                .await;
+            Ok::<_, Rejection>(StatusCode::NO_CONTENT.into_response())
+        })
+        .boxed()
+}
+
+pub(super) fn create_unblock_filter<REv: ReactorEventT>(
+    effect_builder: EffectBuilder<REv>,
+) -> BoxedFilter<(Response<Body>,)> {
+    peers_action(UNBLOCK_API_PATH)
+        .and_then(move |param: String| async move {
+            let peer_id: NodeId = match parse_param(&param) {
+                Ok(peer_id) => peer_id,
+                Err(response) => return Ok(response),
+            };
+            let was_blocked = effect_builder
+                .make_request(
+                    |responder| NetworkAdminRequest::Unblock {
+                        peer_id: Box::new(peer_id),
+                        responder,
+                    },
+                    QueueKind::Api,
+                )
+                .await;
+            if was_blocked {
+                Ok::<_, Rejection>(StatusCode::NO_CONTENT.into_response())
+            } else {
+                Ok(
+                    reply::with_status("peer was not blocked", StatusCode::NOT_FOUND)
+                        .into_response(),
+                )
+            }
+        })
+        .boxed()
+}
+
+pub(super) fn create_reserve_filter<REv: ReactorEventT>(
+    effect_builder: EffectBuilder<REv>,
+) -> BoxedFilter<(Response<Body>,)> {
+    peers_action(RESERVE_API_PATH)
+        .and_then(move |param: String| async move {
+            let peer_id: NodeId = match parse_param(&param) {
+                Ok(peer_id) => peer_id,
+                Err(response) => return Ok(response),
+            };
+            effect_builder
+                .make_request(
+                    |responder| NetworkAdminRequest::Reserve {
+                        peer_id: Box::new(peer_id),
+                        responder,
+                    },
+                    QueueKind::Api,
+                )
+                .await;
+            Ok::<_, Rejection>(StatusCode::NO_CONTENT.into_response())
+        })
+        .boxed()
+}
+
+pub(super) fn create_unreserve_filter<REv: ReactorEventT>(
+    effect_builder: EffectBuilder<REv>,
+) -> BoxedFilter<(Response<Body>,)> {
+    peers_action(UNRESERVE_API_PATH)
+        .and_then(move |param: String| async move {
+            let peer_id: NodeId = match parse_param(&param) {
+                Ok(peer_id) => peer_id,
+                Err(response) => return Ok(response),
+            };
+            let was_reserved = effect_builder
+                .make_request(
+                    |responder| NetworkAdminRequest::Unreserve {
+                        peer_id: Box::new(peer_id),
+                        responder,
+                    },
+                    QueueKind::Api,
+                )
+                .await;
+            if was_reserved {
+                Ok::<_, Rejection>(StatusCode::NO_CONTENT.into_response())
+            } else {
+                Ok(
+                    reply::with_status("peer was not reserved", StatusCode::NOT_FOUND)
+                        .into_response(),
+                )
+            }
+        })
+        .boxed()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{future::Future, net::SocketAddr};
+
+    use futures::join;
+    use http::Response;
+    use hyper::Body;
+    use warp::{filters::BoxedFilter, http::StatusCode};
+
+    use super::*;
+    use crate::{
+        reactor::{EventQueueHandle, Scheduler},
+        testing::TestRng,
+        utils,
+    };
+
+    /// Creates the filter under test, wired to a new scheduler.
+    fn setup<F>(
+        create_filter: F,
+    ) -> (
+        BoxedFilter<(Response<Body>,)>,
+        &'static Scheduler<NetworkAdminRequest>,
+    )
+    where
+        F: FnOnce(EffectBuilder<NetworkAdminRequest>) -> BoxedFilter<(Response<Body>,)>,
+    {
+        let scheduler = utils::leak(Scheduler::new(QueueKind::weights()));
+        let effect_builder = EffectBuilder::new(EventQueueHandle::without_shutdown(scheduler));
+        (create_filter(effect_builder), scheduler)
+    }
+
+    /// Sends a `POST` request to `path`, handling the resulting request to the small network with
+    /// `handle_request`, and returns the status code of the response.
+    async fn post<F, Fut>(
+        filter: &BoxedFilter<(Response<Body>,)>,
+        scheduler: &'static Scheduler<NetworkAdminRequest>,
+        path: &str,
+        handle_request: F,
+    ) -> StatusCode
+    where
+        F: FnOnce(NetworkAdminRequest) -> Fut,
+        Fut: Future<Output = ()>,
+    {
+        let reply = warp::test::request()
+            .method("POST")
+            .path(path)
+            .reply(filter);
+        let handle = async {
+            let ((_ancestor, request), _queue_kind) = scheduler.pop().await;
+            handle_request(request).await;
+        };
+        let (response, ()) = join!(reply, handle);
+        response.status()
+    }
+
+    #[tokio::test]
+    async fn should_request_block_with_duration() {
+        let mut rng = TestRng::new();
+        let expected_peer_id = NodeId::random(&mut rng);
+        let (filter, scheduler) = setup(create_block_filter);
+        let path = format!("/peers/block/{}?duration=1day", expected_peer_id.to_hex());
+
+        let status = post(&filter, scheduler, &path, |request| async move {
+            match request {
+                NetworkAdminRequest::Block {
+                    peer_id,
+                    duration,
+                    responder,
+                } => {
+                    assert_eq!(*peer_id, expected_peer_id);
+                    assert_eq!(duration, Some("1day".parse().unwrap()));
+                    responder.respond(()).await
+                }
+                other => panic!("unexpected request: {}", other),
+            }
+        })
+        .await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+    }
+
+    #[tokio::test]
+    async fn should_reply_not_found_when_unblocking_unblocked_peer() {
+        let mut rng = TestRng::new();
+        let (filter, scheduler) = setup(create_unblock_filter);
+        let path = format!("/peers/unblock/{}", NodeId::random(&mut rng).to_hex());
+
+        let status = post(&filter, scheduler, &path, |request| async move {
+            match request {
+                NetworkAdminRequest::Unblock { responder, .. } => responder.respond(false).await,
+                other => panic!("unexpected request: {}", other),
+            }
+        })
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn should_request_reserve_and_unreserve() {
+        let mut rng = TestRng::new();
+        let expected_peer_id = NodeId::random(&mut rng);
+
+        let (filter, scheduler) = setup(create_reserve_filter);
+        let path = format!("/peers/reserve/{}", expected_peer_id.to_hex());
+        let status = post(&filter, scheduler, &path, |request| async move {
+            match request {
+                NetworkAdminRequest::Reserve { peer_id, responder } => {
+                    assert_eq!(*peer_id, expected_peer_id);
+                    responder.respond(()).await
+                }
+                other => panic!("unexpected request: {}", other),
+            }
+        })
+        .await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+
+        let (filter, scheduler) = setup(create_unreserve_filter);
+        let path = format!("/peers/unreserve/{}", expected_peer_id.to_hex());
+        let status = post(&filter, scheduler, &path, |request| async move {
+            match request {
+                NetworkAdminRequest::Unreserve { peer_id, responder } => {
+                    assert_eq!(*peer_id, expected_peer_id);
+                    responder.respond(true).await
+                }
+                other => panic!("unexpected request: {}", other),
+            }
+        })
+        .await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+    }
+
+    #[tokio::test]
+    async fn should_request_connect_and_disconnect() {
+        let expected_addr: SocketAddr = "1.2.3.4:35000".parse().unwrap();
+
+        let (filter, scheduler) = setup(create_connect_filter);
+        let status = post(
+            &filter,
+            scheduler,
+            "/peers/connect/1.2.3.4:35000",
+            |request| async move {
+                match request {
+                    NetworkAdminRequest::Connect { addr, responder } => {
+                        assert_eq!(addr, expected_addr);
+                        responder.respond(()).await
+                    }
+                    other => panic!("unexpected request: {}", other),
+                }
+            },
+        )
+        .await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+
+        let (filter, scheduler) = setup(create_disconnect_filter);
+        let status = post(
+            &filter,
+            scheduler,
+            "/peers/disconnect/1.2.3.4:35000",
+            |request| async move {
+                match request {
+                    NetworkAdminRequest::Disconnect { addr, responder } => {
+                        assert_eq!(addr, expected_addr);
+                        responder.respond(()).await
+                    }
+                    other => panic!("unexpected request: {}", other),
+                }
+            },
+        )
+        .await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+    }
+
+    #[tokio::test]
+    async fn should_reject_invalid_path_parameters() {
+        let (filter, scheduler) = setup(create_block_filter);
+        let response = warp::test::request()
+            .method("POST")
+            .path("/peers/block/not-a-node-id")
+            .reply(&filter)
+            .await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let (connect_filter, connect_scheduler) = setup(create_connect_filter);
+        let response = warp::test::request()
+            .method("POST")
+            .path("/peers/connect/not-an-address")
+            .reply(&connect_filter)
+            .await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        // No request was sent to the small network.
+        assert_eq!(scheduler.item_count(), 0);
+        assert_eq!(connect_scheduler.item_count(), 0);
+    }
+}
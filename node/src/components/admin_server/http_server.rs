@@ -0,0 +1,60 @@
+use std::{convert::Infallible, time::Duration};
+
+use futures::{future, TryFutureExt};
+use hyper::server::{conn::AddrIncoming, Builder};
+use tokio::sync::oneshot;
+use tower::builder::ServiceBuilder;
+use tracing::{info, warn};
+use warp::Filter;
+
+use super::{filters, ReactorEventT};
+use crate::effect::EffectBuilder;
+
+/// Run the admin HTTP server.
+///
+/// A message received on `shutdown_receiver` will cause the server to exit cleanly.
+pub(super) async fn run<REv: ReactorEventT>(
+    builder: Builder<AddrIncoming>,
+    effect_builder: EffectBuilder<REv>,
+    shutdown_receiver: oneshot::Receiver<()>,
+    qps_limit: u64,
+) {
+    let peers_status = filters::create_peers_status_filter(effect_builder);
+    let connect = filters::create_connect_filter(effect_builder);
+    let disconnect = filters::create_disconnect_filter(effect_builder);
+    let block = filters::create_block_filter(effect_builder);
+    let unblock = filters::create_unblock_filter(effect_builder);
+    let reserve = filters::create_reserve_filter(effect_builder);
+    let unreserve = filters::create_unreserve_filter(effect_builder);
+
+    let service = warp::service(
+        peers_status
+            .or(connect)
+            .or(disconnect)
+            .or(block)
+            .or(unblock)
+            .or(reserve)
+            .or(unreserve),
+    );
+
+    // Start the server, passing a oneshot receiver to allow the server to be shut down gracefully.
+    let make_svc =
+        hyper::service::make_service_fn(move |_| future::ok::<_, Infallible>(service.clone()));
+
+    let rate_limited_service = ServiceBuilder::new()
+        .rate_limit(qps_limit, Duration::from_secs(1))
+        .service(make_svc);
+
+    let server = builder.serve(rate_limited_service);
+    info!(address = %server.local_addr(), "started admin server");
+
+    // Shutdown the server gracefully.
+    let _ = server
+        .with_graceful_shutdown(async {
+            shutdown_receiver.await.ok();
+        })
+        .map_err(|error| {
+            warn!(%error, "error running admin server");
+        })
+        .await;
+}
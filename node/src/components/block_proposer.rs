@@ -237,6 +237,14 @@ where
                     ready_state.sets.pending_deploys.len() as i64
                         + ready_state.sets.pending_transfers.len() as i64,
                 );
+                self.metrics.set_pending_by_gas_price(
+                    ready_state
+                        .sets
+                        .pending_deploys
+                        .values()
+                        .chain(ready_state.sets.pending_transfers.values())
+                        .map(|(deploy_info, _)| deploy_info.header.gas_price()),
+                );
             }
         };
 
@@ -478,6 +486,57 @@ impl BlockProposerReady {
             .all(|dep| past_deploys.contains(dep) || self.contains_finalized(dep))
     }
 
+    /// Returns the pending deploys which are eligible for inclusion in a block with the given
+    /// timestamp, in the order in which they should be tried.
+    ///
+    /// Deploys are ordered by gas price, highest first, then by the time they were received,
+    /// earliest first.  Once an account's deploys make up its maximum share of `block_max_count`,
+    /// its remaining deploys are moved to the end, so that they are only included if there is room
+    /// left after all other accounts' deploys.
+    fn proposable_deploys<'a>(
+        &self,
+        pending: &'a HashMap<DeployHash, (DeployInfo, Timestamp)>,
+        past_deploys: &HashSet<DeployHash>,
+        block_timestamp: Timestamp,
+        block_max_count: u32,
+    ) -> Vec<(&'a DeployHash, &'a DeployInfo)> {
+        let mut candidates: Vec<_> = pending
+            .iter()
+            .filter(|&(hash, (deploy_info, received_time))| {
+                self.deps_resolved(&deploy_info.header, past_deploys)
+                    && !past_deploys.contains(hash)
+                    && !self.contains_finalized(hash)
+                    && block_timestamp.saturating_diff(*received_time)
+                        >= self.local_config.deploy_delay
+            })
+            .collect();
+        candidates.sort_by(|(hash1, (info1, received1)), (hash2, (info2, received2))| {
+            info2
+                .header
+                .gas_price()
+                .cmp(&info1.header.gas_price())
+                .then_with(|| received1.cmp(received2))
+                .then_with(|| hash1.cmp(hash2))
+        });
+
+        let share_percent = u64::from(self.local_config.max_account_share_percent.min(100));
+        let max_per_account = (u64::from(block_max_count) * share_percent / 100).max(1);
+        let mut count_per_account: HashMap<&PublicKey, u64> = HashMap::new();
+        let (mut within_share, beyond_share): (Vec<_>, Vec<_>) =
+            candidates.into_iter().partition(|&(_, (deploy_info, _))| {
+                let count = count_per_account
+                    .entry(deploy_info.header.account())
+                    .or_default();
+                *count += 1;
+                *count <= max_per_account
+            });
+        within_share.extend(beyond_share);
+        within_share
+            .into_iter()
+            .map(|(hash, (deploy_info, _))| (hash, deploy_info))
+            .collect()
+    }
+
     /// Returns a list of candidates for inclusion into a block.
     fn propose_block_payload(
         &mut self,
@@ -497,15 +556,13 @@ impl BlockProposerReady {
         let mut appendable_block = AppendableBlock::new(deploy_config, block_timestamp);
 
         // We prioritize transfers over deploys, so we try to include them first.
-        for (hash, (deploy_info, received_time)) in &self.sets.pending_transfers {
-            if !self.deps_resolved(&deploy_info.header, &past_deploys)
-                || past_deploys.contains(hash)
-                || self.contains_finalized(hash)
-                || block_timestamp.saturating_diff(*received_time) < self.local_config.deploy_delay
-            {
-                continue;
-            }
-
+        let transfers = self.proposable_deploys(
+            &self.sets.pending_transfers,
+            &past_deploys,
+            block_timestamp,
+            deploy_config.block_max_transfer_count,
+        );
+        for (hash, deploy_info) in transfers {
             if let Err(err) = appendable_block.add_transfer(*hash, deploy_info) {
                 match err {
                     // We added the maximum number of transfers.
@@ -521,15 +578,13 @@ impl BlockProposerReady {
         }
 
         // Now we try to add other deploys to the block.
-        for (hash, (deploy_info, received_time)) in &self.sets.pending_deploys {
-            if !self.deps_resolved(&deploy_info.header, &past_deploys)
-                || past_deploys.contains(hash)
-                || self.contains_finalized(hash)
-                || block_timestamp.saturating_diff(*received_time) < self.local_config.deploy_delay
-            {
-                continue;
-            }
-
+        let deploys = self.proposable_deploys(
+            &self.sets.pending_deploys,
+            &past_deploys,
+            block_timestamp,
+            deploy_config.block_max_deploy_count,
+        );
+        for (hash, deploy_info) in deploys {
             if let Err(err) = appendable_block.add_deploy(*hash, deploy_info) {
                 match err {
                     // We added the maximum number of deploys.
@@ -13,12 +13,19 @@ pub struct Config {
     /// other nodes, and don't have to be requested from the proposer afterwards.
     #[serde(default = "default_deploy_delay")]
     pub deploy_delay: TimeDiff,
+    /// The maximum share of the deploys in a block, and separately of the transfers, which may be
+    /// sent by a single account, as a percentage of the chainspec's `block_max_deploy_count` and
+    /// `block_max_transfer_count` respectively.  An account's further deploys are only proposed if
+    /// there is room left once all other accounts' deploys have been considered.
+    #[serde(default = "default_max_account_share_percent")]
+    pub max_account_share_percent: u8,
 }
 
 impl Default for Config {
     fn default() -> Config {
         Config {
             deploy_delay: default_deploy_delay(),
+            max_account_share_percent: default_max_account_share_percent(),
         }
     }
 }
@@ -26,3 +33,7 @@ impl Default for Config {
 fn default_deploy_delay() -> TimeDiff {
     "1min".parse().unwrap()
 }
+
+fn default_max_account_share_percent() -> u8 {
+    25
+}
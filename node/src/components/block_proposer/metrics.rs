@@ -3,12 +3,27 @@ use prometheus::{self, IntGauge, Registry};
 
 use crate::unregister_metric;
 
+/// The highest gas price counted in the low gas price band.
+const LOW_GAS_PRICE_MAX: u64 = 1;
+/// The highest gas price counted in the medium gas price band.  Higher gas prices are counted in
+/// the high gas price band.
+const MEDIUM_GAS_PRICE_MAX: u64 = 10;
+
 /// Metrics for the block proposer.
 #[derive(DataSize, Debug, Clone)]
 pub(super) struct Metrics {
     /// Amount of pending deploys
     #[data_size(skip)]
     pub(super) pending_deploys: IntGauge,
+    /// Amount of pending deploys in the low gas price band.
+    #[data_size(skip)]
+    pending_deploys_low_gas_price: IntGauge,
+    /// Amount of pending deploys in the medium gas price band.
+    #[data_size(skip)]
+    pending_deploys_medium_gas_price: IntGauge,
+    /// Amount of pending deploys in the high gas price band.
+    #[data_size(skip)]
+    pending_deploys_high_gas_price: IntGauge,
     /// Registry stored to allow deregistration later.
     #[data_size(skip)]
     registry: Registry,
@@ -18,16 +33,55 @@ impl Metrics {
     /// Creates a new instance of the block proposer metrics.
     pub fn new(registry: Registry) -> Result<Self, prometheus::Error> {
         let pending_deploys = IntGauge::new("pending_deploy", "the number of pending deploys")?;
+        let pending_deploys_low_gas_price = IntGauge::new(
+            "pending_deploy_low_gas_price",
+            "the number of pending deploys with a gas price of 1",
+        )?;
+        let pending_deploys_medium_gas_price = IntGauge::new(
+            "pending_deploy_medium_gas_price",
+            "the number of pending deploys with a gas price from 2 to 10",
+        )?;
+        let pending_deploys_high_gas_price = IntGauge::new(
+            "pending_deploy_high_gas_price",
+            "the number of pending deploys with a gas price above 10",
+        )?;
         registry.register(Box::new(pending_deploys.clone()))?;
+        registry.register(Box::new(pending_deploys_low_gas_price.clone()))?;
+        registry.register(Box::new(pending_deploys_medium_gas_price.clone()))?;
+        registry.register(Box::new(pending_deploys_high_gas_price.clone()))?;
         Ok(Metrics {
             pending_deploys,
+            pending_deploys_low_gas_price,
+            pending_deploys_medium_gas_price,
+            pending_deploys_high_gas_price,
             registry,
         })
     }
+
+    /// Sets the number of pending deploys in each gas price band, given the gas prices of all
+    /// pending deploys.
+    pub(super) fn set_pending_by_gas_price<I: Iterator<Item = u64>>(&self, gas_prices: I) {
+        let (mut low, mut medium, mut high) = (0, 0, 0);
+        for gas_price in gas_prices {
+            if gas_price <= LOW_GAS_PRICE_MAX {
+                low += 1;
+            } else if gas_price <= MEDIUM_GAS_PRICE_MAX {
+                medium += 1;
+            } else {
+                high += 1;
+            }
+        }
+        self.pending_deploys_low_gas_price.set(low);
+        self.pending_deploys_medium_gas_price.set(medium);
+        self.pending_deploys_high_gas_price.set(high);
+    }
 }
 
 impl Drop for Metrics {
     fn drop(&mut self) {
         unregister_metric!(self.registry, self.pending_deploys);
+        unregister_metric!(self.registry, self.pending_deploys_low_gas_price);
+        unregister_metric!(self.registry, self.pending_deploys_medium_gas_price);
+        unregister_metric!(self.registry, self.pending_deploys_high_gas_price);
     }
 }
@@ -65,6 +65,24 @@ fn generate_deploy(
     gas_price: u64,
 ) -> Deploy {
     let secret_key = SecretKey::random(rng);
+    generate_deploy_signed_by(
+        &secret_key,
+        timestamp,
+        ttl,
+        dependencies,
+        payment_amount,
+        gas_price,
+    )
+}
+
+fn generate_deploy_signed_by(
+    secret_key: &SecretKey,
+    timestamp: Timestamp,
+    ttl: TimeDiff,
+    dependencies: Vec<DeployHash>,
+    payment_amount: Gas,
+    gas_price: u64,
+) -> Deploy {
     let chain_name = "chain".to_string();
     let args = runtime_args! {
         ARG_AMOUNT => payment_amount.value()
@@ -86,14 +104,17 @@ fn generate_deploy(
         chain_name,
         payment,
         session,
-        &secret_key,
+        secret_key,
         None,
     )
 }
 
 fn create_test_proposer(deploy_delay: TimeDiff) -> BlockProposerReady {
     BlockProposerReady {
-        local_config: Config { deploy_delay },
+        local_config: Config {
+            deploy_delay,
+            ..Default::default()
+        },
         ..Default::default()
     }
 }
@@ -762,3 +783,128 @@ fn should_respect_deploy_delay() {
     );
     assert_eq!(&vec![*deploy.id()], block.deploy_hashes());
 }
+
+#[test]
+fn should_propose_deploys_by_gas_price_then_received_time() {
+    let mut rng = crate::new_rng();
+    let creation_time = Timestamp::from(0);
+    let ttl = TimeDiff::from(10000);
+    let block_time = Timestamp::from(200);
+    let deploy_config = DeployConfig {
+        block_max_deploy_count: 2,
+        ..Default::default()
+    };
+    let mut proposer = create_test_proposer(0.into());
+
+    let mut generate_and_add = |gas_price: u64, received_time: u64| {
+        let deploy = generate_deploy(
+            &mut rng,
+            creation_time,
+            ttl,
+            vec![],
+            default_gas_payment(),
+            gas_price,
+        );
+        proposer.add_deploy(
+            received_time.into(),
+            deploy.deploy_or_transfer_hash(),
+            deploy.deploy_info().unwrap(),
+        );
+        *deploy.id()
+    };
+    let _cheap_early = generate_and_add(1, 100);
+    let pricey_late = generate_and_add(5, 150);
+    let _medium_late = generate_and_add(3, 150);
+    let medium_early = generate_and_add(3, 120);
+
+    let block = proposer.propose_block_payload(
+        deploy_config,
+        BlockContext::new(block_time, vec![]),
+        vec![],
+        true,
+    );
+    assert_eq!(&vec![pricey_late, medium_early], block.deploy_hashes());
+}
+
+#[test]
+fn should_limit_each_accounts_share_of_block() {
+    let mut rng = crate::new_rng();
+    let creation_time = Timestamp::from(0);
+    let ttl = TimeDiff::from(10000);
+    let block_time = Timestamp::from(200);
+    let deploy_config = DeployConfig {
+        block_max_deploy_count: 4,
+        ..Default::default()
+    };
+    let mut proposer = create_test_proposer(0.into());
+    proposer.local_config.max_account_share_percent = 50;
+
+    // A busy account sends four deploys at a high gas price. Their timestamps differ, so that
+    // their hashes do.
+    let busy_secret_key = SecretKey::random(&mut rng);
+    let busy_deploys: Vec<_> = (0..4)
+        .map(|index| {
+            generate_deploy_signed_by(
+                &busy_secret_key,
+                creation_time + TimeDiff::from(index),
+                ttl,
+                vec![],
+                default_gas_payment(),
+                10,
+            )
+        })
+        .collect();
+    for deploy in &busy_deploys {
+        proposer.add_deploy(
+            creation_time,
+            deploy.deploy_or_transfer_hash(),
+            deploy.deploy_info().unwrap(),
+        );
+    }
+
+    // Without other deploys pending, the busy account's deploys may fill the block.
+    let block = proposer.propose_block_payload(
+        deploy_config,
+        BlockContext::new(block_time, vec![]),
+        vec![],
+        true,
+    );
+    assert_eq!(block.deploy_hashes().len(), 4);
+
+    // Once other accounts' deploys are pending, the busy account only gets half of the block,
+    // despite its higher gas price.
+    let other_deploys: Vec<_> = (0..2)
+        .map(|_| {
+            generate_deploy(
+                &mut rng,
+                creation_time,
+                ttl,
+                vec![],
+                default_gas_payment(),
+                DEFAULT_TEST_GAS_PRICE,
+            )
+        })
+        .collect();
+    for deploy in &other_deploys {
+        proposer.add_deploy(
+            creation_time,
+            deploy.deploy_or_transfer_hash(),
+            deploy.deploy_info().unwrap(),
+        );
+    }
+    let block = proposer.propose_block_payload(
+        deploy_config,
+        BlockContext::new(block_time, vec![]),
+        vec![],
+        true,
+    );
+    assert_eq!(block.deploy_hashes().len(), 4);
+    for deploy in &other_deploys {
+        assert!(block.deploy_hashes().contains(deploy.id()));
+    }
+    let busy_count = busy_deploys
+        .iter()
+        .filter(|deploy| block.deploy_hashes().contains(deploy.id()))
+        .count();
+    assert_eq!(busy_count, 2);
+}
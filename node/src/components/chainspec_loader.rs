@@ -420,7 +420,7 @@ impl ChainspecLoader {
                 // This is a valid run immediately after upgrading the node version.
                 trace!("valid run immediately after upgrade");
                 let upgrade_config =
-                    self.new_upgrade_config(&highest_block, previous_protocol_version);
+                    new_upgrade_config(&self.chainspec, &highest_block, previous_protocol_version);
                 self.after_upgrade = true;
                 return effect_builder
                     .upgrade_contract_runtime(upgrade_config)
@@ -513,49 +513,6 @@ impl ChainspecLoader {
         Effects::new()
     }
 
-    fn new_upgrade_config(
-        &self,
-        block: &Block,
-        previous_version: ProtocolVersion,
-    ) -> Box<UpgradeConfig> {
-        let new_version = self.chainspec.protocol_config.version;
-        let global_state_update = self
-            .chainspec
-            .protocol_config
-            .global_state_update
-            .as_ref()
-            .map(|state_update| {
-                state_update
-                    .0
-                    .iter()
-                    .map(|(key, stored_value_bytes)| {
-                        let stored_value = StoredValue::from_bytes(stored_value_bytes)
-                            .unwrap_or_else(|error| {
-                                panic!(
-                                "failed to parse global state value as StoredValue for upgrade: {}",
-                                error
-                            )
-                            })
-                            .0;
-                        (*key, stored_value)
-                    })
-                    .collect()
-            })
-            .unwrap_or_default();
-        Box::new(UpgradeConfig::new(
-            *block.state_root_hash(),
-            previous_version,
-            new_version,
-            Some(self.chainspec.protocol_config.activation_point.era_id()),
-            Some(self.chainspec.core_config.validator_slots),
-            Some(self.chainspec.core_config.auction_delay),
-            Some(self.chainspec.core_config.locked_funds_period.millis()),
-            Some(self.chainspec.core_config.round_seigniorage_rate),
-            Some(self.chainspec.core_config.unbonding_delay),
-            global_state_update,
-        ))
-    }
-
     fn handle_commit_genesis_result(
         &mut self,
         result: Result<GenesisSuccess, engine_state::Error>,
@@ -721,6 +678,50 @@ impl UpgradePoint {
     }
 }
 
+/// Returns the config for upgrading global state from the post-state of `block`, the last switch
+/// block before the chainspec's activation point, to the chainspec's protocol version.
+pub(crate) fn new_upgrade_config(
+    chainspec: &Chainspec,
+    block: &Block,
+    previous_version: ProtocolVersion,
+) -> Box<UpgradeConfig> {
+    let new_version = chainspec.protocol_config.version;
+    let global_state_update = chainspec
+        .protocol_config
+        .global_state_update
+        .as_ref()
+        .map(|state_update| {
+            state_update
+                .0
+                .iter()
+                .map(|(key, stored_value_bytes)| {
+                    let stored_value = StoredValue::from_bytes(stored_value_bytes)
+                        .unwrap_or_else(|error| {
+                            panic!(
+                                "failed to parse global state value as StoredValue for upgrade: {}",
+                                error
+                            )
+                        })
+                        .0;
+                    (*key, stored_value)
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    Box::new(UpgradeConfig::new(
+        *block.state_root_hash(),
+        previous_version,
+        new_version,
+        Some(chainspec.protocol_config.activation_point.era_id()),
+        Some(chainspec.core_config.validator_slots),
+        Some(chainspec.core_config.auction_delay),
+        Some(chainspec.core_config.locked_funds_period.millis()),
+        Some(chainspec.core_config.round_seigniorage_rate),
+        Some(chainspec.core_config.unbonding_delay),
+        global_state_update,
+    ))
+}
+
 fn dir_name_from_version(version: &ProtocolVersion) -> PathBuf {
     PathBuf::from(version.to_string().replace(".", "_"))
 }
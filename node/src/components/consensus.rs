@@ -10,6 +10,7 @@ mod era_supervisor;
 mod highway_core;
 mod metrics;
 mod protocols;
+mod signer;
 #[cfg(test)]
 mod tests;
 mod traits;
@@ -29,7 +30,11 @@ use hex_fmt::HexFmt;
 use serde::{Deserialize, Serialize};
 use tracing::error;
 
-use casper_types::{EraId, PublicKey, U512};
+use casper_hashing::Digest;
+use casper_types::{
+    bytesrepr::{self, FromBytes, ToBytes, U32_SERIALIZED_LENGTH, U8_SERIALIZED_LENGTH},
+    EraId, PublicKey, Signature, U512,
+};
 
 use crate::{
     components::Component,
@@ -54,9 +59,13 @@ pub(crate) use config::Config;
 pub(crate) use consensus_protocol::{BlockContext, EraReport, ProposedBlock};
 pub(crate) use era_supervisor::EraSupervisor;
 pub(crate) use protocols::highway::HighwayProtocol;
+pub(crate) use signer::{Error as SignerError, Signer, SigningRequest};
 use traits::NodeIdT;
 pub(crate) use validator_change::ValidatorChange;
 
+const PROTOCOL_TAG: u8 = 0;
+const EVIDENCE_REQUEST_TAG: u8 = 1;
+
 #[derive(DataSize, Clone, Serialize, Deserialize)]
 pub(crate) enum ConsensusMessage {
     /// A protocol message, to be handled by the instance in the specified era.
@@ -104,6 +113,12 @@ pub(crate) enum Event<I> {
     },
     /// A queued action to be handled by a specific era.
     Action { era_id: EraId, action_id: ActionId },
+    /// The signature of one of our own vertices in a specific era, or `None` if signing failed.
+    VertexSigned {
+        era_id: EraId,
+        hash: Digest,
+        signature: Option<Signature>,
+    },
     /// We are receiving the data we require to propose a new block.
     NewBlockPayload(NewBlockPayload),
     #[from]
@@ -168,6 +183,60 @@ impl Display for ConsensusMessage {
     }
 }
 
+impl ToBytes for ConsensusMessage {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut buffer = bytesrepr::allocate_buffer(self)?;
+        match self {
+            ConsensusMessage::Protocol { era_id, payload } => {
+                buffer.push(PROTOCOL_TAG);
+                buffer.extend(era_id.to_bytes()?);
+                buffer.extend(bytesrepr::Bytes::from(payload.as_slice()).to_bytes()?);
+            }
+            ConsensusMessage::EvidenceRequest { era_id, pub_key } => {
+                buffer.push(EVIDENCE_REQUEST_TAG);
+                buffer.extend(era_id.to_bytes()?);
+                buffer.extend(pub_key.to_bytes()?);
+            }
+        }
+        Ok(buffer)
+    }
+
+    fn serialized_length(&self) -> usize {
+        U8_SERIALIZED_LENGTH
+            + match self {
+                ConsensusMessage::Protocol { era_id, payload } => {
+                    era_id.serialized_length() + U32_SERIALIZED_LENGTH + payload.len()
+                }
+                ConsensusMessage::EvidenceRequest { era_id, pub_key } => {
+                    era_id.serialized_length() + pub_key.serialized_length()
+                }
+            }
+    }
+}
+
+impl FromBytes for ConsensusMessage {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (tag, remainder) = u8::from_bytes(bytes)?;
+        match tag {
+            PROTOCOL_TAG => {
+                let (era_id, remainder) = EraId::from_bytes(remainder)?;
+                let (payload, remainder) = bytesrepr::Bytes::from_bytes(remainder)?;
+                let payload = payload.into();
+                Ok((ConsensusMessage::Protocol { era_id, payload }, remainder))
+            }
+            EVIDENCE_REQUEST_TAG => {
+                let (era_id, remainder) = EraId::from_bytes(remainder)?;
+                let (pub_key, remainder) = PublicKey::from_bytes(remainder)?;
+                Ok((
+                    ConsensusMessage::EvidenceRequest { era_id, pub_key },
+                    remainder,
+                ))
+            }
+            _ => Err(bytesrepr::Error::Formatting),
+        }
+    }
+}
+
 impl<I: Debug> Display for Event<I> {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
@@ -184,6 +253,21 @@ impl<I: Debug> Display for Event<I> {
             Event::Action { era_id, action_id } => {
                 write!(f, "action (ID {}) for {}", action_id.0, era_id)
             }
+            Event::VertexSigned {
+                era_id,
+                hash,
+                signature,
+            } => write!(
+                f,
+                "vertex {} in {} {}",
+                hash,
+                era_id,
+                if signature.is_some() {
+                    "signed"
+                } else {
+                    "could not be signed"
+                }
+            ),
             Event::NewBlockPayload(NewBlockPayload {
                 era_id,
                 block_payload,
@@ -297,6 +381,11 @@ where
                 timer_id,
             } => handling_es.handle_timer(era_id, timestamp, timer_id),
             Event::Action { era_id, action_id } => handling_es.handle_action(era_id, action_id),
+            Event::VertexSigned {
+                era_id,
+                hash,
+                signature,
+            } => handling_es.handle_vertex_signed(era_id, hash, signature),
             Event::MessageReceived { sender, msg } => handling_es.handle_message(sender, msg),
             Event::NewBlockPayload(new_block_payload) => {
                 handling_es.handle_new_block_payload(new_block_payload)
@@ -1,45 +1,65 @@
 use std::sync::Arc;
 
 use datasize::DataSize;
-use tracing::info;
+use tracing::{error, info};
 
 use casper_hashing::Digest;
-use casper_types::{PublicKey, SecretKey, Signature};
+use casper_types::{PublicKey, Signature};
 
 use crate::{
-    components::consensus::traits::{ConsensusValueT, Context, ValidatorSecret},
+    components::consensus::{
+        signer::{Signer, SigningRequest},
+        traits::{ConsensusValueT, Context, ValidatorSecret},
+    },
     crypto,
     types::BlockPayload,
 };
 
+/// The validator's signer for a single Highway instance.
 #[derive(DataSize)]
-pub struct Keypair {
-    secret_key: Arc<SecretKey>,
-    public_key: PublicKey,
+pub struct EraSigner {
+    #[data_size(skip)]
+    signer: Arc<Signer>,
+    instance_id: Digest,
 }
 
-impl Keypair {
-    pub(crate) fn new(secret_key: Arc<SecretKey>, public_key: PublicKey) -> Self {
+impl EraSigner {
+    pub(crate) fn new(signer: Arc<Signer>, instance_id: Digest) -> Self {
         Self {
-            secret_key,
-            public_key,
+            signer,
+            instance_id,
         }
     }
-}
 
-impl From<Arc<SecretKey>> for Keypair {
-    fn from(secret_key: Arc<SecretKey>) -> Self {
-        let public_key: PublicKey = secret_key.as_ref().into();
-        Self::new(secret_key, public_key)
+    fn sign_request(&self, request: SigningRequest) -> Option<Signature> {
+        self.signer
+            .sign_local(&request)
+            .map_err(|error| error!(%error, ?request, "failed to sign"))
+            .ok()
     }
 }
 
-impl ValidatorSecret for Keypair {
+impl ValidatorSecret for EraSigner {
     type Hash = Digest;
     type Signature = Signature;
 
-    fn sign(&self, hash: &Digest) -> Signature {
-        crypto::sign(hash, self.secret_key.as_ref(), &self.public_key)
+    fn sign(&self, hash: &Digest) -> Option<Signature> {
+        self.sign_request(SigningRequest::Vertex {
+            instance_id: self.instance_id,
+            hash: *hash,
+        })
+    }
+
+    fn sign_unit(&self, hash: &Digest, seq_number: u64) -> Option<Signature> {
+        self.sign_request(SigningRequest::Unit {
+            instance_id: self.instance_id,
+            seq_number,
+            hash: *hash,
+        })
+    }
+
+    fn is_remote(&self) -> bool {
+        self.signer.is_external()
     }
 }
 
@@ -56,7 +76,7 @@ pub struct ClContext;
 impl Context for ClContext {
     type ConsensusValue = Arc<BlockPayload>;
     type ValidatorId = PublicKey;
-    type ValidatorSecret = Keypair;
+    type ValidatorSecret = EraSigner;
     type Signature = Signature;
     type Hash = Digest;
     type InstanceId = Digest;
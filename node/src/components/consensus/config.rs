@@ -1,4 +1,7 @@
-use std::{path::Path, sync::Arc};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
 use datasize::DataSize;
 use serde::Deserialize;
@@ -7,7 +10,9 @@ use casper_hashing::Digest;
 use casper_types::{PublicKey, SecretKey};
 
 use crate::{
-    components::consensus::{protocols::highway::config::Config as HighwayConfig, EraId},
+    components::consensus::{
+        protocols::highway::config::Config as HighwayConfig, signer::Signer, EraId,
+    },
     types::{chainspec::HighwayConfig as HighwayProtocolConfig, Chainspec, TimeDiff, Timestamp},
     utils::{External, LoadError, Loadable},
 };
@@ -17,8 +22,12 @@ use crate::{
 // Disallow unknown fields to ensure config files and command-line overrides contain valid keys.
 #[serde(deny_unknown_fields)]
 pub(crate) struct Config {
-    /// Path to secret key file.
+    /// Path to secret key file.  Not used if `external_signer` is set.
+    #[serde(default)]
     pub(crate) secret_key_path: External,
+    /// The external signer holding the validator's secret key, if it isn't loaded into the node.
+    #[serde(default)]
+    pub(crate) external_signer: Option<ExternalSignerConfig>,
     /// Highway-specific node configuration.
     pub(crate) highway: HighwayConfig,
 }
@@ -27,11 +36,29 @@ impl Default for Config {
     fn default() -> Self {
         Config {
             secret_key_path: External::Missing,
+            external_signer: None,
             highway: HighwayConfig::default(),
         }
     }
 }
 
+/// Configuration of an external signer process, reached over a Unix socket.
+#[derive(DataSize, Debug, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct ExternalSignerConfig {
+    /// Path to the Unix socket the external signer is listening on.
+    pub(crate) socket_path: PathBuf,
+    /// The validator's public key.
+    pub(crate) public_key: PublicKey,
+    /// The timeout for a single signing request, including connecting to the socket.
+    #[serde(default = "default_request_timeout")]
+    pub(crate) request_timeout: TimeDiff,
+}
+
+fn default_request_timeout() -> TimeDiff {
+    "5sec".parse().unwrap()
+}
+
 impl Config {
     /// Loads the secret key from the configuration file and derives the public key.
     #[allow(clippy::type_complexity)]
@@ -43,6 +70,26 @@ impl Config {
         let public_key: PublicKey = PublicKey::from(secret_signing_key.as_ref());
         Ok((secret_signing_key, public_key))
     }
+
+    /// Creates the signer for consensus messages and finality signatures: the external signer if
+    /// configured, otherwise one using the secret key loaded from the configured file.
+    #[allow(clippy::type_complexity)]
+    pub(crate) fn signer<P: AsRef<Path>>(
+        &self,
+        root: P,
+    ) -> Result<Signer, LoadError<<Arc<SecretKey> as Loadable>::Error>> {
+        match &self.external_signer {
+            Some(external_signer) => Ok(Signer::external(
+                root.as_ref().join(&external_signer.socket_path),
+                external_signer.request_timeout.into(),
+                external_signer.public_key.clone(),
+            )),
+            None => {
+                let (secret_signing_key, public_key) = self.load_keys(root)?;
+                Ok(Signer::local(secret_signing_key, public_key))
+            }
+        }
+    }
 }
 
 /// Consensus protocol configuration.
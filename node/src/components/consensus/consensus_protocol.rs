@@ -232,6 +232,13 @@ pub(crate) enum ProtocolOutcome<I, C: Context> {
     StandstillAlert,
     /// We want to disconnect from a sender of invalid data.
     Disconnect(I),
+    /// Request a signature of our own vertex with the given hash from the validator's remote
+    /// secret, and pass it to `ConsensusProtocol::handle_signature`. The sequence number is present
+    /// if the vertex is a unit.
+    RequestSignature {
+        hash: C::Hash,
+        seq_number: Option<u64>,
+    },
 }
 
 /// An API for a single instance of the consensus.
@@ -266,6 +273,15 @@ pub(crate) trait ConsensusProtocol<I, C: Context>: Send {
         now: Timestamp,
     ) -> ProtocolOutcomes<I, C>;
 
+    /// Handles the signature of our own vertex requested via `ProtocolOutcome::RequestSignature`,
+    /// or `None` if it could not be signed.
+    fn handle_signature(
+        &mut self,
+        hash: C::Hash,
+        signature: Option<C::Signature>,
+        now: Timestamp,
+    ) -> ProtocolOutcomes<I, C>;
+
     /// Marks the `value` as valid or invalid, based on validation requested via
     /// `ProtocolOutcome::ValidateConsensusvalue`.
     fn resolve_validity(
@@ -26,18 +26,20 @@ use rand::Rng;
 use tracing::{debug, error, info, trace, warn};
 
 use casper_hashing::Digest;
-use casper_types::{AsymmetricType, EraId, PublicKey, SecretKey, U512};
+use casper_types::{AsymmetricType, EraId, PublicKey, Signature, U512};
 
 pub use self::era::Era;
 use crate::{
     components::consensus::{
-        cl_context::{ClContext, Keypair},
+        cl_context::{ClContext, EraSigner},
         config::ProtocolConfig,
         consensus_protocol::{
             ConsensusProtocol, EraReport, FinalizedBlock as CpFinalizedBlock, ProposedBlock,
             ProtocolOutcome, ProtocolOutcomes,
         },
+        highway_core::active_validator::read_last_unit,
         metrics::Metrics,
+        signer::{Signer, SigningRequest},
         traits::NodeIdT,
         validator_change::ValidatorChanges,
         ActionId, Config, ConsensusMessage, Event, NewBlockPayload, ReactorEventT, ResolveValidity,
@@ -51,7 +53,7 @@ use crate::{
     fatal,
     types::{
         ActivationPoint, BlockHash, BlockHeader, Deploy, DeployHash, DeployOrTransferHash,
-        FinalitySignature, FinalizedBlock, TimeDiff, Timestamp,
+        FinalizedBlock, OffenseSeverity, TimeDiff, Timestamp,
     },
     utils::WithDir,
     NodeRng,
@@ -73,6 +75,7 @@ type ConsensusConstructor<I> = dyn Fn(
         Timestamp,           // start time for this era
         u64,                 // random seed
         Timestamp,           // now timestamp
+        Option<PathBuf>,     // the write-ahead log file, if we are a validator
     ) -> (
         Box<dyn ConsensusProtocol<I, ClContext>>,
         Vec<ProtocolOutcome<I, ClContext>>,
@@ -86,7 +89,9 @@ pub struct EraSupervisor<I> {
     /// This map always contains exactly `2 * bonded_eras + 1` entries, with the last one being the
     /// current one.
     active_eras: HashMap<EraId, Era<I>>,
-    secret_signing_key: Arc<SecretKey>,
+    /// The signer for our units, endorsements, pings and finality signatures.
+    #[data_size(skip)]
+    signer: Arc<Signer>,
     public_signing_key: PublicKey,
     current_era: EraId,
     protocol_config: ProtocolConfig,
@@ -149,7 +154,8 @@ where
         }
         let unit_files_folder = storage_dir.join("unit_files");
         let (root, config) = config.into_parts();
-        let (secret_signing_key, public_signing_key) = config.load_keys(root)?;
+        let signer = Arc::new(config.signer(root)?);
+        let public_signing_key = signer.public_key().clone();
         info!(our_id = %public_signing_key, "EraSupervisor pubkey",);
         let metrics =
             Metrics::new(registry).expect("failed to set up and register consensus metrics");
@@ -160,7 +166,7 @@ where
 
         let era_supervisor = Self {
             active_eras: Default::default(),
-            secret_signing_key,
+            signer,
             public_signing_key,
             current_era,
             protocol_config,
@@ -347,15 +353,24 @@ where
             start_time,
             seed,
             now,
+            validators
+                .contains_key(our_id)
+                .then(|| self.write_ahead_log_file(&instance_id)),
         );
 
         if should_activate {
-            let secret = Keypair::new(self.secret_signing_key.clone(), our_id.clone());
+            let unit_file = self.unit_file(&instance_id);
+            // The signer only remembers the units it signed since the node started.
+            if let Ok(swunit) = read_last_unit::<ClContext, _>(&unit_file) {
+                self.signer
+                    .restore_last_unit(instance_id, swunit.wire_unit().seq_number);
+            }
+            let secret = EraSigner::new(Arc::clone(&self.signer), instance_id);
             outcomes.extend(consensus.activate_validator(
                 our_id.clone(),
                 secret,
                 now,
-                Some(self.unit_file(&instance_id)),
+                Some(unit_file),
             ))
         }
 
@@ -402,6 +417,12 @@ where
         if let Some(obsolete_era_id) = oldest_evidence_era_id.checked_sub(1) {
             if let Some(_era) = self.active_eras.remove(&obsolete_era_id) {
                 trace!(era = obsolete_era_id.value(), "removing obsolete era");
+                let instance_ids = self
+                    .active_eras
+                    .values()
+                    .map(|era| *era.consensus.instance_id())
+                    .collect();
+                self.signer.retain_instances(&instance_ids);
             }
         }
 
@@ -412,7 +433,13 @@ where
         let valid_unit_files: HashSet<_> = self
             .active_eras
             .iter()
-            .map(|(_, era)| self.unit_file(era.consensus.instance_id()))
+            .flat_map(|(_, era)| {
+                let instance_id = era.consensus.instance_id();
+                vec![
+                    self.unit_file(instance_id),
+                    self.write_ahead_log_file(instance_id),
+                ]
+            })
             .collect();
 
         let dir_iterator = match fs::read_dir(&self.unit_files_folder) {
@@ -614,6 +641,15 @@ where
             self.public_signing_key.to_hex()
         ))
     }
+
+    /// Returns the path to the era's write-ahead log of vertices.
+    fn write_ahead_log_file(&self, instance_id: &Digest) -> PathBuf {
+        self.unit_files_folder.join(format!(
+            "wal_{:?}_{}.dat",
+            instance_id,
+            self.public_signing_key.to_hex()
+        ))
+    }
 }
 
 #[cfg(test)]
@@ -783,6 +819,17 @@ where
         })
     }
 
+    pub(super) fn handle_vertex_signed(
+        &mut self,
+        era_id: EraId,
+        hash: Digest,
+        signature: Option<Signature>,
+    ) -> Effects<Event<I>> {
+        self.delegate_to_era(era_id, move |consensus, _| {
+            consensus.handle_signature(hash, signature, Timestamp::now())
+        })
+    }
+
     pub(super) fn handle_message(&mut self, sender: I, msg: ConsensusMessage) -> Effects<Event<I>> {
         match msg {
             ConsensusMessage::Protocol { era_id, payload } => {
@@ -831,18 +878,25 @@ where
 
     pub(super) fn handle_block_added(&mut self, block_header: BlockHeader) -> Effects<Event<I>> {
         let our_pk = self.era_supervisor.public_signing_key.clone();
-        let our_sk = self.era_supervisor.secret_signing_key.clone();
         let era_id = block_header.era_id();
         self.era_supervisor.executed_block(&block_header);
         let mut effects = if self.era_supervisor.is_validator_in(&our_pk, era_id) {
-            self.effect_builder
-                .announce_created_finality_signature(FinalitySignature::new(
-                    block_header.hash(),
-                    era_id,
-                    &our_sk,
-                    our_pk,
-                ))
-                .ignore()
+            let block_hash = block_header.hash();
+            let signer = Arc::clone(&self.era_supervisor.signer);
+            let effect_builder = self.effect_builder;
+            async move {
+                match signer.finality_signature(block_hash, era_id).await {
+                    Ok(finality_signature) => {
+                        effect_builder
+                            .announce_created_finality_signature(finality_signature)
+                            .await
+                    }
+                    Err(error) => {
+                        error!(%error, %block_hash, "failed to create finality signature")
+                    }
+                }
+            }
+            .ignore()
         } else {
             Effects::new()
         };
@@ -1275,6 +1329,30 @@ where
                     })
                 })
                 .collect(),
+            ProtocolOutcome::RequestSignature { hash, seq_number } => {
+                let instance_id = *self.era(era_id).consensus.instance_id();
+                let request = match seq_number {
+                    Some(seq_number) => SigningRequest::Unit {
+                        instance_id,
+                        seq_number,
+                        hash,
+                    },
+                    None => SigningRequest::Vertex { instance_id, hash },
+                };
+                let signer = Arc::clone(&self.era_supervisor.signer);
+                async move {
+                    signer
+                        .sign(&request)
+                        .await
+                        .map_err(|error| error!(%error, ?request, "failed to sign vertex"))
+                        .ok()
+                }
+                .event(move |signature| Event::VertexSigned {
+                    era_id,
+                    hash,
+                    signature,
+                })
+            }
             ProtocolOutcome::WeAreFaulty => Default::default(),
             ProtocolOutcome::DoppelgangerDetected => Default::default(),
             ProtocolOutcome::FttExceeded => {
@@ -1324,7 +1402,7 @@ where
 
     fn disconnect(&self, sender: I) -> Effects<Event<I>> {
         self.effect_builder
-            .announce_disconnect_from_peer(sender)
+            .announce_offense(sender, OffenseSeverity::Critical)
             .ignore()
     }
 
@@ -1,4 +1,5 @@
 use std::{
+    collections::HashMap,
     fmt::{self, Debug},
     fs::{self, File},
     io::{self, Read, Write},
@@ -12,7 +13,7 @@ use tracing::{error, info, trace, warn};
 use super::{
     endorsement::{Endorsement, SignedEndorsement},
     evidence::Evidence,
-    highway::{Ping, ValidVertex, Vertex, WireUnit},
+    highway::{HashedWireUnit, Ping, ValidVertex, Vertex, WireUnit},
     state::{self, Panorama, State, Unit, Weight},
     validators::ValidatorIndex,
     ENABLE_ENDORSEMENTS,
@@ -41,6 +42,23 @@ pub(crate) enum Effect<C: Context> {
     ///
     /// When this is returned, the validator automatically deactivates.
     WeAreFaulty(Fault<C>),
+    /// The vertex with the given hash needs to be signed by the validator's remote secret, and the
+    /// signature passed to `on_signature`. The sequence number is present if the vertex is a unit.
+    RequestSignature {
+        hash: C::Hash,
+        seq_number: Option<u64>,
+    },
+}
+
+/// A vertex created by us that is waiting for its signature.
+#[derive(Clone, Debug)]
+enum UnsignedVertex<C: Context> {
+    Unit(HashedWireUnit<C>),
+    Ping {
+        timestamp: Timestamp,
+        instance_id: C::InstanceId,
+    },
+    Endorsement(Endorsement<C>),
 }
 
 /// A validator that actively participates in consensus by creating new vertices.
@@ -81,6 +99,9 @@ where
     target_ftt: Weight,
     /// If this flag is set we don't create new units and just send pings instead.
     paused: bool,
+    /// Our vertices waiting for a signature from the remote secret, by hash.
+    #[data_size(skip)]
+    pending_signatures: HashMap<C::Hash, UnsignedVertex<C>>,
 }
 
 impl<C: Context> Debug for ActiveValidator<C> {
@@ -127,9 +148,10 @@ impl<C: Context> ActiveValidator<C> {
             own_last_unit,
             target_ftt,
             paused: false,
+            pending_signatures: HashMap::new(),
         };
         let mut effects = av.schedule_timer(start_time, state);
-        effects.push(av.send_ping(current_time, instance_id));
+        effects.extend(av.send_ping(current_time, instance_id));
         (av, effects)
     }
 
@@ -198,7 +220,7 @@ impl<C: Context> ActiveValidator<C> {
                 return effects;
             } else if timestamp == r_id + self.witness_offset(r_len) {
                 let panorama = self.panorama_at(state, timestamp);
-                if let Some(witness_unit) =
+                if let Some(witness_effect) =
                     self.new_unit(panorama, timestamp, None, state, instance_id)
                 {
                     if self
@@ -207,7 +229,7 @@ impl<C: Context> ActiveValidator<C> {
                     {
                         info!(round_id = %r_id, "sending witness in round with no proposal");
                     }
-                    effects.push(Effect::NewVertex(ValidVertex(Vertex::Unit(witness_unit))));
+                    effects.push(witness_effect);
                     return effects;
                 }
             }
@@ -217,15 +239,21 @@ impl<C: Context> ActiveValidator<C> {
         let one_max_round_ago = timestamp.saturating_sub(state.params().max_round_length());
         if !state.has_ping(self.vidx, one_max_round_ago + 1.into()) {
             warn!(%timestamp, "too many validators offline, sending ping");
-            effects.push(self.send_ping(timestamp, instance_id));
+            effects.extend(self.send_ping(timestamp, instance_id));
         }
         effects
     }
 
-    /// Creates a Ping vertex.
-    pub(crate) fn send_ping(&self, timestamp: Timestamp, instance_id: C::InstanceId) -> Effect<C> {
-        let ping = Ping::new(self.vidx, timestamp, instance_id, &self.secret);
-        Effect::NewVertex(ValidVertex(Vertex::Ping(ping)))
+    /// Creates a Ping vertex, unless it can't be signed.
+    pub(crate) fn send_ping(
+        &mut self,
+        timestamp: Timestamp,
+        instance_id: C::InstanceId,
+    ) -> Option<Effect<C>> {
+        self.sign_vertex(UnsignedVertex::Ping {
+            timestamp,
+            instance_id,
+        })
     }
 
     /// Returns whether enough validators are online to finalize values with the target fault
@@ -259,17 +287,11 @@ impl<C: Context> ActiveValidator<C> {
         if self.should_send_confirmation(uhash, now, state) {
             let panorama = state.confirmation_panorama(self.vidx, uhash);
             if panorama.has_correct() {
-                if let Some(confirmation_unit) =
-                    self.new_unit(panorama, now, None, state, instance_id)
-                {
-                    let vv = ValidVertex(Vertex::Unit(confirmation_unit));
-                    effects.push(Effect::NewVertex(vv));
-                }
+                effects.extend(self.new_unit(panorama, now, None, state, instance_id));
             }
         };
         if self.should_endorse(uhash, state) {
-            let endorsement = self.endorse(uhash);
-            effects.extend(vec![Effect::NewVertex(ValidVertex(endorsement))]);
+            effects.extend(self.endorse(uhash));
         }
         effects
     }
@@ -292,8 +314,7 @@ impl<C: Context> ActiveValidator<C> {
                 let unit = state.unit(v);
                 unit.new_hash_obs(state, vidx)
             })
-            .map(|v| self.endorse(v))
-            .map(|endorsement| Effect::NewVertex(ValidVertex(endorsement)))
+            .filter_map(|v| self.endorse(v))
             .collect()
     }
 
@@ -315,9 +336,7 @@ impl<C: Context> ActiveValidator<C> {
         let maybe_parent_hash = state.fork_choice(&panorama);
         // If the parent is a terminal block, just create a unit without a new block.
         if maybe_parent_hash.map_or(false, |hash| state.is_terminal_block(hash)) {
-            return self
-                .new_unit(panorama, timestamp, None, state, instance_id)
-                .map(|proposal_unit| Effect::NewVertex(ValidVertex(Vertex::Unit(proposal_unit))));
+            return self.new_unit(panorama, timestamp, None, state, instance_id);
         }
         // Otherwise we need to request a new consensus value to propose.
         let ancestor_values = match maybe_parent_hash {
@@ -360,7 +379,6 @@ impl<C: Context> ActiveValidator<C> {
             return vec![];
         }
         self.new_unit(panorama, timestamp, Some(value), state, instance_id)
-            .map(|proposal_unit| Effect::NewVertex(ValidVertex(Vertex::Unit(proposal_unit))))
             .into_iter()
             .collect()
     }
@@ -409,7 +427,8 @@ impl<C: Context> ActiveValidator<C> {
         true
     }
 
-    /// Returns a new unit with the given data, and the correct sequence number.
+    /// Returns an effect with a new unit with the given data, and the correct sequence number, or
+    /// requesting its signature.
     ///
     /// Returns `None` if it's not possible to create a valid unit with the given panorama.
     fn new_unit(
@@ -419,7 +438,7 @@ impl<C: Context> ActiveValidator<C> {
         value: Option<C::ConsensusValue>,
         state: &State<C>,
         instance_id: C::InstanceId,
-    ) -> Option<SignedWireUnit<C>> {
+    ) -> Option<Effect<C>> {
         if value.is_none() && !panorama.has_correct() {
             return None; // Wait for the first proposal before creating a unit without a value.
         }
@@ -427,6 +446,10 @@ impl<C: Context> ActiveValidator<C> {
             info!(?self.own_last_unit, "not voting - last own unit unknown");
             return None;
         }
+        if self.has_pending_unit() {
+            info!("not voting - waiting for the signature of our previous unit");
+            return None;
+        }
         if let Some((prop_context, _)) = self.next_proposal.take() {
             warn!(?prop_context, "canceling proposal due to unit");
         }
@@ -459,14 +482,106 @@ impl<C: Context> ActiveValidator<C> {
             endorsed,
         }
         .into_hashed();
-        let swunit = SignedWireUnit::new(hwunit, &self.secret);
-        write_last_unit(&self.unit_file, swunit.clone()).unwrap_or_else(|err| {
-            panic!(
-                "should successfully write unit's hash to {:?}, got {:?}",
-                self.unit_file, err
-            )
-        });
-        Some(swunit)
+        self.sign_vertex(UnsignedVertex::Unit(hwunit))
+    }
+
+    /// Returns an effect with the signed vertex if the secret is local. Otherwise stores the vertex
+    /// until `on_signature` is called, and returns an effect requesting its signature.
+    ///
+    /// Returns `None` if the vertex can't be signed.
+    fn sign_vertex(&mut self, unsigned: UnsignedVertex<C>) -> Option<Effect<C>> {
+        let (hash, seq_number) = match &unsigned {
+            UnsignedVertex::Unit(hwunit) => (hwunit.hash(), Some(hwunit.wire_unit().seq_number)),
+            UnsignedVertex::Ping {
+                timestamp,
+                instance_id,
+            } => (Ping::<C>::hash(self.vidx, *timestamp, *instance_id), None),
+            UnsignedVertex::Endorsement(endorsement) => (endorsement.hash(), None),
+        };
+        if self.secret.is_remote() {
+            self.pending_signatures.insert(hash, unsigned);
+            return Some(Effect::RequestSignature { hash, seq_number });
+        }
+        let signature = match seq_number {
+            Some(seq_number) => self.secret.sign_unit(&hash, seq_number)?,
+            None => self.secret.sign(&hash)?,
+        };
+        Some(self.signed_vertex(unsigned, signature))
+    }
+
+    /// Returns actions a validator needs to take upon receiving the signature of one of its
+    /// vertices from the remote secret, or `None` if the vertex couldn't be signed.
+    pub(crate) fn on_signature(
+        &mut self,
+        hash: &C::Hash,
+        signature: Option<C::Signature>,
+        state: &State<C>,
+    ) -> Vec<Effect<C>> {
+        let unsigned = match self.pending_signatures.remove(hash) {
+            Some(unsigned) => unsigned,
+            None => {
+                warn!(?hash, "received signature for unknown vertex");
+                return vec![];
+            }
+        };
+        let signature = match signature {
+            Some(signature) => signature,
+            None => {
+                warn!(?hash, ?unsigned, "vertex could not be signed");
+                return vec![];
+            }
+        };
+        if let UnsignedVertex::Unit(hwunit) = &unsigned {
+            // Our state may have changed while we were waiting for the signature.
+            if self.is_faulty(state)
+                || hwunit.wire_unit().panorama[self.vidx] != state.panorama()[self.vidx]
+            {
+                warn!(
+                    ?hash,
+                    "dropping unit: our latest unit has changed since it was created"
+                );
+                return vec![];
+            }
+        }
+        vec![self.signed_vertex(unsigned, signature)]
+    }
+
+    /// Returns an effect with the vertex signed by the given signature.
+    ///
+    /// Units are written to the unit file before they are returned.
+    fn signed_vertex(&self, unsigned: UnsignedVertex<C>, signature: C::Signature) -> Effect<C> {
+        let vertex = match unsigned {
+            UnsignedVertex::Unit(hwunit) => {
+                let swunit = SignedWireUnit::from_signature(hwunit, signature);
+                write_last_unit(&self.unit_file, swunit.clone()).unwrap_or_else(|err| {
+                    panic!(
+                        "should successfully write unit's hash to {:?}, got {:?}",
+                        self.unit_file, err
+                    )
+                });
+                Vertex::Unit(swunit)
+            }
+            UnsignedVertex::Ping {
+                timestamp,
+                instance_id,
+            } => Vertex::Ping(Ping::from_signature(
+                self.vidx,
+                timestamp,
+                instance_id,
+                signature,
+            )),
+            UnsignedVertex::Endorsement(endorsement) => {
+                Vertex::Endorsements(SignedEndorsement::new(endorsement, signature).into())
+            }
+        };
+        Effect::NewVertex(ValidVertex(vertex))
+    }
+
+    /// Returns whether one of our units is waiting for its signature.
+    fn has_pending_unit(&self) -> bool {
+        self.pending_signatures
+            .values()
+            .any(|unsigned| matches!(unsigned, UnsignedVertex::Unit(_)))
     }
 
     /// Returns a `ScheduleTimer` effect for the next time we need to be called.
@@ -560,11 +675,10 @@ impl<C: Context> ActiveValidator<C> {
                 .any(|(vidx, _)| state.is_faulty(vidx) && unit.new_hash_obs(state, vidx))
     }
 
-    /// Creates endorsement of the `vhash`.
-    fn endorse(&self, vhash: &C::Hash) -> Vertex<C> {
+    /// Creates endorsement of the `vhash`, unless it can't be signed.
+    fn endorse(&mut self, vhash: &C::Hash) -> Option<Effect<C>> {
         let endorsement = Endorsement::new(*vhash, self.vidx);
-        let signature = self.secret.sign(&endorsement.hash());
-        Vertex::Endorsements(SignedEndorsement::new(endorsement, signature).into())
+        self.sign_vertex(UnsignedVertex::Endorsement(endorsement))
     }
 
     /// Returns a panorama that is valid to use in our own unit at the given timestamp.
@@ -989,7 +1103,8 @@ mod tests {
             TEST_INSTANCE_ID,
         );
 
-        let ping = Vertex::Ping(Ping::new(ALICE, 500.into(), TEST_INSTANCE_ID, &ALICE_SEC));
+        let ping =
+            Vertex::Ping(Ping::new(ALICE, 500.into(), TEST_INSTANCE_ID, &ALICE_SEC).unwrap());
 
         // The ping is suspicious if it is newer than the latest ping (or unit) that has been added
         // to the state.
@@ -462,6 +462,23 @@ impl<C: Context> Highway<C> {
         })
     }
 
+    /// Handles the signature of one of our own vertices, created by the remote secret.
+    pub(crate) fn handle_signature(
+        &mut self,
+        hash: &C::Hash,
+        signature: Option<C::Signature>,
+        timestamp: Timestamp,
+    ) -> Vec<Effect<C>> {
+        self.map_active_validator(
+            |av, state| av.on_signature(hash, signature, state),
+            timestamp,
+        )
+        .unwrap_or_else(|| {
+            debug!("ignoring signature: validator has been deactivated");
+            vec![]
+        })
+    }
+
     pub(crate) fn validators(&self) -> &Validators<C::ValidatorId> {
         &self.validators
     }
@@ -552,7 +569,9 @@ impl<C: Context> Highway<C> {
                     result.extend(self.add_valid_vertex(vv.clone(), timestamp))
                 }
                 Effect::WeAreFaulty(_) => self.deactivate_validator(),
-                Effect::ScheduleTimer(_) | Effect::RequestNewBlock(_) => (),
+                Effect::ScheduleTimer(_)
+                | Effect::RequestNewBlock(_)
+                | Effect::RequestSignature { .. } => (),
             }
         }
         result.extend(effects);
@@ -832,7 +851,7 @@ pub(crate) mod tests {
         assert_eq!(Err(expected), highway.pre_validate_vertex(invalid_vertex));
 
         let hwunit = wunit.into_hashed();
-        let valid_signature = CAROL_SEC.sign(&hwunit.hash());
+        let valid_signature = CAROL_SEC.sign(&hwunit.hash()).unwrap();
         let correct_signature_unit = SignedWireUnit {
             hashed_wire_unit: hwunit,
             signature: valid_signature,
@@ -926,9 +945,9 @@ pub(crate) mod tests {
                         wunit1: &WireUnit<TestContext>,
                         signer1: &TestSecret| {
             let hwunit0 = wunit0.clone().into_hashed();
-            let swunit0 = SignedWireUnit::new(hwunit0, signer0);
+            let swunit0 = SignedWireUnit::new(hwunit0, signer0).unwrap();
             let hwunit1 = wunit1.clone().into_hashed();
-            let swunit1 = SignedWireUnit::new(hwunit1, signer1);
+            let swunit1 = SignedWireUnit::new(hwunit1, signer1).unwrap();
             let evidence = Evidence::Equivocation(swunit0, swunit1);
             let vertex = Vertex::Evidence(evidence);
             highway
@@ -1022,7 +1041,7 @@ pub(crate) mod tests {
         // Ping by validator that is not bonded, with an index that is outside of boundaries of the
         // state.
         let ping: Vertex<TestContext> =
-            Vertex::Ping(Ping::new(DAN, now, TEST_INSTANCE_ID, &DAN_SEC));
+            Vertex::Ping(Ping::new(DAN, now, TEST_INSTANCE_ID, &DAN_SEC).unwrap());
         assert!(
             DAN.0 >= WEIGHTS.len() as u32,
             "should use validator that is not bonded"
@@ -1048,9 +1067,9 @@ pub(crate) mod tests {
         let _effects =
             highway.activate_validator(ALICE.0, ALICE_SEC.clone(), now, None, target_ftt);
 
-        let ping = Vertex::Ping(Ping::new(ALICE, now, TEST_INSTANCE_ID, &ALICE_SEC));
+        let ping = Vertex::Ping(Ping::new(ALICE, now, TEST_INSTANCE_ID, &ALICE_SEC).unwrap());
         assert!(!highway.is_doppelganger_vertex(&ping));
-        let ping = Vertex::Ping(Ping::new(ALICE, later, TEST_INSTANCE_ID, &ALICE_SEC));
+        let ping = Vertex::Ping(Ping::new(ALICE, later, TEST_INSTANCE_ID, &ALICE_SEC).unwrap());
         assert!(highway.is_doppelganger_vertex(&ping));
     }
 }
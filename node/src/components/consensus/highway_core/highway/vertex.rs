@@ -12,10 +12,12 @@ use crate::{
             state::{self, Panorama},
             validators::{ValidatorIndex, Validators},
         },
-        traits::{Context, ValidatorSecret},
+        traits::Context,
     },
     types::Timestamp,
 };
+#[cfg(test)]
+use crate::components::consensus::traits::ValidatorSecret;
 
 /// A dependency of a `Vertex` that can be satisfied by one or more other vertices.
 #[derive(Clone, DataSize, Debug, Eq, PartialEq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
@@ -147,11 +149,22 @@ where
 }
 
 impl<C: Context> SignedWireUnit<C> {
+    /// Signs the unit, or returns `None` if the signature could not be created.
+    #[cfg(test)]
     pub(crate) fn new(
         hashed_wire_unit: HashedWireUnit<C>,
         secret_key: &C::ValidatorSecret,
+    ) -> Option<Self> {
+        let seq_number = hashed_wire_unit.wire_unit().seq_number;
+        let signature = secret_key.sign_unit(&hashed_wire_unit.hash, seq_number)?;
+        Some(SignedWireUnit::from_signature(hashed_wire_unit, signature))
+    }
+
+    /// Creates a signed unit from a signature that was created separately.
+    pub(crate) fn from_signature(
+        hashed_wire_unit: HashedWireUnit<C>,
+        signature: C::Signature,
     ) -> Self {
-        let signature = secret_key.sign(&hashed_wire_unit.hash);
         SignedWireUnit {
             hashed_wire_unit,
             signature,
@@ -336,14 +349,30 @@ where
 }
 
 impl<C: Context> Ping<C> {
-    /// Creates a new signed ping.
+    /// Creates a new signed ping, or returns `None` if the signature could not be created.
+    #[cfg(test)]
     pub(crate) fn new(
         creator: ValidatorIndex,
         timestamp: Timestamp,
         instance_id: C::InstanceId,
         sk: &C::ValidatorSecret,
+    ) -> Option<Self> {
+        let signature = sk.sign(&Self::hash(creator, timestamp, instance_id))?;
+        Some(Ping::from_signature(
+            creator,
+            timestamp,
+            instance_id,
+            signature,
+        ))
+    }
+
+    /// Creates a ping from a signature that was created separately.
+    pub(crate) fn from_signature(
+        creator: ValidatorIndex,
+        timestamp: Timestamp,
+        instance_id: C::InstanceId,
+        signature: C::Signature,
     ) -> Self {
-        let signature = sk.sign(&Self::hash(creator, timestamp, instance_id));
         Ping {
             creator,
             timestamp,
@@ -386,7 +415,11 @@ impl<C: Context> Ping<C> {
     }
 
     /// Computes the hash of a ping, i.e. of the creator and timestamp.
-    fn hash(creator: ValidatorIndex, timestamp: Timestamp, instance_id: C::InstanceId) -> C::Hash {
+    pub(crate) fn hash(
+        creator: ValidatorIndex,
+        timestamp: Timestamp,
+        instance_id: C::InstanceId,
+    ) -> C::Hash {
         let bytes = bincode::serialize(&(creator, timestamp, instance_id)).expect("serialize Ping");
         <C as Context>::hash(&bytes)
     }
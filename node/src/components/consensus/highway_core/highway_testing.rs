@@ -115,6 +115,9 @@ impl From<Effect<TestContext>> for HighwayMessage {
             Effect::ScheduleTimer(t) => HighwayMessage::Timer(t),
             Effect::RequestNewBlock(block_context) => HighwayMessage::RequestBlock(block_context),
             Effect::WeAreFaulty(fault) => HighwayMessage::WeAreFaulty(Box::new(fault)),
+            Effect::RequestSignature { .. } => {
+                unreachable!("test secrets sign vertices synchronously")
+            }
         }
     }
 }
@@ -276,7 +279,7 @@ impl HighwayValidator {
                                 }
                                 let secret = TestSecret(wunit2.creator.0.into());
                                 let hwunit2 = wunit2.into_hashed();
-                                let swunit2 = SignedWireUnit::new(hwunit2, &secret);
+                                let swunit2 = SignedWireUnit::new(hwunit2, &secret).unwrap();
                                 let vertex2 = Box::new(Vertex::Unit(swunit2));
                                 vec![msg, HighwayMessage::NewVertex(vertex2)]
                             }
@@ -1030,8 +1033,8 @@ impl ValidatorSecret for TestSecret {
     type Hash = HashWrapper;
     type Signature = SignatureWrapper;
 
-    fn sign(&self, data: &Self::Hash) -> Self::Signature {
-        SignatureWrapper(data.0 + self.0)
+    fn sign(&self, data: &Self::Hash) -> Option<Self::Signature> {
+        Some(SignatureWrapper(data.0 + self.0))
     }
 }
 
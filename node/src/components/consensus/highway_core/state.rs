@@ -47,10 +47,9 @@ use crate::{
 use block::Block;
 use tallies::Tallies;
 
-// TODO: The restart mechanism only persists and loads our own latest unit, so that we don't
-// equivocate after a restart. It doesn't yet persist our latest endorsed units, so we could
-// accidentally endorse conflicting votes. Fix this and enable detecting conflicting
-// endorsements again.
+// TODO: Our endorsements are now persisted across restarts in the era's write-ahead log, so we
+// won't accidentally endorse conflicting votes anymore. Detecting conflicting endorsements can be
+// enabled again once all validators on the network persist their endorsements.
 pub(super) const TODO_ENDORSEMENT_EVIDENCE_DISABLED: bool = true;
 
 /// Number of maximum-length rounds after which a validator counts as offline, if we haven't heard
@@ -3,6 +3,7 @@ mod participation;
 mod round_success_meter;
 #[cfg(test)]
 mod tests;
+mod write_ahead_log;
 
 use std::{
     any::Any,
@@ -45,7 +46,7 @@ use crate::{
     NodeRng,
 };
 
-use self::round_success_meter::RoundSuccessMeter;
+use self::{round_success_meter::RoundSuccessMeter, write_ahead_log::WriteAheadLog};
 
 /// Never allow more than this many units in a piece of evidence for conflicting endorsements,
 /// even if eras are longer than this.
@@ -87,6 +88,8 @@ where
     /// The panorama snapshot. This is updated periodically, and if it does not change for too
     /// long, an alert is raised.
     last_panorama: Panorama<C>,
+    /// The log of all vertices added to the protocol state, if we are a validator in this era.
+    write_ahead_log: Option<WriteAheadLog>,
     config: config::Config,
 }
 
@@ -104,6 +107,7 @@ impl<I: NodeIdT, C: Context + 'static> HighwayProtocol<I, C> {
         era_start_time: Timestamp,
         seed: u64,
         now: Timestamp,
+        write_ahead_log_file: Option<PathBuf>,
     ) -> (Box<dyn ConsensusProtocol<I, C>>, ProtocolOutcomes<I, C>) {
         let validators_count = validator_stakes.len();
         let sum_stakes: U512 = validator_stakes.iter().map(|(_, stake)| *stake).sum();
@@ -187,11 +191,22 @@ impl<I: NodeIdT, C: Context + 'static> HighwayProtocol<I, C> {
             endorsement_evidence_limit,
         );
 
-        let outcomes = Self::initialize_timers(now, era_start_time, &config.highway);
+        let mut outcomes = Self::initialize_timers(now, era_start_time, &config.highway);
 
-        let highway = Highway::new(instance_id, validators, params);
+        let mut highway = Highway::new(instance_id, validators, params);
+        let write_ahead_log =
+            write_ahead_log_file.and_then(|path| match WriteAheadLog::open(&path) {
+                Ok((wal, vertices)) => {
+                    Self::replay(&mut highway, vertices, now);
+                    Some(wal)
+                }
+                Err(err) => {
+                    error!(?path, %err, "could not open write-ahead log; continuing without it");
+                    None
+                }
+            });
         let last_panorama = highway.state().panorama().clone();
-        let hw_proto = Box::new(HighwayProtocol {
+        let mut hw_proto = Box::new(HighwayProtocol {
             pending_values: HashMap::new(),
             finality_detector: FinalityDetector::new(ftt),
             highway,
@@ -200,12 +215,41 @@ impl<I: NodeIdT, C: Context + 'static> HighwayProtocol<I, C> {
             pvv_cache: Default::default(),
             evidence_only: false,
             last_panorama,
+            write_ahead_log,
             config: config.highway.clone(),
         });
+        outcomes.extend(hw_proto.detect_finality());
 
         (hw_proto, outcomes)
     }
 
+    /// Adds the vertices from the write-ahead log to the protocol state, in the order in which
+    /// they were originally added.
+    ///
+    /// The effects are dropped: Our own units have already been gossiped before the restart, and
+    /// the active validator is not activated yet.
+    fn replay(highway: &mut Highway<C>, vertices: Vec<Vertex<C>>, now: Timestamp) {
+        let count = vertices.len();
+        for vertex in vertices {
+            if highway.has_vertex(&vertex) {
+                continue;
+            }
+            let vv = match highway.pre_validate_vertex(vertex).and_then(|pvv| {
+                highway
+                    .validate_vertex(pvv)
+                    .map_err(|(pvv, err)| (Vertex::from(pvv), err))
+            }) {
+                Ok(vv) => vv,
+                Err((vertex, err)) => {
+                    warn!(?vertex, ?err, "invalid vertex in the write-ahead log");
+                    continue;
+                }
+            };
+            let _effects = highway.add_valid_vertex(vv, now);
+        }
+        info!(%count, "replayed vertices from the write-ahead log");
+    }
+
     fn initialize_timers(
         now: Timestamp,
         era_start_time: Timestamp,
@@ -249,6 +293,8 @@ impl<I: NodeIdT, C: Context + 'static> HighwayProtocol<I, C> {
     fn process_av_effect(&mut self, effect: AvEffect<C>, now: Timestamp) -> ProtocolOutcomes<I, C> {
         match effect {
             AvEffect::NewVertex(vv) => {
+                // Our own vertex must be on disk before it is gossiped.
+                self.log_vertex(vv.inner(), true);
                 self.log_unit_size(vv.inner(), "sending new unit");
                 self.calculate_round_exponent(&vv, now);
                 self.process_new_vertex(vv)
@@ -266,6 +312,9 @@ impl<I: NodeIdT, C: Context + 'static> HighwayProtocol<I, C> {
                 error!("this validator is faulty: {:?}", fault);
                 vec![ProtocolOutcome::WeAreFaulty]
             }
+            AvEffect::RequestSignature { hash, seq_number } => {
+                vec![ProtocolOutcome::RequestSignature { hash, seq_number }]
+            }
         }
     }
 
@@ -423,12 +472,36 @@ impl<I: NodeIdT, C: Context + 'static> HighwayProtocol<I, C> {
         // round has finished, we now have all the vertices from that round in the state, and no
         // newer ones.
         self.calculate_round_exponent(&vv, now);
+        self.log_vertex(vv.inner(), false);
         let av_effects = self.highway.add_valid_vertex(vv, now);
         // Once vertex is added to the state, we can remove it from the cache.
         self.pvv_cache.remove(&vertex_id);
         self.process_av_effects(av_effects, now)
     }
 
+    /// Appends the vertex to the write-ahead log, if there is one, and flushes the log to disk if
+    /// `sync` is `true`.
+    ///
+    /// If writing fails, the log is closed: Entries after a partially written one could not be
+    /// replayed anyway.
+    fn log_vertex(&mut self, vertex: &Vertex<C>, sync: bool) {
+        let wal = match self.write_ahead_log.as_mut() {
+            Some(wal) => wal,
+            None => return,
+        };
+        let result = wal
+            .append(vertex)
+            .and_then(|()| if sync { wal.sync() } else { Ok(()) });
+        if let Err(err) = result {
+            error!(
+                path = ?wal.path(),
+                %err,
+                "could not write to the write-ahead log; continuing without it"
+            );
+            self.write_ahead_log = None;
+        }
+    }
+
     /// Returns an instance of `RoundSuccessMeter` for the new era: resetting the counters where
     /// appropriate.
     fn next_era_round_succ_meter(&self, timestamp: Timestamp) -> RoundSuccessMeter<C> {
@@ -965,6 +1038,16 @@ where
         self.process_av_effects(effects, now)
     }
 
+    fn handle_signature(
+        &mut self,
+        hash: C::Hash,
+        signature: Option<C::Signature>,
+        now: Timestamp,
+    ) -> ProtocolOutcomes<I, C> {
+        let effects = self.highway.handle_signature(&hash, signature, now);
+        self.process_av_effects(effects, now)
+    }
+
     fn resolve_validity(
         &mut self,
         proposed_block: ProposedBlock<C>,
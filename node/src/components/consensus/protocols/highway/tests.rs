@@ -1,4 +1,4 @@
-use std::{collections::BTreeSet, sync::Arc};
+use std::{collections::BTreeSet, path::PathBuf, sync::Arc, time::Duration};
 
 use datasize::DataSize;
 use derive_more::Display;
@@ -7,9 +7,9 @@ use casper_types::{PublicKey, U512};
 
 use crate::{
     components::consensus::{
-        cl_context::{ClContext, Keypair},
+        cl_context::{ClContext, EraSigner},
         config::Config,
-        consensus_protocol::{ConsensusProtocol, ProtocolOutcome},
+        consensus_protocol::{ConsensusProtocol, ProposedBlock, ProtocolOutcome},
         highway_core::{
             highway::{SignedWireUnit, Vertex, WireUnit},
             highway_testing,
@@ -19,8 +19,9 @@ use crate::{
         },
         protocols::highway::{
             config::Config as HighwayConfig, HighwayMessage, ACTION_ID_VERTEX,
-            TIMER_ID_STANDSTILL_ALERT,
+            TIMER_ID_ACTIVE_VALIDATOR, TIMER_ID_STANDSTILL_ALERT,
         },
+        signer::{Signer, SigningRequest},
         tests::utils::{new_test_chainspec, ALICE_PUBLIC_KEY, ALICE_SECRET_KEY, BOB_PUBLIC_KEY},
         traits::Context,
         HighwayProtocol,
@@ -61,6 +62,33 @@ pub(crate) fn new_test_highway_protocol<I1, I2, T>(
     weights: I1,
     init_faulty: I2,
 ) -> Box<dyn ConsensusProtocol<NodeId, ClContext>>
+where
+    I1: IntoIterator<Item = (PublicKey, T)>,
+    I2: IntoIterator<Item = PublicKey>,
+    T: Into<U512>,
+{
+    let (hw_proto, outcomes) = new_test_highway_protocol_with_wal(weights, init_faulty, None);
+    // We expect five messages:
+    // * log participation timer,
+    // * log synchronizer queue length timer,
+    // * purge synchronizer queue timer,
+    // * standstill alert timer,
+    // * latest state request timer
+    // If there are more, the tests might need to handle them.
+    assert_eq!(4, outcomes.len());
+    hw_proto
+}
+
+/// Returns a new Highway instance that logs its vertices to the given write-ahead log file, and
+/// the outcomes of its initialization.
+fn new_test_highway_protocol_with_wal<I1, I2, T>(
+    weights: I1,
+    init_faulty: I2,
+    write_ahead_log_file: Option<PathBuf>,
+) -> (
+    Box<dyn ConsensusProtocol<NodeId, ClContext>>,
+    Vec<ProtocolOutcome<NodeId, ClContext>>,
+)
 where
     I1: IntoIterator<Item = (PublicKey, T)>,
     I2: IntoIterator<Item = PublicKey>,
@@ -73,6 +101,7 @@ where
     let chainspec = new_test_chainspec(weights.clone());
     let config = Config {
         secret_key_path: Default::default(),
+        external_signer: None,
         highway: HighwayConfig {
             pending_vertex_timeout: "1min".parse().unwrap(),
             standstill_timeout: Some(STANDSTILL_TIMEOUT.parse().unwrap()),
@@ -83,7 +112,7 @@ where
     };
     // Timestamp of the genesis era start and test start.
     let start_timestamp: Timestamp = 0.into();
-    let (hw_proto, outcomes) = HighwayProtocol::<NodeId, ClContext>::new_boxed(
+    HighwayProtocol::<NodeId, ClContext>::new_boxed(
         ClContext::hash(INSTANCE_ID_DATA),
         weights.into_iter().collect(),
         &init_faulty.into_iter().collect(),
@@ -94,16 +123,14 @@ where
         start_timestamp,
         0,
         start_timestamp,
-    );
-    // We expect five messages:
-    // * log participation timer,
-    // * log synchronizer queue length timer,
-    // * purge synchronizer queue timer,
-    // * standstill alert timer,
-    // * latest state request timer
-    // If there are more, the tests might need to handle them.
-    assert_eq!(4, outcomes.len());
-    hw_proto
+        write_ahead_log_file,
+    )
+}
+
+/// Returns Alice's signer for the test Highway instance.
+fn alice_signer() -> EraSigner {
+    let signer = Signer::local(Arc::clone(&*ALICE_SECRET_KEY), ALICE_PUBLIC_KEY.clone());
+    EraSigner::new(Arc::new(signer), ClContext::hash(INSTANCE_ID_DATA))
 }
 
 #[test]
@@ -157,9 +184,9 @@ fn send_a_wire_unit_with_too_small_a_round_exp() {
         round_exp: 0,
         endorsed: BTreeSet::new(),
     };
-    let alice_keypair: Keypair = Keypair::from(Arc::clone(&*ALICE_SECRET_KEY));
+    let alice_signer = alice_signer();
     let highway_message: HighwayMessage<ClContext> = HighwayMessage::NewVertex(Vertex::Unit(
-        SignedWireUnit::new(wunit.into_hashed(), &alice_keypair),
+        SignedWireUnit::new(wunit.into_hashed(), &alice_signer).unwrap(),
     ));
     let mut highway_protocol = new_test_highway_protocol(validators, vec![]);
     let sender = NodeId(123);
@@ -211,9 +238,9 @@ fn send_a_valid_wire_unit() {
         round_exp: 14,
         endorsed: BTreeSet::new(),
     };
-    let alice_keypair: Keypair = Keypair::from(Arc::clone(&*ALICE_SECRET_KEY));
+    let alice_signer = alice_signer();
     let highway_message: HighwayMessage<ClContext> = HighwayMessage::NewVertex(Vertex::Unit(
-        SignedWireUnit::new(wunit.into_hashed(), &alice_keypair),
+        SignedWireUnit::new(wunit.into_hashed(), &alice_signer).unwrap(),
     ));
 
     let mut highway_protocol = new_test_highway_protocol(validators, vec![]);
@@ -277,13 +304,13 @@ fn detect_doppelganger() {
         round_exp,
         endorsed: BTreeSet::new(),
     };
-    let alice_keypair: Keypair = Keypair::from(Arc::clone(&*ALICE_SECRET_KEY));
+    let alice_signer = alice_signer();
     let highway_message: HighwayMessage<ClContext> = HighwayMessage::NewVertex(Vertex::Unit(
-        SignedWireUnit::new(wunit.into_hashed(), &alice_keypair),
+        SignedWireUnit::new(wunit.into_hashed(), &alice_signer).unwrap(),
     ));
     let mut highway_protocol = new_test_highway_protocol(validators, vec![]);
     // Activate ALICE as validator.
-    let _ = highway_protocol.activate_validator(ALICE_PUBLIC_KEY.clone(), alice_keypair, now, None);
+    let _ = highway_protocol.activate_validator(ALICE_PUBLIC_KEY.clone(), alice_signer, now, None);
     assert!(highway_protocol.is_active());
     let sender = NodeId(123);
     let msg = bincode::serialize(&highway_message).unwrap();
@@ -302,3 +329,158 @@ fn detect_doppelganger() {
     }
     panic!("failed to return DoppelgangerDetected effect");
 }
+
+#[test]
+fn replay_write_ahead_log() {
+    let mut rng = TestRng::new();
+    let dir = tempfile::tempdir().unwrap();
+    let wal_path = dir.path().join("wal.dat");
+    let creator: ValidatorIndex = ALICE;
+    let validators = vec![(ALICE_PUBLIC_KEY.clone(), 100)];
+    let state: State<ClContext> = new_test_state(validators.iter().map(|(_pk, w)| *w), 0);
+    let panorama: Panorama<ClContext> = Panorama::from(vec![N]);
+    let seq_number = panorama.next_seq_num(&state, creator);
+    let now = Timestamp::zero();
+    let wunit: WireUnit<ClContext> = WireUnit {
+        panorama,
+        creator,
+        instance_id: ClContext::hash(INSTANCE_ID_DATA),
+        value: Some(Arc::new(BlockPayload::new(vec![], vec![], vec![], false))),
+        seq_number,
+        timestamp: now,
+        round_exp: 14,
+        endorsed: BTreeSet::new(),
+    };
+    let swunit = SignedWireUnit::new(wunit.into_hashed(), &alice_signer()).unwrap();
+    let unit_hash = swunit.hash();
+    let msg = bincode::serialize(&HighwayMessage::NewVertex(Vertex::Unit(swunit))).unwrap();
+
+    let (mut highway_protocol, _) =
+        new_test_highway_protocol_with_wal(validators.clone(), vec![], Some(wal_path.clone()));
+    let mut outcomes = highway_protocol.handle_message(&mut rng, NodeId(123), msg, now);
+    while let Some(outcome) = outcomes.pop() {
+        if let ProtocolOutcome::QueueAction(ACTION_ID_VERTEX) = outcome {
+            outcomes.extend(highway_protocol.handle_action(ACTION_ID_VERTEX, now))
+        }
+    }
+    drop(highway_protocol);
+
+    // After a restart, the unit is restored from the log and the block it proposed is finalized
+    // again.
+    let (highway_protocol, outcomes) =
+        new_test_highway_protocol_with_wal(validators, vec![], Some(wal_path));
+    let highway_protocol = highway_protocol
+        .as_any()
+        .downcast_ref::<HighwayProtocol<NodeId, ClContext>>()
+        .unwrap();
+    let state = highway_protocol.highway.state();
+    assert!(state.has_unit(&unit_hash));
+    assert_eq!(1, state.unit_count());
+    assert!(
+        outcomes
+            .iter()
+            .any(|outcome| matches!(outcome, ProtocolOutcome::FinalizedBlock(_))),
+        "Unexpected outcomes: {:?}",
+        outcomes
+    );
+}
+
+#[test]
+fn sign_vertices_with_remote_secret() {
+    let dir = tempfile::tempdir().unwrap();
+    let instance_id = ClContext::hash(INSTANCE_ID_DATA);
+    let validators = vec![(ALICE_PUBLIC_KEY.clone(), 100)];
+    let mut highway_protocol = new_test_highway_protocol(validators, vec![]);
+    // The external signer is never contacted: signatures are requested via outcomes.
+    let external_signer = Signer::external(
+        dir.path().join("signer.sock"),
+        Duration::from_secs(1),
+        ALICE_PUBLIC_KEY.clone(),
+    );
+    let secret = EraSigner::new(Arc::new(external_signer), instance_id);
+    let local_signer = Signer::local(Arc::clone(&*ALICE_SECRET_KEY), ALICE_PUBLIC_KEY.clone());
+    let unit_file = dir.path().join("unit_file");
+    let mut now = Timestamp::zero();
+
+    let mut outcomes = highway_protocol.activate_validator(
+        ALICE_PUBLIC_KEY.clone(),
+        secret,
+        now,
+        Some(unit_file.clone()),
+    );
+    let is_gossip = |outcome: &ProtocolOutcome<NodeId, ClContext>| {
+        matches!(outcome, ProtocolOutcome::CreatedGossipMessage(_))
+    };
+    let request_signature = |outcomes: &[ProtocolOutcome<NodeId, ClContext>]| {
+        assert!(!outcomes.iter().any(is_gossip), "unsigned vertex gossiped");
+        outcomes
+            .iter()
+            .find_map(|outcome| match outcome {
+                ProtocolOutcome::RequestSignature { hash, seq_number } => {
+                    Some((*hash, *seq_number))
+                }
+                _ => None,
+            })
+            .expect("expected a signature request")
+    };
+
+    // The initial ping is only gossiped once it is signed.
+    let (ping_hash, seq_number) = request_signature(&outcomes);
+    assert_eq!(None, seq_number);
+    let signature = local_signer
+        .sign_local(&SigningRequest::Vertex {
+            instance_id,
+            hash: ping_hash,
+        })
+        .unwrap();
+    let ping_outcomes = highway_protocol.handle_signature(ping_hash, Some(signature), now);
+    assert!(ping_outcomes.iter().any(is_gossip));
+    assert!(highway_protocol
+        .handle_signature(ping_hash, Some(signature), now)
+        .is_empty());
+    outcomes.extend(ping_outcomes);
+
+    // Follow the timers until it is our turn to propose a block.
+    let block_context = loop {
+        let timestamp = outcomes
+            .iter()
+            .find_map(|outcome| match outcome {
+                ProtocolOutcome::ScheduleTimer(timestamp, TIMER_ID_ACTIVE_VALIDATOR) => {
+                    Some(*timestamp)
+                }
+                _ => None,
+            })
+            .expect("expected an active validator timer");
+        now = timestamp;
+        outcomes = highway_protocol.handle_timer(now, TIMER_ID_ACTIVE_VALIDATOR);
+        if let Some(block_context) = outcomes.iter().find_map(|outcome| match outcome {
+            ProtocolOutcome::CreateNewBlock(block_context) => Some(block_context.clone()),
+            _ => None,
+        }) {
+            break block_context;
+        }
+    };
+    let block_payload = Arc::new(BlockPayload::new(vec![], vec![], vec![], false));
+    let outcomes = highway_protocol.propose(ProposedBlock::new(block_payload, block_context), now);
+    let (unit_hash, seq_number) = request_signature(&outcomes);
+    assert_eq!(Some(0), seq_number);
+    assert!(!unit_file.exists());
+
+    // Once signed, the unit is written to the unit file, added to the state and gossiped.
+    let signature = local_signer
+        .sign_local(&SigningRequest::Unit {
+            instance_id,
+            seq_number: 0,
+            hash: unit_hash,
+        })
+        .unwrap();
+    let outcomes = highway_protocol.handle_signature(unit_hash, Some(signature), now);
+    assert!(outcomes.iter().any(is_gossip));
+    assert!(unit_file.exists());
+    let highway = &highway_protocol
+        .as_any()
+        .downcast_ref::<HighwayProtocol<NodeId, ClContext>>()
+        .unwrap()
+        .highway;
+    assert!(highway.state().has_unit(&unit_hash));
+}
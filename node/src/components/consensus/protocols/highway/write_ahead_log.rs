@@ -0,0 +1,155 @@
+//! A write-ahead log of the vertices added to a `Highway` instance.
+//!
+//! Every vertex is appended to the log before it is added to the protocol state or gossiped, so
+//! that after a restart the state, including our own units, endorsements and evidence, can be
+//! restored by replaying the log before the validator is activated again.
+//!
+//! Syncing the file to disk is expensive, so it is only done before our own vertices are gossiped,
+//! which also persists all vertices from other validators logged since the previous sync. Those
+//! could be lost in a crash, but they can be requested from our peers again.
+
+use std::{
+    convert::{TryFrom, TryInto},
+    fs::{self, File, OpenOptions},
+    io::{self, Read, Write},
+    path::{Path, PathBuf},
+};
+
+use datasize::DataSize;
+use tracing::warn;
+
+use crate::components::consensus::{highway_core::highway::Vertex, traits::Context};
+
+/// The number of bytes of the length prefix of each entry.
+const LENGTH_PREFIX_SIZE: usize = 4;
+
+/// An append-only file of length-prefixed, bincode-encoded vertices.
+#[derive(DataSize, Debug)]
+pub(crate) struct WriteAheadLog {
+    /// The path of the log file.
+    path: PathBuf,
+    /// The log file, opened for appending.
+    #[data_size(skip)]
+    file: File,
+}
+
+impl WriteAheadLog {
+    /// Opens the log at the given path, creating it if it doesn't exist, and returns it together
+    /// with all vertices logged so far.
+    ///
+    /// An incomplete entry at the end, left by a crash during a write, is discarded.
+    pub(crate) fn open<C, P>(path: P) -> io::Result<(Self, Vec<Vertex<C>>)>
+    where
+        C: Context,
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref().to_path_buf();
+        if let Some(parent_directory) = path.parent() {
+            fs::create_dir_all(parent_directory)?;
+        }
+        let mut file = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create(true)
+            .open(&path)?;
+        let mut bytes = Vec::new();
+        file.read_to_end(&mut bytes)?;
+
+        let mut vertices = Vec::new();
+        let mut remaining = bytes.as_slice();
+        while let Some((vertex, rest)) = Self::read_entry(remaining) {
+            vertices.push(vertex);
+            remaining = rest;
+        }
+        if !remaining.is_empty() {
+            warn!(
+                ?path,
+                discarded_bytes = remaining.len(),
+                "discarding incomplete entry at the end of the write-ahead log"
+            );
+            file.set_len(bytes.len().saturating_sub(remaining.len()) as u64)?;
+        }
+        Ok((WriteAheadLog { path, file }, vertices))
+    }
+
+    /// Appends the vertex to the log, without waiting for it to be written to disk.
+    pub(crate) fn append<C: Context>(&mut self, vertex: &Vertex<C>) -> io::Result<()> {
+        let bytes = bincode::serialize(vertex)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        let len = u32::try_from(bytes.len())
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        let mut entry = len.to_le_bytes().to_vec();
+        entry.extend_from_slice(&bytes);
+        self.file.write_all(&entry)
+    }
+
+    /// Flushes all vertices appended so far to disk.
+    pub(crate) fn sync(&mut self) -> io::Result<()> {
+        self.file.sync_data()
+    }
+
+    /// Returns the path of the log file.
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Decodes the entry at the beginning of `bytes`, and returns it together with the bytes
+    /// following it, or `None` if there is no complete, valid entry.
+    fn read_entry<C: Context>(bytes: &[u8]) -> Option<(Vertex<C>, &[u8])> {
+        let len_bytes = bytes.get(..LENGTH_PREFIX_SIZE)?.try_into().ok()?;
+        let len = usize::try_from(u32::from_le_bytes(len_bytes)).ok()?;
+        let rest = bytes.get(LENGTH_PREFIX_SIZE..)?;
+        let vertex = bincode::deserialize(rest.get(..len)?).ok()?;
+        Some((vertex, rest.get(len..)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::{
+        components::consensus::highway_core::{
+            highway::Ping,
+            highway_testing::TEST_INSTANCE_ID,
+            state::tests::{TestContext, ALICE, ALICE_SEC, BOB, BOB_SEC},
+        },
+        types::Timestamp,
+    };
+
+    #[test]
+    fn should_replay_logged_vertices_and_discard_incomplete_entry() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("wal.dat");
+        let ping0 = Vertex::<TestContext>::Ping(
+            Ping::new(ALICE, Timestamp::from(1), TEST_INSTANCE_ID, &ALICE_SEC).unwrap(),
+        );
+        let ping1 = Vertex::<TestContext>::Ping(
+            Ping::new(BOB, Timestamp::from(2), TEST_INSTANCE_ID, &BOB_SEC).unwrap(),
+        );
+
+        let (mut wal, vertices) = WriteAheadLog::open::<TestContext, _>(&path).unwrap();
+        assert!(vertices.is_empty());
+        wal.append(&ping0).unwrap();
+        wal.append(&ping1).unwrap();
+        wal.sync().unwrap();
+        drop(wal);
+
+        // Simulate a crash in the middle of writing a third entry.
+        let full_len = fs::metadata(&path).unwrap().len();
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(&[100, 0, 0, 0, 1, 2]).unwrap();
+        drop(file);
+
+        let (mut wal, vertices) = WriteAheadLog::open::<TestContext, _>(&path).unwrap();
+        assert_eq!(vec![ping0.clone(), ping1.clone()], vertices);
+        assert_eq!(full_len, fs::metadata(&path).unwrap().len());
+
+        // New entries are appended after the last complete one.
+        wal.append(&ping0).unwrap();
+        drop(wal);
+        let (_, vertices) = WriteAheadLog::open::<TestContext, _>(&path).unwrap();
+        assert_eq!(vec![ping0.clone(), ping1, ping0], vertices);
+    }
+}
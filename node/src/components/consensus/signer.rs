@@ -0,0 +1,543 @@
+//! Signing of Highway vertices, finality signatures and network handshakes on behalf of the
+//! validator.
+//!
+//! The validator's secret key is either loaded into the node, or held by an external signer process
+//! which the node reaches over a Unix socket. The external signer is sent one JSON-encoded
+//! `ExternalRequest` per connection, terminated by a newline, and replies with a JSON-encoded
+//! `ExternalResponse`, also terminated by a newline. Talking to the external signer is
+//! asynchronous, so it must not be done on the reactor thread: use `Signer::sign` in an effect.
+//!
+//! Regardless of the backend, the `Signer` refuses to sign a unit unless its sequence number is
+//! higher than that of every unit it signed before in the same Highway instance. The sequence
+//! numbers are kept in memory only; after a restart they are restored from the unit files via
+//! `Signer::restore_last_unit`.
+
+use std::{
+    collections::{HashMap, HashSet},
+    io,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::UnixStream,
+};
+
+use casper_hashing::Digest;
+use casper_types::{EraId, PublicKey, SecretKey, Signature};
+
+use crate::{
+    crypto,
+    types::{BlockHash, FinalitySignature},
+};
+
+/// An error signing a message.
+#[derive(Debug, Error)]
+pub(crate) enum Error {
+    /// A unit with the same or a higher sequence number has already been signed.
+    #[error(
+        "refusing to sign unit {seq_number} in instance {instance_id}, already signed unit \
+        {last_seq_number}"
+    )]
+    DoubleSign {
+        instance_id: Digest,
+        seq_number: u64,
+        last_seq_number: u64,
+    },
+    /// The secret key is held by an external signer, so the message can't be signed synchronously.
+    #[error("cannot sign synchronously with an external signer")]
+    NotLocal,
+    /// Connecting to the external signer failed.
+    #[error("could not connect to external signer: {0}")]
+    Connect(io::Error),
+    /// Communicating with the external signer failed.
+    #[error("could not communicate with external signer: {0}")]
+    Io(#[from] io::Error),
+    /// The external signer didn't respond in time.
+    #[error("external signer did not respond within {0:?}")]
+    Timeout(Duration),
+    /// The message to or from the external signer could not be encoded or decoded.
+    #[error("invalid message to or from external signer: {0}")]
+    Json(#[from] serde_json::Error),
+    /// The external signer refused to sign.
+    #[error("external signer refused to sign: {0}")]
+    Refused(String),
+    /// The external signer returned a signature which doesn't match the message or public key.
+    #[error("external signer returned an invalid signature: {0}")]
+    InvalidSignature(crypto::Error),
+}
+
+/// A message to be signed by the validator.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) enum SigningRequest {
+    /// A Highway unit.
+    Unit {
+        instance_id: Digest,
+        seq_number: u64,
+        hash: Digest,
+    },
+    /// A Highway vertex other than a unit, i.e. a ping or an endorsement.
+    Vertex { instance_id: Digest, hash: Digest },
+    /// A finality signature for a block.
+    FinalitySignature {
+        era_id: EraId,
+        block_hash: BlockHash,
+    },
+    /// The consensus certificate in a network handshake, proving to the peer that we are a
+    /// validator.
+    Handshake { connection_id: Digest },
+}
+
+impl SigningRequest {
+    /// Returns the bytes to be signed: the hash for Highway vertices, the block hash followed by
+    /// the little-endian era ID for finality signatures, and the connection ID for handshakes.
+    pub(crate) fn message(&self) -> Vec<u8> {
+        match self {
+            SigningRequest::Unit { hash, .. } | SigningRequest::Vertex { hash, .. } => {
+                hash.into_vec()
+            }
+            SigningRequest::FinalitySignature { era_id, block_hash } => {
+                FinalitySignature::bytes_to_sign(block_hash, *era_id)
+            }
+            SigningRequest::Handshake { connection_id } => connection_id.into_vec(),
+        }
+    }
+
+    /// Returns the instance ID and sequence number if this is a unit.
+    fn unit(&self) -> Option<(Digest, u64)> {
+        match self {
+            SigningRequest::Unit {
+                instance_id,
+                seq_number,
+                ..
+            } => Some((*instance_id, *seq_number)),
+            SigningRequest::Vertex { .. }
+            | SigningRequest::FinalitySignature { .. }
+            | SigningRequest::Handshake { .. } => None,
+        }
+    }
+}
+
+/// A request sent to the external signer.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) struct ExternalRequest {
+    /// The public key of the validator whose secret key should be used.
+    pub(crate) public_key: PublicKey,
+    /// The message to sign.
+    pub(crate) request: SigningRequest,
+}
+
+/// The external signer's response to an `ExternalRequest`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) enum ExternalResponse {
+    /// The signature of the requested message.
+    Signature(Signature),
+    /// The reason the external signer refused to sign.
+    Error(String),
+}
+
+/// A signer process holding the secret key, reached over a Unix socket.
+struct ExternalSigner {
+    /// The path of the socket the external signer is listening on.
+    socket_path: PathBuf,
+    /// The timeout for the whole exchange with the external signer, including connecting.
+    timeout: Duration,
+}
+
+impl ExternalSigner {
+    async fn sign(
+        &self,
+        public_key: &PublicKey,
+        request: &SigningRequest,
+    ) -> Result<Signature, Error> {
+        let signature = tokio::time::timeout(self.timeout, self.exchange(public_key, request))
+            .await
+            .map_err(|_elapsed| Error::Timeout(self.timeout))??;
+        crypto::verify(request.message(), &signature, public_key)
+            .map_err(Error::InvalidSignature)?;
+        Ok(signature)
+    }
+
+    /// Sends the request to the external signer and reads its response.
+    async fn exchange(
+        &self,
+        public_key: &PublicKey,
+        request: &SigningRequest,
+    ) -> Result<Signature, Error> {
+        let mut stream = UnixStream::connect(&self.socket_path)
+            .await
+            .map_err(Error::Connect)?;
+        let external_request = ExternalRequest {
+            public_key: public_key.clone(),
+            request: request.clone(),
+        };
+        let mut line = serde_json::to_vec(&external_request)?;
+        line.push(b'\n');
+        stream.write_all(&line).await?;
+
+        let mut response = String::new();
+        BufReader::new(stream).read_line(&mut response).await?;
+        match serde_json::from_str(&response)? {
+            ExternalResponse::Signature(signature) => Ok(signature),
+            ExternalResponse::Error(reason) => Err(Error::Refused(reason)),
+        }
+    }
+}
+
+/// A holder of the validator's secret key.
+enum Backend {
+    /// The secret key is held in process memory.
+    Local(Arc<SecretKey>),
+    /// The secret key is held by an external signer process.
+    External(ExternalSigner),
+}
+
+/// Signs messages on behalf of the validator, refusing to sign conflicting units.
+pub(crate) struct Signer {
+    backend: Backend,
+    public_key: PublicKey,
+    /// The sequence number of the last unit signed in each Highway instance.
+    last_signed_units: Mutex<HashMap<Digest, u64>>,
+}
+
+impl Signer {
+    /// Creates a signer using the given secret key.
+    pub(crate) fn local(secret_key: Arc<SecretKey>, public_key: PublicKey) -> Self {
+        Self::new(Backend::Local(secret_key), public_key)
+    }
+
+    /// Creates a signer using the external signer listening on the given socket.
+    pub(crate) fn external(socket_path: PathBuf, timeout: Duration, public_key: PublicKey) -> Self {
+        Self::new(
+            Backend::External(ExternalSigner {
+                socket_path,
+                timeout,
+            }),
+            public_key,
+        )
+    }
+
+    fn new(backend: Backend, public_key: PublicKey) -> Self {
+        Signer {
+            backend,
+            public_key,
+            last_signed_units: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns the validator's public key.
+    pub(crate) fn public_key(&self) -> &PublicKey {
+        &self.public_key
+    }
+
+    /// Returns whether the secret key is held by an external signer, i.e. whether messages can
+    /// only be signed asynchronously.
+    pub(crate) fn is_external(&self) -> bool {
+        matches!(self.backend, Backend::External(_))
+    }
+
+    /// Signs the request's message with the secret key held in memory.
+    ///
+    /// Returns `Error::NotLocal` if the secret key is held by an external signer. Units are only
+    /// signed if their sequence number is higher than that of every unit signed before in the same
+    /// instance.
+    pub(crate) fn sign_local(&self, request: &SigningRequest) -> Result<Signature, Error> {
+        let secret_key = match &self.backend {
+            Backend::Local(secret_key) => secret_key,
+            Backend::External(_) => return Err(Error::NotLocal),
+        };
+        let sign = || crypto::sign(request.message(), secret_key, &self.public_key);
+        let (instance_id, seq_number) = match request.unit() {
+            Some(unit) => unit,
+            None => return Ok(sign()),
+        };
+        let mut last_signed_units = self.last_signed_units.lock().unwrap();
+        Self::check_seq_number(&last_signed_units, instance_id, seq_number)?;
+        last_signed_units.insert(instance_id, seq_number);
+        Ok(sign())
+    }
+
+    /// Signs the request's message, using whichever backend holds the secret key.
+    ///
+    /// Units are only signed if their sequence number is higher than that of every unit signed
+    /// before in the same instance. A unit counts as signed unless the external signer couldn't be
+    /// reached or refused to sign it.
+    pub(crate) async fn sign(&self, request: &SigningRequest) -> Result<Signature, Error> {
+        let external_signer = match &self.backend {
+            Backend::Local(_) => return self.sign_local(request),
+            Backend::External(external_signer) => external_signer,
+        };
+        let (instance_id, seq_number) = match request.unit() {
+            Some(unit) => unit,
+            None => return external_signer.sign(&self.public_key, request).await,
+        };
+        // Reserve the sequence number before talking to the external signer, so that two
+        // conflicting units can't be signed concurrently.
+        let previous = {
+            let mut last_signed_units = self.last_signed_units.lock().unwrap();
+            Self::check_seq_number(&last_signed_units, instance_id, seq_number)?;
+            last_signed_units.insert(instance_id, seq_number)
+        };
+        let result = external_signer.sign(&self.public_key, request).await;
+        // Once the request has been sent, the external signer may have signed the unit even if we
+        // didn't get the signature, so the reservation is only released if it can't have.
+        if matches!(result, Err(Error::Connect(_)) | Err(Error::Refused(_))) {
+            // Release the reservation, unless a later unit has been signed in the meantime.
+            let mut last_signed_units = self.last_signed_units.lock().unwrap();
+            if last_signed_units.get(&instance_id) == Some(&seq_number) {
+                match previous {
+                    Some(previous) => last_signed_units.insert(instance_id, previous),
+                    None => last_signed_units.remove(&instance_id),
+                };
+            }
+        }
+        result
+    }
+
+    /// Records that a unit with the given sequence number was signed in the instance before, e.g.
+    /// as read from the unit file after a restart.
+    pub(crate) fn restore_last_unit(&self, instance_id: Digest, seq_number: u64) {
+        let mut last_signed_units = self.last_signed_units.lock().unwrap();
+        let last_seq_number = last_signed_units.entry(instance_id).or_insert(seq_number);
+        *last_seq_number = seq_number.max(*last_seq_number);
+    }
+
+    /// Forgets the signed units of all instances other than the given ones.
+    pub(crate) fn retain_instances(&self, instance_ids: &HashSet<Digest>) {
+        self.last_signed_units
+            .lock()
+            .unwrap()
+            .retain(|instance_id, _| instance_ids.contains(instance_id));
+    }
+
+    /// Creates our finality signature for the given block.
+    pub(crate) async fn finality_signature(
+        &self,
+        block_hash: BlockHash,
+        era_id: EraId,
+    ) -> Result<FinalitySignature, Error> {
+        let signature = self
+            .sign(&SigningRequest::FinalitySignature { era_id, block_hash })
+            .await?;
+        Ok(FinalitySignature {
+            block_hash,
+            era_id,
+            signature,
+            public_key: self.public_key.clone(),
+        })
+    }
+
+    /// Returns an error if a unit with the same or a higher sequence number has been signed.
+    fn check_seq_number(
+        last_signed_units: &HashMap<Digest, u64>,
+        instance_id: Digest,
+        seq_number: u64,
+    ) -> Result<(), Error> {
+        match last_signed_units.get(&instance_id) {
+            Some(last_seq_number) if seq_number <= *last_seq_number => Err(Error::DoubleSign {
+                instance_id,
+                seq_number,
+                last_seq_number: *last_seq_number,
+            }),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{BufRead, BufReader, Write},
+        iter,
+        os::unix::net::UnixListener,
+        thread,
+    };
+
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::crypto::generate_ed25519_keypair;
+
+    /// Serves the given number of requests as an external signer holding `secret_key`.
+    fn serve_external_signer(listener: UnixListener, secret_key: SecretKey, requests: usize) {
+        for stream in listener.incoming().take(requests) {
+            let stream = stream.unwrap();
+            let mut line = String::new();
+            BufReader::new(&stream).read_line(&mut line).unwrap();
+            let ExternalRequest {
+                public_key,
+                request,
+            } = serde_json::from_str(&line).unwrap();
+            let signature = crypto::sign(request.message(), &secret_key, &public_key);
+            let mut line = serde_json::to_vec(&ExternalResponse::Signature(signature)).unwrap();
+            line.push(b'\n');
+            (&stream).write_all(&line).unwrap();
+        }
+    }
+
+    fn unit(instance_id: Digest, seq_number: u64) -> SigningRequest {
+        SigningRequest::Unit {
+            instance_id,
+            seq_number,
+            hash: Digest::hash(&seq_number.to_le_bytes()),
+        }
+    }
+
+    #[test]
+    fn should_refuse_to_sign_conflicting_units() {
+        let (secret_key, public_key) = generate_ed25519_keypair();
+        let signer = Signer::local(Arc::new(secret_key), public_key);
+        let era0 = Digest::hash(&[0]);
+        let era1 = Digest::hash(&[1]);
+
+        assert!(signer.sign_local(&unit(era0, 0)).is_ok());
+        assert!(signer.sign_local(&unit(era0, 1)).is_ok());
+        assert!(matches!(
+            signer.sign_local(&unit(era0, 1)),
+            Err(Error::DoubleSign {
+                seq_number: 1,
+                last_seq_number: 1,
+                ..
+            })
+        ));
+        assert!(matches!(
+            signer.sign_local(&unit(era0, 0)),
+            Err(Error::DoubleSign { .. })
+        ));
+        // Units in other instances and other vertices are unaffected.
+        assert!(signer.sign_local(&unit(era1, 0)).is_ok());
+        let ping = SigningRequest::Vertex {
+            instance_id: era0,
+            hash: Digest::hash(&[2]),
+        };
+        assert!(signer.sign_local(&ping).is_ok());
+        assert!(signer.sign_local(&ping).is_ok());
+    }
+
+    #[test]
+    fn should_restore_and_prune_signed_units() {
+        let (secret_key, public_key) = generate_ed25519_keypair();
+        let signer = Signer::local(Arc::new(secret_key), public_key);
+        let era0 = Digest::hash(&[0]);
+        let era1 = Digest::hash(&[1]);
+
+        signer.restore_last_unit(era0, 5);
+        signer.restore_last_unit(era0, 3);
+        signer.restore_last_unit(era1, 2);
+        assert!(matches!(
+            signer.sign_local(&unit(era0, 5)),
+            Err(Error::DoubleSign {
+                last_seq_number: 5,
+                ..
+            })
+        ));
+        assert!(signer.sign_local(&unit(era0, 6)).is_ok());
+
+        signer.retain_instances(&iter::once(era0).collect());
+        assert!(signer.sign_local(&unit(era0, 6)).is_err());
+        assert!(signer.sign_local(&unit(era1, 0)).is_ok());
+    }
+
+    #[tokio::test]
+    async fn should_sign_with_external_signer() {
+        let dir = tempdir().unwrap();
+        let socket_path = dir.path().join("signer.sock");
+        let listener = UnixListener::bind(&socket_path).unwrap();
+        let (secret_key, public_key) = generate_ed25519_keypair();
+        let server = thread::spawn(move || serve_external_signer(listener, secret_key, 2));
+        let signer = Signer::external(socket_path, Duration::from_secs(10), public_key);
+        assert!(matches!(
+            signer.sign_local(&unit(Digest::hash(&[5]), 0)),
+            Err(Error::NotLocal)
+        ));
+
+        let block_hash = BlockHash::new(Digest::hash(&[3]));
+        let finality_signature = signer
+            .finality_signature(block_hash, EraId::new(4))
+            .await
+            .unwrap();
+        assert!(finality_signature.verify().is_ok());
+
+        let instance_id = Digest::hash(&[5]);
+        let signature = signer.sign(&unit(instance_id, 0)).await.unwrap();
+        assert!(crypto::verify(
+            unit(instance_id, 0).message(),
+            &signature,
+            signer.public_key()
+        )
+        .is_ok());
+        server.join().unwrap();
+
+        // The double-sign protection doesn't depend on the external signer.
+        assert!(matches!(
+            signer.sign(&unit(instance_id, 0)).await,
+            Err(Error::DoubleSign { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn should_reject_signature_for_wrong_key() {
+        let dir = tempdir().unwrap();
+        let socket_path = dir.path().join("signer.sock");
+        let listener = UnixListener::bind(&socket_path).unwrap();
+        let (other_secret_key, _) = generate_ed25519_keypair();
+        let (_, public_key) = generate_ed25519_keypair();
+        let server = thread::spawn(move || serve_external_signer(listener, other_secret_key, 1));
+        let signer = Signer::external(socket_path, Duration::from_secs(10), public_key);
+
+        let result = signer
+            .finality_signature(BlockHash::new(Digest::hash(&[6])), EraId::new(7))
+            .await;
+        assert!(matches!(result, Err(Error::InvalidSignature(_))));
+        server.join().unwrap();
+    }
+
+    #[tokio::test]
+    async fn should_release_unit_if_not_connected() {
+        let dir = tempdir().unwrap();
+        // Nothing is listening on the socket.
+        let socket_path = dir.path().join("signer.sock");
+        let (_, public_key) = generate_ed25519_keypair();
+        let signer = Signer::external(socket_path, Duration::from_secs(10), public_key);
+
+        let instance_id = Digest::hash(&[9]);
+        assert!(matches!(
+            signer.sign(&unit(instance_id, 0)).await,
+            Err(Error::Connect(_))
+        ));
+        // The request never reached the external signer, so the unit can be signed again.
+        assert!(matches!(
+            signer.sign(&unit(instance_id, 0)).await,
+            Err(Error::Connect(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn should_time_out_and_keep_unit_reserved() {
+        let dir = tempdir().unwrap();
+        let socket_path = dir.path().join("signer.sock");
+        // The listener never accepts the connection, so no response ever arrives.
+        let _listener = UnixListener::bind(&socket_path).unwrap();
+        let (_, public_key) = generate_ed25519_keypair();
+        let signer = Signer::external(socket_path, Duration::from_millis(100), public_key);
+
+        let instance_id = Digest::hash(&[8]);
+        assert!(matches!(
+            signer.sign(&unit(instance_id, 0)).await,
+            Err(Error::Timeout(_))
+        ));
+        // The external signer may still sign the timed out unit, so it mustn't be signed again.
+        assert!(matches!(
+            signer.sign(&unit(instance_id, 0)).await,
+            Err(Error::DoubleSign {
+                seq_number: 0,
+                last_seq_number: 0,
+                ..
+            })
+        ));
+    }
+}
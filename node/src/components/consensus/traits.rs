@@ -48,7 +48,20 @@ pub trait ValidatorSecret: Send + DataSize {
 
     type Signature: Eq + PartialEq + Clone + Debug + Hash + Serialize + DeserializeOwned + DataSize;
 
-    fn sign(&self, hash: &Self::Hash) -> Self::Signature;
+    /// Signs the hash, or returns `None` if the signature could not be created.
+    fn sign(&self, hash: &Self::Hash) -> Option<Self::Signature>;
+
+    /// Signs the hash of a unit with the given sequence number, or returns `None` if the
+    /// signature could not be created or the signer refuses to sign the unit.
+    fn sign_unit(&self, hash: &Self::Hash, _seq_number: u64) -> Option<Self::Signature> {
+        self.sign(hash)
+    }
+
+    /// Returns whether the secret key is held outside of the node. In that case `sign` and
+    /// `sign_unit` must not be called; signatures are requested asynchronously instead.
+    fn is_remote(&self) -> bool {
+        false
+    }
 }
 
 /// The collection of types the user can choose for cryptography, IDs, transactions, etc.
@@ -4,13 +4,15 @@ mod config;
 mod error;
 mod metrics;
 mod operations;
+mod trie_pruning;
 mod types;
 
 use once_cell::sync::Lazy;
 use std::{
     collections::BTreeMap,
     fmt::{self, Debug, Formatter},
-    path::Path,
+    mem,
+    path::{Path, PathBuf},
     sync::{Arc, Mutex},
     time::Instant,
 };
@@ -19,16 +21,17 @@ use datasize::DataSize;
 use lmdb::DatabaseFlags;
 use prometheus::Registry;
 use serde::Serialize;
-use tracing::{debug, info, trace};
+use tracing::{debug, error, info, trace};
 
 use casper_execution_engine::{
     core::engine_state::{
-        self, genesis::GenesisSuccess, EngineConfig, EngineState, GetEraValidatorsError,
-        GetEraValidatorsRequest, UpgradeConfig, UpgradeSuccess,
+        self, genesis::GenesisSuccess, DeployItem, EngineConfig, EngineState, ExecuteRequest,
+        GetEraValidatorsError, GetEraValidatorsRequest, UpgradeConfig, UpgradeSuccess,
     },
     shared::{newtypes::CorrelationId, system_config::SystemConfig, wasm_config::WasmConfig},
     storage::{
-        global_state::lmdb::LmdbGlobalState, transaction_source::lmdb::LmdbEnvironment,
+        global_state::lmdb::{LmdbGlobalState, TriePruningBatch, TriePruningProgress},
+        transaction_source::lmdb::LmdbEnvironment,
         trie_store::lmdb::LmdbTrieStore,
     },
 };
@@ -50,8 +53,9 @@ pub(crate) use config::Config;
 pub(crate) use error::{BlockExecutionError, ConfigError};
 use metrics::Metrics;
 pub use operations::execute_finalized_block;
-pub use types::BlockAndExecutionEffects;
+use trie_pruning::RetainedStateRoots;
 pub(crate) use types::EraValidatorsRequest;
+pub use types::{BlockAndExecutionEffects, SpeculativeExecutionResult};
 
 /// Maximum number of resource intensive tasks that can be run in parallel.
 ///
@@ -136,9 +140,16 @@ pub(crate) struct ContractRuntime {
     engine_state: Arc<EngineState<LmdbGlobalState>>,
     metrics: Arc<Metrics>,
     protocol_version: ProtocolVersion,
+    config: Config,
 
     /// Finalized blocks waiting for their pre-state hash to start executing.
     exec_queue: ExecQueue,
+    /// The state roots whose tries are retained by trie pruning.
+    retained_state_roots: Arc<Mutex<RetainedStateRoots>>,
+    /// The directory holding global state, in which trie pruning marks the reachable tries.
+    storage_dir: PathBuf,
+    /// Whether the gas charged to speculatively executed deploys is profiled.
+    gas_profiling_enabled: bool,
 }
 
 impl Debug for ContractRuntime {
@@ -321,9 +332,12 @@ where
                 );
                 let engine_state = Arc::clone(&self.engine_state);
                 let metrics = Arc::clone(&self.metrics);
+                let retained_state_roots = Arc::clone(&self.retained_state_roots);
                 async move {
                     let result = run_intensive_task(move || {
-                        execute_finalized_block(
+                        // Hold the lock until the new state root is recorded, see `trie_pruning`.
+                        let mut retained_state_roots = retained_state_roots.lock().unwrap();
+                        let result = execute_finalized_block(
                             engine_state.as_ref(),
                             Some(metrics),
                             protocol_version,
@@ -331,7 +345,11 @@ where
                             finalized_block,
                             deploys,
                             transfers,
-                        )
+                        );
+                        if let Ok(BlockAndExecutionEffects { block, .. }) = &result {
+                            retained_state_roots.push(*block.state_root_hash());
+                        }
+                        result
                     })
                     .await;
                     trace!(?result, "execute block response");
@@ -339,6 +357,40 @@ where
                 }
                 .ignore()
             }
+            ContractRuntimeRequest::SpeculativeDeployExecution {
+                state_root_hash,
+                block_time,
+                protocol_version,
+                proposer,
+                deploy,
+                responder,
+            } => {
+                trace!(%state_root_hash, ?deploy, "speculative deploy execution request");
+                let engine_state = Arc::clone(&self.engine_state);
+                let metrics = Arc::clone(&self.metrics);
+                let profile_gas = self.gas_profiling_enabled;
+                async move {
+                    let execute_request = ExecuteRequest::new(
+                        state_root_hash,
+                        block_time,
+                        vec![DeployItem::from(*deploy)],
+                        protocol_version,
+                        proposer,
+                    );
+                    let result = run_intensive_task(move || {
+                        operations::execute_only(
+                            engine_state.as_ref(),
+                            Some(metrics),
+                            execute_request,
+                            profile_gas,
+                        )
+                    })
+                    .await;
+                    trace!(?result, "speculative deploy execution response");
+                    responder.respond(result).await
+                }
+                .ignore()
+            }
             ContractRuntimeRequest::EnqueueBlockForExecution {
                 finalized_block,
                 deploys,
@@ -350,6 +402,7 @@ where
                 let metrics = Arc::clone(&self.metrics);
                 let exec_queue = Arc::clone(&self.exec_queue);
                 let execution_pre_state = Arc::clone(&self.execution_pre_state);
+                let retained_state_roots = Arc::clone(&self.retained_state_roots);
                 let protocol_version = self.protocol_version;
                 if self.execution_pre_state.lock().unwrap().next_block_height
                     == finalized_block.height()
@@ -360,6 +413,7 @@ where
                             metrics,
                             exec_queue,
                             execution_pre_state,
+                            retained_state_roots,
                             effect_builder,
                             protocol_version,
                             finalized_block,
@@ -437,18 +491,29 @@ impl ContractRuntime {
             max_runtime_call_stack_height,
             wasm_config,
             system_config,
+        )
+        .with_max_wasm_module_cache_entries(
+            contract_runtime_config.max_wasm_module_cache_entries(),
         );
 
         let engine_state = Arc::new(EngineState::new(global_state, engine_config));
 
         let metrics = Arc::new(Metrics::new(registry)?);
 
+        let retained_state_roots = Arc::new(Mutex::new(RetainedStateRoots::new(
+            contract_runtime_config.trie_pruning_retained_state_roots(),
+        )));
+
         Ok(ContractRuntime {
             execution_pre_state,
             protocol_version,
+            config: *contract_runtime_config,
             exec_queue: Arc::new(Mutex::new(BTreeMap::new())),
+            retained_state_roots,
             engine_state,
             metrics,
+            storage_dir: storage_dir.to_path_buf(),
+            gas_profiling_enabled: contract_runtime_config.gas_profiling_enabled(),
         })
     }
 
@@ -462,23 +527,38 @@ impl ContractRuntime {
         let protocol_version = chainspec.protocol_config.version;
         // Transforms a chainspec into a valid genesis config for execution engine.
         let ee_config = chainspec.into();
-        self.engine_state.commit_genesis(
+        let mut retained_state_roots = self.retained_state_roots.lock().unwrap();
+        let result = self.engine_state.commit_genesis(
             correlation_id,
             genesis_config_hash,
             protocol_version,
             &ee_config,
-        )
+        );
+        if let Ok(GenesisSuccess {
+            post_state_hash, ..
+        }) = &result
+        {
+            retained_state_roots.push(*post_state_hash);
+        }
+        result
     }
 
-    fn commit_upgrade(
+    pub(crate) fn commit_upgrade(
         &self,
         upgrade_config: UpgradeConfig,
     ) -> Result<UpgradeSuccess, engine_state::Error> {
         debug!(?upgrade_config, "upgrade");
         let start = Instant::now();
+        let mut retained_state_roots = self.retained_state_roots.lock().unwrap();
         let result = self
             .engine_state
             .commit_upgrade(CorrelationId::new(), upgrade_config);
+        if let Ok(UpgradeSuccess {
+            post_state_hash, ..
+        }) = &result
+        {
+            retained_state_roots.push(*post_state_hash);
+        }
         self.metrics
             .commit_upgrade
             .observe(start.elapsed().as_secs_f64());
@@ -502,6 +582,172 @@ impl ContractRuntime {
         result
     }
 
+    /// Prunes all tries unreachable from the given state roots, running batches back to back.
+    ///
+    /// Must only be used while no other process is committing to global state.  Returns the number
+    /// of tries deleted.
+    pub(crate) fn prune_tries(
+        &self,
+        mut state_roots: Vec<Digest>,
+    ) -> Result<u64, engine_state::Error> {
+        let mut progress = Self::new_trie_pruning_progress(&self.storage_dir, &self.config)?;
+        let mut deleted = 0;
+        loop {
+            let batch = Self::prune_tries_batch(
+                &self.engine_state,
+                &self.metrics,
+                &mut progress,
+                mem::take(&mut state_roots),
+                self.config.trie_pruning_batch_size(),
+            )?;
+            deleted += batch.deleted as u64;
+            if batch.complete {
+                break;
+            }
+        }
+        self.engine_state
+            .flush_environment()
+            .map_err(|error| engine_state::Error::Storage(error.into()))?;
+        Ok(deleted)
+    }
+
+    /// Starts pruning tries unreachable from the most recent state roots in the background.
+    ///
+    /// `state_roots` are those of the highest stored blocks, ordered from the highest one.  After
+    /// each pruning run, the next one is started once the configured run interval has elapsed.
+    pub(crate) fn start_trie_pruning<REv: Send>(
+        &self,
+        effect_builder: EffectBuilder<REv>,
+        mut state_roots: Vec<Digest>,
+    ) -> Effects<ContractRuntimeRequest> {
+        // The pre-state of the next block is not yet the state root of any stored block after an
+        // upgrade, so it needs to be retained explicitly.
+        state_roots.insert(
+            0,
+            self.execution_pre_state.lock().unwrap().pre_state_root_hash,
+        );
+        self.retained_state_roots.lock().unwrap().reset(state_roots);
+
+        let engine_state = Arc::clone(&self.engine_state);
+        let metrics = Arc::clone(&self.metrics);
+        let retained_state_roots = Arc::clone(&self.retained_state_roots);
+        let storage_dir = self.storage_dir.clone();
+        let config = self.config;
+        async move {
+            loop {
+                Self::run_trie_pruning(
+                    Arc::clone(&engine_state),
+                    Arc::clone(&metrics),
+                    Arc::clone(&retained_state_roots),
+                    &storage_dir,
+                    effect_builder,
+                    config,
+                )
+                .await;
+                effect_builder
+                    .set_timeout(config.trie_pruning_run_interval().into())
+                    .await;
+            }
+        }
+        .ignore()
+    }
+
+    /// Runs a single pruning run, marking the retained state roots and then sweeping the trie store
+    /// in throttled batches.
+    async fn run_trie_pruning<REv>(
+        engine_state: Arc<EngineState<LmdbGlobalState>>,
+        metrics: Arc<Metrics>,
+        retained_state_roots: Arc<Mutex<RetainedStateRoots>>,
+        storage_dir: &Path,
+        effect_builder: EffectBuilder<REv>,
+        config: Config,
+    ) {
+        info!("starting to prune unreachable tries");
+        let mut progress = match Self::new_trie_pruning_progress(storage_dir, &config) {
+            Ok(progress) => progress,
+            Err(error) => {
+                error!(%error, "failed to start pruning unreachable tries");
+                return;
+            }
+        };
+        let mut state_roots = retained_state_roots.lock().unwrap().start_run();
+
+        let mut deleted = 0;
+        loop {
+            effect_builder
+                .set_timeout(config.trie_pruning_batch_interval().into())
+                .await;
+            let engine_state = Arc::clone(&engine_state);
+            let metrics = Arc::clone(&metrics);
+            let retained_state_roots = Arc::clone(&retained_state_roots);
+            let mut batch_state_roots = mem::take(&mut state_roots);
+            let (batch_progress, result) = run_intensive_task(move || {
+                // Hold the lock while pruning, so no commit can happen before its state root is
+                // marked, see `trie_pruning`.
+                let mut retained_state_roots = retained_state_roots.lock().unwrap();
+                batch_state_roots.extend(retained_state_roots.take_unmarked());
+                let result = Self::prune_tries_batch(
+                    &engine_state,
+                    &metrics,
+                    &mut progress,
+                    batch_state_roots,
+                    config.trie_pruning_batch_size(),
+                );
+                (progress, result)
+            })
+            .await;
+            progress = batch_progress;
+            match result {
+                Ok(batch) => {
+                    deleted += batch.deleted as u64;
+                    if batch.complete {
+                        info!(
+                            deleted,
+                            reachable = progress.reachable_count(),
+                            "finished pruning unreachable tries"
+                        );
+                        return;
+                    }
+                }
+                Err(error) => {
+                    error!(%error, "failed to prune unreachable tries");
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Creates the progress of a new pruning run, marking reachable tries in `storage_dir`.
+    fn new_trie_pruning_progress(
+        storage_dir: &Path,
+        config: &Config,
+    ) -> Result<TriePruningProgress, engine_state::Error> {
+        TriePruningProgress::new(storage_dir, config.max_global_state_size())
+            .map_err(engine_state::Error::Storage)
+    }
+
+    /// Runs a single batch of pruning unreachable tries, updating the metrics.
+    fn prune_tries_batch(
+        engine_state: &EngineState<LmdbGlobalState>,
+        metrics: &Metrics,
+        progress: &mut TriePruningProgress,
+        state_roots: Vec<Digest>,
+        batch_size: usize,
+    ) -> Result<TriePruningBatch, engine_state::Error> {
+        let start = Instant::now();
+        let result =
+            engine_state.prune_tries(CorrelationId::new(), progress, state_roots, batch_size);
+        metrics.prune_tries.observe(start.elapsed().as_secs_f64());
+        if let Ok(batch) = &result {
+            metrics.pruned_tries.inc_by(batch.deleted as u64);
+            metrics
+                .reachable_tries
+                .set(progress.reachable_count() as i64);
+            debug!(?batch, "ran trie pruning batch");
+        }
+        result
+    }
+
     pub(crate) fn set_initial_state(&mut self, sequential_block_state: ExecutionPreState) {
         *self.execution_pre_state.lock().unwrap() = sequential_block_state;
     }
@@ -512,6 +758,7 @@ impl ContractRuntime {
         metrics: Arc<Metrics>,
         exec_queue: ExecQueue,
         execution_pre_state: Arc<Mutex<ExecutionPreState>>,
+        retained_state_roots: Arc<Mutex<RetainedStateRoots>>,
         effect_builder: EffectBuilder<REv>,
         protocol_version: ProtocolVersion,
         finalized_block: FinalizedBlock,
@@ -529,7 +776,9 @@ impl ContractRuntime {
             execution_results,
             maybe_step_effect_and_upcoming_era_validators,
         } = match run_intensive_task(move || {
-            execute_finalized_block(
+            // Hold the lock until the new state root is recorded, see `trie_pruning`.
+            let mut retained_state_roots = retained_state_roots.lock().unwrap();
+            let result = execute_finalized_block(
                 engine_state.as_ref(),
                 Some(metrics),
                 protocol_version,
@@ -537,7 +786,11 @@ impl ContractRuntime {
                 finalized_block,
                 deploys,
                 transfers,
-            )
+            );
+            if let Ok(BlockAndExecutionEffects { block, .. }) = &result {
+                retained_state_roots.push(*block.state_root_hash());
+            }
+            result
         })
         .await
         {
@@ -1,12 +1,22 @@
 use datasize::DataSize;
 use serde::{Deserialize, Serialize};
 
-use casper_execution_engine::shared::utils;
+use casper_execution_engine::shared::{
+    utils, wasm_module_cache::DEFAULT_MAX_WASM_MODULE_CACHE_ENTRIES,
+};
+
+use crate::types::TimeDiff;
 
 const DEFAULT_MAX_GLOBAL_STATE_SIZE: usize = 805_306_368_000; // 750 GiB
 const DEFAULT_MAX_READERS: u32 = 512;
 const DEFAULT_MAX_QUERY_DEPTH: u64 = 5;
 const DEFAULT_MANUAL_SYNC_ENABLED: bool = true;
+const DEFAULT_TRIE_PRUNING_ENABLED: bool = false;
+const DEFAULT_TRIE_PRUNING_RETAINED_STATE_ROOTS: usize = 1_000;
+const DEFAULT_TRIE_PRUNING_BATCH_SIZE: usize = 10_000;
+const DEFAULT_TRIE_PRUNING_BATCH_INTERVAL: &str = "1sec";
+const DEFAULT_TRIE_PRUNING_RUN_INTERVAL: &str = "1hr";
+const DEFAULT_GAS_PROFILING_ENABLED: bool = false;
 
 /// Contract runtime configuration.
 #[derive(Clone, Copy, DataSize, Debug, Deserialize, Serialize)]
@@ -31,6 +41,34 @@ pub struct Config {
     ///
     /// Defaults to `false`.
     enable_manual_sync: Option<bool>,
+    /// Enable pruning tries unreachable from the retained state roots in the background.
+    ///
+    /// Defaults to `false`.
+    enable_trie_pruning: Option<bool>,
+    /// The number of highest blocks whose state roots are retained when pruning tries.
+    ///
+    /// Defaults to 1,000.
+    trie_pruning_retained_state_roots: Option<usize>,
+    /// The maximum number of tries marked as reachable or checked by a single pruning batch.
+    ///
+    /// Defaults to 10,000.
+    trie_pruning_batch_size: Option<usize>,
+    /// The delay between two pruning batches.
+    ///
+    /// Defaults to 1 second.
+    trie_pruning_batch_interval: Option<TimeDiff>,
+    /// The delay between the end of a pruning run and the start of the next one.
+    ///
+    /// Defaults to 1 hour.
+    trie_pruning_run_interval: Option<TimeDiff>,
+    /// The maximum number of deserialized stored contract modules kept in memory.
+    ///
+    /// Defaults to 256. Setting it to 0 disables the cache.
+    max_wasm_module_cache_entries: Option<usize>,
+    /// Enable profiling the gas charged by speculatively executed deploys.
+    ///
+    /// Defaults to `false`.
+    enable_gas_profiling: Option<bool>,
 }
 
 impl Config {
@@ -54,6 +92,42 @@ impl Config {
         self.enable_manual_sync
             .unwrap_or(DEFAULT_MANUAL_SYNC_ENABLED)
     }
+
+    pub(crate) fn trie_pruning_enabled(&self) -> bool {
+        self.enable_trie_pruning
+            .unwrap_or(DEFAULT_TRIE_PRUNING_ENABLED)
+    }
+
+    pub(crate) fn trie_pruning_retained_state_roots(&self) -> usize {
+        self.trie_pruning_retained_state_roots
+            .unwrap_or(DEFAULT_TRIE_PRUNING_RETAINED_STATE_ROOTS)
+    }
+
+    pub(crate) fn trie_pruning_batch_size(&self) -> usize {
+        self.trie_pruning_batch_size
+            .unwrap_or(DEFAULT_TRIE_PRUNING_BATCH_SIZE)
+            .max(1)
+    }
+
+    pub(crate) fn trie_pruning_batch_interval(&self) -> TimeDiff {
+        self.trie_pruning_batch_interval
+            .unwrap_or_else(|| DEFAULT_TRIE_PRUNING_BATCH_INTERVAL.parse().unwrap())
+    }
+
+    pub(crate) fn trie_pruning_run_interval(&self) -> TimeDiff {
+        self.trie_pruning_run_interval
+            .unwrap_or_else(|| DEFAULT_TRIE_PRUNING_RUN_INTERVAL.parse().unwrap())
+    }
+
+    pub(crate) fn max_wasm_module_cache_entries(&self) -> usize {
+        self.max_wasm_module_cache_entries
+            .unwrap_or(DEFAULT_MAX_WASM_MODULE_CACHE_ENTRIES)
+    }
+
+    pub(crate) fn gas_profiling_enabled(&self) -> bool {
+        self.enable_gas_profiling
+            .unwrap_or(DEFAULT_GAS_PROFILING_ENABLED)
+    }
 }
 
 impl Default for Config {
@@ -63,6 +137,13 @@ impl Default for Config {
             max_readers: Some(DEFAULT_MAX_READERS),
             max_query_depth: Some(DEFAULT_MAX_QUERY_DEPTH),
             enable_manual_sync: Some(DEFAULT_MANUAL_SYNC_ENABLED),
+            enable_trie_pruning: Some(DEFAULT_TRIE_PRUNING_ENABLED),
+            trie_pruning_retained_state_roots: Some(DEFAULT_TRIE_PRUNING_RETAINED_STATE_ROOTS),
+            trie_pruning_batch_size: Some(DEFAULT_TRIE_PRUNING_BATCH_SIZE),
+            trie_pruning_batch_interval: Some(DEFAULT_TRIE_PRUNING_BATCH_INTERVAL.parse().unwrap()),
+            trie_pruning_run_interval: Some(DEFAULT_TRIE_PRUNING_RUN_INTERVAL.parse().unwrap()),
+            max_wasm_module_cache_entries: Some(DEFAULT_MAX_WASM_MODULE_CACHE_ENTRIES),
+            enable_gas_profiling: Some(DEFAULT_GAS_PROFILING_ENABLED),
         }
     }
 }
@@ -1,4 +1,6 @@
-use prometheus::{self, Gauge, Histogram, IntGauge, Registry};
+use prometheus::{self, Gauge, Histogram, IntCounter, IntGauge, Registry};
+
+use casper_execution_engine::shared::wasm_module_cache::WasmModuleCache;
 
 use crate::{unregister_metric, utils};
 
@@ -61,6 +63,23 @@ const EXEC_BLOCK_HELP: &str = "time in seconds to execute all deploys in a block
 const LATEST_COMMIT_STEP_NAME: &str = "contract_runtime_latest_commit_step";
 const LATEST_COMMIT_STEP_HELP: &str = "duration in seconds of latest commit step at era end";
 
+const PRUNE_TRIES_NAME: &str = "contract_runtime_prune_tries";
+const PRUNE_TRIES_HELP: &str = "time in seconds to run a batch of pruning unreachable tries";
+
+const PRUNED_TRIES_NAME: &str = "contract_runtime_pruned_tries";
+const PRUNED_TRIES_HELP: &str = "number of unreachable tries deleted from global state";
+
+const REACHABLE_TRIES_NAME: &str = "contract_runtime_reachable_tries";
+const REACHABLE_TRIES_HELP: &str = "number of tries marked as reachable by the latest pruning run";
+
+const WASM_MODULE_CACHE_HITS_NAME: &str = "contract_runtime_wasm_module_cache_hits";
+const WASM_MODULE_CACHE_HITS_HELP: &str =
+    "number of stored contract modules served from the wasm module cache";
+
+const WASM_MODULE_CACHE_MISSES_NAME: &str = "contract_runtime_wasm_module_cache_misses";
+const WASM_MODULE_CACHE_MISSES_HELP: &str =
+    "number of stored contract modules missing from the wasm module cache";
+
 /// Metrics for the contract runtime component.
 #[derive(Debug)]
 pub struct Metrics {
@@ -79,6 +98,11 @@ pub struct Metrics {
     pub(super) chain_height: IntGauge,
     pub(super) exec_block: Histogram,
     pub(super) latest_commit_step: Gauge,
+    pub(super) prune_tries: Histogram,
+    pub(super) pruned_tries: IntCounter,
+    pub(super) reachable_tries: IntGauge,
+    pub(super) wasm_module_cache_hits: IntCounter,
+    pub(super) wasm_module_cache_misses: IntCounter,
     registry: Registry,
 }
 
@@ -97,6 +121,20 @@ impl Metrics {
         let latest_commit_step = Gauge::new(LATEST_COMMIT_STEP_NAME, LATEST_COMMIT_STEP_HELP)?;
         registry.register(Box::new(latest_commit_step.clone()))?;
 
+        let pruned_tries = IntCounter::new(PRUNED_TRIES_NAME, PRUNED_TRIES_HELP)?;
+        registry.register(Box::new(pruned_tries.clone()))?;
+
+        let reachable_tries = IntGauge::new(REACHABLE_TRIES_NAME, REACHABLE_TRIES_HELP)?;
+        registry.register(Box::new(reachable_tries.clone()))?;
+
+        let wasm_module_cache_hits =
+            IntCounter::new(WASM_MODULE_CACHE_HITS_NAME, WASM_MODULE_CACHE_HITS_HELP)?;
+        registry.register(Box::new(wasm_module_cache_hits.clone()))?;
+
+        let wasm_module_cache_misses =
+            IntCounter::new(WASM_MODULE_CACHE_MISSES_NAME, WASM_MODULE_CACHE_MISSES_HELP)?;
+        registry.register(Box::new(wasm_module_cache_misses.clone()))?;
+
         Ok(Metrics {
             run_execute: utils::register_histogram_metric(
                 registry,
@@ -175,12 +213,32 @@ impl Metrics {
                 registry,
                 EXEC_BLOCK_NAME,
                 EXEC_BLOCK_HELP,
-                common_buckets,
+                common_buckets.clone(),
             )?,
             latest_commit_step,
+            prune_tries: utils::register_histogram_metric(
+                registry,
+                PRUNE_TRIES_NAME,
+                PRUNE_TRIES_HELP,
+                common_buckets,
+            )?,
+            pruned_tries,
+            reachable_tries,
+            wasm_module_cache_hits,
+            wasm_module_cache_misses,
             registry: registry.clone(),
         })
     }
+
+    /// Brings the Wasm module cache counters up to the totals reported by the cache.
+    pub(super) fn update_wasm_module_cache(&self, wasm_module_cache: &WasmModuleCache) {
+        let hits = wasm_module_cache.hits();
+        self.wasm_module_cache_hits
+            .inc_by(hits.saturating_sub(self.wasm_module_cache_hits.get()));
+        let misses = wasm_module_cache.misses();
+        self.wasm_module_cache_misses
+            .inc_by(misses.saturating_sub(self.wasm_module_cache_misses.get()));
+    }
 }
 
 impl Drop for Metrics {
@@ -200,5 +258,10 @@ impl Drop for Metrics {
         unregister_metric!(self.registry, self.chain_height);
         unregister_metric!(self.registry, self.exec_block);
         unregister_metric!(self.registry, self.latest_commit_step);
+        unregister_metric!(self.registry, self.prune_tries);
+        unregister_metric!(self.registry, self.pruned_tries);
+        unregister_metric!(self.registry, self.reachable_tries);
+        unregister_metric!(self.registry, self.wasm_module_cache_hits);
+        unregister_metric!(self.registry, self.wasm_module_cache_misses);
     }
 }
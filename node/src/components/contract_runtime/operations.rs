@@ -1,18 +1,25 @@
+#[cfg(feature = "parallel-execution")]
+mod parallel;
+
 use std::{
     collections::{BTreeMap, HashMap, VecDeque},
+    io,
     sync::Arc,
     time::Instant,
 };
 
 use itertools::Itertools;
-use tracing::{debug, trace};
+use tracing::{debug, trace, warn};
 
+#[cfg(any(not(feature = "parallel-execution"), test))]
+use casper_execution_engine::core::engine_state::DeployItem;
 use casper_execution_engine::{
     core::engine_state::{
-        self, step::EvictItem, DeployItem, EngineState, ExecuteRequest,
+        self, step::EvictItem, EngineState, ExecuteRequest,
         ExecutionResult as EngineExecutionResult, ExecutionResults, GetEraValidatorsRequest,
         RewardItem, StepError, StepRequest, StepSuccess,
     },
+    core::runtime::gas_profiler::GasProfile,
     shared::{additive_map::AdditiveMap, newtypes::CorrelationId, transform::Transform},
     storage::global_state::lmdb::LmdbGlobalState,
 };
@@ -24,7 +31,7 @@ use crate::{
         consensus::EraReport,
         contract_runtime::{
             error::BlockExecutionError, types::StepEffectAndUpcomingEraValidators,
-            BlockAndExecutionEffects, ExecutionPreState, Metrics,
+            BlockAndExecutionEffects, ExecutionPreState, Metrics, SpeculativeExecutionResult,
         },
     },
     types::{Block, Deploy, DeployHash, DeployHeader, FinalizedBlock},
@@ -58,35 +65,31 @@ pub fn execute_finalized_block(
     // Run any deploys that must be executed
     let block_time = finalized_block.timestamp().millis();
     let start = Instant::now();
-    for deploy in deploys.into_iter().chain(transfers) {
-        let deploy_hash = *deploy.id();
-        let deploy_header = deploy.header().clone();
-        let execute_request = ExecuteRequest::new(
+    #[cfg(not(feature = "parallel-execution"))]
+    {
+        state_root_hash = execute_deploys_serially(
+            engine_state,
+            metrics.clone(),
             state_root_hash,
             block_time,
-            vec![DeployItem::from(deploy)],
             protocol_version,
-            finalized_block.proposer().clone(),
-        );
-
-        // TODO: this is currently working coincidentally because we are passing only one
-        // deploy_item per exec. The execution results coming back from the ee lacks the
-        // mapping between deploy_hash and execution result, and this outer logic is
-        // enriching it with the deploy hash. If we were passing multiple deploys per exec
-        // the relation between the deploy and the execution results would be lost.
-        let result = execute(engine_state, metrics.clone(), execute_request)?;
-
-        trace!(?deploy_hash, ?result, "deploy execution result");
-        // As for now a given state is expected to exist.
-        let (state_hash, execution_result) = commit_execution_effects(
+            &finalized_block.proposer(),
+            deploys.into_iter().chain(transfers),
+            &mut execution_results,
+        )?;
+    }
+    #[cfg(feature = "parallel-execution")]
+    {
+        state_root_hash = parallel::execute_deploys(
             engine_state,
             metrics.clone(),
             state_root_hash,
-            deploy_hash,
-            result,
+            block_time,
+            protocol_version,
+            &finalized_block.proposer(),
+            deploys.into_iter().chain(transfers).collect(),
+            &mut execution_results,
         )?;
-        execution_results.insert(deploy_hash, (deploy_header, execution_result));
-        state_root_hash = state_hash;
     }
 
     // Flush once, after all deploys have been executed.
@@ -94,6 +97,7 @@ pub fn execute_finalized_block(
 
     if let Some(metrics) = metrics.as_ref() {
         metrics.exec_block.observe(start.elapsed().as_secs_f64());
+        metrics.update_wasm_module_cache(engine_state.wasm_module_cache());
     }
 
     // If the finalized block has an era report, run the auction contract and get the upcoming era
@@ -160,6 +164,111 @@ pub fn execute_finalized_block(
     })
 }
 
+/// Executes the given deploys one after the other and commits their effects on top of
+/// `pre_state_root_hash`.
+///
+/// Returns the resulting state root hash.
+#[cfg(any(not(feature = "parallel-execution"), test))]
+#[allow(clippy::too_many_arguments)]
+fn execute_deploys_serially(
+    engine_state: &EngineState<LmdbGlobalState>,
+    metrics: Option<Arc<Metrics>>,
+    pre_state_root_hash: Digest,
+    block_time: u64,
+    protocol_version: ProtocolVersion,
+    proposer: &PublicKey,
+    deploys: impl IntoIterator<Item = Deploy>,
+    execution_results: &mut HashMap<DeployHash, (DeployHeader, ExecutionResult)>,
+) -> Result<Digest, BlockExecutionError> {
+    let mut state_root_hash = pre_state_root_hash;
+    for deploy in deploys {
+        let deploy_hash = *deploy.id();
+        let deploy_header = deploy.header().clone();
+        let execute_request = ExecuteRequest::new(
+            state_root_hash,
+            block_time,
+            vec![DeployItem::from(deploy)],
+            protocol_version,
+            proposer.clone(),
+        );
+
+        // TODO: this is currently working coincidentally because we are passing only one
+        // deploy_item per exec. The execution results coming back from the ee lacks the
+        // mapping between deploy_hash and execution result, and this outer logic is
+        // enriching it with the deploy hash. If we were passing multiple deploys per exec
+        // the relation between the deploy and the execution results would be lost.
+        let result = execute(engine_state, metrics.clone(), execute_request)?;
+
+        trace!(?deploy_hash, ?result, "deploy execution result");
+        // As for now a given state is expected to exist.
+        let (state_hash, execution_result) = commit_execution_effects(
+            engine_state,
+            metrics.clone(),
+            state_root_hash,
+            deploy_hash,
+            result,
+        )?;
+        execution_results.insert(deploy_hash, (deploy_header, execution_result));
+        state_root_hash = state_hash;
+    }
+    Ok(state_root_hash)
+}
+
+/// Executes a deploy without committing its effects to global state.
+///
+/// If `profile_gas` is true, the gas charged while executing the deploy is profiled and returned
+/// alongside the execution result in the folded stacks format.
+pub(super) fn execute_only(
+    engine_state: &EngineState<LmdbGlobalState>,
+    metrics: Option<Arc<Metrics>>,
+    execute_request: ExecuteRequest,
+    profile_gas: bool,
+) -> Result<Option<SpeculativeExecutionResult>, engine_state::Error> {
+    // There's only one deploy in the request, so we expect at most one result.
+    if profile_gas {
+        return execute_profiling_gas(engine_state, execute_request);
+    }
+    Ok(execute(engine_state, metrics, execute_request)?
+        .pop_front()
+        .map(|ee_execution_result| SpeculativeExecutionResult {
+            execution_result: ExecutionResult::from(&ee_execution_result),
+            gas_profile: None,
+        }))
+}
+
+/// Executes a single deploy while profiling the gas it is charged.
+///
+/// Failing to encode the profile is logged rather than failing the execution.
+fn execute_profiling_gas(
+    engine_state: &EngineState<LmdbGlobalState>,
+    execute_request: ExecuteRequest,
+) -> Result<Option<SpeculativeExecutionResult>, engine_state::Error> {
+    trace!(?execute_request, "execute profiling gas");
+    let mut results =
+        engine_state.run_execute_profiling_gas(CorrelationId::new(), execute_request)?;
+    let (ee_execution_result, gas_profile) = match results.pop() {
+        Some(result) => result,
+        None => return Ok(None),
+    };
+    let gas_profile = match folded_gas_profile(&gas_profile) {
+        Ok(folded) => Some(folded),
+        Err(error) => {
+            warn!(%error, "failed to encode gas profile");
+            None
+        }
+    };
+    Ok(Some(SpeculativeExecutionResult {
+        execution_result: ExecutionResult::from(&ee_execution_result),
+        gas_profile,
+    }))
+}
+
+fn folded_gas_profile(gas_profile: &GasProfile) -> io::Result<String> {
+    let mut folded = Vec::new();
+    gas_profile.write_folded(&mut folded)?;
+    String::from_utf8(folded).map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))
+}
+
 /// Commits the execution effects.
 fn commit_execution_effects(
     engine_state: &EngineState<LmdbGlobalState>,
@@ -0,0 +1,291 @@
+//! Optimistic parallel execution of the deploys of a block.
+//!
+//! All deploys are first executed at the same time against the block's pre-state, each recording
+//! the global state accesses it made. Their effects are then committed one by one in the
+//! canonical order. A deploy whose recorded accesses have the same outcome against the state left
+//! by the deploys committed before it would have produced the same execution result in a serial
+//! execution, so its speculative result is committed as is. Any other deploy is re-executed
+//! against that state, hence the committed effects and state root hash are identical to those of
+//! executing the deploys serially.
+//!
+//! Deploys conflict whenever they read a value changed by an earlier deploy of the block, e.g.
+//! the balance of a purse paid into by an earlier deploy, so the speed-up depends on the block.
+//! This is enabled via the `parallel-execution` feature, for benchmarking with `dry-run-deploys`.
+
+use std::{collections::HashMap, iter, panic, sync::Arc};
+
+use tracing::debug;
+
+use casper_execution_engine::{
+    core::engine_state::{
+        read_set::ReadSet, DeployItem, EngineState, ExecuteRequest,
+        ExecutionResult as EngineExecutionResult,
+    },
+    shared::newtypes::CorrelationId,
+    storage::global_state::lmdb::LmdbGlobalState,
+};
+use casper_hashing::Digest;
+use casper_types::{ExecutionResult, ProtocolVersion, PublicKey};
+
+use super::{commit_execution_effects, execute};
+use crate::{
+    components::contract_runtime::{error::BlockExecutionError, Metrics},
+    types::{Deploy, DeployHash, DeployHeader},
+};
+
+/// Executes the given deploys in parallel and commits their effects in order on top of
+/// `pre_state_root_hash`, re-executing the deploys which conflict with earlier ones.
+///
+/// Returns the resulting state root hash.
+#[allow(clippy::too_many_arguments)]
+pub(super) fn execute_deploys(
+    engine_state: &EngineState<LmdbGlobalState>,
+    metrics: Option<Arc<Metrics>>,
+    pre_state_root_hash: Digest,
+    block_time: u64,
+    protocol_version: ProtocolVersion,
+    proposer: &PublicKey,
+    deploys: Vec<Deploy>,
+    execution_results: &mut HashMap<DeployHash, (DeployHeader, ExecutionResult)>,
+) -> Result<Digest, BlockExecutionError> {
+    let speculative_results = execute_speculatively(
+        engine_state,
+        pre_state_root_hash,
+        block_time,
+        protocol_version,
+        proposer,
+        &deploys,
+    )?;
+
+    let deploy_count = deploys.len();
+    let mut reexecuted_count = 0;
+    let mut state_root_hash = pre_state_root_hash;
+    for (deploy, (speculative_result, read_set)) in deploys.into_iter().zip(speculative_results) {
+        let deploy_hash = *deploy.id();
+        let deploy_header = deploy.header().clone();
+        let result = if engine_state.has_same_read_outcome(
+            CorrelationId::new(),
+            &read_set,
+            pre_state_root_hash,
+            state_root_hash,
+        )? {
+            iter::once(speculative_result).collect()
+        } else {
+            reexecuted_count += 1;
+            let execute_request = ExecuteRequest::new(
+                state_root_hash,
+                block_time,
+                vec![DeployItem::from(deploy)],
+                protocol_version,
+                proposer.clone(),
+            );
+            execute(engine_state, metrics.clone(), execute_request)?
+        };
+
+        let (state_hash, execution_result) = commit_execution_effects(
+            engine_state,
+            metrics.clone(),
+            state_root_hash,
+            deploy_hash,
+            result,
+        )?;
+        execution_results.insert(deploy_hash, (deploy_header, execution_result));
+        state_root_hash = state_hash;
+    }
+
+    debug!(
+        "re-executed {} of {} deploys executed in parallel",
+        reexecuted_count, deploy_count
+    );
+    Ok(state_root_hash)
+}
+
+/// Executes all deploys against `pre_state_root_hash`, spread over one thread per CPU.
+///
+/// Returns the execution result and recorded accesses of each deploy, in the order of `deploys`.
+fn execute_speculatively(
+    engine_state: &EngineState<LmdbGlobalState>,
+    pre_state_root_hash: Digest,
+    block_time: u64,
+    protocol_version: ProtocolVersion,
+    proposer: &PublicKey,
+    deploys: &[Deploy],
+) -> Result<Vec<(EngineExecutionResult, ReadSet)>, BlockExecutionError> {
+    if deploys.is_empty() {
+        return Ok(Vec::new());
+    }
+    let thread_count = num_cpus::get().clamp(1, deploys.len());
+    let chunk_size = (deploys.len() + thread_count - 1) / thread_count;
+
+    let chunk_results = crossbeam_utils::thread::scope(|scope| {
+        let handles: Vec<_> = deploys
+            .chunks(chunk_size)
+            .map(|chunk| {
+                scope.spawn(move |_| {
+                    let execute_request = ExecuteRequest::new(
+                        pre_state_root_hash,
+                        block_time,
+                        chunk.iter().cloned().map(DeployItem::from).collect(),
+                        protocol_version,
+                        proposer.clone(),
+                    );
+                    engine_state.run_execute_recording_reads(CorrelationId::new(), execute_request)
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| {
+                handle
+                    .join()
+                    .unwrap_or_else(|panic| panic::resume_unwind(panic))
+            })
+            .collect::<Vec<_>>()
+    })
+    .unwrap_or_else(|panic| panic::resume_unwind(panic));
+
+    let mut results = Vec::with_capacity(deploys.len());
+    for chunk_result in chunk_results {
+        results.extend(chunk_result?);
+    }
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use prometheus::Registry;
+
+    use casper_execution_engine::core::engine_state::{
+        executable_deploy_item::ExecutableDeployItem, genesis::GenesisSuccess,
+    };
+    use casper_types::{runtime_args, Motes, RuntimeArgs, SecretKey, U512};
+
+    use super::*;
+    use crate::{
+        components::contract_runtime::{Config, ContractRuntime},
+        crypto::AsymmetricKeyExt,
+        testing::TestRng,
+        types::{
+            chainspec::{AccountConfig, AccountsConfig},
+            Chainspec, TimeDiff, Timestamp,
+        },
+        utils::Loadable,
+    };
+
+    const SENDER_COUNT: usize = 4;
+
+    fn transfer(sender: &SecretKey, target: &PublicKey, amount: u64, id: u64) -> Deploy {
+        let payment = ExecutableDeployItem::ModuleBytes {
+            module_bytes: Default::default(),
+            args: runtime_args! { "amount" => U512::from(100_000_000u64) },
+        };
+        let session = ExecutableDeployItem::Transfer {
+            args: runtime_args! {
+                "amount" => U512::from(amount),
+                "target" => target.to_account_hash(),
+                "id" => Some(id),
+            },
+        };
+        Deploy::new(
+            Timestamp::now(),
+            TimeDiff::from(60_000),
+            1,
+            vec![],
+            "casper-example".to_string(),
+            payment,
+            session,
+            sender,
+            None,
+        )
+    }
+
+    #[test]
+    fn should_produce_same_results_as_serial_execution() {
+        let mut rng = TestRng::new();
+        let senders: Vec<SecretKey> = (0..SENDER_COUNT)
+            .map(|_| SecretKey::random(&mut rng))
+            .collect();
+
+        let mut chainspec = Chainspec::from_resources("local");
+        let mut accounts = chainspec.network_config.accounts_config.accounts().to_vec();
+        accounts.extend(senders.iter().map(|sender| {
+            AccountConfig::new(
+                PublicKey::from(sender),
+                Motes::new(U512::from(1_000_000_000_000_000u64)),
+                None,
+            )
+        }));
+        chainspec.network_config.accounts_config = AccountsConfig::new(accounts, vec![]);
+
+        let storage_dir = tempfile::tempdir().unwrap();
+        let contract_runtime = ContractRuntime::new(
+            chainspec.protocol_config.version,
+            storage_dir.path(),
+            &Config::default(),
+            chainspec.wasm_config,
+            chainspec.system_costs_config,
+            chainspec.core_config.max_associated_keys,
+            chainspec.core_config.max_runtime_call_stack_height,
+            &Registry::new(),
+        )
+        .unwrap();
+        let GenesisSuccess {
+            post_state_hash, ..
+        } = contract_runtime.commit_genesis(&chainspec).unwrap();
+
+        // Senders transferring to each other, to a shared new account and more than once make
+        // some of the deploys conflict with earlier ones.
+        let shared_target = PublicKey::random(&mut rng);
+        let mut deploys = Vec::new();
+        for (index, sender) in senders.iter().enumerate() {
+            let next = PublicKey::from(&senders[(index + 1) % SENDER_COUNT]);
+            let unrelated = PublicKey::random(&mut rng);
+            deploys.push(transfer(sender, &next, 3_000_000_000, index as u64));
+            deploys.push(transfer(
+                sender,
+                &shared_target,
+                4_000_000_000,
+                index as u64,
+            ));
+            deploys.push(transfer(sender, &unrelated, 5_000_000_000, index as u64));
+        }
+
+        let proposer = PublicKey::from(&senders[0]);
+        let block_time = Timestamp::now().millis();
+        let protocol_version = chainspec.protocol_config.version;
+        let engine_state = &contract_runtime.engine_state;
+
+        let mut serial_results = HashMap::new();
+        let serial_state_root_hash = super::super::execute_deploys_serially(
+            engine_state,
+            None,
+            post_state_hash,
+            block_time,
+            protocol_version,
+            &proposer,
+            deploys.clone(),
+            &mut serial_results,
+        )
+        .unwrap();
+
+        let mut parallel_results = HashMap::new();
+        let parallel_state_root_hash = execute_deploys(
+            engine_state,
+            None,
+            post_state_hash,
+            block_time,
+            protocol_version,
+            &proposer,
+            deploys,
+            &mut parallel_results,
+        )
+        .unwrap();
+
+        assert_ne!(serial_state_root_hash, post_state_hash);
+        assert_eq!(parallel_state_root_hash, serial_state_root_hash);
+        assert_eq!(parallel_results, serial_results);
+        assert!(serial_results
+            .values()
+            .all(|(_, result)| matches!(result, ExecutionResult::Success { .. })));
+    }
+}
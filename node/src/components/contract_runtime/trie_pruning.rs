@@ -0,0 +1,94 @@
+//! Pruning of tries unreachable from the state roots of the most recent blocks.
+//!
+//! A pruning run works in throttled batches, first marking all tries reachable from the retained
+//! state roots in a scratch LMDB environment, then sweeping the trie store, deleting the tries not
+//! marked.  Every commit to global state records its new state root in `RetainedStateRoots` while
+//! holding its lock, and every batch holds the same lock while it marks the state roots recorded
+//! since the previous batch and sweeps, so tries written by a commit are never deleted before the
+//! commit's state root is marked.
+
+use std::{collections::VecDeque, mem};
+
+use datasize::DataSize;
+
+use casper_hashing::Digest;
+
+/// The state roots whose tries are retained by trie pruning.
+#[derive(DataSize, Debug)]
+pub(super) struct RetainedStateRoots {
+    /// The maximum number of recent state roots retained.
+    capacity: usize,
+    /// The most recent state roots, oldest first.
+    recent: VecDeque<Digest>,
+    /// The state roots recorded since the last pruning batch, which still need to be marked.
+    unmarked: Vec<Digest>,
+    /// Whether trie pruning has been started, i.e. whether unmarked state roots are recorded.
+    pruning: bool,
+}
+
+impl RetainedStateRoots {
+    /// Creates an empty set of retained state roots, holding at most `capacity` of them.
+    pub(super) fn new(capacity: usize) -> Self {
+        RetainedStateRoots {
+            capacity,
+            recent: VecDeque::new(),
+            unmarked: Vec::new(),
+            pruning: false,
+        }
+    }
+
+    /// Replaces the retained state roots with the given ones, ordered from newest to oldest, and
+    /// starts recording unmarked state roots for trie pruning.
+    pub(super) fn reset(&mut self, state_roots: Vec<Digest>) {
+        self.recent = state_roots.into_iter().take(self.capacity).rev().collect();
+        self.unmarked.clear();
+        self.pruning = true;
+    }
+
+    /// Records the state root of a new commit to global state.
+    pub(super) fn push(&mut self, state_root: Digest) {
+        if self.recent.back() != Some(&state_root) {
+            self.recent.push_back(state_root);
+            if self.recent.len() > self.capacity {
+                self.recent.pop_front();
+            }
+        }
+        if self.pruning {
+            self.unmarked.push(state_root);
+        }
+    }
+
+    /// Returns all retained state roots, to be marked at the start of a new pruning run.
+    pub(super) fn start_run(&mut self) -> Vec<Digest> {
+        self.unmarked.clear();
+        self.recent.iter().copied().collect()
+    }
+
+    /// Returns the state roots recorded since the last call, to be marked by the next batch.
+    pub(super) fn take_unmarked(&mut self) -> Vec<Digest> {
+        mem::take(&mut self.unmarked)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_retain_most_recent_state_roots() {
+        let roots: Vec<Digest> = (0u8..5).map(|i| Digest::hash(&[i])).collect();
+        let mut retained = RetainedStateRoots::new(3);
+
+        // Seeded newest first.
+        retained.reset(vec![roots[2], roots[1], roots[0]]);
+        retained.push(roots[3]);
+        retained.push(roots[3]);
+        retained.push(roots[4]);
+        assert_eq!(retained.take_unmarked(), vec![roots[3], roots[3], roots[4]]);
+        assert!(retained.take_unmarked().is_empty());
+
+        retained.push(roots[0]);
+        assert_eq!(retained.start_run(), vec![roots[3], roots[4], roots[0]]);
+        assert!(retained.take_unmarked().is_empty());
+    }
+}
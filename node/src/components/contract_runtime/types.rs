@@ -101,3 +101,13 @@ pub struct BlockAndExecutionEffects {
     /// The [`ExecutionJournal`] and the upcoming validator sets determined by the `step`
     pub maybe_step_effect_and_upcoming_era_validators: Option<StepEffectAndUpcomingEraValidators>,
 }
+
+/// The result of speculatively executing a deploy in the `ContractRuntime`.
+#[derive(Debug)]
+pub struct SpeculativeExecutionResult {
+    /// The result of executing the deploy.
+    pub execution_result: ExecutionResult,
+    /// The gas charged while executing the deploy per Wasm function and host function, in the
+    /// folded stacks format, if gas profiling is enabled.
+    pub gas_profile: Option<String>,
+}
@@ -28,13 +28,13 @@ use casper_types::{
 use crate::{
     components::Component,
     effect::{
-        announcements::DeployAcceptorAnnouncement,
+        announcements::{BlocklistAnnouncement, DeployAcceptorAnnouncement},
         requests::{ContractRuntimeRequest, StorageRequest},
         EffectBuilder, EffectExt, Effects, Responder,
     },
     types::{
         chainspec::DeployConfig, Block, Chainspec, Deploy, DeployConfigurationFailure, NodeId,
-        Timestamp,
+        OffenseSeverity, Timestamp,
     },
     utils::Source,
     NodeRng,
@@ -126,6 +126,7 @@ pub(crate) enum DeployParameterFailure {
 pub(crate) trait ReactorEventT:
     From<Event>
     + From<DeployAcceptorAnnouncement<NodeId>>
+    + From<BlocklistAnnouncement<NodeId>>
     + From<StorageRequest>
     + From<ContractRuntimeRequest>
     + Send
@@ -135,6 +136,7 @@ pub(crate) trait ReactorEventT:
 impl<REv> ReactorEventT for REv where
     REv: From<Event>
         + From<DeployAcceptorAnnouncement<NodeId>>
+        + From<BlocklistAnnouncement<NodeId>>
         + From<StorageRequest>
         + From<ContractRuntimeRequest>
         + Send
@@ -818,12 +820,25 @@ impl DeployAcceptor {
             // The client has submitted a deploy with one or more invalid signatures.
             // Return an error to the RPC component via the responder.
             debug!("deploy is cryptographically invalid");
-            return self.handle_invalid_deploy_result(
+            // A peer should never gossip a deploy which it could not have validated itself.
+            let maybe_offender = match event_metadata.source {
+                Source::Peer(peer) => Some(peer),
+                Source::Client | Source::Ourself => None,
+            };
+            let mut effects = self.handle_invalid_deploy_result(
                 effect_builder,
                 event_metadata,
                 Error::InvalidDeployConfiguration(deploy_configuration_failure),
                 verification_start_timestamp,
             );
+            if let Some(peer) = maybe_offender {
+                effects.extend(
+                    effect_builder
+                        .announce_offense(peer, OffenseSeverity::Major)
+                        .ignore(),
+                );
+            }
+            return effects;
         }
 
         effect_builder
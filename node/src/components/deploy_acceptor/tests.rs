@@ -28,7 +28,7 @@ use super::*;
 use crate::{
     components::storage::{self, Storage},
     effect::{
-        announcements::{ControlAnnouncement, DeployAcceptorAnnouncement},
+        announcements::{BlocklistAnnouncement, ControlAnnouncement, DeployAcceptorAnnouncement},
         requests::ContractRuntimeRequest,
         Responder,
     },
@@ -58,6 +58,8 @@ enum Event {
     #[from]
     DeployAcceptorAnnouncement(#[serde(skip_serializing)] DeployAcceptorAnnouncement<NodeId>),
     #[from]
+    BlocklistAnnouncement(BlocklistAnnouncement<NodeId>),
+    #[from]
     ContractRuntime(#[serde(skip_serializing)] ContractRuntimeRequest),
 }
 
@@ -86,6 +88,9 @@ impl Display for Event {
             Event::DeployAcceptorAnnouncement(ann) => {
                 write!(formatter, "deploy-acceptor announcement: {}", ann)
             }
+            Event::BlocklistAnnouncement(ann) => {
+                write!(formatter, "blocklist announcement: {}", ann)
+            }
 
             Event::ContractRuntime(event) => {
                 write!(formatter, "contract-runtime event: {:?}", event)
@@ -451,6 +456,10 @@ impl reactor::Reactor for Reactor {
                 // We do not care about deploy acceptor announcements in the acceptor tests.
                 Effects::new()
             }
+            Event::BlocklistAnnouncement(_) => {
+                // There is no network component to block offending peers in the acceptor tests.
+                Effects::new()
+            }
             Event::ContractRuntime(event) => match event {
                 ContractRuntimeRequest::Query {
                     query_request,
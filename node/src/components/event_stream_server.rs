@@ -15,7 +15,9 @@
 //!
 //! This component uses a ring buffer for outbound events providing some robustness against
 //! unintended subscriber disconnects, if a disconnected subscriber re-subscribes before the buffer
-//! has advanced past their last received event.
+//! has advanced past their last received event.  Unless disabled, events are also written to a
+//! persistent log on disk, from which a re-subscribing client is served its missed events, allowing
+//! recovery from longer outages and across node restarts.
 //!
 //! For details about the SSE model and a list of supported SSEs, see:
 //! <https://github.com/CasperLabs/ceps/blob/master/text/0009-client-api.md#rpcs>
@@ -24,6 +26,7 @@ mod config;
 mod deploy_getter;
 mod event;
 mod event_indexer;
+mod event_log;
 mod http_server;
 mod sse_server;
 #[cfg(test)]
@@ -52,6 +55,7 @@ pub use config::Config;
 pub(crate) use deploy_getter::DeployGetter;
 pub(crate) use event::Event;
 use event_indexer::{EventIndex, EventIndexer};
+use event_log::EventLog;
 use sse_server::ChannelsAndFilter;
 pub(crate) use sse_server::SseData;
 
@@ -98,6 +102,10 @@ impl EventStreamServer {
             ListeningError::ResolveAddress(error)
         })?;
 
+        let event_log = EventLog::new(&storage_path, &config).unwrap_or_else(|error| {
+            warn!(%error, "failed to open event stream log, events will not be persisted");
+            None
+        });
         let event_indexer = EventIndexer::new(storage_path);
         let (sse_data_sender, sse_data_receiver) = mpsc::unbounded_channel();
 
@@ -135,6 +143,7 @@ impl EventStreamServer {
             sse_data_receiver,
             event_broadcaster,
             new_subscriber_info_receiver,
+            event_log,
         ));
 
         Ok(EventStreamServer {
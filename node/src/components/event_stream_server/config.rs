@@ -12,6 +12,12 @@ const DEFAULT_EVENT_STREAM_BUFFER_LENGTH: u32 = 5000;
 /// Default maximum number of subscribers.
 const DEFAULT_MAX_CONCURRENT_SUBSCRIBERS: u32 = 100;
 
+/// Default number of SSEs to keep in the persistent event log.
+const DEFAULT_EVENT_LOG_LENGTH: u32 = 20_000;
+
+/// Default maximum size of the persistent event log (1 GiB).
+const DEFAULT_MAX_EVENT_LOG_SIZE: usize = 1024 * 1024 * 1024;
+
 /// SSE HTTP server configuration.
 #[derive(Clone, DataSize, Debug, Deserialize, Serialize)]
 // Disallow unknown fields to ensure config files and command-line overrides contain valid keys.
@@ -25,6 +31,15 @@ pub struct Config {
 
     /// Default maximum number of subscribers across all event streams permitted at any one time.
     pub max_concurrent_subscribers: u32,
+
+    /// Number of SSEs to keep in the persistent event log.  If 0, events are not persisted.
+    #[serde(default = "default_event_log_length")]
+    pub event_log_length: u32,
+
+    /// Maximum size in bytes of the persistent event log.  Once reached, the oldest events are
+    /// evicted to make room for new ones.
+    #[serde(default = "default_max_event_log_size")]
+    pub max_event_log_size: usize,
 }
 
 impl Config {
@@ -34,6 +49,8 @@ impl Config {
             address: DEFAULT_ADDRESS.to_string(),
             event_stream_buffer_length: DEFAULT_EVENT_STREAM_BUFFER_LENGTH,
             max_concurrent_subscribers: DEFAULT_MAX_CONCURRENT_SUBSCRIBERS,
+            event_log_length: default_event_log_length(),
+            max_event_log_size: default_max_event_log_size(),
         }
     }
 }
@@ -43,3 +60,11 @@ impl Default for Config {
         Config::new()
     }
 }
+
+fn default_event_log_length() -> u32 {
+    DEFAULT_EVENT_LOG_LENGTH
+}
+
+fn default_max_event_log_size() -> usize {
+    DEFAULT_MAX_EVENT_LOG_SIZE
+}
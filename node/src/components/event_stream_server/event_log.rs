@@ -0,0 +1,419 @@
+//! A persistent log of the events emitted by the event stream server.
+//!
+//! Events are held in an LMDB database keyed by a sequential position, along with an index from
+//! event ID to position.  Once the log holds more than the configured number of events, the oldest
+//! are removed as new ones are appended.  The oldest events are likewise evicted if the log
+//! reaches its configured maximum size.
+
+use std::{convert::TryInto, fs, path::Path, sync::Arc};
+
+use lmdb::{
+    Cursor, Database, DatabaseFlags, Environment, EnvironmentFlags, RwTransaction, Transaction,
+    WriteFlags,
+};
+use thiserror::Error;
+use tokio::sync::mpsc::UnboundedSender;
+use tracing::debug;
+
+use super::{
+    sse_server::{Id, ServerSentEvent, SseData},
+    Config,
+};
+
+/// Filename for the LMDB database holding the event log.
+const EVENT_LOG_FILENAME: &str = "sse_event_log.lmdb";
+/// Maximum number of concurrent read transactions, one per client replaying the log.
+const MAX_READERS: u32 = 126;
+/// The number of databases in the environment.
+const MAX_DB_COUNT: u32 = 2;
+
+/// Error in the persistent event log.
+#[derive(Debug, Error)]
+pub(super) enum EventLogError {
+    /// Failed to create the directory holding the event log.
+    #[error("failed to create event log directory: {0}")]
+    CreateDir(#[from] std::io::Error),
+    /// LMDB error.
+    #[error("event log LMDB error: {0}")]
+    Lmdb(#[from] lmdb::Error),
+    /// Failed to serialize or deserialize an event.
+    #[error("event log serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+    /// A stored record is malformed.
+    #[error("event log record malformed")]
+    MalformedRecord,
+}
+
+/// The persistent event log, used to append events as they are emitted.
+#[derive(Debug)]
+pub(super) struct EventLog {
+    reader: EventLogReader,
+    /// The maximum number of events retained.
+    length: u64,
+    /// The position of the oldest event in the log.
+    first_position: u64,
+    /// The position at which the next event will be appended.
+    next_position: u64,
+}
+
+/// A handle to the persistent event log, used to replay the logged events to clients.
+#[derive(Clone, Debug)]
+pub(super) struct EventLogReader {
+    env: Arc<Environment>,
+    /// Maps from position to the event ID and the JSON-encoded event data.
+    events_db: Database,
+    /// Maps from event ID to position.
+    ids_db: Database,
+}
+
+impl EventLog {
+    /// Opens the event log under `storage_path`, or returns `None` if it is disabled in `config`.
+    pub(super) fn new(storage_path: &Path, config: &Config) -> Result<Option<Self>, EventLogError> {
+        if config.event_log_length == 0 {
+            return Ok(None);
+        }
+
+        fs::create_dir_all(storage_path)?;
+        let env = Environment::new()
+            .set_flags(
+                // We manage our own directory.
+                EnvironmentFlags::NO_SUB_DIR
+                // Disable thread local storage, strongly suggested for operation with tokio.
+                | EnvironmentFlags::NO_TLS,
+            )
+            .set_max_readers(MAX_READERS)
+            .set_max_dbs(MAX_DB_COUNT)
+            .set_map_size(config.max_event_log_size)
+            .open(&storage_path.join(EVENT_LOG_FILENAME))?;
+        let events_db = env.create_db(Some("events"), DatabaseFlags::empty())?;
+        let ids_db = env.create_db(Some("ids"), DatabaseFlags::empty())?;
+
+        // Positions are contiguous, so we only need the first and the count of entries.
+        let (first_position, count) = {
+            let txn = env.begin_ro_txn()?;
+            let mut cursor = txn.open_ro_cursor(events_db)?;
+            let mut first_position = None;
+            let mut count = 0_u64;
+            for (key, _) in cursor.iter() {
+                if first_position.is_none() {
+                    first_position = Some(decode_position(key)?);
+                }
+                count += 1;
+            }
+            (first_position.unwrap_or_default(), count)
+        };
+        debug!(%first_position, %count, "opened sse event log");
+
+        let mut event_log = EventLog {
+            reader: EventLogReader {
+                env: Arc::new(env),
+                events_db,
+                ids_db,
+            },
+            length: u64::from(config.event_log_length),
+            first_position,
+            next_position: first_position + count,
+        };
+        // The configured length may have been reduced since the last run.
+        let mut txn = event_log.reader.env.begin_rw_txn()?;
+        let first_position =
+            event_log.prune(&mut txn, event_log.next_position, event_log.length)?;
+        txn.commit()?;
+        event_log.first_position = first_position;
+
+        Ok(Some(event_log))
+    }
+
+    /// Returns a handle which can be used to replay events from the log.
+    pub(super) fn reader(&self) -> EventLogReader {
+        self.reader.clone()
+    }
+
+    /// Appends the event to the log, removing the oldest events if the log is full.
+    ///
+    /// The log is full once it holds `event_log_length` events, or once it reaches
+    /// `max_event_log_size` bytes, in which case the oldest half of the events are evicted and the
+    /// append retried.  This blocks while writing to disk, so should not be called from an async
+    /// context.
+    pub(super) fn append(&mut self, id: Id, data: &SseData) -> Result<(), EventLogError> {
+        let mut record = id.to_le_bytes().to_vec();
+        serde_json::to_writer(&mut record, data)?;
+
+        loop {
+            match self.write(id, &record) {
+                Err(EventLogError::Lmdb(lmdb::Error::MapFull))
+                    if self.first_position < self.next_position =>
+                {
+                    let count = self.next_position - self.first_position;
+                    debug!(%count, "sse event log reached its maximum size, evicting oldest events");
+                    let mut txn = self.reader.env.begin_rw_txn()?;
+                    let first_position = self.prune(&mut txn, self.next_position, count / 2)?;
+                    txn.commit()?;
+                    self.first_position = first_position;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Writes the record at the next position in a single transaction, pruning the log to
+    /// `self.length` events.
+    fn write(&mut self, id: Id, record: &[u8]) -> Result<(), EventLogError> {
+        let position = self.next_position.to_be_bytes();
+        let next_position = self.next_position + 1;
+        let mut txn = self.reader.env.begin_rw_txn()?;
+        txn.put(
+            self.reader.events_db,
+            &position,
+            &record,
+            WriteFlags::APPEND,
+        )?;
+        txn.put(
+            self.reader.ids_db,
+            &id.to_be_bytes(),
+            &position,
+            WriteFlags::empty(),
+        )?;
+        let first_position = self.prune(&mut txn, next_position, self.length)?;
+        txn.commit()?;
+
+        self.first_position = first_position;
+        self.next_position = next_position;
+        Ok(())
+    }
+
+    /// Removes the oldest events until no more than `length` remain before `next_position`,
+    /// returning the new position of the oldest event.
+    fn prune(
+        &self,
+        txn: &mut RwTransaction,
+        next_position: u64,
+        length: u64,
+    ) -> Result<u64, EventLogError> {
+        let mut first_position = self.first_position;
+        while next_position - first_position > length {
+            let position = first_position.to_be_bytes();
+            let id = decode_id(txn.get(self.reader.events_db, &position)?)?;
+            // The ID may have been reused by a later event if the event indexer was reset, in
+            // which case the index entry should be kept.
+            let indexed_position = txn.get(self.reader.ids_db, &id.to_be_bytes()).ok();
+            if indexed_position == Some(&position[..]) {
+                txn.del(self.reader.ids_db, &id.to_be_bytes(), None)?;
+            }
+            txn.del(self.reader.events_db, &position, None)?;
+            first_position += 1;
+        }
+        Ok(first_position)
+    }
+}
+
+impl EventLogReader {
+    /// Sends all logged events from the one with ID `start_from` onwards via `sender`.
+    ///
+    /// If `start_from` is older than the oldest logged event, the whole log is sent.  This blocks
+    /// while reading from disk, so should not be called from an async context.
+    pub(super) fn replay(
+        &self,
+        start_from: Id,
+        sender: &UnboundedSender<ServerSentEvent>,
+    ) -> Result<(), EventLogError> {
+        let txn = self.env.begin_ro_txn()?;
+        let start_position = match txn.get(self.ids_db, &start_from.to_be_bytes()) {
+            Ok(position) => position.to_vec(),
+            Err(lmdb::Error::NotFound) => {
+                let mut cursor = txn.open_ro_cursor(self.events_db)?;
+                let (position, record) = match cursor.iter().next() {
+                    Some(entry) => entry,
+                    None => return Ok(()),
+                };
+                // IDs wrap, so treat `start_from` as older than the oldest logged event if it's
+                // within half the ID range below it; otherwise it's not yet been emitted.
+                let oldest_id = decode_id(record)?;
+                if oldest_id.wrapping_sub(start_from) > Id::MAX / 2 {
+                    return Ok(());
+                }
+                position.to_vec()
+            }
+            Err(error) => return Err(error.into()),
+        };
+
+        let mut cursor = txn.open_ro_cursor(self.events_db)?;
+        for (_, record) in cursor.iter_from(&start_position) {
+            let (id, data) = decode_record(record)?;
+            if sender.send(ServerSentEvent { id: Some(id), data }).is_err() {
+                // The client has disconnected.
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The number of bytes used to encode an event ID at the start of each record.
+const ID_LENGTH: usize = 4;
+
+fn decode_position(bytes: &[u8]) -> Result<u64, EventLogError> {
+    let bytes = bytes
+        .try_into()
+        .map_err(|_| EventLogError::MalformedRecord)?;
+    Ok(u64::from_be_bytes(bytes))
+}
+
+fn decode_id(record: &[u8]) -> Result<Id, EventLogError> {
+    let id_bytes = record
+        .get(..ID_LENGTH)
+        .ok_or(EventLogError::MalformedRecord)?
+        .try_into()
+        .map_err(|_| EventLogError::MalformedRecord)?;
+    Ok(Id::from_le_bytes(id_bytes))
+}
+
+fn decode_record(record: &[u8]) -> Result<(Id, SseData), EventLogError> {
+    let id = decode_id(record)?;
+    let data = serde_json::from_slice(&record[ID_LENGTH..])?;
+    Ok((id, data))
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::sync::mpsc;
+
+    use super::*;
+    use crate::{logging, testing::TestRng};
+
+    /// Returns a config for an event log retaining `length` events.
+    fn config(length: u32) -> Config {
+        Config {
+            event_log_length: length,
+            max_event_log_size: 10 * 1024 * 1024,
+            ..Default::default()
+        }
+    }
+
+    /// Appends an event with each of the given IDs, returning the events.
+    fn append_events(
+        rng: &mut TestRng,
+        event_log: &mut EventLog,
+        ids: impl IntoIterator<Item = Id>,
+    ) -> Vec<ServerSentEvent> {
+        ids.into_iter()
+            .map(|id| {
+                let data = SseData::random_fault(rng);
+                event_log.append(id, &data).unwrap();
+                ServerSentEvent { id: Some(id), data }
+            })
+            .collect()
+    }
+
+    /// Returns the events replayed from `start_from`.
+    fn replay(event_log: &EventLog, start_from: Id) -> Vec<ServerSentEvent> {
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        event_log.reader().replay(start_from, &sender).unwrap();
+        drop(sender);
+        let mut events = vec![];
+        while let Ok(event) = receiver.try_recv() {
+            events.push(event);
+        }
+        events
+    }
+
+    #[test]
+    fn should_not_open_if_disabled() {
+        let tempdir = tempfile::tempdir().unwrap();
+        assert!(EventLog::new(tempdir.path(), &config(0)).unwrap().is_none());
+    }
+
+    #[test]
+    fn should_replay_from_id() {
+        let _ = logging::init();
+        let mut rng = crate::new_rng();
+        let tempdir = tempfile::tempdir().unwrap();
+        let mut event_log = EventLog::new(tempdir.path(), &config(10)).unwrap().unwrap();
+        let events = append_events(&mut rng, &mut event_log, 100..105);
+
+        assert_eq!(replay(&event_log, 102), events[2..]);
+        assert_eq!(replay(&event_log, 100), events);
+        // Events older than the log should cause the whole log to be replayed.
+        assert_eq!(replay(&event_log, 50), events);
+        // Events not yet emitted should cause nothing to be replayed.
+        assert!(replay(&event_log, 105).is_empty());
+        assert!(replay(&event_log, 1000).is_empty());
+    }
+
+    #[test]
+    fn should_replay_across_wrapped_ids() {
+        let _ = logging::init();
+        let mut rng = crate::new_rng();
+        let tempdir = tempfile::tempdir().unwrap();
+        let mut event_log = EventLog::new(tempdir.path(), &config(10)).unwrap().unwrap();
+        let ids = (Id::MAX - 2..=Id::MAX).chain(0..3);
+        let events = append_events(&mut rng, &mut event_log, ids);
+
+        assert_eq!(replay(&event_log, Id::MAX), events[2..]);
+        assert_eq!(replay(&event_log, 1), events[4..]);
+        assert_eq!(replay(&event_log, Id::MAX - 5), events);
+        assert!(replay(&event_log, 3).is_empty());
+    }
+
+    #[test]
+    fn should_prune_and_persist() {
+        let _ = logging::init();
+        let mut rng = crate::new_rng();
+        let tempdir = tempfile::tempdir().unwrap();
+
+        let events = {
+            let mut event_log = EventLog::new(tempdir.path(), &config(5)).unwrap().unwrap();
+            let events = append_events(&mut rng, &mut event_log, 0..8);
+            // Only the last five events should be retained.
+            assert_eq!(replay(&event_log, 0), events[3..]);
+            events
+        };
+
+        // The retained events should be available after reopening, and further events should be
+        // appended after them.
+        let mut event_log = EventLog::new(tempdir.path(), &config(5)).unwrap().unwrap();
+        assert_eq!(replay(&event_log, 0), events[3..]);
+        let more_events = append_events(&mut rng, &mut event_log, 8..10);
+        let expected: Vec<_> = events[5..].iter().chain(&more_events).cloned().collect();
+        assert_eq!(replay(&event_log, 0), expected);
+        drop(event_log);
+
+        // Reducing the configured length should prune the log on reopening.
+        let event_log = EventLog::new(tempdir.path(), &config(2)).unwrap().unwrap();
+        assert_eq!(replay(&event_log, 0), more_events);
+    }
+
+    #[test]
+    fn should_handle_reused_ids() {
+        let _ = logging::init();
+        let mut rng = crate::new_rng();
+        let tempdir = tempfile::tempdir().unwrap();
+        let mut event_log = EventLog::new(tempdir.path(), &config(4)).unwrap().unwrap();
+
+        // Simulate the event indexer being reset, so IDs 0 and 1 are reused.
+        let events = append_events(&mut rng, &mut event_log, vec![0, 1, 2, 0, 1]);
+        assert_eq!(replay(&event_log, 0), events[3..]);
+        assert_eq!(replay(&event_log, 2), events[2..]);
+    }
+
+    #[test]
+    fn should_evict_oldest_events_when_full() {
+        let _ = logging::init();
+        let mut rng = crate::new_rng();
+        let tempdir = tempfile::tempdir().unwrap();
+        // A map this small holds far fewer events than the configured length.
+        let config = Config {
+            event_log_length: 100_000,
+            max_event_log_size: 64 * 1024,
+            ..Default::default()
+        };
+        let mut event_log = EventLog::new(tempdir.path(), &config).unwrap().unwrap();
+        let events = append_events(&mut rng, &mut event_log, 0..1_000);
+
+        // Every append should have succeeded, with the oldest events evicted to make room.
+        let replayed = replay(&event_log, 0);
+        assert!(!replayed.is_empty());
+        assert!(replayed.len() < events.len());
+        assert_eq!(replayed, events[events.len() - replayed.len()..]);
+    }
+}
@@ -4,16 +4,53 @@ use tokio::{
     sync::{broadcast, mpsc, oneshot},
     task,
 };
-use tracing::{info, trace};
+use tracing::{info, trace, warn};
 use wheelbuf::WheelBuf;
 
 use casper_types::ProtocolVersion;
 
 use super::{
     sse_server::{BroadcastChannelMessage, Id, NewSubscriberInfo, ServerSentEvent},
-    Config, EventIndex, SseData,
+    Config, EventIndex, EventLog, SseData,
 };
 
+/// A request to the blocking task which owns the persistent event log.
+enum EventLogRequest {
+    /// Append the event to the log.
+    Append(EventIndex, SseData),
+    /// Replay the logged events from the given ID onwards to a new client.
+    Replay(Id, mpsc::UnboundedSender<ServerSentEvent>),
+}
+
+/// Spawns a blocking task which owns `event_log` and handles requests in the order received, so
+/// that a replay includes every event appended before it was requested.
+fn spawn_event_log_task(mut event_log: EventLog) -> mpsc::UnboundedSender<EventLogRequest> {
+    let (sender, mut receiver) = mpsc::unbounded_channel();
+    task::spawn_blocking(move || {
+        while let Some(request) = receiver.blocking_recv() {
+            match request {
+                EventLogRequest::Append(event_index, data) => {
+                    if let Err(error) = event_log.append(event_index, &data) {
+                        warn!(%error, "failed to write event to event stream log");
+                    }
+                }
+                EventLogRequest::Replay(start_index, initial_events_sender) => {
+                    // Replays read from disk, so are each done on their own blocking task to
+                    // avoid holding up further appends; the client's initial stream ends once the
+                    // sender is dropped at the end of the replay.
+                    let reader = event_log.reader();
+                    task::spawn_blocking(move || {
+                        if let Err(error) = reader.replay(start_index, &initial_events_sender) {
+                            warn!(%error, "failed to replay events from event stream log");
+                        }
+                    });
+                }
+            }
+        }
+    });
+    sender
+}
+
 /// Run the HTTP server.
 ///
 /// * `server_with_shutdown` is the actual server as a future which can be gracefully shut down.
@@ -25,6 +62,9 @@ use super::{
 /// * `new_subscriber_info_receiver` is used to notify the server of the details of a new client
 ///   having subscribed to the event stream.  It allows the server to populate that client's stream
 ///   with the requested number of historical events.
+/// * `event_log`, if provided, is used to persist all events, and to provide the historical events
+///   requested by new clients in place of the in-memory buffer.
+#[allow(clippy::too_many_arguments)]
 pub(super) async fn run(
     config: Config,
     api_version: ProtocolVersion,
@@ -33,9 +73,13 @@ pub(super) async fn run(
     mut data_receiver: mpsc::UnboundedReceiver<(EventIndex, SseData)>,
     broadcaster: broadcast::Sender<BroadcastChannelMessage>,
     mut new_subscriber_info_receiver: mpsc::UnboundedReceiver<NewSubscriberInfo>,
+    event_log: Option<EventLog>,
 ) {
     let server_joiner = task::spawn(server_with_shutdown);
 
+    // Writing to and reading from the persistent log blocks, so is kept off the executor.
+    let event_log_sender = event_log.map(spawn_event_log_task);
+
     // Initialize the index and buffer for the SSEs.
     let mut buffer = WheelBuf::new(vec![
         ServerSentEvent::initial_event(api_version);
@@ -54,9 +98,22 @@ pub(super) async fn run(
                         let _ = subscriber
                             .initial_events_sender
                             .send(ServerSentEvent::initial_event(api_version));
-                        // If the client supplied a "start_from" index, provide the buffered events.
-                        // If they requested more than is buffered, just provide the whole buffer.
-                        if let Some(start_index) = subscriber.start_from {
+                        // If the client supplied a "start_from" index and we have a persistent log,
+                        // replay the logged events from that index.
+                        if let (Some(start_index), Some(event_log_sender)) =
+                            (subscriber.start_from, &event_log_sender)
+                        {
+                            let request = EventLogRequest::Replay(
+                                start_index,
+                                subscriber.initial_events_sender,
+                            );
+                            if event_log_sender.send(request).is_err() {
+                                warn!("event stream log task has stopped");
+                            }
+                        } else if let Some(start_index) = subscriber.start_from {
+                            // Otherwise, provide the buffered events.  If they requested more than
+                            // is buffered, just provide the whole buffer.
+                            //
                             // If the buffer's first event ID is in the range [0, buffer size) or
                             // (Id::MAX - buffer size, Id::MAX], then the events in the buffer are
                             // considered to have their IDs wrapping round, or that was recently the
@@ -91,8 +148,14 @@ pub(super) async fn run(
                 maybe_data = data_receiver.recv() => {
                     match maybe_data {
                         Some((event_index, data)) => {
-                            // Buffer the data and broadcast it to subscribed clients.
+                            // Log and buffer the data and broadcast it to subscribed clients.
                             trace!("Event stream server received {:?}", data);
+                            if let Some(event_log_sender) = &event_log_sender {
+                                let request = EventLogRequest::Append(event_index, data.clone());
+                                if event_log_sender.send(request).is_err() {
+                                    warn!("event stream log task has stopped");
+                                }
+                            }
                             let event = ServerSentEvent { id: Some(event_index), data };
                             buffer.push(event.clone());
                             let message = BroadcastChannelMessage::ServerSentEvent(event);
@@ -34,7 +34,9 @@ use warp::{
     Filter, Reply,
 };
 
-use casper_types::{EraId, ExecutionEffect, ExecutionResult, ProtocolVersion, PublicKey};
+use casper_types::{
+    AsymmetricType, EraId, ExecutionEffect, ExecutionResult, Key, ProtocolVersion, PublicKey,
+};
 
 use super::DeployGetter;
 use crate::types::{
@@ -54,6 +56,16 @@ pub const SSE_API_DEPLOYS_PATH: &str = "deploys";
 pub const SSE_API_SIGNATURES_PATH: &str = "sigs";
 /// The URL query string field name.
 pub const QUERY_FIELD: &str = "start_from";
+/// The URL query string field name for filtering by the accounts which sent deploys.
+pub const ACCOUNT_QUERY_FIELD: &str = "account";
+/// The URL query string field name for filtering by the keys touched in execution effects.
+pub const KEY_QUERY_FIELD: &str = "key";
+/// The URL query string field name for filtering by event type.
+pub const EVENT_TYPE_QUERY_FIELD: &str = "event_type";
+/// The URL query string field name for filtering by era.
+pub const ERA_QUERY_FIELD: &str = "era";
+/// The separator between multiple values provided for a single URL query string field.
+const QUERY_VALUE_SEPARATOR: char = ',';
 
 /// The filter associated with `/events/main` path.
 const MAIN_FILTER: [EventFilter; 5] = [
@@ -132,6 +144,32 @@ impl SseData {
             SseData::Step { .. } => filter.contains(&EventFilter::Step),
         }
     }
+
+    /// Returns the execution effect of the event, if it has one.
+    fn execution_effect(&self) -> Option<&ExecutionEffect> {
+        match self {
+            SseData::DeployProcessed {
+                execution_result, ..
+            } => match &**execution_result {
+                ExecutionResult::Success { effect, .. }
+                | ExecutionResult::Failure { effect, .. } => Some(effect),
+            },
+            SseData::Step {
+                execution_effect, ..
+            } => Some(execution_effect),
+            _ => None,
+        }
+    }
+
+    /// Returns the era of the event, if it is known.
+    fn era_id(&self) -> Option<EraId> {
+        match self {
+            SseData::BlockAdded { block, .. } => Some(block.header.era_id),
+            SseData::Fault { era_id, .. } | SseData::Step { era_id, .. } => Some(*era_id),
+            SseData::FinalitySignature(finality_signature) => Some(finality_signature.era_id),
+            _ => None,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -276,13 +314,105 @@ pub(super) enum EventFilter {
     Step,
 }
 
+impl EventFilter {
+    /// Parses an event type as named in the `SseData` variants, e.g. "BlockAdded".
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "BlockAdded" => Some(EventFilter::BlockAdded),
+            "DeployAccepted" => Some(EventFilter::DeployAccepted),
+            "DeployProcessed" => Some(EventFilter::DeployProcessed),
+            "DeployExpired" => Some(EventFilter::DeployExpired),
+            "Fault" => Some(EventFilter::Fault),
+            "FinalitySignature" => Some(EventFilter::FinalitySignature),
+            "Step" => Some(EventFilter::Step),
+            _ => None,
+        }
+    }
+}
+
+/// A filter on the content of events, as specified by a client in the URL query string.
+///
+/// This further restricts the events allowed through by the `EventFilter` associated with the URL
+/// path.  Each constraint which is set causes events not carrying the relevant field to be dropped,
+/// e.g. if filtering by account, only `DeployAccepted` and `DeployProcessed` events can pass.
+#[derive(Clone, Default, Eq, PartialEq, Debug)]
+pub(super) struct ContentFilter {
+    /// If set, only events of these types are included.
+    event_types: Option<Vec<EventFilter>>,
+    /// If set, only events for deploys sent by one of these accounts are included.
+    accounts: Option<Vec<PublicKey>>,
+    /// If set, only events whose execution effect touches one of these keys are included.  The
+    /// keys are held as formatted strings, matching the form used in `TransformEntry`.
+    keys: Option<Vec<String>>,
+    /// If set, only events from this era are included.
+    era_id: Option<EraId>,
+}
+
+impl ContentFilter {
+    /// Returns whether the event should be included.
+    ///
+    /// For `DeployAccepted` events, the account is not checked here as it isn't known until the
+    /// deploy has been retrieved; `includes_account()` should be called at that point.
+    fn should_include(&self, data: &SseData) -> bool {
+        if let SseData::ApiVersion(_) = data {
+            return true;
+        }
+
+        if let Some(event_types) = &self.event_types {
+            if !data.should_include(event_types) {
+                return false;
+            }
+        }
+
+        if self.accounts.is_some() {
+            match data {
+                SseData::DeployAccepted { .. } => (),
+                SseData::DeployProcessed { account, .. } => {
+                    if !self.includes_account(account) {
+                        return false;
+                    }
+                }
+                _ => return false,
+            }
+        }
+
+        if let Some(keys) = &self.keys {
+            let touches_key = data
+                .execution_effect()
+                .into_iter()
+                .flat_map(|effect| effect.transforms.iter())
+                .any(|transform_entry| keys.contains(&transform_entry.key));
+            if !touches_key {
+                return false;
+            }
+        }
+
+        if let Some(era_id) = self.era_id {
+            if data.era_id() != Some(era_id) {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Returns whether a deploy sent by the given account should be included.
+    fn includes_account(&self, account: &PublicKey) -> bool {
+        match &self.accounts {
+            Some(accounts) => accounts.contains(account),
+            None => true,
+        }
+    }
+}
+
 /// Filters the `event`, mapping it to a warp event, or `None` if it should be filtered out.
 async fn filter_map_server_sent_event(
     event: &ServerSentEvent,
     event_filter: &[EventFilter],
+    content_filter: &ContentFilter,
     deploy_getter: DeployGetter,
 ) -> Option<Result<WarpServerSentEvent, RecvError>> {
-    if !event.data.should_include(event_filter) {
+    if !event.data.should_include(event_filter) || !content_filter.should_include(&event.data) {
         return None;
     }
 
@@ -339,6 +469,9 @@ async fn filter_map_server_sent_event(
                         deploy_getter.get(deploy_hash).await?
                     }
                 };
+            if !content_filter.includes_account(deploy_accepted.header().account()) {
+                return None;
+            }
 
             Some(Ok(WarpServerSentEvent::default()
                 .json_data(&DeployAccepted { deploy_accepted })
@@ -361,26 +494,61 @@ pub(super) fn get_filter(path_param: &str) -> Option<&'static [EventFilter]> {
     }
 }
 
-/// Extracts the starting event ID from the provided query, or `None` if `query` is empty.
+/// Extracts the starting event ID and the content filter from the provided query.
 ///
-/// If `query` is not empty, returns a 422 response if `query` doesn't have exactly one entry,
-/// "starts_from" mapped to a value representing an event ID.
-fn parse_query(query: HashMap<String, String>) -> Result<Option<Id>, Response> {
-    if query.is_empty() {
-        return Ok(None);
+/// The starting event ID is `None` if "start_from" is not provided, and the content filter has no
+/// constraints set for any filter fields not provided.  Fields which take a list of values expect
+/// them to be comma-separated.
+///
+/// Returns a 422 response if `query` has any unknown fields or any values which can't be parsed.
+fn parse_query(query: HashMap<String, String>) -> Result<(Option<Id>, ContentFilter), Response> {
+    let mut start_from = None;
+    let mut content_filter = ContentFilter::default();
+
+    for (field, value) in &query {
+        match field.as_str() {
+            QUERY_FIELD => {
+                start_from = Some(value.parse::<Id>().map_err(|_| create_422())?);
+            }
+            ACCOUNT_QUERY_FIELD => {
+                let accounts = parse_query_values(value, |account| PublicKey::from_hex(account))
+                    .ok_or_else(create_422)?;
+                content_filter.accounts = Some(accounts);
+            }
+            KEY_QUERY_FIELD => {
+                let keys = parse_query_values(value, |key| {
+                    Key::from_formatted_str(key).map(|key| key.to_formatted_string())
+                })
+                .ok_or_else(create_422)?;
+                content_filter.keys = Some(keys);
+            }
+            EVENT_TYPE_QUERY_FIELD => {
+                let event_types = parse_query_values(value, |event_type| {
+                    EventFilter::from_name(event_type).ok_or(())
+                })
+                .ok_or_else(create_422)?;
+                content_filter.event_types = Some(event_types);
+            }
+            ERA_QUERY_FIELD => {
+                let era_id = value.parse::<u64>().map_err(|_| create_422())?;
+                content_filter.era_id = Some(EraId::new(era_id));
+            }
+            _ => return Err(create_422()),
+        }
     }
 
-    if query.len() > 1 {
-        return Err(create_422());
-    }
+    Ok((start_from, content_filter))
+}
 
-    match query
-        .get(QUERY_FIELD)
-        .and_then(|id_str| id_str.parse::<Id>().ok())
-    {
-        Some(id) => Ok(Some(id)),
-        None => Err(create_422()),
-    }
+/// Parses each of the comma-separated values in `value`, returning `None` if any fail.
+fn parse_query_values<T, E, F>(value: &str, parse: F) -> Option<Vec<T>>
+where
+    F: Fn(&str) -> Result<T, E>,
+{
+    value
+        .split(QUERY_VALUE_SEPARATOR)
+        .map(|item| parse(item.trim()).ok())
+        .collect()
 }
 
 /// Creates a 404 response with a useful error message in the body.
@@ -400,8 +568,9 @@ fn create_404() -> Response {
 /// string.
 fn create_422() -> Response {
     let mut response = Response::new(Body::from(format!(
-        "invalid query: expected single field '{}=<EVENT ID>'\n",
-        QUERY_FIELD
+        "invalid query: expected any of '{}=<EVENT ID>', '{}=<PUBLIC KEYS>', '{}=<KEYS>', \
+        '{}=<EVENT TYPES>' or '{}=<ERA ID>', with lists of values comma-separated\n",
+        QUERY_FIELD, ACCOUNT_QUERY_FIELD, KEY_QUERY_FIELD, EVENT_TYPE_QUERY_FIELD, ERA_QUERY_FIELD
     )));
     *response.status_mut() = StatusCode::UNPROCESSABLE_ENTITY;
     response
@@ -458,8 +627,8 @@ impl ChannelsAndFilter {
                     None => return create_404(),
                 };
 
-                let start_from = match parse_query(query) {
-                    Ok(maybe_id) => maybe_id,
+                let (start_from, content_filter) = match parse_query(query) {
+                    Ok(parsed_query) => parsed_query,
                     Err(error_response) => return error_response,
                 };
 
@@ -488,6 +657,7 @@ impl ChannelsAndFilter {
                     initial_events_receiver,
                     ongoing_events_receiver,
                     event_filter,
+                    content_filter,
                     deploy_getter.clone(),
                 )))
                 .into_response()
@@ -516,13 +686,16 @@ impl ChannelsAndFilter {
 /// subscribed to the server's event stream.
 ///
 /// It also takes an `EventFilter` which causes events to which the client didn't subscribe to be
-/// skipped.
+/// skipped, and a `ContentFilter` which skips events not matching the client's query.
 fn stream_to_client(
     initial_events: mpsc::UnboundedReceiver<ServerSentEvent>,
     ongoing_events: broadcast::Receiver<BroadcastChannelMessage>,
     event_filter: &'static [EventFilter],
+    content_filter: ContentFilter,
     deploy_getter: DeployGetter,
 ) -> impl Stream<Item = Result<WarpServerSentEvent, RecvError>> + 'static {
+    let content_filter = Arc::new(content_filter);
+
     // Keep a record of the IDs of the events delivered via the `initial_events` receiver.
     let initial_stream_ids = Arc::new(RwLock::new(HashSet::new()));
     let cloned_initial_ids = Arc::clone(&initial_stream_ids);
@@ -557,7 +730,7 @@ fn stream_to_client(
         .take_while(|result| future::ready(!matches!(result, Err(RecvError::Closed))));
 
     // Serve the initial events followed by the ongoing ones, filtering as dictated by the
-    // `event_filter`.
+    // `event_filter` and `content_filter`.
     UnboundedReceiverStream::new(initial_events)
         .map(move |event| {
             if let Some(id) = event.id {
@@ -567,12 +740,18 @@ fn stream_to_client(
         })
         .chain(ongoing_stream)
         .filter_map(move |result| {
+            let cloned_content_filter = Arc::clone(&content_filter);
             let cloned_deploy_getter = deploy_getter.clone();
             async move {
                 match result {
                     Ok(event) => {
-                        filter_map_server_sent_event(&event, event_filter, cloned_deploy_getter)
-                            .await
+                        filter_map_server_sent_event(
+                            &event,
+                            event_filter,
+                            &cloned_content_filter,
+                            cloned_deploy_getter,
+                        )
+                        .await
                     }
                     Err(error) => Some(Err(error)),
                 }
@@ -584,6 +763,8 @@ fn stream_to_client(
 mod tests {
     use std::iter;
 
+    use casper_types::{Transform, TransformEntry, U512};
+
     use super::*;
     use crate::{logging, testing::TestRng};
 
@@ -593,7 +774,7 @@ mod tests {
         deploy_getter: DeployGetter,
     ) {
         assert!(
-            filter_map_server_sent_event(event, filter, deploy_getter)
+            filter_map_server_sent_event(event, filter, &ContentFilter::default(), deploy_getter)
                 .await
                 .is_none(),
             "should filter out {:?} with {:?}",
@@ -608,7 +789,7 @@ mod tests {
         deploy_getter: DeployGetter,
     ) {
         assert!(
-            filter_map_server_sent_event(event, filter, deploy_getter)
+            filter_map_server_sent_event(event, filter, &ContentFilter::default(), deploy_getter)
                 .await
                 .is_some(),
             "should not filter out {:?} with {:?}",
@@ -757,6 +938,199 @@ mod tests {
         }
     }
 
+    /// This test checks that events are filtered according to the content filter.
+    #[tokio::test]
+    async fn should_filter_events_by_content() {
+        const ALL_EVENTS_FILTER: [EventFilter; 7] = [
+            EventFilter::BlockAdded,
+            EventFilter::DeployAccepted,
+            EventFilter::DeployProcessed,
+            EventFilter::DeployExpired,
+            EventFilter::Fault,
+            EventFilter::FinalitySignature,
+            EventFilter::Step,
+        ];
+
+        async fn is_included(
+            event: &ServerSentEvent,
+            content_filter: &ContentFilter,
+            deploy_getter: DeployGetter,
+        ) -> bool {
+            filter_map_server_sent_event(
+                event,
+                &ALL_EVENTS_FILTER[..],
+                content_filter,
+                deploy_getter,
+            )
+            .await
+            .is_some()
+        }
+
+        let _ = logging::init();
+        let mut rng = crate::new_rng();
+
+        let api_version = ServerSentEvent::initial_event(ProtocolVersion::V1_0_0);
+        let (sse_data, deploy) = SseData::random_deploy_accepted(&mut rng);
+        let deploy_accepted = ServerSentEvent {
+            id: Some(rng.gen()),
+            data: sse_data,
+        };
+        let deploy_account = deploy.header().account().clone();
+        let mut deploys = HashMap::new();
+        let _ = deploys.insert(*deploy.id(), deploy);
+        let getter = DeployGetter::with_deploys(deploys);
+        // The processed deploy touches a single, known key.
+        let touched_key = Key::Account(rng.gen()).to_formatted_string();
+        let mut deploy_processed_data = SseData::random_deploy_processed(&mut rng);
+        let processed_account = match &mut deploy_processed_data {
+            SseData::DeployProcessed {
+                account,
+                execution_result,
+                ..
+            } => {
+                **execution_result = ExecutionResult::Success {
+                    effect: ExecutionEffect::new(vec![TransformEntry {
+                        key: touched_key.clone(),
+                        transform: Transform::Identity,
+                    }]),
+                    transfers: vec![],
+                    cost: U512::one(),
+                };
+                (**account).clone()
+            }
+            _ => unreachable!(),
+        };
+        let deploy_processed = ServerSentEvent {
+            id: Some(rng.gen()),
+            data: deploy_processed_data,
+        };
+        let fault_data = SseData::random_fault(&mut rng);
+        let fault_era_id = fault_data.era_id().unwrap();
+        let fault = ServerSentEvent {
+            id: Some(rng.gen()),
+            data: fault_data,
+        };
+        let step = ServerSentEvent {
+            id: Some(rng.gen()),
+            data: SseData::random_step(&mut rng),
+        };
+
+        // With no constraints set, everything should be included.
+        let no_constraints = ContentFilter::default();
+        for event in &[
+            &api_version,
+            &deploy_accepted,
+            &deploy_processed,
+            &fault,
+            &step,
+        ] {
+            assert!(is_included(event, &no_constraints, getter.clone()).await);
+        }
+
+        // Filtering by event type.
+        let by_event_type = ContentFilter {
+            event_types: Some(vec![EventFilter::Fault, EventFilter::Step]),
+            ..ContentFilter::default()
+        };
+        assert!(is_included(&api_version, &by_event_type, getter.clone()).await);
+        assert!(is_included(&fault, &by_event_type, getter.clone()).await);
+        assert!(is_included(&step, &by_event_type, getter.clone()).await);
+        assert!(!is_included(&deploy_accepted, &by_event_type, getter.clone()).await);
+        assert!(!is_included(&deploy_processed, &by_event_type, getter.clone()).await);
+
+        // Filtering by account.
+        let by_account = ContentFilter {
+            accounts: Some(vec![deploy_account, processed_account]),
+            ..ContentFilter::default()
+        };
+        assert!(is_included(&api_version, &by_account, getter.clone()).await);
+        assert!(is_included(&deploy_accepted, &by_account, getter.clone()).await);
+        assert!(is_included(&deploy_processed, &by_account, getter.clone()).await);
+        assert!(!is_included(&fault, &by_account, getter.clone()).await);
+        assert!(!is_included(&step, &by_account, getter.clone()).await);
+        let by_other_account = ContentFilter {
+            accounts: Some(vec![PublicKey::random(&mut rng)]),
+            ..ContentFilter::default()
+        };
+        assert!(!is_included(&deploy_accepted, &by_other_account, getter.clone()).await);
+        assert!(!is_included(&deploy_processed, &by_other_account, getter.clone()).await);
+
+        // Filtering by key.
+        let by_key = ContentFilter {
+            keys: Some(vec![touched_key]),
+            ..ContentFilter::default()
+        };
+        assert!(is_included(&api_version, &by_key, getter.clone()).await);
+        assert!(is_included(&deploy_processed, &by_key, getter.clone()).await);
+        assert!(!is_included(&deploy_accepted, &by_key, getter.clone()).await);
+        assert!(!is_included(&fault, &by_key, getter.clone()).await);
+        let by_other_key = ContentFilter {
+            keys: Some(vec![Key::Account(rng.gen()).to_formatted_string()]),
+            ..ContentFilter::default()
+        };
+        assert!(!is_included(&deploy_processed, &by_other_key, getter.clone()).await);
+
+        // Filtering by era.
+        let by_era = ContentFilter {
+            era_id: Some(fault_era_id),
+            ..ContentFilter::default()
+        };
+        assert!(is_included(&fault, &by_era, getter.clone()).await);
+        assert!(!is_included(&deploy_processed, &by_era, getter.clone()).await);
+        let by_other_era = ContentFilter {
+            era_id: Some(fault_era_id.successor()),
+            ..ContentFilter::default()
+        };
+        assert!(!is_included(&fault, &by_other_era, getter).await);
+    }
+
+    #[test]
+    fn should_parse_query() {
+        let mut rng = crate::new_rng();
+        let public_key = PublicKey::random(&mut rng);
+        let key = Key::Account(public_key.to_account_hash()).to_formatted_string();
+
+        let query: HashMap<String, String> = [
+            (QUERY_FIELD, "3".to_string()),
+            (ACCOUNT_QUERY_FIELD, public_key.to_hex()),
+            (KEY_QUERY_FIELD, key.clone()),
+            (
+                EVENT_TYPE_QUERY_FIELD,
+                "BlockAdded,DeployProcessed".to_string(),
+            ),
+            (ERA_QUERY_FIELD, "7".to_string()),
+        ]
+        .iter()
+        .map(|(field, value)| (field.to_string(), value.clone()))
+        .collect();
+        let (start_from, content_filter) = parse_query(query).unwrap();
+        assert_eq!(start_from, Some(3));
+        assert_eq!(
+            content_filter,
+            ContentFilter {
+                event_types: Some(vec![EventFilter::BlockAdded, EventFilter::DeployProcessed]),
+                accounts: Some(vec![public_key]),
+                keys: Some(vec![key]),
+                era_id: Some(EraId::new(7)),
+            }
+        );
+
+        let (start_from, content_filter) = parse_query(HashMap::new()).unwrap();
+        assert!(start_from.is_none());
+        assert_eq!(content_filter, ContentFilter::default());
+
+        for (field, value) in &[
+            (ACCOUNT_QUERY_FIELD, "not-a-key"),
+            (KEY_QUERY_FIELD, "not-a-key"),
+            (EVENT_TYPE_QUERY_FIELD, "BlockAdded,Nothing"),
+            (ERA_QUERY_FIELD, "-1"),
+            ("unknown", "1"),
+        ] {
+            let query = iter::once((field.to_string(), value.to_string())).collect();
+            assert!(parse_query(query).is_err());
+        }
+    }
+
     async fn should_filter_duplicate_events(path_filter: &str) {
         // Returns `count` random SSE events, all of a single variant defined by `path_filter`.  The
         // events will have sequential IDs starting from `start_id`, and if the path filter
@@ -871,6 +1245,7 @@ mod tests {
                 initial_events_receiver,
                 ongoing_events_receiver,
                 get_filter(path_filter).unwrap(),
+                ContentFilter::default(),
                 deploy_getter,
             )
             .collect()
@@ -27,7 +27,8 @@ use tracing::debug;
 use super::*;
 use crate::{logging, testing::TestRng};
 use sse_server::{
-    DeployAccepted, Id, QUERY_FIELD, SSE_API_DEPLOYS_PATH as DEPLOYS_PATH,
+    DeployAccepted, Id, ACCOUNT_QUERY_FIELD, ERA_QUERY_FIELD, EVENT_TYPE_QUERY_FIELD,
+    KEY_QUERY_FIELD, QUERY_FIELD, SSE_API_DEPLOYS_PATH as DEPLOYS_PATH,
     SSE_API_MAIN_PATH as MAIN_PATH, SSE_API_ROOT_PATH as ROOT_PATH,
     SSE_API_SIGNATURES_PATH as SIGS_PATH,
 };
@@ -82,6 +83,9 @@ struct ServerBehavior {
     /// If `Some`, sets the `max_concurrent_subscribers` server config value, otherwise uses the
     /// config default.
     max_concurrent_subscribers: Option<u32>,
+    /// If `Some`, sets the `event_log_length` server config value, otherwise uses the config
+    /// default.
+    event_log_length: Option<u32>,
     clients: Vec<ClientSyncBehavior>,
 }
 
@@ -94,6 +98,7 @@ impl ServerBehavior {
             has_delay_between_events: true,
             repeat_events: false,
             max_concurrent_subscribers: None,
+            event_log_length: None,
             clients: Vec::new(),
         }
     }
@@ -107,6 +112,7 @@ impl ServerBehavior {
             has_delay_between_events: false,
             repeat_events: true,
             max_concurrent_subscribers: None,
+            event_log_length: None,
             clients: Vec::new(),
         }
     }
@@ -124,6 +130,11 @@ impl ServerBehavior {
         self.max_concurrent_subscribers = Some(count);
     }
 
+    /// Sets the `event_log_length` server config value.
+    fn set_event_log_length(&mut self, length: u32) {
+        self.event_log_length = Some(length);
+    }
+
     /// Waits for all clients which specified they wanted to join just before the given event ID.
     async fn wait_for_clients(&self, id: Id) {
         for client_behavior in &self.clients {
@@ -266,6 +277,9 @@ impl TestFixture {
             max_concurrent_subscribers: server_behavior
                 .max_concurrent_subscribers
                 .unwrap_or(Config::default().max_concurrent_subscribers),
+            event_log_length: server_behavior
+                .event_log_length
+                .unwrap_or(Config::default().event_log_length),
             ..Default::default()
         };
         let mut server = EventStreamServer::new(
@@ -660,7 +674,8 @@ async fn should_serve_signature_events_with_query() {
 ///   * connected just before event ID 75
 ///
 /// Expected to receive main, deploy-accepted or signature events (depending on `path`) from ID 25
-/// onwards, as events 0 to 24 should have been purged from the server buffer.
+/// onwards, as events 0 to 24 should have been purged from the server buffer, and the server's
+/// persistent event log is disabled.
 async fn should_serve_remaining_events_with_query(path: &str) {
     let mut rng = crate::new_rng();
     let mut fixture = TestFixture::new(&mut rng);
@@ -669,6 +684,7 @@ async fn should_serve_remaining_events_with_query(path: &str) {
     let start_from_event_id = 0;
 
     let mut server_behavior = ServerBehavior::new();
+    server_behavior.set_event_log_length(0);
     let barrier = server_behavior.add_client_sync_before_event(connect_at_event_id);
     let server_address = fixture.run_server(server_behavior).await;
 
@@ -696,6 +712,47 @@ async fn should_serve_remaining_signature_events_with_query() {
     should_serve_remaining_events_with_query(SIGS_PATH).await;
 }
 
+/// Client setup:
+///   * `<IP:port>/events/<path>?start_from=0`
+///   * connected just before event ID 75
+///
+/// Expected to receive all main, deploy-accepted or signature events (depending on `path`), as
+/// events 0 to 24, although purged from the server buffer, are still held in the persistent event
+/// log.
+async fn should_serve_logged_events_with_query(path: &str) {
+    let mut rng = crate::new_rng();
+    let mut fixture = TestFixture::new(&mut rng);
+
+    let connect_at_event_id = BUFFER_LENGTH * 3 / 2;
+    let start_from_event_id = 0;
+
+    let mut server_behavior = ServerBehavior::new();
+    let barrier = server_behavior.add_client_sync_before_event(connect_at_event_id);
+    let server_address = fixture.run_server(server_behavior).await;
+
+    let url = url(server_address, path, Some(start_from_event_id));
+    let (expected_events, final_id) = fixture.all_filtered_events(path);
+    let received_events = subscribe(&url, barrier, final_id, "client").await.unwrap();
+    fixture.stop_server().await;
+
+    assert_eq!(received_events, expected_events);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn should_serve_logged_main_events_with_query() {
+    should_serve_logged_events_with_query(MAIN_PATH).await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn should_serve_logged_deploy_accepted_events_with_query() {
+    should_serve_logged_events_with_query(DEPLOYS_PATH).await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn should_serve_logged_signature_events_with_query() {
+    should_serve_logged_events_with_query(SIGS_PATH).await;
+}
+
 /// Client setup:
 ///   * `<IP:port>/events/<path>?start_from=25`
 ///   * connected before first event
@@ -937,11 +994,16 @@ async fn should_handle_bad_url_query() {
         format!("{}?{}=0&extra=1", main_url, QUERY_FIELD),
         format!("{}?{}=0&extra=1", deploys_url, QUERY_FIELD),
         format!("{}?{}=0&extra=1", sigs_url, QUERY_FIELD),
+        format!("{}?{}=not-a-key", main_url, ACCOUNT_QUERY_FIELD),
+        format!("{}?{}=not-a-key", main_url, KEY_QUERY_FIELD),
+        format!("{}?{}=NotAnEvent", main_url, EVENT_TYPE_QUERY_FIELD),
+        format!("{}?{}=not-integer", main_url, ERA_QUERY_FIELD),
     ];
 
     let expected_body = format!(
-        "invalid query: expected single field '{}=<EVENT ID>'",
-        QUERY_FIELD
+        "invalid query: expected any of '{}=<EVENT ID>', '{}=<PUBLIC KEYS>', '{}=<KEYS>', \
+        '{}=<EVENT TYPES>' or '{}=<ERA ID>', with lists of values comma-separated",
+        QUERY_FIELD, ACCOUNT_QUERY_FIELD, KEY_QUERY_FIELD, EVENT_TYPE_QUERY_FIELD, ERA_QUERY_FIELD
     );
     for url in &urls {
         let response = reqwest::get(url).await.unwrap();
@@ -16,11 +16,12 @@ use casper_types::{Key, StoredValue};
 use crate::{
     components::{fetcher::event::FetchResponder, Component},
     effect::{
+        announcements::BlocklistAnnouncement,
         requests::{ContractRuntimeRequest, LinearChainRequest, NetworkRequest, StorageRequest},
         EffectBuilder, EffectExt, Effects,
     },
     protocol::Message,
-    types::{Block, BlockByHeight, BlockHash, Deploy, DeployHash, Item, NodeId},
+    types::{Block, BlockByHeight, BlockHash, Deploy, DeployHash, Item, NodeId, OffenseSeverity},
     utils::Source,
     NodeRng,
 };
@@ -37,6 +38,7 @@ pub(crate) trait ReactorEventT<T>:
     + From<ContractRuntimeRequest>
     // Won't be needed when we implement "get block by height" feature in storage.
     + From<LinearChainRequest<NodeId>>
+    + From<BlocklistAnnouncement<NodeId>>
     + Send
     + 'static
 where
@@ -54,6 +56,7 @@ where
         + From<StorageRequest>
         + From<ContractRuntimeRequest>
         + From<LinearChainRequest<NodeId>>
+        + From<BlocklistAnnouncement<NodeId>>
         + Send
         + 'static,
 {
@@ -77,7 +80,7 @@ pub(crate) trait ItemFetcher<T: Item + 'static> {
         // Capture responder for later signalling.
         let responders = self.responders();
         responders
-            .entry(id)
+            .entry(id.clone())
             .or_default()
             .entry(peer)
             .or_default()
@@ -365,7 +368,20 @@ where
             Event::TimeoutPeer { id, peer } => {
                 info!(%id, %peer, "request timed out");
                 self.metrics.timeouts.inc();
-                self.signal(id, None, peer)
+                // Only penalize the peer if it never answered the request.
+                let still_pending = self
+                    .responders()
+                    .get(&id)
+                    .map_or(false, |responders| responders.contains_key(&peer));
+                let mut effects = self.signal(id, None, peer);
+                if still_pending {
+                    effects.extend(
+                        effect_builder
+                            .announce_offense(peer, OffenseSeverity::Minor)
+                            .ignore(),
+                    );
+                }
+                effects
             }
         }
     }
@@ -14,7 +14,7 @@ use super::*;
 use crate::{
     components::{deploy_acceptor, in_memory_network::NetworkController, storage},
     effect::{
-        announcements::{DeployAcceptorAnnouncement, NetworkAnnouncement},
+        announcements::{BlocklistAnnouncement, DeployAcceptorAnnouncement, NetworkAnnouncement},
         Responder,
     },
     protocol::Message,
@@ -108,6 +108,8 @@ reactor!(Reactor {
         // from a client.
         RpcServerAnnouncement -> [deploy_acceptor];
         ChainspecLoaderAnnouncement -> [!];
+        // Offenses are only acted upon by the real network component.
+        BlocklistAnnouncement<NodeId> -> [];
     }
 });
 
@@ -20,12 +20,14 @@ use tracing::{debug, error, warn};
 use crate::{
     components::Component,
     effect::{
-        announcements::GossiperAnnouncement,
+        announcements::{BlocklistAnnouncement, GossiperAnnouncement},
         requests::{NetworkRequest, StorageRequest},
         EffectBuilder, EffectExt, Effects,
     },
     protocol::Message as NodeMessage,
-    types::{Deploy, DeployHash, Item, NodeId},
+    types::{
+        Deploy, DeployHash, FinalitySignature, FinalitySignatureId, Item, NodeId, OffenseSeverity,
+    },
     utils::Source,
     NodeRng,
 };
@@ -43,6 +45,7 @@ pub(crate) trait ReactorEventT<T>:
     + From<NetworkRequest<NodeId, NodeMessage>>
     + From<StorageRequest>
     + From<GossiperAnnouncement<T>>
+    + From<BlocklistAnnouncement<NodeId>>
     + Send
     + 'static
 where
@@ -60,6 +63,7 @@ where
         + From<NetworkRequest<NodeId, NodeMessage>>
         + From<StorageRequest>
         + From<GossiperAnnouncement<T>>
+        + From<BlocklistAnnouncement<NodeId>>
         + Send
         + 'static,
 {
@@ -91,6 +95,29 @@ pub(crate) fn get_deploy_from_storage<T: Item + 'static, REv: ReactorEventT<T>>(
         })
 }
 
+/// This function can be passed in to `Gossiper::new()` as the `get_from_holder` arg when
+/// constructing a `Gossiper<FinalitySignature>`.
+pub(crate) fn get_finality_signature_from_storage<T: Item + 'static, REv: ReactorEventT<T>>(
+    effect_builder: EffectBuilder<REv>,
+    finality_signature_id: FinalitySignatureId,
+    sender: NodeId,
+) -> Effects<Event<FinalitySignature>> {
+    effect_builder
+        .get_signatures_from_storage(finality_signature_id.block_hash)
+        .event(move |maybe_signatures| {
+            let result = maybe_signatures
+                .and_then(|signatures| {
+                    signatures.finality_signature(&finality_signature_id.public_key)
+                })
+                .ok_or_else(|| String::from("failed to get finality signature from storage"));
+            Event::GetFromHolderResult {
+                item_id: finality_signature_id,
+                requester: sender,
+                result: Box::new(result),
+            }
+        })
+}
+
 /// The component which gossips to peers and handles incoming gossip messages from peers.
 #[allow(clippy::type_complexity)]
 #[derive(DataSize)]
@@ -105,6 +132,8 @@ where
     #[data_size(skip)] // Not well supported by datasize.
     get_from_holder:
         Box<dyn Fn(EffectBuilder<REv>, T::Id, NodeId) -> Effects<Event<T>> + Send + 'static>,
+    /// Whether a holder which fails to provide an item in time is reported as an offender.
+    penalize_unresponsive_holders: bool,
     #[data_size(skip)]
     metrics: Metrics,
 }
@@ -120,6 +149,10 @@ impl<T: Item + 'static, REv: ReactorEventT<T>> Gossiper<T, REv> {
     /// For an example of how `get_from_holder` should be implemented, see
     /// `gossiper::get_deploy_from_store()` which is used by `Gossiper<Deploy>`.
     ///
+    /// `penalize_unresponsive_holders` should be `false` where the component validating a received
+    /// item can hold on to it or drop it without announcing it, as the holder which provided it
+    /// would then appear not to have responded.
+    ///
     /// Must be supplied with a name, which should be a snake-case identifier to disambiguate the
     /// specific gossiper from other potentially present gossipers.
     pub(crate) fn new_for_partial_items(
@@ -128,6 +161,7 @@ impl<T: Item + 'static, REv: ReactorEventT<T>> Gossiper<T, REv> {
         get_from_holder: impl Fn(EffectBuilder<REv>, T::Id, NodeId) -> Effects<Event<T>>
             + Send
             + 'static,
+        penalize_unresponsive_holders: bool,
         registry: &Registry,
     ) -> Result<Self, prometheus::Error> {
         assert!(
@@ -139,6 +173,7 @@ impl<T: Item + 'static, REv: ReactorEventT<T>> Gossiper<T, REv> {
             gossip_timeout: config.gossip_request_timeout().into(),
             get_from_peer_timeout: config.get_remainder_timeout().into(),
             get_from_holder: Box::new(get_from_holder),
+            penalize_unresponsive_holders,
             metrics: Metrics::new(name, registry)?,
         })
     }
@@ -164,6 +199,7 @@ impl<T: Item + 'static, REv: ReactorEventT<T>> Gossiper<T, REv> {
             get_from_holder: Box::new(|_, item, _| {
                 panic!("gossiper should never try to get {}", item)
             }),
+            penalize_unresponsive_holders: true,
             metrics: Metrics::new(name, registry)?,
         })
     }
@@ -207,7 +243,7 @@ impl<T: Item + 'static, REv: ReactorEventT<T>> Gossiper<T, REv> {
         count: usize,
         exclude_peers: HashSet<NodeId>,
     ) -> Effects<Event<T>> {
-        let message = Message::Gossip(item_id);
+        let message = Message::Gossip(item_id.clone());
         effect_builder
             .gossip_message(message, count, exclude_peers)
             .event(move |peers| Event::GossipedTo {
@@ -240,11 +276,16 @@ impl<T: Item + 'static, REv: ReactorEventT<T>> Gossiper<T, REv> {
                 .table
                 .reduce_in_flight_count(&item_id, requested_count - peers.len())
         {
-            effects.extend(effect_builder.announce_finished_gossiping(item_id).ignore());
+            effects.extend(
+                effect_builder
+                    .announce_finished_gossiping(item_id.clone())
+                    .ignore(),
+            );
         }
 
         // Set timeouts to check later that the specified peers all responded.
         for peer in peers {
+            let item_id = item_id.clone();
             effects.extend(
                 effect_builder
                     .set_timeout(self.gossip_timeout)
@@ -303,16 +344,29 @@ impl<T: Item + 'static, REv: ReactorEventT<T>> Gossiper<T, REv> {
                 // The previous peer failed to provide the item, so we still need to get it.  Send
                 // a `GetRequest` to a different holder and set a timeout to check we got the
                 // response.
+                let mut effects = Effects::new();
+                if self.penalize_unresponsive_holders {
+                    effects.extend(
+                        effect_builder
+                            .announce_offense(peer, OffenseSeverity::Minor)
+                            .ignore(),
+                    );
+                }
                 let request = match NodeMessage::new_get_request::<T>(&item_id) {
                     Ok(request) => request,
                     Err(error) => {
                         error!("failed to create get-request: {}", error);
                         // Treat this as if the holder didn't respond - i.e. try to get from a
                         // different holder.
-                        return self.check_get_from_peer_timeout(effect_builder, item_id, holder);
+                        effects.extend(self.check_get_from_peer_timeout(
+                            effect_builder,
+                            item_id,
+                            holder,
+                        ));
+                        return effects;
                     }
                 };
-                let mut effects = effect_builder.send_message(holder, request).ignore();
+                effects.extend(effect_builder.send_message(holder, request).ignore());
                 effects.extend(
                     effect_builder
                         .set_timeout(self.get_from_peer_timeout)
@@ -353,7 +407,7 @@ impl<T: Item + 'static, REv: ReactorEventT<T>> Gossiper<T, REv> {
                 // Gossip the item ID.
                 let mut effects = self.gossip(
                     effect_builder,
-                    item_id,
+                    item_id.clone(),
                     should_gossip.count,
                     should_gossip.exclude_peers,
                 );
@@ -363,7 +417,7 @@ impl<T: Item + 'static, REv: ReactorEventT<T>> Gossiper<T, REv> {
                     debug!(item=%item_id, "announcing new complete gossip item received");
                     effects.extend(
                         effect_builder
-                            .announce_complete_item_received_via_gossip(item_id)
+                            .announce_complete_item_received_via_gossip(item_id.clone())
                             .ignore(),
                     );
                 }
@@ -381,7 +435,7 @@ impl<T: Item + 'static, REv: ReactorEventT<T>> Gossiper<T, REv> {
                 // Send a response to the sender indicating we want the full item from them, and set
                 // a timeout for this response.
                 let reply = Message::GossipResponse {
-                    item_id,
+                    item_id: item_id.clone(),
                     is_already_held: false,
                 };
                 let mut effects = effect_builder.send_message(sender, reply).ignore();
@@ -400,7 +454,7 @@ impl<T: Item + 'static, REv: ReactorEventT<T>> Gossiper<T, REv> {
             | GossipAction::AnnounceFinished => {
                 // Send a response to the sender indicating we already hold the item.
                 let reply = Message::GossipResponse {
-                    item_id,
+                    item_id: item_id.clone(),
                     is_already_held: true,
                 };
                 let mut effects = effect_builder.send_message(sender, reply).ignore();
@@ -429,7 +483,11 @@ impl<T: Item + 'static, REv: ReactorEventT<T>> Gossiper<T, REv> {
             if !T::ID_IS_COMPLETE_ITEM {
                 // `sender` doesn't hold the full item; get the item from the component responsible
                 // for holding it, then send it to `sender`.
-                effects.extend((self.get_from_holder)(effect_builder, item_id, sender));
+                effects.extend((self.get_from_holder)(
+                    effect_builder,
+                    item_id.clone(),
+                    sender,
+                ));
             }
             self.table.we_infected(&item_id, sender)
         };
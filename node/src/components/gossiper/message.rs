@@ -2,8 +2,13 @@ use std::fmt::{self, Display, Formatter};
 
 use serde::{Deserialize, Serialize};
 
+use casper_types::bytesrepr::{self, FromBytes, ToBytes, U8_SERIALIZED_LENGTH};
+
 use super::Item;
 
+const GOSSIP_TAG: u8 = 0;
+const GOSSIP_RESPONSE_TAG: u8 = 1;
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(bound = "for<'a> T: Deserialize<'a>")]
 pub enum Message<T: Item> {
@@ -32,3 +37,67 @@ impl<T: Item> Display for Message<T> {
         }
     }
 }
+
+impl<T> ToBytes for Message<T>
+where
+    T: Item,
+    T::Id: ToBytes,
+{
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut buffer = bytesrepr::allocate_buffer(self)?;
+        match self {
+            Message::Gossip(item_id) => {
+                buffer.push(GOSSIP_TAG);
+                buffer.extend(item_id.to_bytes()?);
+            }
+            Message::GossipResponse {
+                item_id,
+                is_already_held,
+            } => {
+                buffer.push(GOSSIP_RESPONSE_TAG);
+                buffer.extend(item_id.to_bytes()?);
+                buffer.extend(is_already_held.to_bytes()?);
+            }
+        }
+        Ok(buffer)
+    }
+
+    fn serialized_length(&self) -> usize {
+        U8_SERIALIZED_LENGTH
+            + match self {
+                Message::Gossip(item_id) => item_id.serialized_length(),
+                Message::GossipResponse {
+                    item_id,
+                    is_already_held,
+                } => item_id.serialized_length() + is_already_held.serialized_length(),
+            }
+    }
+}
+
+impl<T> FromBytes for Message<T>
+where
+    T: Item,
+    T::Id: FromBytes,
+{
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (tag, remainder) = u8::from_bytes(bytes)?;
+        match tag {
+            GOSSIP_TAG => {
+                let (item_id, remainder) = T::Id::from_bytes(remainder)?;
+                Ok((Message::Gossip(item_id), remainder))
+            }
+            GOSSIP_RESPONSE_TAG => {
+                let (item_id, remainder) = T::Id::from_bytes(remainder)?;
+                let (is_already_held, remainder) = bool::from_bytes(remainder)?;
+                Ok((
+                    Message::GossipResponse {
+                        item_id,
+                        is_already_held,
+                    },
+                    remainder,
+                ))
+            }
+            _ => Err(bytesrepr::Error::Formatting),
+        }
+    }
+}
@@ -33,8 +33,8 @@ use crate::{
     },
     effect::{
         announcements::{
-            ControlAnnouncement, DeployAcceptorAnnouncement, GossiperAnnouncement,
-            NetworkAnnouncement, RpcServerAnnouncement,
+            BlocklistAnnouncement, ControlAnnouncement, DeployAcceptorAnnouncement,
+            GossiperAnnouncement, NetworkAnnouncement, RpcServerAnnouncement,
         },
         requests::{ConsensusRequest, ContractRuntimeRequest, LinearChainRequest},
         Responder,
@@ -46,7 +46,7 @@ use crate::{
         network::{Network, NetworkedReactor},
         ConditionCheckReactor, TestRng,
     },
-    types::{Chainspec, Deploy, NodeId, Tag},
+    types::{BlockHash, BlockSignatures, Chainspec, Deploy, FinalitySignature, NodeId, Tag},
     utils::{Loadable, WithDir},
     NodeRng,
 };
@@ -66,6 +66,8 @@ enum Event {
     #[from]
     DeployGossiper(super::Event<Deploy>),
     #[from]
+    FinalitySignatureGossiper(super::Event<FinalitySignature>),
+    #[from]
     NetworkRequest(NetworkRequest<NodeId, NodeMessage>),
     #[from]
     ControlAnnouncement(ControlAnnouncement),
@@ -78,6 +80,12 @@ enum Event {
     #[from]
     DeployGossiperAnnouncement(#[serde(skip_serializing)] GossiperAnnouncement<Deploy>),
     #[from]
+    FinalitySignatureGossiperAnnouncement(
+        #[serde(skip_serializing)] GossiperAnnouncement<FinalitySignature>,
+    ),
+    #[from]
+    BlocklistAnnouncement(BlocklistAnnouncement<NodeId>),
+    #[from]
     ContractRuntime(#[serde(skip_serializing)] Box<ContractRuntimeRequest>),
 }
 
@@ -109,6 +117,12 @@ impl From<NetworkRequest<NodeId, Message<Deploy>>> for Event {
     }
 }
 
+impl From<NetworkRequest<NodeId, Message<FinalitySignature>>> for Event {
+    fn from(request: NetworkRequest<NodeId, Message<FinalitySignature>>) -> Self {
+        Event::NetworkRequest(request.map_payload(NodeMessage::from))
+    }
+}
+
 impl From<ConsensusRequest> for Event {
     fn from(_request: ConsensusRequest) -> Self {
         unimplemented!("not implemented for gossiper tests")
@@ -134,6 +148,9 @@ impl Display for Event {
             Event::Storage(event) => write!(formatter, "storage: {}", event),
             Event::DeployAcceptor(event) => write!(formatter, "deploy acceptor: {}", event),
             Event::DeployGossiper(event) => write!(formatter, "deploy gossiper: {}", event),
+            Event::FinalitySignatureGossiper(event) => {
+                write!(formatter, "finality signature gossiper: {}", event)
+            }
             Event::NetworkRequest(req) => write!(formatter, "network request: {}", req),
             Event::ControlAnnouncement(ctrl_ann) => write!(formatter, "control: {}", ctrl_ann),
             Event::NetworkAnnouncement(ann) => write!(formatter, "network announcement: {}", ann),
@@ -146,6 +163,16 @@ impl Display for Event {
             Event::DeployGossiperAnnouncement(ann) => {
                 write!(formatter, "deploy-gossiper announcement: {}", ann)
             }
+            Event::FinalitySignatureGossiperAnnouncement(ann) => {
+                write!(
+                    formatter,
+                    "finality-signature-gossiper announcement: {}",
+                    ann
+                )
+            }
+            Event::BlocklistAnnouncement(ann) => {
+                write!(formatter, "blocklist announcement: {}", ann)
+            }
             Event::ContractRuntime(event) => {
                 write!(formatter, "contract-runtime event: {:?}", event)
             }
@@ -165,7 +192,10 @@ struct Reactor {
     storage: Storage,
     deploy_acceptor: DeployAcceptor,
     deploy_gossiper: Gossiper<Deploy, Event>,
+    finality_signature_gossiper: Gossiper<FinalitySignature, Event>,
     contract_runtime: ContractRuntime,
+    /// The peers reported as offenders by this node.
+    offenders: Vec<NodeId>,
     _storage_tempdir: TempDir,
 }
 
@@ -221,7 +251,15 @@ impl reactor::Reactor for Reactor {
         let deploy_gossiper = Gossiper::new_for_partial_items(
             "deploy_gossiper",
             config,
-            get_deploy_from_storage,
+            get_deploy_from_storage::<Deploy, Event>,
+            true,
+            registry,
+        )?;
+        let finality_signature_gossiper = Gossiper::new_for_partial_items(
+            "finality_signature_gossiper",
+            config,
+            get_finality_signature_from_storage::<FinalitySignature, Event>,
+            false,
             registry,
         )?;
 
@@ -230,7 +268,9 @@ impl reactor::Reactor for Reactor {
             storage,
             deploy_acceptor,
             deploy_gossiper,
+            finality_signature_gossiper,
             contract_runtime,
+            offenders: vec![],
             _storage_tempdir: storage_tempdir,
         };
 
@@ -260,6 +300,11 @@ impl reactor::Reactor for Reactor {
                 self.deploy_gossiper
                     .handle_event(effect_builder, rng, event),
             ),
+            Event::FinalitySignatureGossiper(event) => reactor::wrap_effects(
+                Event::FinalitySignatureGossiper,
+                self.finality_signature_gossiper
+                    .handle_event(effect_builder, rng, event),
+            ),
             Event::NetworkRequest(request) => reactor::wrap_effects(
                 Event::Network,
                 self.network
@@ -325,9 +370,59 @@ impl reactor::Reactor for Reactor {
                             maybe_responder: None,
                         })
                     }
+                    NodeMessage::GetRequest {
+                        tag: Tag::FinalitySignature,
+                        serialized_id,
+                    } => {
+                        let finality_signature_id = match bincode::deserialize(&serialized_id) {
+                            Ok(finality_signature_id) => finality_signature_id,
+                            Err(error) => {
+                                error!(
+                                    "failed to decode {:?} from {}: {}",
+                                    serialized_id, sender, error
+                                );
+                                return Effects::new();
+                            }
+                        };
+                        match self.storage.read_finality_signature(&finality_signature_id) {
+                            Ok(Some(finality_signature)) => {
+                                let message =
+                                    NodeMessage::new_get_response(&finality_signature).unwrap();
+                                return effect_builder.send_message(sender, message).ignore();
+                            }
+                            Ok(None) | Err(_) => {
+                                debug!(%sender, %finality_signature_id, "failed to get finality signature");
+                                return Effects::new();
+                            }
+                        }
+                    }
+                    NodeMessage::GetResponse {
+                        tag: Tag::FinalitySignature,
+                        serialized_item,
+                    } => {
+                        let finality_signature = match bincode::deserialize(&serialized_item) {
+                            Ok(finality_signature) => finality_signature,
+                            Err(error) => {
+                                error!(
+                                    "failed to decode finality signature from {}: {}",
+                                    sender, error
+                                );
+                                return Effects::new();
+                            }
+                        };
+                        return store_finality_signature(finality_signature, Source::Peer(sender))(
+                            effect_builder,
+                        );
+                    }
                     NodeMessage::DeployGossiper(message) => {
                         Event::DeployGossiper(super::Event::MessageReceived { sender, message })
                     }
+                    NodeMessage::FinalitySignatureGossiper(message) => {
+                        Event::FinalitySignatureGossiper(super::Event::MessageReceived {
+                            sender,
+                            message,
+                        })
+                    }
                     msg => panic!("should not get {}", msg),
                 };
                 self.dispatch_event(effect_builder, rng, reactor_event)
@@ -368,6 +463,19 @@ impl reactor::Reactor for Reactor {
                 // We do not care about deploy gossiper announcements in the gossiper test.
                 Effects::new()
             }
+            Event::FinalitySignatureGossiperAnnouncement(_ann) => {
+                // We do not care about finality signature gossiper announcements in the gossiper
+                // test.
+                Effects::new()
+            }
+            Event::BlocklistAnnouncement(BlocklistAnnouncement::OffenseCommitted {
+                offender,
+                ..
+            }) => {
+                // The in-memory network does not block offending peers, so just record them.
+                self.offenders.push(*offender);
+                Effects::new()
+            }
             Event::Network(event) => reactor::wrap_effects(
                 Event::Network,
                 self.network.handle_event(effect_builder, rng, event),
@@ -404,6 +512,45 @@ fn announce_deploy_received(
     }
 }
 
+/// Stores the finality signature, standing in for its validation by the linear chain, then passes
+/// it to the gossiper.
+fn store_finality_signature(
+    finality_signature: FinalitySignature,
+    source: Source<NodeId>,
+) -> impl FnOnce(EffectBuilder<Event>) -> Effects<Event> {
+    move |effect_builder: EffectBuilder<Event>| {
+        let mut block_signatures =
+            BlockSignatures::new(finality_signature.block_hash, finality_signature.era_id);
+        block_signatures.insert_proof(
+            finality_signature.public_key.clone(),
+            finality_signature.signature,
+        );
+        effect_builder
+            .put_signatures_to_storage(block_signatures)
+            .event(move |_| {
+                Event::FinalitySignatureGossiper(super::Event::ItemReceived {
+                    item_id: finality_signature.id(),
+                    source,
+                })
+            })
+    }
+}
+
+/// Returns whether the node holds the finality signature in storage.
+fn holds_finality_signature(
+    runner: &Runner<ConditionCheckReactor<Reactor>>,
+    finality_signature: &FinalitySignature,
+) -> bool {
+    runner
+        .reactor()
+        .inner()
+        .storage
+        .read_finality_signature(&finality_signature.id())
+        .unwrap()
+        .as_ref()
+        == Some(finality_signature)
+}
+
 async fn run_gossip(rng: &mut TestRng, network_size: usize, deploy_count: usize) {
     const TIMEOUT: Duration = Duration::from_secs(20);
     const QUIET_FOR: Duration = Duration::from_millis(50);
@@ -531,6 +678,10 @@ async fn should_get_from_alternate_source() {
     };
     network.settle_on(&mut rng, deploy_held, TIMEOUT).await;
 
+    // Node 0 failed to provide the deploy it claimed to hold.
+    let offenders = &network.nodes()[&node_ids[2]].reactor().inner().offenders;
+    assert_eq!(offenders, &vec![node_ids[0]]);
+
     NetworkController::<NodeMessage>::remove_active();
 }
 
@@ -603,3 +754,133 @@ async fn should_timeout_gossip_response() {
 
     NetworkController::<NodeMessage>::remove_active();
 }
+
+#[tokio::test]
+async fn should_gossip_finality_signatures() {
+    const NETWORK_SIZE: usize = 5;
+    const SIGNATURE_COUNT: usize = 10;
+    const TIMEOUT: Duration = Duration::from_secs(20);
+    const QUIET_FOR: Duration = Duration::from_millis(50);
+
+    NetworkController::<NodeMessage>::create_active();
+    let mut network = Network::<Reactor>::new();
+    let mut rng = crate::new_rng();
+
+    // Add `NETWORK_SIZE` nodes.
+    let node_ids = network.add_nodes(&mut rng, NETWORK_SIZE).await;
+
+    // Create `SIGNATURE_COUNT` finality signatures for random blocks.
+    let finality_signatures: Vec<_> = iter::repeat_with(|| {
+        FinalitySignature::random_for_block(BlockHash::random(&mut rng), rng.gen())
+    })
+    .take(SIGNATURE_COUNT)
+    .collect();
+
+    // Give each finality signature to a randomly-chosen node to be gossiped.
+    for finality_signature in &finality_signatures {
+        let index: usize = rng.gen_range(0..NETWORK_SIZE);
+        network
+            .process_injected_effect_on(
+                &node_ids[index],
+                store_finality_signature(finality_signature.clone(), Source::Ourself),
+            )
+            .await;
+    }
+
+    // Check every node has every finality signature stored locally.
+    let all_signatures_held = |nodes: &HashMap<NodeId, Runner<ConditionCheckReactor<Reactor>>>| {
+        nodes.values().all(|runner| {
+            finality_signatures
+                .iter()
+                .all(|finality_signature| holds_finality_signature(runner, finality_signature))
+        })
+    };
+    network
+        .settle_on(&mut rng, all_signatures_held, TIMEOUT)
+        .await;
+
+    // Ensure all responders are called before dropping the network.
+    network.settle(&mut rng, QUIET_FOR, TIMEOUT).await;
+
+    NetworkController::<NodeMessage>::remove_active();
+}
+
+#[tokio::test]
+async fn should_get_finality_signature_from_alternate_source_without_penalizing() {
+    const NETWORK_SIZE: usize = 3;
+    const POLL_DURATION: Duration = Duration::from_millis(10);
+    const TIMEOUT: Duration = Duration::from_secs(2);
+
+    NetworkController::<NodeMessage>::create_active();
+    let mut network = Network::<Reactor>::new();
+    let mut rng = crate::new_rng();
+
+    // Add `NETWORK_SIZE` nodes.
+    let node_ids = network.add_nodes(&mut rng, NETWORK_SIZE).await;
+
+    // Create a finality signature for a random block.
+    let finality_signature = FinalitySignature::random_for_block(BlockHash::random(&mut rng), 1);
+
+    // Give the finality signature to nodes 0 and 1 to be gossiped.
+    for node_id in node_ids.iter().take(2) {
+        network
+            .process_injected_effect_on(
+                node_id,
+                store_finality_signature(finality_signature.clone(), Source::Ourself),
+            )
+            .await;
+    }
+
+    // Run node 0 until it has sent the gossip request then remove it from the network.
+    let made_gossip_request = |event: &Event| -> bool {
+        matches!(event, Event::NetworkRequest(NetworkRequest::Gossip { .. }))
+    };
+    network
+        .crank_until(&node_ids[0], &mut rng, made_gossip_request, TIMEOUT)
+        .await;
+    assert!(network.remove_node(&node_ids[0]).is_some());
+    debug!("removed node {}", &node_ids[0]);
+
+    // Run node 2 until it receives and responds to the gossip request from node 0.
+    let node_id_0 = node_ids[0];
+    let sent_gossip_response = move |event: &Event| -> bool {
+        match event {
+            Event::NetworkRequest(NetworkRequest::SendMessage { dest, payload, .. }) => {
+                if let NodeMessage::FinalitySignatureGossiper(Message::GossipResponse { .. }) =
+                    **payload
+                {
+                    **dest == node_id_0
+                } else {
+                    false
+                }
+            }
+            _ => false,
+        }
+    };
+    network
+        .crank_until(&node_ids[2], &mut rng, sent_gossip_response, TIMEOUT)
+        .await;
+
+    // Run nodes 1 and 2 until settled.  Node 2 will be waiting for the signature from node 0.
+    network.settle(&mut rng, POLL_DURATION, TIMEOUT).await;
+
+    // Advance time to trigger node 2's timeout causing it to request the signature by its ID from
+    // node 1.
+    let duration_to_advance = Config::default().get_remainder_timeout();
+    testing::advance_time(duration_to_advance.into()).await;
+
+    // Check node 2 has the finality signature stored locally.
+    let signature_held = |nodes: &HashMap<NodeId, Runner<ConditionCheckReactor<Reactor>>>| {
+        holds_finality_signature(&nodes[&node_ids[2]], &finality_signature)
+    };
+    network.settle_on(&mut rng, signature_held, TIMEOUT).await;
+
+    // Node 0 may have held the signature back pending its validation, so isn't penalized.
+    assert!(network.nodes()[&node_ids[2]]
+        .reactor()
+        .inner()
+        .offenders
+        .is_empty());
+
+    NetworkController::<NodeMessage>::remove_active();
+}
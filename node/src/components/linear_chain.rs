@@ -85,13 +85,9 @@ where
             Outcome::StoreBlock(block) => effect_builder
                 .put_block_to_storage(block.clone())
                 .event(move |_| Event::PutBlockResult { block }),
-            Outcome::Gossip(fs) => {
-                let message = Message::FinalitySignature(fs);
-                effect_builder.broadcast_message(message).ignore()
-            }
-            Outcome::AnnounceSignature(fs) => {
-                effect_builder.announce_finality_signature(fs).ignore()
-            }
+            Outcome::AnnounceSignature(fs, source) => effect_builder
+                .announce_finality_signature(fs, source)
+                .ignore(),
             Outcome::AnnounceBlock(block) => effect_builder.announce_block_added(block).ignore(),
             Outcome::LoadSignatures(fs) => effect_builder
                 .get_signatures_from_storage(fs.block_hash)
@@ -187,10 +183,10 @@ where
                 let outcomes = self.linear_chain_state.handle_put_block(block);
                 outcomes_to_effects(effect_builder, outcomes)
             }
-            Event::FinalitySignatureReceived(fs, gossiped) => {
+            Event::FinalitySignatureReceived(fs, source) => {
                 let outcomes = self
                     .linear_chain_state
-                    .handle_finality_signature(fs, gossiped);
+                    .handle_finality_signature(fs, source);
                 outcomes_to_effects(effect_builder, outcomes)
             }
             Event::GetStoredFinalitySignaturesResult(fs, maybe_signatures) => {
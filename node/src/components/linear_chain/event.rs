@@ -8,7 +8,8 @@ use derive_more::From;
 
 use crate::{
     effect::requests::LinearChainRequest,
-    types::{Block, BlockSignatures, DeployHash, FinalitySignature},
+    types::{Block, BlockSignatures, DeployHash, FinalitySignature, NodeId},
+    utils::Source,
 };
 
 #[derive(Debug, From)]
@@ -23,9 +24,9 @@ pub(crate) enum Event<I> {
         /// The deploys' execution results.
         execution_results: HashMap<DeployHash, ExecutionResult>,
     },
-    /// Finality signature received.
+    /// Finality signature received from the given source.
     /// Not necessarily _new_ finality signature.
-    FinalitySignatureReceived(Box<FinalitySignature>, bool),
+    FinalitySignatureReceived(Box<FinalitySignature>, Source<NodeId>),
     /// The result of putting a block to storage.
     PutBlockResult {
         /// The block.
@@ -44,10 +45,10 @@ impl<I: Display> Display for Event<I> {
             Event::NewLinearChainBlock { block, .. } => {
                 write!(f, "linear chain new block: {}", block.hash())
             }
-            Event::FinalitySignatureReceived(fs, gossiped) => write!(
+            Event::FinalitySignatureReceived(fs, source) => write!(
                 f,
-                "linear-chain new finality signature for block: {}, from: {}, external: {}",
-                fs.block_hash, fs.public_key, gossiped
+                "linear-chain new finality signature for block: {}, from: {}, source: {}",
+                fs.block_hash, fs.public_key, source
             ),
             Event::PutBlockResult { .. } => write!(f, "linear-chain put-block result"),
             Event::GetStoredFinalitySignaturesResult(finality_signature, maybe_signatures) => {
@@ -145,7 +145,11 @@ impl PendingSignatures {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{crypto::generate_ed25519_keypair, testing::TestRng, types::FinalitySignature};
+    use crate::{
+        crypto::generate_ed25519_keypair,
+        testing::TestRng,
+        types::{FinalitySignature, NodeId},
+    };
     use casper_types::EraId;
 
     use std::collections::BTreeMap;
@@ -160,7 +164,10 @@ mod tests {
         let sig_b = FinalitySignature::random_for_block(block_hash_other, 0);
         let public_key = sig_a.public_key.clone();
         let public_key_other = sig_b.public_key;
-        assert!(pending_sigs.add(Signature::External(Box::new(sig_a))));
+        assert!(pending_sigs.add(Signature::External(
+            Box::new(sig_a),
+            NodeId::random(&mut rng)
+        )));
         assert!(pending_sigs.has_finality_signature(&public_key, &block_hash));
         assert!(!pending_sigs.has_finality_signature(&public_key_other, &block_hash));
         assert!(!pending_sigs.has_finality_signature(&public_key, &block_hash_other));
@@ -175,11 +182,20 @@ mod tests {
         let sig_a1 = FinalitySignature::random_for_block(block_hash, 0);
         let sig_a2 = FinalitySignature::random_for_block(block_hash, 0);
         let sig_b = FinalitySignature::random_for_block(block_hash_other, 0);
-        assert!(pending_sigs.add(Signature::External(Box::new(sig_a1.clone()))));
+        assert!(pending_sigs.add(Signature::External(
+            Box::new(sig_a1.clone()),
+            NodeId::random(&mut rng)
+        )));
         assert!(pending_sigs.mark_bonded(sig_a1.public_key.clone(), block_hash));
-        assert!(pending_sigs.add(Signature::External(Box::new(sig_a2.clone()))));
+        assert!(pending_sigs.add(Signature::External(
+            Box::new(sig_a2.clone()),
+            NodeId::random(&mut rng)
+        )));
         assert!(pending_sigs.mark_bonded(sig_a2.public_key.clone(), block_hash));
-        assert!(pending_sigs.add(Signature::External(Box::new(sig_b))));
+        assert!(pending_sigs.add(Signature::External(
+            Box::new(sig_b),
+            NodeId::random(&mut rng)
+        )));
         let collected_sigs: BTreeMap<PublicKey, FinalitySignature> = pending_sigs
             .collect_pending(&block_hash)
             .into_iter()
@@ -206,7 +222,10 @@ mod tests {
         let mut pending_sigs = PendingSignatures::new();
         let block_hash = BlockHash::random(&mut rng);
         let sig = FinalitySignature::random_for_block(block_hash, 0);
-        assert!(pending_sigs.add(Signature::External(Box::new(sig.clone()))));
+        assert!(pending_sigs.add(Signature::External(
+            Box::new(sig.clone()),
+            NodeId::random(&mut rng)
+        )));
         let removed_sig = pending_sigs.remove(&sig.public_key, &sig.block_hash);
         assert!(removed_sig.is_some());
         assert!(!pending_sigs.has_finality_signature(&sig.public_key, &sig.block_hash));
@@ -224,11 +243,11 @@ mod tests {
         for _ in 0..MAX_PENDING_FINALITY_SIGNATURES_PER_VALIDATOR {
             let block_hash = BlockHash::random(&mut rng);
             let sig = FinalitySignature::new(block_hash, era_id, &sec_key, pub_key.clone());
-            assert!(pending_sigs.add(Signature::External(Box::new(sig))));
+            assert!(pending_sigs.add(Signature::External(Box::new(sig), NodeId::random(&mut rng))));
         }
         let block_hash = BlockHash::random(&mut rng);
         let sig = FinalitySignature::new(block_hash, era_id, &sec_key, pub_key);
-        assert!(!pending_sigs.add(Signature::External(Box::new(sig))));
+        assert!(!pending_sigs.add(Signature::External(Box::new(sig), NodeId::random(&mut rng))));
     }
 
     #[test]
@@ -241,7 +260,10 @@ mod tests {
         let block_hash = BlockHash::random(&mut rng);
         let sig_a = FinalitySignature::random_for_block(block_hash, 0);
         let public_key = sig_a.public_key.clone();
-        assert!(pending_sigs.add(Signature::External(Box::new(sig_a))));
+        assert!(pending_sigs.add(Signature::External(
+            Box::new(sig_a),
+            NodeId::random(&mut rng)
+        )));
         assert!(pending_sigs.has_finality_signature(&public_key, &block_hash));
         assert!(pending_sigs.mark_bonded(public_key.clone(), block_hash));
         assert!(pending_sigs.has_finality_signature(&public_key, &block_hash));
@@ -1,19 +1,30 @@
 use casper_types::PublicKey;
 use datasize::DataSize;
 
-use crate::types::{BlockHash, FinalitySignature};
+use crate::{
+    types::{BlockHash, FinalitySignature, NodeId},
+    utils::Source,
+};
 
 #[derive(DataSize, Debug, Clone)]
 pub(super) enum Signature {
     Local(Box<FinalitySignature>),
-    External(Box<FinalitySignature>),
+    External(Box<FinalitySignature>, NodeId),
 }
 
 impl Signature {
     pub(super) fn to_inner(&self) -> &FinalitySignature {
         match self {
             Signature::Local(fs) => fs,
-            Signature::External(fs) => fs,
+            Signature::External(fs, _) => fs,
+        }
+    }
+
+    /// Returns where the signature came from.
+    pub(super) fn source(&self) -> Source<NodeId> {
+        match self {
+            Signature::Local(_) => Source::Ourself,
+            Signature::External(_, sender) => Source::Peer(*sender),
         }
     }
 
@@ -31,11 +42,7 @@ impl Signature {
 
     pub(super) fn take(self) -> Box<FinalitySignature> {
         match self {
-            Signature::Local(fs) | Signature::External(fs) => fs,
+            Signature::Local(fs) | Signature::External(fs, _) => fs,
         }
     }
-
-    pub(super) fn is_local(&self) -> bool {
-        matches!(self, Signature::Local(_))
-    }
 }
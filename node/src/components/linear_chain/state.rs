@@ -10,7 +10,10 @@ use casper_types::{ExecutionResult, ProtocolVersion};
 use super::{
     pending_signatures::PendingSignatures, signature::Signature, signature_cache::SignatureCache,
 };
-use crate::types::{Block, BlockHash, BlockSignatures, DeployHash, FinalitySignature};
+use crate::{
+    types::{Block, BlockHash, BlockSignatures, DeployHash, FinalitySignature, NodeId},
+    utils::Source,
+};
 
 #[derive(DataSize, Debug)]
 pub(crate) struct LinearChain {
@@ -35,10 +38,9 @@ pub(super) enum Outcome {
     StoreBlock(Box<Block>),
     // Read finality signatures for the block from storage.
     LoadSignatures(Box<FinalitySignature>),
-    // Gossip finality signature to peers.
-    Gossip(Box<FinalitySignature>),
-    // Create a reactor announcement about new (valid) finality signatures.
-    AnnounceSignature(Box<FinalitySignature>),
+    // Create a reactor announcement about new (valid) finality signatures, along with where they
+    // came from. The reactor gossips them to peers.
+    AnnounceSignature(Box<FinalitySignature>, Source<NodeId>),
     // Create a reactor announcement about new (valid) block.
     AnnounceBlock(Box<Block>),
     // Check if creator of `new_fs` is known trusted validator.
@@ -106,7 +108,11 @@ impl LinearChain {
 
     /// Tries to add the finality signature to the collection of pending finality signatures.
     /// Returns true if added successfully, otherwise false.
-    fn add_pending_finality_signature(&mut self, fs: FinalitySignature, gossiped: bool) -> bool {
+    fn add_pending_finality_signature(
+        &mut self,
+        fs: FinalitySignature,
+        source: Source<NodeId>,
+    ) -> bool {
         let FinalitySignature {
             block_hash,
             public_key,
@@ -151,10 +157,9 @@ impl LinearChain {
             return false;
         }
         debug!(%block_hash, %public_key, "received new finality signature");
-        let signature = if gossiped {
-            Signature::External(Box::new(fs))
-        } else {
-            Signature::Local(Box::new(fs))
+        let signature = match source {
+            Source::Peer(sender) => Signature::External(Box::new(fs), sender),
+            Source::Client | Source::Ourself => Signature::Local(Box::new(fs)),
         };
         self.pending_finality_signatures.add(signature)
     }
@@ -232,10 +237,8 @@ impl LinearChain {
             }
             outcomes.push(Outcome::StoreBlockSignatures(block_signatures));
             for signature in signatures {
-                if signature.is_local() {
-                    outcomes.push(Outcome::Gossip(Box::new(signature.to_inner().clone())));
-                }
-                outcomes.push(Outcome::AnnounceSignature(signature.take()));
+                let source = signature.source();
+                outcomes.push(Outcome::AnnounceSignature(signature.take(), source));
             }
         };
         let block_hash = *block.hash();
@@ -255,10 +258,10 @@ impl LinearChain {
     pub(super) fn handle_finality_signature(
         &mut self,
         fs: Box<FinalitySignature>,
-        gossiped: bool,
+        source: Source<NodeId>,
     ) -> Outcomes {
         let FinalitySignature { block_hash, .. } = *fs;
-        if !self.add_pending_finality_signature(*fs.clone(), gossiped) {
+        if !self.add_pending_finality_signature(*fs.clone(), source) {
             // If we did not add the signature it means it's either incorrect or we already
             // know it.
             return vec![];
@@ -358,17 +361,14 @@ impl LinearChain {
                 // manage to store it in the database.
                 self.cache_signatures(*known_signatures.clone());
                 debug!(hash = %known_signatures.block_hash, "storing finality signatures");
-                // Announce new finality signatures for other components to pick up.
-                let mut outcomes = vec![Outcome::AnnounceSignature(new_fs.clone())];
+                let mut outcomes = vec![];
+                // This shouldn't return `None` as we added the `fs` to the pending collection when we
+                // received it. If it _is_ `None` then a concurrent flow must have already removed
+                // and announced it.
                 if let Some(signature) = self.remove_from_pending_fs(&*new_fs) {
-                    // This shouldn't return `None` as we added the `fs` to the pending collection
-                    // when we received it. If it _is_ `None` then a concurrent
-                    // flow must have already removed it. If it's a signature
-                    // created by this node, gossip it.
-                    if signature.is_local() {
-                        outcomes.push(Outcome::Gossip(new_fs.clone()));
-                    }
-                };
+                    // Announce new finality signatures for other components to pick up.
+                    outcomes.push(Outcome::AnnounceSignature(new_fs, signature.source()));
+                }
                 outcomes.push(Outcome::StoreBlockSignatures(*known_signatures));
                 outcomes
             }
@@ -423,10 +423,10 @@ mod tests {
         lc: &mut LinearChain,
         block_hash: BlockHash,
         era_id: EraId,
-        local: bool,
+        source: Source<NodeId>,
     ) -> FinalitySignature {
         let sig = FinalitySignature::random_for_block(block_hash, era_id.value());
-        let outcomes = lc.handle_finality_signature(Box::new(sig.clone()), !local);
+        let outcomes = lc.handle_finality_signature(Box::new(sig.clone()), source);
         assert!(matches!(&*outcomes, [Outcome::LoadSignatures(_)]));
         sig
     }
@@ -446,9 +446,19 @@ mod tests {
         let block_hash = *block.hash();
         let block_era = block.header().era_id();
         // Store some pending finality signatures
-        let _sig_a = add_pending(&mut lc, block_hash, block_era, true);
-        let _sig_b = add_pending(&mut lc, block_hash, block_era, false);
-        let _sig_c = add_pending(&mut lc, block_hash, block_era, false);
+        let _sig_a = add_pending(&mut lc, block_hash, block_era, Source::Ourself);
+        let _sig_b = add_pending(
+            &mut lc,
+            block_hash,
+            block_era,
+            Source::Peer(NodeId::random(&mut rng)),
+        );
+        let _sig_c = add_pending(
+            &mut lc,
+            block_hash,
+            block_era,
+            Source::Peer(NodeId::random(&mut rng)),
+        );
 
         let execution_results = HashMap::new();
         let outcomes = lc.handle_new_block(Box::new(block), execution_results);
@@ -488,9 +498,10 @@ mod tests {
         let block_hash = *block.hash();
         let block_era = block.header().era_id();
         // Store some pending finality signatures
-        let sig_a = add_pending(&mut lc, block_hash, block_era, true);
-        let sig_b = add_pending(&mut lc, block_hash, block_era, false);
-        let sig_c = add_pending(&mut lc, block_hash, block_era, true);
+        let sender_b = NodeId::random(&mut rng);
+        let sig_a = add_pending(&mut lc, block_hash, block_era, Source::Ourself);
+        let sig_b = add_pending(&mut lc, block_hash, block_era, Source::Peer(sender_b));
+        let sig_c = add_pending(&mut lc, block_hash, block_era, Source::Ourself);
         // Mark two of the creators as bonded.
         mark_bonded(&mut lc, sig_a.clone());
         mark_bonded(&mut lc, sig_b.clone());
@@ -503,10 +514,14 @@ mod tests {
             block_signatures.insert_proof(sig_a.public_key.clone(), sig_a.signature);
             block_signatures.insert_proof(sig_b.public_key.clone(), sig_b.signature);
             tmp.push(Outcome::StoreBlockSignatures(block_signatures));
-            // Only `sig_a` was created locally and we don't "regossip" incoming signatures.
-            tmp.push(Outcome::Gossip(Box::new(sig_a.clone())));
-            tmp.push(Outcome::AnnounceSignature(Box::new(sig_a.clone())));
-            tmp.push(Outcome::AnnounceSignature(Box::new(sig_b.clone())));
+            tmp.push(Outcome::AnnounceSignature(
+                Box::new(sig_a.clone()),
+                Source::Ourself,
+            ));
+            tmp.push(Outcome::AnnounceSignature(
+                Box::new(sig_b.clone()),
+                Source::Peer(sender_b),
+            ));
             tmp.push(Outcome::StoreBlock(Box::new(block)));
             tmp.push(Outcome::StoreExecutionResults(
                 block_hash,
@@ -522,8 +537,10 @@ mod tests {
         #[allow(clippy::vec_init_then_push)]
         let expected_outcomes = {
             let mut tmp = vec![];
-            tmp.push(Outcome::AnnounceSignature(Box::new(sig_c.clone())));
-            tmp.push(Outcome::Gossip(Box::new(sig_c.clone())));
+            tmp.push(Outcome::AnnounceSignature(
+                Box::new(sig_c.clone()),
+                Source::Ourself,
+            ));
             let mut block_signatures = BlockSignatures::new(block_hash, block_era);
             block_signatures.insert_proof(sig_a.public_key.clone(), sig_a.signature);
             block_signatures.insert_proof(sig_b.public_key.clone(), sig_b.signature);
@@ -541,13 +558,14 @@ mod tests {
         let mut lc = LinearChain::new(protocol_version, 1u64, 1u64);
         let block_hash = BlockHash::random(&mut rng);
         let valid_sig = FinalitySignature::random_for_block(block_hash, 0);
-        let handle_sig_outcomes = lc.handle_finality_signature(Box::new(valid_sig.clone()), false);
+        let handle_sig_outcomes =
+            lc.handle_finality_signature(Box::new(valid_sig.clone()), Source::Ourself);
         assert!(matches!(
             &*handle_sig_outcomes,
             &[Outcome::LoadSignatures(_)]
         ));
         assert!(
-            lc.handle_finality_signature(Box::new(valid_sig), false)
+            lc.handle_finality_signature(Box::new(valid_sig), Source::Ourself)
                 .is_empty(),
             "adding already-pending signature should be a no-op"
         );
@@ -556,6 +574,9 @@ mod tests {
     // Forces caching of the finality signature. Requires confirming that creator is known to be
     // bonded.
     fn cache_signature(lc: &mut LinearChain, fs: FinalitySignature) {
+        // Validated signatures are only announced if they were pending.
+        let outcomes = lc.handle_finality_signature(Box::new(fs.clone()), Source::Ourself);
+        assert!(matches!(&*outcomes, [Outcome::LoadSignatures(_)]));
         // We need to signal that block is known. Otherwise we won't cache the signature.
         let mut block_signatures = BlockSignatures::new(fs.block_hash, fs.era_id);
         let outcomes = lc.handle_cached_signatures(
@@ -571,7 +592,7 @@ mod tests {
                 // After confirming that signature is valid and block known, we want to store the
                 // signature and announce it.
                 match &*outcomes {
-                    [Outcome::AnnounceSignature(outcome_fs), Outcome::StoreBlockSignatures(outcome_block_signatures)] =>
+                    [Outcome::AnnounceSignature(outcome_fs, Source::Ourself), Outcome::StoreBlockSignatures(outcome_block_signatures)] =>
                     {
                         assert_eq!(&fs, &**outcome_fs);
                         // LinearChain component will update the `block_signatures` with a new
@@ -596,7 +617,7 @@ mod tests {
         let valid_sig =
             FinalitySignature::random_for_block(*block.hash(), block.header().era_id().value());
         cache_signature(&mut lc, valid_sig.clone());
-        let outcomes = lc.handle_finality_signature(Box::new(valid_sig), false);
+        let outcomes = lc.handle_finality_signature(Box::new(valid_sig), Source::Ourself);
         assert!(
             outcomes.is_empty(),
             "adding already-known signature should be a no-op"
@@ -630,11 +651,11 @@ mod tests {
         );
         // signature's era either too low or too high
         let era_too_low_sig = FinalitySignature::random_for_block(block_hash, 0);
-        let outcomes = lc.handle_finality_signature(Box::new(era_too_low_sig), false);
+        let outcomes = lc.handle_finality_signature(Box::new(era_too_low_sig), Source::Ourself);
         assert!(outcomes.is_empty());
         let era_too_high_sig =
             FinalitySignature::random_for_block(block_hash, block_era.value() + auction_delay + 1);
-        let outcomes = lc.handle_finality_signature(Box::new(era_too_high_sig), false);
+        let outcomes = lc.handle_finality_signature(Box::new(era_too_high_sig), Source::Ourself);
         assert!(outcomes.is_empty());
         // signature is not valid
         let block_hash = BlockHash::random(&mut rng);
@@ -642,7 +663,7 @@ mod tests {
         let mut invalid_sig = FinalitySignature::random_for_block(block_hash, block_era.value());
         // replace the public key so that the verification fails.
         invalid_sig.public_key = pub_key;
-        let outcomes = lc.handle_finality_signature(Box::new(invalid_sig), false);
+        let outcomes = lc.handle_finality_signature(Box::new(invalid_sig), Source::Ourself);
         assert!(outcomes.is_empty())
     }
 
@@ -672,7 +693,7 @@ mod tests {
         // Verify that all outcomes are expected.
         assert_equal(expected_outcomes, put_block_outcomes);
         let valid_sig = FinalitySignature::random_for_block(block_hash, block_era.value());
-        let outcomes = lc.handle_finality_signature(Box::new(valid_sig.clone()), false);
+        let outcomes = lc.handle_finality_signature(Box::new(valid_sig.clone()), Source::Ourself);
         assert!(matches!(&*outcomes, [Outcome::LoadSignatures(_)]));
         let cached_sigs_outcomes = lc.handle_cached_signatures(None, Box::new(valid_sig.clone()));
         assert!(matches!(
@@ -686,8 +707,7 @@ mod tests {
             block_signatures.insert_proof(valid_sig.public_key.clone(), valid_sig.signature);
             vec![
                 Outcome::StoreBlockSignatures(block_signatures),
-                Outcome::Gossip(Box::new(valid_sig.clone())),
-                Outcome::AnnounceSignature(Box::new(valid_sig)),
+                Outcome::AnnounceSignature(Box::new(valid_sig), Source::Ourself),
             ]
         };
         // Verify that all outcomes are expected.
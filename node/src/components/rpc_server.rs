@@ -104,6 +104,7 @@ impl RpcServer {
             effect_builder,
             api_version,
             config.qps_limit,
+            config.enable_speculative_exec,
         ));
 
         Ok(RpcServer {
@@ -266,6 +267,12 @@ where
                     peers,
                     main_responder: responder,
                 }),
+            Event::RpcRequest(RpcRequest::GetBlockedPeers { responder }) => effect_builder
+                .network_blocked_peers()
+                .event(move |blocked_peers| Event::GetBlockedPeersResult {
+                    blocked_peers,
+                    main_responder: responder,
+                }),
             Event::RpcRequest(RpcRequest::GetStatus { responder }) => {
                 let node_uptime = self.node_startup_instant.elapsed();
                 async move {
@@ -321,6 +328,10 @@ where
                 peers,
                 main_responder,
             } => main_responder.respond(peers).ignore(),
+            Event::GetBlockedPeersResult {
+                blocked_peers,
+                main_responder,
+            } => main_responder.respond(blocked_peers).ignore(),
         }
     }
 }
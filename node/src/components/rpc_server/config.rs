@@ -18,6 +18,10 @@ pub struct Config {
 
     /// Max rate limit in qps.
     pub qps_limit: u64,
+
+    /// Setting to enable the `speculative_exec` JSON-RPC endpoint.
+    #[serde(default)]
+    pub enable_speculative_exec: bool,
 }
 
 impl Config {
@@ -26,6 +30,7 @@ impl Config {
         Config {
             address: DEFAULT_ADDRESS.to_string(),
             qps_limit: DEFAULT_QPS_LIMIT,
+            enable_speculative_exec: false,
         }
     }
 }
@@ -13,7 +13,9 @@ use casper_types::{system::auction::EraValidators, Transfer};
 use crate::{
     effect::{requests::RpcRequest, Responder},
     rpcs::chain::BlockIdentifier,
-    types::{Block, BlockHash, BlockSignatures, Deploy, DeployHash, DeployMetadata, NodeId},
+    types::{
+        Block, BlockHash, BlockSignatures, BlockedPeer, Deploy, DeployHash, DeployMetadata, NodeId,
+    },
 };
 
 #[derive(Debug, From)]
@@ -51,6 +53,10 @@ pub(crate) enum Event {
         peers: BTreeMap<NodeId, String>,
         main_responder: Responder<BTreeMap<NodeId, String>>,
     },
+    GetBlockedPeersResult {
+        blocked_peers: BTreeMap<NodeId, BlockedPeer>,
+        main_responder: Responder<BTreeMap<NodeId, BlockedPeer>>,
+    },
     GetBalanceResult {
         result: Result<BalanceResult, engine_state::Error>,
         main_responder: Responder<Result<BalanceResult, engine_state::Error>>,
@@ -99,6 +105,9 @@ impl Display for Event {
                 write!(formatter, "get deploy result for {}: {:?}", hash, result)
             }
             Event::GetPeersResult { peers, .. } => write!(formatter, "get peers: {}", peers.len()),
+            Event::GetBlockedPeersResult { blocked_peers, .. } => {
+                write!(formatter, "get blocked peers: {}", blocked_peers.len())
+            }
         }
     }
 }
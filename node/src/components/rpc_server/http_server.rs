@@ -44,28 +44,54 @@ fn new_error_response(error: warp_json_rpc::Error) -> Response<Body> {
         .unwrap()
 }
 
+/// Returns a filter which passes requests through only if `enabled` is true.
+///
+/// Rejected requests fall through to the filter catching unknown methods.
+fn enabled_filter(enabled: bool) -> impl Filter<Extract = (), Error = Rejection> + Clone {
+    warp::any()
+        .and_then(move || async move {
+            if enabled {
+                Ok(())
+            } else {
+                Err(warp::reject::not_found())
+            }
+        })
+        .untuple_one()
+}
+
 /// Run the JSON-RPC server.
 pub(super) async fn run<REv: ReactorEventT>(
     builder: Builder<AddrIncoming>,
     effect_builder: EffectBuilder<REv>,
     api_version: ProtocolVersion,
     qps_limit: u64,
+    enable_speculative_exec: bool,
 ) {
     // RPC filters.
     let rpc_put_deploy = rpcs::account::PutDeploy::create_filter(effect_builder, api_version);
+    let rpc_speculative_exec = enabled_filter(enable_speculative_exec).and(
+        rpcs::account::SpeculativeExec::create_filter(effect_builder, api_version),
+    );
+    let rpc_get_account_deploys =
+        rpcs::account::GetAccountDeploys::create_filter(effect_builder, api_version);
     let rpc_get_block = rpcs::chain::GetBlock::create_filter(effect_builder, api_version);
     let rpc_get_block_transfers =
         rpcs::chain::GetBlockTransfers::create_filter(effect_builder, api_version);
     let rpc_get_state_root_hash =
         rpcs::chain::GetStateRootHash::create_filter(effect_builder, api_version);
+    let rpc_get_block_headers =
+        rpcs::chain::GetBlockHeaders::create_filter(effect_builder, api_version);
     let rpc_get_item = rpcs::state::GetItem::create_filter(effect_builder, api_version);
     let rpc_query_global_state =
         rpcs::state::QueryGlobalState::create_filter(effect_builder, api_version);
+    let rpc_query_batch = rpcs::state::QueryBatch::create_filter(effect_builder, api_version);
     let rpc_get_balance = rpcs::state::GetBalance::create_filter(effect_builder, api_version);
     let rpc_get_account_info =
         rpcs::state::GetAccountInfo::create_filter(effect_builder, api_version);
     let rpc_get_deploy = rpcs::info::GetDeploy::create_filter(effect_builder, api_version);
     let rpc_get_peers = rpcs::info::GetPeers::create_filter(effect_builder, api_version);
+    let rpc_get_blocked_peers =
+        rpcs::info::GetBlockedPeers::create_filter(effect_builder, api_version);
     let rpc_get_status = rpcs::info::GetStatus::create_filter(effect_builder, api_version);
     let rpc_get_era_info =
         rpcs::chain::GetEraInfoBySwitchBlock::create_filter(effect_builder, api_version);
@@ -100,7 +126,9 @@ pub(super) async fn run<REv: ReactorEventT>(
         .or(rpc_get_item)
         .or(rpc_get_balance)
         .or(rpc_get_deploy)
+        .or(rpc_get_account_deploys)
         .or(rpc_get_peers)
+        .or(rpc_get_blocked_peers)
         .or(rpc_get_status)
         .or(rpc_get_era_info)
         .or(rpc_get_auction_info)
@@ -110,6 +138,9 @@ pub(super) async fn run<REv: ReactorEventT>(
         .or(rpc_get_dictionary_item)
         .or(rpc_get_trie)
         .or(rpc_query_global_state)
+        .or(rpc_query_batch)
+        .or(rpc_speculative_exec)
+        .or(rpc_get_block_headers)
         .or(unknown_method)
         .or(parse_failure);
 
@@ -150,3 +181,54 @@ pub(super) async fn run<REv: ReactorEventT>(
 
     trace!("JSON-RPC server stopped");
 }
+
+#[cfg(test)]
+mod tests {
+    use hyper::{service::Service, Request};
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::{
+        reactor::{participating::ParticipatingEvent, EventQueueHandle, QueueKind, Scheduler},
+        rpcs::RpcWithParams,
+        utils,
+    };
+
+    /// Sends a `speculative_exec` request without params to a service serving the
+    /// `speculative_exec` filter gated by `enabled`, and returns the response.
+    async fn request_without_params(enabled: bool) -> Response<Body> {
+        let scheduler = utils::leak(Scheduler::<ParticipatingEvent>::new(QueueKind::weights()));
+        let effect_builder = EffectBuilder::new(EventQueueHandle::without_shutdown(scheduler));
+        let filter = enabled_filter(enabled).and(rpcs::account::SpeculativeExec::create_filter(
+            effect_builder,
+            ProtocolVersion::V1_0_0,
+        ));
+        let body = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": rpcs::account::SpeculativeExec::METHOD,
+        });
+        let request = Request::post(format!("/{}", RPC_API_PATH))
+            .header("Content-Type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        warp_json_rpc::service(filter).call(request).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn should_serve_speculative_exec_if_enabled() {
+        let response = request_without_params(true).await;
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let body: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(
+            body["error"]["code"],
+            json!(warp_json_rpc::Error::INVALID_PARAMS.code)
+        );
+    }
+
+    #[tokio::test]
+    async fn should_reject_speculative_exec_if_disabled() {
+        let response = request_without_params(false).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+}
@@ -49,6 +49,10 @@ enum ErrorCode {
     NoSuchAccount = -32009,
     FailedToGetDictionaryURef = -32010,
     FailedToGetTrie = -32011,
+    TooManyBatchQueries = -32012,
+    SpeculativeExecFailed = -32013,
+    BlockPruned = -32014,
+    DeployPruned = -32015,
     // Same error code as warp_json INTERNAL_ERROR.
     InternalError = -32063,
 }
@@ -3,7 +3,7 @@
 // TODO - remove once schemars stops causing warning.
 #![allow(clippy::field_reassign_with_default)]
 
-use std::str;
+use std::{ops::Bound, str};
 
 use futures::{future::BoxFuture, FutureExt};
 use http::Response;
@@ -14,19 +14,25 @@ use serde::{Deserialize, Serialize};
 use tracing::info;
 use warp_json_rpc::Builder;
 
-use casper_types::ProtocolVersion;
+use casper_types::{ExecutionResult, ProtocolVersion, PublicKey};
 
 use super::{
+    chain::{self, BlockIdentifier},
     docs::{DocExample, DOCS_EXAMPLE_PROTOCOL_VERSION},
     Error, ReactorEventT, RpcRequest, RpcWithParams, RpcWithParamsExt,
 };
 use crate::{
-    components::rpc_server::rpcs::ErrorCode,
+    components::{contract_runtime::SpeculativeExecutionResult, rpc_server::rpcs::ErrorCode},
     effect::EffectBuilder,
     reactor::QueueKind,
-    types::{Deploy, DeployHash},
+    types::{Block, BlockHash, Deploy, DeployHash},
 };
 
+/// The number of deploys returned by "account_get_deploys" if the request doesn't specify a limit.
+const DEFAULT_ACCOUNT_DEPLOYS_LIMIT: u32 = 25;
+/// The maximum number of deploys returned by a single "account_get_deploys" request.
+const MAX_ACCOUNT_DEPLOYS_LIMIT: u32 = 100;
+
 static PUT_DEPLOY_PARAMS: Lazy<PutDeployParams> = Lazy::new(|| PutDeployParams {
     deploy: Deploy::doc_example().clone(),
 });
@@ -34,6 +40,33 @@ static PUT_DEPLOY_RESULT: Lazy<PutDeployResult> = Lazy::new(|| PutDeployResult {
     api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
     deploy_hash: *Deploy::doc_example().id(),
 });
+static GET_ACCOUNT_DEPLOYS_PARAMS: Lazy<GetAccountDeploysParams> =
+    Lazy::new(|| GetAccountDeploysParams {
+        public_key: Deploy::doc_example().header().account().clone(),
+        start_height: Some(Block::doc_example().header().height()),
+        cursor: None,
+        limit: Some(DEFAULT_ACCOUNT_DEPLOYS_LIMIT),
+    });
+static GET_ACCOUNT_DEPLOYS_RESULT: Lazy<GetAccountDeploysResult> =
+    Lazy::new(|| GetAccountDeploysResult {
+        api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+        deploys: vec![JsonAccountDeploy {
+            deploy_hash: *Deploy::doc_example().id(),
+            block_hash: *Block::doc_example().hash(),
+            block_height: Block::doc_example().header().height(),
+        }],
+        next_cursor: None,
+    });
+static SPECULATIVE_EXEC_PARAMS: Lazy<SpeculativeExecParams> = Lazy::new(|| SpeculativeExecParams {
+    block_identifier: Some(BlockIdentifier::Hash(*Block::doc_example().hash())),
+    deploy: Deploy::doc_example().clone(),
+});
+static SPECULATIVE_EXEC_RESULT: Lazy<SpeculativeExecResult> = Lazy::new(|| SpeculativeExecResult {
+    api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+    block_hash: *Block::doc_example().hash(),
+    execution_result: ExecutionResult::example().clone(),
+    gas_profile: None,
+});
 
 /// Params for "account_put_deploy" RPC request.
 #[derive(Serialize, Deserialize, Debug, JsonSchema)]
@@ -123,3 +156,364 @@ impl RpcWithParamsExt for PutDeploy {
         .boxed()
     }
 }
+
+/// Params for "speculative_exec" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct SpeculativeExecParams {
+    /// The block on top of which to execute the deploy, or the highest block if not specified.
+    pub block_identifier: Option<BlockIdentifier>,
+    /// The `Deploy` to execute.
+    pub deploy: Deploy,
+}
+
+impl DocExample for SpeculativeExecParams {
+    fn doc_example() -> &'static Self {
+        &*SPECULATIVE_EXEC_PARAMS
+    }
+}
+
+/// Result for "speculative_exec" RPC response.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct SpeculativeExecResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// Hash of the block on top of which the deploy was executed.
+    pub block_hash: BlockHash,
+    /// The result of executing the deploy.
+    pub execution_result: ExecutionResult,
+    /// The gas charged while executing the deploy per Wasm function and host function, in the
+    /// folded stacks format used by flamegraph tools, if gas profiling is enabled on the node.
+    pub gas_profile: Option<String>,
+}
+
+impl DocExample for SpeculativeExecResult {
+    fn doc_example() -> &'static Self {
+        &*SPECULATIVE_EXEC_RESULT
+    }
+}
+
+/// "speculative_exec" RPC
+pub struct SpeculativeExec {}
+
+impl RpcWithParams for SpeculativeExec {
+    const METHOD: &'static str = "speculative_exec";
+    type RequestParams = SpeculativeExecParams;
+    type ResponseResult = SpeculativeExecResult;
+}
+
+impl RpcWithParamsExt for SpeculativeExec {
+    fn handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        response_builder: Builder,
+        params: Self::RequestParams,
+        api_version: ProtocolVersion,
+    ) -> BoxFuture<'static, Result<Response<Body>, Error>> {
+        async move {
+            let mut deploy = params.deploy;
+            let deploy_hash = *deploy.id();
+
+            if let Err(error) = deploy.is_valid() {
+                info!(
+                    %deploy_hash,
+                    %error,
+                    "the deploy submitted for speculative execution was invalid",
+                );
+                return Ok(response_builder.error(warp_json_rpc::Error::custom(
+                    ErrorCode::InvalidDeploy as i64,
+                    error.to_string(),
+                ))?);
+            }
+
+            // Execute against the post-state of the given block.
+            let block = match chain::get_block(params.block_identifier, effect_builder).await {
+                Ok(Some(block)) => block,
+                Ok(None) => {
+                    let error = warp_json_rpc::Error::custom(
+                        ErrorCode::NoSuchBlock as i64,
+                        "block not known",
+                    );
+                    return Ok(response_builder.error(error)?);
+                }
+                Err(error) => return Ok(response_builder.error(error)?),
+            };
+            let block_hash = *block.hash();
+
+            let execution_result = effect_builder
+                .speculatively_execute_deploy(
+                    *block.state_root_hash(),
+                    block.timestamp().millis(),
+                    block.protocol_version(),
+                    block.body().proposer().clone(),
+                    Box::new(deploy),
+                )
+                .await;
+
+            let error_msg = match execution_result {
+                Ok(Some(SpeculativeExecutionResult {
+                    execution_result,
+                    gas_profile,
+                })) => {
+                    let result = Self::ResponseResult {
+                        api_version,
+                        block_hash,
+                        execution_result,
+                        gas_profile,
+                    };
+                    return Ok(response_builder.success(result)?);
+                }
+                Ok(None) => "speculative execution returned no result".to_string(),
+                Err(error) => format!("speculative execution failed: {}", error),
+            };
+            info!(%deploy_hash, %block_hash, "{}", error_msg);
+            Ok(response_builder.error(warp_json_rpc::Error::custom(
+                ErrorCode::SpeculativeExecFailed as i64,
+                error_msg,
+            ))?)
+        }
+        .boxed()
+    }
+}
+
+/// Params for "account_get_deploys" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetAccountDeploysParams {
+    /// The public key of the account which sent the deploys.
+    pub public_key: PublicKey,
+    /// Only deploys included in blocks at or above this height are returned.  Defaults to 0.
+    pub start_height: Option<u64>,
+    /// The position after which to continue listing, as returned in the `next_cursor` field of a
+    /// previous response.  Takes precedence over `start_height`.
+    pub cursor: Option<AccountDeploysCursor>,
+    /// The maximum number of deploys to return, capped at 100.  Must not be 0.  Defaults to 25.
+    pub limit: Option<u32>,
+}
+
+impl DocExample for GetAccountDeploysParams {
+    fn doc_example() -> &'static Self {
+        &*GET_ACCOUNT_DEPLOYS_PARAMS
+    }
+}
+
+/// A deploy sent by an account, along with the block in which it was included.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct JsonAccountDeploy {
+    /// The deploy hash.
+    pub deploy_hash: DeployHash,
+    /// The hash of the block containing the deploy.
+    pub block_hash: BlockHash,
+    /// The height of the block containing the deploy.
+    pub block_height: u64,
+}
+
+/// A position in an account's deploy history, identifying the last deploy of a page.
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct AccountDeploysCursor {
+    /// The height of the block containing the deploy.
+    pub block_height: u64,
+    /// The deploy hash.
+    pub deploy_hash: DeployHash,
+}
+
+/// Result for "account_get_deploys" RPC response.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetAccountDeploysResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The deploys sent by the account, in ascending order of block height.
+    pub deploys: Vec<JsonAccountDeploy>,
+    /// The cursor to pass in a subsequent request to retrieve the next page of deploys, if there
+    /// are any more.
+    pub next_cursor: Option<AccountDeploysCursor>,
+}
+
+impl DocExample for GetAccountDeploysResult {
+    fn doc_example() -> &'static Self {
+        &*GET_ACCOUNT_DEPLOYS_RESULT
+    }
+}
+
+/// "account_get_deploys" RPC
+pub struct GetAccountDeploys {}
+
+impl RpcWithParams for GetAccountDeploys {
+    const METHOD: &'static str = "account_get_deploys";
+    type RequestParams = GetAccountDeploysParams;
+    type ResponseResult = GetAccountDeploysResult;
+}
+
+impl RpcWithParamsExt for GetAccountDeploys {
+    fn handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        response_builder: Builder,
+        params: Self::RequestParams,
+        api_version: ProtocolVersion,
+    ) -> BoxFuture<'static, Result<Response<Body>, Error>> {
+        async move {
+            let limit = params
+                .limit
+                .unwrap_or(DEFAULT_ACCOUNT_DEPLOYS_LIMIT)
+                .min(MAX_ACCOUNT_DEPLOYS_LIMIT) as usize;
+            if limit == 0 {
+                info!("account_get_deploys limit must not be 0");
+                return Ok(response_builder.error(warp_json_rpc::Error::INVALID_PARAMS)?);
+            }
+            // The cursor holds the position in the account's deploy history itself, so it remains
+            // valid even if its deploy has since been pruned.
+            let start = match params.cursor {
+                Some(cursor) => Bound::Excluded((cursor.block_height, cursor.deploy_hash)),
+                None => Bound::Included((
+                    params.start_height.unwrap_or_default(),
+                    DeployHash::default(),
+                )),
+            };
+
+            // Request one more than the limit to find out whether there is a further page.
+            let mut account_deploys = effect_builder
+                .get_account_deploys_from_storage(params.public_key, start, limit + 1)
+                .await;
+            let next_cursor = if account_deploys.len() > limit {
+                account_deploys.truncate(limit);
+                account_deploys
+                    .last()
+                    .map(|(block_height, _, deploy_hash)| AccountDeploysCursor {
+                        block_height: *block_height,
+                        deploy_hash: *deploy_hash,
+                    })
+            } else {
+                None
+            };
+
+            let deploys = account_deploys
+                .into_iter()
+                .map(
+                    |(block_height, block_hash, deploy_hash)| JsonAccountDeploy {
+                        deploy_hash,
+                        block_hash,
+                        block_height,
+                    },
+                )
+                .collect();
+            let result = Self::ResponseResult {
+                api_version,
+                deploys,
+                next_cursor,
+            };
+            Ok(response_builder.success(result)?)
+        }
+        .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::join;
+    use hyper::{service::Service, Request};
+    use serde_json::{json, Value};
+    use warp::filters::BoxedFilter;
+
+    use casper_hashing::Digest;
+
+    use super::*;
+    use crate::{
+        components::rpc_server::rpcs::RPC_API_PATH,
+        effect::requests::StorageRequest,
+        reactor::{participating::ParticipatingEvent, EventQueueHandle, Scheduler},
+        utils,
+    };
+
+    /// Creates an "account_get_deploys" filter wired to a new scheduler.
+    fn setup() -> (
+        BoxedFilter<(Response<Body>,)>,
+        &'static Scheduler<ParticipatingEvent>,
+    ) {
+        let scheduler = utils::leak(Scheduler::<ParticipatingEvent>::new(QueueKind::weights()));
+        let effect_builder = EffectBuilder::new(EventQueueHandle::without_shutdown(scheduler));
+        let filter = GetAccountDeploys::create_filter(effect_builder, ProtocolVersion::V1_0_0);
+        (filter, scheduler)
+    }
+
+    /// Sends an "account_get_deploys" request with the given params and returns the response body.
+    async fn get_account_deploys(filter: BoxedFilter<(Response<Body>,)>, params: Value) -> Value {
+        let body = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": GetAccountDeploys::METHOD,
+            "params": params,
+        });
+        let request = Request::post(format!("/{}", RPC_API_PATH))
+            .header("Content-Type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        let response = warp_json_rpc::service(filter).call(request).await.unwrap();
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    #[tokio::test]
+    async fn should_reject_zero_limit() {
+        let (filter, _scheduler) = setup();
+        let public_key = Deploy::doc_example().header().account().clone();
+
+        let response =
+            get_account_deploys(filter, json!({ "public_key": public_key, "limit": 0 })).await;
+        assert_eq!(
+            response["error"]["code"],
+            json!(warp_json_rpc::Error::INVALID_PARAMS.code)
+        );
+    }
+
+    #[tokio::test]
+    async fn should_continue_from_cursor_position() {
+        let (filter, scheduler) = setup();
+        let public_key = Deploy::doc_example().header().account().clone();
+        let cursor = AccountDeploysCursor {
+            block_height: 5,
+            deploy_hash: DeployHash::new(Digest::hash(&[1])),
+        };
+        let block_hash = BlockHash::new(Digest::hash(&[2]));
+        let account_deploys: Vec<_> = (6..9)
+            .map(|height: u64| {
+                let deploy_hash = DeployHash::new(Digest::hash(&height.to_le_bytes()));
+                (height, block_hash, deploy_hash)
+            })
+            .collect();
+
+        let params = json!({ "public_key": public_key, "cursor": cursor, "limit": 2 });
+        let handle_request = async {
+            let ((_ancestor, event), _queue_kind) = scheduler.pop().await;
+            match event {
+                ParticipatingEvent::StorageRequest(StorageRequest::GetAccountDeploys {
+                    start,
+                    max_count,
+                    responder,
+                    ..
+                }) => {
+                    // The cursor is used as the position in the index without being looked up.
+                    assert_eq!(
+                        start,
+                        Bound::Excluded((cursor.block_height, cursor.deploy_hash))
+                    );
+                    assert_eq!(max_count, 3);
+                    responder.respond(account_deploys.clone()).await
+                }
+                other => panic!("unexpected event: {}", other),
+            }
+        };
+        let (response, ()) = join!(get_account_deploys(filter, params), handle_request);
+
+        let result = &response["result"];
+        assert_eq!(result["deploys"].as_array().unwrap().len(), 2);
+        assert_eq!(
+            result["next_cursor"],
+            json!({ "block_height": 7, "deploy_hash": account_deploys[1].2 })
+        );
+    }
+}
@@ -22,16 +22,21 @@ use casper_types::{Key, ProtocolVersion, Transfer};
 use super::{
     docs::{DocExample, DOCS_EXAMPLE_PROTOCOL_VERSION},
     Error, ErrorCode, ReactorEventT, RpcRequest, RpcWithOptionalParams, RpcWithOptionalParamsExt,
+    RpcWithParams, RpcWithParamsExt,
 };
 use crate::{
     effect::EffectBuilder,
     reactor::QueueKind,
     rpcs::common,
-    types::{Block, BlockHash, BlockSignatures, Item, JsonBlock},
+    types::{Block, BlockHash, BlockSignatures, Item, JsonBlock, JsonBlockHeader, JsonProof},
 };
 pub use era_summary::EraSummary;
 use era_summary::ERA_SUMMARY;
 
+/// The maximum number of block headers which can be requested in a single
+/// "chain_get_block_headers" request.
+const MAX_BLOCK_HEADERS_COUNT: u32 = 100;
+
 static GET_BLOCK_PARAMS: Lazy<GetBlockParams> = Lazy::new(|| GetBlockParams {
     block_identifier: BlockIdentifier::Hash(Block::doc_example().id()),
 });
@@ -65,6 +70,19 @@ static GET_ERA_INFO_RESULT: Lazy<GetEraInfoResult> = Lazy::new(|| GetEraInfoResu
     api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
     era_summary: Some(ERA_SUMMARY.clone()),
 });
+static GET_BLOCK_HEADERS_PARAMS: Lazy<GetBlockHeadersParams> =
+    Lazy::new(|| GetBlockHeadersParams {
+        start_height: Block::doc_example().header().height(),
+        count: 1,
+    });
+static GET_BLOCK_HEADERS_RESULT: Lazy<GetBlockHeadersResult> =
+    Lazy::new(|| GetBlockHeadersResult {
+        api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+        headers: vec![BlockHeaderWithSignatures {
+            header: JsonBlock::doc_example().header.clone(),
+            proofs: JsonBlock::doc_example().proofs.clone(),
+        }],
+    });
 
 /// Identifier for possible ways to retrieve a block.
 #[derive(Serialize, Deserialize, Debug, Clone, Copy, JsonSchema)]
@@ -468,7 +486,101 @@ impl RpcWithOptionalParamsExt for GetEraInfoBySwitchBlock {
     }
 }
 
-async fn get_block<REv: ReactorEventT>(
+/// Params for "chain_get_block_headers" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetBlockHeadersParams {
+    /// The height of the first block header to retrieve.
+    pub start_height: u64,
+    /// The number of consecutive block headers to retrieve, capped at 100.
+    pub count: u32,
+}
+
+impl DocExample for GetBlockHeadersParams {
+    fn doc_example() -> &'static Self {
+        &*GET_BLOCK_HEADERS_PARAMS
+    }
+}
+
+/// A block header and the finality signatures of the block.
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct BlockHeaderWithSignatures {
+    /// JSON-friendly block header.
+    pub header: JsonBlockHeader,
+    /// JSON-friendly list of proofs for the block.
+    pub proofs: Vec<JsonProof>,
+}
+
+/// Result for "chain_get_block_headers" RPC response.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetBlockHeadersResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The block headers in order of height, starting at the requested height.  Fewer headers
+    /// than requested are returned if the node doesn't have all of them.
+    pub headers: Vec<BlockHeaderWithSignatures>,
+}
+
+impl DocExample for GetBlockHeadersResult {
+    fn doc_example() -> &'static Self {
+        &*GET_BLOCK_HEADERS_RESULT
+    }
+}
+
+/// "chain_get_block_headers" RPC.
+pub struct GetBlockHeaders {}
+
+impl RpcWithParams for GetBlockHeaders {
+    const METHOD: &'static str = "chain_get_block_headers";
+    type RequestParams = GetBlockHeadersParams;
+    type ResponseResult = GetBlockHeadersResult;
+}
+
+impl RpcWithParamsExt for GetBlockHeaders {
+    fn handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        response_builder: Builder,
+        params: Self::RequestParams,
+        api_version: ProtocolVersion,
+    ) -> BoxFuture<'static, Result<Response<Body>, Error>> {
+        async move {
+            let count = u64::from(params.count.min(MAX_BLOCK_HEADERS_COUNT));
+            let end_height = params.start_height.saturating_add(count);
+
+            let mut headers = Vec::new();
+            for height in params.start_height..end_height {
+                let header = match effect_builder
+                    .get_block_header_at_height_from_storage(height)
+                    .await
+                {
+                    Some(header) => header,
+                    None => break,
+                };
+                let proofs = effect_builder
+                    .get_signatures_from_storage(header.hash())
+                    .await
+                    .map(|signatures| signatures.proofs.into_iter().map(JsonProof::from).collect())
+                    .unwrap_or_default();
+                headers.push(BlockHeaderWithSignatures {
+                    header: JsonBlockHeader::from(header),
+                    proofs,
+                });
+            }
+
+            let result = Self::ResponseResult {
+                api_version,
+                headers,
+            };
+            Ok(response_builder.success(result)?)
+        }
+        .boxed()
+    }
+}
+
+pub(super) async fn get_block<REv: ReactorEventT>(
     maybe_id: Option<BlockIdentifier>,
     effect_builder: EffectBuilder<REv>,
 ) -> Result<Option<Block>, warp_json_rpc::Error> {
@@ -482,12 +594,11 @@ async fn get_block<REv: ReactorEventT>(
     }
 }
 
-async fn get_block_with_metadata<REv: ReactorEventT>(
+pub(super) async fn get_block_with_metadata<REv: ReactorEventT>(
     maybe_id: Option<BlockIdentifier>,
     effect_builder: EffectBuilder<REv>,
 ) -> Result<Option<(Block, BlockSignatures)>, warp_json_rpc::Error> {
     // Get the block from storage or the latest from the linear chain.
-    let getting_specific_block = maybe_id.is_some();
     let maybe_result = effect_builder
         .make_request(
             |responder| RpcRequest::GetBlock {
@@ -498,8 +609,24 @@ async fn get_block_with_metadata<REv: ReactorEventT>(
         )
         .await;
 
-    if maybe_result.is_none() && getting_specific_block {
-        info!("failed to get {:?} from storage", maybe_id.unwrap());
+    if let (None, Some(block_id)) = (&maybe_result, maybe_id) {
+        info!("failed to get {:?} from storage", block_id);
+        let is_pruned = match block_id {
+            BlockIdentifier::Hash(block_hash) => {
+                effect_builder.is_block_pruned_in_storage(block_hash).await
+            }
+            BlockIdentifier::Height(height) => {
+                effect_builder
+                    .is_block_at_height_pruned_in_storage(height)
+                    .await
+            }
+        };
+        if is_pruned {
+            return Err(warp_json_rpc::Error::custom(
+                ErrorCode::BlockPruned as i64,
+                "block has been pruned",
+            ));
+        }
         return Err(warp_json_rpc::Error::custom(
             ErrorCode::NoSuchBlock as i64,
             "block not known",
@@ -19,9 +19,9 @@ use warp_json_rpc::Builder;
 use casper_types::ProtocolVersion;
 
 use super::{
-    account::PutDeploy,
-    chain::{GetBlock, GetBlockTransfers, GetStateRootHash},
-    info::{GetDeploy, GetPeers, GetStatus},
+    account::{GetAccountDeploys, PutDeploy, SpeculativeExec},
+    chain::{GetBlock, GetBlockHeaders, GetBlockTransfers, GetStateRootHash},
+    info::{GetBlockedPeers, GetDeploy, GetPeers, GetStatus},
     state::{GetAuctionInfo, GetBalance, GetItem},
     Error, ReactorEventT, RpcWithOptionalParams, RpcWithParams, RpcWithoutParams,
     RpcWithoutParamsExt,
@@ -31,7 +31,7 @@ use crate::{
     rpcs::{
         chain::GetEraInfoBySwitchBlock,
         info::GetValidatorChanges,
-        state::{GetAccountInfo, GetDictionaryItem, QueryGlobalState},
+        state::{GetAccountInfo, GetDictionaryItem, QueryBatch, QueryGlobalState},
     },
 };
 
@@ -75,13 +75,25 @@ pub(crate) static OPEN_RPC_SCHEMA: Lazy<OpenRpcSchema> = Lazy::new(|| {
     };
 
     schema.push_with_params::<PutDeploy>("receives a Deploy to be executed by the network");
+    schema.push_with_params::<SpeculativeExec>(
+        "executes a Deploy against the global state of a Block without committing its effects",
+    );
     schema.push_with_params::<GetDeploy>("returns a Deploy from the network");
+    schema.push_with_params::<GetAccountDeploys>(
+        "returns a page of the Deploys sent by an account, ordered by Block height",
+    );
     schema.push_with_params::<GetAccountInfo>("returns an Account from the network");
     schema.push_with_params::<GetDictionaryItem>("returns an item from a Dictionary");
     schema.push_with_params::<QueryGlobalState>(
         "a query to global state using either a Block hash or state root hash",
     );
+    schema.push_with_params::<QueryBatch>(
+        "returns a Block with its Deploys, transfers and the results of global state queries against its state root hash",
+    );
     schema.push_without_params::<GetPeers>("returns a list of peers connected to the node");
+    schema.push_without_params::<GetBlockedPeers>(
+        "returns a list of peers blocked by the node after committing offenses",
+    );
     schema.push_without_params::<GetStatus>("returns the current status of the node");
     schema
         .push_without_params::<GetValidatorChanges>("returns status changes of active validators");
@@ -92,6 +104,9 @@ pub(crate) static OPEN_RPC_SCHEMA: Lazy<OpenRpcSchema> = Lazy::new(|| {
     schema.push_with_optional_params::<GetStateRootHash>(
         "returns a state root hash at a given Block",
     );
+    schema.push_with_params::<GetBlockHeaders>(
+        "returns a range of Block headers with their finality signatures, starting at a given height",
+    );
     schema.push_with_params::<GetItem>("returns a stored value from the network. This RPC is deprecated, use `query_global_state` instead.");
     schema.push_with_params::<GetBalance>("returns a purse's balance from the network");
     schema.push_with_optional_params::<GetEraInfoBySwitchBlock>(
@@ -26,7 +26,10 @@ use crate::{
     crypto::AsymmetricKeyExt,
     effect::EffectBuilder,
     reactor::QueueKind,
-    types::{Block, BlockHash, Deploy, DeployHash, GetStatusResult, Item, PeersMap},
+    types::{
+        Block, BlockHash, BlockedPeer, BlockedPeersMap, Deploy, DeployHash, DeployMetadata,
+        GetStatusResult, Item, NodeId, OffenseSeverity, PeersMap, Timestamp,
+    },
 };
 
 static GET_DEPLOY_PARAMS: Lazy<GetDeployParams> = Lazy::new(|| GetDeployParams {
@@ -44,6 +47,20 @@ static GET_PEERS_RESULT: Lazy<GetPeersResult> = Lazy::new(|| GetPeersResult {
     api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
     peers: GetStatusResult::doc_example().peers.clone(),
 });
+static GET_BLOCKED_PEERS_RESULT: Lazy<GetBlockedPeersResult> = Lazy::new(|| {
+    let mut blocked_peers = BTreeMap::new();
+    let _ = blocked_peers.insert(
+        *NodeId::doc_example(),
+        BlockedPeer {
+            blocked_until: *Timestamp::doc_example(),
+            offense: Some(OffenseSeverity::Major),
+        },
+    );
+    GetBlockedPeersResult {
+        api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+        blocked_peers: BlockedPeersMap::from(blocked_peers),
+    }
+});
 static GET_VALIDATOR_CHANGES_RESULT: Lazy<GetValidatorChangesResult> = Lazy::new(|| {
     let change = JsonValidatorStatusChange::new(EraId::new(1), ValidatorChange::Added);
     let public_key = PublicKey::doc_example().clone();
@@ -115,29 +132,11 @@ impl RpcWithParamsExt for GetDeploy {
     ) -> BoxFuture<'static, Result<Response<Body>, Error>> {
         async move {
             // Try to get the deploy and metadata from storage.
-            let maybe_deploy_and_metadata = effect_builder
-                .make_request(
-                    |responder| RpcRequest::GetDeploy {
-                        hash: params.deploy_hash,
-                        responder,
-                    },
-                    QueueKind::Api,
-                )
-                .await;
-
-            let (deploy, metadata) = match maybe_deploy_and_metadata {
-                Some((deploy, metadata)) => (deploy, metadata),
-                None => {
-                    info!(
-                        "failed to get {} and metadata from storage",
-                        params.deploy_hash
-                    );
-                    return Ok(response_builder.error(warp_json_rpc::Error::custom(
-                        ErrorCode::NoSuchDeploy as i64,
-                        "deploy not known",
-                    ))?);
-                }
-            };
+            let (deploy, metadata) =
+                match get_deploy_with_metadata(params.deploy_hash, effect_builder).await {
+                    Ok(deploy_and_metadata) => deploy_and_metadata,
+                    Err(error) => return Ok(response_builder.error(error)?),
+                };
 
             // Return the result.
             let execution_results = metadata
@@ -206,6 +205,56 @@ impl RpcWithoutParamsExt for GetPeers {
     }
 }
 
+/// Result for "info_get_blocked_peers" RPC response.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetBlockedPeersResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The node ID of each blocked peer, the time its block expires and the severity of the
+    /// offense which got it blocked.
+    pub blocked_peers: BlockedPeersMap,
+}
+
+impl DocExample for GetBlockedPeersResult {
+    fn doc_example() -> &'static Self {
+        &*GET_BLOCKED_PEERS_RESULT
+    }
+}
+
+/// "info_get_blocked_peers" RPC.
+pub struct GetBlockedPeers {}
+
+impl RpcWithoutParams for GetBlockedPeers {
+    const METHOD: &'static str = "info_get_blocked_peers";
+    type ResponseResult = GetBlockedPeersResult;
+}
+
+impl RpcWithoutParamsExt for GetBlockedPeers {
+    fn handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        response_builder: Builder,
+        api_version: ProtocolVersion,
+    ) -> BoxFuture<'static, Result<Response<Body>, Error>> {
+        async move {
+            let blocked_peers = effect_builder
+                .make_request(
+                    |responder| RpcRequest::GetBlockedPeers { responder },
+                    QueueKind::Api,
+                )
+                .await;
+
+            let result = Self::ResponseResult {
+                api_version,
+                blocked_peers: BlockedPeersMap::from(blocked_peers),
+            };
+            Ok(response_builder.success(result)?)
+        }
+        .boxed()
+    }
+}
+
 /// "info_get_status" RPC.
 pub struct GetStatus {}
 
@@ -342,3 +391,37 @@ impl RpcWithoutParamsExt for GetValidatorChanges {
         .boxed()
     }
 }
+
+pub(super) async fn get_deploy_with_metadata<REv: ReactorEventT>(
+    deploy_hash: DeployHash,
+    effect_builder: EffectBuilder<REv>,
+) -> Result<(Deploy, DeployMetadata), warp_json_rpc::Error> {
+    let maybe_deploy_and_metadata = effect_builder
+        .make_request(
+            |responder| RpcRequest::GetDeploy {
+                hash: deploy_hash,
+                responder,
+            },
+            QueueKind::Api,
+        )
+        .await;
+
+    if let Some(deploy_and_metadata) = maybe_deploy_and_metadata {
+        return Ok(deploy_and_metadata);
+    }
+
+    info!("failed to get {} and metadata from storage", deploy_hash);
+    let is_pruned = effect_builder
+        .is_deploy_pruned_in_storage(deploy_hash)
+        .await;
+    if is_pruned {
+        return Err(warp_json_rpc::Error::custom(
+            ErrorCode::DeployPruned as i64,
+            "deploy has been pruned",
+        ));
+    }
+    Err(warp_json_rpc::Error::custom(
+        ErrorCode::NoSuchDeploy as i64,
+        "deploy not known",
+    ))
+}
@@ -18,8 +18,8 @@ use casper_execution_engine::core::engine_state::{BalanceResult, GetBidsResult,
 use casper_hashing::Digest;
 use casper_types::{
     bytesrepr::{Bytes, ToBytes},
-    CLValue, Key, ProtocolVersion, PublicKey, SecretKey, StoredValue as DomainStoredValue, URef,
-    U512,
+    CLValue, ExecutionResult, Key, ProtocolVersion, PublicKey, SecretKey,
+    StoredValue as DomainStoredValue, Transfer, URef, U512,
 };
 
 use super::{
@@ -31,16 +31,19 @@ use crate::{
     effect::EffectBuilder,
     reactor::QueueKind,
     rpcs::{
-        chain::BlockIdentifier,
+        chain::{self, BlockIdentifier},
         common::{self, MERKLE_PROOF},
-        RpcWithOptionalParamsExt,
+        info, RpcWithOptionalParamsExt,
     },
     types::{
         json_compatibility::{Account as JsonAccount, AuctionState, StoredValue},
-        Block, BlockHash, JsonBlockHeader,
+        Block, BlockHash, Deploy, JsonBlock, JsonBlockHeader,
     },
 };
 
+/// The maximum number of global state queries accepted in a single "query_batch" RPC request.
+const MAX_BATCH_QUERIES: usize = 100;
+
 static GET_ITEM_PARAMS: Lazy<GetItemParams> = Lazy::new(|| GetItemParams {
     state_root_hash: *Block::doc_example().header().state_root_hash(),
     key: "deploy-af684263911154d26fa05be9963171802801a0b6aff8f199b7391eacb8edc9e1".to_string(),
@@ -112,6 +115,33 @@ static QUERY_GLOBAL_STATE_RESULT: Lazy<QueryGlobalStateResult> =
         stored_value: StoredValue::Account(JsonAccount::doc_example().clone()),
         merkle_proof: MERKLE_PROOF.clone(),
     });
+static QUERY_BATCH_PARAMS: Lazy<QueryBatchParams> = Lazy::new(|| QueryBatchParams {
+    block_identifier: Some(BlockIdentifier::Hash(*Block::doc_example().hash())),
+    include_deploys: true,
+    include_transfers: true,
+    queries: vec![BatchQuery {
+        key: "account-hash-e94daaff79c2ab8d9c31d9c3058d7d0a0dd31204a5638dc1451fa67b2e3fb88c"
+            .to_string(),
+        path: vec![],
+    }],
+});
+static QUERY_BATCH_RESULT: Lazy<QueryBatchResult> = Lazy::new(|| QueryBatchResult {
+    api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+    block: JsonBlock::doc_example().clone(),
+    deploys: Some(vec![JsonBlockDeploy {
+        deploy: Deploy::doc_example().clone(),
+        execution_result: Some(ExecutionResult::example().clone()),
+    }]),
+    transfers: Some(vec![Transfer::default()]),
+    query_results: vec![BatchQueryResult {
+        key: "account-hash-e94daaff79c2ab8d9c31d9c3058d7d0a0dd31204a5638dc1451fa67b2e3fb88c"
+            .to_string(),
+        path: vec![],
+        stored_value: Some(StoredValue::Account(JsonAccount::doc_example().clone())),
+        merkle_proof: Some(MERKLE_PROOF.clone()),
+        error: None,
+    }],
+});
 static GET_TRIE_PARAMS: Lazy<GetTrieParams> = Lazy::new(|| GetTrieParams {
     trie_key: *Block::doc_example().header().state_root_hash(),
 });
@@ -176,33 +206,20 @@ impl RpcWithParamsExt for GetItem {
     ) -> BoxFuture<'static, Result<Response<Body>, Error>> {
         async move {
             // Try to parse a `casper_types::Key` from the params.
-            let base_key = match Key::from_formatted_str(&params.key)
-                .map_err(|error| format!("failed to parse key: {}", error))
-            {
+            let base_key = match parse_key(&params.key) {
                 Ok(key) => key,
-                Err(error_msg) => {
-                    info!("{}", error_msg);
-                    return Ok(response_builder.error(warp_json_rpc::Error::custom(
-                        ErrorCode::ParseQueryKey as i64,
-                        error_msg,
-                    ))?);
-                }
+                Err(error) => return Ok(response_builder.error(error)?),
             };
 
             // Run the query.
-            let query_result = effect_builder
-                .make_request(
-                    |responder| RpcRequest::QueryGlobalState {
-                        state_root_hash: params.state_root_hash,
-                        base_key,
-                        path: params.path,
-                        responder,
-                    },
-                    QueueKind::Api,
-                )
-                .await;
-
-            let (stored_value, proof_bytes) = match common::extract_query_result(query_result) {
+            let (stored_value, proof_bytes) = match run_query(
+                effect_builder,
+                params.state_root_hash,
+                base_key,
+                params.path,
+            )
+            .await
+            {
                 Ok(tuple) => tuple,
                 Err((error_code, error_msg)) => {
                     info!("{}", error_msg);
@@ -965,39 +982,20 @@ impl RpcWithParamsExt for QueryGlobalState {
                 GlobalStateIdentifier::StateRootHash(state_root_hash) => (state_root_hash, None),
             };
 
-            let base_key = match Key::from_formatted_str(&params.key)
-                .map_err(|error| format!("failed to parse key: {}", error))
-            {
+            let base_key = match parse_key(&params.key) {
                 Ok(key) => key,
-                Err(error_msg) => {
-                    info!("{}", error_msg);
-                    return Ok(response_builder.error(warp_json_rpc::Error::custom(
-                        ErrorCode::ParseQueryKey as i64,
-                        error_msg,
-                    ))?);
-                }
+                Err(error) => return Ok(response_builder.error(error)?),
             };
 
-            let query_result = effect_builder
-                .make_request(
-                    |responder| RpcRequest::QueryGlobalState {
-                        state_root_hash,
-                        base_key,
-                        path: params.path,
-                        responder,
-                    },
-                    QueueKind::Api,
-                )
-                .await;
-
-            let (stored_value, proof_bytes) = match common::extract_query_result(query_result) {
-                Ok(tuple) => tuple,
-                Err((error_code, error_msg)) => {
-                    info!("{}", error_msg);
-                    return Ok(response_builder
-                        .error(warp_json_rpc::Error::custom(error_code as i64, error_msg))?);
-                }
-            };
+            let (stored_value, proof_bytes) =
+                match run_query(effect_builder, state_root_hash, base_key, params.path).await {
+                    Ok(tuple) => tuple,
+                    Err((error_code, error_msg)) => {
+                        info!("{}", error_msg);
+                        return Ok(response_builder
+                            .error(warp_json_rpc::Error::custom(error_code as i64, error_msg))?);
+                    }
+                };
 
             let result = Self::ResponseResult {
                 api_version,
@@ -1012,6 +1010,229 @@ impl RpcWithParamsExt for QueryGlobalState {
     }
 }
 
+/// A single global state query within a "query_batch" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct BatchQuery {
+    /// `casper_types::Key` as formatted string.
+    pub key: String,
+    /// The path components starting from the key as base.
+    #[serde(default)]
+    pub path: Vec<String>,
+}
+
+/// Params for "query_batch" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct QueryBatchParams {
+    /// The block to query, or the highest block if not specified.
+    pub block_identifier: Option<BlockIdentifier>,
+    /// Whether to include the block's deploys and transfers with their execution results.
+    #[serde(default)]
+    pub include_deploys: bool,
+    /// Whether to include the transfers made in the block.
+    #[serde(default)]
+    pub include_transfers: bool,
+    /// The queries to run against the block's state root hash.
+    #[serde(default)]
+    pub queries: Vec<BatchQuery>,
+}
+
+impl DocExample for QueryBatchParams {
+    fn doc_example() -> &'static Self {
+        &*QUERY_BATCH_PARAMS
+    }
+}
+
+/// A deploy included in the block of a "query_batch" RPC response.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct JsonBlockDeploy {
+    /// The deploy.
+    pub deploy: Deploy,
+    /// The result of executing the deploy in the block, if known.
+    pub execution_result: Option<ExecutionResult>,
+}
+
+/// The result of a single global state query within a "query_batch" RPC response.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct BatchQueryResult {
+    /// The queried key.
+    pub key: String,
+    /// The queried path.
+    pub path: Vec<String>,
+    /// The stored value, if the query succeeded.
+    pub stored_value: Option<StoredValue>,
+    /// The merkle proof, if the query succeeded.
+    pub merkle_proof: Option<String>,
+    /// The error message, if the query failed.
+    pub error: Option<String>,
+}
+
+/// Result for "query_batch" RPC response.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct QueryBatchResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The block.
+    pub block: JsonBlock,
+    /// The block's deploys and transfers, if requested.
+    pub deploys: Option<Vec<JsonBlockDeploy>>,
+    /// The block's transfers, if requested.
+    pub transfers: Option<Vec<Transfer>>,
+    /// The results of the queries, in the order they were requested.
+    pub query_results: Vec<BatchQueryResult>,
+}
+
+impl DocExample for QueryBatchResult {
+    fn doc_example() -> &'static Self {
+        &*QUERY_BATCH_RESULT
+    }
+}
+
+/// "query_batch" RPC.
+pub struct QueryBatch {}
+
+impl RpcWithParams for QueryBatch {
+    const METHOD: &'static str = "query_batch";
+    type RequestParams = QueryBatchParams;
+    type ResponseResult = QueryBatchResult;
+}
+
+impl RpcWithParamsExt for QueryBatch {
+    fn handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        response_builder: Builder,
+        params: Self::RequestParams,
+        api_version: ProtocolVersion,
+    ) -> BoxFuture<'static, Result<Response<Body>, Error>> {
+        async move {
+            if params.queries.len() > MAX_BATCH_QUERIES {
+                let error_msg = format!(
+                    "query_batch accepts at most {} queries, got {}",
+                    MAX_BATCH_QUERIES,
+                    params.queries.len()
+                );
+                info!("{}", error_msg);
+                return Ok(response_builder.error(warp_json_rpc::Error::custom(
+                    ErrorCode::TooManyBatchQueries as i64,
+                    error_msg,
+                ))?);
+            }
+
+            // Parse all keys up front so that a malformed request doesn't hit storage.
+            let mut base_keys = Vec::with_capacity(params.queries.len());
+            for query in &params.queries {
+                match parse_key(&query.key) {
+                    Ok(key) => base_keys.push(key),
+                    Err(error) => return Ok(response_builder.error(error)?),
+                }
+            }
+
+            // Get the block, which fixes the state root hash for all the queries.
+            let (block, signatures) =
+                match chain::get_block_with_metadata(params.block_identifier, effect_builder).await
+                {
+                    Ok(Some((block, signatures))) => (block, signatures),
+                    Ok(None) => {
+                        let error = warp_json_rpc::Error::custom(
+                            ErrorCode::NoSuchBlock as i64,
+                            "block not known",
+                        );
+                        return Ok(response_builder.error(error)?);
+                    }
+                    Err(error) => return Ok(response_builder.error(error)?),
+                };
+            let block_hash = *block.hash();
+            let state_root_hash = *block.header().state_root_hash();
+
+            let deploys = if params.include_deploys {
+                let deploy_hashes = block
+                    .deploy_hashes()
+                    .iter()
+                    .chain(block.transfer_hashes())
+                    .copied()
+                    .collect::<Vec<_>>();
+                let mut deploys = Vec::with_capacity(deploy_hashes.len());
+                for deploy_hash in deploy_hashes {
+                    let (deploy, mut metadata) =
+                        match info::get_deploy_with_metadata(deploy_hash, effect_builder).await {
+                            Ok(deploy_and_metadata) => deploy_and_metadata,
+                            Err(error) => return Ok(response_builder.error(error)?),
+                        };
+                    let execution_result = metadata.execution_results.remove(&block_hash);
+                    deploys.push(JsonBlockDeploy {
+                        deploy,
+                        execution_result,
+                    });
+                }
+                Some(deploys)
+            } else {
+                None
+            };
+
+            let transfers = if params.include_transfers {
+                effect_builder
+                    .make_request(
+                        |responder| RpcRequest::GetBlockTransfers {
+                            block_hash,
+                            responder,
+                        },
+                        QueueKind::Api,
+                    )
+                    .await
+            } else {
+                None
+            };
+
+            let mut query_results = Vec::with_capacity(params.queries.len());
+            for (query, base_key) in params.queries.into_iter().zip(base_keys) {
+                let query_result = match run_query(
+                    effect_builder,
+                    state_root_hash,
+                    base_key,
+                    query.path.clone(),
+                )
+                .await
+                {
+                    Ok((stored_value, proof_bytes)) => BatchQueryResult {
+                        key: query.key,
+                        path: query.path,
+                        stored_value: Some(stored_value),
+                        merkle_proof: Some(base16::encode_lower(&proof_bytes)),
+                        error: None,
+                    },
+                    Err((_error_code, error_msg)) => {
+                        info!("{}", error_msg);
+                        BatchQueryResult {
+                            key: query.key,
+                            path: query.path,
+                            stored_value: None,
+                            merkle_proof: None,
+                            error: Some(error_msg),
+                        }
+                    }
+                };
+                query_results.push(query_result);
+            }
+
+            let result = Self::ResponseResult {
+                api_version,
+                block: JsonBlock::new(block, Some(signatures)),
+                deploys,
+                transfers,
+                query_results,
+            };
+
+            Ok(response_builder.success(result)?)
+        }
+        .boxed()
+    }
+}
+
 /// Parameters for "state_get_trie" RPC request.
 #[derive(Serialize, Deserialize, Debug, JsonSchema)]
 pub struct GetTrieParams {
@@ -1100,3 +1321,214 @@ impl RpcWithParamsExt for GetTrie {
         .boxed()
     }
 }
+
+fn parse_key(key: &str) -> Result<Key, warp_json_rpc::Error> {
+    Key::from_formatted_str(key).map_err(|error| {
+        let error_msg = format!("failed to parse key: {}", error);
+        info!("{}", error_msg);
+        warp_json_rpc::Error::custom(ErrorCode::ParseQueryKey as i64, error_msg)
+    })
+}
+
+async fn run_query<REv: ReactorEventT>(
+    effect_builder: EffectBuilder<REv>,
+    state_root_hash: Digest,
+    base_key: Key,
+    path: Vec<String>,
+) -> Result<(StoredValue, Vec<u8>), (ErrorCode, String)> {
+    let query_result = effect_builder
+        .make_request(
+            |responder| RpcRequest::QueryGlobalState {
+                state_root_hash,
+                base_key,
+                path,
+                responder,
+            },
+            QueueKind::Api,
+        )
+        .await;
+
+    common::extract_query_result(query_result)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::iter;
+
+    use futures::join;
+    use hyper::{service::Service, Request};
+    use serde_json::{json, Value};
+    use warp::filters::BoxedFilter;
+
+    use super::*;
+    use crate::{
+        components::rpc_server::{self, rpcs::RPC_API_PATH},
+        reactor::{participating::ParticipatingEvent, EventQueueHandle, Scheduler},
+        testing::TestRng,
+        types::{BlockSignatures, NodeId},
+        utils,
+    };
+
+    const ACCOUNT_KEY: &str =
+        "account-hash-e94daaff79c2ab8d9c31d9c3058d7d0a0dd31204a5638dc1451fa67b2e3fb88c";
+    const UREF_KEY: &str =
+        "uref-09480c3248ef76b603d386f3f4f8a5f87f597d4eaffd475433f861af187ab5db-007";
+
+    /// Creates a "query_batch" filter wired to a new scheduler.
+    fn setup() -> (
+        BoxedFilter<(Response<Body>,)>,
+        &'static Scheduler<ParticipatingEvent>,
+    ) {
+        let scheduler = utils::leak(Scheduler::<ParticipatingEvent>::new(QueueKind::weights()));
+        let effect_builder = EffectBuilder::new(EventQueueHandle::without_shutdown(scheduler));
+        let filter = QueryBatch::create_filter(effect_builder, ProtocolVersion::V1_0_0);
+        (filter, scheduler)
+    }
+
+    /// Sends a "query_batch" request with the given params and returns the response body.
+    async fn query_batch(filter: BoxedFilter<(Response<Body>,)>, params: Value) -> Value {
+        let body = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": QueryBatch::METHOD,
+            "params": params,
+        });
+        let request = Request::post(format!("/{}", RPC_API_PATH))
+            .header("Content-Type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        let response = warp_json_rpc::service(filter).call(request).await.unwrap();
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    /// Pops the next event from the scheduler, expecting it to be an RPC request.
+    async fn next_rpc_request(
+        scheduler: &'static Scheduler<ParticipatingEvent>,
+    ) -> RpcRequest<NodeId> {
+        let ((_ancestor, event), _queue_kind) = scheduler.pop().await;
+        match event {
+            ParticipatingEvent::RpcServer(rpc_server::Event::RpcRequest(request)) => request,
+            other => panic!("unexpected event: {}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn should_reject_too_many_batch_queries() {
+        let (filter, _scheduler) = setup();
+        let queries: Vec<_> = iter::repeat(json!({ "key": ACCOUNT_KEY }))
+            .take(MAX_BATCH_QUERIES + 1)
+            .collect();
+
+        let response = query_batch(filter, json!({ "queries": queries })).await;
+        assert_eq!(
+            response["error"]["code"],
+            json!(ErrorCode::TooManyBatchQueries as i64)
+        );
+    }
+
+    #[tokio::test]
+    async fn should_reject_malformed_batch_query_key() {
+        let (filter, _scheduler) = setup();
+        let queries = json!([{ "key": ACCOUNT_KEY }, { "key": "not-a-key" }]);
+
+        let response = query_batch(filter, json!({ "queries": queries })).await;
+        assert_eq!(
+            response["error"]["code"],
+            json!(ErrorCode::ParseQueryKey as i64)
+        );
+    }
+
+    #[tokio::test]
+    async fn should_run_batch_queries_against_the_block_state_root_hash() {
+        let mut rng = TestRng::new();
+        let block = Block::random(&mut rng);
+        let block_hash = *block.hash();
+        let state_root_hash = *block.header().state_root_hash();
+        let signatures = BlockSignatures::new(block_hash, block.header().era_id());
+        let (filter, scheduler) = setup();
+
+        let params = json!({
+            "block_identifier": { "Hash": block_hash },
+            "include_transfers": true,
+            "queries": [{ "key": ACCOUNT_KEY }, { "key": UREF_KEY, "path": ["missing"] }],
+        });
+        let handle_requests = async {
+            match next_rpc_request(scheduler).await {
+                RpcRequest::GetBlock {
+                    maybe_id,
+                    responder,
+                } => {
+                    assert!(
+                        matches!(maybe_id, Some(BlockIdentifier::Hash(hash)) if hash == block_hash)
+                    );
+                    responder.respond(Some((block.clone(), signatures))).await
+                }
+                other => panic!("unexpected request: {}", other),
+            }
+            match next_rpc_request(scheduler).await {
+                RpcRequest::GetBlockTransfers {
+                    block_hash: requested_block_hash,
+                    responder,
+                } => {
+                    assert_eq!(requested_block_hash, block_hash);
+                    responder.respond(Some(vec![])).await
+                }
+                other => panic!("unexpected request: {}", other),
+            }
+            match next_rpc_request(scheduler).await {
+                RpcRequest::QueryGlobalState {
+                    state_root_hash: requested_state_root_hash,
+                    base_key,
+                    path,
+                    responder,
+                } => {
+                    assert_eq!(requested_state_root_hash, state_root_hash);
+                    assert_eq!(base_key, Key::from_formatted_str(ACCOUNT_KEY).unwrap());
+                    assert!(path.is_empty());
+                    let query_result = QueryResult::Success {
+                        value: Box::new(DomainStoredValue::CLValue(CLValue::from_t(1u64).unwrap())),
+                        proofs: vec![],
+                    };
+                    responder.respond(Ok(query_result)).await
+                }
+                other => panic!("unexpected request: {}", other),
+            }
+            match next_rpc_request(scheduler).await {
+                RpcRequest::QueryGlobalState {
+                    state_root_hash: requested_state_root_hash,
+                    base_key,
+                    path,
+                    responder,
+                } => {
+                    assert_eq!(requested_state_root_hash, state_root_hash);
+                    assert_eq!(base_key, Key::from_formatted_str(UREF_KEY).unwrap());
+                    assert_eq!(path, vec!["missing".to_string()]);
+                    let query_result = QueryResult::ValueNotFound("missing".to_string());
+                    responder.respond(Ok(query_result)).await
+                }
+                other => panic!("unexpected request: {}", other),
+            }
+        };
+        let (response, ()) = join!(query_batch(filter, params), handle_requests);
+
+        let result: QueryBatchResult = serde_json::from_value(response["result"].clone()).unwrap();
+        assert_eq!(result.block.hash, block_hash);
+        assert!(result.deploys.is_none());
+        assert_eq!(result.transfers, Some(vec![]));
+        assert_eq!(result.query_results.len(), 2);
+
+        let found = &result.query_results[0];
+        assert_eq!(found.key, ACCOUNT_KEY);
+        assert!(matches!(found.stored_value, Some(StoredValue::CLValue(_))));
+        assert!(found.merkle_proof.is_some());
+        assert!(found.error.is_none());
+
+        let not_found = &result.query_results[1];
+        assert_eq!(not_found.key, UREF_KEY);
+        assert_eq!(not_found.path, vec!["missing".to_string()]);
+        assert!(not_found.stored_value.is_none());
+        assert!(not_found.merkle_proof.is_none());
+        assert!(not_found.error.is_some());
+    }
+}
@@ -23,6 +23,8 @@
 //! Nodes gossip their public listening addresses periodically, and will try to establish and
 //! maintain an outgoing connection to any new address learned.
 
+mod binary_format;
+mod blocklist;
 mod chain_info;
 mod config;
 mod counting_format;
@@ -34,10 +36,12 @@ mod message;
 mod message_pack_format;
 mod metrics;
 mod outgoing;
+mod reserved_peers;
 mod symmetry;
 pub(crate) mod tasks;
 #[cfg(test)]
 mod tests;
+mod wire_encoding;
 
 use std::{
     collections::{BTreeMap, HashMap, HashSet},
@@ -45,6 +49,7 @@ use std::{
     fmt::{self, Debug, Display, Formatter},
     io,
     net::{SocketAddr, TcpListener},
+    path::PathBuf,
     result,
     sync::{Arc, Weak},
     time::{Duration, Instant},
@@ -60,6 +65,7 @@ use rand::seq::{IteratorRandom, SliceRandom};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use tokio::{
+    io::{AsyncRead, AsyncWrite},
     net::TcpStream,
     sync::{
         mpsc::{self, UnboundedSender},
@@ -72,17 +78,18 @@ use tokio_util::codec::LengthDelimitedCodec;
 use tracing::{debug, error, info, trace, warn, Instrument, Span};
 
 use self::{
+    blocklist::Blocklist,
     chain_info::ChainInfo,
     counting_format::{ConnectionId, CountingFormat, Role},
     error::{ConnectionError, Result},
     event::{IncomingConnection, OutgoingConnection},
     limiter::Limiter,
-    message::ConsensusKeyPair,
-    message_pack_format::MessagePackFormat,
     metrics::Metrics,
-    outgoing::{DialOutcome, DialRequest, OutgoingConfig, OutgoingManager},
+    outgoing::{DialOutcome, DialRequest, OutgoingConfig, OutgoingManager, OutgoingStatus},
+    reserved_peers::ReservedPeers,
     symmetry::ConnectionSymmetry,
     tasks::NetworkContext,
+    wire_encoding::WireFormat,
 };
 pub(crate) use self::{
     config::Config,
@@ -90,28 +97,32 @@ pub(crate) use self::{
     event::Event,
     gossiped_address::GossipedAddress,
     message::{Message, MessageKind, Payload, PayloadWeights},
+    wire_encoding::WireEncoding,
 };
 use super::{consensus, contract_runtime::ContractRuntimeAnnouncement};
 use crate::{
     components::Component,
     effect::{
         announcements::{BlocklistAnnouncement, NetworkAnnouncement},
-        requests::{NetworkInfoRequest, NetworkRequest, StorageRequest},
+        requests::{NetworkAdminRequest, NetworkInfoRequest, NetworkRequest, StorageRequest},
         EffectBuilder, EffectExt, Effects,
     },
     reactor::{EventQueueHandle, Finalize, ReactorEvent},
     tls::{self, TlsCert, ValidationError},
-    types::NodeId,
+    types::{BlockedPeer, NodeId, Timestamp},
     utils::{self, display_error, WithDir},
     NodeRng,
 };
 
+/// Name of the file the blocklist is persisted to, in the storage directory.
+pub(crate) const BLOCKLIST_FILENAME: &str = "blocklist.json";
+
+/// Name of the file the reserved peers are persisted to, in the storage directory.
+pub(crate) const RESERVED_PEERS_FILENAME: &str = "reserved_peers.json";
+
 const MAX_METRICS_DROP_ATTEMPTS: usize = 25;
 const DROP_RETRY_DELAY: Duration = Duration::from_millis(100);
 
-/// Duration peers are kept on the block list, before being redeemed.
-const BLOCKLIST_RETAIN_DURATION: Duration = Duration::from_secs(60 * 10);
-
 /// How often to keep attempting to reconnect to a node before giving up. Note that reconnection
 /// delays increase exponentially!
 const RECONNECTION_ATTEMPTS: u8 = 8;
@@ -137,6 +148,27 @@ impl<P> Display for OutgoingHandle<P> {
     }
 }
 
+/// A blocked peer, as reported to the node operator.
+#[derive(Clone, Debug, Serialize)]
+pub(crate) struct BlockedPeerStatus {
+    /// The full hex node ID of the peer.
+    node_id: String,
+    /// The block of the peer.
+    #[serde(flatten)]
+    block: BlockedPeer,
+}
+
+/// The state of the peers, as reported to the node operator.
+#[derive(Clone, Debug, Serialize)]
+pub(crate) struct PeersStatus {
+    /// The state of every known outgoing address.
+    outgoing: Vec<OutgoingStatus>,
+    /// The currently blocked peers.
+    blocked: Vec<BlockedPeerStatus>,
+    /// The full hex node IDs of the reserved peers.
+    reserved: Vec<String>,
+}
+
 #[derive(DataSize)]
 pub(crate) struct SmallNetwork<REv, P>
 where
@@ -152,6 +184,10 @@ where
     outgoing_manager: OutgoingManager<OutgoingHandle<P>, ConnectionError>,
     /// Tracks whether a connection is symmetric or not.
     connection_symmetries: HashMap<NodeId, ConnectionSymmetry>,
+    /// Reputations of peers and the node IDs blocked after committing offenses.
+    blocklist: Blocklist,
+    /// Peers reserved by the node operator, which are never blocked after committing offenses.
+    reserved_peers: ReservedPeers,
 
     /// Channel signaling a shutdown of the small network.
     // Note: This channel is closed when `SmallNetwork` is dropped, signalling the receivers that
@@ -191,7 +227,7 @@ where
         ReactorEvent + From<Event<P>> + From<NetworkAnnouncement<NodeId, P>> + From<StorageRequest>,
 {
     /// Creates a new small network component instance.
-    #[allow(clippy::type_complexity)]
+    #[allow(clippy::type_complexity, clippy::too_many_arguments)]
     pub(crate) fn new<C: Into<ChainInfo>>(
         event_queue: EventQueueHandle<REv>,
         cfg: Config,
@@ -199,6 +235,8 @@ where
         registry: &Registry,
         small_network_identity: SmallNetworkIdentity,
         chain_info_source: C,
+        blocklist_path: Option<PathBuf>,
+        reserved_peers_path: Option<PathBuf>,
     ) -> Result<(SmallNetwork<REv, P>, Effects<Event<P>>)> {
         let mut known_addresses = HashSet::new();
         for address in &cfg.known_addresses {
@@ -240,7 +278,7 @@ where
         let outgoing_manager = OutgoingManager::new(OutgoingConfig {
             retry_attempts: RECONNECTION_ATTEMPTS,
             base_timeout: BASE_RECONNECTION_TIMEOUT,
-            unblock_after: BLOCKLIST_RETAIN_DURATION,
+            unblock_after: cfg.blocklist_retain_duration.into(),
             sweep_timeout: cfg.max_addr_pending_time.into(),
         });
 
@@ -249,6 +287,13 @@ where
 
         let net_metrics = Arc::new(Metrics::new(registry)?);
 
+        let blocklist = Blocklist::new(
+            cfg.reputation_half_life,
+            cfg.blocklist_retain_duration,
+            blocklist_path,
+            Timestamp::now(),
+        );
+
         // We can now create a listener.
         let bind_address = utils::resolve_address(&cfg.bind_address).map_err(Error::ResolveAddr)?;
         let listener = TcpListener::bind(bind_address)
@@ -265,15 +310,13 @@ where
             public_addr.set_port(local_addr.port());
         }
 
-        // If given consensus key configuration, load it for handshake signing.
-        let consensus_keys = consensus_cfg
-            .map(|cfg| {
-                let root = cfg.dir();
-                cfg.value().load_keys(root)
-            })
+        // If given consensus key configuration, create a signer for handshakes. This uses the
+        // external signer if one is configured.
+        let consensus_signer = consensus_cfg
+            .map(|cfg| cfg.value().signer(cfg.dir()))
             .transpose()
             .map_err(Error::LoadConsensusKeys)?
-            .map(|(secret_key, public_key)| ConsensusKeyPair::new(secret_key, public_key));
+            .map(Arc::new);
 
         let context = Arc::new(NetworkContext {
             event_queue,
@@ -283,8 +326,10 @@ where
             net_metrics: Arc::downgrade(&net_metrics),
             chain_info: chain_info_source.into(),
             public_addr,
-            consensus_keys,
+            consensus_signer,
             payload_weights: cfg.estimator_weights.clone(),
+            wire_encodings: cfg.wire_encodings.clone(),
+            compression_threshold: cfg.compression_threshold,
         });
 
         // Run the server task.
@@ -308,6 +353,8 @@ where
             context,
             outgoing_manager,
             connection_symmetries: HashMap::new(),
+            blocklist,
+            reserved_peers: ReservedPeers::new(reserved_peers_path),
             shutdown_sender: Some(server_shutdown_sender),
             shutdown_receiver,
             server_join_handle: Some(server_join_handle),
@@ -445,12 +492,21 @@ where
                 peer_consensus_public_key,
                 stream,
             } => {
+                if self.blocklist.is_blocked(&peer_id, Timestamp::now()) {
+                    // Dropping the stream closes the connection.
+                    info!(%public_addr, "rejecting incoming connection from blocked peer");
+                    drop(stream);
+                    return Effects::new();
+                }
+
                 info!(%public_addr, "new incoming connection established");
 
-                // Learn the address the peer gave us.
-                let dial_requests =
-                    self.outgoing_manager
-                        .learn_addr(public_addr, false, Instant::now());
+                // Learn the address the peer gave us, never forgetting those of reserved peers.
+                let dial_requests = self.outgoing_manager.learn_addr(
+                    public_addr,
+                    self.reserved_peers.contains(&peer_id),
+                    Instant::now(),
+                );
                 let mut effects = self.process_dial_requests(dial_requests);
 
                 // Update connection symmetries.
@@ -534,7 +590,7 @@ where
             | ConnectionError::InvalidConsensusCertificate(_) => false,
 
             // Definitely something we want to avoid.
-            ConnectionError::WrongNetwork(_) => true,
+            ConnectionError::WrongNetwork(_) | ConnectionError::PeerBlocked => true,
         }
     }
 
@@ -591,8 +647,33 @@ where
                 peer_consensus_public_key,
                 sink,
             } => {
+                if self.blocklist.is_blocked(&peer_id, Timestamp::now()) {
+                    // The peer's address may have changed since it was blocked, so we block the
+                    // new one as well. Dropping the sink closes the connection.
+                    info!("outgoing connection to blocked peer, will be dropped");
+                    drop(sink);
+                    // As with failed connections, we block first to not trigger a reconnection.
+                    let mut requests: Vec<_> = self
+                        .outgoing_manager
+                        .block_addr(peer_addr, now)
+                        .into_iter()
+                        .collect();
+                    requests.extend(self.outgoing_manager.handle_dial_outcome(
+                        DialOutcome::Failed {
+                            addr: peer_addr,
+                            error: ConnectionError::PeerBlocked,
+                            when: now,
+                        },
+                    ));
+                    return self.process_dial_requests(requests);
+                }
+
                 info!("new outgoing connection established");
 
+                if self.reserved_peers.contains(&peer_id) {
+                    self.outgoing_manager.mark_unforgettable(peer_addr);
+                }
+
                 let (sender, receiver) = mpsc::unbounded_channel();
                 let handle = OutgoingHandle { sender, peer_addr };
 
@@ -691,6 +772,96 @@ where
         effects
     }
 
+    /// Blocks the address we are connected to `peer_id` through, if any.
+    ///
+    /// Incoming connections of a blocked peer are ignored, but we also stop connecting to its
+    /// current address.
+    fn block_peer_addr(&mut self, peer_id: NodeId) -> Effects<Event<P>> {
+        match self.outgoing_manager.get_addr(peer_id) {
+            Some(addr) => {
+                let requests = self.outgoing_manager.block_addr(addr, Instant::now());
+                self.process_dial_requests(requests)
+            }
+            None => Effects::new(),
+        }
+    }
+
+    /// Handles a request from the node operator to manage the peers.
+    fn handle_admin_request(&mut self, req: NetworkAdminRequest) -> Effects<Event<P>> {
+        match req {
+            NetworkAdminRequest::GetPeersStatus { responder } => {
+                let status = PeersStatus {
+                    outgoing: self.outgoing_manager.status(),
+                    blocked: self
+                        .blocklist
+                        .blocked_peers(Timestamp::now())
+                        .into_iter()
+                        .map(|(peer_id, block)| BlockedPeerStatus {
+                            node_id: peer_id.to_hex(),
+                            block,
+                        })
+                        .collect(),
+                    reserved: self.reserved_peers.iter().map(NodeId::to_hex).collect(),
+                };
+                responder.respond(status).ignore()
+            }
+            NetworkAdminRequest::Connect { addr, responder } => {
+                info!(%addr, "connecting to address, as requested by the operator");
+                let request = self.outgoing_manager.connect_addr(addr, Instant::now());
+                let mut effects = self.process_dial_requests(request);
+                effects.extend(responder.respond(()).ignore());
+                effects
+            }
+            NetworkAdminRequest::Disconnect { addr, responder } => {
+                info!(%addr, "disconnecting from address, as requested by the operator");
+                let request = self.outgoing_manager.disconnect_addr(addr);
+                let mut effects = self.process_dial_requests(request);
+                effects.extend(responder.respond(()).ignore());
+                effects
+            }
+            NetworkAdminRequest::Block {
+                peer_id,
+                duration,
+                responder,
+            } => {
+                let peer_id = *peer_id;
+                if self.reserved_peers.unreserve(&peer_id) {
+                    info!(%peer_id, "revoked reservation of peer to be blocked");
+                }
+                warn!(%peer_id, "adding peer to blocklist, as requested by the operator");
+                self.blocklist.block(peer_id, duration, Timestamp::now());
+                let mut effects = self.block_peer_addr(peer_id);
+                effects.extend(responder.respond(()).ignore());
+                effects
+            }
+            NetworkAdminRequest::Unblock { peer_id, responder } => {
+                let peer_id = *peer_id;
+                let was_blocked = self.blocklist.unblock(&peer_id, Timestamp::now());
+                if was_blocked {
+                    info!(%peer_id, "removed peer from blocklist, as requested by the operator");
+                }
+                responder.respond(was_blocked).ignore()
+            }
+            NetworkAdminRequest::Reserve { peer_id, responder } => {
+                let peer_id = *peer_id;
+                info!(%peer_id, "reserving peer, as requested by the operator");
+                self.reserved_peers.reserve(peer_id);
+                if let Some(addr) = self.outgoing_manager.get_addr(peer_id) {
+                    self.outgoing_manager.mark_unforgettable(addr);
+                }
+                responder.respond(()).ignore()
+            }
+            NetworkAdminRequest::Unreserve { peer_id, responder } => {
+                let peer_id = *peer_id;
+                let was_reserved = self.reserved_peers.unreserve(&peer_id);
+                if was_reserved {
+                    info!(%peer_id, "revoked reservation of peer, as requested by the operator");
+                }
+                responder.respond(was_reserved).ignore()
+            }
+        }
+    }
+
     /// Handles a received message.
     fn handle_incoming_message(
         &mut self,
@@ -710,9 +881,16 @@ where
                 warn!("received unexpected handshake");
                 Effects::new()
             }
-            Message::Payload(payload) => effect_builder
-                .announce_message_received(peer_id, payload)
-                .ignore(),
+            Message::Payload(payload) => {
+                if self.blocklist.is_blocked(&peer_id, Timestamp::now()) {
+                    // The connection may have been established before the peer got blocked.
+                    debug!("dropping message from blocked peer");
+                    return Effects::new();
+                }
+                effect_builder
+                    .announce_message_received(peer_id, payload)
+                    .ignore()
+            }
         })
     }
 
@@ -865,6 +1043,9 @@ where
                     peers_vec.shuffle(rng);
                     responder.respond(peers_vec).ignore()
                 }
+                NetworkInfoRequest::GetBlockedPeers { responder } => responder
+                    .respond(self.blocklist.blocked_peers(Timestamp::now()))
+                    .ignore(),
             },
             Event::PeerAddressReceived(gossiped_address) => {
                 let requests = self.outgoing_manager.learn_addr(
@@ -874,18 +1055,24 @@ where
                 );
                 self.process_dial_requests(requests)
             }
-            Event::BlocklistAnnouncement(BlocklistAnnouncement::OffenseCommitted(peer_id)) => {
-                // TODO: We do not have a proper by-node-ID blocklist, but rather only block the
-                // current outgoing address of a peer.
-                warn!(%peer_id, "adding peer to blocklist after transgression");
-
-                if let Some(addr) = self.outgoing_manager.get_addr(*peer_id) {
-                    let requests = self.outgoing_manager.block_addr(addr, Instant::now());
-                    self.process_dial_requests(requests)
-                } else {
-                    // Peer got away with it, no longer an outgoing connection.
-                    Effects::new()
+            Event::NetworkAdminRequest { req } => self.handle_admin_request(*req),
+            Event::BlocklistAnnouncement(BlocklistAnnouncement::OffenseCommitted {
+                offender,
+                severity,
+            }) => {
+                let peer_id = *offender;
+                if self.reserved_peers.contains(&peer_id) {
+                    info!(%peer_id, %severity, "ignoring offense of reserved peer");
+                    return Effects::new();
+                }
+                if !self
+                    .blocklist
+                    .record_offense(peer_id, severity, Timestamp::now())
+                {
+                    return Effects::new();
                 }
+                warn!(%peer_id, %severity, "adding peer to blocklist after transgression");
+                self.block_peer_addr(peer_id)
             }
             Event::ContractRuntimeAnnouncement(
                 ContractRuntimeAnnouncement::LinearChainBlock(_)
@@ -945,6 +1132,8 @@ where
                 effects
             }
             Event::SweepOutgoing => {
+                self.blocklist.sweep(Timestamp::now());
+
                 let now = Instant::now();
                 let requests = self.outgoing_manager.perform_housekeeping(now);
                 let mut effects = self.process_dial_requests(requests);
@@ -1013,23 +1202,30 @@ impl From<&SmallNetworkIdentity> for NodeId {
 /// Transport type alias for base encrypted connections.
 type Transport = SslStream<TcpStream>;
 
-/// A framed transport for `Message`s.
-pub(crate) type FramedTransport<P> = tokio_serde::Framed<
-    tokio_util::codec::Framed<Transport, LengthDelimitedCodec>,
+/// A framed stream of `Message`s.
+type FramedStream<S, P> = tokio_serde::Framed<
+    tokio_util::codec::Framed<S, LengthDelimitedCodec>,
     Message<P>,
     Arc<Message<P>>,
-    CountingFormat<MessagePackFormat>,
+    CountingFormat<WireFormat>,
 >;
 
+/// A framed transport for `Message`s.
+pub(crate) type FramedTransport<P> = FramedStream<Transport, P>;
+
 /// Constructs a new framed transport on a stream.
-fn framed<P>(
+///
+/// The transport encodes messages with MessagePack until it is switched over to the wire encoding
+/// negotiated during the handshake using `reframed`.
+fn framed<S, P>(
     metrics: Weak<Metrics>,
     connection_id: ConnectionId,
-    stream: Transport,
+    stream: S,
     role: Role,
     maximum_net_message_size: u32,
-) -> FramedTransport<P>
+) -> FramedStream<S, P>
 where
+    S: AsyncRead + AsyncWrite,
     for<'de> P: Serialize + Deserialize<'de>,
     for<'de> Message<P>: Serialize + Deserialize<'de>,
 {
@@ -1040,9 +1236,46 @@ where
             .new_codec(),
     );
 
+    let encoding = WireEncoding::MessagePack;
     tokio_serde::Framed::new(
         length_delimited,
-        CountingFormat::new(metrics, connection_id, role, MessagePackFormat),
+        CountingFormat::new(
+            metrics,
+            connection_id,
+            role,
+            encoding,
+            WireFormat::new(encoding, 0, maximum_net_message_size),
+        ),
+    )
+}
+
+/// Switches a framed transport which has exchanged handshakes over to the given wire encoding.
+///
+/// Frames already received but not yet decoded are retained, as the length delimited framing of
+/// the transport is carried over.
+fn reframed<S, P>(
+    transport: FramedStream<S, P>,
+    metrics: Weak<Metrics>,
+    connection_id: ConnectionId,
+    role: Role,
+    encoding: WireEncoding,
+    compression_threshold: u32,
+    maximum_net_message_size: u32,
+) -> FramedStream<S, P>
+where
+    for<'de> P: Serialize + Deserialize<'de>,
+    for<'de> Message<P>: Serialize + Deserialize<'de>,
+{
+    tokio_serde::Framed::new(
+        transport.into_inner(),
+        CountingFormat::new(
+            metrics,
+            connection_id,
+            role,
+            encoding,
+            WireFormat::new(encoding, compression_threshold, maximum_net_message_size),
+        )
+        .after_handshake(),
     )
 }
 
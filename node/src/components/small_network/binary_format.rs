@@ -0,0 +1,281 @@
+//! Compact binary wire format encoder.
+//!
+//! Message payloads are serialized with `bytesrepr`, which, unlike MessagePack, encodes the byte
+//! arrays making up most of the consensus and deploy payloads as raw bytes. The result is wrapped
+//! in a `bytesrepr` frame, which records whether the payload was compressed with zstd. Only
+//! payloads at least as large as the configured compression threshold are compressed, as
+//! compressing small payloads rarely saves any space.
+//!
+//! The format is only negotiated during the handshake, hence handshakes themselves are never
+//! encoded with it.
+
+use std::{io, pin::Pin, sync::Arc};
+
+use bytes::{Bytes, BytesMut};
+use tokio_serde::{Deserializer, Serializer};
+
+use casper_types::bytesrepr::{self, FromBytes, ToBytes, U8_SERIALIZED_LENGTH};
+
+use super::Message;
+
+/// The zstd compression level used for large messages.
+const ZSTD_COMPRESSION_LEVEL: i32 = 3;
+
+/// Tag of a frame holding an uncompressed message.
+const UNCOMPRESSED_TAG: u8 = 0;
+/// Tag of a frame holding a zstd compressed message.
+const ZSTD_COMPRESSED_TAG: u8 = 1;
+
+/// A single message on the wire.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Frame {
+    /// Whether the body is compressed with zstd.
+    compressed: bool,
+    /// The `bytesrepr` encoded payload, possibly compressed.
+    body: bytesrepr::Bytes,
+}
+
+impl ToBytes for Frame {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut buffer = bytesrepr::allocate_buffer(self)?;
+        let tag = if self.compressed {
+            ZSTD_COMPRESSED_TAG
+        } else {
+            UNCOMPRESSED_TAG
+        };
+        buffer.push(tag);
+        buffer.extend(self.body.to_bytes()?);
+        Ok(buffer)
+    }
+
+    fn serialized_length(&self) -> usize {
+        U8_SERIALIZED_LENGTH + self.body.serialized_length()
+    }
+}
+
+impl FromBytes for Frame {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (tag, remainder) = u8::from_bytes(bytes)?;
+        let compressed = match tag {
+            UNCOMPRESSED_TAG => false,
+            ZSTD_COMPRESSED_TAG => true,
+            _ => return Err(bytesrepr::Error::Formatting),
+        };
+        let (body, remainder) = bytesrepr::Bytes::from_bytes(remainder)?;
+        Ok((Frame { compressed, body }, remainder))
+    }
+}
+
+/// Converts a `bytesrepr` error into an IO error.
+fn bytesrepr_error(error: bytesrepr::Error) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error.to_string())
+}
+
+/// Compact binary encoder/decoder for messages.
+#[derive(Debug)]
+pub struct BinaryFormat {
+    /// Minimum size of a message to be compressed, compression is disabled if `None`.
+    compression_threshold: Option<usize>,
+    /// Maximum size of a decompressed message.
+    maximum_message_size: usize,
+}
+
+impl BinaryFormat {
+    /// Creates a new binary encoder/decoder.
+    ///
+    /// A `compression_threshold` of 0 disables compression of outgoing messages; compressed
+    /// incoming messages are always accepted, as long as they do not decompress to more than
+    /// `maximum_message_size` bytes.
+    pub(super) fn new(compression_threshold: u32, maximum_message_size: u32) -> Self {
+        BinaryFormat {
+            compression_threshold: Some(compression_threshold as usize)
+                .filter(|threshold| *threshold > 0),
+            maximum_message_size: maximum_message_size as usize,
+        }
+    }
+
+    /// Encodes a message into a frame.
+    fn encode<P: ToBytes>(&self, message: &Message<P>) -> io::Result<Frame> {
+        let encoded = match message {
+            Message::Payload(payload) => payload.to_bytes().map_err(bytesrepr_error)?,
+            Message::Handshake { .. } => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "handshakes are never encoded with the binary format",
+                ))
+            }
+        };
+
+        match self.compression_threshold {
+            Some(threshold) if encoded.len() >= threshold => {
+                let compressed = zstd::block::compress(&encoded, ZSTD_COMPRESSION_LEVEL)?;
+                // Incompressible messages are sent as they are.
+                if compressed.len() < encoded.len() {
+                    return Ok(Frame {
+                        compressed: true,
+                        body: compressed.into(),
+                    });
+                }
+            }
+            _ => (),
+        }
+
+        Ok(Frame {
+            compressed: false,
+            body: encoded.into(),
+        })
+    }
+
+    /// Decodes a message from a frame.
+    fn decode<P: FromBytes>(&self, frame: Frame) -> io::Result<Message<P>> {
+        let encoded = if frame.compressed {
+            zstd::block::decompress(frame.body.as_slice(), self.maximum_message_size)?
+        } else {
+            frame.body.into()
+        };
+
+        bytesrepr::deserialize(encoded)
+            .map(Message::Payload)
+            .map_err(bytesrepr_error)
+    }
+}
+
+impl<P: ToBytes> Serializer<Arc<Message<P>>> for BinaryFormat {
+    type Error = io::Error;
+
+    #[inline]
+    fn serialize(self: Pin<&mut Self>, item: &Arc<Message<P>>) -> Result<Bytes, Self::Error> {
+        let frame = self.encode(&**item)?;
+        frame.into_bytes().map(Into::into).map_err(bytesrepr_error)
+    }
+}
+
+impl<P: FromBytes> Deserializer<Message<P>> for BinaryFormat {
+    type Error = io::Error;
+
+    #[inline]
+    fn deserialize(self: Pin<&mut Self>, src: &BytesMut) -> Result<Message<P>, Self::Error> {
+        let (frame, remainder) = Frame::from_bytes(src).map_err(bytesrepr_error)?;
+        if !remainder.is_empty() {
+            return Err(bytesrepr_error(bytesrepr::Error::LeftOverBytes));
+        }
+        self.decode(frame)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{pin::Pin, sync::Arc};
+
+    use bytes::BytesMut;
+    use tokio_serde::{Deserializer, Serializer};
+
+    use casper_types::bytesrepr::{self, Bytes, FromBytes, ToBytes};
+
+    use super::{BinaryFormat, Frame, Message};
+
+    /// Maximum message size used in tests.
+    const MAXIMUM_MESSAGE_SIZE: u32 = 1024 * 1024;
+
+    /// Serializes and deserializes a payload, returning the deserialized payload and the size of
+    /// the serialized message.
+    fn roundtrip(format: &mut BinaryFormat, payload: Bytes) -> (Bytes, usize) {
+        let serialized = Serializer::<Arc<Message<Bytes>>>::serialize(
+            Pin::new(&mut *format),
+            &Arc::new(Message::Payload(payload)),
+        )
+        .expect("serialization failed");
+        let size = serialized.len();
+        let deserialized: Message<Bytes> =
+            Deserializer::deserialize(Pin::new(format), &BytesMut::from(&serialized[..]))
+                .expect("deserialization failed");
+        match deserialized {
+            Message::Payload(payload) => (payload, size),
+            Message::Handshake { .. } => panic!("did not expect a handshake"),
+        }
+    }
+
+    #[test]
+    fn frame_bytesrepr_roundtrip() {
+        let frame = Frame {
+            compressed: true,
+            body: vec![1, 2, 3].into(),
+        };
+        bytesrepr::test_serialization_roundtrip(&frame);
+    }
+
+    #[test]
+    fn rejects_unknown_frame_tag() {
+        let mut serialized = Frame {
+            compressed: false,
+            body: vec![1, 2, 3].into(),
+        }
+        .to_bytes()
+        .unwrap();
+        serialized[0] = 2;
+
+        assert_eq!(
+            Frame::from_bytes(&serialized),
+            Err(bytesrepr::Error::Formatting)
+        );
+    }
+
+    #[test]
+    fn small_messages_are_not_compressed() {
+        let mut format = BinaryFormat::new(1024, MAXIMUM_MESSAGE_SIZE);
+        let payload = Bytes::from(vec![0; 100]);
+
+        let (deserialized, size) = roundtrip(&mut format, payload.clone());
+
+        assert_eq!(deserialized, payload);
+        assert!(size > payload.len());
+        assert!(
+            !format
+                .encode(&Message::Payload(payload))
+                .unwrap()
+                .compressed
+        );
+    }
+
+    #[test]
+    fn large_messages_are_compressed() {
+        let mut format = BinaryFormat::new(1024, MAXIMUM_MESSAGE_SIZE);
+        let payload = Bytes::from(vec![7; 64 * 1024]);
+
+        let (deserialized, size) = roundtrip(&mut format, payload.clone());
+
+        assert_eq!(deserialized, payload);
+        assert!(size < payload.len());
+        assert!(
+            format
+                .encode(&Message::Payload(payload))
+                .unwrap()
+                .compressed
+        );
+    }
+
+    #[test]
+    fn compression_can_be_disabled() {
+        let format = BinaryFormat::new(0, MAXIMUM_MESSAGE_SIZE);
+        let payload = Bytes::from(vec![7; 64 * 1024]);
+
+        assert!(
+            !format
+                .encode(&Message::Payload(payload))
+                .unwrap()
+                .compressed
+        );
+    }
+
+    #[test]
+    fn rejects_messages_decompressing_beyond_maximum_size() {
+        let sender = BinaryFormat::new(1024, MAXIMUM_MESSAGE_SIZE);
+        let receiver = BinaryFormat::new(1024, 4 * 1024);
+        let frame = sender
+            .encode(&Message::Payload(Bytes::from(vec![7; 64 * 1024])))
+            .unwrap();
+        assert!(frame.compressed);
+
+        assert!(receiver.decode::<Bytes>(frame).is_err());
+    }
+}
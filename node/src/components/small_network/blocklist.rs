@@ -0,0 +1,387 @@
+//! Node ID based peer reputation and blocklist.
+//!
+//! Every offense reported against a peer lowers its reputation by an amount depending on the
+//! severity of the offense. Penalties decay exponentially over time, so that occasional minor
+//! offenses are forgiven. Once the accumulated penalty of a peer reaches the block threshold, the
+//! peer is blocked by its node ID for a limited time.
+//!
+//! The node operator can also block and unblock peers manually, regardless of their reputation.
+//!
+//! Blocks are persisted to disk, so that restarting a node does not redeem blocked peers.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::PathBuf,
+};
+
+use datasize::DataSize;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info, warn};
+
+use crate::{
+    types::{BlockedPeer, NodeId, OffenseSeverity, TimeDiff, Timestamp},
+    utils,
+};
+
+/// Accumulated penalty at which a peer gets blocked.
+const BLOCK_THRESHOLD: f64 = 100.0;
+
+/// Penalties below this value are considered fully decayed and forgotten.
+const NEGLIGIBLE_PENALTY: f64 = 0.1;
+
+/// Returns the penalty for an offense of the given severity.
+fn penalty(severity: OffenseSeverity) -> f64 {
+    match severity {
+        OffenseSeverity::Minor => 10.0,
+        OffenseSeverity::Major => 40.0,
+        OffenseSeverity::Critical => BLOCK_THRESHOLD,
+    }
+}
+
+/// The accumulated penalty of a peer, at the time it was last updated.
+#[derive(Clone, Copy, Debug)]
+struct Reputation {
+    penalty: f64,
+    updated: Timestamp,
+}
+
+impl Reputation {
+    /// Returns the penalty decayed up to `now`.
+    fn decayed_penalty(&self, half_life: TimeDiff, now: Timestamp) -> f64 {
+        if half_life.millis() == 0 {
+            return 0.0;
+        }
+        let elapsed = now.saturating_diff(self.updated).millis() as f64;
+        self.penalty * 0.5f64.powf(elapsed / half_life.millis() as f64)
+    }
+}
+
+/// A blocked peer, as persisted to disk.
+#[derive(Serialize, Deserialize)]
+struct PersistedBlock {
+    node_id: NodeId,
+    blocked_until: Timestamp,
+    offense: Option<OffenseSeverity>,
+}
+
+/// Reputation tracker and blocklist of peers.
+#[derive(DataSize, Debug)]
+pub(super) struct Blocklist {
+    /// Time after which half of a peer's penalty is forgiven.
+    reputation_half_life: TimeDiff,
+    /// Duration peers stay blocked.
+    retain_duration: TimeDiff,
+    /// Penalties of peers which are not blocked.
+    #[data_size(skip)]
+    reputations: HashMap<NodeId, Reputation>,
+    /// Currently blocked peers.
+    blocked: BTreeMap<NodeId, BlockedPeer>,
+    /// Path the blocklist is persisted to, if any.
+    path: Option<PathBuf>,
+}
+
+impl Blocklist {
+    /// Creates a new blocklist, loading unexpired blocks persisted at `path`, if any.
+    pub(super) fn new(
+        reputation_half_life: TimeDiff,
+        retain_duration: TimeDiff,
+        path: Option<PathBuf>,
+        now: Timestamp,
+    ) -> Self {
+        let mut blocked = BTreeMap::new();
+        if let Some(path) = path.as_ref().filter(|path| path.exists()) {
+            let persisted = utils::read_file(path)
+                .map_err(|err| err.to_string())
+                .and_then(|bytes| {
+                    serde_json::from_slice::<Vec<PersistedBlock>>(&bytes)
+                        .map_err(|err| err.to_string())
+                });
+            match persisted {
+                Ok(persisted) => {
+                    for entry in persisted {
+                        if entry.blocked_until > now {
+                            blocked.insert(
+                                entry.node_id,
+                                BlockedPeer {
+                                    blocked_until: entry.blocked_until,
+                                    offense: entry.offense,
+                                },
+                            );
+                        }
+                    }
+                    info!(path=%path.display(), count = blocked.len(), "loaded blocklist");
+                }
+                Err(err) => {
+                    warn!(path=%path.display(), %err, "could not load blocklist, starting empty");
+                }
+            }
+        }
+
+        Blocklist {
+            reputation_half_life,
+            retain_duration,
+            reputations: HashMap::new(),
+            blocked,
+            path,
+        }
+    }
+
+    /// Records an offense committed by `peer_id`.
+    ///
+    /// Returns `true` if the offense caused the peer to be blocked.
+    pub(super) fn record_offense(
+        &mut self,
+        peer_id: NodeId,
+        severity: OffenseSeverity,
+        now: Timestamp,
+    ) -> bool {
+        if self.is_blocked(&peer_id, now) {
+            debug!(%peer_id, %severity, "offense by already blocked peer");
+            return false;
+        }
+
+        let previous_penalty = self.reputations.get(&peer_id).map_or(0.0, |reputation| {
+            reputation.decayed_penalty(self.reputation_half_life, now)
+        });
+        let total_penalty = previous_penalty + penalty(severity);
+
+        if total_penalty < BLOCK_THRESHOLD {
+            debug!(%peer_id, %severity, penalty = total_penalty, "lowered reputation of peer");
+            self.reputations.insert(
+                peer_id,
+                Reputation {
+                    penalty: total_penalty,
+                    updated: now,
+                },
+            );
+            return false;
+        }
+
+        self.reputations.remove(&peer_id);
+        self.blocked.insert(
+            peer_id,
+            BlockedPeer {
+                blocked_until: now + self.retain_duration,
+                offense: Some(severity),
+            },
+        );
+        self.persist();
+        true
+    }
+
+    /// Blocks `peer_id` on behalf of the node operator, for `duration` or the retain duration if
+    /// not given.
+    pub(super) fn block(&mut self, peer_id: NodeId, duration: Option<TimeDiff>, now: Timestamp) {
+        self.reputations.remove(&peer_id);
+        self.blocked.insert(
+            peer_id,
+            BlockedPeer {
+                blocked_until: now + duration.unwrap_or(self.retain_duration),
+                offense: None,
+            },
+        );
+        self.persist();
+    }
+
+    /// Unblocks `peer_id` and forgets its past offenses.
+    ///
+    /// Returns `true` if the peer was blocked.
+    pub(super) fn unblock(&mut self, peer_id: &NodeId, now: Timestamp) -> bool {
+        self.reputations.remove(peer_id);
+        let was_blocked = self.is_blocked(peer_id, now);
+        if self.blocked.remove(peer_id).is_some() {
+            self.persist();
+        }
+        was_blocked
+    }
+
+    /// Returns whether `peer_id` is currently blocked.
+    pub(super) fn is_blocked(&self, peer_id: &NodeId, now: Timestamp) -> bool {
+        self.blocked
+            .get(peer_id)
+            .map_or(false, |blocked_peer| blocked_peer.blocked_until > now)
+    }
+
+    /// Returns the currently blocked peers.
+    pub(super) fn blocked_peers(&self, now: Timestamp) -> BTreeMap<NodeId, BlockedPeer> {
+        self.blocked
+            .iter()
+            .filter(|(_, blocked_peer)| blocked_peer.blocked_until > now)
+            .map(|(peer_id, blocked_peer)| (*peer_id, *blocked_peer))
+            .collect()
+    }
+
+    /// Redeems peers whose blocks have expired and forgets fully decayed penalties.
+    pub(super) fn sweep(&mut self, now: Timestamp) {
+        let half_life = self.reputation_half_life;
+        self.reputations.retain(|_, reputation| {
+            reputation.decayed_penalty(half_life, now) >= NEGLIGIBLE_PENALTY
+        });
+
+        let blocked_count = self.blocked.len();
+        self.blocked.retain(|peer_id, blocked_peer| {
+            let retain = blocked_peer.blocked_until > now;
+            if !retain {
+                info!(%peer_id, "redeemed blocked peer");
+            }
+            retain
+        });
+        if self.blocked.len() != blocked_count {
+            self.persist();
+        }
+    }
+
+    /// Writes the blocklist to disk, if it has a path.
+    fn persist(&self) {
+        let path = match self.path.as_ref() {
+            Some(path) => path,
+            None => return,
+        };
+        let persisted: Vec<_> = self
+            .blocked
+            .iter()
+            .map(|(node_id, blocked_peer)| PersistedBlock {
+                node_id: *node_id,
+                blocked_until: blocked_peer.blocked_until,
+                offense: blocked_peer.offense,
+            })
+            .collect();
+        let result = serde_json::to_vec_pretty(&persisted)
+            .map_err(|err| err.to_string())
+            .and_then(|json| utils::write_file(path, json).map_err(|err| err.to_string()));
+        if let Err(err) = result {
+            warn!(path=%path.display(), %err, "could not persist blocklist");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        testing::TestRng,
+        types::{NodeId, OffenseSeverity, TimeDiff, Timestamp},
+    };
+
+    use super::Blocklist;
+
+    const HALF_LIFE: TimeDiff = TimeDiff::from_seconds(60);
+    const RETAIN_DURATION: TimeDiff = TimeDiff::from_seconds(600);
+
+    #[test]
+    fn should_block_after_repeated_offenses() {
+        let mut rng = TestRng::new();
+        let peer_id = NodeId::random(&mut rng);
+        let now = Timestamp::from(1_000_000);
+        let mut blocklist = Blocklist::new(HALF_LIFE, RETAIN_DURATION, None, now);
+
+        assert!(!blocklist.record_offense(peer_id, OffenseSeverity::Major, now));
+        assert!(!blocklist.record_offense(peer_id, OffenseSeverity::Major, now));
+        assert!(!blocklist.is_blocked(&peer_id, now));
+        assert!(blocklist.record_offense(peer_id, OffenseSeverity::Major, now));
+        assert!(blocklist.is_blocked(&peer_id, now));
+
+        let blocked_peers = blocklist.blocked_peers(now);
+        assert_eq!(blocked_peers.len(), 1);
+        assert_eq!(blocked_peers[&peer_id].blocked_until, now + RETAIN_DURATION);
+        assert_eq!(
+            blocked_peers[&peer_id].offense,
+            Some(OffenseSeverity::Major)
+        );
+    }
+
+    #[test]
+    fn should_block_immediately_on_critical_offense() {
+        let mut rng = TestRng::new();
+        let peer_id = NodeId::random(&mut rng);
+        let now = Timestamp::from(1_000_000);
+        let mut blocklist = Blocklist::new(HALF_LIFE, RETAIN_DURATION, None, now);
+
+        assert!(blocklist.record_offense(peer_id, OffenseSeverity::Critical, now));
+        assert!(blocklist.is_blocked(&peer_id, now));
+        assert!(!blocklist.record_offense(peer_id, OffenseSeverity::Critical, now));
+    }
+
+    #[test]
+    fn should_forgive_decayed_offenses() {
+        let mut rng = TestRng::new();
+        let peer_id = NodeId::random(&mut rng);
+        let mut now = Timestamp::from(1_000_000);
+        let mut blocklist = Blocklist::new(HALF_LIFE, RETAIN_DURATION, None, now);
+
+        // Each offense is followed by two half-lives, so the penalty never exceeds 4/3 of a
+        // single major offense.
+        for _ in 0..10 {
+            assert!(!blocklist.record_offense(peer_id, OffenseSeverity::Major, now));
+            now += HALF_LIFE * 2;
+        }
+        assert!(!blocklist.is_blocked(&peer_id, now));
+
+        now += HALF_LIFE * 20;
+        blocklist.sweep(now);
+        assert!(blocklist.reputations.is_empty());
+    }
+
+    #[test]
+    fn should_block_and_unblock_manually() {
+        let mut rng = TestRng::new();
+        let peer_id = NodeId::random(&mut rng);
+        let now = Timestamp::from(1_000_000);
+        let mut blocklist = Blocklist::new(HALF_LIFE, RETAIN_DURATION, None, now);
+
+        assert!(!blocklist.record_offense(peer_id, OffenseSeverity::Major, now));
+        blocklist.block(peer_id, Some(HALF_LIFE), now);
+        assert!(blocklist.is_blocked(&peer_id, now));
+        assert_eq!(blocklist.blocked_peers(now)[&peer_id].offense, None);
+        assert!(!blocklist.is_blocked(&peer_id, now + HALF_LIFE));
+
+        blocklist.block(peer_id, None, now);
+        assert!(blocklist.unblock(&peer_id, now));
+        assert!(!blocklist.is_blocked(&peer_id, now));
+        assert!(!blocklist.unblock(&peer_id, now));
+
+        // Past offenses are forgotten on unblocking.
+        assert!(!blocklist.record_offense(peer_id, OffenseSeverity::Major, now));
+        assert!(!blocklist.record_offense(peer_id, OffenseSeverity::Major, now));
+        assert!(!blocklist.is_blocked(&peer_id, now));
+    }
+
+    #[test]
+    fn should_redeem_peer_after_block_expires() {
+        let mut rng = TestRng::new();
+        let peer_id = NodeId::random(&mut rng);
+        let now = Timestamp::from(1_000_000);
+        let mut blocklist = Blocklist::new(HALF_LIFE, RETAIN_DURATION, None, now);
+
+        assert!(blocklist.record_offense(peer_id, OffenseSeverity::Critical, now));
+        let later = now + RETAIN_DURATION;
+        assert!(!blocklist.is_blocked(&peer_id, later));
+        blocklist.sweep(later);
+        assert!(blocklist.blocked_peers(later).is_empty());
+        assert!(blocklist.blocked.is_empty());
+    }
+
+    #[test]
+    fn should_persist_blocklist() {
+        let mut rng = TestRng::new();
+        let blocked_peer_id = NodeId::random(&mut rng);
+        let expiring_peer_id = NodeId::random(&mut rng);
+        let now = Timestamp::from(1_000_000);
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("blocklist.json");
+
+        let mut blocklist = Blocklist::new(HALF_LIFE, RETAIN_DURATION, Some(path.clone()), now);
+        assert!(blocklist.record_offense(expiring_peer_id, OffenseSeverity::Critical, now));
+        let later = now + HALF_LIFE;
+        assert!(blocklist.record_offense(blocked_peer_id, OffenseSeverity::Critical, later));
+
+        // After a restart, only the blocks which have not expired yet are restored.
+        let restart = now + RETAIN_DURATION;
+        let restored = Blocklist::new(HALF_LIFE, RETAIN_DURATION, Some(path), restart);
+        assert!(!restored.is_blocked(&expiring_peer_id, restart));
+        assert!(restored.is_blocked(&blocked_peer_id, restart));
+        assert_eq!(
+            restored.blocked_peers(restart),
+            blocklist.blocked_peers(restart)
+        );
+    }
+}
@@ -8,11 +8,7 @@ use std::net::SocketAddr;
 use casper_types::ProtocolVersion;
 use datasize::DataSize;
 
-use super::{
-    counting_format::ConnectionId,
-    message::{ConsensusCertificate, ConsensusKeyPair},
-    Message,
-};
+use super::{message::ConsensusCertificate, Message, WireEncoding};
 use crate::types::Chainspec;
 
 /// Data retained from the chainspec by the small networking component.
@@ -44,15 +40,15 @@ impl ChainInfo {
     pub(super) fn create_handshake<P>(
         &self,
         public_addr: SocketAddr,
-        consensus_keys: Option<&ConsensusKeyPair>,
-        connection_id: ConnectionId,
+        consensus_certificate: Option<ConsensusCertificate>,
+        wire_encodings: &[WireEncoding],
     ) -> Message<P> {
         Message::Handshake {
             network_name: self.network_name.clone(),
             public_addr,
             protocol_version: self.protocol_version,
-            consensus_certificate: consensus_keys
-                .map(|key_pair| ConsensusCertificate::create(connection_id, key_pair)),
+            consensus_certificate,
+            wire_encodings: wire_encodings.to_vec(),
         }
     }
 }
@@ -7,7 +7,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::types::TimeDiff;
 
-use super::PayloadWeights;
+use super::{PayloadWeights, WireEncoding};
 
 /// Default binding address.
 ///
@@ -22,6 +22,15 @@ const DEFAULT_PUBLIC_ADDRESS: &str = "127.0.0.1:0";
 /// Default interval for gossiping network addresses.
 const DEFAULT_GOSSIP_INTERVAL: &str = "30sec";
 
+/// Default duration peers are kept on the blocklist, before being redeemed.
+const DEFAULT_BLOCKLIST_RETAIN_DURATION: &str = "10min";
+
+/// Default time after which half of a peer's offense penalty is forgiven.
+const DEFAULT_REPUTATION_HALF_LIFE: &str = "10min";
+
+/// Default minimum size in bytes of a message to be compressed when using the binary wire encoding.
+const DEFAULT_COMPRESSION_THRESHOLD: u32 = 4096;
+
 // Default values for networking configuration:
 impl Default for Config {
     fn default() -> Self {
@@ -35,10 +44,30 @@ impl Default for Config {
             max_outgoing_byte_rate_non_validators: 0,
             max_incoming_message_rate_non_validators: 0,
             estimator_weights: Default::default(),
+            blocklist_retain_duration: default_blocklist_retain_duration(),
+            reputation_half_life: default_reputation_half_life(),
+            wire_encodings: default_wire_encodings(),
+            compression_threshold: default_compression_threshold(),
         }
     }
 }
 
+fn default_blocklist_retain_duration() -> TimeDiff {
+    TimeDiff::from_str(DEFAULT_BLOCKLIST_RETAIN_DURATION).unwrap()
+}
+
+fn default_reputation_half_life() -> TimeDiff {
+    TimeDiff::from_str(DEFAULT_REPUTATION_HALF_LIFE).unwrap()
+}
+
+fn default_wire_encodings() -> Vec<WireEncoding> {
+    vec![WireEncoding::MessagePack, WireEncoding::Binary]
+}
+
+fn default_compression_threshold() -> u32 {
+    DEFAULT_COMPRESSION_THRESHOLD
+}
+
 /// Small network configuration.
 #[derive(DataSize, Debug, Clone, Deserialize, Serialize)]
 // Disallow unknown fields to ensure config files and command-line overrides contain valid keys.
@@ -64,6 +93,22 @@ pub struct Config {
     pub max_incoming_message_rate_non_validators: u32,
     /// Weight distribution for the payload impact estimator.
     pub estimator_weights: PayloadWeights,
+    /// Duration peers are kept on the blocklist, before being redeemed.
+    #[serde(default = "default_blocklist_retain_duration")]
+    pub blocklist_retain_duration: TimeDiff,
+    /// Time after which half of the penalty for a peer's offenses is forgiven.
+    #[serde(default = "default_reputation_half_life")]
+    pub reputation_half_life: TimeDiff,
+    /// Wire encodings offered to peers during the handshake.
+    ///
+    /// The most compact encoding offered by both peers is used for a connection, falling back to
+    /// MessagePack if there is none.
+    #[serde(default = "default_wire_encodings")]
+    pub wire_encodings: Vec<WireEncoding>,
+    /// Minimum size in bytes of a message to be compressed with zstd when using the binary wire
+    /// encoding. Compression is disabled if 0.
+    #[serde(default = "default_compression_threshold")]
+    pub compression_threshold: u32,
 }
 
 #[cfg(test)]
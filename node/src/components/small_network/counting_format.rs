@@ -23,7 +23,7 @@ use tracing::{trace, warn};
 
 use casper_hashing::Digest;
 
-use super::{tls::KeyFingerprint, Message, Metrics, Payload};
+use super::{tls::KeyFingerprint, Message, Metrics, Payload, WireEncoding};
 #[cfg(test)]
 use crate::testing::TestRng;
 use crate::{types::NodeId, utils};
@@ -59,6 +59,8 @@ pub struct CountingFormat<F> {
     in_count: u64,
     /// Our role in the connection.
     role: Role,
+    /// The wire encoding used by the inner serializer.
+    encoding: WireEncoding,
     /// Metrics to update.
     metrics: Weak<Metrics>,
 }
@@ -70,6 +72,7 @@ impl<F> CountingFormat<F> {
         metrics: Weak<Metrics>,
         connection_id: ConnectionId,
         role: Role,
+        encoding: WireEncoding,
         inner: F,
     ) -> Self {
         Self {
@@ -78,9 +81,22 @@ impl<F> CountingFormat<F> {
             out_count: 0,
             in_count: 0,
             role,
+            encoding,
             inner,
         }
     }
+
+    /// Accounts for the handshakes already exchanged on the connection, which are always the first
+    /// message sent in either direction.
+    ///
+    /// Used when switching a connection over to the negotiated wire encoding, to keep the trace
+    /// IDs of subsequent messages unique.
+    #[inline]
+    pub(super) fn after_handshake(mut self) -> Self {
+        self.out_count = 1;
+        self.in_count = 1;
+        self
+    }
 }
 
 impl<F, P> Serializer<Arc<Message<P>>> for CountingFormat<F>
@@ -99,6 +115,7 @@ where
         let msg_size = serialized.len() as u64;
         let msg_kind = item.classify();
         Metrics::record_payload_out(this.metrics, msg_kind, msg_size);
+        Metrics::record_encoding_out(this.metrics, *this.encoding, msg_size);
 
         let trace_id = this
             .connection_id
@@ -108,7 +125,8 @@ where
         trace!(target: "net_out",
             msg_id = %trace_id,
             msg_size,
-            msg_kind = %msg_kind, "sending");
+            msg_kind = %msg_kind,
+            encoding = %this.encoding, "sending");
 
         Ok(serialized)
     }
@@ -127,6 +145,7 @@ where
         let projection: Pin<&mut F> = this.inner;
 
         let msg_size = src.len() as u64;
+        Metrics::record_encoding_in(this.metrics, *this.encoding, msg_size);
 
         let deserialized = F::deserialize(projection, src)?;
         let msg_kind = deserialized.classify();
@@ -139,7 +158,8 @@ where
         trace!(target: "net_in",
             msg_id = %trace_id,
             msg_size,
-            msg_kind = %msg_kind, "received");
+            msg_kind = %msg_kind,
+            encoding = %this.encoding, "received");
 
         Ok(deserialized)
     }
@@ -264,6 +284,12 @@ impl ConnectionId {
         &self.0
     }
 
+    /// Returns the connection ID as a digest, to be signed in a handshake.
+    #[inline]
+    pub(crate) fn to_digest(self) -> Digest {
+        Digest::from(self.0)
+    }
+
     /// Creates a new connection ID from an existing SSL connection.
     #[inline]
     pub(crate) fn from_connection(ssl: &SslRef, our_id: NodeId, their_id: NodeId) -> Self {
@@ -152,6 +152,9 @@ pub enum ConnectionError {
         #[source]
         crypto::Error,
     ),
+    /// The peer's node ID is on our blocklist.
+    #[error("peer is blocked")]
+    PeerBlocked,
 }
 
 /// IO operation that can time out or close.
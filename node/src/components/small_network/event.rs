@@ -17,7 +17,7 @@ use crate::{
     components::contract_runtime::ContractRuntimeAnnouncement,
     effect::{
         announcements::BlocklistAnnouncement,
-        requests::{NetworkInfoRequest, NetworkRequest},
+        requests::{NetworkAdminRequest, NetworkInfoRequest, NetworkRequest},
     },
     protocol::Message as ProtocolMessage,
 };
@@ -80,6 +80,13 @@ pub(crate) enum Event<P> {
         req: Box<NetworkInfoRequest<NodeId>>,
     },
 
+    /// Incoming network admin request.
+    #[from]
+    NetworkAdminRequest {
+        #[serde(skip_serializing)]
+        req: Box<NetworkAdminRequest>,
+    },
+
     /// The node should gossip its own public listening address.
     GossipOurAddress,
 
@@ -110,6 +117,12 @@ impl From<NetworkInfoRequest<NodeId>> for Event<ProtocolMessage> {
     }
 }
 
+impl From<NetworkAdminRequest> for Event<ProtocolMessage> {
+    fn from(req: NetworkAdminRequest) -> Self {
+        Self::NetworkAdminRequest { req: Box::new(req) }
+    }
+}
+
 impl<P: Display> Display for Event<P> {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
@@ -132,6 +145,7 @@ impl<P: Display> Display for Event<P> {
             }
             Event::NetworkRequest { req } => write!(f, "request: {}", req),
             Event::NetworkInfoRequest { req } => write!(f, "request: {}", req),
+            Event::NetworkAdminRequest { req } => write!(f, "admin request: {}", req),
             Event::GossipOurAddress => write!(f, "gossip our address"),
             Event::PeerAddressReceived(gossiped_address) => {
                 write!(f, "received gossiped peer address {}", gossiped_address)
@@ -6,6 +6,8 @@ use std::{
 use datasize::DataSize;
 use serde::{Deserialize, Serialize};
 
+use casper_types::bytesrepr::{self, FromBytes, ToBytes};
+
 use crate::types::{Item, Tag};
 
 /// Used to gossip our public listening address to peers.
@@ -26,6 +28,24 @@ impl Display for GossipedAddress {
     }
 }
 
+impl ToBytes for GossipedAddress {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        self.0.to_string().to_bytes()
+    }
+
+    fn serialized_length(&self) -> usize {
+        self.0.to_string().serialized_length()
+    }
+}
+
+impl FromBytes for GossipedAddress {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (address, remainder) = String::from_bytes(bytes)?;
+        let address = address.parse().map_err(|_| bytesrepr::Error::Formatting)?;
+        Ok((GossipedAddress(address), remainder))
+    }
+}
+
 impl Item for GossipedAddress {
     type Id = GossipedAddress;
     const TAG: Tag = Tag::GossipedAddress;
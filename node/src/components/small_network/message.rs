@@ -1,23 +1,33 @@
 use std::{
     fmt::{self, Debug, Display, Formatter},
     net::SocketAddr,
-    sync::Arc,
 };
 
-use casper_types::{AsymmetricType, ProtocolVersion, PublicKey, SecretKey, Signature};
+#[cfg(test)]
+use casper_types::SecretKey;
+use casper_types::{
+    bytesrepr::{FromBytes, ToBytes},
+    AsymmetricType, ProtocolVersion, PublicKey, Signature,
+};
 use datasize::DataSize;
 use serde::{
     de::{DeserializeOwned, Error as SerdeError},
     Deserialize, Deserializer, Serialize, Serializer,
 };
 
-use crate::crypto;
 #[cfg(test)]
 use crate::crypto::AsymmetricKeyExt;
 #[cfg(test)]
 use crate::testing::TestRng;
+use crate::{
+    components::consensus::{Signer, SignerError, SigningRequest},
+    crypto,
+};
 
-use super::counting_format::ConnectionId;
+use super::{
+    counting_format::ConnectionId,
+    wire_encoding::{self, WireEncoding},
+};
 
 /// The default protocol version to use in absence of one in the protocol version field.
 #[inline]
@@ -39,6 +49,13 @@ pub(crate) enum Message<P> {
         /// A self-signed certificate indicating validator status.
         #[serde(default)]
         consensus_certificate: Option<ConsensusCertificate>,
+        /// Wire encodings the node supports, in addition to MessagePack.
+        #[serde(
+            default,
+            serialize_with = "wire_encoding::serialize_offered",
+            deserialize_with = "wire_encoding::deserialize_offered"
+        )]
+        wire_encodings: Vec<WireEncoding>,
     },
     Payload(P),
 }
@@ -63,27 +80,6 @@ impl<P: Payload> Message<P> {
     }
 }
 
-/// A pair of secret keys used by consensus.
-pub(super) struct ConsensusKeyPair {
-    secret_key: Arc<SecretKey>,
-    public_key: PublicKey,
-}
-
-impl ConsensusKeyPair {
-    /// Creates a new key pair for consensus signing.
-    pub(super) fn new(secret_key: Arc<SecretKey>, public_key: PublicKey) -> Self {
-        Self {
-            secret_key,
-            public_key,
-        }
-    }
-
-    /// Sign a value using this keypair.
-    fn sign<T: AsRef<[u8]>>(&self, value: T) -> Signature {
-        crypto::sign(value, &self.secret_key, &self.public_key)
-    }
-}
-
 /// Certificate used to indicate that the peer is a validator using the specified public key.
 ///
 /// Note that this type has custom `Serialize` and `Deserialize` implementations to allow the
@@ -96,13 +92,20 @@ pub(crate) struct ConsensusCertificate {
 }
 
 impl ConsensusCertificate {
-    /// Creates a new consensus certificate from a connection ID and key pair.
-    pub(super) fn create(connection_id: ConnectionId, key_pair: &ConsensusKeyPair) -> Self {
-        let signature = key_pair.sign(connection_id.as_bytes());
-        ConsensusCertificate {
-            public_key: key_pair.public_key.clone(),
+    /// Creates a new consensus certificate by having the validator's signer sign the connection
+    /// ID.
+    pub(super) async fn create(
+        connection_id: ConnectionId,
+        signer: &Signer,
+    ) -> Result<Self, SignerError> {
+        let request = SigningRequest::Handshake {
+            connection_id: connection_id.to_digest(),
+        };
+        let signature = signer.sign(&request).await?;
+        Ok(ConsensusCertificate {
+            public_key: signer.public_key().clone(),
             signature,
-        }
+        })
     }
 
     /// Validates a certificate, returning a `PublicKey` if valid.
@@ -116,10 +119,15 @@ impl ConsensusCertificate {
     fn random(rng: &mut TestRng) -> Self {
         let secret_key = SecretKey::random(rng);
         let public_key = PublicKey::from(&secret_key);
-        ConsensusCertificate::create(
-            ConnectionId::random(rng),
-            &ConsensusKeyPair::new(Arc::new(secret_key), public_key),
-        )
+        let signature = crypto::sign(
+            ConnectionId::random(rng).as_bytes(),
+            &secret_key,
+            &public_key,
+        );
+        ConsensusCertificate {
+            public_key,
+            signature,
+        }
     }
 }
 
@@ -218,6 +226,7 @@ impl<P: Display> Display for Message<P> {
                 public_addr,
                 protocol_version,
                 consensus_certificate,
+                ..
             } => {
                 write!(
                     f,
@@ -274,7 +283,7 @@ impl Display for MessageKind {
 /// Payloads are what is transferred across the network outside of control messages from the
 /// networking component itself.
 pub(crate) trait Payload:
-    Serialize + DeserializeOwned + Clone + Debug + Display + Send + Sync + 'static
+    Serialize + DeserializeOwned + ToBytes + FromBytes + Clone + Debug + Display + Send + Sync + 'static
 {
     /// Classifies the payload based on its contents.
     fn classify(&self) -> MessageKind;
@@ -302,12 +311,17 @@ pub struct PayloadWeights {
 // We use a variety of weird names in these tests.
 #[allow(non_camel_case_types)]
 mod tests {
-    use std::net::SocketAddr;
+    use std::{net::SocketAddr, sync::Arc};
 
     use casper_types::ProtocolVersion;
+    use rand::Rng;
     use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
-    use crate::protocol;
+    use crate::{
+        components::{consensus::ConsensusMessage, gossiper, small_network::GossipedAddress},
+        protocol,
+        types::{BlockHash, Deploy, FinalitySignature, SharedObject, Tag},
+    };
 
     use super::*;
 
@@ -326,6 +340,36 @@ mod tests {
         Payload(protocol::Message),
     }
 
+    /// Version 1.4.3 network level message.
+    #[derive(Clone, Debug, Deserialize, Serialize)]
+    pub(crate) enum V1_4_3_Message {
+        Handshake {
+            network_name: String,
+            public_addr: SocketAddr,
+            protocol_version: ProtocolVersion,
+            consensus_certificate: Option<ConsensusCertificate>,
+        },
+        Payload(V1_4_3_Payload),
+    }
+
+    /// Version 1.4.3 reactor message, in which finality signatures were broadcast rather than
+    /// gossiped.
+    #[derive(Clone, Debug, Deserialize, Serialize)]
+    pub(crate) enum V1_4_3_Payload {
+        Consensus(ConsensusMessage),
+        DeployGossiper(gossiper::Message<Deploy>),
+        AddressGossiper(gossiper::Message<GossipedAddress>),
+        GetRequest {
+            tag: Tag,
+            serialized_id: Vec<u8>,
+        },
+        GetResponse {
+            tag: Tag,
+            serialized_item: SharedObject<Vec<u8>>,
+        },
+        FinalitySignature(Box<FinalitySignature>),
+    }
+
     /// A "conserved" version 1.0.0 handshake.
     ///
     /// NEVER CHANGE THIS CONSTANT TO MAKE TESTS PASS, AS IT IS BASED ON MAINNET DATA.
@@ -451,6 +495,7 @@ mod tests {
             public_addr: ([12, 34, 56, 78], 12346).into(),
             protocol_version: ProtocolVersion::from_parts(5, 6, 7),
             consensus_certificate: Some(ConsensusCertificate::random(&mut rng)),
+            wire_encodings: vec![WireEncoding::Binary],
         };
 
         let legacy_handshake: V1_0_0_Message = roundtrip_message(&modern_handshake);
@@ -484,11 +529,13 @@ mod tests {
                 public_addr,
                 protocol_version,
                 consensus_certificate,
+                wire_encodings,
             } => {
                 assert_eq!(network_name, "example-handshake");
                 assert_eq!(public_addr, ([12, 34, 56, 78], 12346).into());
                 assert_eq!(protocol_version, ProtocolVersion::V1_0_0);
                 assert!(consensus_certificate.is_none());
+                assert!(wire_encodings.is_empty());
             }
             Message::Payload(_) => {
                 panic!("did not expect modern handshake to deserialize to payload")
@@ -506,11 +553,13 @@ mod tests {
                 public_addr,
                 protocol_version,
                 consensus_certificate,
+                wire_encodings,
             } => {
                 assert_eq!(network_name, "serialization-test");
                 assert_eq!(public_addr, ([12, 34, 56, 78], 12346).into());
                 assert_eq!(protocol_version, ProtocolVersion::V1_0_0);
                 assert!(consensus_certificate.is_none());
+                assert!(wire_encodings.is_empty());
             }
             Message::Payload(_) => {
                 panic!("did not expect modern handshake to deserialize to payload")
@@ -528,10 +577,12 @@ mod tests {
                 public_addr,
                 protocol_version,
                 consensus_certificate,
+                wire_encodings,
             } => {
                 assert_eq!(network_name, "example-handshake");
                 assert_eq!(public_addr, ([12, 34, 56, 78], 12346).into());
                 assert_eq!(protocol_version, ProtocolVersion::from_parts(1, 4, 2));
+                assert!(wire_encodings.is_empty());
                 let ConsensusCertificate {
                     public_key,
                     signature,
@@ -569,10 +620,12 @@ mod tests {
                 public_addr,
                 protocol_version,
                 consensus_certificate,
+                wire_encodings,
             } => {
                 assert_eq!(network_name, "example-handshake");
                 assert_eq!(public_addr, ([12, 34, 56, 78], 12346).into());
                 assert_eq!(protocol_version, ProtocolVersion::from_parts(1, 4, 3));
+                assert!(wire_encodings.is_empty());
                 let ConsensusCertificate {
                     public_key,
                     signature,
@@ -600,6 +653,101 @@ mod tests {
         }
     }
 
+    #[test]
+    fn current_handshake_roundtrips_wire_encodings() {
+        let handshake = Message::<protocol::Message>::Handshake {
+            network_name: "example-handshake".to_string(),
+            public_addr: ([12, 34, 56, 78], 12346).into(),
+            protocol_version: ProtocolVersion::from_parts(5, 6, 7),
+            consensus_certificate: None,
+            wire_encodings: vec![WireEncoding::MessagePack, WireEncoding::Binary],
+        };
+
+        let roundtripped: Message<protocol::Message> = roundtrip_message(&handshake);
+
+        match roundtripped {
+            Message::Handshake { wire_encodings, .. } => assert_eq!(
+                wire_encodings,
+                vec![WireEncoding::MessagePack, WireEncoding::Binary]
+            ),
+            Message::Payload(_) => {
+                panic!("did not expect modern handshake to deserialize to payload")
+            }
+        }
+    }
+
+    #[test]
+    fn unknown_wire_encodings_are_ignored() {
+        /// A handshake sent by a future version supporting an encoding we do not know about.
+        #[derive(Serialize)]
+        enum FutureMessage {
+            Handshake {
+                network_name: String,
+                public_addr: SocketAddr,
+                protocol_version: ProtocolVersion,
+                consensus_certificate: Option<ConsensusCertificate>,
+                wire_encodings: Vec<u8>,
+            },
+        }
+
+        let future_handshake = FutureMessage::Handshake {
+            network_name: "example-handshake".to_string(),
+            public_addr: ([12, 34, 56, 78], 12346).into(),
+            protocol_version: ProtocolVersion::from_parts(5, 6, 7),
+            consensus_certificate: None,
+            wire_encodings: vec![1, 200],
+        };
+
+        let modern_handshake: Message<protocol::Message> = roundtrip_message(&future_handshake);
+
+        match modern_handshake {
+            Message::Handshake { wire_encodings, .. } => {
+                assert_eq!(wire_encodings, vec![WireEncoding::Binary])
+            }
+            Message::Payload(_) => {
+                panic!("did not expect modern handshake to deserialize to payload")
+            }
+        }
+    }
+
+    #[test]
+    fn current_finality_signature_decodes_from_v1_4_3() {
+        let mut rng = crate::new_rng();
+        let finality_signature =
+            FinalitySignature::random_for_block(BlockHash::random(&mut rng), rng.gen());
+        let legacy_message = V1_4_3_Message::Payload(V1_4_3_Payload::FinalitySignature(Box::new(
+            finality_signature.clone(),
+        )));
+
+        let modern_message: Message<protocol::Message> = roundtrip_message(&legacy_message);
+
+        match modern_message {
+            Message::Payload(protocol::Message::FinalitySignature(decoded)) => {
+                assert_eq!(*decoded, finality_signature)
+            }
+            other => panic!("did not expect {:?} as the deserialized product", other),
+        }
+    }
+
+    #[test]
+    fn v1_4_3_can_decode_current_finality_signature() {
+        let mut rng = crate::new_rng();
+        let finality_signature =
+            FinalitySignature::random_for_block(BlockHash::random(&mut rng), rng.gen());
+        let modern_message = Message::Payload(protocol::Message::FinalitySignature(Box::new(
+            finality_signature.clone(),
+        )));
+
+        let legacy_message: V1_4_3_Message = roundtrip_message(&modern_message);
+
+        match legacy_message {
+            V1_4_3_Message::Payload(V1_4_3_Payload::FinalitySignature(decoded)) => {
+                assert_eq!(*decoded, finality_signature)
+            }
+            other => panic!("did not expect {:?} as the deserialized product", other),
+        }
+    }
+
     fn roundtrip_certificate(use_human_readable: bool) {
         let mut rng = crate::new_rng();
         let certificate = ConsensusCertificate::random(&mut rng);
@@ -623,4 +771,22 @@ mod tests {
     fn bincode_roundtrip_certificate() {
         roundtrip_certificate(false)
     }
+
+    #[tokio::test]
+    async fn certificate_signed_by_signer_validates() {
+        let mut rng = crate::new_rng();
+        let secret_key = SecretKey::random(&mut rng);
+        let public_key = PublicKey::from(&secret_key);
+        let signer = Signer::local(Arc::new(secret_key), public_key.clone());
+        let connection_id = ConnectionId::random(&mut rng);
+
+        let certificate = ConsensusCertificate::create(connection_id, &signer)
+            .await
+            .unwrap();
+        assert!(certificate
+            .clone()
+            .validate(ConnectionId::random(&mut rng))
+            .is_err());
+        assert_eq!(certificate.validate(connection_id).unwrap(), public_key);
+    }
 }
@@ -3,7 +3,7 @@ use std::sync::Weak;
 use prometheus::{IntCounter, IntGauge, Registry};
 use tracing::debug;
 
-use super::MessageKind;
+use super::{MessageKind, WireEncoding};
 use crate::unregister_metric;
 
 /// Network-type agnostic networking metrics.
@@ -50,6 +50,23 @@ pub(super) struct Metrics {
     /// Volume in bytes of outgoing messages with other payload.
     pub(super) out_bytes_other: IntCounter,
 
+    /// Count of outgoing messages encoded with MessagePack.
+    pub(super) out_count_message_pack: IntCounter,
+    /// Count of outgoing messages encoded with the binary format.
+    pub(super) out_count_binary: IntCounter,
+    /// Volume in bytes of outgoing messages encoded with MessagePack.
+    pub(super) out_bytes_message_pack: IntCounter,
+    /// Volume in bytes of outgoing messages encoded with the binary format.
+    pub(super) out_bytes_binary: IntCounter,
+    /// Count of incoming messages encoded with MessagePack.
+    pub(super) in_count_message_pack: IntCounter,
+    /// Count of incoming messages encoded with the binary format.
+    pub(super) in_count_binary: IntCounter,
+    /// Volume in bytes of incoming messages encoded with MessagePack.
+    pub(super) in_bytes_message_pack: IntCounter,
+    /// Volume in bytes of incoming messages encoded with the binary format.
+    pub(super) in_bytes_binary: IntCounter,
+
     /// Registry instance.
     registry: Registry,
 }
@@ -129,6 +146,39 @@ impl Metrics {
             "volume in bytes of outgoing messages with other payload",
         )?;
 
+        let out_count_message_pack = IntCounter::new(
+            "net_out_count_message_pack",
+            "count of outgoing messages encoded with MessagePack",
+        )?;
+        let out_count_binary = IntCounter::new(
+            "net_out_count_binary",
+            "count of outgoing messages encoded with the binary format",
+        )?;
+        let out_bytes_message_pack = IntCounter::new(
+            "net_out_bytes_message_pack",
+            "volume in bytes of outgoing messages encoded with MessagePack",
+        )?;
+        let out_bytes_binary = IntCounter::new(
+            "net_out_bytes_binary",
+            "volume in bytes of outgoing messages encoded with the binary format",
+        )?;
+        let in_count_message_pack = IntCounter::new(
+            "net_in_count_message_pack",
+            "count of incoming messages encoded with MessagePack",
+        )?;
+        let in_count_binary = IntCounter::new(
+            "net_in_count_binary",
+            "count of incoming messages encoded with the binary format",
+        )?;
+        let in_bytes_message_pack = IntCounter::new(
+            "net_in_bytes_message_pack",
+            "volume in bytes of incoming messages encoded with MessagePack",
+        )?;
+        let in_bytes_binary = IntCounter::new(
+            "net_in_bytes_binary",
+            "volume in bytes of incoming messages encoded with the binary format",
+        )?;
+
         registry.register(Box::new(broadcast_requests.clone()))?;
         registry.register(Box::new(direct_message_requests.clone()))?;
         registry.register(Box::new(open_connections.clone()))?;
@@ -151,6 +201,15 @@ impl Metrics {
         registry.register(Box::new(out_bytes_block_transfer.clone()))?;
         registry.register(Box::new(out_bytes_other.clone()))?;
 
+        registry.register(Box::new(out_count_message_pack.clone()))?;
+        registry.register(Box::new(out_count_binary.clone()))?;
+        registry.register(Box::new(out_bytes_message_pack.clone()))?;
+        registry.register(Box::new(out_bytes_binary.clone()))?;
+        registry.register(Box::new(in_count_message_pack.clone()))?;
+        registry.register(Box::new(in_count_binary.clone()))?;
+        registry.register(Box::new(in_bytes_message_pack.clone()))?;
+        registry.register(Box::new(in_bytes_binary.clone()))?;
+
         Ok(Metrics {
             broadcast_requests,
             direct_message_requests,
@@ -171,6 +230,14 @@ impl Metrics {
             out_bytes_deploy_transfer,
             out_bytes_block_transfer,
             out_bytes_other,
+            out_count_message_pack,
+            out_count_binary,
+            out_bytes_message_pack,
+            out_bytes_binary,
+            in_count_message_pack,
+            in_count_binary,
+            in_bytes_message_pack,
+            in_bytes_binary,
             registry: registry.clone(),
         })
     }
@@ -212,6 +279,42 @@ impl Metrics {
             debug!("not recording metrics, component already shut down");
         }
     }
+
+    /// Records the encoding of an outgoing message.
+    pub(crate) fn record_encoding_out(this: &mut Weak<Self>, encoding: WireEncoding, size: u64) {
+        if let Some(metrics) = this.upgrade() {
+            match encoding {
+                WireEncoding::MessagePack => {
+                    metrics.out_bytes_message_pack.inc_by(size);
+                    metrics.out_count_message_pack.inc();
+                }
+                WireEncoding::Binary => {
+                    metrics.out_bytes_binary.inc_by(size);
+                    metrics.out_count_binary.inc();
+                }
+            }
+        } else {
+            debug!("not recording metrics, component already shut down");
+        }
+    }
+
+    /// Records the encoding of an incoming message.
+    pub(crate) fn record_encoding_in(this: &mut Weak<Self>, encoding: WireEncoding, size: u64) {
+        if let Some(metrics) = this.upgrade() {
+            match encoding {
+                WireEncoding::MessagePack => {
+                    metrics.in_bytes_message_pack.inc_by(size);
+                    metrics.in_count_message_pack.inc();
+                }
+                WireEncoding::Binary => {
+                    metrics.in_bytes_binary.inc_by(size);
+                    metrics.in_count_binary.inc();
+                }
+            }
+        } else {
+            debug!("not recording metrics, component already shut down");
+        }
+    }
 }
 
 impl Drop for Metrics {
@@ -237,5 +340,14 @@ impl Drop for Metrics {
         unregister_metric!(self.registry, self.out_bytes_deploy_transfer);
         unregister_metric!(self.registry, self.out_bytes_block_transfer);
         unregister_metric!(self.registry, self.out_bytes_other);
+
+        unregister_metric!(self.registry, self.out_count_message_pack);
+        unregister_metric!(self.registry, self.out_count_binary);
+        unregister_metric!(self.registry, self.out_bytes_message_pack);
+        unregister_metric!(self.registry, self.out_bytes_binary);
+        unregister_metric!(self.registry, self.in_count_message_pack);
+        unregister_metric!(self.registry, self.in_count_binary);
+        unregister_metric!(self.registry, self.in_bytes_message_pack);
+        unregister_metric!(self.registry, self.in_bytes_binary);
     }
 }
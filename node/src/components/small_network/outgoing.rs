@@ -12,6 +12,9 @@
 //!   connection to the given address, only giving up if retry thresholds are exceeded, after which
 //!   it will be forgotten.
 //! * `block_addr` and `redeem_addr` can be used to maintain a `SocketAddr`-keyed block list.
+//! * `connect_addr` and `disconnect_addr` allow the node operator to override the automatic
+//!   connection management for individual addresses, while `status` reports the state of every
+//!   known address.
 //! * `OutgoingManager` maintains an internal routing table. The `get_route` function can be used to
 //!   retrieve a "route" (typically a `sync::channel` accepting network messages) to a remote peer
 //!   by `NodeId`.
@@ -102,6 +105,7 @@ use std::{
 };
 
 use datasize::DataSize;
+use serde::Serialize;
 
 use tracing::{debug, error_span, field::Empty, info, trace, warn, Span};
 
@@ -182,6 +186,21 @@ where
     }
 }
 
+/// The state of an outgoing address, as reported to the node operator.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub(crate) struct OutgoingStatus {
+    /// The outgoing address.
+    pub(crate) addr: SocketAddr,
+    /// The state the address is in.
+    pub(crate) state: String,
+    /// The full hex node ID of the peer, if connected.
+    pub(crate) peer_id: Option<String>,
+    /// The most recent connection error, if waiting for a retry.
+    pub(crate) last_error: Option<String>,
+    /// Whether the address is unforgettable, see `learn_addr` for details.
+    pub(crate) unforgettable: bool,
+}
+
 /// The result of dialing `SocketAddr`.
 #[derive(Debug)]
 pub enum DialOutcome<H, E> {
@@ -502,6 +521,120 @@ where
             })
     }
 
+    /// Connects to an address on behalf of the node operator, marking it as unforgettable.
+    ///
+    /// Unknown addresses are learned, blocked addresses are redeemed and addresses waiting for a
+    /// retry are dialed immediately.
+    pub(crate) fn connect_addr(
+        &mut self,
+        addr: SocketAddr,
+        now: Instant,
+    ) -> Option<DialRequest<H>> {
+        match self.outgoing.get_mut(&addr) {
+            None => return self.learn_addr(addr, true, now),
+            Some(outgoing) => {
+                outgoing.is_unforgettable = true;
+                if matches!(outgoing.state, OutgoingState::Blocked { .. }) {
+                    return self.redeem_addr(addr, now);
+                }
+            }
+        }
+
+        let span = make_span(addr, self.outgoing.get(&addr));
+        span.clone().in_scope(move || {
+            match self.outgoing.get(&addr).map(|outgoing| &outgoing.state) {
+                Some(OutgoingState::Waiting { .. }) => {
+                    info!("retrying address, as requested");
+                    self.change_outgoing_state(
+                        addr,
+                        OutgoingState::Connecting {
+                            failures_so_far: 0,
+                            since: now,
+                        },
+                    );
+                    Some(DialRequest::Dial { addr, span })
+                }
+                _ => {
+                    debug!("address connection request ignored, already connecting or connected");
+                    None
+                }
+            }
+        })
+    }
+
+    /// Disconnects from and forgets an address on behalf of the node operator.
+    ///
+    /// The address is connected to again if it is learned anew, e.g. through gossip. Blocked and
+    /// loopback addresses are not forgotten, and a dial already in progress is not aborted.
+    pub(crate) fn disconnect_addr(&mut self, addr: SocketAddr) -> Option<DialRequest<H>> {
+        let span = make_span(addr, self.outgoing.get(&addr));
+        span.clone().in_scope(move || {
+            match self.outgoing.get(&addr).map(|outgoing| &outgoing.state) {
+                None => {
+                    debug!("unknown address disconnect ignored");
+                    None
+                }
+                Some(OutgoingState::Blocked { .. }) | Some(OutgoingState::Loopback) => {
+                    debug!("address disconnect ignored, not connecting");
+                    None
+                }
+                Some(_) => {
+                    info!("forgetting address, as requested");
+                    match self.outgoing.remove(&addr) {
+                        Some(Outgoing {
+                            state: OutgoingState::Connected { peer_id, handle },
+                            ..
+                        }) => {
+                            debug!(%peer_id, "route removed");
+                            self.routes.remove(&peer_id);
+                            Some(DialRequest::Disconnect { handle, span })
+                        }
+                        _ => None,
+                    }
+                }
+            }
+        })
+    }
+
+    /// Marks a known address as unforgettable, see `learn_addr` for details.
+    ///
+    /// Returns `false` if the address is unknown.
+    pub(crate) fn mark_unforgettable(&mut self, addr: SocketAddr) -> bool {
+        match self.outgoing.get_mut(&addr) {
+            Some(outgoing) => {
+                outgoing.is_unforgettable = true;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the state of every known address, ordered by address.
+    pub(crate) fn status(&self) -> Vec<OutgoingStatus> {
+        let mut status: Vec<_> = self
+            .outgoing
+            .iter()
+            .map(|(&addr, outgoing)| {
+                let (peer_id, last_error) = match outgoing.state {
+                    OutgoingState::Connected { peer_id, .. } => (Some(peer_id.to_hex()), None),
+                    OutgoingState::Waiting { ref error, .. } => {
+                        (None, error.as_ref().map(ToString::to_string))
+                    }
+                    _ => (None, None),
+                };
+                OutgoingStatus {
+                    addr,
+                    state: outgoing.state.to_string(),
+                    peer_id,
+                    last_error,
+                    unforgettable: outgoing.is_unforgettable,
+                }
+            })
+            .collect();
+        status.sort_by_key(|status| status.addr);
+        status
+    }
+
     /// Checks if an address is blocked.
     #[cfg(test)]
     pub(crate) fn is_blocked(&self, addr: SocketAddr) -> bool {
@@ -514,8 +647,6 @@ where
     /// Removes an address from the block list.
     ///
     /// Does nothing if the address was not blocked.
-    // This function is currently not in use by `small_network` itself.
-    #[allow(dead_code)]
     pub(crate) fn redeem_addr(&mut self, addr: SocketAddr, now: Instant) -> Option<DialRequest<H>> {
         let span = make_span(addr, self.outgoing.get(&addr));
         span.clone()
@@ -1335,4 +1466,82 @@ mod tests {
         assert!(manager.perform_housekeeping(clock.now()).is_empty());
         assert!(manager.is_blocked(addr_a));
     }
+
+    #[test]
+    fn operator_connect_and_disconnect_work() {
+        init_logging();
+
+        let mut rng = crate::new_rng();
+        let mut clock = TestClock::new();
+
+        let addr_a: SocketAddr = "1.2.3.4:1234".parse().unwrap();
+        let addr_b: SocketAddr = "5.6.7.8:5678".parse().unwrap();
+        let addr_c: SocketAddr = "9.0.1.2:9012".parse().unwrap();
+        let id_a = NodeId::random(&mut rng);
+
+        let mut manager = OutgoingManager::<u32, TestDialerError>::new(test_config());
+
+        // Connecting to an unknown address learns it as unforgettable.
+        assert!(dials(addr_a, &manager.connect_addr(addr_a, clock.now())));
+        assert!(manager
+            .handle_dial_outcome(DialOutcome::Successful {
+                addr: addr_a,
+                handle: 1,
+                node_id: id_a,
+            })
+            .is_none());
+        assert!(manager.connect_addr(addr_a, clock.now()).is_none());
+
+        // A waiting address is retried immediately, without waiting for the backoff.
+        assert!(dials(
+            addr_b,
+            &manager.learn_addr(addr_b, false, clock.now())
+        ));
+        assert!(manager
+            .handle_dial_outcome(DialOutcome::Failed {
+                addr: addr_b,
+                error: TestDialerError { id: 2 },
+                when: clock.now(),
+            })
+            .is_none());
+        assert!(dials(addr_b, &manager.connect_addr(addr_b, clock.now())));
+
+        // A blocked address is redeemed.
+        assert!(manager.block_addr(addr_c, clock.now()).is_none());
+        assert!(dials(addr_c, &manager.connect_addr(addr_c, clock.now())));
+        assert!(!manager.is_blocked(addr_c));
+
+        let status = manager.status();
+        assert_eq!(
+            status.iter().map(|status| status.addr).collect::<Vec<_>>(),
+            vec![addr_a, addr_b, addr_c]
+        );
+        assert_eq!(status[0].state, "connected");
+        assert_eq!(status[0].peer_id, Some(id_a.to_hex()));
+        assert!(status.iter().all(|status| status.unforgettable));
+
+        // Disconnecting drops the connection and the route, and forgets the address.
+        assert!(disconnects(1, &manager.disconnect_addr(addr_a)));
+        assert!(manager.get_route(id_a).is_none());
+        assert!(manager.disconnect_addr(addr_a).is_none());
+        assert_eq!(manager.status().len(), 2);
+
+        // Blocked addresses stay blocked.
+        assert!(manager.block_addr(addr_c, clock.now()).is_none());
+        assert!(manager.disconnect_addr(addr_c).is_none());
+        assert!(manager.is_blocked(addr_c));
+
+        clock.advance_time(1_000);
+        assert!(manager
+            .handle_dial_outcome(DialOutcome::Failed {
+                addr: addr_b,
+                error: TestDialerError { id: 3 },
+                when: clock.now(),
+            })
+            .is_none());
+        assert_eq!(
+            manager.status()[0].last_error,
+            Some("test dialer error(3)".to_string())
+        );
+    }
 }
@@ -0,0 +1,118 @@
+//! Peers reserved by the node operator.
+//!
+//! Reserved peers are never blocked after committing offenses, and their addresses are never
+//! forgotten. Like the blocklist, the reservations are persisted to disk, so that they survive a
+//! restart of the node.
+
+use std::{collections::BTreeSet, path::PathBuf};
+
+use datasize::DataSize;
+use tracing::{info, warn};
+
+use crate::{types::NodeId, utils};
+
+/// The set of reserved peers.
+#[derive(DataSize, Debug)]
+pub(super) struct ReservedPeers {
+    /// The node IDs of the reserved peers.
+    peers: BTreeSet<NodeId>,
+    /// Path the reserved peers are persisted to, if any.
+    path: Option<PathBuf>,
+}
+
+impl ReservedPeers {
+    /// Creates a new set of reserved peers, loading those persisted at `path`, if any.
+    pub(super) fn new(path: Option<PathBuf>) -> Self {
+        let mut peers = BTreeSet::new();
+        if let Some(path) = path.as_ref().filter(|path| path.exists()) {
+            let persisted = utils::read_file(path)
+                .map_err(|err| err.to_string())
+                .and_then(|bytes| {
+                    serde_json::from_slice::<BTreeSet<NodeId>>(&bytes)
+                        .map_err(|err| err.to_string())
+                });
+            match persisted {
+                Ok(persisted) => {
+                    peers = persisted;
+                    info!(path=%path.display(), count = peers.len(), "loaded reserved peers");
+                }
+                Err(err) => {
+                    warn!(path=%path.display(), %err, "could not load reserved peers, starting empty");
+                }
+            }
+        }
+
+        ReservedPeers { peers, path }
+    }
+
+    /// Reserves `peer_id`.
+    pub(super) fn reserve(&mut self, peer_id: NodeId) {
+        if self.peers.insert(peer_id) {
+            self.persist();
+        }
+    }
+
+    /// Revokes the reservation of `peer_id`.
+    ///
+    /// Returns `true` if the peer was reserved.
+    pub(super) fn unreserve(&mut self, peer_id: &NodeId) -> bool {
+        let was_reserved = self.peers.remove(peer_id);
+        if was_reserved {
+            self.persist();
+        }
+        was_reserved
+    }
+
+    /// Returns whether `peer_id` is reserved.
+    pub(super) fn contains(&self, peer_id: &NodeId) -> bool {
+        self.peers.contains(peer_id)
+    }
+
+    /// Returns an iterator over the reserved peers.
+    pub(super) fn iter(&self) -> impl Iterator<Item = &NodeId> {
+        self.peers.iter()
+    }
+
+    /// Writes the reserved peers to disk, if there is a path.
+    fn persist(&self) {
+        let path = match self.path.as_ref() {
+            Some(path) => path,
+            None => return,
+        };
+        let result = serde_json::to_vec_pretty(&self.peers)
+            .map_err(|err| err.to_string())
+            .and_then(|json| utils::write_file(path, json).map_err(|err| err.to_string()));
+        if let Err(err) = result {
+            warn!(path=%path.display(), %err, "could not persist reserved peers");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::tempdir;
+
+    use crate::{testing::TestRng, types::NodeId};
+
+    use super::ReservedPeers;
+
+    #[test]
+    fn should_persist_reservations() {
+        let mut rng = TestRng::new();
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("reserved_peers.json");
+        let peer_id = NodeId::random(&mut rng);
+        let other_peer_id = NodeId::random(&mut rng);
+
+        let mut reserved_peers = ReservedPeers::new(Some(path.clone()));
+        reserved_peers.reserve(peer_id);
+        reserved_peers.reserve(other_peer_id);
+        assert!(reserved_peers.unreserve(&other_peer_id));
+        assert!(!reserved_peers.unreserve(&other_peer_id));
+
+        let reserved_peers = ReservedPeers::new(Some(path));
+        assert!(reserved_peers.contains(&peer_id));
+        assert!(!reserved_peers.contains(&other_peer_id));
+        assert_eq!(reserved_peers.iter().count(), 1);
+    }
+}
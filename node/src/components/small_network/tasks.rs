@@ -40,10 +40,11 @@ use super::{
     event::{IncomingConnection, OutgoingConnection},
     framed,
     limiter::LimiterHandle,
-    message::{ConsensusKeyPair, PayloadWeights},
-    Event, FramedTransport, Message, Metrics, Payload, Transport,
+    message::{ConsensusCertificate, PayloadWeights},
+    reframed, Event, FramedTransport, Message, Metrics, Payload, Transport, WireEncoding,
 };
 use crate::{
+    components::consensus::Signer,
     reactor::{EventQueueHandle, QueueKind},
     tls::{self, TlsCert},
     types::NodeId,
@@ -122,7 +123,7 @@ where
 
     // Setup connection sink and stream.
     let connection_id = ConnectionId::from_connection(transport.ssl(), context.our_id, peer_id);
-    let mut transport = framed::<P>(
+    let mut transport = framed::<_, P>(
         context.net_metrics.clone(),
         connection_id,
         transport,
@@ -132,7 +133,7 @@ where
 
     // Negotiate the handshake, concluding the incoming connection process.
     match negotiate_handshake(&context, &mut transport, connection_id).await {
-        Ok((public_addr, peer_consensus_public_key)) => {
+        Ok((public_addr, peer_consensus_public_key, encoding)) => {
             if let Some(ref public_key) = peer_consensus_public_key {
                 Span::current().record("validator_id", &field::display(public_key));
             }
@@ -142,6 +143,16 @@ where
                 warn!(%public_addr, %peer_addr, "peer advertises a different public address than what we connected to");
             }
 
+            let transport = reframed(
+                transport,
+                context.net_metrics.clone(),
+                connection_id,
+                Role::Dialer,
+                encoding,
+                context.compression_threshold,
+                context.chain_info.maximum_net_message_size,
+            );
+
             // Close the receiving end of the transport.
             let (sink, _stream) = transport.split();
 
@@ -179,10 +190,14 @@ where
     pub(super) chain_info: ChainInfo,
     /// Our own public listening address.
     pub(super) public_addr: SocketAddr,
-    /// Optional set of consensus keys, to identify as a validator during handshake.
-    pub(super) consensus_keys: Option<ConsensusKeyPair>,
+    /// Optional consensus signer, to identify as a validator during handshake.
+    pub(super) consensus_signer: Option<Arc<Signer>>,
     /// Weights to estimate payloads with.
     pub(super) payload_weights: PayloadWeights,
+    /// Wire encodings offered to peers during the handshake.
+    pub(super) wire_encodings: Vec<WireEncoding>,
+    /// Minimum size in bytes of a message to be compressed when using the binary wire encoding.
+    pub(super) compression_threshold: u32,
 }
 
 /// Handles an incoming connection.
@@ -219,7 +234,7 @@ where
 
     // Setup connection sink and stream.
     let connection_id = ConnectionId::from_connection(transport.ssl(), context.our_id, peer_id);
-    let mut transport = framed::<P>(
+    let mut transport = framed::<_, P>(
         context.net_metrics.clone(),
         connection_id,
         transport,
@@ -229,11 +244,21 @@ where
 
     // Negotiate the handshake, concluding the incoming connection process.
     match negotiate_handshake(&context, &mut transport, connection_id).await {
-        Ok((public_addr, peer_consensus_public_key)) => {
+        Ok((public_addr, peer_consensus_public_key, encoding)) => {
             if let Some(ref public_key) = peer_consensus_public_key {
                 Span::current().record("validator_id", &field::display(public_key));
             }
 
+            let transport = reframed(
+                transport,
+                context.net_metrics.clone(),
+                connection_id,
+                Role::Listener,
+                encoding,
+                context.compression_threshold,
+                context.chain_info.maximum_net_message_size,
+            );
+
             // Close the receiving end of the transport.
             let (_sink, stream) = transport.split();
 
@@ -315,19 +340,33 @@ where
     }
 }
 
+/// Exchanges handshakes with a peer.
+///
+/// Returns the peer's public address, its consensus key if it is a validator and the wire encoding
+/// negotiated for the rest of the connection.
 async fn negotiate_handshake<P, REv>(
     context: &NetworkContext<REv>,
     transport: &mut FramedTransport<P>,
     connection_id: ConnectionId,
-) -> Result<(SocketAddr, Option<PublicKey>), ConnectionError>
+) -> Result<(SocketAddr, Option<PublicKey>, WireEncoding), ConnectionError>
 where
     P: Payload,
 {
+    // If we are a validator, prove it by signing the connection ID. The signer may be an external
+    // process, so a failure only means we connect as a non-validator.
+    let consensus_certificate = match context.consensus_signer.as_ref() {
+        Some(signer) => ConsensusCertificate::create(connection_id, signer)
+            .await
+            .map_err(|error| warn!(%error, "could not sign handshake"))
+            .ok(),
+        None => None,
+    };
+
     // Send down a handshake and expect one in response.
     let handshake = context.chain_info.create_handshake(
         context.public_addr,
-        context.consensus_keys.as_ref(),
-        connection_id,
+        consensus_certificate,
+        &context.wire_encodings,
     );
 
     io_timeout(HANDSHAKE_TIMEOUT, transport.send(Arc::new(handshake)))
@@ -343,6 +382,7 @@ where
         public_addr,
         protocol_version,
         consensus_certificate,
+        wire_encodings,
     } = remote_handshake
     {
         debug!(%protocol_version, "handshake received");
@@ -359,7 +399,10 @@ where
             })
             .transpose()?;
 
-        Ok((public_addr, peer_consensus_public_key))
+        let encoding = WireEncoding::negotiate(&context.wire_encodings, &wire_encodings);
+        debug!(%encoding, "negotiated wire encoding");
+
+        Ok((public_addr, peer_consensus_public_key, encoding))
     } else {
         // Received a non-handshake, this is an error.
         Err(ConnectionError::DidNotSendHandshake)
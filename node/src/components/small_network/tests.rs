@@ -6,19 +6,27 @@
 use std::{
     collections::{HashMap, HashSet},
     fmt::{self, Debug, Display, Formatter},
+    sync::{Arc, Weak},
     time::{Duration, Instant},
 };
 
 use derive_more::From;
+use futures::{SinkExt, StreamExt};
 use pnet::datalink;
 use prometheus::Registry;
 use reactor::ReactorEvent;
 use serde::{Deserialize, Serialize};
 use tracing::{debug, info};
 
+use casper_types::bytesrepr::{self, FromBytes, ToBytes};
+
 use super::{
-    chain_info::ChainInfo, Config, Event as SmallNetworkEvent, GossipedAddress, MessageKind,
-    Payload, SmallNetwork,
+    chain_info::ChainInfo,
+    counting_format::{ConnectionId, Role},
+    framed,
+    message::Message as NetworkMessage,
+    reframed, Config, Event as SmallNetworkEvent, FramedStream, GossipedAddress, MessageKind,
+    Payload, SmallNetwork, WireEncoding,
 };
 use crate::{
     components::{
@@ -27,7 +35,9 @@ use crate::{
         Component,
     },
     effect::{
-        announcements::{ControlAnnouncement, GossiperAnnouncement, NetworkAnnouncement},
+        announcements::{
+            BlocklistAnnouncement, ControlAnnouncement, GossiperAnnouncement, NetworkAnnouncement,
+        },
         requests::{NetworkRequest, StorageRequest},
         EffectBuilder, Effects,
     },
@@ -58,6 +68,8 @@ enum Event {
     NetworkAnnouncement(#[serde(skip_serializing)] NetworkAnnouncement<NodeId, Message>),
     #[from]
     AddressGossiperAnnouncement(#[serde(skip_serializing)] GossiperAnnouncement<GossipedAddress>),
+    #[from]
+    BlocklistAnnouncement(BlocklistAnnouncement<NodeId>),
 }
 
 impl ReactorEvent for Event {
@@ -114,6 +126,27 @@ impl Display for Message {
     }
 }
 
+impl ToBytes for Message {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        match self {
+            Message::AddressGossiper(gossiped_address) => gossiped_address.to_bytes(),
+        }
+    }
+
+    fn serialized_length(&self) -> usize {
+        match self {
+            Message::AddressGossiper(gossiped_address) => gossiped_address.serialized_length(),
+        }
+    }
+}
+
+impl FromBytes for Message {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (gossiped_address, remainder) = gossiper::Message::from_bytes(bytes)?;
+        Ok((Message::AddressGossiper(gossiped_address), remainder))
+    }
+}
+
 impl Payload for Message {
     #[inline]
     fn classify(&self) -> MessageKind {
@@ -151,6 +184,8 @@ impl Reactor for TestReactor {
             registry,
             small_network_identity,
             ChainInfo::create_for_testing(),
+            None,
+            None,
         )?;
         let gossiper_config = gossiper::Config::new_with_small_timeouts();
         let address_gossiper =
@@ -222,6 +257,11 @@ impl Reactor for TestReactor {
                 // We do not care about the announcement of gossiping finished in this test.
                 Effects::new()
             }
+            Event::BlocklistAnnouncement(ann) => self.dispatch_event(
+                effect_builder,
+                rng,
+                Event::SmallNet(SmallNetworkEvent::from(ann)),
+            ),
         }
     }
 
@@ -487,3 +527,122 @@ async fn ensure_peers_metric_is_correct() {
         net.finalize().await;
     }
 }
+
+/// Receives the handshake of the peer on a connection and switches it over to the wire encoding
+/// negotiated with the peer.
+async fn receive_handshake_and_reframe<S>(
+    transport: FramedStream<S, Message>,
+    connection_id: ConnectionId,
+    role: Role,
+    our_wire_encodings: &[WireEncoding],
+) -> (FramedStream<S, Message>, WireEncoding)
+where
+    S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin,
+{
+    let mut transport = transport;
+    let their_wire_encodings = match transport.next().await {
+        Some(Ok(NetworkMessage::Handshake { wire_encodings, .. })) => wire_encodings,
+        other => panic!("expected a handshake, got {:?}", other),
+    };
+    let encoding = WireEncoding::negotiate(our_wire_encodings, &their_wire_encodings);
+    let maximum_net_message_size = ChainInfo::create_for_testing().maximum_net_message_size;
+    let transport = reframed(
+        transport,
+        Weak::new(),
+        connection_id,
+        role,
+        encoding,
+        0,
+        maximum_net_message_size,
+    );
+    (transport, encoding)
+}
+
+/// Checks that both ends of a connection switch over to the negotiated wire encoding after the
+/// handshake, including for messages sent before the receiving end switched over.
+#[tokio::test]
+async fn reframing_switches_both_ends_to_negotiated_encoding() {
+    let mut rng = crate::new_rng();
+    let chain_info = ChainInfo::create_for_testing();
+    let connection_id = ConnectionId::random(&mut rng);
+    let public_addr = "127.0.0.1:34553".parse().unwrap();
+    let dialer_encodings = [WireEncoding::MessagePack, WireEncoding::Binary];
+    let listener_encodings = [WireEncoding::Binary, WireEncoding::MessagePack];
+
+    let (dialer_stream, listener_stream) = tokio::io::duplex(64 * 1024);
+    let mut dialer = framed::<_, Message>(
+        Weak::new(),
+        connection_id,
+        dialer_stream,
+        Role::Dialer,
+        chain_info.maximum_net_message_size,
+    );
+    let mut listener = framed::<_, Message>(
+        Weak::new(),
+        connection_id,
+        listener_stream,
+        Role::Listener,
+        chain_info.maximum_net_message_size,
+    );
+
+    dialer
+        .send(Arc::new(chain_info.create_handshake(
+            public_addr,
+            None,
+            &dialer_encodings,
+        )))
+        .await
+        .expect("dialer failed to send handshake");
+    listener
+        .send(Arc::new(chain_info.create_handshake(
+            public_addr,
+            None,
+            &listener_encodings,
+        )))
+        .await
+        .expect("listener failed to send handshake");
+
+    // The dialer switches over first and immediately sends a payload, which the listener only
+    // decodes after it has switched over as well.
+    let (mut dialer, dialer_encoding) =
+        receive_handshake_and_reframe(dialer, connection_id, Role::Dialer, &dialer_encodings).await;
+    let gossiped_address = GossipedAddress::new(public_addr);
+    dialer
+        .send(Arc::new(NetworkMessage::Payload(Message::AddressGossiper(
+            gossiper::Message::Gossip(gossiped_address),
+        ))))
+        .await
+        .expect("dialer failed to send payload");
+
+    let (mut listener, listener_encoding) =
+        receive_handshake_and_reframe(listener, connection_id, Role::Listener, &listener_encodings)
+            .await;
+    assert_eq!(dialer_encoding, WireEncoding::Binary);
+    assert_eq!(listener_encoding, WireEncoding::Binary);
+
+    match listener.next().await {
+        Some(Ok(NetworkMessage::Payload(Message::AddressGossiper(gossiper::Message::Gossip(
+            received,
+        ))))) => assert_eq!(received, gossiped_address),
+        other => panic!("expected a gossiped address, got {:?}", other),
+    }
+
+    listener
+        .send(Arc::new(NetworkMessage::Payload(Message::AddressGossiper(
+            gossiper::Message::GossipResponse {
+                item_id: gossiped_address,
+                is_already_held: true,
+            },
+        ))))
+        .await
+        .expect("listener failed to send payload");
+    match dialer.next().await {
+        Some(Ok(NetworkMessage::Payload(Message::AddressGossiper(
+            gossiper::Message::GossipResponse {
+                item_id,
+                is_already_held: true,
+            },
+        )))) => assert_eq!(item_id, gossiped_address),
+        other => panic!("expected a gossip response, got {:?}", other),
+    }
+}
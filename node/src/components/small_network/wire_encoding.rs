@@ -0,0 +1,351 @@
+//! Negotiable wire encodings.
+//!
+//! Every connection starts out encoding messages with MessagePack, which is what all nodes
+//! understand. Both peers list the additional encodings they support in their handshake, and once
+//! the handshakes have been exchanged, both ends of the connection switch to the most compact
+//! encoding supported by both of them.
+
+use std::{
+    convert::TryFrom,
+    fmt::{self, Display, Formatter},
+    io,
+    pin::Pin,
+    sync::Arc,
+};
+
+use bytes::{Bytes, BytesMut};
+use datasize::DataSize;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use casper_types::bytesrepr::{FromBytes, ToBytes};
+
+use super::{binary_format::BinaryFormat, message_pack_format::MessagePackFormat, Message};
+
+/// An encoding used to serialize messages sent across the network.
+///
+/// Variants are ordered by preference, later ones being more compact.
+#[derive(
+    Copy, Clone, DataSize, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum WireEncoding {
+    /// MessagePack, the encoding every node supports.
+    MessagePack,
+    /// The `bytesrepr` encoding of a message payload, optionally compressed with zstd.
+    Binary,
+}
+
+impl WireEncoding {
+    /// Returns the ID identifying the encoding in handshakes.
+    fn id(self) -> u8 {
+        match self {
+            WireEncoding::MessagePack => 0,
+            WireEncoding::Binary => 1,
+        }
+    }
+
+    /// Negotiates the encoding to use on a connection.
+    ///
+    /// Returns the most compact encoding offered by both peers, falling back to MessagePack if
+    /// there is none.
+    pub(super) fn negotiate(ours: &[WireEncoding], theirs: &[WireEncoding]) -> WireEncoding {
+        ours.iter()
+            .filter(|encoding| theirs.contains(encoding))
+            .max()
+            .copied()
+            .unwrap_or(WireEncoding::MessagePack)
+    }
+}
+
+impl TryFrom<u8> for WireEncoding {
+    type Error = u8;
+
+    fn try_from(id: u8) -> Result<Self, Self::Error> {
+        match id {
+            0 => Ok(WireEncoding::MessagePack),
+            1 => Ok(WireEncoding::Binary),
+            unknown => Err(unknown),
+        }
+    }
+}
+
+impl Display for WireEncoding {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            WireEncoding::MessagePack => f.write_str("message_pack"),
+            WireEncoding::Binary => f.write_str("binary"),
+        }
+    }
+}
+
+/// Serializes the wire encodings offered in a handshake as their IDs.
+pub(super) fn serialize_offered<S: Serializer>(
+    encodings: &[WireEncoding],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let ids: Vec<u8> = encodings.iter().map(|encoding| encoding.id()).collect();
+    ids.serialize(serializer)
+}
+
+/// Deserializes the wire encodings offered in a handshake.
+///
+/// IDs of encodings unknown to us, which may have been added by newer versions of the node, are
+/// skipped.
+pub(super) fn deserialize_offered<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<WireEncoding>, D::Error> {
+    let ids = Vec::<u8>::deserialize(deserializer)?;
+    Ok(ids
+        .into_iter()
+        .filter_map(|id| WireEncoding::try_from(id).ok())
+        .collect())
+}
+
+/// Encoder/decoder for messages using a negotiated wire encoding.
+#[derive(Debug)]
+pub enum WireFormat {
+    /// Messages are encoded with MessagePack.
+    MessagePack(MessagePackFormat),
+    /// Messages are encoded with the compact binary format.
+    Binary(BinaryFormat),
+}
+
+impl WireFormat {
+    /// Creates a new encoder/decoder for the given encoding.
+    ///
+    /// `compression_threshold` and `maximum_message_size` are only used by the binary format.
+    pub(super) fn new(
+        encoding: WireEncoding,
+        compression_threshold: u32,
+        maximum_message_size: u32,
+    ) -> Self {
+        match encoding {
+            WireEncoding::MessagePack => WireFormat::MessagePack(MessagePackFormat),
+            WireEncoding::Binary => WireFormat::Binary(BinaryFormat::new(
+                compression_threshold,
+                maximum_message_size,
+            )),
+        }
+    }
+}
+
+impl<P> tokio_serde::Serializer<Arc<Message<P>>> for WireFormat
+where
+    Message<P>: Serialize,
+    P: ToBytes,
+{
+    type Error = io::Error;
+
+    #[inline]
+    fn serialize(self: Pin<&mut Self>, item: &Arc<Message<P>>) -> Result<Bytes, Self::Error> {
+        match self.get_mut() {
+            WireFormat::MessagePack(format) => {
+                tokio_serde::Serializer::serialize(Pin::new(format), item)
+            }
+            WireFormat::Binary(format) => {
+                tokio_serde::Serializer::serialize(Pin::new(format), item)
+            }
+        }
+    }
+}
+
+impl<P> tokio_serde::Deserializer<Message<P>> for WireFormat
+where
+    for<'de> Message<P>: Deserialize<'de>,
+    P: FromBytes,
+{
+    type Error = io::Error;
+
+    #[inline]
+    fn deserialize(self: Pin<&mut Self>, src: &BytesMut) -> Result<Message<P>, Self::Error> {
+        match self.get_mut() {
+            WireFormat::MessagePack(format) => {
+                tokio_serde::Deserializer::deserialize(Pin::new(format), src)
+            }
+            WireFormat::Binary(format) => {
+                tokio_serde::Deserializer::deserialize(Pin::new(format), src)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{pin::Pin, sync::Arc};
+
+    use bytes::BytesMut;
+    use rand::Rng;
+    use tokio_serde::{Deserializer, Serializer};
+
+    use casper_types::{EraId, PublicKey};
+
+    use super::{Message, WireEncoding, WireFormat};
+    use crate::{
+        components::{consensus::ConsensusMessage, gossiper, small_network::GossipedAddress},
+        crypto::AsymmetricKeyExt,
+        protocol,
+        testing::TestRng,
+        types::{BlockHash, Deploy, FinalitySignature, FinalitySignatureId, Item, SharedObject},
+    };
+
+    /// Compression threshold used in tests.
+    const COMPRESSION_THRESHOLD: u32 = 1024;
+    /// Maximum message size used in tests.
+    const MAXIMUM_MESSAGE_SIZE: u32 = 1024 * 1024;
+
+    /// Sends a payload through a binary encoder/decoder and checks that it arrives unchanged.
+    fn assert_binary_roundtrip(payload: protocol::Message) {
+        let mut format = WireFormat::new(
+            WireEncoding::Binary,
+            COMPRESSION_THRESHOLD,
+            MAXIMUM_MESSAGE_SIZE,
+        );
+        let expected = bincode::serialize(&payload).unwrap();
+
+        let serialized =
+            Serializer::serialize(Pin::new(&mut format), &Arc::new(Message::Payload(payload)))
+                .expect("serialization failed");
+        let deserialized: Message<protocol::Message> =
+            Deserializer::deserialize(Pin::new(&mut format), &BytesMut::from(&serialized[..]))
+                .expect("deserialization failed");
+
+        match deserialized {
+            Message::Payload(payload) => {
+                assert_eq!(bincode::serialize(&payload).unwrap(), expected)
+            }
+            Message::Handshake { .. } => panic!("did not expect a handshake"),
+        }
+    }
+
+    #[test]
+    fn binary_format_roundtrips_consensus_messages() {
+        let mut rng = TestRng::new();
+        let era_id = EraId::from(rng.gen::<u64>());
+
+        // Large enough to get compressed.
+        let payload = vec![rng.gen(); 4 * COMPRESSION_THRESHOLD as usize];
+        assert_binary_roundtrip(protocol::Message::Consensus(ConsensusMessage::Protocol {
+            era_id,
+            payload,
+        }));
+        assert_binary_roundtrip(protocol::Message::Consensus(
+            ConsensusMessage::EvidenceRequest {
+                era_id,
+                pub_key: PublicKey::random(&mut rng),
+            },
+        ));
+    }
+
+    #[test]
+    fn binary_format_roundtrips_gossip_messages() {
+        let mut rng = TestRng::new();
+        let deploy = Deploy::random(&mut rng);
+
+        assert_binary_roundtrip(protocol::Message::DeployGossiper(
+            gossiper::Message::Gossip(*deploy.id()),
+        ));
+        assert_binary_roundtrip(protocol::Message::DeployGossiper(
+            gossiper::Message::GossipResponse {
+                item_id: *deploy.id(),
+                is_already_held: false,
+            },
+        ));
+        assert_binary_roundtrip(protocol::Message::AddressGossiper(
+            gossiper::Message::Gossip(GossipedAddress::new("127.0.0.1:34553".parse().unwrap())),
+        ));
+        assert_binary_roundtrip(protocol::Message::AddressGossiper(
+            gossiper::Message::Gossip(GossipedAddress::new("[::1]:34553".parse().unwrap())),
+        ));
+        assert_binary_roundtrip(protocol::Message::FinalitySignatureGossiper(
+            gossiper::Message::Gossip(FinalitySignatureId {
+                block_hash: BlockHash::random(&mut rng),
+                public_key: PublicKey::random(&mut rng),
+            }),
+        ));
+    }
+
+    #[test]
+    fn binary_format_roundtrips_get_requests_and_responses() {
+        let mut rng = TestRng::new();
+        let deploy = Deploy::random(&mut rng);
+        let finality_signature =
+            FinalitySignature::random_for_block(BlockHash::random(&mut rng), rng.gen());
+
+        assert_binary_roundtrip(protocol::Message::new_get_request::<Deploy>(deploy.id()).unwrap());
+        assert_binary_roundtrip(protocol::Message::new_get_response(&deploy).unwrap());
+        assert_binary_roundtrip(
+            protocol::Message::new_get_request::<FinalitySignature>(&finality_signature.id())
+                .unwrap(),
+        );
+        assert_binary_roundtrip(protocol::Message::new_get_response(&finality_signature).unwrap());
+        assert_binary_roundtrip(protocol::Message::GetResponse {
+            tag: Deploy::TAG,
+            serialized_item: SharedObject::owned(vec![0; 4 * COMPRESSION_THRESHOLD as usize]),
+        });
+    }
+
+    #[test]
+    fn binary_format_roundtrips_legacy_finality_signatures() {
+        let mut rng = TestRng::new();
+        let finality_signature =
+            FinalitySignature::random_for_block(BlockHash::random(&mut rng), rng.gen());
+
+        assert_binary_roundtrip(protocol::Message::FinalitySignature(Box::new(
+            finality_signature,
+        )));
+    }
+
+    #[test]
+    fn binary_format_rejects_handshakes() {
+        let mut format = WireFormat::new(
+            WireEncoding::Binary,
+            COMPRESSION_THRESHOLD,
+            MAXIMUM_MESSAGE_SIZE,
+        );
+        let handshake: Message<protocol::Message> = Message::Handshake {
+            network_name: "rust-tests-network".to_string(),
+            public_addr: "127.0.0.1:34553".parse().unwrap(),
+            protocol_version: Default::default(),
+            consensus_certificate: None,
+            wire_encodings: vec![WireEncoding::Binary],
+        };
+
+        assert!(Serializer::serialize(Pin::new(&mut format), &Arc::new(handshake)).is_err());
+    }
+
+    #[test]
+    fn negotiates_most_compact_common_encoding() {
+        let both = [WireEncoding::MessagePack, WireEncoding::Binary];
+        let message_pack_only = [WireEncoding::MessagePack];
+
+        assert_eq!(WireEncoding::negotiate(&both, &both), WireEncoding::Binary);
+        assert_eq!(
+            WireEncoding::negotiate(&both, &message_pack_only),
+            WireEncoding::MessagePack
+        );
+        assert_eq!(
+            WireEncoding::negotiate(&message_pack_only, &both),
+            WireEncoding::MessagePack
+        );
+    }
+
+    #[test]
+    fn negotiation_is_symmetric_regardless_of_order() {
+        let ours = [WireEncoding::Binary, WireEncoding::MessagePack];
+        let theirs = [WireEncoding::MessagePack, WireEncoding::Binary];
+
+        assert_eq!(
+            WireEncoding::negotiate(&ours, &theirs),
+            WireEncoding::negotiate(&theirs, &ours)
+        );
+    }
+
+    #[test]
+    fn falls_back_to_message_pack_for_legacy_peers() {
+        // Peers running older versions do not offer any encodings in their handshake.
+        assert_eq!(
+            WireEncoding::negotiate(&[WireEncoding::Binary], &[]),
+            WireEncoding::MessagePack
+        );
+    }
+}
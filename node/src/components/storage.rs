@@ -7,8 +7,10 @@
 //! * storing and loading deploys,
 //! * [temporary until refactored] holding `DeployMetadata` for each deploy,
 //! * holding a read-only copy of the chainspec,
-//! * keeping an index of blocks by height and
-//! * [unimplemented] managing disk usage by pruning blocks and deploys from storage.
+//! * keeping an index of blocks by height,
+//! * keeping an index of deploys by the account which sent them and
+//! * optionally managing disk usage by pruning the bodies, deploys and execution results of blocks
+//!   older than a configured number of eras.
 //!
 //! Any I/O performed by the component is done on the event handling thread, this is on purpose as
 //! the assumption is that caching by LMDB will offset any gains from offloading it onto a separate
@@ -26,6 +28,16 @@
 //! * Storing a deploy or block that already exists (same hash) is fine and will silently be
 //!   accepted.
 //!
+//! ## Pruning
+//!
+//! If `keep_last_eras` is configured, the bodies of all blocks older than the retained eras are
+//! deleted, together with their deploys, execution results and transfers. Blocks are pruned in
+//! batches of at most `PRUNE_BATCH_SIZE` blocks, each in its own transaction: all of them on
+//! start-up, and one batch whenever a block is stored, so that pruning a long history doesn't hold
+//! up the storage component. Block headers, finality signatures and switch blocks are
+//! always kept, as are the hashes of pruned deploys, so that pruned blocks and deploys can be
+//! told apart from unknown ones and deploys are never executed twice.
+//!
 //! ## Indices
 //!
 //! The current implementation keeps only in-memory indices, which are not persisted, based upon the
@@ -45,10 +57,11 @@ mod tests;
 #[cfg(test)]
 use std::collections::BTreeSet;
 use std::{
-    collections::{btree_map::Entry, BTreeMap, HashSet},
+    collections::{btree_map::Entry, BTreeMap, HashMap, HashSet},
     convert::TryFrom,
     fmt::{self, Display, Formatter},
     fs, io, mem,
+    ops::Bound,
     path::{Path, PathBuf},
     sync::Arc,
 };
@@ -84,8 +97,8 @@ use crate::{
     types::{
         error::BlockValidationError, Block, BlockBody, BlockHash, BlockHeader,
         BlockHeaderWithMetadata, BlockSignatures, Deploy, DeployHash, DeployHeader, DeployMetadata,
-        HashingAlgorithmVersion, Item, MerkleBlockBody, MerkleBlockBodyPart, MerkleLinkedListNode,
-        SharedObject, TimeDiff,
+        FinalitySignature, FinalitySignatureId, HashingAlgorithmVersion, Item, MerkleBlockBody,
+        MerkleBlockBodyPart, MerkleLinkedListNode, SharedObject, TimeDiff,
     },
     utils::{display_error, WithDir},
     NodeRng,
@@ -112,7 +125,15 @@ const DEFAULT_MAX_DEPLOY_METADATA_STORE_SIZE: usize = 300 * GIB;
 /// Default max state store size.
 const DEFAULT_MAX_STATE_STORE_SIZE: usize = 10 * GIB;
 /// Maximum number of allowed dbs.
-const MAX_DB_COUNT: u32 = 11;
+const MAX_DB_COUNT: u32 = 13;
+/// Key in the state store under which the height below which blocks have been pruned is stored.
+const PRUNED_BELOW_HEIGHT_KEY: &[u8] = b"storage_pruned_below_height";
+/// Key in the state store under which the height below which the deploys of all stored blocks have
+/// been added to the index of deploys by account is stored.
+const ACCOUNT_DEPLOYS_INDEXED_BELOW_HEIGHT_KEY: &[u8] =
+    b"storage_account_deploys_indexed_below_height";
+/// Maximum number of blocks pruned in a single transaction.
+const PRUNE_BATCH_SIZE: u64 = 100;
 
 /// OS-specific lmdb flags.
 #[cfg(not(target_os = "macos"))]
@@ -347,6 +368,14 @@ pub struct Storage {
     /// The state storage database.
     #[data_size(skip)]
     state_store_db: Database,
+    /// The database of pruned deploys, mapping their hashes to the hashes of the blocks which
+    /// contained them.
+    #[data_size(skip)]
+    pruned_deploys_db: Database,
+    /// The index of deploys by sending account, mapping the account's public key, the height of the
+    /// block containing a deploy and the deploy hash to the block hash.
+    #[data_size(skip)]
+    account_deploys_db: Database,
     /// A map of block height to block ID.
     block_height_index: BTreeMap<u64, BlockHash>,
     /// A map of era ID to switch block ID.
@@ -357,6 +386,10 @@ pub struct Storage {
     enable_mem_deduplication: bool,
     /// Pool of loaded items.
     deploy_cache: BlobCache<<Deploy as Item>::Id>,
+    /// The number of eras before the current one whose blocks are kept, if pruning is enabled.
+    keep_last_eras: Option<u64>,
+    /// The height below which the bodies of all blocks other than switch blocks have been pruned.
+    pruned_below_height: u64,
 }
 
 impl<REv> Component<REv> for Storage
@@ -452,6 +485,8 @@ impl Storage {
         let deploy_hashes_db = env.create_db(Some("deploy_hashes"), DatabaseFlags::empty())?;
         let transfer_hashes_db = env.create_db(Some("transfer_hashes"), DatabaseFlags::empty())?;
         let proposer_db = env.create_db(Some("proposers"), DatabaseFlags::empty())?;
+        let pruned_deploys_db = env.create_db(Some("pruned_deploys"), DatabaseFlags::empty())?;
+        let account_deploys_db = env.create_db(Some("account_deploys"), DatabaseFlags::empty())?;
 
         // We now need to restore the block-height index. Log messages allow timing here.
         info!("reindexing block store");
@@ -459,6 +494,15 @@ impl Storage {
         let mut switch_block_era_id_index = BTreeMap::new();
         let mut deploy_hash_index = BTreeMap::new();
         let mut block_txn = env.begin_rw_txn()?;
+        let pruned_below_height: u64 = block_txn
+            .get_value(state_store_db, &PRUNED_BELOW_HEIGHT_KEY)?
+            .unwrap_or_default();
+        let account_deploys_indexed_below_height: u64 = block_txn
+            .get_value(state_store_db, &ACCOUNT_DEPLOYS_INDEXED_BELOW_HEIGHT_KEY)?
+            .unwrap_or_default();
+        // The deploys of blocks which are not yet in the index of deploys by account, indexed once
+        // the cursor is released.
+        let mut blocks_to_index_by_account = vec![];
         let mut cursor = block_txn.open_rw_cursor(block_header_db)?;
 
         let mut deleted_block_hashes = HashSet::new();
@@ -501,14 +545,10 @@ impl Storage {
             )?;
 
             let mut body_txn = env.begin_ro_txn()?;
-            let block_body: BlockBody = match block_header.hashing_algorithm_version() {
-                HashingAlgorithmVersion::V1 => body_txn
-                    .get_value(block_body_v1_db, block_header.body_hash())?
-                    .ok_or_else(|| {
-                        Error::NonExistentBlockBodyReferredToByHeader(Box::new(
-                            block_header.clone(),
-                        ))
-                    })?,
+            let maybe_body: Option<BlockBody> = match block_header.hashing_algorithm_version() {
+                HashingAlgorithmVersion::V1 => {
+                    body_txn.get_value(block_body_v1_db, block_header.body_hash())?
+                }
                 HashingAlgorithmVersion::V2 => get_single_block_body_v2(
                     &mut body_txn,
                     block_body_v2_db,
@@ -516,10 +556,17 @@ impl Storage {
                     transfer_hashes_db,
                     proposer_db,
                     block_header.body_hash(),
-                )?
-                .ok_or_else(|| {
-                    Error::NonExistentBlockBodyReferredToByHeader(Box::new(block_header.clone()))
-                })?,
+                )?,
+            };
+            let block_body = match maybe_body {
+                Some(block_body) => block_body,
+                // The deploys of pruned blocks are indexed from the pruned deploys database.
+                None if is_pruned(&block_header, pruned_below_height) => continue,
+                None => {
+                    return Err(Error::NonExistentBlockBodyReferredToByHeader(Box::new(
+                        block_header,
+                    )))
+                }
             };
 
             if should_check_integrity {
@@ -527,9 +574,50 @@ impl Storage {
             }
 
             insert_to_deploy_index(&mut deploy_hash_index, block_header.hash(), &block_body)?;
+            if block_header.height() >= account_deploys_indexed_below_height {
+                blocks_to_index_by_account.push((
+                    block_header.height(),
+                    block_header.hash(),
+                    block_body,
+                ));
+            }
         }
-        info!("block store reindexing complete");
         drop(cursor);
+        if !blocks_to_index_by_account.is_empty() {
+            info!(
+                block_count = blocks_to_index_by_account.len(),
+                "indexing deploys by account"
+            );
+        }
+        for (block_height, block_hash, block_body) in &blocks_to_index_by_account {
+            insert_to_account_deploys_index(
+                &mut block_txn,
+                deploy_db,
+                account_deploys_db,
+                *block_height,
+                block_hash,
+                block_body,
+            )?;
+        }
+        if let Some(highest_height) = block_height_index.keys().next_back() {
+            if *highest_height >= account_deploys_indexed_below_height {
+                block_txn.put_value(
+                    state_store_db,
+                    &ACCOUNT_DEPLOYS_INDEXED_BELOW_HEIGHT_KEY,
+                    &(highest_height + 1),
+                    true,
+                )?;
+            }
+        }
+        for (raw_key, raw_val) in block_txn.open_ro_cursor(pruned_deploys_db)?.iter() {
+            let deploy_hash = DeployHash::new(
+                Digest::try_from(raw_key)
+                    .map_err(|err| LmdbExtError::DataCorrupted(Box::new(err)))?,
+            );
+            let block_hash: BlockHash = lmdb_ext::deserialize(raw_val)?;
+            deploy_hash_index.insert(deploy_hash, block_hash);
+        }
+        info!("block store reindexing complete");
         block_txn.commit()?;
 
         let deleted_block_hashes_raw = deleted_block_hashes.iter().map(BlockHash::as_ref).collect();
@@ -561,7 +649,7 @@ impl Storage {
         )?;
         initialize_deploy_metadata_db(&env, &deploy_metadata_db, &deleted_block_hashes)?;
 
-        Ok(Storage {
+        let mut storage = Storage {
             root,
             env,
             block_header_db,
@@ -575,12 +663,18 @@ impl Storage {
             deploy_metadata_db,
             transfer_db,
             state_store_db,
+            pruned_deploys_db,
+            account_deploys_db,
             block_height_index,
             switch_block_era_id_index,
             deploy_hash_index,
             enable_mem_deduplication: config.enable_mem_deduplication,
             deploy_cache: BlobCache::new(config.mem_pool_prune_interval),
-        })
+            keep_last_eras: config.keep_last_eras,
+            pruned_below_height,
+        };
+        storage.prune_blocks()?;
+        Ok(storage)
     }
 
     /// Handles a state store request.
@@ -718,58 +812,7 @@ impl Storage {
                 execution_results,
                 responder,
             } => {
-                let mut txn = self.env.begin_rw_txn()?;
-
-                let mut transfers: Vec<Transfer> = vec![];
-
-                for (deploy_hash, execution_result) in execution_results {
-                    let mut metadata = self
-                        .get_deploy_metadata(&mut txn, &deploy_hash)?
-                        .unwrap_or_default();
-
-                    // If we have a previous execution result, we can continue if it is the same.
-                    if let Some(prev) = metadata.execution_results.get(&block_hash) {
-                        if prev == &execution_result {
-                            continue;
-                        } else {
-                            debug!(%deploy_hash, %block_hash, "different execution result");
-                        }
-                    }
-
-                    if let ExecutionResult::Success { effect, .. } = execution_result.clone() {
-                        for transform_entry in effect.transforms {
-                            if let Transform::WriteTransfer(transfer) = transform_entry.transform {
-                                transfers.push(transfer);
-                            }
-                        }
-                    }
-
-                    // TODO: this is currently done like this because rpc get_deploy returns the
-                    // data, but the organization of deploy, block_hash, and
-                    // execution_result is incorrectly represented. it should be
-                    // inverted; for a given block_hash 0n deploys and each deploy has exactly 1
-                    // result (aka deploy_metadata in this context).
-
-                    // Update metadata and write back to db.
-                    metadata
-                        .execution_results
-                        .insert(*block_hash, execution_result);
-                    let was_written =
-                        txn.put_value(self.deploy_metadata_db, &deploy_hash, &metadata, true)?;
-                    if !was_written {
-                        error!(?block_hash, ?deploy_hash, "failed to write deploy metadata");
-                        debug_assert!(was_written);
-                    }
-                }
-
-                let was_written =
-                    txn.put_value(self.transfer_db, &*block_hash, &transfers, true)?;
-                if !was_written {
-                    error!(?block_hash, "failed to write transfers");
-                    debug_assert!(was_written);
-                }
-
-                txn.commit()?;
+                self.write_execution_results(&block_hash, execution_results)?;
                 responder.respond(()).ignore()
             }
             StorageRequest::GetDeployAndMetadata {
@@ -892,13 +935,67 @@ impl Storage {
             StorageRequest::GetFinalizedDeploys { ttl, responder } => {
                 responder.respond(self.get_finalized_deploys(ttl)?).ignore()
             }
+            StorageRequest::GetAccountDeploys {
+                public_key,
+                start,
+                max_count,
+                responder,
+            } => responder
+                .respond(self.get_account_deploys(&public_key, start, max_count)?)
+                .ignore(),
+            StorageRequest::IsBlockPruned {
+                block_hash,
+                responder,
+            } => {
+                let maybe_block_header =
+                    self.get_single_block_header(&mut self.env.begin_ro_txn()?, &block_hash)?;
+                responder
+                    .respond(maybe_block_header.map_or(false, |block_header| {
+                        is_pruned(&block_header, self.pruned_below_height)
+                    }))
+                    .ignore()
+            }
+            StorageRequest::IsBlockAtHeightPruned { height, responder } => {
+                let maybe_block_header =
+                    self.get_block_header_by_height(&mut self.env.begin_ro_txn()?, height)?;
+                responder
+                    .respond(maybe_block_header.map_or(false, |block_header| {
+                        is_pruned(&block_header, self.pruned_below_height)
+                    }))
+                    .ignore()
+            }
+            StorageRequest::IsDeployPruned {
+                deploy_hash,
+                responder,
+            } => {
+                let maybe_block_hash: Option<BlockHash> = self
+                    .env
+                    .begin_ro_txn()?
+                    .get_value(self.pruned_deploys_db, &deploy_hash)?;
+                responder.respond(maybe_block_hash.is_some()).ignore()
+            }
         })
     }
 
     /// Put a single deploy into storage.
-    pub fn put_deploy(&self, deploy: &Deploy) -> Result<bool, Error> {
+    pub fn put_deploy(&mut self, deploy: &Deploy) -> Result<bool, Error> {
         let mut txn = self.env.begin_rw_txn()?;
         let outcome = txn.put_value(self.deploy_db, deploy.id(), &deploy, false)?;
+
+        // The block containing the deploy may have been stored before the deploy itself, in which
+        // case the deploy could not be added to the account index at that time.
+        let maybe_block_header = match self.deploy_hash_index.get(deploy.id()) {
+            Some(block_hash) => self.get_single_block_header(&mut txn, block_hash)?,
+            None => None,
+        };
+        if let Some(block_header) = maybe_block_header {
+            let key = account_deploys_key(
+                deploy.header().account(),
+                block_header.height(),
+                deploy.id(),
+            )?;
+            txn.put_value(self.account_deploys_db, &key, &block_header.hash(), true)?;
+        }
         txn.commit()?;
         Ok(outcome)
     }
@@ -938,6 +1035,77 @@ impl Storage {
         Ok(txn.get_value(self.deploy_db, &deploy_hash)?)
     }
 
+    /// Directly returns a deploy's metadata, including its execution results, from internal store.
+    pub fn read_deploy_metadata_by_hash(
+        &self,
+        deploy_hash: &DeployHash,
+    ) -> Result<Option<DeployMetadata>, Error> {
+        self.get_deploy_metadata(&mut self.env.begin_ro_txn()?, deploy_hash)
+    }
+
+    /// Writes the execution results of the deploys of a single block to storage, along with the
+    /// transfers they performed.
+    ///
+    /// Storing an identical execution result for a deploy and block twice is a no-op.
+    pub fn write_execution_results(
+        &mut self,
+        block_hash: &BlockHash,
+        execution_results: HashMap<DeployHash, ExecutionResult>,
+    ) -> Result<(), Error> {
+        let mut txn = self.env.begin_rw_txn()?;
+
+        let mut transfers: Vec<Transfer> = vec![];
+
+        for (deploy_hash, execution_result) in execution_results {
+            let mut metadata = self
+                .get_deploy_metadata(&mut txn, &deploy_hash)?
+                .unwrap_or_default();
+
+            // If we have a previous execution result, we can continue if it is the same.
+            if let Some(prev) = metadata.execution_results.get(block_hash) {
+                if prev == &execution_result {
+                    continue;
+                } else {
+                    debug!(%deploy_hash, %block_hash, "different execution result");
+                }
+            }
+
+            if let ExecutionResult::Success { effect, .. } = execution_result.clone() {
+                for transform_entry in effect.transforms {
+                    if let Transform::WriteTransfer(transfer) = transform_entry.transform {
+                        transfers.push(transfer);
+                    }
+                }
+            }
+
+            // TODO: this is currently done like this because rpc get_deploy returns the
+            // data, but the organization of deploy, block_hash, and
+            // execution_result is incorrectly represented. it should be
+            // inverted; for a given block_hash 0n deploys and each deploy has exactly 1
+            // result (aka deploy_metadata in this context).
+
+            // Update metadata and write back to db.
+            metadata
+                .execution_results
+                .insert(*block_hash, execution_result);
+            let was_written =
+                txn.put_value(self.deploy_metadata_db, &deploy_hash, &metadata, true)?;
+            if !was_written {
+                error!(?block_hash, ?deploy_hash, "failed to write deploy metadata");
+                debug_assert!(was_written);
+            }
+        }
+
+        let was_written = txn.put_value(self.transfer_db, block_hash, &transfers, true)?;
+        if !was_written {
+            error!(?block_hash, "failed to write transfers");
+            debug_assert!(was_written);
+        }
+
+        txn.commit()?;
+        Ok(())
+    }
+
     /// Writes a block to storage, updating indices as necessary
     /// Returns `Ok(true)` if the block has been successfully written, `Ok(false)` if a part of it
     /// couldn't be written because it already existed, and `Err(_)` if there was an error.
@@ -979,10 +1147,163 @@ impl Storage {
             block.header().hash(),
             block.body(),
         )?;
+        insert_to_account_deploys_index(
+            &mut txn,
+            self.deploy_db,
+            self.account_deploys_db,
+            block.height(),
+            block.hash(),
+            block.body(),
+        )?;
+        txn.commit()?;
+        self.prune_block_batch()?;
+        Ok(true)
+    }
+
+    /// Prunes all blocks other than switch blocks which precede the retained eras, if pruning is
+    /// enabled, in batches of at most `PRUNE_BATCH_SIZE` blocks.
+    fn prune_blocks(&mut self) -> Result<(), Error> {
+        while self.prune_block_batch()? {}
+        Ok(())
+    }
+
+    /// Prunes the bodies, deploys, execution results and transfers of the next batch of at most
+    /// `PRUNE_BATCH_SIZE` blocks other than switch blocks which precede the retained eras, if
+    /// pruning is enabled, in a single transaction.
+    ///
+    /// The blocks of the current era, i.e. the one following the last switch block, and of the
+    /// `keep_last_eras` eras before it are retained. Returns whether a batch was pruned.
+    fn prune_block_batch(&mut self) -> Result<bool, Error> {
+        let keep_last_eras = match self.keep_last_eras {
+            Some(keep_last_eras) => keep_last_eras,
+            None => return Ok(false),
+        };
+        let current_era_id = match self.switch_block_era_id_index.keys().next_back() {
+            Some(last_switch_block_era_id) => last_switch_block_era_id.successor(),
+            None => return Ok(false),
+        };
+        let oldest_kept_era_id = current_era_id.saturating_sub(keep_last_eras);
+        let last_pruned_era_id = match oldest_kept_era_id.checked_sub(1) {
+            Some(last_pruned_era_id) => last_pruned_era_id,
+            None => return Ok(false),
+        };
+
+        let mut txn = self.env.begin_rw_txn()?;
+        // All blocks up to and including the last pruned era's switch block are pruned.
+        let prune_below_height =
+            match self.get_switch_block_header_by_era_id(&mut txn, last_pruned_era_id)? {
+                Some(switch_block_header) => switch_block_header.height().saturating_add(1),
+                None => return Ok(false),
+            };
+        if prune_below_height <= self.pruned_below_height {
+            return Ok(false);
+        }
+        let batch_end_height =
+            prune_below_height.min(self.pruned_below_height.saturating_add(PRUNE_BATCH_SIZE));
+
+        let block_hashes: Vec<BlockHash> = self
+            .block_height_index
+            .range(self.pruned_below_height..batch_end_height)
+            .map(|(_, block_hash)| *block_hash)
+            .collect();
+        let mut pruned_block_count = 0_usize;
+        let mut pruned_deploy_count = 0_usize;
+        for block_hash in block_hashes {
+            let block_header = match self.get_single_block_header(&mut txn, &block_hash)? {
+                Some(block_header) if !block_header.is_switch_block() => block_header,
+                _ => continue,
+            };
+            let block_body = match self.get_body_for_block_header(&mut txn, &block_header)? {
+                Some(block_body) => block_body,
+                None => continue,
+            };
+            self.delete_unshared_block_body_parts(&mut txn, &block_header, &block_body)?;
+            txn.delete_value(self.transfer_db, &block_hash)?;
+            for deploy_hash in block_body
+                .deploy_hashes()
+                .iter()
+                .chain(block_body.transfer_hashes())
+            {
+                let maybe_deploy: Option<Deploy> = txn.get_value(self.deploy_db, deploy_hash)?;
+                if let Some(deploy) = maybe_deploy {
+                    let key = account_deploys_key(
+                        deploy.header().account(),
+                        block_header.height(),
+                        deploy_hash,
+                    )?;
+                    txn.delete_value(self.account_deploys_db, &key)?;
+                    txn.delete_value(self.deploy_db, deploy_hash)?;
+                }
+                txn.delete_value(self.deploy_metadata_db, deploy_hash)?;
+                txn.put_value(self.pruned_deploys_db, deploy_hash, &block_hash, true)?;
+                pruned_deploy_count += 1;
+            }
+            pruned_block_count += 1;
+        }
+        txn.put_value(
+            self.state_store_db,
+            &PRUNED_BELOW_HEIGHT_KEY,
+            &batch_end_height,
+            true,
+        )?;
         txn.commit()?;
+        self.pruned_below_height = batch_end_height;
+
+        info!(
+            pruned_block_count,
+            pruned_deploy_count,
+            pruned_below_height = batch_end_height,
+            prune_below_height,
+            "pruned blocks from storage"
+        );
         Ok(true)
     }
 
+    /// Deletes the parts of a block body which can't be shared with the body of any other block.
+    ///
+    /// Since every deploy is contained in only one block, only bodies containing deploys or
+    /// transfers are unique. Proposer parts, as well as bodies without any deploys and transfers,
+    /// can be referred to by several block headers, so they are kept.
+    fn delete_unshared_block_body_parts(
+        &self,
+        txn: &mut RwTransaction,
+        block_header: &BlockHeader,
+        block_body: &BlockBody,
+    ) -> Result<(), LmdbExtError> {
+        let has_deploys = !block_body.deploy_hashes().is_empty();
+        let has_transfers = !block_body.transfer_hashes().is_empty();
+        if !has_deploys && !has_transfers {
+            return Ok(());
+        }
+        match block_header.hashing_algorithm_version() {
+            HashingAlgorithmVersion::V1 => {
+                txn.delete_value(self.block_body_v1_db, block_header.body_hash())?;
+            }
+            HashingAlgorithmVersion::V2 => {
+                let MerkleBlockBody {
+                    deploy_hashes,
+                    transfer_hashes,
+                    ..
+                } = block_body.merklize();
+                txn.delete_value(
+                    self.block_body_v2_db,
+                    deploy_hashes.merkle_linked_list_node_hash(),
+                )?;
+                if has_deploys {
+                    txn.delete_value(self.deploy_hashes_db, deploy_hashes.value_hash())?;
+                }
+                if has_transfers {
+                    txn.delete_value(
+                        self.block_body_v2_db,
+                        transfer_hashes.merkle_linked_list_node_hash(),
+                    )?;
+                    txn.delete_value(self.transfer_hashes_db, transfer_hashes.value_hash())?;
+                }
+            }
+        }
+        Ok(())
+    }
+
     /// Retrieves a block header to handle a network request.
     pub(crate) fn read_block_header_and_finality_signatures_by_height(
         &self,
@@ -1056,6 +1377,51 @@ impl Storage {
             .transpose()
     }
 
+    /// Returns up to `max_count` deploys sent by the given account, in ascending order of the
+    /// height of the blocks containing them, starting from the given position.
+    ///
+    /// Each entry consists of the block height, the block hash and the deploy hash.
+    fn get_account_deploys(
+        &self,
+        public_key: &PublicKey,
+        start: Bound<(u64, DeployHash)>,
+        max_count: usize,
+    ) -> Result<Vec<(u64, BlockHash, DeployHash)>, Error> {
+        let prefix = lmdb_ext::serialize_bytesrepr(public_key)?;
+        let start_key = match start {
+            Bound::Included((height, deploy_hash)) | Bound::Excluded((height, deploy_hash)) => {
+                account_deploys_key(public_key, height, &deploy_hash)?
+            }
+            Bound::Unbounded => prefix.clone(),
+        };
+
+        let txn = self.env.begin_ro_txn()?;
+        let mut cursor = txn.open_ro_cursor(self.account_deploys_db)?;
+        // Note: `iter_from` has an undocumented panic if there is no key at or after the given one.
+        match cursor.get(Some(&start_key), None, lmdb_sys::MDB_SET_RANGE) {
+            Ok(_) => (),
+            Err(lmdb::Error::NotFound) => return Ok(vec![]),
+            Err(error) => return Err(error.into()),
+        }
+        let mut account_deploys = vec![];
+        for (key, value) in cursor.iter_from(&start_key) {
+            if account_deploys.len() >= max_count || !key.starts_with(&prefix) {
+                break;
+            }
+            if matches!(start, Bound::Excluded(_)) && key == start_key.as_slice() {
+                continue;
+            }
+            let (height, deploy_hash) = parse_account_deploys_key_suffix(&key[prefix.len()..])?;
+            let block_hash: BlockHash = lmdb_ext::deserialize(value)?;
+            // Entries of blocks removed by a hard reset are not deleted from the index, and are
+            // skipped unless the block was stored again.
+            if self.block_height_index.get(&height) == Some(&block_hash) {
+                account_deploys.push((height, block_hash, deploy_hash));
+            }
+        }
+        Ok(account_deploys)
+    }
+
     /// Retrieves the highest block from the storage, if one exists.
     /// May return an LMDB error.
     fn get_highest_block<Tx: Transaction>(&self, txn: &mut Tx) -> Result<Option<Block>, Error> {
@@ -1134,14 +1500,11 @@ impl Storage {
 
     /// Retrieves the state root hashes from storage to check the integrity of the trie store.
     pub(crate) fn read_state_root_hashes_for_trie_check(&self) -> Result<Vec<Digest>, Error> {
-        let mut blake_hashes: Vec<Digest> = Vec::new();
-        let txn = self.env.begin_ro_txn()?;
-        let mut cursor = txn.open_ro_cursor(self.block_header_db)?;
-        for (_, raw_val) in cursor.iter() {
-            let header: BlockHeader = lmdb_ext::deserialize(raw_val)?;
-            let blake_hash = *header.state_root_hash();
-            blake_hashes.push(blake_hash);
-        }
+        let mut blake_hashes: Vec<Digest> = self
+            .read_heights_and_state_root_hashes()?
+            .into_iter()
+            .map(|(_, blake_hash)| blake_hash)
+            .collect();
 
         blake_hashes.sort();
         blake_hashes.dedup();
@@ -1149,6 +1512,40 @@ impl Storage {
         Ok(blake_hashes)
     }
 
+    /// Retrieves the state root hashes of the `count` highest blocks from storage, i.e. those whose
+    /// tries are retained when pruning the trie store.
+    ///
+    /// The state root hashes are ordered from the highest block to the lowest one.
+    pub(crate) fn read_state_root_hashes_for_trie_pruning(
+        &self,
+        count: usize,
+    ) -> Result<Vec<Digest>, Error> {
+        let mut heights_and_blake_hashes = self.read_heights_and_state_root_hashes()?;
+        heights_and_blake_hashes
+            .sort_unstable_by(|(height1, _), (height2, _)| height2.cmp(height1));
+
+        let mut blake_hashes: Vec<Digest> = heights_and_blake_hashes
+            .into_iter()
+            .take(count)
+            .map(|(_, blake_hash)| blake_hash)
+            .collect();
+        blake_hashes.dedup();
+
+        Ok(blake_hashes)
+    }
+
+    /// Retrieves the heights and state root hashes of all stored block headers.
+    fn read_heights_and_state_root_hashes(&self) -> Result<Vec<(u64, Digest)>, Error> {
+        let mut heights_and_blake_hashes = Vec::new();
+        let txn = self.env.begin_ro_txn()?;
+        let mut cursor = txn.open_ro_cursor(self.block_header_db)?;
+        for (_, raw_val) in cursor.iter() {
+            let header: BlockHeader = lmdb_ext::deserialize(raw_val)?;
+            heights_and_blake_hashes.push((header.height(), *header.state_root_hash()));
+        }
+        Ok(heights_and_blake_hashes)
+    }
+
     /// Retrieves a single block header in a separate transaction from storage.
     fn get_single_block_header<Tx: Transaction>(
         &self,
@@ -1264,6 +1661,20 @@ impl Storage {
         Ok(maybe_block_header)
     }
 
+    /// Retrieves a single finality signature to handle a network request.
+    pub(crate) fn read_finality_signature(
+        &self,
+        finality_signature_id: &FinalitySignatureId,
+    ) -> Result<Option<FinalitySignature>, Error> {
+        let mut txn = self.env.begin_ro_txn()?;
+        let maybe_block_signatures =
+            self.get_finality_signatures(&mut txn, &finality_signature_id.block_hash)?;
+        drop(txn);
+        Ok(maybe_block_signatures.and_then(|block_signatures| {
+            block_signatures.finality_signature(&finality_signature_id.public_key)
+        }))
+    }
+
     /// Retrieves a single block in a separate transaction from storage.
     fn get_single_block<Tx: Transaction>(
         &self,
@@ -1278,10 +1689,12 @@ impl Storage {
         let block_body = match maybe_block_body {
             Some(block_body) => block_body,
             None => {
-                warn!(
-                    ?block_header,
-                    "retrieved block header but block body is missing from database"
-                );
+                if !is_pruned(&block_header, self.pruned_below_height) {
+                    warn!(
+                        ?block_header,
+                        "retrieved block header but block body is missing from database"
+                    );
+                }
                 return Ok(None);
             }
         };
@@ -1380,6 +1793,14 @@ impl Storage {
     }
 }
 
+/// Returns whether the block with the given header has been pruned, given the height below which
+/// blocks have been pruned.
+///
+/// Switch blocks are never pruned.
+fn is_pruned(block_header: &BlockHeader, pruned_below_height: u64) -> bool {
+    !block_header.is_switch_block() && block_header.height() < pruned_below_height
+}
+
 /// Inserts the relevant entries to the two indices.
 ///
 /// If a duplicate entry is encountered, neither index is updated and an error is returned.
@@ -1456,6 +1877,66 @@ fn insert_to_deploy_index(
     Ok(())
 }
 
+/// Inserts the deploys of a block into the index of deploys by sending account.
+///
+/// Deploys which are not present in the deploy database yet are skipped; they are indexed once they
+/// get stored.
+fn insert_to_account_deploys_index(
+    txn: &mut RwTransaction,
+    deploy_db: Database,
+    account_deploys_db: Database,
+    block_height: u64,
+    block_hash: &BlockHash,
+    block_body: &BlockBody,
+) -> Result<(), LmdbExtError> {
+    for deploy_hash in block_body
+        .deploy_hashes()
+        .iter()
+        .chain(block_body.transfer_hashes().iter())
+    {
+        let maybe_deploy: Option<Deploy> = txn.get_value(deploy_db, deploy_hash)?;
+        if let Some(deploy) = maybe_deploy {
+            let key = account_deploys_key(deploy.header().account(), block_height, deploy_hash)?;
+            txn.put_value(account_deploys_db, &key, block_hash, true)?;
+        }
+    }
+    Ok(())
+}
+
+/// Returns the key of a deploy in the index of deploys by sending account.
+///
+/// Keys consist of the serialized public key, followed by the big-endian block height and the
+/// deploy hash, so that the deploys of an account are ordered by block height.
+fn account_deploys_key(
+    public_key: &PublicKey,
+    block_height: u64,
+    deploy_hash: &DeployHash,
+) -> Result<Vec<u8>, LmdbExtError> {
+    let mut key = lmdb_ext::serialize_bytesrepr(public_key)?;
+    key.extend_from_slice(&block_height.to_be_bytes());
+    key.extend_from_slice(deploy_hash.as_ref());
+    Ok(key)
+}
+
+/// Parses the block height and deploy hash following the public key in a key of the index of
+/// deploys by sending account.
+fn parse_account_deploys_key_suffix(suffix: &[u8]) -> Result<(u64, DeployHash), LmdbExtError> {
+    const HEIGHT_LENGTH: usize = mem::size_of::<u64>();
+    if suffix.len() != HEIGHT_LENGTH + Digest::LENGTH {
+        return Err(LmdbExtError::DataCorrupted(
+            "invalid account deploys index key".into(),
+        ));
+    }
+    let (height_bytes, deploy_hash_bytes) = suffix.split_at(HEIGHT_LENGTH);
+    let mut height = [0; HEIGHT_LENGTH];
+    height.copy_from_slice(height_bytes);
+    let deploy_hash = DeployHash::new(
+        Digest::try_from(deploy_hash_bytes)
+            .map_err(|err| LmdbExtError::DataCorrupted(Box::new(err)))?,
+    );
+    Ok((u64::from_be_bytes(height), deploy_hash))
+}
+
 fn should_move_storage_files_to_network_subdir(
     root: &Path,
     file_names: &[&str],
@@ -1541,6 +2022,11 @@ pub struct Config {
     enable_mem_deduplication: bool,
     /// How many loads before memory duplication checks for dead references.
     mem_pool_prune_interval: u16,
+    /// The number of eras before the current one whose blocks are kept in full.
+    ///
+    /// If set, the bodies, deploys, execution results and transfers of older blocks are pruned,
+    /// except for switch blocks. If unset, all blocks are kept.
+    keep_last_eras: Option<u64>,
 }
 
 impl Default for Config {
@@ -1554,6 +2040,7 @@ impl Default for Config {
             max_state_store_size: DEFAULT_MAX_STATE_STORE_SIZE,
             enable_mem_deduplication: false,
             mem_pool_prune_interval: 1024,
+            keep_last_eras: None,
         }
     }
 }
@@ -122,6 +122,12 @@ pub(super) trait WriteTransactionExt {
         value: &V,
         overwrite: bool,
     ) -> Result<bool, LmdbExtError>;
+
+    /// Helper function to delete a value from a database.
+    ///
+    /// Returns `true` if the value has actually been deleted, `false` if the key didn't exist.
+    fn delete_value<K: AsRef<[u8]>>(&mut self, db: Database, key: &K)
+        -> Result<bool, LmdbExtError>;
 }
 
 impl<T> TransactionExt for T
@@ -203,6 +209,18 @@ impl WriteTransactionExt for RwTransaction<'_> {
             Err(err) => Err(err.into()),
         }
     }
+
+    fn delete_value<K: AsRef<[u8]>>(
+        &mut self,
+        db: Database,
+        key: &K,
+    ) -> Result<bool, LmdbExtError> {
+        match self.del(db, key, None) {
+            Ok(()) => Ok(true),
+            Err(lmdb::Error::NotFound) => Ok(false),
+            Err(err) => Err(err.into()),
+        }
+    }
 }
 
 /// Deserializes from a buffer.
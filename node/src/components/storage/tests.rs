@@ -5,6 +5,7 @@ use std::{
     collections::{HashMap, HashSet},
     convert::TryFrom,
     fs::{self, File},
+    ops::Bound,
 };
 
 use lmdb::{Cursor, Transaction};
@@ -13,12 +14,12 @@ use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use smallvec::smallvec;
 
 use casper_hashing::Digest;
-use casper_types::{EraId, ExecutionResult, ProtocolVersion, PublicKey, SecretKey};
+use casper_types::{EraId, ExecutionResult, ProtocolVersion, PublicKey, SecretKey, Transfer};
 
 use super::{
     construct_block_body_to_block_header_reverse_lookup, garbage_collect_block_body_v2_db,
     move_storage_files_to_network_subdir, should_move_storage_files_to_network_subdir, Config,
-    Storage,
+    Storage, PRUNE_BATCH_SIZE,
 };
 use crate::{
     components::storage::lmdb_ext::{TransactionExt, WriteTransactionExt},
@@ -29,8 +30,9 @@ use crate::{
     },
     testing::{ComponentHarness, TestRng, UnitTestEvent},
     types::{
-        Block, BlockHash, BlockHeader, BlockSignatures, Deploy, DeployHash, DeployMetadata,
-        FinalitySignature, HashingAlgorithmVersion,
+        Block, BlockHash, BlockHeader, BlockPayload, BlockSignatures, Deploy, DeployHash,
+        DeployMetadata, FinalitySignature, FinalitySignatureId, FinalizedBlock,
+        HashingAlgorithmVersion, Item, TimeDiff, Timestamp,
     },
     utils::WithDir,
 };
@@ -47,6 +49,7 @@ fn new_config(harness: &ComponentHarness<UnitTestEvent>) -> Config {
         max_state_store_size: 50 * MIB,
         enable_mem_deduplication: false,
         mem_pool_prune_interval: 1024,
+        keep_last_eras: None,
     }
 }
 
@@ -114,6 +117,32 @@ fn storage_fixture_with_hard_reset_and_protocol_version(
     .expect("could not create storage component fixture")
 }
 
+/// Storage component test fixture.
+///
+/// Creates a storage component in a temporary directory, pruning blocks older than the given
+/// number of eras.
+///
+/// # Panics
+///
+/// Panics if setting up the storage fixture fails.
+fn storage_fixture_with_keep_last_eras(
+    harness: &ComponentHarness<UnitTestEvent>,
+    keep_last_eras: u64,
+) -> Storage {
+    let cfg = Config {
+        keep_last_eras: Some(keep_last_eras),
+        ..new_config(harness)
+    };
+    Storage::new(
+        &WithDir::new(harness.tmp.path(), cfg),
+        None,
+        ProtocolVersion::from_parts(1, 0, 0),
+        false,
+        "test",
+    )
+    .expect("could not create storage component fixture")
+}
+
 /// Creates a random block with a specific block height.
 fn random_block_at_height(rng: &mut TestRng, height: u64) -> Box<Block> {
     let mut block = Box::new(Block::random(rng));
@@ -121,6 +150,59 @@ fn random_block_at_height(rng: &mut TestRng, height: u64) -> Box<Block> {
     block
 }
 
+/// Creates a random non-switch block at a specific height, containing the given deploys.
+fn random_block_with_deploys(
+    rng: &mut TestRng,
+    height: u64,
+    deploy_hashes: Vec<DeployHash>,
+) -> Box<Block> {
+    random_block_in_era_with_deploys(rng, EraId::from(1), height, deploy_hashes)
+}
+
+/// Creates a random non-switch block in a specific era and at a specific height, containing the
+/// given deploys.
+fn random_block_in_era_with_deploys(
+    rng: &mut TestRng,
+    era_id: EraId,
+    height: u64,
+    deploy_hashes: Vec<DeployHash>,
+) -> Box<Block> {
+    let block_payload = BlockPayload::new(deploy_hashes, vec![], vec![], rng.gen());
+    let finalized_block = FinalizedBlock::new(
+        block_payload,
+        None,
+        Timestamp::now(),
+        era_id,
+        height,
+        PublicKey::from(&SecretKey::random(rng)),
+    );
+    let block = Block::new(
+        BlockHash::new(rng.gen::<[u8; Digest::LENGTH]>().into()),
+        rng.gen::<[u8; Digest::LENGTH]>().into(),
+        rng.gen::<[u8; Digest::LENGTH]>().into(),
+        finalized_block,
+        None,
+        ProtocolVersion::V1_0_0,
+    )
+    .expect("could not create block");
+    Box::new(block)
+}
+
+/// Creates a random deploy sent by the account with the given secret key.
+fn random_deploy_from_account(rng: &mut TestRng, secret_key: &SecretKey) -> Deploy {
+    Deploy::new(
+        Timestamp::now(),
+        TimeDiff::from(60_000),
+        1,
+        vec![],
+        String::from("casper-example"),
+        rng.gen(),
+        rng.gen(),
+        secret_key,
+        None,
+    )
+}
+
 /// Creates 3 random signatures for the given block.
 fn random_signatures(rng: &mut TestRng, block: &Block) -> BlockSignatures {
     let block_hash = *block.hash();
@@ -233,6 +315,87 @@ fn get_deploy_and_metadata(
     response
 }
 
+/// Requests the transfers of a block from a storage component.
+fn get_block_transfers(
+    harness: &mut ComponentHarness<UnitTestEvent>,
+    storage: &mut Storage,
+    block_hash: BlockHash,
+) -> Option<Vec<Transfer>> {
+    let response = harness.send_request(storage, move |responder| {
+        StorageRequest::GetBlockTransfers {
+            block_hash,
+            responder,
+        }
+        .into()
+    });
+    assert!(harness.is_idle());
+    response
+}
+
+/// Requests the header of the block containing a deploy from a storage component.
+fn get_block_header_for_deploy(
+    harness: &mut ComponentHarness<UnitTestEvent>,
+    storage: &mut Storage,
+    deploy_hash: DeployHash,
+) -> Option<BlockHeader> {
+    let response = harness.send_request(storage, move |responder| {
+        StorageRequest::GetBlockHeaderForDeploy {
+            deploy_hash,
+            responder,
+        }
+        .into()
+    });
+    assert!(harness.is_idle());
+    response
+}
+
+/// Checks whether a block has been pruned by a storage component.
+fn is_block_pruned(
+    harness: &mut ComponentHarness<UnitTestEvent>,
+    storage: &mut Storage,
+    block_hash: BlockHash,
+) -> bool {
+    let response = harness.send_request(storage, move |responder| {
+        StorageRequest::IsBlockPruned {
+            block_hash,
+            responder,
+        }
+        .into()
+    });
+    assert!(harness.is_idle());
+    response
+}
+
+/// Checks whether the block at a specific height has been pruned by a storage component.
+fn is_block_at_height_pruned(
+    harness: &mut ComponentHarness<UnitTestEvent>,
+    storage: &mut Storage,
+    height: u64,
+) -> bool {
+    let response = harness.send_request(storage, move |responder| {
+        StorageRequest::IsBlockAtHeightPruned { height, responder }.into()
+    });
+    assert!(harness.is_idle());
+    response
+}
+
+/// Checks whether a deploy has been pruned by a storage component.
+fn is_deploy_pruned(
+    harness: &mut ComponentHarness<UnitTestEvent>,
+    storage: &mut Storage,
+    deploy_hash: DeployHash,
+) -> bool {
+    let response = harness.send_request(storage, move |responder| {
+        StorageRequest::IsDeployPruned {
+            deploy_hash,
+            responder,
+        }
+        .into()
+    });
+    assert!(harness.is_idle());
+    response
+}
+
 /// Requests the highest block from a storage component.
 fn get_highest_block(
     harness: &mut ComponentHarness<UnitTestEvent>,
@@ -245,6 +408,27 @@ fn get_highest_block(
     response
 }
 
+/// Requests the deploys sent by an account from a storage component.
+fn get_account_deploys(
+    harness: &mut ComponentHarness<UnitTestEvent>,
+    storage: &mut Storage,
+    public_key: PublicKey,
+    start: Bound<(u64, DeployHash)>,
+    max_count: usize,
+) -> Vec<(u64, BlockHash, DeployHash)> {
+    let response = harness.send_request(storage, move |responder| {
+        StorageRequest::GetAccountDeploys {
+            public_key: Box::new(public_key),
+            start,
+            max_count,
+            responder,
+        }
+        .into()
+    });
+    assert!(harness.is_idle());
+    response
+}
+
 /// Loads state from the storage component.
 #[cfg(test)]
 fn load_state<T>(
@@ -483,6 +667,58 @@ fn test_get_block_header_and_finality_signatures_by_height() {
     }
 }
 
+#[test]
+fn can_retrieve_single_finality_signature() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    let block = Block::random(&mut harness.rng);
+    let block_signatures = random_signatures(&mut harness.rng, &block);
+    let (public_key, signature) = block_signatures
+        .proofs
+        .iter()
+        .next()
+        .map(|(public_key, signature)| (public_key.clone(), *signature))
+        .expect("should have a signature");
+    let finality_signature_id = FinalitySignatureId {
+        block_hash: *block.hash(),
+        public_key,
+    };
+
+    // Nothing is returned before the signatures are stored.
+    assert_eq!(
+        storage
+            .read_finality_signature(&finality_signature_id)
+            .expect("should not throw exception"),
+        None
+    );
+
+    assert!(put_block_signatures(
+        &mut harness,
+        &mut storage,
+        block_signatures
+    ));
+    let finality_signature = storage
+        .read_finality_signature(&finality_signature_id)
+        .expect("should not throw exception")
+        .expect("should not be None");
+    assert_eq!(finality_signature.id(), finality_signature_id);
+    assert_eq!(finality_signature.signature, signature);
+    assert_eq!(finality_signature.era_id, block.header().era_id());
+
+    // A signer who didn't sign the block is unknown.
+    let other_signer_id = FinalitySignatureId {
+        block_hash: *block.hash(),
+        public_key: PublicKey::from(&SecretKey::random(&mut harness.rng)),
+    };
+    assert_eq!(
+        storage
+            .read_finality_signature(&other_signer_id)
+            .expect("should not throw exception"),
+        None
+    );
+}
+
 #[test]
 fn can_retrieve_block_by_height() {
     let mut harness = ComponentHarness::default();
@@ -1063,6 +1299,259 @@ fn persist_blocks_deploys_and_deploy_metadata_across_instantiations() {
     );
 }
 
+#[test]
+fn can_retrieve_deploys_by_account() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    let secret_key = SecretKey::random(&mut harness.rng);
+    let public_key = PublicKey::from(&secret_key);
+    let deploys: Vec<Deploy> = (0..3)
+        .map(|_| random_deploy_from_account(&mut harness.rng, &secret_key))
+        .collect();
+    let other_deploy = Deploy::random(&mut harness.rng);
+
+    // The first block is stored after its deploys.
+    put_deploy(&mut harness, &mut storage, Box::new(deploys[0].clone()));
+    put_deploy(&mut harness, &mut storage, Box::new(other_deploy.clone()));
+    let block_5 = random_block_with_deploys(
+        &mut harness.rng,
+        5,
+        vec![*deploys[0].id(), *other_deploy.id()],
+    );
+    put_block(&mut harness, &mut storage, block_5.clone());
+
+    // The second block is stored before its deploy.
+    let block_7 = random_block_with_deploys(&mut harness.rng, 7, vec![*deploys[1].id()]);
+    put_block(&mut harness, &mut storage, block_7.clone());
+    put_deploy(&mut harness, &mut storage, Box::new(deploys[1].clone()));
+
+    put_deploy(&mut harness, &mut storage, Box::new(deploys[2].clone()));
+    let block_9 = random_block_with_deploys(&mut harness.rng, 9, vec![*deploys[2].id()]);
+    put_block(&mut harness, &mut storage, block_9.clone());
+
+    let expected = vec![
+        (5, *block_5.hash(), *deploys[0].id()),
+        (7, *block_7.hash(), *deploys[1].id()),
+        (9, *block_9.hash(), *deploys[2].id()),
+    ];
+    let from_start = Bound::Included((0, DeployHash::default()));
+
+    assert_eq!(
+        get_account_deploys(
+            &mut harness,
+            &mut storage,
+            public_key.clone(),
+            from_start,
+            10
+        ),
+        expected
+    );
+    assert_eq!(
+        get_account_deploys(
+            &mut harness,
+            &mut storage,
+            public_key.clone(),
+            Bound::Included((6, DeployHash::default())),
+            1
+        ),
+        expected[1..2].to_vec()
+    );
+    assert_eq!(
+        get_account_deploys(
+            &mut harness,
+            &mut storage,
+            public_key.clone(),
+            Bound::Excluded((7, *deploys[1].id())),
+            10
+        ),
+        expected[2..].to_vec()
+    );
+    assert_eq!(
+        get_account_deploys(
+            &mut harness,
+            &mut storage,
+            other_deploy.header().account().clone(),
+            from_start,
+            10
+        ),
+        vec![(5, *block_5.hash(), *other_deploy.id())]
+    );
+    let unknown_public_key = PublicKey::from(&SecretKey::random(&mut harness.rng));
+    assert!(get_account_deploys(
+        &mut harness,
+        &mut storage,
+        unknown_public_key,
+        from_start,
+        10
+    )
+    .is_empty());
+
+    // The index persists across restarts.
+    let (on_disk, rng) = harness.into_parts();
+    let mut harness = ComponentHarness::builder()
+        .on_disk(on_disk)
+        .rng(rng)
+        .build();
+    let mut storage = storage_fixture(&harness);
+
+    assert_eq!(
+        get_account_deploys(&mut harness, &mut storage, public_key, from_start, 10),
+        expected
+    );
+}
+
+#[test]
+fn should_prune_blocks_older_than_retained_eras() {
+    let blocks_per_era = 3;
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture_with_keep_last_eras(&harness, 1);
+
+    let secret_key = SecretKey::random(&mut harness.rng);
+    let public_key = PublicKey::from(&secret_key);
+
+    // Store 11 blocks, 0-2 in era 0, 3-5 in era 1, 6-8 in era 2 and 9, 10 in era 3, each
+    // non-switch block containing a deploy with an execution result.
+    let mut blocks = vec![];
+    let mut deploys = vec![];
+    for height in 0..11 {
+        let era_id = EraId::from(height / blocks_per_era);
+        if height % blocks_per_era == blocks_per_era - 1 {
+            let block = Block::random_with_specifics(
+                &mut harness.rng,
+                era_id,
+                height,
+                ProtocolVersion::V1_0_0,
+                true,
+            );
+            assert!(put_block(
+                &mut harness,
+                &mut storage,
+                Box::new(block.clone())
+            ));
+            blocks.push(block);
+            continue;
+        }
+        let deploy = random_deploy_from_account(&mut harness.rng, &secret_key);
+        let block =
+            random_block_in_era_with_deploys(&mut harness.rng, era_id, height, vec![*deploy.id()]);
+        put_deploy(&mut harness, &mut storage, Box::new(deploy.clone()));
+        assert!(put_block(&mut harness, &mut storage, block.clone()));
+        let mut execution_results = HashMap::new();
+        execution_results.insert(*deploy.id(), harness.rng.gen());
+        put_execution_results(&mut harness, &mut storage, *block.hash(), execution_results);
+        blocks.push(*block);
+        deploys.push((height, deploy));
+    }
+
+    // The current era is era 3, so only the blocks of eras 2 and 3 and the switch blocks are kept.
+    let check = |harness: &mut ComponentHarness<UnitTestEvent>, storage: &mut Storage| {
+        for block in &blocks {
+            let height = block.height();
+            let is_pruned = height < 6 && !block.header().is_switch_block();
+            assert_eq!(
+                get_block(harness, storage, *block.hash()).is_none(),
+                is_pruned
+            );
+            assert_eq!(is_block_pruned(harness, storage, *block.hash()), is_pruned);
+            assert_eq!(
+                is_block_at_height_pruned(harness, storage, height),
+                is_pruned
+            );
+            assert_eq!(
+                get_block_header_at_height(harness, storage, height),
+                Some(block.header().clone())
+            );
+            if !block.header().is_switch_block() {
+                assert_eq!(
+                    get_block_transfers(harness, storage, *block.hash()).is_none(),
+                    is_pruned
+                );
+            }
+        }
+        for (height, deploy) in &deploys {
+            let is_pruned = *height < 6;
+            assert_eq!(
+                get_deploy_and_metadata(harness, storage, *deploy.id()).is_none(),
+                is_pruned
+            );
+            assert_eq!(is_deploy_pruned(harness, storage, *deploy.id()), is_pruned);
+            // Pruned deploys are still known to have been included in a block.
+            assert_eq!(
+                get_block_header_for_deploy(harness, storage, *deploy.id()),
+                Some(blocks[*height as usize].header().clone())
+            );
+        }
+        let account_deploy_heights: Vec<u64> =
+            get_account_deploys(harness, storage, public_key.clone(), Bound::Unbounded, 10)
+                .into_iter()
+                .map(|(height, _, _)| height)
+                .collect();
+        assert_eq!(account_deploy_heights, vec![6, 7, 9, 10]);
+        let unknown_block_hash = BlockHash::new(harness.rng.gen::<[u8; Digest::LENGTH]>().into());
+        assert!(!is_block_pruned(harness, storage, unknown_block_hash));
+    };
+    check(&mut harness, &mut storage);
+
+    // Pruned blocks are tolerated and pruned deploys indexed on restart.
+    let (on_disk, rng) = harness.into_parts();
+    let mut harness = ComponentHarness::builder()
+        .on_disk(on_disk)
+        .rng(rng)
+        .build();
+    let mut storage = storage_fixture(&harness);
+    check(&mut harness, &mut storage);
+}
+
+#[test]
+fn should_prune_blocks_in_batches() {
+    let blocks_per_era = 2 * PRUNE_BATCH_SIZE + 10;
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture_with_keep_last_eras(&harness, 0);
+
+    // Store era 0 and the switch block of era 1. Era 1 is then the current era, and every stored
+    // block only prunes a single batch of era 0.
+    for height in 0..=blocks_per_era {
+        let is_switch_block = height == blocks_per_era - 1;
+        let block = Block::random_with_specifics(
+            &mut harness.rng,
+            EraId::from(height / blocks_per_era),
+            height,
+            ProtocolVersion::V1_0_0,
+            is_switch_block,
+        );
+        assert!(put_block(&mut harness, &mut storage, Box::new(block)));
+    }
+    assert!(is_block_at_height_pruned(
+        &mut harness,
+        &mut storage,
+        2 * PRUNE_BATCH_SIZE - 1
+    ));
+    assert!(!is_block_at_height_pruned(
+        &mut harness,
+        &mut storage,
+        2 * PRUNE_BATCH_SIZE
+    ));
+
+    // On restart, the remaining blocks are pruned.
+    let (on_disk, rng) = harness.into_parts();
+    let mut harness = ComponentHarness::builder()
+        .on_disk(on_disk)
+        .rng(rng)
+        .build();
+    let mut storage = storage_fixture_with_keep_last_eras(&harness, 0);
+    assert!(is_block_at_height_pruned(
+        &mut harness,
+        &mut storage,
+        blocks_per_era - 2
+    ));
+    assert!(!is_block_at_height_pruned(
+        &mut harness,
+        &mut storage,
+        blocks_per_era
+    ));
+}
+
 #[test]
 fn should_hard_reset() {
     let blocks_count = 8_usize;
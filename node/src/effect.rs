@@ -67,6 +67,7 @@ use std::{
     collections::{BTreeMap, HashMap, HashSet},
     fmt::{self, Debug, Display, Formatter},
     future::Future,
+    ops::Bound,
     sync::Arc,
     time::{Duration, Instant},
 };
@@ -109,9 +110,9 @@ use crate::{
     },
     reactor::{EventQueueHandle, QueueKind},
     types::{
-        Block, BlockByHeight, BlockHash, BlockHeader, BlockPayload, BlockSignatures, Chainspec,
-        ChainspecInfo, Deploy, DeployHash, DeployHeader, DeployMetadata, FinalitySignature,
-        FinalizedBlock, Item, TimeDiff, Timestamp,
+        Block, BlockByHeight, BlockHash, BlockHeader, BlockPayload, BlockSignatures, BlockedPeer,
+        Chainspec, ChainspecInfo, Deploy, DeployHash, DeployHeader, DeployMetadata,
+        FinalitySignature, FinalizedBlock, Item, NodeId, OffenseSeverity, TimeDiff, Timestamp,
     },
     utils::{SharedFlag, Source},
 };
@@ -129,6 +130,7 @@ use requests::{
 use self::announcements::{BlockProposerAnnouncement, BlocklistAnnouncement};
 use crate::components::contract_runtime::{
     BlockAndExecutionEffects, BlockExecutionError, ContractRuntimeAnnouncement, ExecutionPreState,
+    SpeculativeExecutionResult,
 };
 
 /// A resource that will never be available, thus trying to acquire it will wait forever.
@@ -618,6 +620,19 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Gets the network peers currently blocked after committing offenses.
+    pub(crate) async fn network_blocked_peers<I>(self) -> BTreeMap<I, BlockedPeer>
+    where
+        REv: From<NetworkInfoRequest<I>>,
+        I: Send + 'static,
+    {
+        self.make_request(
+            |responder| NetworkInfoRequest::GetBlockedPeers { responder },
+            QueueKind::Api,
+        )
+        .await
+    }
+
     /// Gets the current network peers in a random order.
     pub async fn get_peers_in_random_order<I>(self) -> Vec<I>
     where
@@ -1214,6 +1229,32 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Executes a deploy against the given global state without committing its effects.
+    pub(crate) async fn speculatively_execute_deploy(
+        self,
+        state_root_hash: Digest,
+        block_time: u64,
+        protocol_version: ProtocolVersion,
+        proposer: PublicKey,
+        deploy: Box<Deploy>,
+    ) -> Result<Option<SpeculativeExecutionResult>, engine_state::Error>
+    where
+        REv: From<ContractRuntimeRequest>,
+    {
+        self.make_request(
+            |responder| ContractRuntimeRequest::SpeculativeDeployExecution {
+                state_root_hash,
+                block_time,
+                protocol_version,
+                proposer,
+                deploy,
+                responder,
+            },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
     /// Executes a finalized block.
     pub(crate) async fn execute_finalized_block(
         self,
@@ -1334,14 +1375,18 @@ impl<REv> EffectBuilder<REv> {
             .await
     }
 
-    /// Announce the intent to disconnect from a specific peer, which consensus thinks is faulty.
-    pub(crate) async fn announce_disconnect_from_peer<I>(self, peer: I)
+    /// Announces that a peer committed an offense, lowering its reputation and potentially
+    /// getting it blocked.
+    pub(crate) async fn announce_offense<I>(self, peer: I, severity: OffenseSeverity)
     where
         REv: From<BlocklistAnnouncement<I>>,
     {
         self.event_queue
             .schedule(
-                BlocklistAnnouncement::OffenseCommitted(Box::new(peer)),
+                BlocklistAnnouncement::OffenseCommitted {
+                    offender: Box::new(peer),
+                    severity,
+                },
                 QueueKind::Regular,
             )
             .await
@@ -1360,14 +1405,17 @@ impl<REv> EffectBuilder<REv> {
             .await
     }
 
-    /// The linear chain has stored a new finality signature.
-    pub(crate) async fn announce_finality_signature(self, fs: Box<FinalitySignature>)
-    where
+    /// The linear chain has stored a new finality signature received from `source`.
+    pub(crate) async fn announce_finality_signature(
+        self,
+        fs: Box<FinalitySignature>,
+        source: Source<NodeId>,
+    ) where
         REv: From<LinearChainAnnouncement>,
     {
         self.event_queue
             .schedule(
-                LinearChainAnnouncement::NewFinalitySignature(fs),
+                LinearChainAnnouncement::NewFinalitySignature(fs, source),
                 QueueKind::Regular,
             )
             .await
@@ -1445,6 +1493,71 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Retrieves up to `max_count` deploys sent by the given account, starting from the given
+    /// position in the account's deploy history.
+    pub(crate) async fn get_account_deploys_from_storage(
+        self,
+        public_key: PublicKey,
+        start: Bound<(u64, DeployHash)>,
+        max_count: usize,
+    ) -> Vec<(u64, BlockHash, DeployHash)>
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            move |responder| StorageRequest::GetAccountDeploys {
+                public_key: Box::new(public_key),
+                start,
+                max_count,
+                responder,
+            },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
+    /// Checks whether the block with the given hash has been pruned from storage.
+    pub(crate) async fn is_block_pruned_in_storage(self, block_hash: BlockHash) -> bool
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::IsBlockPruned {
+                block_hash,
+                responder,
+            },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
+    /// Checks whether the block at the given height has been pruned from storage.
+    pub(crate) async fn is_block_at_height_pruned_in_storage(self, height: u64) -> bool
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::IsBlockAtHeightPruned { height, responder },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
+    /// Checks whether the deploy with the given hash has been pruned from storage.
+    pub(crate) async fn is_deploy_pruned_in_storage(self, deploy_hash: DeployHash) -> bool
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::IsDeployPruned {
+                deploy_hash,
+                responder,
+            },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
     /// Loads potentially previously stored state from storage.
     ///
     /// Key must be a unique key across the the application, as all keys share a common namespace.
@@ -19,7 +19,8 @@ use crate::{
     },
     effect::Responder,
     types::{
-        Block, Deploy, DeployHash, DeployHeader, FinalitySignature, FinalizedBlock, Item, Timestamp,
+        Block, Deploy, DeployHash, DeployHeader, FinalitySignature, FinalizedBlock, Item, NodeId,
+        OffenseSeverity, Timestamp,
     },
     utils::Source,
 };
@@ -217,7 +218,12 @@ impl Display for ConsensusAnnouncement {
 #[derive(Debug, Serialize)]
 pub(crate) enum BlocklistAnnouncement<I> {
     /// A given peer committed a blockable offense.
-    OffenseCommitted(Box<I>),
+    OffenseCommitted {
+        /// The peer which committed the offense.
+        offender: Box<I>,
+        /// The severity of the offense.
+        severity: OffenseSeverity,
+    },
 }
 
 impl<I> Display for BlocklistAnnouncement<I>
@@ -226,8 +232,8 @@ where
 {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
-            BlocklistAnnouncement::OffenseCommitted(peer) => {
-                write!(f, "peer {} committed offense", peer)
+            BlocklistAnnouncement::OffenseCommitted { offender, severity } => {
+                write!(f, "peer {} committed {} offense", offender, severity)
             }
         }
     }
@@ -268,8 +274,8 @@ impl<T: Item> Display for GossiperAnnouncement<T> {
 pub(crate) enum LinearChainAnnouncement {
     /// A new block has been created and stored locally.
     BlockAdded(Box<Block>),
-    /// New finality signature received.
-    NewFinalitySignature(Box<FinalitySignature>),
+    /// New finality signature received from the given source.
+    NewFinalitySignature(Box<FinalitySignature>, Source<NodeId>),
 }
 
 impl Display for LinearChainAnnouncement {
@@ -278,8 +284,12 @@ impl Display for LinearChainAnnouncement {
             LinearChainAnnouncement::BlockAdded(block) => {
                 write!(f, "block added {}", block.hash())
             }
-            LinearChainAnnouncement::NewFinalitySignature(fs) => {
-                write!(f, "new finality signature {}", fs.block_hash)
+            LinearChainAnnouncement::NewFinalitySignature(fs, source) => {
+                write!(
+                    f,
+                    "new finality signature {} from {}",
+                    fs.block_hash, source
+                )
             }
         }
     }
@@ -8,6 +8,8 @@ use std::{
     collections::{BTreeMap, HashMap, HashSet},
     fmt::{self, Debug, Display, Formatter},
     mem,
+    net::SocketAddr,
+    ops::Bound,
     sync::Arc,
 };
 
@@ -40,16 +42,18 @@ use crate::{
         consensus::{BlockContext, ClContext, ValidatorChange},
         contract_runtime::{
             BlockAndExecutionEffects, BlockExecutionError, EraValidatorsRequest, ExecutionPreState,
+            SpeculativeExecutionResult,
         },
         deploy_acceptor::Error,
         fetcher::FetchResult,
+        small_network::PeersStatus,
     },
     effect::Responder,
     rpcs::{chain::BlockIdentifier, docs::OpenRpcSchema},
     types::{
-        Block, BlockHash, BlockHeader, BlockPayload, BlockSignatures, Chainspec, ChainspecInfo,
-        Deploy, DeployHash, DeployHeader, DeployMetadata, FinalizedBlock, Item, NodeId, StatusFeed,
-        TimeDiff,
+        Block, BlockHash, BlockHeader, BlockPayload, BlockSignatures, BlockedPeer, Chainspec,
+        ChainspecInfo, Deploy, DeployHash, DeployHeader, DeployMetadata, FinalizedBlock, Item,
+        NodeId, StatusFeed, TimeDiff,
     },
     utils::DisplayIter,
 };
@@ -190,6 +194,11 @@ pub(crate) enum NetworkInfoRequest<I> {
         /// Responds with a vector in a random order.
         responder: Responder<Vec<I>>,
     },
+    /// Get the peers currently blocked after committing offenses.
+    GetBlockedPeers {
+        /// Responder to be called with all blocked peers.
+        responder: Responder<BTreeMap<I, BlockedPeer>>,
+    },
 }
 
 impl<I> Display for NetworkInfoRequest<I>
@@ -202,6 +211,92 @@ where
             NetworkInfoRequest::GetPeersInRandomOrder { responder: _ } => {
                 write!(formatter, "get peers in random order")
             }
+            NetworkInfoRequest::GetBlockedPeers { responder: _ } => {
+                write!(formatter, "get blocked peers")
+            }
+        }
+    }
+}
+
+/// A request from the node operator to manage the peers of the network component.
+#[derive(Debug)]
+#[must_use]
+pub(crate) enum NetworkAdminRequest {
+    /// Get the state of all known addresses and the blocked and reserved peers.
+    GetPeersStatus {
+        /// Responder to be called with the state of the peers.
+        responder: Responder<PeersStatus>,
+    },
+    /// Connect to an address and keep reconnecting to it, no matter how often connecting fails.
+    Connect {
+        /// The address to connect to.
+        addr: SocketAddr,
+        /// Responder to be called once the connection attempt has been started.
+        responder: Responder<()>,
+    },
+    /// Disconnect from and forget an address.
+    Disconnect {
+        /// The address to disconnect from.
+        addr: SocketAddr,
+        /// Responder to be called once disconnected.
+        responder: Responder<()>,
+    },
+    /// Block a peer.
+    Block {
+        /// The peer to block.
+        peer_id: Box<NodeId>,
+        /// The duration of the block, the configured blocklist retain duration if `None`.
+        duration: Option<TimeDiff>,
+        /// Responder to be called once the peer is blocked.
+        responder: Responder<()>,
+    },
+    /// Unblock a peer.
+    Unblock {
+        /// The peer to unblock.
+        peer_id: Box<NodeId>,
+        /// Responder to be called with whether the peer was blocked.
+        responder: Responder<bool>,
+    },
+    /// Reserve a peer, so that it is never blocked after committing offenses and its address is
+    /// never forgotten.
+    Reserve {
+        /// The peer to reserve.
+        peer_id: Box<NodeId>,
+        /// Responder to be called once the peer is reserved.
+        responder: Responder<()>,
+    },
+    /// Revoke the reservation of a peer.
+    Unreserve {
+        /// The peer to unreserve.
+        peer_id: Box<NodeId>,
+        /// Responder to be called with whether the peer was reserved.
+        responder: Responder<bool>,
+    },
+}
+
+impl Display for NetworkAdminRequest {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            NetworkAdminRequest::GetPeersStatus { .. } => write!(formatter, "get peers status"),
+            NetworkAdminRequest::Connect { addr, .. } => write!(formatter, "connect to {}", addr),
+            NetworkAdminRequest::Disconnect { addr, .. } => {
+                write!(formatter, "disconnect from {}", addr)
+            }
+            NetworkAdminRequest::Block {
+                peer_id, duration, ..
+            } => match duration {
+                Some(duration) => write!(formatter, "block {} for {}", peer_id, duration),
+                None => write!(formatter, "block {}", peer_id),
+            },
+            NetworkAdminRequest::Unblock { peer_id, .. } => {
+                write!(formatter, "unblock {}", peer_id)
+            }
+            NetworkAdminRequest::Reserve { peer_id, .. } => {
+                write!(formatter, "reserve {}", peer_id)
+            }
+            NetworkAdminRequest::Unreserve { peer_id, .. } => {
+                write!(formatter, "unreserve {}", peer_id)
+            }
         }
     }
 }
@@ -299,6 +394,39 @@ pub(crate) enum StorageRequest {
         /// Responder to call with the results.
         responder: Responder<Vec<(DeployHash, DeployHeader)>>,
     },
+    /// Retrieve the deploys sent by an account, ordered by the height of the blocks containing
+    /// them.
+    GetAccountDeploys {
+        /// Public key of the account which sent the deploys.
+        public_key: Box<PublicKey>,
+        /// Position in the account's deploy history from which to start.
+        start: Bound<(BlockHeight, DeployHash)>,
+        /// Maximum number of deploys to be retrieved.
+        max_count: usize,
+        /// Responder to call with the block height, block hash and deploy hash of each deploy.
+        responder: Responder<Vec<(BlockHeight, BlockHash, DeployHash)>>,
+    },
+    /// Check whether the block with the given hash has been pruned from storage.
+    IsBlockPruned {
+        /// Hash of the block.
+        block_hash: BlockHash,
+        /// Responder to call with the result.
+        responder: Responder<bool>,
+    },
+    /// Check whether the block at the given height has been pruned from storage.
+    IsBlockAtHeightPruned {
+        /// Height of the block.
+        height: BlockHeight,
+        /// Responder to call with the result.
+        responder: Responder<bool>,
+    },
+    /// Check whether the deploy with the given hash has been pruned from storage.
+    IsDeployPruned {
+        /// Hash of the deploy.
+        deploy_hash: DeployHash,
+        /// Responder to call with the result.
+        responder: Responder<bool>,
+    },
     /// Store execution results for a set of deploys of a single block.
     ///
     /// Will return a fatal error if there are already execution results known for a specific
@@ -421,6 +549,18 @@ impl Display for StorageRequest {
             StorageRequest::GetFinalizedDeploys { ttl, .. } => {
                 write!(formatter, "get finalized deploys, ttl: {:?}", ttl)
             }
+            StorageRequest::GetAccountDeploys { public_key, .. } => {
+                write!(formatter, "get deploys sent by {}", public_key)
+            }
+            StorageRequest::IsBlockPruned { block_hash, .. } => {
+                write!(formatter, "is {} pruned", block_hash)
+            }
+            StorageRequest::IsBlockAtHeightPruned { height, .. } => {
+                write!(formatter, "is block at height {} pruned", height)
+            }
+            StorageRequest::IsDeployPruned { deploy_hash, .. } => {
+                write!(formatter, "is {} pruned", deploy_hash)
+            }
         }
     }
 }
@@ -589,6 +729,11 @@ pub(crate) enum RpcRequest<I> {
         /// Responder to call with the result.
         responder: Responder<BTreeMap<I, String>>,
     },
+    /// Return the peers currently blocked after committing offenses.
+    GetBlockedPeers {
+        /// Responder to call with the result.
+        responder: Responder<BTreeMap<I, BlockedPeer>>,
+    },
     /// Return string formatted status or `None` if an error occurred.
     GetStatus {
         /// Responder to call with the result.
@@ -642,6 +787,7 @@ impl<I> Display for RpcRequest<I> {
             ),
             RpcRequest::GetDeploy { hash, .. } => write!(formatter, "get {}", hash),
             RpcRequest::GetPeers { .. } => write!(formatter, "get peers"),
+            RpcRequest::GetBlockedPeers { .. } => write!(formatter, "get blocked peers"),
             RpcRequest::GetStatus { .. } => write!(formatter, "get status"),
         }
     }
@@ -787,6 +933,21 @@ pub(crate) enum ContractRuntimeRequest {
         /// Responder to call with the result.
         responder: Responder<Result<BlockAndExecutionEffects, BlockExecutionError>>,
     },
+    /// Execute a deploy against the given global state without committing its effects.
+    SpeculativeDeployExecution {
+        /// Hash of the state root against which to execute the deploy.
+        state_root_hash: Digest,
+        /// The block time to use during execution, in milliseconds since the Unix epoch.
+        block_time: u64,
+        /// The protocol version under which to execute the deploy.
+        protocol_version: ProtocolVersion,
+        /// The proposer to use during execution.
+        proposer: PublicKey,
+        /// The deploy to execute.
+        deploy: Box<Deploy>,
+        /// Responder to call with the execution result, or `None` if the engine returned none.
+        responder: Responder<Result<Option<SpeculativeExecutionResult>, engine_state::Error>>,
+    },
 }
 
 impl Display for ContractRuntimeRequest {
@@ -845,6 +1006,18 @@ impl Display for ContractRuntimeRequest {
             } => {
                 write!(formatter, "Execute finalized block: {}", finalized_block)
             }
+            ContractRuntimeRequest::SpeculativeDeployExecution {
+                state_root_hash,
+                deploy,
+                ..
+            } => {
+                write!(
+                    formatter,
+                    "speculatively execute {} against state root {}",
+                    deploy.id(),
+                    state_root_hash
+                )
+            }
         }
     }
 }
@@ -60,8 +60,8 @@ use signal_hook::{
 };
 
 pub(crate) use components::{
-    block_proposer::Config as BlockProposerConfig, consensus::Config as ConsensusConfig,
-    contract_runtime::Config as ContractRuntimeConfig,
+    admin_server::Config as AdminServerConfig, block_proposer::Config as BlockProposerConfig,
+    consensus::Config as ConsensusConfig, contract_runtime::Config as ContractRuntimeConfig,
     deploy_acceptor::Config as DeployAcceptorConfig,
     event_stream_server::Config as EventStreamServerConfig, fetcher::Config as FetcherConfig,
     gossiper::Config as GossipConfig, linear_chain_sync::Config as LinearChainSyncConfig,
@@ -7,6 +7,10 @@ use fmt::Debug;
 use hex_fmt::HexFmt;
 use serde::{Deserialize, Serialize};
 
+use casper_types::bytesrepr::{
+    self, FromBytes, ToBytes, U32_SERIALIZED_LENGTH, U8_SERIALIZED_LENGTH,
+};
+
 use crate::{
     components::{
         consensus, gossiper,
@@ -15,6 +19,14 @@ use crate::{
     types::{Deploy, FinalitySignature, Item, SharedObject, Tag},
 };
 
+const CONSENSUS_TAG: u8 = 0;
+const DEPLOY_GOSSIPER_TAG: u8 = 1;
+const ADDRESS_GOSSIPER_TAG: u8 = 2;
+const GET_REQUEST_TAG: u8 = 3;
+const GET_RESPONSE_TAG: u8 = 4;
+const FINALITY_SIGNATURE_TAG: u8 = 5;
+const FINALITY_SIGNATURE_GOSSIPER_TAG: u8 = 6;
+
 /// Reactor message.
 #[derive(Clone, From, Serialize, Deserialize)]
 pub(crate) enum Message {
@@ -41,9 +53,12 @@ pub(crate) enum Message {
         /// The serialized item.
         serialized_item: SharedObject<Vec<u8>>,
     },
-    /// Finality signature.
+    /// Finality signature, as broadcast by nodes which don't gossip finality signatures yet.
     #[from]
     FinalitySignature(Box<FinalitySignature>),
+    /// Finality signature gossiper component message.
+    #[from]
+    FinalitySignatureGossiper(gossiper::Message<FinalitySignature>),
 }
 
 impl Payload for Message {
@@ -62,9 +77,12 @@ impl Payload for Message {
                     Tag::BlockByHeight => MessageKind::BlockTransfer,
                     Tag::BlockHeaderByHash => MessageKind::BlockTransfer,
                     Tag::BlockHeaderAndFinalitySignaturesByHeight => MessageKind::BlockTransfer,
+                    Tag::FinalitySignature => MessageKind::BlockTransfer,
                 }
             }
-            Message::FinalitySignature(_) => MessageKind::Consensus,
+            Message::FinalitySignature(_) | Message::FinalitySignatureGossiper(_) => {
+                MessageKind::Consensus
+            }
         }
     }
 
@@ -81,8 +99,10 @@ impl Payload for Message {
                 Tag::BlockByHeight => 0,
                 Tag::BlockHeaderByHash => 0,
                 Tag::BlockHeaderAndFinalitySignaturesByHeight => 0,
+                Tag::FinalitySignature => 0,
             },
             Message::FinalitySignature(_) => 0,
+            Message::FinalitySignatureGossiper(_) => 0,
         }
     }
 }
@@ -134,6 +154,10 @@ impl Debug for Message {
             Message::FinalitySignature(fs) => {
                 f.debug_tuple("FinalitySignature").field(&fs).finish()
             }
+            Message::FinalitySignatureGossiper(fsg) => f
+                .debug_tuple("FinalitySignatureGossiper")
+                .field(&fsg)
+                .finish(),
         }
     }
 }
@@ -156,6 +180,118 @@ impl Display for Message {
             Message::FinalitySignature(fs) => {
                 write!(f, "FinalitySignature::({})", fs)
             }
+            Message::FinalitySignatureGossiper(fsg) => {
+                write!(f, "FinalitySignatureGossiper::{}", fsg)
+            }
+        }
+    }
+}
+
+impl ToBytes for Message {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut buffer = bytesrepr::allocate_buffer(self)?;
+        match self {
+            Message::Consensus(consensus) => {
+                buffer.push(CONSENSUS_TAG);
+                buffer.extend(consensus.to_bytes()?);
+            }
+            Message::DeployGossiper(deploy) => {
+                buffer.push(DEPLOY_GOSSIPER_TAG);
+                buffer.extend(deploy.to_bytes()?);
+            }
+            Message::AddressGossiper(gossiped_address) => {
+                buffer.push(ADDRESS_GOSSIPER_TAG);
+                buffer.extend(gossiped_address.to_bytes()?);
+            }
+            Message::GetRequest { tag, serialized_id } => {
+                buffer.push(GET_REQUEST_TAG);
+                buffer.extend(tag.to_bytes()?);
+                buffer.extend(bytesrepr::Bytes::from(serialized_id.as_slice()).to_bytes()?);
+            }
+            Message::GetResponse {
+                tag,
+                serialized_item,
+            } => {
+                buffer.push(GET_RESPONSE_TAG);
+                buffer.extend(tag.to_bytes()?);
+                buffer.extend(bytesrepr::Bytes::from(serialized_item.as_slice()).to_bytes()?);
+            }
+            Message::FinalitySignature(fs) => {
+                buffer.push(FINALITY_SIGNATURE_TAG);
+                buffer.extend(fs.to_bytes()?);
+            }
+            Message::FinalitySignatureGossiper(fsg) => {
+                buffer.push(FINALITY_SIGNATURE_GOSSIPER_TAG);
+                buffer.extend(fsg.to_bytes()?);
+            }
+        }
+        Ok(buffer)
+    }
+
+    fn serialized_length(&self) -> usize {
+        U8_SERIALIZED_LENGTH
+            + match self {
+                Message::Consensus(consensus) => consensus.serialized_length(),
+                Message::DeployGossiper(deploy) => deploy.serialized_length(),
+                Message::AddressGossiper(gossiped_address) => gossiped_address.serialized_length(),
+                Message::GetRequest { tag, serialized_id } => {
+                    tag.serialized_length() + U32_SERIALIZED_LENGTH + serialized_id.len()
+                }
+                Message::GetResponse {
+                    tag,
+                    serialized_item,
+                } => tag.serialized_length() + U32_SERIALIZED_LENGTH + serialized_item.len(),
+                Message::FinalitySignature(fs) => fs.serialized_length(),
+                Message::FinalitySignatureGossiper(fsg) => fsg.serialized_length(),
+            }
+    }
+}
+
+impl FromBytes for Message {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (tag, remainder) = u8::from_bytes(bytes)?;
+        match tag {
+            CONSENSUS_TAG => {
+                let (consensus, remainder) = consensus::ConsensusMessage::from_bytes(remainder)?;
+                Ok((Message::Consensus(consensus), remainder))
+            }
+            DEPLOY_GOSSIPER_TAG => {
+                let (deploy, remainder) = gossiper::Message::<Deploy>::from_bytes(remainder)?;
+                Ok((Message::DeployGossiper(deploy), remainder))
+            }
+            ADDRESS_GOSSIPER_TAG => {
+                let (gossiped_address, remainder) =
+                    gossiper::Message::<GossipedAddress>::from_bytes(remainder)?;
+                Ok((Message::AddressGossiper(gossiped_address), remainder))
+            }
+            GET_REQUEST_TAG => {
+                let (tag, remainder) = Tag::from_bytes(remainder)?;
+                let (serialized_id, remainder) = bytesrepr::Bytes::from_bytes(remainder)?;
+                let message = Message::GetRequest {
+                    tag,
+                    serialized_id: serialized_id.into(),
+                };
+                Ok((message, remainder))
+            }
+            GET_RESPONSE_TAG => {
+                let (tag, remainder) = Tag::from_bytes(remainder)?;
+                let (serialized_item, remainder) = bytesrepr::Bytes::from_bytes(remainder)?;
+                let message = Message::GetResponse {
+                    tag,
+                    serialized_item: SharedObject::owned(serialized_item.into()),
+                };
+                Ok((message, remainder))
+            }
+            FINALITY_SIGNATURE_TAG => {
+                let (fs, remainder) = FinalitySignature::from_bytes(remainder)?;
+                Ok((Message::FinalitySignature(Box::new(fs)), remainder))
+            }
+            FINALITY_SIGNATURE_GOSSIPER_TAG => {
+                let (fsg, remainder) =
+                    gossiper::Message::<FinalitySignature>::from_bytes(remainder)?;
+                Ok((Message::FinalitySignatureGossiper(fsg), remainder))
+            }
+            _ => Err(bytesrepr::Error::Formatting),
         }
     }
 }
@@ -40,8 +40,9 @@ use crate::{
     },
     effect::{
         announcements::{
-            ChainspecLoaderAnnouncement, ControlAnnouncement, DeployAcceptorAnnouncement,
-            GossiperAnnouncement, LinearChainAnnouncement, LinearChainBlock, NetworkAnnouncement,
+            BlocklistAnnouncement, ChainspecLoaderAnnouncement, ControlAnnouncement,
+            DeployAcceptorAnnouncement, GossiperAnnouncement, LinearChainAnnouncement,
+            LinearChainBlock, NetworkAnnouncement,
         },
         requests::{
             BlockProposerRequest, BlockValidationRequest, ChainspecLoaderRequest, ConsensusRequest,
@@ -193,6 +194,10 @@ pub(crate) enum JoinerEvent {
     #[from]
     ChainspecLoaderAnnouncement(#[serde(skip_serializing)] ChainspecLoaderAnnouncement),
 
+    /// Blocklist announcement.
+    #[from]
+    BlocklistAnnouncement(BlocklistAnnouncement<NodeId>),
+
     /// Consensus request.
     #[from]
     ConsensusRequest(#[serde(skip_serializing)] ConsensusRequest),
@@ -239,6 +244,7 @@ impl ReactorEvent for JoinerEvent {
             JoinerEvent::DeployAcceptorAnnouncement(_) => "DeployAcceptorAnnouncement",
             JoinerEvent::LinearChainAnnouncement(_) => "LinearChainAnnouncement",
             JoinerEvent::ChainspecLoaderAnnouncement(_) => "ChainspecLoaderAnnouncement",
+            JoinerEvent::BlocklistAnnouncement(_) => "BlocklistAnnouncement",
             JoinerEvent::ConsensusRequest(_) => "ConsensusRequest",
         }
     }
@@ -330,6 +336,9 @@ impl Display for JoinerEvent {
             JoinerEvent::ChainspecLoaderAnnouncement(ann) => {
                 write!(f, "chainspec loader announcement: {}", ann)
             }
+            JoinerEvent::BlocklistAnnouncement(ann) => {
+                write!(f, "blocklist announcement: {}", ann)
+            }
             JoinerEvent::StateStoreRequest(req) => write!(f, "state store request: {}", req),
             JoinerEvent::ConsensusRequest(req) => write!(f, "consensus request: {:?}", req),
         }
@@ -414,6 +423,12 @@ impl reactor::Reactor for Reactor {
             registry,
             small_network_identity,
             chainspec_loader.chainspec().as_ref(),
+            Some(storage.root_path().join(small_network::BLOCKLIST_FILENAME)),
+            Some(
+                storage
+                    .root_path()
+                    .join(small_network::RESERVED_PEERS_FILENAME),
+            ),
         )?;
 
         let linear_chain_fetcher = Fetcher::new("linear_chain", config.fetcher, registry)?;
@@ -658,7 +673,7 @@ impl reactor::Reactor for Reactor {
                     });
                     self.dispatch_event(effect_builder, rng, event)
                 }
-                Message::FinalitySignature(_) => {
+                Message::FinalitySignature(_) | Message::FinalitySignatureGossiper(_) => {
                     debug!("finality signatures not handled in joiner reactor");
                     Effects::new()
                 }
@@ -847,7 +862,7 @@ impl reactor::Reactor for Reactor {
                 effects
             }
             JoinerEvent::LinearChainAnnouncement(
-                LinearChainAnnouncement::NewFinalitySignature(fs),
+                LinearChainAnnouncement::NewFinalitySignature(fs, _source),
             ) => {
                 let reactor_event = JoinerEvent::EventStreamServer(
                     event_stream_server::Event::FinalitySignature(fs),
@@ -884,6 +899,10 @@ impl reactor::Reactor for Reactor {
                 let event = JoinerEvent::SmallNetwork(small_network::Event::from(req));
                 self.dispatch_event(effect_builder, rng, event)
             }
+            JoinerEvent::BlocklistAnnouncement(ann) => {
+                let event = JoinerEvent::SmallNetwork(small_network::Event::from(ann));
+                self.dispatch_event(effect_builder, rng, event)
+            }
             JoinerEvent::ChainspecLoaderAnnouncement(
                 ChainspecLoaderAnnouncement::UpgradeActivationPointRead(next_upgrade),
             ) => {
@@ -27,6 +27,7 @@ use crate::testing::network::NetworkedReactor;
 
 use crate::{
     components::{
+        admin_server::AdminServer,
         block_proposer::{self, BlockProposer},
         block_validator::{self, BlockValidator},
         chainspec_loader::{self, ChainspecLoader},
@@ -54,14 +55,17 @@ use crate::{
         requests::{
             BlockProposerRequest, BlockValidationRequest, ChainspecLoaderRequest, ConsensusRequest,
             ContractRuntimeRequest, FetcherRequest, LinearChainRequest, MetricsRequest,
-            NetworkInfoRequest, NetworkRequest, RestRequest, RpcRequest, StateStoreRequest,
-            StorageRequest,
+            NetworkAdminRequest, NetworkInfoRequest, NetworkRequest, RestRequest, RpcRequest,
+            StateStoreRequest, StorageRequest,
         },
         EffectBuilder, EffectExt, Effects,
     },
     protocol::Message,
     reactor::{self, event_queue_metrics::EventQueueMetrics, EventQueueHandle, ReactorExit},
-    types::{BlockHash, BlockHeader, Deploy, ExitCode, NodeId, Tag},
+    types::{
+        BlockHash, BlockHeader, Deploy, ExitCode, FinalitySignature, FinalitySignatureId, Item,
+        NodeId, Tag,
+    },
     utils::{Source, WithDir},
     NodeRng,
 };
@@ -112,6 +116,9 @@ pub(crate) enum ParticipatingEvent {
     /// Address gossiper event.
     #[from]
     AddressGossiper(gossiper::Event<GossipedAddress>),
+    /// Finality signature gossiper event.
+    #[from]
+    FinalitySignatureGossiper(#[serde(skip_serializing)] gossiper::Event<FinalitySignature>),
     /// Block validator event.
     #[from]
     BlockValidator(#[serde(skip_serializing)] block_validator::Event<NodeId>),
@@ -128,6 +135,9 @@ pub(crate) enum ParticipatingEvent {
     /// Network info request.
     #[from]
     NetworkInfoRequest(#[serde(skip_serializing)] NetworkInfoRequest<NodeId>),
+    /// Network admin request.
+    #[from]
+    NetworkAdminRequest(#[serde(skip_serializing)] NetworkAdminRequest),
     /// Deploy fetcher request.
     #[from]
     DeployFetcherRequest(#[serde(skip_serializing)] FetcherRequest<NodeId, Deploy>),
@@ -175,6 +185,11 @@ pub(crate) enum ParticipatingEvent {
     /// Address Gossiper announcement.
     #[from]
     AddressGossiperAnnouncement(#[serde(skip_serializing)] GossiperAnnouncement<GossipedAddress>),
+    /// Finality signature gossiper announcement.
+    #[from]
+    FinalitySignatureGossiperAnnouncement(
+        #[serde(skip_serializing)] GossiperAnnouncement<FinalitySignature>,
+    ),
     /// Linear chain announcement.
     #[from]
     LinearChainAnnouncement(#[serde(skip_serializing)] LinearChainAnnouncement),
@@ -213,11 +228,13 @@ impl ReactorEvent for ParticipatingEvent {
             ParticipatingEvent::DeployFetcher(_) => "DeployFetcher",
             ParticipatingEvent::DeployGossiper(_) => "DeployGossiper",
             ParticipatingEvent::AddressGossiper(_) => "AddressGossiper",
+            ParticipatingEvent::FinalitySignatureGossiper(_) => "FinalitySignatureGossiper",
             ParticipatingEvent::BlockValidator(_) => "BlockValidator",
             ParticipatingEvent::LinearChain(_) => "LinearChain",
             ParticipatingEvent::ContractRuntime(_) => "ContractRuntime",
             ParticipatingEvent::NetworkRequest(_) => "NetworkRequest",
             ParticipatingEvent::NetworkInfoRequest(_) => "NetworkInfoRequest",
+            ParticipatingEvent::NetworkAdminRequest(_) => "NetworkAdminRequest",
             ParticipatingEvent::DeployFetcherRequest(_) => "DeployFetcherRequest",
             ParticipatingEvent::BlockProposerRequest(_) => "BlockProposerRequest",
             ParticipatingEvent::BlockValidatorRequest(_) => "BlockValidatorRequest",
@@ -233,6 +250,9 @@ impl ReactorEvent for ParticipatingEvent {
             ParticipatingEvent::ContractRuntimeAnnouncement(_) => "ContractRuntimeAnnouncement",
             ParticipatingEvent::DeployGossiperAnnouncement(_) => "DeployGossiperAnnouncement",
             ParticipatingEvent::AddressGossiperAnnouncement(_) => "AddressGossiperAnnouncement",
+            ParticipatingEvent::FinalitySignatureGossiperAnnouncement(_) => {
+                "FinalitySignatureGossiperAnnouncement"
+            }
             ParticipatingEvent::LinearChainAnnouncement(_) => "LinearChainAnnouncement",
             ParticipatingEvent::ChainspecLoaderAnnouncement(_) => "ChainspecLoaderAnnouncement",
             ParticipatingEvent::BlocklistAnnouncement(_) => "BlocklistAnnouncement",
@@ -277,6 +297,12 @@ impl From<NetworkRequest<NodeId, gossiper::Message<GossipedAddress>>> for Partic
     }
 }
 
+impl From<NetworkRequest<NodeId, gossiper::Message<FinalitySignature>>> for ParticipatingEvent {
+    fn from(request: NetworkRequest<NodeId, gossiper::Message<FinalitySignature>>) -> Self {
+        ParticipatingEvent::NetworkRequest(request.map_payload(Message::from))
+    }
+}
+
 impl From<ConsensusRequest> for ParticipatingEvent {
     fn from(request: ConsensusRequest) -> Self {
         ParticipatingEvent::Consensus(consensus::Event::ConsensusRequest(request))
@@ -306,6 +332,9 @@ impl Display for ParticipatingEvent {
             ParticipatingEvent::DeployFetcher(event) => write!(f, "deploy fetcher: {}", event),
             ParticipatingEvent::DeployGossiper(event) => write!(f, "deploy gossiper: {}", event),
             ParticipatingEvent::AddressGossiper(event) => write!(f, "address gossiper: {}", event),
+            ParticipatingEvent::FinalitySignatureGossiper(event) => {
+                write!(f, "finality signature gossiper: {}", event)
+            }
             ParticipatingEvent::ContractRuntime(event) => {
                 write!(f, "contract runtime: {:?}", event)
             }
@@ -315,6 +344,9 @@ impl Display for ParticipatingEvent {
             ParticipatingEvent::NetworkInfoRequest(req) => {
                 write!(f, "network info request: {}", req)
             }
+            ParticipatingEvent::NetworkAdminRequest(req) => {
+                write!(f, "network admin request: {}", req)
+            }
             ParticipatingEvent::ChainspecLoaderRequest(req) => {
                 write!(f, "chainspec loader request: {}", req)
             }
@@ -352,6 +384,9 @@ impl Display for ParticipatingEvent {
             ParticipatingEvent::AddressGossiperAnnouncement(ann) => {
                 write!(f, "address gossiper announcement: {}", ann)
             }
+            ParticipatingEvent::FinalitySignatureGossiperAnnouncement(ann) => {
+                write!(f, "finality signature gossiper announcement: {}", ann)
+            }
             ParticipatingEvent::LinearChainAnnouncement(ann) => {
                 write!(f, "linear chain announcement: {}", ann)
             }
@@ -412,6 +447,7 @@ pub(crate) struct Reactor {
     deploy_acceptor: DeployAcceptor,
     deploy_fetcher: Fetcher<Deploy>,
     deploy_gossiper: Gossiper<Deploy, ParticipatingEvent>,
+    finality_signature_gossiper: Gossiper<FinalitySignature, ParticipatingEvent>,
     block_proposer: BlockProposer,
     block_validator: BlockValidator<NodeId>,
     linear_chain: LinearChainComponent<NodeId>,
@@ -420,6 +456,9 @@ pub(crate) struct Reactor {
     #[data_size(skip)] // Never allocates heap data.
     memory_metrics: MemoryMetrics,
 
+    #[data_size(skip)]
+    admin_server: Option<AdminServer>,
+
     #[data_size(skip)]
     event_queue_metrics: EventQueueMetrics,
 }
@@ -492,6 +531,11 @@ impl reactor::Reactor for Reactor {
             *protocol_version,
             node_startup_instant,
         )?;
+        let admin_server = if config.admin_server.enable_server {
+            Some(AdminServer::new(config.admin_server, effect_builder)?)
+        } else {
+            None
+        };
 
         let deploy_acceptor = DeployAcceptor::new(
             config.deploy_acceptor,
@@ -504,6 +548,16 @@ impl reactor::Reactor for Reactor {
             "deploy_gossiper",
             config.gossip,
             gossiper::get_deploy_from_storage::<Deploy, ParticipatingEvent>,
+            true,
+            registry,
+        )?;
+        let finality_signature_gossiper = Gossiper::new_for_partial_items(
+            "finality_signature_gossiper",
+            config.gossip,
+            gossiper::get_finality_signature_from_storage::<FinalitySignature, ParticipatingEvent>,
+            // The linear chain keeps signatures for unknown blocks pending and silently drops
+            // those it already knows, so an honest holder can appear unresponsive.
+            false,
             registry,
         )?;
         let (block_proposer, block_proposer_effects) = BlockProposer::new(
@@ -529,6 +583,12 @@ impl reactor::Reactor for Reactor {
             registry,
             small_network_identity,
             chainspec_loader.chainspec().as_ref(),
+            Some(storage.root_path().join(small_network::BLOCKLIST_FILENAME)),
+            Some(
+                storage
+                    .root_path()
+                    .join(small_network::RESERVED_PEERS_FILENAME),
+            ),
         )?;
 
         let mut effects =
@@ -568,6 +628,15 @@ impl reactor::Reactor for Reactor {
             _ => chainspec_loader.initial_execution_pre_state(),
         };
         contract_runtime.set_initial_state(execution_pre_state);
+        if config.contract_runtime.trie_pruning_enabled() {
+            let state_roots = storage.read_state_root_hashes_for_trie_pruning(
+                config.contract_runtime.trie_pruning_retained_state_roots(),
+            )?;
+            effects.extend(reactor::wrap_effects(
+                Into::into,
+                contract_runtime.start_trie_pruning(effect_builder, state_roots),
+            ));
+        }
 
         let block_validator = BlockValidator::new(Arc::clone(chainspec_loader.chainspec()));
         let linear_chain = linear_chain::LinearChainComponent::new(
@@ -603,10 +672,12 @@ impl reactor::Reactor for Reactor {
                 deploy_acceptor,
                 deploy_fetcher,
                 deploy_gossiper,
+                finality_signature_gossiper,
                 block_proposer,
                 block_validator,
                 linear_chain,
                 memory_metrics,
+                admin_server,
                 event_queue_metrics,
             },
             effects,
@@ -673,6 +744,11 @@ impl reactor::Reactor for Reactor {
                 self.address_gossiper
                     .handle_event(effect_builder, rng, event),
             ),
+            ParticipatingEvent::FinalitySignatureGossiper(event) => reactor::wrap_effects(
+                ParticipatingEvent::FinalitySignatureGossiper,
+                self.finality_signature_gossiper
+                    .handle_event(effect_builder, rng, event),
+            ),
             ParticipatingEvent::ContractRuntime(event) => reactor::wrap_effects(
                 Into::into,
                 self.contract_runtime
@@ -697,6 +773,10 @@ impl reactor::Reactor for Reactor {
                 let event = ParticipatingEvent::SmallNetwork(small_network::Event::from(req));
                 self.dispatch_event(effect_builder, rng, event)
             }
+            ParticipatingEvent::NetworkAdminRequest(req) => {
+                let event = ParticipatingEvent::SmallNetwork(small_network::Event::from(req));
+                self.dispatch_event(effect_builder, rng, event)
+            }
             ParticipatingEvent::DeployFetcherRequest(req) => self.dispatch_event(
                 effect_builder,
                 rng,
@@ -755,6 +835,11 @@ impl reactor::Reactor for Reactor {
                             message,
                         })
                     }
+                    Message::FinalitySignatureGossiper(message) => {
+                        ParticipatingEvent::FinalitySignatureGossiper(
+                            gossiper::Event::MessageReceived { sender, message },
+                        )
+                    }
                     Message::GetRequest { tag, serialized_id } => match tag {
                         Tag::Deploy => {
                             let deploy_hash = match bincode::deserialize(&serialized_id) {
@@ -899,6 +984,48 @@ impl reactor::Reactor for Reactor {
                                 }
                             }
                         }
+                        Tag::FinalitySignature => {
+                            let finality_signature_id: FinalitySignatureId =
+                                match bincode::deserialize(&serialized_id) {
+                                    Ok(finality_signature_id) => finality_signature_id,
+                                    Err(error) => {
+                                        error!(
+                                            "failed to decode {:?} from {}: {}",
+                                            serialized_id, sender, error
+                                        );
+                                        return Effects::new();
+                                    }
+                                };
+                            match self.storage.read_finality_signature(&finality_signature_id) {
+                                Ok(Some(finality_signature)) => {
+                                    match Message::new_get_response(&finality_signature) {
+                                        Ok(message) => {
+                                            return effect_builder
+                                                .send_message(sender, message)
+                                                .ignore();
+                                        }
+                                        Err(error) => {
+                                            error!("failed to create get-response: {}", error);
+                                            return Effects::new();
+                                        }
+                                    };
+                                }
+                                Ok(None) => {
+                                    debug!(
+                                        "failed to get {} for {}",
+                                        finality_signature_id, sender
+                                    );
+                                    return Effects::new();
+                                }
+                                Err(error) => {
+                                    error!(
+                                        "failed to get {} for {}: {}",
+                                        finality_signature_id, sender, error
+                                    );
+                                    return Effects::new();
+                                }
+                            }
+                        }
                     },
                     Message::GetResponse {
                         tag,
@@ -954,9 +1081,30 @@ impl reactor::Reactor for Reactor {
                             );
                             return Effects::new();
                         }
+                        Tag::FinalitySignature => {
+                            let finality_signature: Box<FinalitySignature> =
+                                match bincode::deserialize(&serialized_item) {
+                                    Ok(finality_signature) => Box::new(finality_signature),
+                                    Err(error) => {
+                                        error!(
+                                            "failed to decode finality signature from {}: {}",
+                                            sender, error
+                                        );
+                                        return Effects::new();
+                                    }
+                                };
+                            // The linear chain announces the signature once it is validated, at
+                            // which point it is gossiped onwards.
+                            ParticipatingEvent::LinearChain(
+                                linear_chain::Event::FinalitySignatureReceived(
+                                    finality_signature,
+                                    Source::Peer(sender),
+                                ),
+                            )
+                        }
                     },
                     Message::FinalitySignature(fs) => ParticipatingEvent::LinearChain(
-                        linear_chain::Event::FinalitySignatureReceived(fs, true),
+                        linear_chain::Event::FinalitySignatureReceived(fs, Source::Peer(sender)),
                     ),
                 };
                 self.dispatch_event(effect_builder, rng, reactor_event)
@@ -1061,7 +1209,7 @@ impl reactor::Reactor for Reactor {
                         effect_builder,
                         rng,
                         ParticipatingEvent::LinearChain(
-                            linear_chain::Event::FinalitySignatureReceived(fs, false),
+                            linear_chain::Event::FinalitySignatureReceived(fs, Source::Ourself),
                         ),
                     ),
                     ConsensusAnnouncement::Fault {
@@ -1158,6 +1306,21 @@ impl reactor::Reactor for Reactor {
                 // We don't care about completion of gossiping an address.
                 Effects::new()
             }
+            ParticipatingEvent::FinalitySignatureGossiperAnnouncement(
+                GossiperAnnouncement::NewCompleteItem(gossiped_finality_signature_id),
+            ) => {
+                error!(
+                    %gossiped_finality_signature_id,
+                    "gossiper should not announce new finality signature"
+                );
+                Effects::new()
+            }
+            ParticipatingEvent::FinalitySignatureGossiperAnnouncement(
+                GossiperAnnouncement::FinishedGossiping(_),
+            ) => {
+                // We don't care about completion of gossiping a finality signature.
+                Effects::new()
+            }
             ParticipatingEvent::LinearChainAnnouncement(LinearChainAnnouncement::BlockAdded(
                 block,
             )) => {
@@ -1181,12 +1344,25 @@ impl reactor::Reactor for Reactor {
                 self.dispatch_event(effect_builder, rng, reactor_event)
             }
             ParticipatingEvent::LinearChainAnnouncement(
-                LinearChainAnnouncement::NewFinalitySignature(fs),
+                LinearChainAnnouncement::NewFinalitySignature(fs, source),
             ) => {
+                // The linear chain announces both our own signatures and those received from
+                // peers once they are validated, so gossip them onwards.
+                let event = gossiper::Event::ItemReceived {
+                    item_id: fs.id(),
+                    source,
+                };
+                let mut effects = self.dispatch_event(
+                    effect_builder,
+                    rng,
+                    ParticipatingEvent::FinalitySignatureGossiper(event),
+                );
+
                 let reactor_event = ParticipatingEvent::EventStreamServer(
                     event_stream_server::Event::FinalitySignature(fs),
                 );
-                self.dispatch_event(effect_builder, rng, reactor_event)
+                effects.extend(self.dispatch_event(effect_builder, rng, reactor_event));
+                effects
             }
             ParticipatingEvent::ChainspecLoaderAnnouncement(
                 ChainspecLoaderAnnouncement::UpgradeActivationPointRead(next_upgrade),
@@ -2,10 +2,10 @@ use datasize::DataSize;
 use serde::Deserialize;
 
 use crate::{
-    logging::LoggingConfig, types::NodeConfig, BlockProposerConfig, ConsensusConfig,
-    ContractRuntimeConfig, DeployAcceptorConfig, EventStreamServerConfig, FetcherConfig,
-    GossipConfig, LinearChainSyncConfig, RestServerConfig, RpcServerConfig, SmallNetworkConfig,
-    StorageConfig,
+    logging::LoggingConfig, types::NodeConfig, AdminServerConfig, BlockProposerConfig,
+    ConsensusConfig, ContractRuntimeConfig, DeployAcceptorConfig, EventStreamServerConfig,
+    FetcherConfig, GossipConfig, LinearChainSyncConfig, RestServerConfig, RpcServerConfig,
+    SmallNetworkConfig, StorageConfig,
 };
 
 /// Root configuration.
@@ -27,6 +27,9 @@ pub(crate) struct Config {
     pub(crate) rest_server: RestServerConfig,
     /// RPC API server configuration.
     pub(crate) rpc_server: RpcServerConfig,
+    /// Admin API server configuration.
+    #[serde(default)]
+    pub(crate) admin_server: AdminServerConfig,
     /// On-disk storage configuration.
     pub(crate) storage: StorageConfig,
     /// Gossip protocol configuration.
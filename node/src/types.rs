@@ -2,10 +2,12 @@
 
 pub(crate) mod appendable_block;
 mod block;
+mod blocked_peers;
 pub mod chainspec;
 mod deploy;
 pub mod error;
 mod exit_code;
+mod header_chain;
 mod item;
 pub mod json_compatibility;
 mod node_config;
@@ -20,11 +22,14 @@ use rand::{CryptoRng, RngCore};
 use rand_chacha::ChaCha20Rng;
 
 pub use block::{
-    json_compatibility::{JsonBlock, JsonBlockHeader},
-    Block, BlockBody, BlockHash, BlockHeader, BlockSignatures, FinalitySignature, FinalizedBlock,
-    HashingAlgorithmVersion, MerkleBlockBody, MerkleBlockBodyPart, MerkleLinkedListNode,
+    json_compatibility::{JsonBlock, JsonBlockHeader, JsonProof},
+    Block, BlockBody, BlockHash, BlockHeader, BlockSignatures, FinalitySignature,
+    FinalitySignatureId, FinalizedBlock, HashingAlgorithmVersion, MerkleBlockBody,
+    MerkleBlockBodyPart, MerkleLinkedListNode,
 };
 pub(crate) use block::{BlockByHeight, BlockHeaderWithMetadata, BlockPayload};
+pub(crate) use blocked_peers::BlockedPeer;
+pub use blocked_peers::{BlockedPeersMap, OffenseSeverity};
 pub(crate) use chainspec::ActivationPoint;
 pub use chainspec::Chainspec;
 pub use datasize::DataSize;
@@ -32,8 +37,9 @@ pub use deploy::{
     Approval, Deploy, DeployConfigurationFailure, DeployHash, DeployHeader, DeployMetadata,
     DeployOrTransferHash, Error as DeployError, ExcessiveSizeError as ExcessiveSizeDeployError,
 };
-pub use error::BlockValidationError;
+pub use error::{BlockValidationError, HeaderChainError};
 pub use exit_code::ExitCode;
+pub use header_chain::{validate_finality_signatures, TrustedHeaderChain};
 pub use item::{Item, Tag};
 pub use node_config::NodeConfig;
 pub(crate) use node_id::NodeId;
@@ -1215,7 +1215,8 @@ pub struct BlockSignatures {
 }
 
 impl BlockSignatures {
-    pub(crate) fn new(block_hash: BlockHash, era_id: EraId) -> Self {
+    /// Creates an empty set of finality signatures for the given block.
+    pub fn new(block_hash: BlockHash, era_id: EraId) -> Self {
         BlockSignatures {
             block_hash,
             era_id,
@@ -1223,7 +1224,8 @@ impl BlockSignatures {
         }
     }
 
-    pub(crate) fn insert_proof(
+    /// Inserts a finality signature, returning the previous signature by the same validator.
+    pub fn insert_proof(
         &mut self,
         public_key: PublicKey,
         signature: Signature,
@@ -1235,6 +1237,18 @@ impl BlockSignatures {
         self.proofs.contains_key(public_key)
     }
 
+    /// Returns the finality signature by the validator with the given public key, if any.
+    pub(crate) fn finality_signature(&self, public_key: &PublicKey) -> Option<FinalitySignature> {
+        self.proofs
+            .get(public_key)
+            .map(|signature| FinalitySignature {
+                block_hash: self.block_hash,
+                era_id: self.era_id,
+                signature: *signature,
+                public_key: public_key.clone(),
+            })
+    }
+
     /// Verify the signatures contained within.
     pub(crate) fn verify(&self) -> crypto::Result<()> {
         for (public_key, signature) in self.proofs.iter() {
@@ -1875,6 +1889,18 @@ pub(crate) mod json_compatibility {
         signature: Signature,
     }
 
+    impl JsonProof {
+        /// Returns the public key of the validator which created the signature.
+        pub fn public_key(&self) -> &PublicKey {
+            &self.public_key
+        }
+
+        /// Returns the finality signature.
+        pub fn signature(&self) -> &Signature {
+            &self.signature
+        }
+    }
+
     impl From<(PublicKey, Signature)> for JsonProof {
         fn from((public_key, signature): (PublicKey, Signature)) -> JsonProof {
             JsonProof {
@@ -1924,8 +1950,7 @@ impl FinalitySignature {
         secret_key: &SecretKey,
         public_key: PublicKey,
     ) -> Self {
-        let mut bytes = block_hash.inner().into_vec();
-        bytes.extend_from_slice(&era_id.to_le_bytes());
+        let bytes = Self::bytes_to_sign(&block_hash, era_id);
         let signature = crypto::sign(bytes, secret_key, &public_key);
         FinalitySignature {
             block_hash,
@@ -1937,12 +1962,17 @@ impl FinalitySignature {
 
     /// Verifies whether the signature is correct.
     pub fn verify(&self) -> crypto::Result<()> {
-        // NOTE: This needs to be in sync with the `new` constructor.
-        let mut bytes = self.block_hash.inner().into_vec();
-        bytes.extend_from_slice(&self.era_id.to_le_bytes());
+        let bytes = Self::bytes_to_sign(&self.block_hash, self.era_id);
         crypto::verify(bytes, &self.signature, &self.public_key)
     }
 
+    /// Returns the bytes signed by the validator: the block hash followed by the era ID.
+    pub(crate) fn bytes_to_sign(block_hash: &BlockHash, era_id: EraId) -> Vec<u8> {
+        let mut bytes = block_hash.inner().into_vec();
+        bytes.extend_from_slice(&era_id.to_le_bytes());
+        bytes
+    }
+
     #[cfg(test)]
     pub fn random_for_block(block_hash: BlockHash, era_id: u64) -> Self {
         let (sec_key, pub_key) = generate_ed25519_keypair();
@@ -1960,6 +1990,99 @@ impl Display for FinalitySignature {
     }
 }
 
+impl ToBytes for FinalitySignature {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut buffer = bytesrepr::allocate_buffer(self)?;
+        buffer.extend(self.block_hash.to_bytes()?);
+        buffer.extend(self.era_id.to_bytes()?);
+        buffer.extend(self.signature.to_bytes()?);
+        buffer.extend(self.public_key.to_bytes()?);
+        Ok(buffer)
+    }
+
+    fn serialized_length(&self) -> usize {
+        self.block_hash.serialized_length()
+            + self.era_id.serialized_length()
+            + self.signature.serialized_length()
+            + self.public_key.serialized_length()
+    }
+}
+
+impl FromBytes for FinalitySignature {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (block_hash, remainder) = BlockHash::from_bytes(bytes)?;
+        let (era_id, remainder) = EraId::from_bytes(remainder)?;
+        let (signature, remainder) = Signature::from_bytes(remainder)?;
+        let (public_key, remainder) = PublicKey::from_bytes(remainder)?;
+        let finality_signature = FinalitySignature {
+            block_hash,
+            era_id,
+            signature,
+            public_key,
+        };
+        Ok((finality_signature, remainder))
+    }
+}
+
+impl Item for FinalitySignature {
+    type Id = FinalitySignatureId;
+
+    const TAG: Tag = Tag::FinalitySignature;
+    const ID_IS_COMPLETE_ITEM: bool = false;
+
+    fn id(&self) -> Self::Id {
+        FinalitySignatureId {
+            block_hash: self.block_hash,
+            public_key: self.public_key.clone(),
+        }
+    }
+}
+
+/// The identifier of a finality signature: the hash of the signed block and the public key of the
+/// signing validator.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, DataSize)]
+pub struct FinalitySignatureId {
+    /// Hash of the block the signature is for.
+    pub block_hash: BlockHash,
+    /// Public key of the signing validator.
+    pub public_key: PublicKey,
+}
+
+impl ToBytes for FinalitySignatureId {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut buffer = bytesrepr::allocate_buffer(self)?;
+        buffer.extend(self.block_hash.to_bytes()?);
+        buffer.extend(self.public_key.to_bytes()?);
+        Ok(buffer)
+    }
+
+    fn serialized_length(&self) -> usize {
+        self.block_hash.serialized_length() + self.public_key.serialized_length()
+    }
+}
+
+impl FromBytes for FinalitySignatureId {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (block_hash, remainder) = BlockHash::from_bytes(bytes)?;
+        let (public_key, remainder) = PublicKey::from_bytes(remainder)?;
+        let id = FinalitySignatureId {
+            block_hash,
+            public_key,
+        };
+        Ok((id, remainder))
+    }
+}
+
+impl Display for FinalitySignatureId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "finality signature id for block hash {}, from {}",
+            self.block_hash, self.public_key
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::rc::Rc;
@@ -2101,6 +2224,24 @@ mod tests {
         assert!(fs_manufactured.verify().is_err());
     }
 
+    #[test]
+    fn finality_signature_from_block_signatures() {
+        let mut rng = TestRng::new();
+        let block = Block::random(&mut rng);
+        let fs =
+            FinalitySignature::random_for_block(*block.hash(), block.header().era_id().value());
+        let mut block_signatures = BlockSignatures::new(*block.hash(), block.header().era_id());
+        assert_eq!(block_signatures.finality_signature(&fs.public_key), None);
+
+        block_signatures.insert_proof(fs.public_key.clone(), fs.signature);
+        let stored_fs = block_signatures
+            .finality_signature(&fs.public_key)
+            .expect("should have finality signature");
+        assert_eq!(stored_fs, fs);
+        assert_eq!(stored_fs.id(), fs.id());
+        assert!(stored_fs.verify().is_ok());
+    }
+
     #[test]
     fn block_body_merkle_proof_should_be_correct() {
         let mut rng = TestRng::new();
@@ -0,0 +1,74 @@
+// TODO - remove once schemars stops causing warning.
+#![allow(clippy::field_reassign_with_default)]
+
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Display, Formatter},
+};
+
+use datasize::DataSize;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::types::{NodeId, Timestamp};
+
+/// The severity of an offense committed by a peer, determining how much it lowers the peer's
+/// reputation.
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, DataSize, JsonSchema,
+)]
+pub enum OffenseSeverity {
+    /// The peer failed to provide data it was asked for or claimed to hold.
+    Minor,
+    /// The peer sent invalid data.
+    Major,
+    /// The peer is provably faulty.
+    Critical,
+}
+
+impl Display for OffenseSeverity {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            OffenseSeverity::Minor => write!(formatter, "minor"),
+            OffenseSeverity::Major => write!(formatter, "major"),
+            OffenseSeverity::Critical => write!(formatter, "critical"),
+        }
+    }
+}
+
+/// The block of a peer which committed too many offenses or was blocked by the node operator.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, DataSize)]
+pub(crate) struct BlockedPeer {
+    /// The time the block expires.
+    pub(crate) blocked_until: Timestamp,
+    /// The severity of the offense which got the peer blocked, `None` if it was blocked by the
+    /// node operator.
+    pub(crate) offense: Option<OffenseSeverity>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, JsonSchema)]
+#[serde(deny_unknown_fields)]
+struct BlockedPeerEntry {
+    node_id: String,
+    blocked_until: Timestamp,
+    offense: Option<OffenseSeverity>,
+}
+
+/// Map of blocked peer IDs to the time their block expires.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct BlockedPeersMap(Vec<BlockedPeerEntry>);
+
+impl From<BTreeMap<NodeId, BlockedPeer>> for BlockedPeersMap {
+    fn from(input: BTreeMap<NodeId, BlockedPeer>) -> Self {
+        let ret = input
+            .into_iter()
+            .map(|(node_id, blocked_peer)| BlockedPeerEntry {
+                node_id: node_id.to_string(),
+                blocked_until: blocked_peer.blocked_until,
+                offense: blocked_peer.offense,
+            })
+            .collect();
+        BlockedPeersMap(ret)
+    }
+}
@@ -533,6 +533,11 @@ impl Approval {
     pub fn signature(&self) -> &Signature {
         &self.signature
     }
+
+    /// Verifies that the approval is a valid signature of the given deploy hash by its signer.
+    pub fn verify(&self, deploy_hash: &DeployHash) -> Result<(), crypto::Error> {
+        crypto::verify(deploy_hash, &self.signature, &self.signer)
+    }
 }
 
 impl Display for Approval {
@@ -632,6 +637,16 @@ impl Deploy {
         self.approvals.push(approval);
     }
 
+    /// Adds an approval made by another party, e.g. one taken from a copy of this deploy which was
+    /// signed separately.
+    ///
+    /// The approval is not verified here; it is checked along with the others by
+    /// [`Deploy::is_valid`].
+    pub fn add_approval(&mut self, approval: Approval) {
+        self.approvals.push(approval);
+        self.is_valid = None;
+    }
+
     /// Returns the `DeployHash` identifying this `Deploy`.
     pub fn id(&self) -> &DeployHash {
         &self.hash
@@ -1256,7 +1271,7 @@ fn validate_deploy(deploy: &Deploy) -> Result<(), DeployConfigurationFailure> {
     }
 
     for (index, approval) in deploy.approvals.iter().enumerate() {
-        if let Err(error) = crypto::verify(&deploy.hash, &approval.signature, &approval.signer) {
+        if let Err(error) = approval.verify(&deploy.hash) {
             warn!(?deploy, "failed to verify approval {}: {}", index, error);
             return Err(DeployConfigurationFailure::InvalidApproval {
                 index,
@@ -1532,6 +1547,32 @@ mod tests {
         );
     }
 
+    #[test]
+    fn should_accept_approvals_added_from_separately_signed_copy() {
+        let mut rng = crate::new_rng();
+        let mut deploy = create_deploy(&mut rng, DeployConfig::default().max_ttl, 0, "net-1");
+
+        let mut copy = deploy.clone();
+        copy.sign(&SecretKey::random(&mut rng));
+        let added_approval = copy.approvals.last().expect("should have approval").clone();
+
+        deploy.is_valid().expect("should be valid");
+        deploy.add_approval(added_approval);
+        assert!(deploy.is_valid.is_none(), "is valid should be reset");
+        assert_eq!(deploy.approvals.len(), 2);
+        deploy.is_valid().expect("should still be valid");
+
+        let other_deploy = Deploy::random(&mut rng);
+        deploy.add_approval(other_deploy.approvals[0].clone());
+        check_is_not_valid(
+            deploy,
+            DeployConfigurationFailure::InvalidApproval {
+                index: 2,
+                error_msg: String::new(), // This field is ignored in the check.
+            },
+        );
+    }
+
     #[test]
     fn is_acceptable() {
         let mut rng = crate::new_rng();
@@ -5,9 +5,12 @@ use std::collections::BTreeMap;
 use thiserror::Error;
 
 use casper_hashing::Digest;
-use casper_types::{bytesrepr, PublicKey, U512};
+use casper_types::{bytesrepr, EraId, PublicKey, U512};
 
-use crate::types::{block::EraReport, Block, BlockHash};
+use crate::{
+    crypto,
+    types::{block::EraReport, Block, BlockHash},
+};
 
 /// An error that can arise when creating a block from a finalized block and other components
 #[derive(Error, Debug)]
@@ -67,3 +70,59 @@ impl From<bytesrepr::Error> for BlockValidationError {
         BlockValidationError::BytesReprError(error)
     }
 }
+
+/// An error that can arise when extending a `TrustedHeaderChain` with a new block header.
+#[derive(Error, Debug)]
+pub enum HeaderChainError {
+    /// The header's parent hash is not the hash of the latest trusted header.
+    #[error("unexpected parent hash: expected {expected}, got {actual}")]
+    UnexpectedParentHash {
+        /// The hash of the latest trusted header.
+        expected: BlockHash,
+        /// The parent hash of the new header.
+        actual: BlockHash,
+    },
+
+    /// The header's height does not follow the latest trusted header's.
+    #[error("unexpected height: expected {expected}, got {actual}")]
+    UnexpectedHeight {
+        /// The height following the latest trusted header.
+        expected: u64,
+        /// The height of the new header.
+        actual: u64,
+    },
+
+    /// The header's era does not follow the latest trusted header's.
+    #[error("unexpected era: expected {expected}, got {actual}")]
+    UnexpectedEraId {
+        /// The era in which the block following the latest trusted header is created.
+        expected: EraId,
+        /// The era of the new header.
+        actual: EraId,
+    },
+
+    /// The signatures are not for the given header.
+    #[error("finality signatures are for {signatures_block_hash}, not {block_hash}")]
+    SignaturesForWrongBlock {
+        /// The hash of the new header.
+        block_hash: BlockHash,
+        /// The block hash of the signatures.
+        signatures_block_hash: BlockHash,
+    },
+
+    /// A finality signature was invalid.
+    #[error("invalid finality signature: {0}")]
+    InvalidFinalitySignature(crypto::Error),
+
+    /// The validators' signatures did not carry enough weight to finalize the block.
+    #[error(
+        "insufficient finality signatures: signed weight {signed_weight} of total weight \
+         {total_weight}"
+    )]
+    InsufficientFinalitySignatures {
+        /// The total weight of the validators which signed the block.
+        signed_weight: U512,
+        /// The total weight of all validators.
+        total_weight: U512,
+    },
+}
@@ -0,0 +1,283 @@
+//! Verification of a chain of block headers against the finality signatures of the validators,
+//! following changes to the validator set across eras.
+
+use std::collections::BTreeMap;
+
+use casper_types::{PublicKey, U512};
+
+use crate::types::{error::HeaderChainError, BlockHeader, BlockSignatures};
+
+/// A chain of block headers extending a trusted checkpoint, each of which has been verified to be
+/// the child of its predecessor and to be finalized by the validators of its era.
+///
+/// The validator set is tracked across eras using the `next_era_validator_weights` of each switch
+/// block, so that only the validators of the checkpoint's era need to be known in advance.
+#[derive(Clone, Debug)]
+pub struct TrustedHeaderChain {
+    latest_header: BlockHeader,
+    validator_weights: BTreeMap<PublicKey, U512>,
+}
+
+impl TrustedHeaderChain {
+    /// Creates a new chain starting at the given trusted header.
+    ///
+    /// `validator_weights` are the weights of the validators of the trusted header's era. If the
+    /// trusted header is a switch block, its `next_era_validator_weights` are used instead.
+    pub fn new(trusted_header: BlockHeader, validator_weights: BTreeMap<PublicKey, U512>) -> Self {
+        let validator_weights = trusted_header
+            .next_era_validator_weights()
+            .cloned()
+            .unwrap_or(validator_weights);
+        TrustedHeaderChain {
+            latest_header: trusted_header,
+            validator_weights,
+        }
+    }
+
+    /// Returns the latest verified header.
+    pub fn latest_header(&self) -> &BlockHeader {
+        &self.latest_header
+    }
+
+    /// Returns the weights of the validators of the era in which the next block is created.
+    pub fn validator_weights(&self) -> &BTreeMap<PublicKey, U512> {
+        &self.validator_weights
+    }
+
+    /// Verifies that `header` is the child of the latest header and is finalized by `signatures`,
+    /// and if so, makes it the latest header.
+    pub fn push(
+        &mut self,
+        header: BlockHeader,
+        signatures: &BlockSignatures,
+    ) -> Result<(), HeaderChainError> {
+        let expected_parent_hash = self.latest_header.hash();
+        if *header.parent_hash() != expected_parent_hash {
+            return Err(HeaderChainError::UnexpectedParentHash {
+                expected: expected_parent_hash,
+                actual: *header.parent_hash(),
+            });
+        }
+        let expected_height = self.latest_header.height() + 1;
+        if header.height() != expected_height {
+            return Err(HeaderChainError::UnexpectedHeight {
+                expected: expected_height,
+                actual: header.height(),
+            });
+        }
+        let expected_era_id = self.latest_header.next_block_era_id();
+        if header.era_id() != expected_era_id {
+            return Err(HeaderChainError::UnexpectedEraId {
+                expected: expected_era_id,
+                actual: header.era_id(),
+            });
+        }
+        let block_hash = header.hash();
+        if signatures.block_hash != block_hash || signatures.era_id != header.era_id() {
+            return Err(HeaderChainError::SignaturesForWrongBlock {
+                block_hash,
+                signatures_block_hash: signatures.block_hash,
+            });
+        }
+        validate_finality_signatures(signatures, &self.validator_weights)?;
+
+        if let Some(validator_weights) = header.next_era_validator_weights() {
+            self.validator_weights = validator_weights.clone();
+        }
+        self.latest_header = header;
+        Ok(())
+    }
+}
+
+/// Verifies that `signatures` are valid and were made by validators holding more than a third of
+/// the total weight in `validator_weights`.
+///
+/// Signatures by public keys which are not in `validator_weights` are ignored.
+pub fn validate_finality_signatures(
+    signatures: &BlockSignatures,
+    validator_weights: &BTreeMap<PublicKey, U512>,
+) -> Result<(), HeaderChainError> {
+    signatures
+        .verify()
+        .map_err(HeaderChainError::InvalidFinalitySignature)?;
+
+    let total_weight = validator_weights
+        .values()
+        .fold(U512::zero(), |total, weight| total + *weight);
+    let signed_weight = signatures
+        .proofs
+        .keys()
+        .filter_map(|public_key| validator_weights.get(public_key))
+        .fold(U512::zero(), |total, weight| total + *weight);
+
+    if signed_weight * 3 <= total_weight {
+        return Err(HeaderChainError::InsufficientFinalitySignatures {
+            signed_weight,
+            total_weight,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+
+    use casper_hashing::Digest;
+    use casper_types::{EraId, ProtocolVersion, SecretKey};
+
+    use super::*;
+    use crate::{
+        crypto::generate_ed25519_keypair,
+        testing::TestRng,
+        types::{Block, BlockHash, FinalitySignature, FinalizedBlock},
+    };
+
+    fn child_header(
+        rng: &mut TestRng,
+        parent: &BlockHeader,
+        next_era_validator_weights: Option<BTreeMap<PublicKey, U512>>,
+    ) -> BlockHeader {
+        let finalized_block = FinalizedBlock::random_with_specifics(
+            rng,
+            parent.next_block_era_id(),
+            parent.height() + 1,
+            next_era_validator_weights.is_some(),
+        );
+        Block::new(
+            parent.hash(),
+            parent.accumulated_seed(),
+            Digest::hash(&[rng.gen::<u8>()]),
+            finalized_block,
+            next_era_validator_weights,
+            ProtocolVersion::V1_0_0,
+        )
+        .expect("should create block")
+        .take_header()
+    }
+
+    fn sign(header: &BlockHeader, signers: &[&(SecretKey, PublicKey)]) -> BlockSignatures {
+        let mut signatures = BlockSignatures::new(header.hash(), header.era_id());
+        for (secret_key, public_key) in signers {
+            let finality_signature = FinalitySignature::new(
+                header.hash(),
+                header.era_id(),
+                secret_key,
+                public_key.clone(),
+            );
+            signatures.insert_proof(public_key.clone(), finality_signature.signature);
+        }
+        signatures
+    }
+
+    #[test]
+    fn should_follow_validator_changes_across_eras() {
+        let mut rng = crate::new_rng();
+        let alice = generate_ed25519_keypair();
+        let bob = generate_ed25519_keypair();
+        let carol = generate_ed25519_keypair();
+
+        let trusted_header = Block::random_with_specifics(
+            &mut rng,
+            EraId::new(1),
+            10,
+            ProtocolVersion::V1_0_0,
+            false,
+        )
+        .take_header();
+        let era_1_weights: BTreeMap<PublicKey, U512> = vec![
+            (alice.1.clone(), U512::from(10)),
+            (bob.1.clone(), U512::from(10)),
+            (carol.1.clone(), U512::from(10)),
+        ]
+        .into_iter()
+        .collect();
+        let mut chain = TrustedHeaderChain::new(trusted_header.clone(), era_1_weights);
+
+        // A switch block finalized by a single validator of era 1 (exactly a third of the weight)
+        // is rejected, but accepted once a second validator has signed it.
+        let era_2_weights: BTreeMap<PublicKey, U512> =
+            vec![(carol.1.clone(), U512::from(1))].into_iter().collect();
+        let switch_header = child_header(&mut rng, &trusted_header, Some(era_2_weights.clone()));
+        assert!(matches!(
+            chain.push(switch_header.clone(), &sign(&switch_header, &[&alice])),
+            Err(HeaderChainError::InsufficientFinalitySignatures { .. })
+        ));
+        chain
+            .push(
+                switch_header.clone(),
+                &sign(&switch_header, &[&alice, &bob]),
+            )
+            .expect("should accept switch block");
+        assert_eq!(chain.validator_weights(), &era_2_weights);
+
+        // The next block is in era 2, so must be signed by its only validator.
+        let header = child_header(&mut rng, &switch_header, None);
+        assert_eq!(header.era_id(), EraId::new(2));
+        assert!(matches!(
+            chain.push(header.clone(), &sign(&header, &[&alice, &bob])),
+            Err(HeaderChainError::InsufficientFinalitySignatures { .. })
+        ));
+        chain
+            .push(header.clone(), &sign(&header, &[&carol]))
+            .expect("should accept block");
+        assert_eq!(chain.latest_header(), &header);
+    }
+
+    #[test]
+    fn should_reject_header_with_unexpected_parent() {
+        let mut rng = crate::new_rng();
+        let alice = generate_ed25519_keypair();
+
+        let trusted_header = Block::random_with_specifics(
+            &mut rng,
+            EraId::new(1),
+            10,
+            ProtocolVersion::V1_0_0,
+            false,
+        )
+        .take_header();
+        let weights = vec![(alice.1.clone(), U512::one())].into_iter().collect();
+        let mut chain = TrustedHeaderChain::new(trusted_header, weights);
+
+        let unrelated_header = Block::random_with_specifics(
+            &mut rng,
+            EraId::new(1),
+            11,
+            ProtocolVersion::V1_0_0,
+            false,
+        )
+        .take_header();
+        let signatures = sign(&unrelated_header, &[&alice]);
+        assert!(matches!(
+            chain.push(unrelated_header, &signatures),
+            Err(HeaderChainError::UnexpectedParentHash { .. })
+        ));
+    }
+
+    #[test]
+    fn should_reject_invalid_signature() {
+        let mut rng = crate::new_rng();
+        let alice = generate_ed25519_keypair();
+
+        let header = Block::random_with_specifics(
+            &mut rng,
+            EraId::new(1),
+            10,
+            ProtocolVersion::V1_0_0,
+            false,
+        )
+        .take_header();
+        let weights = vec![(alice.1.clone(), U512::one())].into_iter().collect();
+        let mut signatures = sign(&header, &[&alice]);
+        let other_block_hash = BlockHash::new(Digest::hash(&[1u8]));
+        let forged_signature =
+            FinalitySignature::new(other_block_hash, header.era_id(), &alice.0, alice.1.clone());
+        signatures.insert_proof(alice.1.clone(), forged_signature.signature);
+
+        assert!(matches!(
+            validate_finality_signatures(&signatures, &weights),
+            Err(HeaderChainError::InvalidFinalitySignature(_))
+        ));
+    }
+}
@@ -9,7 +9,10 @@ use serde_repr::{Deserialize_repr, Serialize_repr};
 
 use casper_execution_engine::storage::trie::Trie;
 use casper_hashing::Digest;
-use casper_types::{bytesrepr::ToBytes, Key, StoredValue};
+use casper_types::{
+    bytesrepr::{self, FromBytes, ToBytes, U8_SERIALIZED_LENGTH},
+    Key, StoredValue,
+};
 
 use crate::types::{BlockHash, BlockHeader, BlockHeaderWithMetadata};
 
@@ -42,6 +45,37 @@ pub enum Tag {
     BlockHeaderByHash,
     /// A block header and its finality signatures requested by its height in the linear chain.
     BlockHeaderAndFinalitySignaturesByHeight,
+    /// A finality signature requested by its block hash and the public key of its signer.
+    FinalitySignature,
+}
+
+impl ToBytes for Tag {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        Ok(vec![*self as u8])
+    }
+
+    fn serialized_length(&self) -> usize {
+        U8_SERIALIZED_LENGTH
+    }
+}
+
+impl FromBytes for Tag {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (tag, remainder) = u8::from_bytes(bytes)?;
+        let tag = match tag {
+            tag if tag == Tag::Deploy as u8 => Tag::Deploy,
+            tag if tag == Tag::Block as u8 => Tag::Block,
+            tag if tag == Tag::GossipedAddress as u8 => Tag::GossipedAddress,
+            tag if tag == Tag::BlockByHeight as u8 => Tag::BlockByHeight,
+            tag if tag == Tag::BlockHeaderByHash as u8 => Tag::BlockHeaderByHash,
+            tag if tag == Tag::BlockHeaderAndFinalitySignaturesByHeight as u8 => {
+                Tag::BlockHeaderAndFinalitySignaturesByHeight
+            }
+            tag if tag == Tag::FinalitySignature as u8 => Tag::FinalitySignature,
+            _ => return Err(bytesrepr::Error::Formatting),
+        };
+        Ok((tag, remainder))
+    }
 }
 
 /// A trait which allows an implementing type to be used by the gossiper and fetcher components, and
@@ -49,7 +83,7 @@ pub enum Tag {
 /// type-identifying `TAG`.
 pub trait Item: Clone + Serialize + DeserializeOwned + Send + Sync + Debug + Display {
     /// The type of ID of the item.
-    type Id: Copy + Eq + Hash + Serialize + DeserializeOwned + Send + Sync + Debug + Display;
+    type Id: Clone + Eq + Hash + Serialize + DeserializeOwned + Send + Sync + Debug + Display;
     /// The tag representing the type of the item.
     const TAG: Tag;
     /// Whether the item's ID _is_ the complete item or not.
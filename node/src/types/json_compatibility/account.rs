@@ -64,6 +64,21 @@ pub struct Account {
     action_thresholds: ActionThresholds,
 }
 
+impl Account {
+    /// Returns the weight of the given key if it is associated with the account.
+    pub fn associated_key_weight(&self, account_hash: &AccountHash) -> Option<u8> {
+        self.associated_keys
+            .iter()
+            .find(|associated_key| associated_key.account_hash == *account_hash)
+            .map(|associated_key| associated_key.weight)
+    }
+
+    /// Returns the total weight of associated keys required to sign a deploy.
+    pub fn deployment_threshold(&self) -> u8 {
+        self.action_thresholds.deployment
+    }
+}
+
 impl From<&ExecutionEngineAccount> for Account {
     fn from(ee_account: &ExecutionEngineAccount) -> Self {
         Account {
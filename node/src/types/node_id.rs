@@ -1,4 +1,7 @@
-use std::fmt::{self, Debug, Display, Formatter};
+use std::{
+    fmt::{self, Debug, Display, Formatter},
+    str::FromStr,
+};
 
 use datasize::DataSize;
 use hex_fmt::HexFmt;
@@ -6,6 +9,7 @@ use once_cell::sync::Lazy;
 #[cfg(test)]
 use rand::Rng;
 use serde::{de::Error as SerdeError, Deserialize, Deserializer, Serialize, Serializer};
+use thiserror::Error;
 
 #[cfg(test)]
 use crate::testing::TestRng;
@@ -29,6 +33,22 @@ impl NodeId {
     pub fn hash_bytes(&self) -> &[u8] {
         self.0.as_ref()
     }
+
+    /// Returns the full hex representation of the ID, which can be parsed back using `FromStr`.
+    pub(crate) fn to_hex(&self) -> String {
+        base16::encode_lower(&self.0)
+    }
+}
+
+/// Error parsing a `NodeId` from its full hex representation.
+#[derive(Debug, Error)]
+pub enum NodeIdFromStrError {
+    /// The string is not valid hex.
+    #[error("invalid hex: {0}")]
+    Hex(#[from] base16::DecodeError),
+    /// The decoded ID has the wrong length.
+    #[error("expected {} bytes, got {0}", KeyFingerprint::LENGTH)]
+    WrongLength(usize),
 }
 
 /// Used to serialize and deserialize `NodeID` where the (de)serializer isn't a human-readable type.
@@ -94,6 +114,20 @@ impl Display for NodeId {
     }
 }
 
+impl FromStr for NodeId {
+    type Err = NodeIdFromStrError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let bytes = base16::decode(value.as_bytes())?;
+        if bytes.len() != KeyFingerprint::LENGTH {
+            return Err(NodeIdFromStrError::WrongLength(bytes.len()));
+        }
+        let mut array = [0_u8; KeyFingerprint::LENGTH];
+        array.copy_from_slice(bytes.as_slice());
+        Ok(NodeId(KeyFingerprint::from(array)))
+    }
+}
+
 impl From<KeyFingerprint> for NodeId {
     fn from(id: KeyFingerprint) -> Self {
         NodeId(id)
@@ -181,4 +215,15 @@ mod test {
         let decoded = serde_json::from_str(&json_string).unwrap();
         assert_eq!(node_id, decoded);
     }
+
+    #[test]
+    fn hex_roundtrip() {
+        let mut rng = crate::new_rng();
+        let node_id = NodeId::random(&mut rng);
+        let decoded: NodeId = node_id.to_hex().parse().unwrap();
+        assert_eq!(node_id, decoded);
+
+        assert!("0001".parse::<NodeId>().is_err());
+        assert!("tls:0001".parse::<NodeId>().is_err());
+    }
 }
@@ -103,6 +103,10 @@ pub(crate) enum ListeningError {
         /// The failure reason.
         error: Box<dyn std::error::Error + Send + Sync>,
     },
+
+    /// Refused to listen on an address which is not a loopback address.
+    #[error("refusing to listen on {0}, not a loopback address")]
+    NotLoopback(SocketAddr),
 }
 
 pub(crate) fn start_listening(address: &str) -> Result<Builder<AddrIncoming>, ListeningError> {
@@ -318,7 +322,7 @@ impl<T> WithDir<T> {
 }
 
 /// The source of a piece of data.
-#[derive(Clone, Debug, Serialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub(crate) enum Source<I> {
     /// A peer with the wrapped ID.
     Peer(I),
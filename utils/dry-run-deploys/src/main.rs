@@ -1,3 +1,5 @@
+mod verify;
+
 use std::{path::PathBuf, time::Instant};
 
 use histogram::Histogram;
@@ -5,7 +7,7 @@ use indicatif::{ProgressBar, ProgressStyle};
 use structopt::StructOpt;
 
 use casper_node::{
-    contract_runtime::{execute_finalized_block, ExecutionPreState},
+    contract_runtime::{execute_finalized_block, BlockAndExecutionEffects, ExecutionPreState},
     types::FinalizedBlock,
 };
 
@@ -39,6 +41,13 @@ struct Opts {
 
     #[structopt(short, long, about = "Enable manual syncing after each block to LMDB")]
     manual_sync_enabled: bool,
+
+    #[structopt(
+        long,
+        about = "Compare the state root hash and execution results of each executed block with the \
+                 downloaded ones, stopping at the first mismatch"
+    )]
+    verify: bool,
 }
 
 #[tokio::main]
@@ -128,7 +137,26 @@ async fn main() -> Result<(), anyhow::Error> {
             .increment(elapsed_micros)
             .map_err(anyhow::Error::msg)?;
 
-        let header = block_and_execution_effects.block.take_header();
+        let BlockAndExecutionEffects {
+            block: executed_block,
+            execution_results,
+            ..
+        } = block_and_execution_effects;
+        let header = executed_block.take_header();
+        if opts.verify {
+            if let Some(divergence) =
+                verify::find_divergence(&storage, block, &header, &execution_results)?
+            {
+                if let Some(progress) = progress.as_ref() {
+                    progress.abandon();
+                }
+                println!("{}", divergence);
+                return Err(anyhow::anyhow!(
+                    "execution diverged from the downloaded chain at height {}",
+                    height
+                ));
+            }
+        }
         execution_pre_state = ExecutionPreState::from(&header);
         execute_count += 1;
         if opts.verbose {
@@ -0,0 +1,329 @@
+//! Verification of re-executed blocks against the blocks and execution results downloaded from the
+//! chain.
+
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fmt::{self, Display, Formatter},
+};
+
+use casper_hashing::Digest;
+use casper_node::{
+    storage::Storage,
+    types::{Block, BlockHash, BlockHeader, DeployHash, DeployHeader},
+};
+use casper_types::{ExecutionResult, Transform, TransformEntry, U512};
+
+/// A re-executed block whose state root hash or execution results differ from the stored ones.
+pub struct Divergence {
+    height: u64,
+    block_hash: BlockHash,
+    stored_state_root_hash: Digest,
+    recomputed_state_root_hash: Digest,
+    deploys: Vec<DeployComparison>,
+}
+
+/// The stored and recomputed execution results of a single deploy.
+struct DeployComparison {
+    deploy_hash: DeployHash,
+    stored: Option<ExecutionResult>,
+    recomputed: Option<ExecutionResult>,
+}
+
+/// Compares the header and execution results of a re-executed block with the stored block and the
+/// execution results downloaded along with it.
+///
+/// Returns `None` if the state root hashes match and every stored execution result matches the
+/// recomputed one.  Deploys without a stored execution result, e.g. because their block was
+/// downloaded by an earlier version of `retrieve-state`, are only compared via the state root hash.
+pub fn find_divergence(
+    storage: &Storage,
+    block: Block,
+    recomputed_header: &BlockHeader,
+    recomputed_execution_results: &HashMap<DeployHash, (DeployHeader, ExecutionResult)>,
+) -> Result<Option<Divergence>, anyhow::Error> {
+    let mut deploys = Vec::new();
+    let mut execution_results_match = true;
+    for deploy_hash in block
+        .transfer_hashes()
+        .iter()
+        .chain(block.deploy_hashes().iter())
+    {
+        let stored = storage
+            .read_deploy_metadata_by_hash(deploy_hash)?
+            .and_then(|mut metadata| metadata.execution_results.remove(block.hash()));
+        let recomputed = recomputed_execution_results
+            .get(deploy_hash)
+            .map(|(_, execution_result)| execution_result.clone());
+        if stored.is_some() && stored != recomputed {
+            execution_results_match = false;
+        }
+        deploys.push(DeployComparison {
+            deploy_hash: *deploy_hash,
+            stored,
+            recomputed,
+        });
+    }
+
+    let height = block.height();
+    let block_hash = *block.hash();
+    let stored_state_root_hash = *block.take_header().state_root_hash();
+    let recomputed_state_root_hash = *recomputed_header.state_root_hash();
+    if stored_state_root_hash == recomputed_state_root_hash && execution_results_match {
+        return Ok(None);
+    }
+
+    Ok(Some(Divergence {
+        height,
+        block_hash,
+        stored_state_root_hash,
+        recomputed_state_root_hash,
+        deploys,
+    }))
+}
+
+impl Display for Divergence {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(
+            formatter,
+            "Execution diverged at height {}, block {}",
+            self.height, self.block_hash
+        )?;
+        writeln!(
+            formatter,
+            "  state root hash:  stored {}, recomputed {}{}",
+            self.stored_state_root_hash,
+            self.recomputed_state_root_hash,
+            mismatch_marker(self.stored_state_root_hash != self.recomputed_state_root_hash)
+        )?;
+        for deploy in &self.deploys {
+            write!(formatter, "{}", deploy)?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for DeployComparison {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        write!(formatter, "deploy {}: ", self.deploy_hash)?;
+        let (stored, recomputed) = match (&self.stored, &self.recomputed) {
+            (_, None) => return writeln!(formatter, "not executed"),
+            (None, Some(recomputed)) => {
+                writeln!(formatter, "no stored execution result")?;
+                let (cost, error_message, _) = parts(recomputed);
+                writeln!(formatter, "  gas:              recomputed {}", cost)?;
+                return writeln!(
+                    formatter,
+                    "  error message:    recomputed {:?}",
+                    error_message
+                );
+            }
+            (Some(stored), Some(recomputed)) if stored == recomputed => {
+                return writeln!(formatter, "identical")
+            }
+            (Some(stored), Some(recomputed)) => (stored, recomputed),
+        };
+
+        writeln!(formatter, "differs")?;
+        let (stored_cost, stored_error_message, stored_transforms) = parts(stored);
+        let (recomputed_cost, recomputed_error_message, recomputed_transforms) = parts(recomputed);
+        writeln!(
+            formatter,
+            "  gas:              stored {}, recomputed {}{}",
+            stored_cost,
+            recomputed_cost,
+            mismatch_marker(stored_cost != recomputed_cost)
+        )?;
+        writeln!(
+            formatter,
+            "  error message:    stored {:?}, recomputed {:?}{}",
+            stored_error_message,
+            recomputed_error_message,
+            mismatch_marker(stored_error_message != recomputed_error_message)
+        )?;
+
+        // Transforms are compared per key, as their order is not significant.
+        let stored_transforms = transforms_by_key(stored_transforms);
+        let recomputed_transforms = transforms_by_key(recomputed_transforms);
+        let keys: BTreeSet<&str> = stored_transforms
+            .keys()
+            .chain(recomputed_transforms.keys())
+            .copied()
+            .collect();
+        writeln!(formatter, "  transforms:")?;
+        for key in keys {
+            match (stored_transforms.get(key), recomputed_transforms.get(key)) {
+                (Some(stored), Some(recomputed)) if stored == recomputed => (),
+                (Some(stored), Some(recomputed)) => writeln!(
+                    formatter,
+                    "    ~ {}: stored {:?}, recomputed {:?}",
+                    key, stored, recomputed
+                )?,
+                (Some(stored), None) => writeln!(formatter, "    - {}: {:?}", key, stored)?,
+                (None, Some(recomputed)) => writeln!(formatter, "    + {}: {:?}", key, recomputed)?,
+                (None, None) => unreachable!("key is taken from one of the maps"),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns the cost, error message and transforms of an execution result.
+fn parts(execution_result: &ExecutionResult) -> (&U512, Option<&str>, &[TransformEntry]) {
+    match execution_result {
+        ExecutionResult::Failure {
+            effect,
+            cost,
+            error_message,
+            ..
+        } => (cost, Some(error_message.as_str()), &effect.transforms),
+        ExecutionResult::Success { effect, cost, .. } => (cost, None, &effect.transforms),
+    }
+}
+
+/// Groups transforms by the key they apply to.
+fn transforms_by_key(transforms: &[TransformEntry]) -> BTreeMap<&str, Vec<&Transform>> {
+    let mut transforms_by_key: BTreeMap<&str, Vec<&Transform>> = BTreeMap::new();
+    for transform_entry in transforms {
+        transforms_by_key
+            .entry(transform_entry.key.as_str())
+            .or_default()
+            .push(&transform_entry.transform);
+    }
+    transforms_by_key
+}
+
+/// Returns a marker to highlight a mismatching value.
+fn mismatch_marker(is_mismatch: bool) -> &'static str {
+    if is_mismatch {
+        "  <-- MISMATCH"
+    } else {
+        ""
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use casper_hashing::Digest;
+    use casper_node::{
+        rpcs::docs::DocExample,
+        storage::Storage,
+        types::{Block, BlockHeader, Deploy, DeployHash, DeployHeader, JsonBlockHeader},
+    };
+    use casper_types::{ExecutionEffect, ExecutionResult, U512};
+
+    use retrieve_state::storage::create_storage;
+
+    use super::find_divergence;
+
+    /// Returns a successful execution result with the given cost.
+    fn execution_result(cost: u64) -> ExecutionResult {
+        ExecutionResult::Success {
+            effect: ExecutionEffect::default(),
+            transfers: vec![],
+            cost: U512::from(cost),
+        }
+    }
+
+    /// Returns the hashes of all deploys and transfers in the block.
+    fn deploy_hashes(block: &Block) -> Vec<DeployHash> {
+        block
+            .transfer_hashes()
+            .iter()
+            .chain(block.deploy_hashes().iter())
+            .copied()
+            .collect()
+    }
+
+    /// Creates a storage holding the example block, along with the given stored execution result
+    /// for each of its deploys.
+    fn storage_with_block(stored_execution_result: Option<ExecutionResult>) -> (Storage, Block) {
+        let dir = tempfile::tempdir().unwrap().into_path();
+        let mut storage = create_storage(dir).expect("should create storage");
+        let block = Block::doc_example().clone();
+        storage.write_block(&block).unwrap();
+        if let Some(execution_result) = stored_execution_result {
+            let execution_results = deploy_hashes(&block)
+                .into_iter()
+                .map(|deploy_hash| (deploy_hash, execution_result.clone()))
+                .collect();
+            storage
+                .write_execution_results(block.hash(), execution_results)
+                .unwrap();
+        }
+        (storage, block)
+    }
+
+    /// Returns the given recomputed execution result for each of the block's deploys.
+    fn recomputed(
+        block: &Block,
+        execution_result: ExecutionResult,
+    ) -> HashMap<DeployHash, (DeployHeader, ExecutionResult)> {
+        let deploy_header = Deploy::doc_example().header().clone();
+        deploy_hashes(block)
+            .into_iter()
+            .map(|deploy_hash| {
+                (
+                    deploy_hash,
+                    (deploy_header.clone(), execution_result.clone()),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn should_not_find_divergence_if_everything_matches() {
+        let (storage, block) = storage_with_block(Some(execution_result(1)));
+        let header = block.clone().take_header();
+        let recomputed = recomputed(&block, execution_result(1));
+
+        assert!(find_divergence(&storage, block, &header, &recomputed)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn should_find_diverging_state_root_hash() {
+        let (storage, block) = storage_with_block(Some(execution_result(1)));
+        let mut json_header = JsonBlockHeader::from(block.clone().take_header());
+        json_header.state_root_hash = Digest::hash(b"diverging state root hash");
+        let header = BlockHeader::from(json_header);
+        let recomputed = recomputed(&block, execution_result(1));
+
+        let divergence = find_divergence(&storage, block.clone(), &header, &recomputed)
+            .unwrap()
+            .expect("should diverge");
+        let report = divergence.to_string();
+        assert!(report.contains(&format!("height {}", block.height())));
+        assert!(report.contains("state root hash"));
+        assert!(report.contains("MISMATCH"));
+    }
+
+    #[test]
+    fn should_find_diverging_execution_results() {
+        let (storage, block) = storage_with_block(Some(execution_result(1)));
+        let header = block.clone().take_header();
+        let recomputed = recomputed(&block, execution_result(2));
+
+        let divergence = find_divergence(&storage, block.clone(), &header, &recomputed)
+            .unwrap()
+            .expect("should diverge");
+        let report = divergence.to_string();
+        for deploy_hash in deploy_hashes(&block) {
+            assert!(report.contains(&format!("deploy {}: differs", deploy_hash)));
+        }
+        assert!(report.contains("gas:              stored 1, recomputed 2  <-- MISMATCH"));
+    }
+
+    #[test]
+    fn should_only_compare_state_root_hash_without_stored_execution_results() {
+        let (storage, block) = storage_with_block(None);
+        let header = block.clone().take_header();
+        let recomputed = recomputed(&block, execution_result(2));
+
+        assert!(find_divergence(&storage, block, &header, &recomputed)
+            .unwrap()
+            .is_none());
+    }
+}
@@ -12,13 +12,13 @@ use casper_hashing::Digest;
 use casper_node::{
     rpcs::{
         chain::{BlockIdentifier, GetBlockParams, GetBlockResult},
-        info::{GetDeployParams, GetDeployResult},
+        info::{GetDeployParams, GetDeployResult, JsonExecutionResult},
         state::{GetTrieParams, GetTrieResult},
     },
     storage::Storage,
-    types::{Block, BlockHash, Deploy, DeployOrTransferHash, JsonBlock},
+    types::{Block, BlockHash, Deploy, DeployHash, DeployOrTransferHash, JsonBlock},
 };
-use casper_types::{bytesrepr::FromBytes, Key, StoredValue};
+use casper_types::{bytesrepr::FromBytes, ExecutionResult, Key, StoredValue};
 
 pub mod rpc;
 pub mod storage;
@@ -107,6 +107,22 @@ pub struct BlockWithDeploys {
     pub block: JsonBlock,
     pub transfers: Vec<Deploy>,
     pub deploys: Vec<Deploy>,
+    /// The results of executing the transfers and deploys in the block, missing from block files
+    /// written by earlier versions.
+    #[serde(default)]
+    pub execution_results: Vec<(DeployHash, ExecutionResult)>,
+}
+
+/// Returns the execution result in the block with the given hash, out of all the execution results
+/// of a deploy.
+fn execution_result_in_block(
+    block_hash: &BlockHash,
+    execution_results: Vec<JsonExecutionResult>,
+) -> Option<ExecutionResult> {
+    execution_results
+        .into_iter()
+        .find(|execution_result| execution_result.block_hash == *block_hash)
+        .map(|execution_result| execution_result.result)
 }
 
 /// Download a block, along with all it's deploys.
@@ -121,31 +137,45 @@ pub async fn download_block_with_deploys(
         .block
         .unwrap();
 
+    let mut execution_results = Vec::new();
+
     let mut transfers = Vec::new();
     for transfer_hash in block.transfer_hashes() {
-        let transfer: Deploy = get_deploy(
+        let GetDeployResult {
+            deploy: transfer,
+            execution_results: transfer_execution_results,
+            ..
+        } = get_deploy(
             client,
             url,
             GetDeployParams {
                 deploy_hash: *transfer_hash,
             },
         )
-        .await?
-        .deploy;
+        .await?;
+        if let Some(result) = execution_result_in_block(&block.hash, transfer_execution_results) {
+            execution_results.push((*transfer_hash, result));
+        }
         transfers.push(transfer);
     }
 
     let mut deploys = Vec::new();
     for deploy_hash in block.deploy_hashes() {
-        let deploy: Deploy = get_deploy(
+        let GetDeployResult {
+            deploy,
+            execution_results: deploy_execution_results,
+            ..
+        } = get_deploy(
             client,
             url,
             GetDeployParams {
                 deploy_hash: *deploy_hash,
             },
         )
-        .await?
-        .deploy;
+        .await?;
+        if let Some(result) = execution_result_in_block(&block.hash, deploy_execution_results) {
+            execution_results.push((*deploy_hash, result));
+        }
         deploys.push(deploy);
     }
 
@@ -153,6 +183,7 @@ pub async fn download_block_with_deploys(
         block,
         transfers,
         deploys,
+        execution_results,
     })
 }
 
@@ -265,7 +296,8 @@ async fn get_block_and_download(
     }
 }
 
-/// Store a single [`BlockWithDeploys`]'s [`Block`], `deploys` and `transfers`.
+/// Store a single [`BlockWithDeploys`]'s [`Block`], `deploys`, `transfers` and
+/// `execution_results`.
 pub fn put_block_with_deploys(
     storage: &mut Storage,
     block_with_deploys: &BlockWithDeploys,
@@ -286,6 +318,16 @@ pub fn put_block_with_deploys(
     }
     let block: Block = block_with_deploys.block.clone().into();
     storage.write_block(&block)?;
+    if !block_with_deploys.execution_results.is_empty() {
+        storage.write_execution_results(
+            block.hash(),
+            block_with_deploys
+                .execution_results
+                .iter()
+                .cloned()
+                .collect(),
+        )?;
+    }
     Ok(())
 }
 
@@ -165,6 +165,7 @@ mod tests {
             block: example_block.clone(),
             transfers: vec![example_deploy.clone()],
             deploys: vec![],
+            execution_results: vec![],
         };
 
         put_block_with_deploys(&mut storage, &block_with_deploys).unwrap();